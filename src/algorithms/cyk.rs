@@ -0,0 +1,295 @@
+use std::collections::{HashMap, HashSet};
+
+/// A parse tree built by `CykParser::cyk_with_parse_tree`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseTree {
+    /// A terminal rule `symbol -> terminal`
+    Leaf { symbol: char, terminal: char },
+    /// A binary rule `symbol -> left right`
+    Node {
+        symbol: char,
+        left: Box<ParseTree>,
+        right: Box<ParseTree>,
+    },
+}
+
+impl ParseTree {
+    /// Returns the nonterminal at the root of the tree.
+    pub fn symbol(&self) -> char {
+        match self {
+            ParseTree::Leaf { symbol, .. } | ParseTree::Node { symbol, .. } => *symbol,
+        }
+    }
+
+    /// Returns the terminals at the leaves, from left to right: the parsed input.
+    pub fn leaves(&self) -> Vec<char> {
+        match self {
+            ParseTree::Leaf { terminal, .. } => vec![*terminal],
+            ParseTree::Node { left, right, .. } => {
+                let mut leaves = left.leaves();
+                leaves.extend(right.leaves());
+                leaves
+            }
+        }
+    }
+}
+
+/// How a nonterminal derives a span of the input, to rebuild the parse tree
+#[derive(Clone, Copy)]
+enum Derivation {
+    Terminal(char),
+    /// The span is split after `split` characters, the halves derived by `left` and `right`
+    Binary {
+        split: usize,
+        left: char,
+        right: char,
+    },
+}
+
+/// The Cocke-Younger-Kasami parser for context-free grammars in Chomsky normal form
+/// <https://en.wikipedia.org/wiki/CYK_algorithm>
+///
+/// In Chomsky normal form, every rule either produces a single terminal (`A -> a`) or exactly
+/// two nonterminals (`A -> B C`). The parser fills a chart where the cell for a span of the
+/// input holds every nonterminal deriving it: a span of one character is derived by the
+/// terminal rules producing it, and a longer span by the binary rules whose nonterminals derive
+/// the two halves, for any way of splitting it. This takes O(n^3 |G|) for an input of length n.
+pub struct CykParser {
+    start: char,
+    terminal_rules: HashMap<char, Vec<char>>,
+    binary_rules: Vec<(char, char, char)>,
+}
+
+impl CykParser {
+    /// Creates a parser from the rules of a grammar in Chomsky normal form. Every rule lists the
+    /// productions of a nonterminal, each made of either a single terminal or two nonterminals.
+    /// The start symbol is the nonterminal of the first rule.
+    ///
+    /// Panics if the grammar is empty or not in Chomsky normal form.
+    pub fn new(rules: Vec<(char, Vec<Vec<char>>)>) -> Self {
+        let start = rules.first().expect("the grammar has no rules").0;
+        let nonterminals: HashSet<char> = rules.iter().map(|&(symbol, _)| symbol).collect();
+        let mut terminal_rules: HashMap<char, Vec<char>> = HashMap::new();
+        let mut binary_rules = vec![];
+        for (symbol, productions) in rules {
+            for production in productions {
+                match production[..] {
+                    [terminal] if !nonterminals.contains(&terminal) => {
+                        terminal_rules.entry(terminal).or_default().push(symbol)
+                    }
+                    [left, right]
+                        if nonterminals.contains(&left) && nonterminals.contains(&right) =>
+                    {
+                        binary_rules.push((symbol, left, right))
+                    }
+                    _ => {
+                        panic!("the rule {symbol} -> {production:?} is not in Chomsky normal form")
+                    }
+                }
+            }
+        }
+        CykParser {
+            start,
+            terminal_rules,
+            binary_rules,
+        }
+    }
+
+    /// Fills the chart: `chart[length - 1][i]` maps the nonterminals deriving
+    /// `input[i..i + length]` to one way of deriving it.
+    fn chart(&self, input: &[char]) -> Vec<Vec<HashMap<char, Derivation>>> {
+        let n = input.len();
+        let mut chart: Vec<Vec<HashMap<char, Derivation>>> = (0..n)
+            .map(|length| vec![HashMap::new(); n - length])
+            .collect();
+        for (i, c) in input.iter().enumerate() {
+            for &symbol in self.terminal_rules.get(c).into_iter().flatten() {
+                chart[0][i].insert(symbol, Derivation::Terminal(*c));
+            }
+        }
+        for length in 2..=n {
+            for i in 0..=n - length {
+                for split in 1..length {
+                    for &(symbol, left, right) in &self.binary_rules {
+                        if chart[length - 1][i].contains_key(&symbol) {
+                            continue;
+                        }
+                        if chart[split - 1][i].contains_key(&left)
+                            && chart[length - split - 1][i + split].contains_key(&right)
+                        {
+                            let derivation = Derivation::Binary { split, left, right };
+                            chart[length - 1][i].insert(symbol, derivation);
+                        }
+                    }
+                }
+            }
+        }
+        chart
+    }
+
+    /// Returns `true` if the grammar derives `input`. An empty input is never derived.
+    pub fn parse(&self, input: &[char]) -> bool {
+        self.cyk_with_parse_tree(input).is_some()
+    }
+
+    /// Parses `input`, returning a parse tree rooted at the start symbol if the grammar
+    /// derives it. For an ambiguous grammar, one of the parse trees is returned.
+    pub fn cyk_with_parse_tree(&self, input: &[char]) -> Option<ParseTree> {
+        if input.is_empty() {
+            return None;
+        }
+        let chart = self.chart(input);
+        chart[input.len() - 1][0].get(&self.start)?;
+        Some(Self::build_tree(&chart, self.start, 0, input.len()))
+    }
+
+    fn build_tree(
+        chart: &[Vec<HashMap<char, Derivation>>],
+        symbol: char,
+        start: usize,
+        length: usize,
+    ) -> ParseTree {
+        match chart[length - 1][start][&symbol] {
+            Derivation::Terminal(terminal) => ParseTree::Leaf { symbol, terminal },
+            Derivation::Binary { split, left, right } => ParseTree::Node {
+                symbol,
+                left: Box::new(Self::build_tree(chart, left, start, split)),
+                right: Box::new(Self::build_tree(
+                    chart,
+                    right,
+                    start + split,
+                    length - split,
+                )),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Non-empty balanced parentheses: S -> S S | L R | L A, A -> S R, L -> (, R -> )
+    fn parentheses() -> CykParser {
+        CykParser::new(vec![
+            ('S', vec![vec!['S', 'S'], vec!['L', 'R'], vec!['L', 'A']]),
+            ('A', vec![vec!['S', 'R']]),
+            ('L', vec![vec!['(']]),
+            ('R', vec![vec![')']]),
+        ])
+    }
+
+    /// Sums and products of the digits 0 and 1, in Chomsky normal form
+    fn arithmetic() -> CykParser {
+        CykParser::new(vec![
+            (
+                'E',
+                vec![
+                    vec!['E', 'X'],
+                    vec!['T', 'Y'],
+                    vec!['L', 'Z'],
+                    vec!['0'],
+                    vec!['1'],
+                ],
+            ),
+            ('X', vec![vec!['P', 'T']]),
+            (
+                'T',
+                vec![vec!['T', 'Y'], vec!['L', 'Z'], vec!['0'], vec!['1']],
+            ),
+            ('Y', vec![vec!['M', 'F']]),
+            ('F', vec![vec!['L', 'Z'], vec!['0'], vec!['1']]),
+            ('Z', vec![vec!['E', 'R']]),
+            ('P', vec![vec!['+']]),
+            ('M', vec![vec!['*']]),
+            ('L', vec![vec!['(']]),
+            ('R', vec![vec![')']]),
+        ])
+    }
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    fn is_balanced(s: &[char]) -> bool {
+        let mut depth = 0i32;
+        for &c in s {
+            depth += if c == '(' { 1 } else { -1 };
+            if depth < 0 {
+                return false;
+            }
+        }
+        depth == 0
+    }
+
+    #[test]
+    fn balanced_parentheses() {
+        let parser = parentheses();
+        for valid in ["()", "(())", "()()", "(()())()", "((()))(())"] {
+            assert!(parser.parse(&chars(valid)), "{valid} is balanced");
+        }
+        for invalid in ["", "(", ")(", "(()", "())(", "((())"] {
+            assert!(!parser.parse(&chars(invalid)), "{invalid} is not balanced");
+        }
+    }
+
+    #[test]
+    fn all_short_parentheses_strings() {
+        let parser = parentheses();
+        for length in 1..=10 {
+            for bits in 0..1u32 << length {
+                let s: Vec<char> = (0..length)
+                    .map(|i| if bits >> i & 1 == 1 { '(' } else { ')' })
+                    .collect();
+                assert_eq!(parser.parse(&s), is_balanced(&s), "{s:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn arithmetic_expressions() {
+        let parser = arithmetic();
+        for valid in ["1", "1+0", "1*0+1", "(1+1)*0", "((1))", "1+(0*1)*(1+1)"] {
+            assert!(parser.parse(&chars(valid)), "{valid} is valid");
+        }
+        for invalid in ["+", "1+", "*1", "(1", "1)", "()", "1(0)", "1++0", "2"] {
+            assert!(!parser.parse(&chars(invalid)), "{invalid} is invalid");
+        }
+    }
+
+    #[test]
+    fn parse_tree() {
+        let parser = parentheses();
+        let input = chars("(())()");
+        let tree = parser.cyk_with_parse_tree(&input).unwrap();
+        assert_eq!(tree.symbol(), 'S');
+        assert_eq!(tree.leaves(), input);
+
+        let tree = parser.cyk_with_parse_tree(&chars("()")).unwrap();
+        let expected = ParseTree::Node {
+            symbol: 'S',
+            left: Box::new(ParseTree::Leaf {
+                symbol: 'L',
+                terminal: '(',
+            }),
+            right: Box::new(ParseTree::Leaf {
+                symbol: 'R',
+                terminal: ')',
+            }),
+        };
+        assert_eq!(tree, expected);
+        assert_eq!(parser.cyk_with_parse_tree(&chars(")(")), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Chomsky normal form")]
+    fn rejects_grammars_not_in_normal_form() {
+        CykParser::new(vec![('S', vec![vec!['a', 'S', 'b']])]);
+    }
+
+    #[test]
+    #[should_panic(expected = "the rule S -> ['A'] is not in Chomsky normal form")]
+    fn rejects_unit_rules() {
+        CykParser::new(vec![('S', vec![vec!['A']]), ('A', vec![vec!['a']])]);
+    }
+}
@@ -0,0 +1,561 @@
+use std::collections::{HashMap, HashSet};
+
+/// A symbol of the right-hand side of a rule
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Symbol {
+    Terminal(char),
+    Nonterminal(char),
+}
+
+/// The rules of a grammar, every rule producing a sequence of symbols from a nonterminal. The
+/// start symbol is the nonterminal of the first rule, and an empty sequence is an empty rule.
+pub type Grammar = Vec<(char, Vec<Symbol>)>;
+
+/// A rule with a dot after its first `dot` symbols, the part of the rule matched from the
+/// position `origin` of the input
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct EarleyItem {
+    rule: usize,
+    dot: usize,
+    origin: usize,
+}
+
+/// A parse tree built from a `ParseForest`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EarleyTree {
+    Terminal(char),
+    /// A nonterminal with the trees of the symbols of one of its rules
+    Node {
+        symbol: char,
+        children: Vec<EarleyTree>,
+    },
+}
+
+impl EarleyTree {
+    /// Returns the terminals at the leaves, from left to right: the parsed input.
+    pub fn leaves(&self) -> Vec<char> {
+        match self {
+            EarleyTree::Terminal(terminal) => vec![*terminal],
+            EarleyTree::Node { children, .. } => {
+                children.iter().flat_map(EarleyTree::leaves).collect()
+            }
+        }
+    }
+}
+
+/// A span of the input derived by a symbol, from `start` to `end`
+type Span = (Symbol, usize, usize);
+
+/// All the parse trees of an input, sharing their common subtrees.
+///
+/// Every nonterminal deriving a span of the input in some parse tree is a node, with every way
+/// of deriving it: a rule, and the spans of the input derived by the symbols of the rule. An
+/// ambiguous grammar can derive an input in exponentially many ways, but the forest has at most
+/// O(n^2) nodes, one for every nonterminal and span.
+pub struct ParseForest {
+    root: Span,
+    rules: Grammar,
+    derivations: HashMap<Span, Vec<(usize, Vec<Span>)>>,
+}
+
+impl ParseForest {
+    /// Returns the number of parse trees, saturating at `u64::MAX`, or `None` if there are
+    /// infinitely many, when a nonterminal derives a span through itself, like with a rule
+    /// `A -> A`.
+    pub fn count_trees(&self) -> Option<u64> {
+        self.count(self.root, &mut HashMap::new(), &mut HashSet::new())
+    }
+
+    fn count(
+        &self,
+        span: Span,
+        memo: &mut HashMap<Span, Option<u64>>,
+        path: &mut HashSet<Span>,
+    ) -> Option<u64> {
+        if let Symbol::Terminal(_) = span.0 {
+            return Some(1);
+        }
+        if let Some(&count) = memo.get(&span) {
+            return count;
+        }
+        if !path.insert(span) {
+            return None;
+        }
+        let mut total: Option<u64> = Some(0);
+        for (_, children) in &self.derivations[&span] {
+            let mut product = Some(1u64);
+            for &child in children {
+                product = match (product, self.count(child, memo, path)) {
+                    (Some(a), Some(b)) => Some(a.saturating_mul(b)),
+                    _ => None,
+                };
+            }
+            total = total.zip(product).map(|(a, b)| a.saturating_add(b));
+        }
+        path.remove(&span);
+        memo.insert(span, total);
+        total
+    }
+
+    /// Tells whether the input has more than one parse tree.
+    pub fn is_ambiguous(&self) -> bool {
+        self.count_trees() != Some(1)
+    }
+
+    /// Returns at most `limit` of the parse trees, those deriving no span of the input through
+    /// itself when there are infinitely many.
+    pub fn trees(&self, limit: usize) -> Vec<EarleyTree> {
+        self.trees_of(self.root, limit, &mut HashSet::new())
+    }
+
+    fn trees_of(&self, span: Span, limit: usize, path: &mut HashSet<Span>) -> Vec<EarleyTree> {
+        let symbol = match span.0 {
+            Symbol::Terminal(terminal) => return vec![EarleyTree::Terminal(terminal)],
+            Symbol::Nonterminal(symbol) => symbol,
+        };
+        if !path.insert(span) {
+            return vec![];
+        }
+        let mut trees = vec![];
+        for (rule, children) in &self.derivations[&span] {
+            debug_assert_eq!(self.rules[*rule].0, symbol);
+            // every combination of the trees of the children, up to the limit
+            let mut partial: Vec<Vec<EarleyTree>> = vec![vec![]];
+            for &child in children {
+                let subtrees = self.trees_of(child, limit, path);
+                partial = partial
+                    .iter()
+                    .flat_map(|prefix| {
+                        subtrees.iter().map(move |subtree| {
+                            let mut prefix = prefix.clone();
+                            prefix.push(subtree.clone());
+                            prefix
+                        })
+                    })
+                    .take(limit)
+                    .collect();
+            }
+            for children in partial {
+                if trees.len() == limit {
+                    break;
+                }
+                trees.push(EarleyTree::Node { symbol, children });
+            }
+        }
+        path.remove(&span);
+        trees
+    }
+}
+
+/// The Earley parser for any context-free grammar
+/// <https://en.wikipedia.org/wiki/Earley_parser>
+///
+/// The chart holds a set of items for every position of the input, the rules whose beginning
+/// matches the input up to the position, from some origin. Reading the sets in order, every
+/// item either predicts the rules of the nonterminal after its dot from the current position,
+/// scans the terminal after its dot into the next set if the input has it, or, when the whole
+/// rule matched, completes the items waiting for its nonterminal at its origin. The input is
+/// derived if the last set has a rule of the start symbol matched from the start.
+///
+/// Unlike CYK, the grammar needs no normal form, and left recursive, ambiguous and empty rules
+/// are all fine. This takes O(n^3) for an input of length n in the worst case, O(n^2) for an
+/// unambiguous grammar, and O(n) for most grammars of programming languages. The nonterminals
+/// deriving the empty string are computed beforehand, so that predicting them also moves past
+/// them, which completing the empty rules in the same set would miss (Aycock and Horspool).
+pub struct EarleyParser {
+    rules: Grammar,
+    /// The rules of every nonterminal
+    rules_of: HashMap<char, Vec<usize>>,
+    nullable: HashSet<char>,
+}
+
+impl EarleyParser {
+    /// Creates a parser of the grammar, whose start symbol is the nonterminal of its first rule.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the grammar has no rules.
+    pub fn new(grammar: &Grammar) -> Self {
+        assert!(!grammar.is_empty(), "the grammar has no rules");
+        let mut rules_of: HashMap<char, Vec<usize>> = HashMap::new();
+        for (index, (symbol, _)) in grammar.iter().enumerate() {
+            rules_of.entry(*symbol).or_default().push(index);
+        }
+        let mut nullable = HashSet::new();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (symbol, production) in grammar {
+                if !nullable.contains(symbol)
+                    && production
+                        .iter()
+                        .all(|s| matches!(s, Symbol::Nonterminal(n) if nullable.contains(n)))
+                {
+                    nullable.insert(*symbol);
+                    changed = true;
+                }
+            }
+        }
+        EarleyParser {
+            rules: grammar.clone(),
+            rules_of,
+            nullable,
+        }
+    }
+
+    fn start(&self) -> char {
+        self.rules[0].0
+    }
+
+    fn next_symbol(&self, item: &EarleyItem) -> Option<Symbol> {
+        self.rules[item.rule].1.get(item.dot).copied()
+    }
+
+    /// Fills the chart: `chart[k]` holds the items matching the input up to the position `k`.
+    fn chart(&self, input: &[char]) -> Vec<Vec<EarleyItem>> {
+        let n = input.len();
+        let mut chart: Vec<Vec<EarleyItem>> = vec![vec![]; n + 1];
+        let mut seen: Vec<HashSet<EarleyItem>> = vec![HashSet::new(); n + 1];
+        let mut add = |chart: &mut Vec<Vec<EarleyItem>>, k: usize, item: EarleyItem| {
+            if seen[k].insert(item) {
+                chart[k].push(item);
+            }
+        };
+        for &rule in &self.rules_of[&self.start()] {
+            add(
+                &mut chart,
+                0,
+                EarleyItem {
+                    rule,
+                    dot: 0,
+                    origin: 0,
+                },
+            );
+        }
+        for k in 0..=n {
+            let mut i = 0;
+            while i < chart[k].len() {
+                let item = chart[k][i];
+                let advanced = EarleyItem {
+                    dot: item.dot + 1,
+                    ..item
+                };
+                match self.next_symbol(&item) {
+                    // prediction
+                    Some(Symbol::Nonterminal(symbol)) => {
+                        for &rule in self.rules_of.get(&symbol).into_iter().flatten() {
+                            add(
+                                &mut chart,
+                                k,
+                                EarleyItem {
+                                    rule,
+                                    dot: 0,
+                                    origin: k,
+                                },
+                            );
+                        }
+                        if self.nullable.contains(&symbol) {
+                            add(&mut chart, k, advanced);
+                        }
+                    }
+                    // scanning
+                    Some(Symbol::Terminal(terminal)) => {
+                        if input.get(k) == Some(&terminal) {
+                            add(&mut chart, k + 1, advanced);
+                        }
+                    }
+                    // completion
+                    None => {
+                        let symbol = Symbol::Nonterminal(self.rules[item.rule].0);
+                        let mut j = 0;
+                        while j < chart[item.origin].len() {
+                            let waiting = chart[item.origin][j];
+                            if self.next_symbol(&waiting) == Some(symbol) {
+                                let advanced = EarleyItem {
+                                    dot: waiting.dot + 1,
+                                    ..waiting
+                                };
+                                add(&mut chart, k, advanced);
+                            }
+                            j += 1;
+                        }
+                    }
+                }
+                i += 1;
+            }
+        }
+        chart
+    }
+
+    fn is_complete(&self, item: &EarleyItem) -> bool {
+        item.dot == self.rules[item.rule].1.len()
+    }
+
+    /// Returns `true` if the grammar derives `input`.
+    pub fn parse(&self, input: &[char]) -> bool {
+        let start = self.start();
+        self.chart(input)[input.len()].iter().any(|item| {
+            item.origin == 0 && self.is_complete(item) && self.rules[item.rule].0 == start
+        })
+    }
+
+    /// Parses `input`, returning all its parse trees from the start symbol if the grammar
+    /// derives it.
+    pub fn parse_forest(&self, input: &[char]) -> Option<ParseForest> {
+        let chart = self.chart(input);
+        // the complete items are the spans derived by their nonterminals
+        let mut derived: HashSet<Span> = HashSet::new();
+        let mut complete = vec![];
+        for (end, items) in chart.iter().enumerate() {
+            for item in items.iter().filter(|item| self.is_complete(item)) {
+                derived.insert((
+                    Symbol::Nonterminal(self.rules[item.rule].0),
+                    item.origin,
+                    end,
+                ));
+                complete.push((item.rule, item.origin, end));
+            }
+        }
+        let root = (Symbol::Nonterminal(self.start()), 0, input.len());
+        if !derived.contains(&root) {
+            return None;
+        }
+        let mut derivations: HashMap<Span, Vec<(usize, Vec<Span>)>> = HashMap::new();
+        for (rule, start, end) in complete {
+            let span = (Symbol::Nonterminal(self.rules[rule].0), start, end);
+            let mut splits = vec![];
+            split(
+                input,
+                &derived,
+                &self.rules[rule].1,
+                start,
+                end,
+                &mut vec![],
+                &mut splits,
+            );
+            derivations
+                .entry(span)
+                .or_default()
+                .extend(splits.into_iter().map(|children| (rule, children)));
+        }
+        Some(ParseForest {
+            root,
+            rules: self.rules.clone(),
+            derivations,
+        })
+    }
+}
+
+/// Finds every way for the symbols to derive `input[start..end]` in turn, from the spans
+/// derived by the nonterminals.
+fn split(
+    input: &[char],
+    derived: &HashSet<Span>,
+    symbols: &[Symbol],
+    start: usize,
+    end: usize,
+    children: &mut Vec<Span>,
+    splits: &mut Vec<Vec<Span>>,
+) {
+    let Some((&symbol, rest)) = symbols.split_first() else {
+        if start == end {
+            splits.push(children.clone());
+        }
+        return;
+    };
+    let ends: Vec<usize> = match symbol {
+        Symbol::Terminal(terminal) => (input.get(start) == Some(&terminal) && start < end)
+            .then_some(start + 1)
+            .into_iter()
+            .collect(),
+        Symbol::Nonterminal(_) => (start..=end)
+            .filter(|&middle| derived.contains(&(symbol, start, middle)))
+            .collect(),
+    };
+    for middle in ends {
+        children.push((symbol, start, middle));
+        split(input, derived, rest, middle, end, children, splits);
+        children.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::CykParser;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    /// Reads the rules like `E -> E+T`, the uppercase letters being the nonterminals.
+    fn grammar(rules: &[&str]) -> Grammar {
+        rules
+            .iter()
+            .map(|rule| {
+                let (symbol, production) = rule.split_once(" -> ").unwrap();
+                let production = production
+                    .chars()
+                    .map(|c| {
+                        if c.is_ascii_uppercase() {
+                            Symbol::Nonterminal(c)
+                        } else {
+                            Symbol::Terminal(c)
+                        }
+                    })
+                    .collect();
+                (symbol.chars().next().unwrap(), production)
+            })
+            .collect()
+    }
+
+    /// Sums and products of digits, left recursive for the usual precedence and associativity
+    fn arithmetic() -> EarleyParser {
+        let mut rules = vec!["E -> E+T", "E -> T", "T -> T*F", "T -> F", "F -> (E)"];
+        let digits: Vec<String> = (0..10).map(|d| format!("F -> {d}")).collect();
+        rules.extend(digits.iter().map(String::as_str));
+        EarleyParser::new(&grammar(&rules))
+    }
+
+    #[test]
+    fn arithmetic_expressions() {
+        let parser = arithmetic();
+        for valid in [
+            "1",
+            "1+2",
+            "3*4+5",
+            "(1+2)*3",
+            "((7))",
+            "1+(2*3)*(4+5)",
+            "1+2+3+4",
+        ] {
+            assert!(parser.parse(&chars(valid)), "{valid} is valid");
+            assert_eq!(
+                parser.parse_forest(&chars(valid)).unwrap().count_trees(),
+                Some(1)
+            );
+        }
+        for invalid in ["", "+", "1+", "*1", "(1", "1)", "()", "1(2)", "1++2", "a"] {
+            assert!(!parser.parse(&chars(invalid)), "{invalid} is invalid");
+            assert!(parser.parse_forest(&chars(invalid)).is_none());
+        }
+        // the sum is left associative: ((1+2)+3)
+        let tree = &parser.parse_forest(&chars("1+2+3")).unwrap().trees(2)[0];
+        assert_eq!(tree.leaves(), chars("1+2+3"));
+        let EarleyTree::Node { symbol, children } = tree else {
+            panic!("the root is a nonterminal");
+        };
+        assert_eq!(*symbol, 'E');
+        assert_eq!(children[0].leaves(), chars("1+2"));
+        assert_eq!(children[2].leaves(), chars("3"));
+    }
+
+    #[test]
+    fn ambiguous_grammar() {
+        let parser = EarleyParser::new(&grammar(&["E -> E+E", "E -> E*E", "E -> a"]));
+        let forest = parser.parse_forest(&chars("a+a+a")).unwrap();
+        assert!(forest.is_ambiguous());
+        assert_eq!(forest.count_trees(), Some(2));
+        // the binary trees over 4 operands, a Catalan number
+        let input = chars("a+a*a+a");
+        let forest = parser.parse_forest(&input).unwrap();
+        assert_eq!(forest.count_trees(), Some(5));
+        let trees = forest.trees(10);
+        assert_eq!(trees.len(), 5);
+        assert!(trees.iter().all(|tree| tree.leaves() == input));
+        assert!(trees[1..].iter().all(|tree| *tree != trees[0]));
+        assert_eq!(forest.trees(3).len(), 3);
+        // 12 operands
+        let input = chars("a+a+a+a+a+a+a+a+a+a+a+a");
+        assert_eq!(
+            parser.parse_forest(&input).unwrap().count_trees(),
+            Some(58786)
+        );
+        assert!(!parser.parse(&chars("a+")));
+    }
+
+    #[test]
+    fn empty_rules() {
+        let parser = EarleyParser::new(&grammar(&["S -> aSb", "S -> "]));
+        for (input, valid) in [
+            ("", true),
+            ("ab", true),
+            ("aabb", true),
+            ("aab", false),
+            ("ba", false),
+        ] {
+            assert_eq!(parser.parse(&chars(input)), valid, "{input}");
+        }
+        // the empty nonterminals completed before the items waiting for them
+        let parser = EarleyParser::new(&grammar(&["S -> ABAc", "A -> ", "A -> a", "B -> A"]));
+        for valid in ["c", "ac", "aac", "aaac"] {
+            assert!(parser.parse(&chars(valid)), "{valid}");
+        }
+        assert!(!parser.parse(&chars("aaaac")));
+        assert_eq!(
+            parser.parse_forest(&chars("c")).unwrap().count_trees(),
+            Some(1)
+        );
+        // a, B and A can each take the a
+        assert_eq!(
+            parser.parse_forest(&chars("ac")).unwrap().count_trees(),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn agrees_with_cyk() {
+        let earley = EarleyParser::new(&grammar(&["S -> (S)S", "S -> "]));
+        let cyk = CykParser::new(vec![
+            ('S', vec![vec!['S', 'S'], vec!['L', 'R'], vec!['L', 'A']]),
+            ('A', vec![vec!['S', 'R']]),
+            ('L', vec![vec!['(']]),
+            ('R', vec![vec![')']]),
+        ]);
+        for length in 1..=10 {
+            for bits in 0..1u32 << length {
+                let s: Vec<char> = (0..length)
+                    .map(|i| if bits >> i & 1 == 1 { '(' } else { ')' })
+                    .collect();
+                assert_eq!(earley.parse(&s), cyk.parse(&s), "{s:?}");
+                if earley.parse(&s) {
+                    // the grammar of Earley is unambiguous
+                    assert_eq!(earley.parse_forest(&s).unwrap().count_trees(), Some(1));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cyclic_grammar() {
+        let parser = EarleyParser::new(&grammar(&["S -> S", "S -> a", "S -> SS"]));
+        let forest = parser.parse_forest(&chars("a")).unwrap();
+        assert_eq!(forest.count_trees(), None);
+        assert_eq!(
+            forest.trees(10),
+            [EarleyTree::Node {
+                symbol: 'S',
+                children: vec![EarleyTree::Terminal('a')]
+            }]
+        );
+        assert_eq!(
+            parser.parse_forest(&chars("aa")).unwrap().count_trees(),
+            None
+        );
+        assert!(!parser.parse(&chars("")));
+    }
+
+    #[test]
+    fn undefined_nonterminals() {
+        let parser = EarleyParser::new(&grammar(&["S -> aX", "S -> b"]));
+        assert!(parser.parse(&chars("b")));
+        assert!(!parser.parse(&chars("a")));
+        assert!(!parser.parse(&chars("ax")));
+    }
+
+    #[test]
+    #[should_panic(expected = "the grammar has no rules")]
+    fn empty_grammar() {
+        EarleyParser::new(&vec![]);
+    }
+}
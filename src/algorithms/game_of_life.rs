@@ -0,0 +1,378 @@
+use crate::conversions::ParseError;
+
+/// How the edges of the grid of a `GameOfLife` meet
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Topology {
+    /// The cells beyond the edges are always dead.
+    Finite,
+    /// The left edge meets the right one, and the top edge the bottom one.
+    Toroidal,
+    /// The left edge meets the right one, and the top edge the bottom one flipped left to
+    /// right, as on a Klein bottle.
+    Klein,
+}
+
+/// Conway's Game of Life on a grid of `width` columns and `height` rows
+/// <https://en.wikipedia.org/wiki/Conway%27s_Game_of_Life>
+///
+/// Every step applies the rule B3/S23 to all the cells at once: a dead cell with exactly three
+/// live neighbors among the eight around it is born, a live one with two or three survives, and
+/// all the others die or stay dead. A step takes O(width height). The topology tells which
+/// cells neighbor those of the edges.
+/// <https://en.wikipedia.org/wiki/Klein_bottle>
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameOfLife {
+    width: usize,
+    height: usize,
+    topology: Topology,
+    /// The cells, row by row
+    cells: Vec<bool>,
+}
+
+impl GameOfLife {
+    /// Builds a grid of dead cells.
+    pub fn new(width: usize, height: usize, topology: Topology) -> Self {
+        GameOfLife {
+            width,
+            height,
+            topology,
+            cells: vec![false; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index(&self, x: usize, y: usize) -> usize {
+        assert!(
+            x < self.width && y < self.height,
+            "the cell ({x}, {y}) is outside the {} by {} grid",
+            self.width,
+            self.height
+        );
+        y * self.width + x
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the cell is outside the grid.
+    pub fn set_cell(&mut self, x: usize, y: usize, alive: bool) {
+        let index = self.index(x, y);
+        self.cells[index] = alive;
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the cell is outside the grid.
+    pub fn is_alive(&self, x: usize, y: usize) -> bool {
+        self.cells[self.index(x, y)]
+    }
+
+    pub fn count_alive(&self) -> usize {
+        self.cells.iter().filter(|&&alive| alive).count()
+    }
+
+    /// The cell at `(x, y)` offset by `(dx, dy)`, if it is in the grid with the topology
+    fn neighbor(&self, x: usize, y: usize, dx: isize, dy: isize) -> Option<(usize, usize)> {
+        let (width, height) = (self.width as isize, self.height as isize);
+        let (x, y) = (x as isize + dx, y as isize + dy);
+        let (x, y) = match self.topology {
+            Topology::Finite if x < 0 || y < 0 || x >= width || y >= height => return None,
+            Topology::Finite => (x, y),
+            Topology::Toroidal => (x.rem_euclid(width), y.rem_euclid(height)),
+            Topology::Klein => {
+                let column = x.rem_euclid(width);
+                if (0..height).contains(&y) {
+                    (column, y)
+                } else {
+                    (width - 1 - column, y.rem_euclid(height))
+                }
+            }
+        };
+        Some((x as usize, y as usize))
+    }
+
+    /// Moves all the cells to the next generation.
+    pub fn step(&mut self) {
+        let mut next = vec![false; self.cells.len()];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let mut neighbors = 0;
+                for (dx, dy) in [
+                    (-1, -1),
+                    (0, -1),
+                    (1, -1),
+                    (-1, 0),
+                    (1, 0),
+                    (-1, 1),
+                    (0, 1),
+                    (1, 1),
+                ] {
+                    if let Some((nx, ny)) = self.neighbor(x, y, dx, dy) {
+                        neighbors += self.cells[ny * self.width + nx] as usize;
+                    }
+                }
+                let alive = self.cells[y * self.width + x];
+                next[y * self.width + x] = neighbors == 3 || (alive && neighbors == 2);
+            }
+        }
+        self.cells = next;
+    }
+
+    /// Parses a pattern in the run length encoded format, on a finite grid of the size of its
+    /// header.
+    /// <https://conwaylife.com/wiki/Run_Length_Encoded>
+    ///
+    /// The lines starting with `#` are comments, and the first other one is the header
+    /// `x = <width>, y = <height>`, with an optional `rule = B3/S23`, the only rule of the
+    /// game. The pattern follows, as runs `<count><tag>` where the count defaults to 1 and the
+    /// tag is `b` for dead cells, `o` for live ones, or `$` for the end of a row, until a `!`.
+    /// The cells missing at the end of the rows are dead.
+    ///
+    /// Fails with `ParseError::Empty` without a header or a size in it, with
+    /// `ParseError::InvalidDigit` on an unknown field, rule or tag, with `ParseError::Overflow`
+    /// on a size or a count too large, and with `ParseError::InvalidPadding` if the pattern
+    /// does not fit in its size.
+    pub fn from_rle(rle: &str) -> Result<Self, ParseError> {
+        let offset = |s: &str| s.as_ptr() as usize - rle.as_ptr() as usize;
+        let invalid = |s: &str| ParseError::InvalidDigit {
+            position: offset(s),
+            character: s.chars().next().unwrap_or(' '),
+        };
+        let mut lines = rle
+            .lines()
+            .map(str::trim)
+            .skip_while(|line| line.is_empty() || line.starts_with('#'));
+        let header = lines.next().ok_or(ParseError::Empty)?;
+        let (mut width, mut height) = (None, None);
+        for field in header.split(',') {
+            let (key, value) = field.split_once('=').ok_or_else(|| invalid(field.trim()))?;
+            let (key, value) = (key.trim(), value.trim());
+            match key {
+                "x" | "y" => {
+                    if let Some(i) = value.find(|c: char| !c.is_ascii_digit()) {
+                        return Err(invalid(&value[i..]));
+                    }
+                    if value.is_empty() {
+                        return Err(ParseError::Empty);
+                    }
+                    let size = value.parse().map_err(|_| ParseError::Overflow)?;
+                    *(if key == "x" { &mut width } else { &mut height }) = Some(size);
+                }
+                "rule" if ["b3/s23", "23/3"].contains(&value.to_lowercase().as_str()) => {}
+                "rule" => return Err(invalid(value)),
+                _ => return Err(invalid(key)),
+            }
+        }
+        let (width, height) = width.zip(height).ok_or(ParseError::Empty)?;
+        let mut game = GameOfLife::new(width, height, Topology::Finite);
+        let (mut x, mut y) = (0, 0);
+        let mut count: Option<usize> = None;
+        'pattern: for line in lines {
+            for (i, character) in line.char_indices() {
+                if let Some(digit) = character.to_digit(10) {
+                    let longer = count.unwrap_or(0).checked_mul(10);
+                    count = Some(longer.ok_or(ParseError::Overflow)? + digit as usize);
+                    continue;
+                }
+                let run = match character {
+                    c if c.is_whitespace() => continue,
+                    'b' | 'o' | '$' => count.take().unwrap_or(1),
+                    '!' => break 'pattern,
+                    _ => return Err(invalid(&line[i..])),
+                };
+                if character == '$' {
+                    (x, y) = (0, y + run);
+                    continue;
+                }
+                let end = x + run;
+                if end > width || y >= height {
+                    return Err(ParseError::InvalidPadding);
+                }
+                if character == 'o' {
+                    for column in x..end {
+                        game.set_cell(column, y, true);
+                    }
+                }
+                x = end;
+            }
+        }
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    const GLIDER: &str =
+        "#N Glider\n#C moving down and right\nx = 3, y = 3, rule = B3/S23\nbob$2bo$3o!";
+
+    /// The live cells, in increasing rows then columns
+    fn alive(game: &GameOfLife) -> Vec<(usize, usize)> {
+        let mut cells = vec![];
+        for y in 0..game.height() {
+            for x in 0..game.width() {
+                if game.is_alive(x, y) {
+                    cells.push((x, y));
+                }
+            }
+        }
+        cells
+    }
+
+    #[test]
+    fn glider_period() {
+        let glider = GameOfLife::from_rle(GLIDER).unwrap();
+        assert_eq!(alive(&glider), [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+        // a glider near the corner of a torus, crossing its edges
+        let mut game = GameOfLife::new(8, 6, Topology::Toroidal);
+        for &(x, y) in &alive(&glider) {
+            game.set_cell((x + 6) % 8, (y + 4) % 6, true);
+        }
+        let start = alive(&game);
+        for shift in 1..=24 {
+            // a period of four steps, the fifth generation being the first moved by (1, 1)
+            for _ in 0..4 {
+                game.step();
+                assert_eq!(game.count_alive(), 5);
+            }
+            let mut moved: Vec<(usize, usize)> = start
+                .iter()
+                .map(|&(x, y)| ((x + shift) % 8, (y + shift) % 6))
+                .collect();
+            moved.sort_by_key(|&(x, y)| (y, x));
+            assert_eq!(alive(&game), moved);
+        }
+        // back where it started after going round both ways
+        assert_eq!(alive(&game), start);
+    }
+
+    #[test]
+    fn finite_grid() {
+        // a blinker oscillates, and a block stays still
+        let rle = "x = 9, y = 5\n$b3o$5$";
+        let mut game = GameOfLife::from_rle(rle).unwrap();
+        game.set_cell(6, 1, true);
+        game.set_cell(7, 1, true);
+        game.set_cell(6, 2, true);
+        game.set_cell(7, 2, true);
+        let start = alive(&game);
+        game.step();
+        assert_eq!(
+            alive(&game),
+            [(2, 0), (2, 1), (6, 1), (7, 1), (2, 2), (6, 2), (7, 2)]
+        );
+        game.step();
+        assert_eq!(alive(&game), start);
+        // a blinker along the edge loses the cells beyond it, then dies out
+        let mut edge = GameOfLife::new(5, 5, Topology::Finite);
+        for x in 0..3 {
+            edge.set_cell(x, 0, true);
+        }
+        edge.step();
+        assert_eq!(alive(&edge), [(1, 0), (1, 1)]);
+        edge.step();
+        assert_eq!(edge.count_alive(), 0);
+    }
+
+    #[test]
+    fn klein_bottle() {
+        // the Klein bottle is covered by a torus twice as high, the lower half of which is the
+        // upper one flipped left to right
+        let mut rng = ChaCha8Rng::seed_from_u64(173);
+        let (width, height) = (7, 5);
+        let mut klein = GameOfLife::new(width, height, Topology::Klein);
+        let mut cover = GameOfLife::new(width, 2 * height, Topology::Toroidal);
+        for y in 0..height {
+            for x in 0..width {
+                let alive = rng.gen_bool(0.4);
+                klein.set_cell(x, y, alive);
+                cover.set_cell(x, y, alive);
+                cover.set_cell(width - 1 - x, y + height, alive);
+            }
+        }
+        for _ in 0..20 {
+            klein.step();
+            cover.step();
+            for y in 0..height {
+                for x in 0..width {
+                    assert_eq!(klein.is_alive(x, y), cover.is_alive(x, y));
+                    assert_eq!(
+                        klein.is_alive(x, y),
+                        cover.is_alive(width - 1 - x, y + height)
+                    );
+                }
+            }
+        }
+        // which differs from the torus of the same size
+        let mut game = GameOfLife::new(5, 5, Topology::Klein);
+        for y in [4, 0, 1] {
+            game.set_cell(0, y, true);
+        }
+        let mut torus = game.clone();
+        torus.topology = Topology::Toroidal;
+        game.step();
+        torus.step();
+        assert_eq!(alive(&torus), [(0, 0), (1, 0), (4, 0)]);
+        assert_ne!(alive(&game), alive(&torus));
+    }
+
+    #[test]
+    fn run_length_encoding() {
+        // counts before row ends skip empty rows, and the rows may be cut short
+        let game = GameOfLife::from_rle("x = 4, y = 4\n2o2$\n 3bo!  ignored").unwrap();
+        assert_eq!(alive(&game), [(0, 0), (1, 0), (3, 2)]);
+        assert_eq!((game.width(), game.height()), (4, 4));
+        let long = GameOfLife::from_rle("x=12,y=1,rule=b3/s23\n12o!").unwrap();
+        assert_eq!(long.count_alive(), 12);
+        assert_eq!(GameOfLife::from_rle("#C nothing"), Err(ParseError::Empty));
+        assert_eq!(GameOfLife::from_rle("x = 3"), Err(ParseError::Empty));
+        assert_eq!(
+            GameOfLife::from_rle("x = 3, y = 3\nbqb!"),
+            Err(ParseError::InvalidDigit {
+                position: 14,
+                character: 'q'
+            })
+        );
+        assert_eq!(
+            GameOfLife::from_rle("x = 3, y = 3, rule = B36/S23\nbob!"),
+            Err(ParseError::InvalidDigit {
+                position: 21,
+                character: 'B'
+            })
+        );
+        assert_eq!(
+            GameOfLife::from_rle("x = 3a, y = 3"),
+            Err(ParseError::InvalidDigit {
+                position: 5,
+                character: 'a'
+            })
+        );
+        assert_eq!(
+            GameOfLife::from_rle("x = 2, y = 2\n3o!"),
+            Err(ParseError::InvalidPadding)
+        );
+        assert_eq!(
+            GameOfLife::from_rle("x = 2, y = 2\n2$o!"),
+            Err(ParseError::InvalidPadding)
+        );
+        assert_eq!(
+            GameOfLife::from_rle("x = 99999999999999999999, y = 1"),
+            Err(ParseError::Overflow)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "the cell (3, 0) is outside the 3 by 2 grid")]
+    fn cell_outside_the_grid() {
+        GameOfLife::new(3, 2, Topology::Toroidal).set_cell(3, 0, true);
+    }
+}
@@ -0,0 +1,178 @@
+/// Answers offline range queries with Mo's algorithm, and returns the state over every range
+/// `data[l..r]` of `queries`, in the order of the queries.
+/// <https://cp-algorithms.com/data_structures/sqrt_decomposition.html#mos-algorithm>
+///
+/// The state covers a window of the data, which `add` and `remove` grow and shrink by one item
+/// at either end. The queries are sorted by the block of size `b = n / sqrt(q)` of their start,
+/// and by their end within a block, going up and down in turn, so that the end of the window
+/// moves O(n) per block and its start O(b) per query: O(n sqrt(q)) calls to `add` and `remove`
+/// in all, instead of O(n q) for answering the queries one by one. `remove` only needs to undo
+/// `add`, and the items may come in and out in any order.
+///
+/// # Panics
+///
+/// Panics if a query has `l > r` or `r > data.len()`.
+pub fn mo_algorithm<T, S, A, R>(
+    data: &[T],
+    queries: &[(usize, usize)],
+    initial_state: S,
+    add: A,
+    remove: R,
+) -> Vec<S>
+where
+    S: Clone,
+    A: Fn(&mut S, &T),
+    R: Fn(&mut S, &T),
+{
+    for &(l, r) in queries {
+        assert!(
+            l <= r && r <= data.len(),
+            "{l}..{r} is not a range of {} items",
+            data.len()
+        );
+    }
+    let block = (data.len() / ((queries.len() as f64).sqrt() as usize).max(1)).max(1);
+    let mut order: Vec<usize> = (0..queries.len()).collect();
+    order.sort_unstable_by_key(|&i| {
+        let (l, r) = queries[i];
+        let end = if (l / block).is_multiple_of(2) {
+            r
+        } else {
+            data.len() - r
+        };
+        (l / block, end)
+    });
+
+    let mut answers: Vec<Option<S>> = vec![None; queries.len()];
+    let mut state = initial_state;
+    // the window is data[start..end]
+    let (mut start, mut end) = (0, 0);
+    for i in order {
+        let (l, r) = queries[i];
+        // growing first, so that the window is never reversed
+        while end < r {
+            add(&mut state, &data[end]);
+            end += 1;
+        }
+        while start > l {
+            start -= 1;
+            add(&mut state, &data[start]);
+        }
+        while end > r {
+            end -= 1;
+            remove(&mut state, &data[end]);
+        }
+        while start < l {
+            remove(&mut state, &data[start]);
+            start += 1;
+        }
+        answers[i] = Some(state.clone());
+    }
+    answers.into_iter().map(Option::unwrap).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use std::cell::Cell;
+
+    /// The counts of every value in the window, with the number of values counted at least once
+    #[derive(Clone)]
+    struct Distinct {
+        counts: Vec<usize>,
+        distinct: usize,
+    }
+
+    fn count_distinct(data: &[usize], queries: &[(usize, usize)], values: usize) -> Vec<usize> {
+        let initial = Distinct {
+            counts: vec![0; values],
+            distinct: 0,
+        };
+        let add = |state: &mut Distinct, &value: &usize| {
+            state.counts[value] += 1;
+            if state.counts[value] == 1 {
+                state.distinct += 1;
+            }
+        };
+        let remove = |state: &mut Distinct, &value: &usize| {
+            state.counts[value] -= 1;
+            if state.counts[value] == 0 {
+                state.distinct -= 1;
+            }
+        };
+        mo_algorithm(data, queries, initial, add, remove)
+            .into_iter()
+            .map(|state| state.distinct)
+            .collect()
+    }
+
+    fn naive_distinct(data: &[usize], l: usize, r: usize) -> usize {
+        let mut values = data[l..r].to_vec();
+        values.sort_unstable();
+        values.dedup();
+        values.len()
+    }
+
+    #[test]
+    fn range_sums() {
+        let data = [3, 1, 4, 1, 5, 9, 2, 6];
+        let queries = [(0, 8), (2, 5), (5, 5), (7, 8), (0, 1), (1, 7)];
+        let sums = mo_algorithm(&data, &queries, 0, |s, &x| *s += x, |s, &x| *s -= x);
+        assert_eq!(sums, [31, 10, 0, 6, 3, 22]);
+        assert!(mo_algorithm(&data, &[], 0, |s, &x| *s += x, |s, &x| *s -= x).is_empty());
+        let empty: [i32; 0] = [];
+        assert_eq!(
+            mo_algorithm(&empty, &[(0, 0)], 0, |s, &x| *s += x, |s, &x| *s -= x),
+            [0]
+        );
+    }
+
+    #[test]
+    fn distinct_values_in_ranges() {
+        let mut rng = ChaCha8Rng::seed_from_u64(144);
+        let n = 1000;
+        let data: Vec<usize> = (0..n).map(|_| rng.gen_range(0..100)).collect();
+        let queries: Vec<(usize, usize)> = (0..1000)
+            .map(|_| {
+                let l = rng.gen_range(0..=n);
+                (l, rng.gen_range(l..=n))
+            })
+            .collect();
+        let expected: Vec<usize> = queries
+            .iter()
+            .map(|&(l, r)| naive_distinct(&data, l, r))
+            .collect();
+        assert_eq!(count_distinct(&data, &queries, 100), expected);
+    }
+
+    #[test]
+    fn moves_the_window_o_n_sqrt_q_times() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1440);
+        let n = 10_000;
+        let data = vec![(); n];
+        let queries: Vec<(usize, usize)> = (0..10_000)
+            .map(|_| {
+                let l = rng.gen_range(0..=n);
+                (l, rng.gen_range(l..=n))
+            })
+            .collect();
+        let moves = Cell::new(0);
+        mo_algorithm(
+            &data,
+            &queries,
+            (),
+            |_, _| moves.set(moves.get() + 1),
+            |_, _| moves.set(moves.get() + 1),
+        );
+        // 3 n sqrt(q), where one by one would take about n q / 3
+        assert!(moves.get() < 3 * n * 100, "{} moves", moves.get());
+    }
+
+    #[test]
+    #[should_panic(expected = "3..9 is not a range of 8 items")]
+    fn query_out_of_range() {
+        mo_algorithm(&[0; 8], &[(3, 9)], 0, |s, &x| *s += x, |s, &x| *s -= x);
+    }
+}
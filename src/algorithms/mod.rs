@@ -0,0 +1,21 @@
+mod cyk;
+mod earley;
+mod game_of_life;
+mod mo;
+mod monotonic;
+mod reservoir_sampling;
+mod shuffle;
+mod wfc;
+
+pub use self::cyk::{CykParser, ParseTree};
+pub use self::earley::{EarleyParser, EarleyTree, Grammar, ParseForest, Symbol};
+pub use self::game_of_life::{GameOfLife, Topology};
+pub use self::mo::mo_algorithm;
+pub use self::monotonic::{
+    largest_rectangle_in_histogram, maximal_rectangle, next_greater_indices, next_smaller_indices,
+    previous_greater_indices, previous_smaller_indices, sliding_window_max, sliding_window_min,
+    MonotonicQueue,
+};
+pub use self::reservoir_sampling::{reservoir_sample_l, reservoir_sample_r};
+pub use self::shuffle::{fisher_yates_shuffle, partial_shuffle};
+pub use self::wfc::{ContradictionError, TileId, WaveFunctionCollapse};
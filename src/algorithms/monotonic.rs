@@ -0,0 +1,361 @@
+//! Algorithms on monotonic stacks and queues
+//!
+//! A monotonic stack keeps its items sorted by popping, before every push, the items the new one
+//! dominates. Every item is pushed and popped once, so scanning an array with it takes O(n) in
+//! total, while it still tells for every item the nearest one before it that dominates it. A
+//! monotonic queue does the same at both ends, to give the extremum of a sliding window.
+
+use crate::error::{AlgorithmError, AlgorithmResult};
+use std::collections::VecDeque;
+
+/// For every index, finds the nearest index in `order` whose item beats it, with a monotonic
+/// stack of the indices not beaten yet.
+fn nearest_beating<T, I>(arr: &[T], order: I, beats: impl Fn(&T, &T) -> bool) -> Vec<Option<usize>>
+where
+    I: Iterator<Item = usize>,
+{
+    let mut result = vec![None; arr.len()];
+    let mut stack: Vec<usize> = vec![];
+    for i in order {
+        while let Some(&top) = stack.last() {
+            if beats(&arr[i], &arr[top]) {
+                result[top] = Some(i);
+                stack.pop();
+            } else {
+                break;
+            }
+        }
+        stack.push(i);
+    }
+    result
+}
+
+/// Returns for every index the first later index with a strictly greater item, in O(n).
+pub fn next_greater_indices<T: PartialOrd>(arr: &[T]) -> Vec<Option<usize>> {
+    nearest_beating(arr, 0..arr.len(), |a, b| a > b)
+}
+
+/// Returns for every index the first later index with a strictly smaller item, in O(n).
+pub fn next_smaller_indices<T: PartialOrd>(arr: &[T]) -> Vec<Option<usize>> {
+    nearest_beating(arr, 0..arr.len(), |a, b| a < b)
+}
+
+/// Returns for every index the last earlier index with a strictly greater item, in O(n).
+pub fn previous_greater_indices<T: PartialOrd>(arr: &[T]) -> Vec<Option<usize>> {
+    nearest_beating(arr, (0..arr.len()).rev(), |a, b| a > b)
+}
+
+/// Returns for every index the last earlier index with a strictly smaller item, in O(n).
+pub fn previous_smaller_indices<T: PartialOrd>(arr: &[T]) -> Vec<Option<usize>> {
+    nearest_beating(arr, (0..arr.len()).rev(), |a, b| a < b)
+}
+
+/// A queue of a stream of values answering the maximum of the values pushed since a given
+/// index, in amortized O(1).
+///
+/// It only keeps the values which are not followed by a greater or equal one, since those can
+/// never be the maximum again: they are decreasing from the front to the back, with the maximum
+/// at the front. Wrap the values in `std::cmp::Reverse` to get the minimum instead.
+#[derive(Clone, Debug)]
+pub struct MonotonicQueue<T> {
+    /// The candidates for the maximum with their indices, decreasing
+    deque: VecDeque<(usize, T)>,
+    pushed: usize,
+}
+
+impl<T: PartialOrd> Default for MonotonicQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PartialOrd> MonotonicQueue<T> {
+    pub fn new() -> Self {
+        MonotonicQueue {
+            deque: VecDeque::new(),
+            pushed: 0,
+        }
+    }
+
+    /// Pushes the next value of the stream, and returns its index: the number of values pushed
+    /// before it.
+    pub fn push(&mut self, value: T) -> usize {
+        while self.deque.back().is_some_and(|(_, last)| *last <= value) {
+            self.deque.pop_back();
+        }
+        let index = self.pushed;
+        self.deque.push_back((index, value));
+        self.pushed += 1;
+        index
+    }
+
+    /// Forgets the values pushed before the index `start`, which left the window.
+    pub fn pop_expired(&mut self, start: usize) {
+        while self.deque.front().is_some_and(|&(index, _)| index < start) {
+            self.deque.pop_front();
+        }
+    }
+
+    /// Returns the maximum of the values pushed and not expired, the latest one if several are
+    /// equal.
+    pub fn max(&self) -> Option<&T> {
+        self.deque.front().map(|(_, value)| value)
+    }
+
+    /// Returns the number of values pushed so far.
+    pub fn pushed(&self) -> usize {
+        self.pushed
+    }
+}
+
+fn check_window(len: usize, k: usize) -> AlgorithmResult<()> {
+    if k == 0 || k > len {
+        return Err(AlgorithmError::InvalidParameter {
+            argument: "k",
+            reason: format!("the window must be between 1 and {len}, got {k}"),
+        });
+    }
+    Ok(())
+}
+
+/// Returns the maximum of every window of `k` consecutive items, in O(n).
+pub fn sliding_window_max<T: PartialOrd + Clone>(arr: &[T], k: usize) -> AlgorithmResult<Vec<T>> {
+    check_window(arr.len(), k)?;
+    let mut queue = MonotonicQueue::new();
+    let mut maxima = Vec::with_capacity(arr.len() - k + 1);
+    for item in arr {
+        let index = queue.push(item);
+        if index >= k - 1 {
+            queue.pop_expired(index + 1 - k);
+            maxima.push((*queue.max().unwrap()).clone());
+        }
+    }
+    Ok(maxima)
+}
+
+/// Returns the minimum of every window of `k` consecutive items, in O(n).
+pub fn sliding_window_min<T: PartialOrd + Clone>(arr: &[T], k: usize) -> AlgorithmResult<Vec<T>> {
+    check_window(arr.len(), k)?;
+    let mut queue = MonotonicQueue::new();
+    let mut minima = Vec::with_capacity(arr.len() - k + 1);
+    for item in arr {
+        let index = queue.push(std::cmp::Reverse(item));
+        if index >= k - 1 {
+            queue.pop_expired(index + 1 - k);
+            minima.push(queue.max().unwrap().0.clone());
+        }
+    }
+    Ok(minima)
+}
+
+/// Returns the largest area of a rectangle in a histogram of bars of width 1, in O(n).
+///
+/// The largest rectangle as high as a bar spans from the bar after the previous lower one to
+/// the bar before the next lower one, both given by a monotonic stack.
+pub fn largest_rectangle_in_histogram(heights: &[u64]) -> u64 {
+    let previous = previous_smaller_indices(heights);
+    let next = next_smaller_indices(heights);
+    (0..heights.len())
+        .map(|i| {
+            let start = previous[i].map_or(0, |p| p + 1);
+            let end = next[i].unwrap_or(heights.len());
+            heights[i] * (end - start) as u64
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Returns the largest area of a rectangle of non-zero cells in a grid, in O(rows * columns).
+///
+/// Every row is the base of a histogram whose bars are the numbers of non-zero cells above and
+/// including the row, and the largest rectangle sits on one of these histograms.
+pub fn maximal_rectangle(grid: &[Vec<u8>]) -> AlgorithmResult<u64> {
+    let columns = grid.first().map_or(0, Vec::len);
+    if let Some(row) = grid.iter().find(|row| row.len() != columns) {
+        return Err(AlgorithmError::DimensionMismatch {
+            argument: "grid",
+            expected: columns,
+            found: row.len(),
+        });
+    }
+    let mut heights = vec![0; columns];
+    let mut largest = 0;
+    for row in grid {
+        for (height, &cell) in heights.iter_mut().zip(row) {
+            *height = if cell != 0 { *height + 1 } else { 0 };
+        }
+        largest = largest.max(largest_rectangle_in_histogram(&heights));
+    }
+    Ok(largest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn random_array(rng: &mut ChaCha8Rng, len: usize) -> Vec<u32> {
+        (0..len).map(|_| rng.gen_range(0..10)).collect()
+    }
+
+    #[test]
+    fn nearest_indices() {
+        let arr = [2, 1, 2, 4, 3];
+        assert_eq!(
+            next_greater_indices(&arr),
+            [Some(3), Some(2), Some(3), None, None]
+        );
+        assert_eq!(
+            next_smaller_indices(&arr),
+            [Some(1), None, None, Some(4), None]
+        );
+        assert_eq!(
+            previous_greater_indices(&arr),
+            [None, Some(0), None, None, Some(3)]
+        );
+        assert_eq!(
+            previous_smaller_indices(&arr),
+            [None, None, Some(1), Some(2), Some(2)]
+        );
+    }
+
+    #[test]
+    fn nearest_indices_agree_with_brute_force() {
+        let mut rng = ChaCha8Rng::seed_from_u64(114);
+        for _ in 0..200 {
+            let len = rng.gen_range(0..40);
+            let arr = random_array(&mut rng, len);
+            let n = arr.len();
+            let next = |beats: fn(u32, u32) -> bool| -> Vec<Option<usize>> {
+                (0..n)
+                    .map(|i| (i + 1..n).find(|&j| beats(arr[j], arr[i])))
+                    .collect()
+            };
+            let previous = |beats: fn(u32, u32) -> bool| -> Vec<Option<usize>> {
+                (0..n)
+                    .map(|i| (0..i).rev().find(|&j| beats(arr[j], arr[i])))
+                    .collect()
+            };
+            assert_eq!(next_greater_indices(&arr), next(|a, b| a > b));
+            assert_eq!(next_smaller_indices(&arr), next(|a, b| a < b));
+            assert_eq!(previous_greater_indices(&arr), previous(|a, b| a > b));
+            assert_eq!(previous_smaller_indices(&arr), previous(|a, b| a < b));
+        }
+    }
+
+    #[test]
+    fn all_equal_and_decreasing() {
+        let equal = [5; 6];
+        assert_eq!(next_greater_indices(&equal), [None; 6]);
+        assert_eq!(previous_smaller_indices(&equal), [None; 6]);
+        assert_eq!(sliding_window_max(&equal, 3).unwrap(), [5; 4]);
+        assert_eq!(largest_rectangle_in_histogram(&[5; 6]), 30);
+
+        let decreasing = [6, 5, 4, 3, 2, 1];
+        assert_eq!(next_greater_indices(&decreasing), [None; 6]);
+        assert_eq!(
+            next_smaller_indices(&decreasing),
+            [Some(1), Some(2), Some(3), Some(4), Some(5), None]
+        );
+        assert_eq!(sliding_window_max(&decreasing, 2).unwrap(), [6, 5, 4, 3, 2]);
+        assert_eq!(sliding_window_min(&decreasing, 2).unwrap(), [5, 4, 3, 2, 1]);
+        assert_eq!(largest_rectangle_in_histogram(&[6, 5, 4, 3, 2, 1]), 12);
+    }
+
+    #[test]
+    fn sliding_windows_agree_with_brute_force() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1140);
+        for _ in 0..200 {
+            let len = rng.gen_range(1..50);
+            let arr = random_array(&mut rng, len);
+            for k in [1, 2, 3, rng.gen_range(1..=arr.len()), arr.len()] {
+                if k > arr.len() {
+                    continue;
+                }
+                let windows = arr.windows(k);
+                let maxima: Vec<u32> = windows.clone().map(|w| *w.iter().max().unwrap()).collect();
+                let minima: Vec<u32> = windows.map(|w| *w.iter().min().unwrap()).collect();
+                assert_eq!(sliding_window_max(&arr, k).unwrap(), maxima);
+                assert_eq!(sliding_window_min(&arr, k).unwrap(), minima);
+            }
+        }
+    }
+
+    #[test]
+    fn window_sizes() {
+        let arr = [1, 3, -1, -3, 5, 3, 6, 7];
+        assert_eq!(sliding_window_max(&arr, 3).unwrap(), [3, 3, 5, 5, 6, 7]);
+        assert_eq!(sliding_window_max(&arr, 1).unwrap(), arr);
+        assert_eq!(sliding_window_max(&arr, 8).unwrap(), [7]);
+        assert_eq!(sliding_window_min(&arr, 8).unwrap(), [-3]);
+        for k in [0, 9] {
+            assert!(matches!(
+                sliding_window_max(&arr, k),
+                Err(AlgorithmError::InvalidParameter { argument: "k", .. })
+            ));
+        }
+        assert!(sliding_window_min::<u8>(&[], 1).is_err());
+    }
+
+    #[test]
+    fn streaming_queue() {
+        let mut queue = MonotonicQueue::new();
+        assert_eq!(queue.max(), None);
+        for value in [4, 2, 12, 3, 8] {
+            queue.push(value);
+        }
+        assert_eq!(queue.max(), Some(&12));
+        queue.pop_expired(3);
+        assert_eq!(queue.max(), Some(&8));
+        queue.push(1);
+        queue.pop_expired(5);
+        assert_eq!(queue.max(), Some(&1));
+        assert_eq!(queue.pushed(), 6);
+        queue.pop_expired(6);
+        assert_eq!(queue.max(), None);
+    }
+
+    #[test]
+    fn histograms() {
+        assert_eq!(largest_rectangle_in_histogram(&[2, 1, 5, 6, 2, 3]), 10);
+        assert_eq!(largest_rectangle_in_histogram(&[2, 4]), 4);
+        assert_eq!(largest_rectangle_in_histogram(&[0, 0]), 0);
+        assert_eq!(largest_rectangle_in_histogram(&[]), 0);
+        let mut rng = ChaCha8Rng::seed_from_u64(11);
+        for _ in 0..100 {
+            let heights: Vec<u64> = (0..rng.gen_range(1..30))
+                .map(|_| rng.gen_range(0..8))
+                .collect();
+            let brute_force = (0..heights.len())
+                .flat_map(|i| (i..heights.len()).map(move |j| (i, j)))
+                .map(|(i, j)| heights[i..=j].iter().min().unwrap() * (j - i + 1) as u64)
+                .max()
+                .unwrap();
+            assert_eq!(largest_rectangle_in_histogram(&heights), brute_force);
+        }
+    }
+
+    #[test]
+    fn maximal_rectangles() {
+        let grid = vec![
+            vec![1, 0, 1, 0, 0],
+            vec![1, 0, 1, 1, 1],
+            vec![1, 1, 1, 1, 1],
+            vec![1, 0, 0, 1, 0],
+        ];
+        // the 2 x 3 rectangle in the bottom right of the middle rows
+        assert_eq!(maximal_rectangle(&grid), Ok(6));
+        assert_eq!(maximal_rectangle(&[vec![0, 0], vec![0, 0]]), Ok(0));
+        assert_eq!(maximal_rectangle(&vec![vec![1; 4]; 3]), Ok(12));
+        assert_eq!(maximal_rectangle(&[]), Ok(0));
+        assert_eq!(
+            maximal_rectangle(&[vec![1, 1], vec![1]]),
+            Err(AlgorithmError::DimensionMismatch {
+                argument: "grid",
+                expected: 2,
+                found: 1
+            })
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use rand::Rng;
+
+/// Draws a uniform sample of `k` items from a stream of unknown length, with Vitter's
+/// Algorithm R <https://en.wikipedia.org/wiki/Reservoir_sampling>
+///
+/// The first `k` items fill the reservoir. Afterwards, the `i`-th item (counting from 1)
+/// replaces a random item of the reservoir with probability `k / i`, which keeps every item
+/// seen so far in the reservoir with the same probability. This takes one random number per
+/// item, O(n) in total.
+///
+/// If the stream has fewer than `k` items, all of them are returned. The order of the sample
+/// is not random.
+pub fn reservoir_sample_r<T: Clone>(
+    mut stream: impl Iterator<Item = T>,
+    k: usize,
+    rng: &mut impl Rng,
+) -> Vec<T> {
+    let mut reservoir: Vec<T> = stream.by_ref().take(k).collect();
+    if reservoir.len() < k {
+        return reservoir;
+    }
+    for (i, item) in stream.enumerate() {
+        // the item is the (k + i + 1)-th one of the stream
+        let j = rng.gen_range(0..=k + i);
+        if j < k {
+            reservoir[j] = item;
+        }
+    }
+    reservoir
+}
+
+/// Draws a uniform sample of `k` items from a stream of unknown length, with Li's
+/// Algorithm L.
+///
+/// Instead of drawing a random number for every item like Algorithm R, it computes how many
+/// items to skip before the next replacement, following the geometric distribution of the
+/// gaps between replacements. Only O(k (1 + log(n / k))) random numbers are drawn, which
+/// makes it much faster when skipping items is cheap.
+///
+/// If the stream has fewer than `k` items, all of them are returned.
+pub fn reservoir_sample_l<T: Clone>(
+    mut stream: impl Iterator<Item = T>,
+    k: usize,
+    rng: &mut impl Rng,
+) -> Vec<T> {
+    let mut reservoir: Vec<T> = stream.by_ref().take(k).collect();
+    if k == 0 || reservoir.len() < k {
+        return reservoir;
+    }
+    // a uniform number in (0, 1], so that its logarithm is finite
+    let mut uniform = || 1.0 - rng.gen::<f64>();
+    // w is distributed as the largest of k uniform numbers: the threshold an item's random key
+    // must beat to enter the reservoir
+    let mut w = (uniform().ln() / k as f64).exp();
+    loop {
+        // saturating conversion: a huge skip just exhausts the stream
+        let skip = (uniform().ln() / (1.0 - w).ln()).floor() as usize;
+        match stream.nth(skip) {
+            Some(item) => {
+                let index = (uniform() * k as f64) as usize;
+                reservoir[index.min(k - 1)] = item;
+                w *= (uniform().ln() / k as f64).exp();
+            }
+            None => return reservoir,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    /// Critical value of the chi-squared distribution with 9 degrees of freedom for p = 0.05
+    const CHI_SQUARED_9_DF: f64 = 16.919;
+
+    fn chi_squared(counts: &[usize], expected: f64) -> f64 {
+        counts
+            .iter()
+            .map(|&count| (count as f64 - expected).powi(2) / expected)
+            .sum()
+    }
+
+    fn check_uniform(sample: impl Fn(&mut ChaCha8Rng) -> Vec<usize>) {
+        let mut rng = ChaCha8Rng::seed_from_u64(108);
+        let trials = 10_000;
+        let mut counts = [0; 10];
+        for _ in 0..trials {
+            let chosen = sample(&mut rng);
+            assert_eq!(chosen.len(), 1);
+            counts[chosen[0]] += 1;
+        }
+        let statistic = chi_squared(&counts, trials as f64 / 10.0);
+        assert!(statistic < CHI_SQUARED_9_DF, "chi-squared was {statistic}");
+    }
+
+    #[test]
+    fn algorithm_r_is_uniform() {
+        check_uniform(|rng| reservoir_sample_r(0..10, 1, rng));
+    }
+
+    #[test]
+    fn algorithm_l_is_uniform() {
+        check_uniform(|rng| reservoir_sample_l(0..10, 1, rng));
+    }
+
+    #[test]
+    fn larger_samples_are_uniform() {
+        // every item of 0..100 should appear in 10% of the samples of 10 items
+        let mut rng = ChaCha8Rng::seed_from_u64(1080);
+        let mut counts_r = [0; 100];
+        let mut counts_l = [0; 100];
+        for _ in 0..2000 {
+            for item in reservoir_sample_r(0..100, 10, &mut rng) {
+                counts_r[item] += 1;
+            }
+            for item in reservoir_sample_l(0..100, 10, &mut rng) {
+                counts_l[item] += 1;
+            }
+        }
+        for counts in [counts_r, counts_l] {
+            assert!(counts.iter().all(|&count| (130..270).contains(&count)));
+        }
+    }
+
+    #[test]
+    fn samples_are_distinct_items_of_the_stream() {
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        for sample in [
+            reservoir_sample_r(0..1000, 50, &mut rng),
+            reservoir_sample_l(0..1000, 50, &mut rng),
+        ] {
+            let mut sorted = sample.clone();
+            sorted.sort_unstable();
+            sorted.dedup();
+            assert_eq!(sorted.len(), 50);
+            assert!(sorted.iter().all(|&item| item < 1000));
+        }
+    }
+
+    #[test]
+    fn short_streams_and_empty_samples() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        assert_eq!(reservoir_sample_r(0..3, 5, &mut rng), vec![0, 1, 2]);
+        assert_eq!(reservoir_sample_l(0..3, 5, &mut rng), vec![0, 1, 2]);
+        assert!(reservoir_sample_r(0..3, 0, &mut rng).is_empty());
+        assert!(reservoir_sample_l(0..3, 0, &mut rng).is_empty());
+        assert!(reservoir_sample_l(std::iter::empty::<u8>(), 2, &mut rng).is_empty());
+        assert_eq!(
+            reservoir_sample_l(["a", "b"].into_iter(), 2, &mut rng),
+            ["a", "b"]
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use rand::Rng;
+
+/// Shuffles `arr` in place with the modern Fisher-Yates shuffle (Durstenfeld's variant)
+/// <https://en.wikipedia.org/wiki/Fisher%E2%80%93Yates_shuffle>
+///
+/// Going from the last position down, every position is swapped with a uniformly random
+/// position at or before it. Each of the n! orders comes out with the same probability, as long
+/// as the random position includes the current one: swapping with any position of the whole
+/// slice instead is a well-known mistake which biases the result. This takes O(n).
+pub fn fisher_yates_shuffle<T>(arr: &mut [T], rng: &mut impl Rng) {
+    for i in (1..arr.len()).rev() {
+        let j = rng.gen_range(0..=i);
+        arr.swap(i, j);
+    }
+}
+
+/// Shuffles only the first `k` positions of `arr`, and returns them.
+///
+/// Going from the first position up, every position is swapped with a uniformly random
+/// position at or after it, so that the first `k` items are a uniform sample without
+/// replacement of the slice, in a random order. The rest of the slice holds the other items in
+/// an unspecified order. This takes O(k), and shuffles the whole slice if `k` is at least its
+/// length.
+pub fn partial_shuffle<'a, T>(arr: &'a mut [T], k: usize, rng: &mut impl Rng) -> &'a [T] {
+    let n = arr.len();
+    let k = k.min(n);
+    for i in 0..k {
+        let j = rng.gen_range(i..n);
+        arr.swap(i, j);
+    }
+    &arr[..k]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use std::collections::HashMap;
+
+    /// Critical value of the chi-squared distribution with 23 degrees of freedom for p = 0.01
+    const CHI_SQUARED_23_DF: f64 = 41.638;
+
+    fn chi_squared(counts: impl Iterator<Item = usize>, expected: f64) -> f64 {
+        counts
+            .map(|count| (count as f64 - expected).powi(2) / expected)
+            .sum()
+    }
+
+    fn check_uniform_permutations(shuffle: impl Fn(&mut [u8], &mut ChaCha8Rng)) {
+        let mut rng = ChaCha8Rng::seed_from_u64(109);
+        let trials = 100_000;
+        let mut counts: HashMap<[u8; 4], usize> = HashMap::new();
+        for _ in 0..trials {
+            let mut arr = [0, 1, 2, 3];
+            shuffle(&mut arr, &mut rng);
+            *counts.entry(arr).or_default() += 1;
+        }
+        // every one of the 24 permutations shows up, about 4167 times
+        assert_eq!(counts.len(), 24);
+        let statistic = chi_squared(counts.into_values(), trials as f64 / 24.0);
+        assert!(statistic < CHI_SQUARED_23_DF, "chi-squared was {statistic}");
+    }
+
+    #[test]
+    fn fisher_yates_is_uniform() {
+        check_uniform_permutations(fisher_yates_shuffle);
+    }
+
+    #[test]
+    fn full_partial_shuffle_is_uniform() {
+        check_uniform_permutations(|arr, rng| {
+            partial_shuffle(arr, 4, rng);
+        });
+    }
+
+    #[test]
+    fn partial_shuffle_samples_without_replacement() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1090);
+        let trials = 20_000;
+        let mut counts = [0; 10];
+        for _ in 0..trials {
+            let mut arr: Vec<usize> = (0..10).collect();
+            let sample = partial_shuffle(&mut arr, 3, &mut rng).to_vec();
+            let mut distinct = sample.clone();
+            distinct.sort_unstable();
+            distinct.dedup();
+            assert_eq!(distinct.len(), 3);
+            for item in sample {
+                counts[item] += 1;
+            }
+            // the whole slice is still a permutation
+            arr.sort_unstable();
+            assert_eq!(arr, (0..10).collect::<Vec<_>>());
+        }
+        // every item is chosen in 30% of the samples
+        assert!(counts.iter().all(|&count| (5700..6300).contains(&count)));
+    }
+
+    #[test]
+    fn edge_cases() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut empty: [u8; 0] = [];
+        fisher_yates_shuffle(&mut empty, &mut rng);
+        assert!(partial_shuffle(&mut empty, 3, &mut rng).is_empty());
+        let mut single = [7];
+        fisher_yates_shuffle(&mut single, &mut rng);
+        assert_eq!(single, [7]);
+        let mut arr = [1, 2, 3];
+        assert!(partial_shuffle(&mut arr, 0, &mut rng).is_empty());
+        assert_eq!(arr, [1, 2, 3]);
+        assert_eq!(partial_shuffle(&mut arr, 10, &mut rng).len(), 3);
+    }
+}
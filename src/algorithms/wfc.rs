@@ -0,0 +1,289 @@
+use rand::Rng;
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::fmt;
+
+/// The identifier of a kind of tile
+pub type TileId = usize;
+
+/// A cell of the grid was left without any possible tile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ContradictionError {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl fmt::Display for ContradictionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "no tile fits the cell ({}, {})", self.x, self.y)
+    }
+}
+
+impl Error for ContradictionError {}
+
+/// Fills a grid with tiles whose neighbors all follow adjacency rules, with the wave function
+/// collapse algorithm
+/// <https://github.com/mxgmn/WaveFunctionCollapse>
+///
+/// Every cell starts with all the tiles possible. The algorithm then repeatedly observes the
+/// undecided cell of least entropy, with the fewest tiles left since the tiles are equally
+/// likely, choosing one of its tiles at random, and propagates the choice. The propagation is
+/// AC-3 arc consistency: a tile stays possible in a cell only while every neighbor still has a
+/// tile allowed next to it, and a cell losing tiles has its own neighbors checked again. There
+/// is no backtracking, so that a cell left without any tile ends the generation.
+/// <https://en.wikipedia.org/wiki/AC-3_algorithm>
+///
+/// The rules list the tiles allowed next to every tile, in any of the four directions. Two
+/// tiles can be neighbors only if each one lists the other.
+pub struct WaveFunctionCollapse {
+    width: usize,
+    height: usize,
+    tiles: Vec<TileId>,
+    /// `allowed[i][j]` tells whether the tiles of indices `i` and `j` can be neighbors
+    allowed: Vec<Vec<bool>>,
+    /// The tiles still possible in every cell, row by row
+    wave: Vec<Vec<bool>>,
+}
+
+impl WaveFunctionCollapse {
+    /// # Panics
+    ///
+    /// Panics if the rules name a tile missing from `tiles`.
+    pub fn new(
+        width: usize,
+        height: usize,
+        tiles: Vec<TileId>,
+        adjacency_rules: HashMap<TileId, Vec<TileId>>,
+    ) -> Self {
+        let index = |tile: &TileId| {
+            tiles
+                .iter()
+                .position(|t| t == tile)
+                .unwrap_or_else(|| panic!("the rules name the unknown tile {tile}"))
+        };
+        let mut listed = vec![vec![false; tiles.len()]; tiles.len()];
+        for (tile, neighbors) in &adjacency_rules {
+            for neighbor in neighbors {
+                listed[index(tile)][index(neighbor)] = true;
+            }
+        }
+        let allowed = (0..tiles.len())
+            .map(|i| {
+                (0..tiles.len())
+                    .map(|j| listed[i][j] && listed[j][i])
+                    .collect()
+            })
+            .collect();
+        WaveFunctionCollapse {
+            width,
+            height,
+            wave: vec![],
+            tiles,
+            allowed,
+        }
+    }
+
+    fn neighbors(&self, cell: usize) -> impl Iterator<Item = usize> {
+        let (x, y, width, height) = (
+            cell % self.width,
+            cell / self.width,
+            self.width,
+            self.height,
+        );
+        [
+            (x > 0).then(|| cell - 1),
+            (x + 1 < width).then(|| cell + 1),
+            (y > 0).then(|| cell - width),
+            (y + 1 < height).then(|| cell + width),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    fn contradiction(&self, cell: usize) -> ContradictionError {
+        ContradictionError {
+            x: cell % self.width,
+            y: cell / self.width,
+        }
+    }
+
+    /// Removes the tiles of `cell` without an allowed tile left in `neighbor`, and returns
+    /// whether there were any.
+    fn revise(&mut self, cell: usize, neighbor: usize) -> bool {
+        let mut changed = false;
+        for i in 0..self.tiles.len() {
+            if self.wave[cell][i]
+                && !(0..self.tiles.len()).any(|j| self.wave[neighbor][j] && self.allowed[i][j])
+            {
+                self.wave[cell][i] = false;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Runs AC-3 from the arcs `(cell, neighbor)` of the queue, until every cell has
+    /// support from all its neighbors.
+    fn propagate(&mut self, mut queue: VecDeque<(usize, usize)>) -> Result<(), ContradictionError> {
+        while let Some((cell, neighbor)) = queue.pop_front() {
+            if self.revise(cell, neighbor) {
+                if !self.wave[cell].contains(&true) {
+                    return Err(self.contradiction(cell));
+                }
+                let arcs: Vec<(usize, usize)> = self
+                    .neighbors(cell)
+                    .filter(|&other| other != neighbor)
+                    .map(|other| (other, cell))
+                    .collect();
+                queue.extend(arcs);
+            }
+        }
+        Ok(())
+    }
+
+    /// Generates a new grid, as `height` rows of `width` tiles.
+    pub fn collapse(&mut self, rng: &mut impl Rng) -> Result<Vec<Vec<TileId>>, ContradictionError> {
+        let cells = self.width * self.height;
+        self.wave = vec![vec![true; self.tiles.len()]; cells];
+        if self.tiles.is_empty() && cells > 0 {
+            return Err(self.contradiction(0));
+        }
+        let all_arcs = (0..cells)
+            .flat_map(|cell| self.neighbors(cell).map(move |neighbor| (cell, neighbor)))
+            .collect();
+        self.propagate(all_arcs)?;
+        loop {
+            let counts: Vec<usize> = self
+                .wave
+                .iter()
+                .map(|tiles| tiles.iter().filter(|&&possible| possible).count())
+                .collect();
+            let Some(&least) = counts.iter().filter(|&&count| count > 1).min() else {
+                break;
+            };
+            let undecided: Vec<usize> = (0..cells).filter(|&cell| counts[cell] == least).collect();
+            let cell = undecided[rng.gen_range(0..undecided.len())];
+            let possible: Vec<usize> = (0..self.tiles.len())
+                .filter(|&i| self.wave[cell][i])
+                .collect();
+            let chosen = possible[rng.gen_range(0..possible.len())];
+            for (i, tile) in self.wave[cell].iter_mut().enumerate() {
+                *tile = i == chosen;
+            }
+            let arcs = self
+                .neighbors(cell)
+                .map(|neighbor| (neighbor, cell))
+                .collect();
+            self.propagate(arcs)?;
+        }
+        Ok((0..self.height)
+            .map(|y| {
+                (0..self.width)
+                    .map(|x| {
+                        let tiles = &self.wave[y * self.width + x];
+                        self.tiles[tiles.iter().position(|&possible| possible).unwrap()]
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    const BLACK: TileId = 0;
+    const WHITE: TileId = 1;
+
+    fn rules(pairs: &[(TileId, TileId)]) -> HashMap<TileId, Vec<TileId>> {
+        let mut rules: HashMap<TileId, Vec<TileId>> = HashMap::new();
+        for &(a, b) in pairs {
+            rules.entry(a).or_default().push(b);
+            rules.entry(b).or_default().push(a);
+        }
+        rules
+    }
+
+    fn follows_rules(grid: &[Vec<TileId>], pairs: &[(TileId, TileId)]) -> bool {
+        let allowed = |a: TileId, b: TileId| pairs.contains(&(a, b)) || pairs.contains(&(b, a));
+        (0..grid.len()).all(|y| {
+            (0..grid[y].len()).all(|x| {
+                (x + 1 == grid[y].len() || allowed(grid[y][x], grid[y][x + 1]))
+                    && (y + 1 == grid.len() || allowed(grid[y][x], grid[y + 1][x]))
+            })
+        })
+    }
+
+    #[test]
+    fn checkerboard() {
+        let pairs = [(BLACK, WHITE)];
+        let mut wfc = WaveFunctionCollapse::new(8, 5, vec![BLACK, WHITE], rules(&pairs));
+        let grid = wfc.collapse(&mut ChaCha8Rng::seed_from_u64(142)).unwrap();
+        assert_eq!(grid.len(), 5);
+        assert!(grid.iter().all(|row| row.len() == 8));
+        let corner = grid[0][0];
+        for (y, row) in grid.iter().enumerate() {
+            for (x, &tile) in row.iter().enumerate() {
+                assert_eq!(tile == corner, (x + y) % 2 == 0);
+            }
+        }
+        // the same seed gives the same grid, and other seeds give both checkerboards
+        let again = wfc.collapse(&mut ChaCha8Rng::seed_from_u64(142)).unwrap();
+        assert_eq!(again, grid);
+        let corners: Vec<TileId> = (0..20)
+            .map(|seed| wfc.collapse(&mut ChaCha8Rng::seed_from_u64(seed)).unwrap()[0][0])
+            .collect();
+        assert!(corners.contains(&BLACK) && corners.contains(&WHITE));
+    }
+
+    #[test]
+    fn coastlines() {
+        // land never touches the sea directly
+        let (land, coast, sea) = (10, 20, 30);
+        let pairs = [
+            (land, land),
+            (land, coast),
+            (coast, coast),
+            (coast, sea),
+            (sea, sea),
+        ];
+        let mut wfc = WaveFunctionCollapse::new(20, 15, vec![land, coast, sea], rules(&pairs));
+        let mut rng = ChaCha8Rng::seed_from_u64(1420);
+        for _ in 0..20 {
+            let grid = wfc.collapse(&mut rng).unwrap();
+            assert!(follows_rules(&grid, &pairs));
+        }
+    }
+
+    #[test]
+    fn contradictions() {
+        // every tile lists the next one only, so that no two tiles are allowed side by side
+        let rules = HashMap::from([(0, vec![1]), (1, vec![2]), (2, vec![0])]);
+        let mut rng = ChaCha8Rng::seed_from_u64(1421);
+        let mut wfc = WaveFunctionCollapse::new(2, 2, vec![0, 1, 2], rules.clone());
+        let error = wfc.collapse(&mut rng).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("no tile fits the cell ({}, {})", error.x, error.y)
+        );
+        // a single cell has no neighbors to disagree with
+        let mut single = WaveFunctionCollapse::new(1, 1, vec![0, 1, 2], rules);
+        assert_eq!(single.collapse(&mut rng).unwrap().len(), 1);
+        let mut empty = WaveFunctionCollapse::new(3, 3, vec![], HashMap::new());
+        assert_eq!(
+            empty.collapse(&mut rng),
+            Err(ContradictionError { x: 0, y: 0 })
+        );
+        let mut no_cells = WaveFunctionCollapse::new(0, 0, vec![BLACK], HashMap::new());
+        assert_eq!(no_cells.collapse(&mut rng), Ok(vec![]));
+    }
+
+    #[test]
+    #[should_panic(expected = "the rules name the unknown tile 7")]
+    fn unknown_tile() {
+        WaveFunctionCollapse::new(2, 2, vec![BLACK], HashMap::from([(BLACK, vec![7])]));
+    }
+}
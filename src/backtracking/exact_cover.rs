@@ -0,0 +1,339 @@
+/*
+    Exact cover with Knuth's Algorithm X and dancing links
+    https://en.wikipedia.org/wiki/Knuth%27s_Algorithm_X
+    https://arxiv.org/abs/cs/0011047
+
+    Given a set of columns and rows, each row covering some of the columns, the
+    exact cover problem asks for a set of rows covering every column exactly
+    once. Secondary columns may be covered at most once instead.
+
+    Algorithm X picks the primary column covered by the fewest rows, and tries
+    in turn every row covering it: the row goes into the solution, and the
+    columns it covers are removed along with the rows conflicting with it,
+    before searching the smaller problem which is left. The matrix is a grid
+    of circular doubly linked lists, one per row and one per column, so that
+    a node removed from its lists still knows its neighbors and can be put
+    back in O(1) when backtracking: the links "dance".
+
+    The nodes live in an arena of vectors and link to each other by index.
+*/
+
+use crate::error::{AlgorithmError, AlgorithmResult};
+
+/// The index of a row, in the order the rows were added
+pub type RowId = usize;
+
+/// The head of the list of the primary columns left to cover
+const ROOT: usize = 0;
+
+#[derive(Clone, Debug)]
+pub struct ExactCover {
+    n_columns: usize,
+    n_rows: usize,
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    /// The column header of every node
+    column: Vec<usize>,
+    /// The row of every node, unused for the headers
+    row: Vec<RowId>,
+    /// The number of rows left in every column, indexed by header
+    size: Vec<usize>,
+}
+
+impl ExactCover {
+    /// Creates a problem with `n_columns` primary columns, to be covered exactly once.
+    pub fn new(n_columns: usize) -> Self {
+        Self::with_secondary_columns(n_columns, 0)
+    }
+
+    /// Creates a problem with `n_primary` columns to be covered exactly once, numbered first, and
+    /// `n_secondary` columns to be covered at most once, numbered after them.
+    pub fn with_secondary_columns(n_primary: usize, n_secondary: usize) -> Self {
+        let n_columns = n_primary + n_secondary;
+        // the root and the headers of the columns are the first nodes, and the headers of the
+        // secondary columns are not in the list of the root, so they never need to be covered
+        let headers = n_columns + 1;
+        let mut left: Vec<usize> = (0..headers).map(|i| i.wrapping_sub(1)).collect();
+        let mut right: Vec<usize> = (1..=headers).collect();
+        left[ROOT] = n_primary;
+        right[n_primary] = ROOT;
+        for header in n_primary + 1..headers {
+            left[header] = header;
+            right[header] = header;
+        }
+        ExactCover {
+            n_columns,
+            n_rows: 0,
+            left,
+            right,
+            up: (0..headers).collect(),
+            down: (0..headers).collect(),
+            column: (0..headers).collect(),
+            row: vec![0; headers],
+            size: vec![0; headers],
+        }
+    }
+
+    /// Adds a row covering the given columns, and returns its id.
+    pub fn add_row(&mut self, columns: &[usize]) -> AlgorithmResult<RowId> {
+        if let Some(&column) = columns.iter().find(|&&column| column >= self.n_columns) {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "columns",
+                reason: format!(
+                    "the column {column} is out of the {} columns",
+                    self.n_columns
+                ),
+            });
+        }
+        let mut sorted = columns.to_vec();
+        sorted.sort_unstable();
+        if sorted.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "columns",
+                reason: "a row covers every column at most once".to_string(),
+            });
+        }
+        let id = self.n_rows;
+        self.n_rows += 1;
+        let first = self.column.len();
+        for (k, &column) in columns.iter().enumerate() {
+            let node = first + k;
+            let header = column + 1;
+            self.left.push(if k == 0 {
+                first + columns.len() - 1
+            } else {
+                node - 1
+            });
+            self.right.push(if k + 1 == columns.len() {
+                first
+            } else {
+                node + 1
+            });
+            self.up.push(self.up[header]);
+            self.down.push(header);
+            let last = self.up[header];
+            self.down[last] = node;
+            self.up[header] = node;
+            self.column.push(header);
+            self.row.push(id);
+            self.size[header] += 1;
+        }
+        Ok(id)
+    }
+
+    /// Returns the first solution found, as the ids of its rows in increasing order.
+    pub fn solve_first(&mut self) -> Option<Vec<RowId>> {
+        self.solve_all(1).pop()
+    }
+
+    /// Returns up to `limit` solutions, each as the ids of its rows in increasing order.
+    pub fn solve_all(&mut self, limit: usize) -> Vec<Vec<RowId>> {
+        let mut solutions = vec![];
+        if limit > 0 {
+            self.search(&mut vec![], &mut |rows| {
+                let mut solution = rows.to_vec();
+                solution.sort_unstable();
+                solutions.push(solution);
+                solutions.len() < limit
+            });
+        }
+        solutions
+    }
+
+    /// Counts the solutions, stopping at `limit`: a limit of 2 tells whether a solution is
+    /// unique.
+    pub fn count_solutions(&mut self, limit: usize) -> usize {
+        let mut count = 0;
+        if limit > 0 {
+            self.search(&mut vec![], &mut |_| {
+                count += 1;
+                count < limit
+            });
+        }
+        count
+    }
+
+    /// Calls `visit` on every solution until it returns false, and returns false if it did.
+    fn search(
+        &mut self,
+        partial: &mut Vec<RowId>,
+        visit: &mut dyn FnMut(&[RowId]) -> bool,
+    ) -> bool {
+        if self.right[ROOT] == ROOT {
+            return visit(partial);
+        }
+        let mut best = self.right[ROOT];
+        let mut header = self.right[best];
+        while header != ROOT {
+            if self.size[header] < self.size[best] {
+                best = header;
+            }
+            header = self.right[header];
+        }
+        let mut go_on = true;
+        self.cover(best);
+        let mut node = self.down[best];
+        while node != best && go_on {
+            partial.push(self.row[node]);
+            let mut other = self.right[node];
+            while other != node {
+                self.cover(self.column[other]);
+                other = self.right[other];
+            }
+            go_on = self.search(partial, visit);
+            let mut other = self.left[node];
+            while other != node {
+                self.uncover(self.column[other]);
+                other = self.left[other];
+            }
+            partial.pop();
+            node = self.down[node];
+        }
+        self.uncover(best);
+        go_on
+    }
+
+    /// Removes a column from the columns to cover, and its rows from the other columns.
+    fn cover(&mut self, header: usize) {
+        let (left, right) = (self.left[header], self.right[header]);
+        self.right[left] = right;
+        self.left[right] = left;
+        let mut node = self.down[header];
+        while node != header {
+            let mut other = self.right[node];
+            while other != node {
+                let (up, down) = (self.up[other], self.down[other]);
+                self.down[up] = down;
+                self.up[down] = up;
+                self.size[self.column[other]] -= 1;
+                other = self.right[other];
+            }
+            node = self.down[node];
+        }
+    }
+
+    /// Puts back a column removed by `cover`, undoing its steps in reverse order.
+    fn uncover(&mut self, header: usize) {
+        let mut node = self.up[header];
+        while node != header {
+            let mut other = self.left[node];
+            while other != node {
+                self.size[self.column[other]] += 1;
+                let (up, down) = (self.up[other], self.down[other]);
+                self.down[up] = other;
+                self.up[down] = other;
+                other = self.left[other];
+            }
+            node = self.up[node];
+        }
+        let (left, right) = (self.left[header], self.right[header]);
+        self.right[left] = header;
+        self.left[right] = header;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn knuth_example() {
+        // the example of the paper, whose only solution is the rows 0, 3 and 4
+        let mut problem = ExactCover::new(7);
+        for row in [
+            vec![2, 4, 5],
+            vec![0, 3, 6],
+            vec![1, 2, 5],
+            vec![0, 3],
+            vec![1, 6],
+            vec![3, 4, 6],
+        ] {
+            problem.add_row(&row).unwrap();
+        }
+        assert_eq!(problem.solve_first(), Some(vec![0, 3, 4]));
+        assert_eq!(problem.solve_all(10), [[0, 3, 4]]);
+        assert_eq!(problem.count_solutions(10), 1);
+        // solving leaves the matrix as it was
+        assert_eq!(problem.solve_first(), Some(vec![0, 3, 4]));
+    }
+
+    #[test]
+    fn several_solutions() {
+        let mut problem = ExactCover::new(4);
+        for row in [
+            vec![0, 1],
+            vec![2, 3],
+            vec![0, 2],
+            vec![1, 3],
+            vec![0, 1, 2, 3],
+            vec![1, 2],
+        ] {
+            problem.add_row(&row).unwrap();
+        }
+        let mut solutions = problem.solve_all(10);
+        solutions.sort();
+        assert_eq!(solutions, [vec![0, 1], vec![2, 3], vec![4]]);
+        assert_eq!(problem.count_solutions(10), 3);
+        assert_eq!(problem.count_solutions(2), 2);
+        assert_eq!(problem.solve_all(1).len(), 1);
+        assert!(problem.solve_all(0).is_empty());
+
+        let mut impossible = ExactCover::new(3);
+        impossible.add_row(&[0, 1]).unwrap();
+        impossible.add_row(&[1, 2]).unwrap();
+        assert_eq!(impossible.solve_first(), None);
+        assert_eq!(impossible.count_solutions(5), 0);
+
+        // nothing to cover has one solution, the empty one
+        assert_eq!(ExactCover::new(0).solve_all(5), [Vec::<RowId>::new()]);
+    }
+
+    #[test]
+    fn secondary_columns() {
+        // the primary column 0 is covered by exactly one row, and the secondary column 1 by at
+        // most one: rows 0 and 2 conflict on it, and row 1 leaves it uncovered
+        let mut problem = ExactCover::with_secondary_columns(2, 1);
+        problem.add_row(&[0, 2]).unwrap();
+        problem.add_row(&[1]).unwrap();
+        problem.add_row(&[1, 2]).unwrap();
+        problem.add_row(&[0]).unwrap();
+        let mut solutions = problem.solve_all(10);
+        solutions.sort();
+        assert_eq!(solutions, [vec![0, 1], vec![1, 3], vec![2, 3]]);
+
+        // the columns and rows of a board, and its diagonals as secondary columns
+        let queens = |n: usize| {
+            let mut problem = ExactCover::with_secondary_columns(2 * n, 4 * n - 2);
+            for r in 0..n {
+                for c in 0..n {
+                    let diagonal = 2 * n + r + c;
+                    let anti_diagonal = 4 * n - 1 + r + n - 1 - c;
+                    problem
+                        .add_row(&[r, n + c, diagonal, anti_diagonal])
+                        .unwrap();
+                }
+            }
+            problem.count_solutions(usize::MAX)
+        };
+        assert_eq!(queens(4), 2);
+        assert_eq!(queens(6), 4);
+        assert_eq!(queens(8), 92);
+    }
+
+    #[test]
+    fn invalid_rows() {
+        let mut problem = ExactCover::new(3);
+        assert!(matches!(
+            problem.add_row(&[0, 3]),
+            Err(AlgorithmError::InvalidParameter {
+                argument: "columns",
+                ..
+            })
+        ));
+        assert!(problem.add_row(&[1, 1]).is_err());
+        assert_eq!(problem.add_row(&[0, 1, 2]), Ok(0));
+        assert_eq!(problem.solve_first(), Some(vec![0]));
+    }
+}
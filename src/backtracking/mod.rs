@@ -1,9 +1,13 @@
 mod all_combination_of_size_k;
+mod exact_cover;
 mod n_queens;
 mod permutations;
+mod polyomino_tiling;
 mod sudoku;
 
 pub use all_combination_of_size_k::generate_all_combinations;
-pub use n_queens::n_queens_solver;
+pub use exact_cover::{ExactCover, RowId};
+pub use n_queens::{n_queens_placements, n_queens_solver};
 pub use permutations::permute;
+pub use polyomino_tiling::{count_polyomino_tilings, pentominoes, Polyomino};
 pub use sudoku::Sudoku;
@@ -29,6 +29,8 @@
     ]
 */
 
+use crate::data_structures::PersistentList;
+
 pub fn n_queens_solver(n: usize) -> Vec<Vec<String>> {
     let mut board = vec![vec!['.'; n]; n];
     let mut solutions = Vec::new();
@@ -80,6 +82,34 @@ fn solve(board: &mut Vec<Vec<char>>, row: usize, solutions: &mut Vec<Vec<String>
     }
 }
 
+/// Returns the column of the queen of every row, for every solution, in the same order as
+/// `n_queens_solver`.
+///
+/// The search keeps its partial solutions on an explicit stack, as persistent lists of the
+/// columns from the last row up: extending a partial solution shares it instead of copying it,
+/// so every partial solution costs one node.
+pub fn n_queens_placements(n: usize) -> Vec<Vec<usize>> {
+    let mut solutions = Vec::new();
+    let mut stack = vec![PersistentList::<usize>::new()];
+    while let Some(partial) = stack.pop() {
+        if partial.len() == n {
+            solutions.push(partial.reverse().iter().copied().collect());
+            continue;
+        }
+        // pushed in reverse so that the smallest column is explored first
+        for col in (0..n).rev() {
+            let safe = partial
+                .iter()
+                .enumerate()
+                .all(|(distance, &other)| other != col && other.abs_diff(col) != distance + 1);
+            if safe {
+                stack.push(partial.cons(col));
+            }
+        }
+    }
+    solutions
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -97,4 +127,17 @@ mod tests {
         // Verify the second solution
         assert_eq!(solutions[1], vec!["..Q.", "Q...", "...Q", ".Q.."]);
     }
+
+    #[test]
+    fn placements_match_the_boards() {
+        assert_eq!(n_queens_placements(4), [[1, 3, 0, 2], [2, 0, 3, 1]]);
+        for n in 1..=7 {
+            let boards: Vec<Vec<usize>> = n_queens_solver(n)
+                .iter()
+                .map(|board| board.iter().map(|row| row.find('Q').unwrap()).collect())
+                .collect();
+            assert_eq!(n_queens_placements(n), boards);
+        }
+        assert_eq!(n_queens_placements(8).len(), 92);
+    }
 }
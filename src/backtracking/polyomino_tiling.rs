@@ -0,0 +1,143 @@
+/*
+    Tiling a rectangle with polyominoes, as an exact cover problem
+    https://en.wikipedia.org/wiki/Pentomino
+
+    Every piece is used exactly once, in any rotation or reflection, and every
+    cell of the board is covered exactly once: a placement of a piece covers
+    the column of the piece and the columns of its cells.
+*/
+
+use super::exact_cover::ExactCover;
+use std::collections::BTreeSet;
+
+/// The cells of a piece, as `(row, column)` pairs
+pub type Polyomino = Vec<(usize, usize)>;
+
+/// Returns the twelve pentominoes, in the order F, I, L, N, P, T, U, V, W, X, Y, Z.
+pub fn pentominoes() -> Vec<Polyomino> {
+    [
+        [".##", "##.", ".#."].as_slice(),
+        &["#####"],
+        &["####", "#..."],
+        &["###.", "..##"],
+        &["###", "##."],
+        &["###", ".#.", ".#."],
+        &["#.#", "###"],
+        &["###", "#..", "#.."],
+        &["#..", "##.", ".##"],
+        &[".#.", "###", ".#."],
+        &["####", ".#.."],
+        &["##.", ".#.", ".##"],
+    ]
+    .iter()
+    .map(|rows| {
+        rows.iter()
+            .enumerate()
+            .flat_map(|(r, row)| row.match_indices('#').map(move |(c, _)| (r, c)))
+            .collect()
+    })
+    .collect()
+}
+
+/// Returns the distinct rotations and reflections of a piece, each moved to touch both axes.
+fn orientations(piece: &[(usize, usize)]) -> BTreeSet<Polyomino> {
+    let mut result = BTreeSet::new();
+    let mut cells: Vec<(i64, i64)> = piece.iter().map(|&(r, c)| (r as i64, c as i64)).collect();
+    for _ in 0..2 {
+        for _ in 0..4 {
+            cells = cells.iter().map(|&(r, c)| (c, -r)).collect();
+            let top = cells.iter().map(|cell| cell.0).min().unwrap_or(0);
+            let left = cells.iter().map(|cell| cell.1).min().unwrap_or(0);
+            let mut normalized: Polyomino = cells
+                .iter()
+                .map(|&(r, c)| ((r - top) as usize, (c - left) as usize))
+                .collect();
+            normalized.sort_unstable();
+            result.insert(normalized);
+        }
+        cells = cells.iter().map(|&(r, c)| (r, -c)).collect();
+    }
+    result
+}
+
+/// Counts the tilings of a `height` by `width` rectangle using every piece exactly once,
+/// stopping at `limit`. Tilings which are rotations or reflections of each other are counted
+/// separately.
+pub fn count_polyomino_tilings(
+    pieces: &[Polyomino],
+    height: usize,
+    width: usize,
+    limit: usize,
+) -> usize {
+    let mut problem = ExactCover::new(pieces.len() + height * width);
+    for (i, piece) in pieces.iter().enumerate() {
+        for shape in orientations(piece) {
+            let rows = shape.iter().map(|cell| cell.0).max().unwrap_or(0) + 1;
+            let columns = shape.iter().map(|cell| cell.1).max().unwrap_or(0) + 1;
+            if rows > height || columns > width {
+                continue;
+            }
+            for top in 0..=height - rows {
+                for left in 0..=width - columns {
+                    let mut covered = vec![i];
+                    covered.extend(
+                        shape
+                            .iter()
+                            .map(|&(r, c)| pieces.len() + (top + r) * width + left + c),
+                    );
+                    problem
+                        .add_row(&covered)
+                        .expect("the cells of a placement are distinct and on the board");
+                }
+            }
+        }
+    }
+    problem.count_solutions(limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pentominoes_are_distinct() {
+        let pieces = pentominoes();
+        assert_eq!(pieces.len(), 12);
+        assert!(pieces.iter().all(|piece| piece.len() == 5));
+        let counts: Vec<usize> = pieces
+            .iter()
+            .map(|piece| orientations(piece).len())
+            .collect();
+        // F, I, L, N, P, T, U, V, W, X, Y, Z
+        assert_eq!(counts, [8, 2, 8, 8, 8, 4, 4, 4, 4, 1, 8, 4]);
+    }
+
+    #[test]
+    fn small_tilings() {
+        let pieces = pentominoes();
+        let (i, l, p) = (&pieces[1], &pieces[2], &pieces[4]);
+        assert_eq!(
+            count_polyomino_tilings(std::slice::from_ref(i), 1, 5, 10),
+            1
+        );
+        assert_eq!(
+            count_polyomino_tilings(std::slice::from_ref(i), 5, 5, 10),
+            0
+        );
+        // the rest of a 2 x 5 rectangle after an L at one end is another L, and the same goes for
+        // P, so both are tiled in two ways, with the two pieces swapped
+        let two = |a: &Polyomino, b: &Polyomino| {
+            count_polyomino_tilings(&[a.clone(), b.clone()], 2, 5, 10)
+        };
+        assert_eq!(two(l, l), 4);
+        assert_eq!(two(p, p), 4);
+        assert_eq!(two(i, i), 2);
+        assert_eq!(two(l, p), 0);
+    }
+
+    #[test]
+    fn all_pentominoes_on_a_3_by_20_rectangle() {
+        // two tilings, each in its four symmetric positions
+        assert_eq!(count_polyomino_tilings(&pentominoes(), 3, 20, 100), 8);
+    }
+}
@@ -1,8 +1,18 @@
 /*
     A Rust implementation of Sudoku solver using Backtracking.
     GeeksForGeeks: https://www.geeksforgeeks.org/sudoku-backtracking-7/
+
+    The puzzle is also an exact cover problem: every cell holds exactly one
+    digit, and every row, column and box holds every digit exactly once. Each
+    candidate digit of a cell covers one of each of these 4 * 81 constraints,
+    and dancing links solve it much faster than plain backtracking.
 */
 
+use super::exact_cover::ExactCover;
+
+/// The row, the column and the digit of a row of the exact cover problem
+type Candidate = (usize, usize, u8);
+
 pub struct Sudoku {
     board: [[u8; 9]; 9],
 }
@@ -81,6 +91,63 @@ impl Sudoku {
         false
     }
 
+    /// Builds the exact cover problem of the board, and returns it along with the cell and the
+    /// digit of every row, or None if a cell holds something else than 0 to 9.
+    fn exact_cover(&self) -> Option<(ExactCover, Vec<Candidate>)> {
+        if self.board.iter().flatten().any(|&given| given > 9) {
+            return None;
+        }
+        let mut problem = ExactCover::new(4 * 81);
+        let mut candidates = vec![];
+        for y in 0..9 {
+            for x in 0..9 {
+                let given = self.board[y][x];
+                let digits = if given == 0 { 1..=9 } else { given..=given };
+                for value in digits {
+                    let digit = value as usize - 1;
+                    let cell = 9 * y + x;
+                    let in_row = 81 + 9 * y + digit;
+                    let in_column = 2 * 81 + 9 * x + digit;
+                    let in_box = 3 * 81 + 9 * (3 * (y / 3) + x / 3) + digit;
+                    problem
+                        .add_row(&[cell, in_row, in_column, in_box])
+                        .expect("the constraints are distinct columns of the problem");
+                    candidates.push((y, x, value));
+                }
+            }
+        }
+        Some((problem, candidates))
+    }
+
+    /// Solves the board with dancing links instead of plain backtracking, and returns whether it
+    /// has a solution. A board with a digit above 9 has none.
+    pub fn solve_exact_cover(&mut self) -> bool {
+        let Some((mut problem, candidates)) = self.exact_cover() else {
+            return false;
+        };
+        match problem.solve_first() {
+            Some(rows) => {
+                for row in rows {
+                    let (y, x, value) = candidates[row];
+                    self.board[y][x] = value;
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Counts the solutions of the board, stopping at `limit`: a proper puzzle has exactly one,
+    /// which a limit of 2 checks.
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        self.exact_cover()
+            .map_or(0, |(mut problem, _)| problem.count_solutions(limit))
+    }
+
+    pub fn board(&self) -> [[u8; 9]; 9] {
+        self.board
+    }
+
     pub fn print_board(&self) {
         // helper function to display board
 
@@ -147,6 +214,88 @@ mod tests {
         assert_eq!(sudoku.board, board_result);
     }
 
+    fn parse(puzzle: &str) -> [[u8; 9]; 9] {
+        let mut board = [[0; 9]; 9];
+        for (i, c) in puzzle.chars().enumerate() {
+            board[i / 9][i % 9] = c.to_digit(10).unwrap_or(0) as u8;
+        }
+        board
+    }
+
+    fn is_complete(board: &[[u8; 9]; 9]) -> bool {
+        let groups = (0..9).flat_map(|i| {
+            [
+                (0..9).map(|j| board[i][j]).collect::<Vec<_>>(),
+                (0..9).map(|j| board[j][i]).collect(),
+                (0..9)
+                    .map(|j| board[3 * (i / 3) + j / 3][3 * (i % 3) + j % 3])
+                    .collect(),
+            ]
+        });
+        groups.into_iter().all(|mut group| {
+            group.sort_unstable();
+            group == [1, 2, 3, 4, 5, 6, 7, 8, 9]
+        })
+    }
+
+    #[test]
+    fn exact_cover_agrees_with_backtracking() {
+        for puzzle in [
+            "3.65.84..52........87....31..3.1..8.9..863..5.5..9.6..13....25........74..52.63..",
+            "..3.2.6..9..3.5..1..18.64....81.29..7.......8..67.82....26.95..8..2.3..9..5.1.3..",
+            "2...8.3...6..7..84.3.5..2.9...1.54.8.........4.27.6...3.1..7.4.72..4..6...4.1...3",
+            "......9.7...42.18....7.5.261..9.4....5.....4....5.7..992.1.8....34.59...5.7......",
+        ] {
+            let mut backtracking = Sudoku::new(parse(puzzle));
+            let mut dancing_links = Sudoku::new(parse(puzzle));
+            assert!(backtracking.solve());
+            assert!(dancing_links.solve_exact_cover());
+            assert_eq!(dancing_links.board(), backtracking.board());
+            assert!(is_complete(&dancing_links.board()));
+            assert_eq!(Sudoku::new(parse(puzzle)).count_solutions(2), 1);
+        }
+    }
+
+    #[test]
+    fn several_or_no_solutions() {
+        assert_eq!(Sudoku::new([[0; 9]; 9]).count_solutions(2), 2);
+        // a single row of clues leaves many solutions
+        let mut board = [[0; 9]; 9];
+        board[0] = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+        assert_eq!(Sudoku::new(board).count_solutions(100), 100);
+        // two 1s in the first column
+        let mut board = [[0; 9]; 9];
+        board[0][0] = 1;
+        board[5][0] = 1;
+        let mut sudoku = Sudoku::new(board);
+        assert_eq!(sudoku.count_solutions(2), 0);
+        assert!(!sudoku.solve_exact_cover());
+    }
+
+    #[test]
+    fn digits_above_nine_have_no_solution() {
+        let mut board = [[0; 9]; 9];
+        board[4][4] = 10;
+        let mut sudoku = Sudoku::new(board);
+        assert_eq!(sudoku.count_solutions(2), 0);
+        assert!(!sudoku.solve_exact_cover());
+        assert_eq!(sudoku.board(), board);
+    }
+
+    #[test]
+    fn hard_puzzle() {
+        // designed by Arto Inkala to defeat human solvers, and hard for plain backtracking
+        let puzzle =
+            "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..";
+        let now = std::time::Instant::now();
+        let mut sudoku = Sudoku::new(parse(puzzle));
+        assert!(sudoku.solve_exact_cover());
+        assert!(now.elapsed() < std::time::Duration::from_secs(1));
+        assert!(is_complete(&sudoku.board()));
+        assert_eq!(sudoku.board()[0], [8, 1, 2, 7, 5, 3, 6, 4, 9]);
+        assert_eq!(Sudoku::new(parse(puzzle)).count_solutions(2), 1);
+    }
+
     #[test]
     fn test_sudoku_incorrect() {
         let board: [[u8; 9]; 9] = [
@@ -0,0 +1,188 @@
+// Karatsuba multiplication of big integers
+// https://en.wikipedia.org/wiki/Karatsuba_algorithm
+
+use num_bigint::{BigInt, BigUint};
+
+/// Below this many 32-bit digits in the shorter factor, schoolbook multiplication is faster
+const THRESHOLD: usize = 64;
+
+/// Multiplies two big integers with Karatsuba's algorithm, in O(n^1.585) for n digits.
+///
+/// Both factors are split at the middle, `a = a1 B^m + a0` and `b = b1 B^m + b0`, and the
+/// product `a1 b1 B^2m + (a1 b0 + a0 b1) B^m + a0 b0` only takes three recursive products,
+/// since `a1 b0 + a0 b1 = (a0 + a1)(b0 + b1) - a0 b0 - a1 b1`.
+pub fn karatsuba_multiply(a: &BigInt, b: &BigInt) -> BigInt {
+    let magnitude = karatsuba(
+        &a.magnitude().to_u32_digits(),
+        &b.magnitude().to_u32_digits(),
+    );
+    BigInt::from_biguint(a.sign() * b.sign(), BigUint::new(magnitude))
+}
+
+/// Multiplies two big integers digit by digit, in O(n^2) for n digits.
+pub fn schoolbook_multiply(a: &BigInt, b: &BigInt) -> BigInt {
+    let magnitude = schoolbook(
+        &a.magnitude().to_u32_digits(),
+        &b.magnitude().to_u32_digits(),
+    );
+    BigInt::from_biguint(a.sign() * b.sign(), BigUint::new(magnitude))
+}
+
+/// Multiplies two numbers given as their digits in base 2^32, from the least significant.
+fn karatsuba(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.len().min(b.len()) < THRESHOLD {
+        return schoolbook(a, b);
+    }
+    let m = a.len().max(b.len()) / 2;
+    let mut product = vec![0; a.len() + b.len()];
+    let (a0, a1) = a.split_at(m.min(a.len()));
+    let (b0, b1) = b.split_at(m.min(b.len()));
+    if a1.is_empty() || b1.is_empty() {
+        // one factor is at most half as long as the other, the only one split
+        let (short, (low, high)) = if a1.is_empty() {
+            (a, (b0, b1))
+        } else {
+            (b, (a0, a1))
+        };
+        add_shifted(&mut product, &karatsuba(short, low), 0);
+        add_shifted(&mut product, &karatsuba(short, high), m);
+        return product;
+    }
+    let low = karatsuba(a0, b0);
+    let high = karatsuba(a1, b1);
+    let mut middle = karatsuba(&add(a0, a1), &add(b0, b1));
+    subtract(&mut middle, &low);
+    subtract(&mut middle, &high);
+    add_shifted(&mut product, &low, 0);
+    add_shifted(&mut product, &middle, m);
+    add_shifted(&mut product, &high, 2 * m);
+    product
+}
+
+fn schoolbook(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut product = vec![0; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0;
+        for (j, &y) in b.iter().enumerate() {
+            let t = product[i + j] as u64 + x as u64 * y as u64 + carry;
+            product[i + j] = t as u32;
+            carry = t >> 32;
+        }
+        product[i + b.len()] = carry as u32;
+    }
+    product
+}
+
+fn add(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut sum = vec![0; a.len().max(b.len()) + 1];
+    add_shifted(&mut sum, a, 0);
+    add_shifted(&mut sum, b, 0);
+    sum
+}
+
+/// Adds `b * B^shift` to `a`, which must be long enough to hold the sum, but not necessarily
+/// the leading zeros of `b`.
+fn add_shifted(a: &mut [u32], b: &[u32], shift: usize) {
+    let significant = b.iter().rposition(|&digit| digit != 0).map_or(0, |i| i + 1);
+    let mut carry = 0;
+    let mut i = shift;
+    for &digit in &b[..significant] {
+        let t = a[i] as u64 + digit as u64 + carry;
+        a[i] = t as u32;
+        carry = t >> 32;
+        i += 1;
+    }
+    while carry != 0 {
+        let t = a[i] as u64 + carry;
+        a[i] = t as u32;
+        carry = t >> 32;
+        i += 1;
+    }
+}
+
+/// Subtracts `b` from `a`, which must not be smaller.
+fn subtract(a: &mut [u32], b: &[u32]) {
+    let mut borrow = 0;
+    for (i, digit) in a.iter_mut().enumerate() {
+        if i >= b.len() && borrow == 0 {
+            break;
+        }
+        let t = *digit as i64 - b.get(i).copied().unwrap_or(0) as i64 - borrow;
+        *digit = t.rem_euclid(1 << 32) as u32;
+        borrow = (t < 0) as i64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::Sign;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use std::time::Instant;
+
+    /// Returns a random number of about `digits` decimal digits, of either sign
+    fn random(rng: &mut ChaCha8Rng, digits: usize) -> BigInt {
+        let magnitude = (0..digits * 3322 / 32_000 + 1).map(|_| rng.gen()).collect();
+        let sign = if rng.gen() { Sign::Plus } else { Sign::Minus };
+        BigInt::from_biguint(sign, BigUint::new(magnitude))
+    }
+
+    #[test]
+    fn matches_the_builtin_product() {
+        let mut rng = ChaCha8Rng::seed_from_u64(116);
+        for (a_digits, b_digits) in [(1000, 1000), (1000, 200), (50, 3000), (5000, 4000)] {
+            for _ in 0..5 {
+                let a = random(&mut rng, a_digits);
+                let b = random(&mut rng, b_digits);
+                assert_eq!(karatsuba_multiply(&a, &b), &a * &b);
+                assert_eq!(schoolbook_multiply(&a, &b), &a * &b);
+            }
+        }
+    }
+
+    #[test]
+    fn signs_and_small_values() {
+        let values = [0i64, 1, -1, 7, -12, i64::MAX, i64::MIN];
+        for &a in &values {
+            for &b in &values {
+                let (a, b) = (BigInt::from(a), BigInt::from(b));
+                assert_eq!(karatsuba_multiply(&a, &b), &a * &b);
+            }
+        }
+    }
+
+    #[test]
+    fn carries_through_all_digits() {
+        // (B^n - 1)^2, whose partial sums carry along the whole number
+        let n = 300;
+        let all_ones = BigInt::from_biguint(Sign::Plus, BigUint::new(vec![u32::MAX; n]));
+        assert_eq!(
+            karatsuba_multiply(&all_ones, &all_ones),
+            &all_ones * &all_ones
+        );
+    }
+
+    #[test]
+    #[ignore = "slow test for measuring time"]
+    fn karatsuba_perf() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1160);
+        for digits in [100, 1_000, 10_000, 100_000] {
+            let a = random(&mut rng, digits);
+            let b = random(&mut rng, digits);
+            let now = Instant::now();
+            let fast = karatsuba_multiply(&a, &b);
+            let karatsuba_time = now.elapsed();
+            let now = Instant::now();
+            let slow = schoolbook_multiply(&a, &b);
+            let schoolbook_time = now.elapsed();
+            assert_eq!(fast, slow);
+            println!(
+                "{digits} digits: karatsuba {karatsuba_time:?}, schoolbook {schoolbook_time:?}"
+            );
+            if digits >= 10_000 {
+                assert!(karatsuba_time < schoolbook_time);
+            }
+        }
+    }
+}
@@ -1,7 +1,9 @@
 #![cfg(feature = "big-math")]
 
 mod fast_factorial;
+mod karatsuba;
 mod poly1305;
 
 pub use self::fast_factorial::fast_factorial;
+pub use self::karatsuba::{karatsuba_multiply, schoolbook_multiply};
 pub use self::poly1305::Poly1305;
@@ -0,0 +1,276 @@
+use std::ops::{BitAnd, BitOr, BitXor, Sub};
+
+const WORD_BITS: usize = 64;
+
+/// A growable set of small non-negative integers, stored as one bit per integer in `u64` words.
+///
+/// Membership tests and updates are O(1), and the set operations work a whole word (64 elements)
+/// at a time. The set grows as needed when bits are set; two sets holding the same elements are
+/// equal whatever their capacity.
+#[derive(Clone, Debug, Default)]
+pub struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    pub fn new() -> Self {
+        BitSet { words: vec![] }
+    }
+
+    /// Creates an empty set able to hold the integers below `bits` without reallocating.
+    pub fn with_capacity(bits: usize) -> Self {
+        BitSet {
+            words: vec![0; bits.div_ceil(WORD_BITS)],
+        }
+    }
+
+    /// Adds `index` to the set.
+    pub fn set(&mut self, index: usize) {
+        let word = index / WORD_BITS;
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        self.words[word] |= 1 << (index % WORD_BITS);
+    }
+
+    /// Removes `index` from the set.
+    pub fn clear(&mut self, index: usize) {
+        if let Some(word) = self.words.get_mut(index / WORD_BITS) {
+            *word &= !(1 << (index % WORD_BITS));
+        }
+    }
+
+    /// Returns `true` if `index` is in the set.
+    pub fn test(&self, index: usize) -> bool {
+        self.words
+            .get(index / WORD_BITS)
+            .is_some_and(|word| word >> (index % WORD_BITS) & 1 == 1)
+    }
+
+    /// Returns the number of elements in the set.
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    /// Iterates over the elements of the set in increasing order.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            words: &self.words,
+            word_index: 0,
+            current: self.words.first().copied().unwrap_or(0),
+        }
+    }
+
+    /// Combines the words of both sets, treating the missing words of the shorter one as zeros.
+    fn combine(&self, other: &BitSet, op: impl Fn(u64, u64) -> u64) -> BitSet {
+        let len = self.words.len().max(other.words.len());
+        let words = (0..len)
+            .map(|i| {
+                let a = self.words.get(i).copied().unwrap_or(0);
+                let b = other.words.get(i).copied().unwrap_or(0);
+                op(a, b)
+            })
+            .collect();
+        BitSet { words }
+    }
+
+    pub fn union(&self, other: &BitSet) -> BitSet {
+        self.combine(other, |a, b| a | b)
+    }
+
+    pub fn intersection(&self, other: &BitSet) -> BitSet {
+        self.combine(other, |a, b| a & b)
+    }
+
+    pub fn difference(&self, other: &BitSet) -> BitSet {
+        self.combine(other, |a, b| a & !b)
+    }
+
+    pub fn symmetric_difference(&self, other: &BitSet) -> BitSet {
+        self.combine(other, |a, b| a ^ b)
+    }
+}
+
+impl PartialEq for BitSet {
+    fn eq(&self, other: &Self) -> bool {
+        let (short, long) = if self.words.len() <= other.words.len() {
+            (&self.words, &other.words)
+        } else {
+            (&other.words, &self.words)
+        };
+        short[..] == long[..short.len()] && long[short.len()..].iter().all(|&word| word == 0)
+    }
+}
+
+impl Eq for BitSet {}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = BitSet::new();
+        for index in iter {
+            set.set(index);
+        }
+        set
+    }
+}
+
+impl BitOr for &BitSet {
+    type Output = BitSet;
+
+    fn bitor(self, other: &BitSet) -> BitSet {
+        self.union(other)
+    }
+}
+
+impl BitAnd for &BitSet {
+    type Output = BitSet;
+
+    fn bitand(self, other: &BitSet) -> BitSet {
+        self.intersection(other)
+    }
+}
+
+impl BitXor for &BitSet {
+    type Output = BitSet;
+
+    fn bitxor(self, other: &BitSet) -> BitSet {
+        self.symmetric_difference(other)
+    }
+}
+
+impl Sub for &BitSet {
+    type Output = BitSet;
+
+    fn sub(self, other: &BitSet) -> BitSet {
+        self.difference(other)
+    }
+}
+
+/// Iterator over the elements of a `BitSet`, in increasing order.
+pub struct Iter<'a> {
+    words: &'a [u64],
+    word_index: usize,
+    // bits of the current word that have not been yielded yet
+    current: u64,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.current == 0 {
+            self.word_index += 1;
+            self.current = *self.words.get(self.word_index)?;
+        }
+        let bit = self.current.trailing_zeros() as usize;
+        // clear the lowest set bit
+        self.current &= self.current - 1;
+        Some(self.word_index * WORD_BITS + bit)
+    }
+}
+
+impl<'a> IntoIterator for &'a BitSet {
+    type Item = usize;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use std::collections::HashSet;
+
+    fn sorted(set: &HashSet<usize>) -> Vec<usize> {
+        let mut elements: Vec<usize> = set.iter().copied().collect();
+        elements.sort_unstable();
+        elements
+    }
+
+    #[test]
+    fn set_clear_test() {
+        let mut set = BitSet::new();
+        assert!(set.is_empty());
+        set.set(3);
+        set.set(64);
+        set.set(200);
+        assert!(set.test(3) && set.test(64) && set.test(200));
+        assert!(!set.test(4) && !set.test(63) && !set.test(10_000));
+        assert_eq!(set.count_ones(), 3);
+        set.clear(64);
+        set.clear(10_000);
+        assert!(!set.test(64));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![3, 200]);
+    }
+
+    #[test]
+    fn equality_ignores_capacity() {
+        let mut small = BitSet::new();
+        let mut big = BitSet::with_capacity(1000);
+        assert_eq!(small, big);
+        small.set(5);
+        big.set(5);
+        assert_eq!(small, big);
+        big.set(900);
+        assert_ne!(small, big);
+        big.clear(900);
+        assert_eq!(big, small);
+    }
+
+    #[test]
+    fn word_boundaries() {
+        let elements = [0, 63, 64, 127, 128];
+        let set: BitSet = elements.iter().copied().collect();
+        assert_eq!(set.iter().collect::<Vec<_>>(), elements);
+        assert_eq!(BitSet::new().iter().next(), None);
+        assert_eq!(BitSet::with_capacity(500).iter().next(), None);
+    }
+
+    #[test]
+    fn random_operations_match_hash_set() {
+        let mut rng = ChaCha8Rng::seed_from_u64(105);
+        for _ in 0..50 {
+            let a_len = rng.gen_range(0..100);
+            let a_elements: Vec<usize> = (0..a_len).map(|_| rng.gen_range(0..300)).collect();
+            let b_len = rng.gen_range(0..100);
+            let b_elements: Vec<usize> = (0..b_len).map(|_| rng.gen_range(0..500)).collect();
+            let a: BitSet = a_elements.iter().copied().collect();
+            let b: BitSet = b_elements.iter().copied().collect();
+            let ha: HashSet<usize> = a_elements.into_iter().collect();
+            let hb: HashSet<usize> = b_elements.into_iter().collect();
+
+            assert_eq!(a.count_ones(), ha.len());
+            assert_eq!(a.iter().collect::<Vec<_>>(), sorted(&ha));
+            assert_eq!(
+                (&a | &b).iter().collect::<Vec<_>>(),
+                sorted(&ha.union(&hb).copied().collect())
+            );
+            assert_eq!(
+                (&a & &b).iter().collect::<Vec<_>>(),
+                sorted(&ha.intersection(&hb).copied().collect())
+            );
+            assert_eq!(
+                (&a - &b).iter().collect::<Vec<_>>(),
+                sorted(&ha.difference(&hb).copied().collect())
+            );
+            assert_eq!(
+                (&a ^ &b).iter().collect::<Vec<_>>(),
+                sorted(&ha.symmetric_difference(&hb).copied().collect())
+            );
+            for x in 0..600 {
+                assert_eq!(a.test(x), ha.contains(&x));
+            }
+        }
+    }
+}
@@ -3,9 +3,13 @@ The counting bits algorithm, also known as the "population count" or "Hamming we
 calculates the number of set bits (1s) in the binary representation of an unsigned integer.
 It uses a technique known as Brian Kernighan's algorithm, which efficiently clears the least
 significant set bit in each iteration.
+
+The SWAR ("SIMD within a register") variant counts the bits of all the 2-bit, then 4-bit,
+then 8-bit fields of the integer in parallel, in a constant number of steps and without
+any popcount instruction.
 */
 
-pub fn count_set_bits(mut n: u32) -> u32 {
+pub fn count_set_bits(mut n: u64) -> u32 {
     // Initialize a variable to keep track of the count of set bits
     let mut count = 0;
     while n > 0 {
@@ -20,6 +24,17 @@ pub fn count_set_bits(mut n: u32) -> u32 {
     count
 }
 
+pub fn count_set_bits_swar(mut n: u64) -> u32 {
+    // Every 2-bit field now holds the number of bits it had set
+    n -= (n >> 1) & 0x5555_5555_5555_5555;
+    // Every 4-bit field holds the sum of its two 2-bit fields
+    n = (n & 0x3333_3333_3333_3333) + ((n >> 2) & 0x3333_3333_3333_3333);
+    // Every byte holds the sum of its two 4-bit fields
+    n = (n + (n >> 4)) & 0x0f0f_0f0f_0f0f_0f0f;
+    // The multiplication adds up all the bytes into the top one
+    (n.wrapping_mul(0x0101_0101_0101_0101) >> 56) as u32
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -27,20 +42,36 @@ mod tests {
     #[test]
     fn test_count_set_bits_zero() {
         assert_eq!(count_set_bits(0), 0);
+        assert_eq!(count_set_bits_swar(0), 0);
     }
 
     #[test]
     fn test_count_set_bits_one() {
         assert_eq!(count_set_bits(1), 1);
+        assert_eq!(count_set_bits_swar(1), 1);
     }
 
     #[test]
     fn test_count_set_bits_power_of_two() {
         assert_eq!(count_set_bits(16), 1); // 16 is 2^4, only one set bit
+        assert_eq!(count_set_bits_swar(1 << 63), 1);
     }
 
     #[test]
     fn test_count_set_bits_all_set_bits() {
-        assert_eq!(count_set_bits(u32::MAX), 32); // Maximum value for u32, all set bits
+        assert_eq!(count_set_bits(u32::MAX as u64), 32); // Maximum value for u32, all set bits
+        assert_eq!(count_set_bits(u64::MAX), 64);
+        assert_eq!(count_set_bits_swar(u64::MAX), 64);
+    }
+
+    #[test]
+    fn test_count_set_bits_all_16_bit_values() {
+        for n in 0..=u16::MAX as u64 {
+            let naive = (0..16).filter(|i| n >> i & 1 == 1).count() as u32;
+            assert_eq!(count_set_bits(n), naive);
+            assert_eq!(count_set_bits_swar(n), naive);
+            // move the pattern to the high bits as well
+            assert_eq!(count_set_bits_swar(n << 48 | n), 2 * naive);
+        }
     }
 }
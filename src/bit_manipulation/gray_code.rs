@@ -0,0 +1,50 @@
+// The reflected binary Gray code orders the integers so that consecutive values differ by a
+// single bit. The n-th code word is n ^ (n >> 1), and decoding xors together all the
+// right shifts of the code word.
+
+pub fn gray_code(n: u64) -> u64 {
+    n ^ (n >> 1)
+}
+
+pub fn inverse_gray_code(mut g: u64) -> u64 {
+    // Xor with g >> 1, g >> 2, g >> 3, ... in log2(64) steps
+    let mut shift = 1;
+    while shift < 64 {
+        g ^= g >> shift;
+        shift <<= 1;
+    }
+    g
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gray_code_first_values() {
+        let codes: Vec<u64> = (0..8).map(gray_code).collect();
+        assert_eq!(
+            codes,
+            vec![0b000, 0b001, 0b011, 0b010, 0b110, 0b111, 0b101, 0b100]
+        );
+    }
+
+    #[test]
+    fn test_gray_code_all_16_bit_values() {
+        for n in 0..u16::MAX as u64 {
+            // consecutive code words differ by exactly one bit
+            assert_eq!((gray_code(n) ^ gray_code(n + 1)).count_ones(), 1);
+            assert_eq!(inverse_gray_code(gray_code(n)), n);
+        }
+    }
+
+    #[test]
+    fn test_gray_code_extremes() {
+        assert_eq!(gray_code(u64::MAX), 1 << 63);
+        assert_eq!(inverse_gray_code(1 << 63), u64::MAX);
+        assert_eq!(
+            inverse_gray_code(gray_code(u64::MAX - 12345)),
+            u64::MAX - 12345
+        );
+    }
+}
@@ -23,6 +23,24 @@ pub fn find_highest_set_bit(num: i32) -> Option<i32> {
     Some(position - 1)
 }
 
+// Unsigned version returning the index of the most significant set bit in O(log 64) steps,
+// by binary searching for the highest non-zero half.
+pub fn highest_set_bit(mut x: u64) -> Option<u32> {
+    if x == 0 {
+        return None;
+    }
+    let mut position = 0;
+    let mut shift = 32;
+    while shift > 0 {
+        if x >> shift != 0 {
+            x >>= shift;
+            position += shift;
+        }
+        shift >>= 1;
+    }
+    Some(position)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -45,4 +63,21 @@ mod tests {
         let num = -12;
         find_highest_set_bit(num);
     }
+
+    #[test]
+    fn test_highest_set_bit_edge_cases() {
+        assert_eq!(highest_set_bit(0), None);
+        assert_eq!(highest_set_bit(1), Some(0));
+        assert_eq!(highest_set_bit(u64::MAX), Some(63));
+        assert_eq!(highest_set_bit(1 << 40 | 1), Some(40));
+    }
+
+    #[test]
+    fn test_highest_set_bit_all_16_bit_values() {
+        for x in 1..=u16::MAX as u64 {
+            let naive = (0..16).rev().find(|i| x >> i & 1 == 1);
+            assert_eq!(highest_set_bit(x), naive);
+            assert_eq!(find_highest_set_bit(x as i32).map(|p| p as u32), naive);
+        }
+    }
 }
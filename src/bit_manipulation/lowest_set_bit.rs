@@ -0,0 +1,33 @@
+// Find the index of the least significant bit set to 1.
+// x & x.wrapping_neg() isolates that bit, since negating in two's complement flips every bit
+// above it.
+
+pub fn lowest_set_bit(x: u64) -> Option<u32> {
+    if x == 0 {
+        return None;
+    }
+    let isolated = x & x.wrapping_neg();
+    // The index is the number of ones in isolated - 1
+    Some((isolated - 1).count_ones())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lowest_set_bit_edge_cases() {
+        assert_eq!(lowest_set_bit(0), None);
+        assert_eq!(lowest_set_bit(1), Some(0));
+        assert_eq!(lowest_set_bit(u64::MAX), Some(0));
+        assert_eq!(lowest_set_bit(1 << 63), Some(63));
+    }
+
+    #[test]
+    fn test_lowest_set_bit_all_16_bit_values() {
+        for x in 1..=u16::MAX as u64 {
+            let naive = (0..16).find(|i| x >> i & 1 == 1);
+            assert_eq!(lowest_set_bit(x), naive);
+        }
+    }
+}
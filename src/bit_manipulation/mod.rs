@@ -1,7 +1,19 @@
+mod bit_set;
 mod counting_bits;
+mod gray_code;
 mod highest_set_bit;
+mod lowest_set_bit;
+mod power_of_two;
+mod reverse_bits;
+mod submasks;
 mod sum_of_two_integers;
 
-pub use counting_bits::count_set_bits;
-pub use highest_set_bit::find_highest_set_bit;
+pub use bit_set::BitSet;
+pub use counting_bits::{count_set_bits, count_set_bits_swar};
+pub use gray_code::{gray_code, inverse_gray_code};
+pub use highest_set_bit::{find_highest_set_bit, highest_set_bit};
+pub use lowest_set_bit::lowest_set_bit;
+pub use power_of_two::{is_power_of_two, next_power_of_two};
+pub use reverse_bits::reverse_bits;
+pub use submasks::{iterate_subsets, Submasks};
 pub use sum_of_two_integers::add_two_integers;
@@ -0,0 +1,56 @@
+// A power of two has exactly one set bit, so clearing its lowest set bit with n & (n - 1)
+// leaves zero.
+
+pub fn is_power_of_two(n: u64) -> bool {
+    n != 0 && n & (n - 1) == 0
+}
+
+// Returns the smallest power of two greater than or equal to n,
+// or None if it does not fit in a u64 (n > 2^63).
+pub fn next_power_of_two(n: u64) -> Option<u64> {
+    if n <= 1 {
+        return Some(1);
+    }
+    // Smear the highest set bit of n - 1 into all the lower bits, then add one
+    let mut x = n - 1;
+    let mut shift = 1;
+    while shift < 64 {
+        x |= x >> shift;
+        shift <<= 1;
+    }
+    x.checked_add(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_power_of_two() {
+        assert!(!is_power_of_two(0));
+        assert!(is_power_of_two(1));
+        assert!(is_power_of_two(1 << 63));
+        assert!(!is_power_of_two(u64::MAX));
+        for n in 0..=u16::MAX as u64 {
+            let naive = (0..16).any(|i| n == 1 << i);
+            assert_eq!(is_power_of_two(n), naive);
+        }
+    }
+
+    #[test]
+    fn test_next_power_of_two_edge_cases() {
+        assert_eq!(next_power_of_two(0), Some(1));
+        assert_eq!(next_power_of_two(1), Some(1));
+        assert_eq!(next_power_of_two(1 << 63), Some(1 << 63));
+        assert_eq!(next_power_of_two((1 << 63) + 1), None);
+        assert_eq!(next_power_of_two(u64::MAX), None);
+    }
+
+    #[test]
+    fn test_next_power_of_two_all_16_bit_values() {
+        for n in 1..=u16::MAX as u64 {
+            let naive = (0..).map(|i| 1u64 << i).find(|&p| p >= n).unwrap();
+            assert_eq!(next_power_of_two(n), Some(naive));
+        }
+    }
+}
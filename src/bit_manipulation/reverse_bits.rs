@@ -0,0 +1,52 @@
+// Reverses the order of the lowest `width` bits of x: bit i goes to position width - 1 - i.
+// Bits of x above `width` are ignored.
+
+pub fn reverse_bits(x: u64, width: u32) -> u64 {
+    assert!(width <= 64, "width must be at most 64");
+    if width == 0 {
+        return 0;
+    }
+    // Swap adjacent bits, then pairs, nibbles, bytes, 16-bit and 32-bit halves
+    let mut r = x;
+    r = (r >> 1) & 0x5555_5555_5555_5555 | (r & 0x5555_5555_5555_5555) << 1;
+    r = (r >> 2) & 0x3333_3333_3333_3333 | (r & 0x3333_3333_3333_3333) << 2;
+    r = (r >> 4) & 0x0f0f_0f0f_0f0f_0f0f | (r & 0x0f0f_0f0f_0f0f_0f0f) << 4;
+    r = (r >> 8) & 0x00ff_00ff_00ff_00ff | (r & 0x00ff_00ff_00ff_00ff) << 8;
+    r = (r >> 16) & 0x0000_ffff_0000_ffff | (r & 0x0000_ffff_0000_ffff) << 16;
+    r = r.rotate_left(32);
+    // The lowest `width` bits of x are now the highest `width` bits of r
+    r >> (64 - width)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naive_reverse(x: u64, width: u32) -> u64 {
+        (0..width).fold(0, |acc, i| acc | ((x >> i) & 1) << (width - 1 - i))
+    }
+
+    #[test]
+    fn test_reverse_bits_small() {
+        assert_eq!(reverse_bits(0b0001, 4), 0b1000);
+        assert_eq!(reverse_bits(0b1101, 4), 0b1011);
+        assert_eq!(reverse_bits(0b110, 3), 0b011);
+        assert_eq!(reverse_bits(1, 64), 1 << 63);
+        assert_eq!(reverse_bits(u64::MAX, 64), u64::MAX);
+        assert_eq!(reverse_bits(u64::MAX, 0), 0);
+    }
+
+    #[test]
+    fn test_reverse_bits_ignores_high_bits() {
+        assert_eq!(reverse_bits(0b1111_0001, 4), 0b1000);
+    }
+
+    #[test]
+    fn test_reverse_bits_all_16_bit_values() {
+        for x in 0..=u16::MAX as u64 {
+            assert_eq!(reverse_bits(x, 16), naive_reverse(x, 16));
+            assert_eq!(reverse_bits(x, 11), naive_reverse(x, 11));
+            assert_eq!(reverse_bits(reverse_bits(x, 16), 16), x);
+        }
+    }
+}
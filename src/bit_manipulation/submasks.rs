@@ -0,0 +1,76 @@
+// Iterate over all the submasks of a bitmask, that is all the masks whose set bits are a subset
+// of the set bits of the mask, in decreasing order. Going from a submask s to (s - 1) & mask
+// clears the lowest set bit of s and sets all the bits of the mask below it, which gives the
+// next smaller submask. A mask with k set bits has 2^k submasks, including itself and 0.
+// Looping over the submasks of every mask of n bits takes O(3^n) in total, which is the usual
+// cost of subset dynamic programming.
+
+pub struct Submasks {
+    mask: u64,
+    next: Option<u64>,
+}
+
+impl Iterator for Submasks {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let current = self.next?;
+        self.next = if current == 0 {
+            None
+        } else {
+            Some((current - 1) & self.mask)
+        };
+        Some(current)
+    }
+}
+
+pub fn iterate_subsets(mask: u64) -> Submasks {
+    Submasks {
+        mask,
+        next: Some(mask),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iterate_subsets_small() {
+        let submasks: Vec<u64> = iterate_subsets(0b1010).collect();
+        assert_eq!(submasks, vec![0b1010, 0b1000, 0b0010, 0b0000]);
+        assert_eq!(iterate_subsets(0).collect::<Vec<u64>>(), vec![0]);
+    }
+
+    #[test]
+    fn test_iterate_subsets_counts() {
+        for mask in 0..1u64 << 12 {
+            let submasks: Vec<u64> = iterate_subsets(mask).collect();
+            assert_eq!(submasks.len(), 1 << mask.count_ones());
+            assert!(submasks.iter().all(|&s| s & !mask == 0));
+            // strictly decreasing, hence distinct
+            assert!(submasks.windows(2).all(|w| w[0] > w[1]));
+        }
+    }
+
+    #[test]
+    fn test_iterate_subsets_high_bits() {
+        let mask = 1 << 63 | 1 << 40 | 1;
+        assert_eq!(iterate_subsets(mask).count(), 8);
+        assert_eq!(iterate_subsets(mask).next(), Some(mask));
+    }
+
+    #[test]
+    fn test_subset_dp_sum() {
+        // sum over subsets: f[mask] = sum of a[s] for all submasks s of mask
+        let a: Vec<u64> = (0..16).map(|i| i * i + 1).collect();
+        for mask in 0..16u64 {
+            let by_submasks: u64 = iterate_subsets(mask).map(|s| a[s as usize]).sum();
+            let naive: u64 = (0..16u64)
+                .filter(|s| s & !mask == 0)
+                .map(|s| a[s as usize])
+                .sum();
+            assert_eq!(by_submasks, naive);
+        }
+    }
+}
@@ -0,0 +1,126 @@
+/*
+    A Rust implementation of a base58 encoder and decoder, with the alphabet of Bitcoin.
+    Written from scratch.
+
+    The bytes are read as a big-endian number, written with the 58 digits of the alphabet,
+    which leaves out 0, O, I and l, easy to mistake for each other. Leading zero bytes would
+    vanish from the number, so that each of them is written as the digit of zero, '1'.
+*/
+
+use crate::conversions::{digit_value, ParseError};
+
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/*
+    Converts the digits of a number from one base to another, in O(n^2),
+    by dividing it digit by digit by the new base, as done by hand.
+    The digits are big-endian, and the result has no leading zeros.
+*/
+fn convert(mut digits: Vec<u32>, from: u32, to: u32) -> Vec<u32> {
+    let mut converted = vec![];
+    let mut start = 0;
+    while start < digits.len() {
+        let mut remainder = 0;
+        for digit in &mut digits[start..] {
+            let value = remainder * from + *digit;
+            *digit = value / to;
+            remainder = value % to;
+        }
+        converted.push(remainder);
+        while start < digits.len() && digits[start] == 0 {
+            start += 1;
+        }
+    }
+    converted.reverse();
+    converted
+}
+
+pub fn base58_encode(data: &[u8]) -> String {
+    let zeros = data.iter().take_while(|&&byte| byte == 0).count();
+    let digits = convert(
+        data[zeros..].iter().map(|&byte| byte as u32).collect(),
+        256,
+        58,
+    );
+    let mut encoded = "1".repeat(zeros);
+    encoded.extend(
+        digits
+            .into_iter()
+            .map(|digit| ALPHABET[digit as usize] as char),
+    );
+    encoded
+}
+
+pub fn base58_decode(data: &str) -> Result<Vec<u8>, ParseError> {
+    let digits = data
+        .char_indices()
+        .map(|(position, character)| digit_value(ALPHABET, position, character))
+        .collect::<Result<Vec<u32>, ParseError>>()?;
+    let zeros = digits.iter().take_while(|&&digit| digit == 0).count();
+    let mut decoded = vec![0; zeros];
+    decoded.extend(
+        convert(digits[zeros..].to_vec(), 58, 256)
+            .into_iter()
+            .map(|byte| byte as u8),
+    );
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn vectors() {
+        let vectors: [(&[u8], &str); 7] = [
+            (b"", ""),
+            (b"\0", "1"),
+            (b"\0\0\0\x01", "1112"),
+            (b"\0\0\x28\x7f\xb4\xcd", "11233QC4"),
+            (b"\xff\xff\xff\xff", "7YXq9G"),
+            (b"Hello World!", "2NEpo7TZRRrLZSi2U"),
+            (
+                b"The quick brown fox jumps over the lazy dog",
+                "7DdiPPYtxLjCD3wA1po2rvZHTDYjkZYiEtazrfiwJcwnKCizhGFhBGHeRdx",
+            ),
+        ];
+        for (data, encoded) in vectors {
+            assert_eq!(base58_encode(data), encoded);
+            assert_eq!(base58_decode(encoded).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn invalid_characters() {
+        for (data, position, character) in [
+            ("3O", 1, 'O'),
+            ("0", 0, '0'),
+            ("abIl", 2, 'I'),
+            ("2+", 1, '+'),
+        ] {
+            assert_eq!(
+                base58_decode(data),
+                Err(ParseError::InvalidDigit {
+                    position,
+                    character
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn random_round_trips() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1244);
+        for _ in 0..500 {
+            let zeros = rng.gen_range(0..4);
+            let mut data = vec![0; zeros];
+            data.extend((0..rng.gen_range(0..60)).map(|_| rng.gen::<u8>()));
+            let encoded = base58_encode(&data);
+            assert_eq!(base58_decode(&encoded).unwrap(), data);
+            let ones = encoded.bytes().take_while(|&c| c == b'1').count();
+            assert_eq!(ones, data.iter().take_while(|&&byte| byte == 0).count());
+        }
+    }
+}
@@ -3,95 +3,95 @@
     Written from scratch.
 */
 
-// The charset and padding used for en- and decoding.
+use crate::conversions::{digit_value, ParseError};
+
+// The charsets and padding used for en- and decoding.
 const CHARSET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL_SAFE_CHARSET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
 const PADDING: char = '=';
 
-/*
-    Combines the two provided bytes into an u16,
-    and collects 6 bits from it using an AND mask:
-
-    Example:
-    Bytes: X and Y
-    (Bits of those bytes will be signified using the names of their byte)
-    Offset: 4
+/// The alphabets of RFC 4648, which differ in their last two characters.
+/// <https://datatracker.ietf.org/doc/html/rfc4648>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Base64Alphabet {
+    /// With `+` and `/`
+    Standard,
+    /// With `-` and `_`, which need no escaping in URLs and file names
+    UrlSafe,
+}
 
-    `combined` = 0bXXXXXXXXYYYYYYYY
-    AND mask:
-    0b1111110000000000 >> offset (4) = 0b0000111111000000
-    `combined` with mask applied:
-    0b0000XXYYYY000000
-    Shift the value right by (16 bit number) - (6 bit mask) - (4 offset) = 6:
-    0b0000000000XXYYYY
-    And then turn it into an u8:
-    0b00XXYYYY (Return value)
-*/
-fn collect_six_bits(from: (u8, u8), offset: u8) -> u8 {
-    let combined: u16 = ((from.0 as u16) << 8) | (from.1 as u16);
-    ((combined & (0b1111110000000000u16 >> offset)) >> (10 - offset)) as u8
+impl Base64Alphabet {
+    fn charset(self) -> &'static [u8; 64] {
+        match self {
+            Base64Alphabet::Standard => CHARSET,
+            Base64Alphabet::UrlSafe => URL_SAFE_CHARSET,
+        }
+    }
 }
 
 pub fn base64_encode(data: &[u8]) -> String {
-    let mut bits_encoded = 0usize;
-    let mut encoded_string = String::new();
-    // Using modulo twice to prevent an underflow, Wolfram|Alpha says this is optimal
-    let padding_needed = ((6 - (data.len() * 8) % 6) / 2) % 3;
-    loop {
-        let lower_byte_index_to_encode = bits_encoded / 8usize; // Integer division
-        if lower_byte_index_to_encode == data.len() {
-            break;
+    base64_encode_with(data, Base64Alphabet::Standard, true)
+}
+
+/*
+    Every group of three bytes, 24 bits, makes four characters of 6 bits each.
+    A last group of one or two bytes is completed with zero bits into two or three characters,
+    followed by as many padding characters as needed to make four, if `padding` is set.
+*/
+pub fn base64_encode_with(data: &[u8], alphabet: Base64Alphabet, padding: bool) -> String {
+    let charset = alphabet.charset();
+    let mut encoded_string = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| {
+            group | (byte as u32) << (16 - 8 * i)
+        });
+        let characters = chunk.len() + 1;
+        for i in 0..characters {
+            encoded_string.push(charset[(group >> (18 - 6 * i) & 0b111111) as usize] as char);
+        }
+        if padding {
+            for _ in characters..4 {
+                encoded_string.push(PADDING);
+            }
         }
-        let lower_byte_to_encode = data[lower_byte_index_to_encode];
-        let upper_byte_to_encode = if (lower_byte_index_to_encode + 1) == data.len() {
-            0u8 // Padding
-        } else {
-            data[lower_byte_index_to_encode + 1]
-        };
-        let bytes_to_encode = (lower_byte_to_encode, upper_byte_to_encode);
-        let offset: u8 = (bits_encoded % 8) as u8;
-        encoded_string.push(CHARSET[collect_six_bits(bytes_to_encode, offset) as usize] as char);
-        bits_encoded += 6;
-    }
-    for _ in 0..padding_needed {
-        encoded_string.push(PADDING);
     }
     encoded_string
 }
 
+pub fn base64_decode(data: &str) -> Result<Vec<u8>, ParseError> {
+    base64_decode_with(data, Base64Alphabet::Standard)
+}
+
 /*
-    Performs the exact inverse of the above description of `base64_encode`
+    Performs the exact inverse of `base64_encode_with`, with or without the padding.
+
+    Anything which the encoder cannot output is rejected: a character out of the alphabet,
+    padding which is not at the end or does not complete the last group, a last group of a
+    single character, and unused bits of the last character which are not zero.
 */
-pub fn base64_decode(data: &str) -> Result<Vec<u8>, (&str, u8)> {
+pub fn base64_decode_with(data: &str, alphabet: Base64Alphabet) -> Result<Vec<u8>, ParseError> {
+    let charset = alphabet.charset();
+    let unpadded = data.trim_end_matches(PADDING);
+    let padding = data.len() - unpadded.len();
+    if padding > 0 && (!data.len().is_multiple_of(4) || padding > 2) {
+        return Err(ParseError::InvalidPadding);
+    }
+
+    let mut outputbytes = Vec::with_capacity(unpadded.len() / 4 * 3 + 2);
+    let mut byte_buffer = 0u32;
     let mut collected_bits = 0;
-    let mut byte_buffer = 0u16;
-    let mut databytes = data.bytes();
-    let mut outputbytes = Vec::<u8>::new();
-    'decodeloop: loop {
-        while collected_bits < 8 {
-            if let Some(nextbyte) = databytes.next() {
-                // Finds the first occurence of the latest byte
-                if let Some(idx) = CHARSET.iter().position(|&x| x == nextbyte) {
-                    byte_buffer |= ((idx & 0b00111111) as u16) << (10 - collected_bits);
-                    collected_bits += 6;
-                } else if nextbyte == (PADDING as u8) {
-                    collected_bits -= 2; // Padding only comes at the end so this works
-                } else {
-                    return Err((
-                        "Failed to decode base64: Expected byte from charset, found invalid byte.",
-                        nextbyte,
-                    ));
-                }
-            } else {
-                break 'decodeloop;
-            }
+    for (position, character) in unpadded.char_indices() {
+        byte_buffer = byte_buffer << 6 | digit_value(charset, position, character)?;
+        collected_bits += 6;
+        if collected_bits >= 8 {
+            collected_bits -= 8;
+            outputbytes.push((byte_buffer >> collected_bits) as u8);
+            byte_buffer &= (1 << collected_bits) - 1;
         }
-        outputbytes.push(((0b1111111100000000 & byte_buffer) >> 8) as u8);
-        byte_buffer &= 0b0000000011111111;
-        byte_buffer <<= 8;
-        collected_bits -= 8;
     }
-    if collected_bits != 0 {
-        return Err(("Failed to decode base64: Invalid padding.", collected_bits));
+    if unpadded.len() % 4 == 1 || byte_buffer != 0 {
+        return Err(ParseError::InvalidPadding);
     }
     Ok(outputbytes)
 }
@@ -269,4 +269,92 @@ mod tests {
         test_d_e!("SGFwcHkgSGFja3RvYmVyZmVzdCE=");
         test_d_e!("PVRoZSBBbGdvcml0aG1zPQ==");
     }
+
+    #[test]
+    fn rfc_4648_vectors() {
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for (text, encoded) in vectors {
+            assert_eq!(base64_encode(text.as_bytes()), encoded);
+            assert_eq!(base64_decode(encoded).unwrap(), text.as_bytes());
+            let unpadded = encoded.trim_end_matches('=');
+            assert_eq!(
+                base64_encode_with(text.as_bytes(), Base64Alphabet::Standard, false),
+                unpadded
+            );
+            assert_eq!(base64_decode(unpadded).unwrap(), text.as_bytes());
+        }
+    }
+
+    #[test]
+    fn url_safe_alphabet() {
+        let data = [0xfb, 0xff, 0xbf, 0xfb, 0xff];
+        assert_eq!(base64_encode(&data), "+/+/+/8=");
+        let url_safe = base64_encode_with(&data, Base64Alphabet::UrlSafe, true);
+        assert_eq!(url_safe, "-_-_-_8=");
+        assert_eq!(
+            base64_decode_with(&url_safe, Base64Alphabet::UrlSafe).unwrap(),
+            data
+        );
+        assert_eq!(
+            base64_decode_with("-_-_-_8", Base64Alphabet::UrlSafe).unwrap(),
+            data
+        );
+        assert!(base64_decode("-_-_-_8=").is_err());
+        assert!(base64_decode_with("+/+/+/8=", Base64Alphabet::UrlSafe).is_err());
+    }
+
+    #[test]
+    fn malformed_input() {
+        assert_eq!(
+            base64_decode("Zm9v!A=="),
+            Err(ParseError::InvalidDigit {
+                position: 4,
+                character: '!'
+            })
+        );
+        assert_eq!(
+            base64_decode("Zm 9v"),
+            Err(ParseError::InvalidDigit {
+                position: 2,
+                character: ' '
+            })
+        );
+        // padding in the middle, too much padding, padding which does not complete a group
+        for malformed in ["Zg=a", "Zg==Zm8=", "Z===", "Zm9v====", "Zg=", "Zm8==", "="] {
+            assert!(base64_decode(malformed).is_err(), "{malformed}");
+        }
+        // a lone character holds only 6 bits
+        assert_eq!(base64_decode("Zm9vY"), Err(ParseError::InvalidPadding));
+        // the unused bits of "Zh" are not zero
+        assert_eq!(base64_decode("Zh=="), Err(ParseError::InvalidPadding));
+    }
+
+    #[test]
+    fn random_round_trips() {
+        use rand::{Rng, SeedableRng};
+        use rand_chacha::ChaCha8Rng;
+
+        let mut rng = ChaCha8Rng::seed_from_u64(1243);
+        for _ in 0..1000 {
+            let data: Vec<u8> = (0..rng.gen_range(0..100)).map(|_| rng.gen()).collect();
+            for alphabet in [Base64Alphabet::Standard, Base64Alphabet::UrlSafe] {
+                for padding in [true, false] {
+                    let encoded = base64_encode_with(&data, alphabet, padding);
+                    assert_eq!(
+                        encoded.len().is_multiple_of(4),
+                        padding || data.len().is_multiple_of(3)
+                    );
+                    assert_eq!(base64_decode_with(&encoded, alphabet).unwrap(), data);
+                }
+            }
+        }
+    }
 }
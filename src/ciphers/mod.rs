@@ -1,6 +1,7 @@
 mod aes;
 mod another_rot13;
 mod baconian_cipher;
+mod base58;
 mod base64;
 mod blake2b;
 mod caesar;
@@ -23,7 +24,10 @@ mod xor;
 pub use self::aes::{aes_decrypt, aes_encrypt, AesKey};
 pub use self::another_rot13::another_rot13;
 pub use self::baconian_cipher::{baconian_decode, baconian_encode};
-pub use self::base64::{base64_decode, base64_encode};
+pub use self::base58::{base58_decode, base58_encode};
+pub use self::base64::{
+    base64_decode, base64_decode_with, base64_encode, base64_encode_with, Base64Alphabet,
+};
 pub use self::blake2b::blake2b;
 pub use self::caesar::caesar;
 pub use self::chacha::chacha20;
@@ -6,6 +6,7 @@ mod hexadecimal_to_binary;
 mod hexadecimal_to_decimal;
 mod octal_to_binary;
 mod octal_to_decimal;
+mod radix;
 pub use self::binary_to_decimal::binary_to_decimal;
 pub use self::binary_to_hexadecimal::binary_to_hexadecimal;
 pub use self::decimal_to_binary::decimal_to_binary;
@@ -14,3 +15,5 @@ pub use self::hexadecimal_to_binary::hexadecimal_to_binary;
 pub use self::hexadecimal_to_decimal::hexadecimal_to_decimal;
 pub use self::octal_to_binary::octal_to_binary;
 pub use self::octal_to_decimal::octal_to_decimal;
+pub(crate) use self::radix::digit_value;
+pub use self::radix::{from_base, to_base, ParseError};
@@ -0,0 +1,186 @@
+use std::fmt;
+
+/// The digits of the bases up to 36, which take lowercase and uppercase letters alike
+const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// An error in a string of digits, or in the characters of a binary-to-text encoding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// There are no digits at all
+    Empty,
+    /// The character at the byte `position` is not a digit of the base or the alphabet
+    InvalidDigit { position: usize, character: char },
+    /// The number is too large for its type
+    Overflow,
+    /// The padding or the number of characters does not match an encoded input
+    InvalidPadding,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "there are no digits"),
+            ParseError::InvalidDigit {
+                position,
+                character,
+            } => write!(f, "invalid digit {character:?} at {position}"),
+            ParseError::Overflow => write!(f, "the number is too large for its type"),
+            ParseError::InvalidPadding => write!(f, "invalid padding or length"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Returns the value of `character` in `alphabet`, which lists the digits in increasing order,
+/// with the byte `position` of the character in the input to report when it is not a digit.
+pub(crate) fn digit_value(
+    alphabet: &[u8],
+    position: usize,
+    character: char,
+) -> Result<u32, ParseError> {
+    u8::try_from(character)
+        .ok()
+        .and_then(|byte| alphabet.iter().position(|&digit| digit == byte))
+        .map(|value| value as u32)
+        .ok_or(ParseError::InvalidDigit {
+            position,
+            character,
+        })
+}
+
+fn check_base(base: u32) {
+    assert!(
+        (2..=36).contains(&base),
+        "the base must be between 2 and 36, got {base}"
+    );
+}
+
+/// Writes `n` in the base `base`, with the digits `0-9` and then the lowercase letters.
+///
+/// # Panics
+///
+/// Panics if the base is not between 2 and 36.
+pub fn to_base(mut n: u128, base: u32) -> String {
+    check_base(base);
+    let mut digits = vec![];
+    loop {
+        digits.push(DIGITS[(n % base as u128) as usize]);
+        n /= base as u128;
+        if n == 0 {
+            break;
+        }
+    }
+    digits.reverse();
+    String::from_utf8(digits).unwrap()
+}
+
+/// Reads a number written in the base `base`, the inverse of `to_base`, with the letters in
+/// either case. A sign, a prefix like `0x` or any other character is an invalid digit.
+///
+/// # Panics
+///
+/// Panics if the base is not between 2 and 36.
+pub fn from_base(s: &str, base: u32) -> Result<u128, ParseError> {
+    check_base(base);
+    if s.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    let alphabet = &DIGITS[..base as usize];
+    s.char_indices()
+        .try_fold(0u128, |n, (position, character)| {
+            let digit = digit_value(alphabet, position, character.to_ascii_lowercase())?;
+            n.checked_mul(base as u128)
+                .and_then(|n| n.checked_add(digit as u128))
+                .ok_or(ParseError::Overflow)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn known_values() {
+        assert_eq!(to_base(0, 2), "0");
+        assert_eq!(to_base(255, 16), "ff");
+        assert_eq!(to_base(35, 36), "z");
+        assert_eq!(to_base(36, 36), "10");
+        assert_eq!(to_base(1_000_000, 10), "1000000");
+        assert_eq!(to_base(u128::MAX, 2), "1".repeat(128));
+        assert_eq!(from_base("FF", 16), Ok(255));
+        assert_eq!(from_base("fF", 16), Ok(255));
+        assert_eq!(from_base("Zz", 36), Ok(35 * 36 + 35));
+        assert_eq!(from_base("0007", 8), Ok(7));
+        assert_eq!(from_base("3w5e11264sgsf", 36), Ok(u64::MAX as u128));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(from_base("", 10), Err(ParseError::Empty));
+        assert_eq!(
+            from_base("102", 2),
+            Err(ParseError::InvalidDigit {
+                position: 2,
+                character: '2'
+            })
+        );
+        assert_eq!(
+            from_base("-1", 10),
+            Err(ParseError::InvalidDigit {
+                position: 0,
+                character: '-'
+            })
+        );
+        assert_eq!(
+            from_base("1é", 16),
+            Err(ParseError::InvalidDigit {
+                position: 1,
+                character: 'é'
+            })
+        );
+        let max = to_base(u128::MAX, 7);
+        assert_eq!(from_base(&max, 7), Ok(u128::MAX));
+        assert_eq!(from_base(&format!("{max}0"), 7), Err(ParseError::Overflow));
+        assert_eq!(
+            from_base("340282366920938463463374607431768211456", 10),
+            Err(ParseError::Overflow)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "the base must be between 2 and 36, got 37")]
+    fn invalid_base() {
+        to_base(5, 37);
+    }
+
+    #[test]
+    fn round_trips() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1241);
+        for _ in 0..2000 {
+            let base = rng.gen_range(2..=36);
+            // values of every magnitude
+            let n = rng.gen::<u128>() >> rng.gen_range(0..128);
+            let written = to_base(n, base);
+            assert_eq!(from_base(&written, base), Ok(n));
+            assert_eq!(from_base(&written.to_uppercase(), base), Ok(n));
+            if base == 10 {
+                assert_eq!(written, n.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn base_36_and_base_2_agree() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1242);
+        for _ in 0..500 {
+            let n: u128 = rng.gen();
+            let binary = to_base(n, 2);
+            let base_36 = to_base(from_base(&binary, 2).unwrap(), 36);
+            assert_eq!(to_base(from_base(&base_36, 36).unwrap(), 2), binary);
+            assert_eq!(binary, format!("{n:b}"));
+        }
+    }
+}
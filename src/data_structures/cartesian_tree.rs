@@ -0,0 +1,115 @@
+/// Builds the Cartesian tree of an array in O(n), and returns the parent of every index, `None`
+/// for the root.
+/// <https://en.wikipedia.org/wiki/Cartesian_tree>
+///
+/// The root is the index of the minimum, and its left and right subtrees are the Cartesian
+/// trees of the subarrays on its sides, so that an in-order traversal gives back the indices in
+/// order. Of equal values, the leftmost one is the ancestor of the others. The minimum of any
+/// range `arr[i..=j]` is then at the lowest common ancestor of `i` and `j`, which reduces range
+/// minimum queries to lowest common ancestors.
+///
+/// The indices are added from left to right, and the stack holds the rightmost path of the
+/// tree built so far, by increasing values. A new index pops the greater values, the last of
+/// them becoming its left child, and becomes the right child of the top of the stack. Every
+/// index is pushed and popped once.
+pub fn build_cartesian_tree(arr: &[i64]) -> Vec<Option<usize>> {
+    let mut parent = vec![None; arr.len()];
+    let mut stack: Vec<usize> = vec![];
+    for i in 0..arr.len() {
+        let mut last = None;
+        while let Some(&top) = stack.last() {
+            if arr[top] <= arr[i] {
+                break;
+            }
+            last = stack.pop();
+        }
+        if let Some(last) = last {
+            parent[last] = Some(i);
+        }
+        if let Some(&top) = stack.last() {
+            parent[i] = Some(top);
+        }
+        stack.push(i);
+    }
+    parent
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    /// The parents of the Cartesian tree of `arr[start..end]` under `root`, by the definition
+    fn naive(
+        arr: &[i64],
+        start: usize,
+        end: usize,
+        root: Option<usize>,
+        parent: &mut [Option<usize>],
+    ) {
+        if start >= end {
+            return;
+        }
+        let minimum = (start..end).min_by_key(|&i| (arr[i], i)).unwrap();
+        parent[minimum] = root;
+        naive(arr, start, minimum, Some(minimum), parent);
+        naive(arr, minimum + 1, end, Some(minimum), parent);
+    }
+
+    fn ancestors(parent: &[Option<usize>], mut i: usize) -> Vec<usize> {
+        let mut path = vec![i];
+        while let Some(p) = parent[i] {
+            path.push(p);
+            i = p;
+        }
+        path
+    }
+
+    #[test]
+    fn small_arrays() {
+        //      1
+        //     / \
+        //    3   2
+        //   / \ /
+        //  9  7 8
+        let arr = [9, 3, 7, 1, 8, 2];
+        let parent = build_cartesian_tree(&arr);
+        assert_eq!(parent, [Some(1), Some(3), Some(1), None, Some(5), Some(3)]);
+        assert!(build_cartesian_tree(&[]).is_empty());
+        assert_eq!(build_cartesian_tree(&[5]), [None]);
+        // sorted arrays are paths, and equal values hang to the right of the leftmost one
+        assert_eq!(build_cartesian_tree(&[1, 2, 3]), [None, Some(0), Some(1)]);
+        assert_eq!(build_cartesian_tree(&[3, 2, 1]), [Some(1), Some(2), None]);
+        assert_eq!(build_cartesian_tree(&[4, 4, 4]), [None, Some(0), Some(1)]);
+    }
+
+    #[test]
+    fn matches_the_definition() {
+        let mut rng = ChaCha8Rng::seed_from_u64(141);
+        for _ in 0..200 {
+            let n = rng.gen_range(0..60);
+            let arr: Vec<i64> = (0..n).map(|_| rng.gen_range(-10..10)).collect();
+            let mut expected = vec![None; n];
+            naive(&arr, 0, n, None, &mut expected);
+            assert_eq!(build_cartesian_tree(&arr), expected);
+        }
+    }
+
+    #[test]
+    fn range_minimum_at_the_lowest_common_ancestor() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1410);
+        let arr: Vec<i64> = (0..300).map(|_| rng.gen_range(-1000..1000)).collect();
+        let parent = build_cartesian_tree(&arr);
+        for _ in 0..1000 {
+            let i = rng.gen_range(0..arr.len());
+            let j = rng.gen_range(i..arr.len());
+            let above_i = ancestors(&parent, i);
+            let lca = ancestors(&parent, j)
+                .into_iter()
+                .find(|a| above_i.contains(a))
+                .unwrap();
+            assert_eq!(arr[lca], *arr[i..=j].iter().min().unwrap());
+        }
+    }
+}
@@ -0,0 +1,178 @@
+/// A level of the cascade: a list merged with every other item of the level below it
+struct Level {
+    keys: Vec<i64>,
+    /// `own[p]` is the number of items of the original list among `keys[..p]`
+    own: Vec<usize>,
+    /// `promoted[p]` is the number of items of the level below among `keys[..p]`
+    promoted: Vec<usize>,
+}
+
+/// Searches the same value in several sorted lists at once with fractional cascading, in
+/// O(log(n) + k) for k lists instead of O(k log(n)) for as many binary searches.
+/// <https://en.wikipedia.org/wiki/Fractional_cascading>
+///
+/// The last list is kept as it is, and every list above it is merged with every other item,
+/// the 2nd, the 4th and so on, of the merged list below it, which at most doubles the total
+/// size. A binary search in the first merged list places the value there, and the counts of
+/// the items coming from either list give both its place in the original list and, up to the
+/// one item skipped between two promoted ones, its place in the merged list below.
+pub struct FractionalCascade {
+    levels: Vec<Level>,
+}
+
+impl FractionalCascade {
+    /// # Panics
+    ///
+    /// Panics if a list is not sorted.
+    pub fn new(lists: Vec<Vec<i64>>) -> Self {
+        let mut levels: Vec<Level> = Vec::with_capacity(lists.len());
+        for (i, list) in lists.into_iter().enumerate().rev() {
+            assert!(
+                list.windows(2).all(|w| w[0] <= w[1]),
+                "the list {i} is not sorted"
+            );
+            let below: Vec<i64> = match levels.last() {
+                Some(level) => level.keys.iter().skip(1).step_by(2).copied().collect(),
+                None => vec![],
+            };
+            let mut level = Level {
+                keys: Vec::with_capacity(list.len() + below.len()),
+                own: vec![0],
+                promoted: vec![0],
+            };
+            let (mut a, mut b) = (0, 0);
+            while a < list.len() || b < below.len() {
+                if b == below.len() || (a < list.len() && list[a] <= below[b]) {
+                    level.keys.push(list[a]);
+                    a += 1;
+                } else {
+                    level.keys.push(below[b]);
+                    b += 1;
+                }
+                level.own.push(a);
+                level.promoted.push(b);
+            }
+            levels.push(level);
+        }
+        levels.reverse();
+        FractionalCascade { levels }
+    }
+
+    /// Returns the number of items at most `x` in every list, that is the index where the
+    /// predecessor of `x` would be plus one, or where `x` would be inserted after its equals.
+    pub fn query(&self, x: i64) -> Vec<usize> {
+        let Some(first) = self.levels.first() else {
+            return vec![];
+        };
+        let mut result = Vec::with_capacity(self.levels.len());
+        // the number of items of the current level at most x
+        let mut position = first.keys.partition_point(|&key| key <= x);
+        for (i, level) in self.levels.iter().enumerate() {
+            result.push(level.own[position]);
+            if let Some(below) = self.levels.get(i + 1) {
+                // the promoted items are the odd indices of the level below
+                let next = 2 * level.promoted[position];
+                position = if below.keys.get(next).is_some_and(|&key| key <= x) {
+                    next + 1
+                } else {
+                    next
+                };
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn random_lists(rng: &mut ChaCha8Rng, k: usize, max_len: usize, range: i64) -> Vec<Vec<i64>> {
+        (0..k)
+            .map(|_| {
+                let len = rng.gen_range(0..=max_len);
+                let mut list: Vec<i64> = (0..len).map(|_| rng.gen_range(-range..range)).collect();
+                list.sort_unstable();
+                list
+            })
+            .collect()
+    }
+
+    fn binary_searches(lists: &[Vec<i64>], x: i64) -> Vec<usize> {
+        lists
+            .iter()
+            .map(|list| list.partition_point(|&item| item <= x))
+            .collect()
+    }
+
+    #[test]
+    fn small_lists() {
+        let lists = vec![vec![1, 3, 5, 7], vec![2, 4, 6], vec![], vec![5, 5, 5, 9]];
+        let cascade = FractionalCascade::new(lists);
+        assert_eq!(cascade.query(0), [0, 0, 0, 0]);
+        assert_eq!(cascade.query(4), [2, 2, 0, 0]);
+        assert_eq!(cascade.query(5), [3, 2, 0, 3]);
+        assert_eq!(cascade.query(8), [4, 3, 0, 3]);
+        assert_eq!(cascade.query(100), [4, 3, 0, 4]);
+        assert!(FractionalCascade::new(vec![]).query(3).is_empty());
+        assert_eq!(FractionalCascade::new(vec![vec![]]).query(3), [0]);
+    }
+
+    #[test]
+    fn agrees_with_binary_search() {
+        let mut rng = ChaCha8Rng::seed_from_u64(149);
+        for _ in 0..20 {
+            let k = rng.gen_range(1..15);
+            let range = rng.gen_range(1..1000);
+            let lists = random_lists(&mut rng, k, 100, range);
+            let cascade = FractionalCascade::new(lists.clone());
+            for _ in 0..1000 {
+                let x = rng.gen_range(-range - 2..range + 2);
+                assert_eq!(cascade.query(x), binary_searches(&lists, x), "{x}");
+            }
+        }
+    }
+
+    #[test]
+    fn linear_size() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1490);
+        let lists = random_lists(&mut rng, 30, 1000, 1_000_000);
+        let total: usize = lists.iter().map(Vec::len).sum();
+        let cascade = FractionalCascade::new(lists);
+        let merged: usize = cascade.levels.iter().map(|level| level.keys.len()).sum();
+        assert!(merged <= 2 * total, "{merged} keys for {total} items");
+    }
+
+    #[test]
+    #[should_panic(expected = "the list 1 is not sorted")]
+    fn unsorted_list() {
+        FractionalCascade::new(vec![vec![1, 2], vec![3, 1]]);
+    }
+
+    #[test]
+    #[ignore = "slow test for measuring time"]
+    fn faster_than_binary_searches() {
+        use std::time::Instant;
+        let mut rng = ChaCha8Rng::seed_from_u64(1491);
+        for k in [10, 20, 50] {
+            let lists = random_lists(&mut rng, k, 100_000, 1 << 40);
+            let cascade = FractionalCascade::new(lists.clone());
+            let queries: Vec<i64> = (0..100_000)
+                .map(|_| rng.gen_range(-(1 << 40)..1 << 40))
+                .collect();
+            let start = Instant::now();
+            let cascaded: usize = queries.iter().map(|&x| cascade.query(x)[k - 1]).sum();
+            let cascading = start.elapsed();
+            let start = Instant::now();
+            let searched: usize = queries
+                .iter()
+                .map(|&x| binary_searches(&lists, x)[k - 1])
+                .sum();
+            let searching = start.elapsed();
+            assert_eq!(cascaded, searched);
+            println!("{k} lists: cascading {cascading:?}, binary searches {searching:?}");
+        }
+    }
+}
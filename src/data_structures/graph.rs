@@ -114,6 +114,359 @@ pub trait Graph {
     }
 }
 
+/// An error in a DOT description, with the line where it was found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotParseError {
+    pub line: usize,
+    pub reason: String,
+}
+
+impl fmt::Display for DotParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid DOT on line {}: {}", self.line, self.reason)
+    }
+}
+
+/// Quotes a node name as a DOT identifier, escaping the quotes and backslashes in it.
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Writes a graph in the DOT language of Graphviz, with its nodes and edges sorted so that
+/// the output does not depend on the order of the hash map.
+fn write_dot(keyword: &str, connector: &str, nodes: Vec<&String>, edges: Vec<Edge>) -> String {
+    let mut dot = format!("{keyword} {{\n");
+    for node in nodes {
+        dot += &format!("    {};\n", quote(node));
+    }
+    for (from, to, weight) in edges {
+        dot += &format!(
+            "    {} {connector} {} [label=\"{weight}\"];\n",
+            quote(from),
+            quote(to)
+        );
+    }
+    dot += "}\n";
+    dot
+}
+
+type Edge<'a> = (&'a String, &'a String, i32);
+
+impl DirectedGraph {
+    /// Describes the graph in the DOT language of Graphviz, every edge labelled with its weight.
+    pub fn to_dot(&self) -> String {
+        let mut nodes: Vec<&String> = self.nodes().into_iter().collect();
+        nodes.sort();
+        let mut edges = self.edges();
+        edges.sort();
+        write_dot("digraph", "->", nodes, edges)
+    }
+}
+
+impl UndirectedGraph {
+    /// Describes the graph in the DOT language of Graphviz, every edge labelled with its weight.
+    pub fn to_dot(&self) -> String {
+        let mut nodes: Vec<&String> = self.nodes().into_iter().collect();
+        nodes.sort();
+        // every edge is in the lists of both of its ends, and a loop twice in the same list
+        let mut edges: Vec<Edge> = self
+            .edges()
+            .into_iter()
+            .filter(|(from, to, _)| from <= to)
+            .collect();
+        edges.sort();
+        let mut loops_seen = 0;
+        edges.retain(|(from, to, _)| {
+            if from != to {
+                return true;
+            }
+            loops_seen += 1;
+            loops_seen % 2 == 1
+        });
+        write_dot("graph", "--", nodes, edges)
+    }
+
+    /// Parses an undirected graph from a subset of the DOT language of Graphviz.
+    ///
+    /// The graph may have a name, and its statements, separated by semicolons or new lines, are
+    /// nodes, chains of edges like `a -- b -- c`, and attributes of the graph, nodes or edges.
+    /// The names are identifiers, numbers or quoted strings, and the weight of an edge is the
+    /// integer in its `weight` or `label` attribute, 1 by default, other attributes being
+    /// ignored. Directed graphs, subgraphs and ports are not supported.
+    pub fn from_dot(dot: &str) -> Result<UndirectedGraph, DotParseError> {
+        let mut parser = DotParser {
+            tokens: tokenize(dot)?,
+            position: 0,
+        };
+        parser.parse_graph()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DotToken {
+    /// A name, which is a keyword if it was not quoted
+    Id {
+        name: String,
+        quoted: bool,
+    },
+    Symbol(&'static str),
+}
+
+/// Splits a DOT description into tokens, with their lines, skipping whitespace and comments.
+fn tokenize(dot: &str) -> Result<Vec<(DotToken, usize)>, DotParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = dot.chars().peekable();
+    let mut line = 1;
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            c if c.is_whitespace() => {}
+            '/' if chars.peek() == Some(&'/') => while chars.next_if(|&c| c != '\n').is_some() {},
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                loop {
+                    match chars.next() {
+                        None => {
+                            return Err(DotParseError {
+                                line,
+                                reason: "unterminated comment".to_string(),
+                            })
+                        }
+                        Some('/') if previous == '*' => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            previous = c;
+                        }
+                    }
+                }
+            }
+            '{' | '}' | '[' | ']' | '=' | ';' | ',' => {
+                let symbol = ["{", "}", "[", "]", "=", ";", ","]
+                    .into_iter()
+                    .find(|symbol| symbol.starts_with(c))
+                    .unwrap();
+                tokens.push((DotToken::Symbol(symbol), line));
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                chars.next();
+                tokens.push((DotToken::Symbol("--"), line));
+            }
+            '-' if chars.peek() == Some(&'>') => {
+                chars.next();
+                tokens.push((DotToken::Symbol("->"), line));
+            }
+            '"' => {
+                let start = line;
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        None => {
+                            return Err(DotParseError {
+                                line: start,
+                                reason: "unterminated string".to_string(),
+                            })
+                        }
+                        Some('"') => break,
+                        Some('\\') if matches!(chars.peek(), Some('"' | '\\')) => {
+                            name.push(chars.next().unwrap());
+                        }
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            name.push(c);
+                        }
+                    }
+                }
+                tokens.push((DotToken::Id { name, quoted: true }, start));
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '-' || c == '.' => {
+                let mut name = c.to_string();
+                while let Some(c) = chars.next_if(|&c| c.is_alphanumeric() || c == '_' || c == '.')
+                {
+                    name.push(c);
+                }
+                tokens.push((
+                    DotToken::Id {
+                        name,
+                        quoted: false,
+                    },
+                    line,
+                ));
+            }
+            c => {
+                return Err(DotParseError {
+                    line,
+                    reason: format!("unexpected character {c:?}"),
+                })
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct DotParser {
+    tokens: Vec<(DotToken, usize)>,
+    position: usize,
+}
+
+impl DotParser {
+    fn peek(&self) -> Option<&DotToken> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    /// Returns the line of the next token, or of the last one at the end.
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.position)
+            .or(self.tokens.last())
+            .map_or(1, |&(_, line)| line)
+    }
+
+    fn error(&self, reason: &str) -> DotParseError {
+        DotParseError {
+            line: self.line(),
+            reason: reason.to_string(),
+        }
+    }
+
+    /// Consumes the next token if it is the given symbol.
+    fn eat(&mut self, symbol: &str) -> bool {
+        let found = matches!(self.peek(), Some(DotToken::Symbol(s)) if *s == symbol);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), DotParseError> {
+        if self.eat(symbol) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected {symbol:?}")))
+        }
+    }
+
+    /// Consumes the next token if it is a name, returning it and whether it was quoted.
+    fn id(&mut self) -> Option<(String, bool)> {
+        match self.peek() {
+            Some(DotToken::Id { name, quoted }) => {
+                let id = (name.clone(), *quoted);
+                self.position += 1;
+                Some(id)
+            }
+            _ => None,
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let found = matches!(
+            self.peek(),
+            Some(DotToken::Id { name, quoted: false }) if name.eq_ignore_ascii_case(keyword)
+        );
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn parse_graph(&mut self) -> Result<UndirectedGraph, DotParseError> {
+        if self.keyword("strict") {
+            return Err(self.error("strict graphs are not supported"));
+        }
+        if self.keyword("digraph") {
+            return Err(self.error("directed graphs are not supported"));
+        }
+        if !self.keyword("graph") {
+            return Err(self.error("expected \"graph\""));
+        }
+        self.id();
+        self.expect("{")?;
+        let mut graph = UndirectedGraph::new();
+        while !self.eat("}") {
+            if self.eat(";") {
+                continue;
+            }
+            self.parse_statement(&mut graph)?;
+        }
+        if self.peek().is_some() {
+            return Err(self.error("unexpected text after the graph"));
+        }
+        Ok(graph)
+    }
+
+    fn parse_statement(&mut self, graph: &mut UndirectedGraph) -> Result<(), DotParseError> {
+        if self.keyword("subgraph") || self.peek() == Some(&DotToken::Symbol("{")) {
+            return Err(self.error("subgraphs are not supported"));
+        }
+        let (name, quoted) = self
+            .id()
+            .ok_or_else(|| self.error("expected a statement"))?;
+        let keyword = !quoted && ["graph", "node", "edge"].contains(&name.to_lowercase().as_str());
+        if keyword {
+            // default attributes, which do not change the nodes or the edges
+            self.parse_attributes()?;
+            return Ok(());
+        }
+        if self.eat("=") {
+            // an attribute of the graph
+            self.id().ok_or_else(|| self.error("expected a value"))?;
+            return Ok(());
+        }
+        let mut chain = vec![name];
+        while self.eat("--") {
+            let (next, _) = self.id().ok_or_else(|| self.error("expected a node"))?;
+            chain.push(next);
+        }
+        if self.peek() == Some(&DotToken::Symbol("->")) {
+            return Err(self.error("directed edges are not supported"));
+        }
+        let attributes = self.parse_attributes()?;
+        if chain.len() == 1 {
+            graph.add_node(&chain[0]);
+            return Ok(());
+        }
+        let weight = match attributes
+            .iter()
+            .find(|(key, _, _)| key == "weight")
+            .or(attributes.iter().find(|(key, _, _)| key == "label"))
+        {
+            None => 1,
+            Some((key, value, line)) => value.parse().map_err(|_| DotParseError {
+                line: *line,
+                reason: format!("the {key} of an edge must be an integer"),
+            })?,
+        };
+        for pair in chain.windows(2) {
+            graph.add_edge((&pair[0], &pair[1], weight));
+        }
+        Ok(())
+    }
+
+    /// Parses the lists of attributes after a statement, if any, with the lines of their values.
+    fn parse_attributes(&mut self) -> Result<Vec<(String, String, usize)>, DotParseError> {
+        let mut attributes = Vec::new();
+        while self.eat("[") {
+            while !self.eat("]") {
+                let (key, _) = self
+                    .id()
+                    .ok_or_else(|| self.error("expected an attribute"))?;
+                self.expect("=")?;
+                let line = self.line();
+                let (value, _) = self.id().ok_or_else(|| self.error("expected a value"))?;
+                attributes.push((key, value, line));
+                if !self.eat(",") {
+                    self.eat(";");
+                }
+            }
+        }
+        Ok(attributes)
+    }
+}
+
 #[cfg(test)]
 mod test_undirected_graph {
     use super::Graph;
@@ -154,6 +507,118 @@ mod test_undirected_graph {
     }
 }
 
+#[cfg(test)]
+mod test_dot {
+    use super::{DirectedGraph, DotParseError, Graph, UndirectedGraph};
+
+    /// Returns the edges of a graph, sorted.
+    fn sorted_edges(graph: &impl Graph) -> Vec<(String, String, i32)> {
+        let mut edges: Vec<_> = graph
+            .edges()
+            .into_iter()
+            .map(|(from, to, weight)| (from.clone(), to.clone(), weight))
+            .collect();
+        edges.sort();
+        edges
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let mut graph = UndirectedGraph::new();
+        graph.add_edge(("b", "c", 10));
+        graph.add_edge(("a", "b", 5));
+        graph.add_edge(("c", "a", -7));
+        graph.add_node("d");
+        assert_eq!(
+            graph.to_dot(),
+            "graph {\n    \"a\";\n    \"b\";\n    \"c\";\n    \"d\";\n    \
+             \"a\" -- \"b\" [label=\"5\"];\n    \
+             \"a\" -- \"c\" [label=\"-7\"];\n    \
+             \"b\" -- \"c\" [label=\"10\"];\n}\n"
+        );
+
+        let mut graph = DirectedGraph::new();
+        graph.add_edge(("a", "b", 5));
+        graph.add_edge(("b", "a", 3));
+        graph.add_edge(("say \"hi\"", "a", 1));
+        assert_eq!(
+            graph.to_dot(),
+            "digraph {\n    \"a\";\n    \"b\";\n    \"say \\\"hi\\\"\";\n    \
+             \"a\" -> \"b\" [label=\"5\"];\n    \
+             \"b\" -> \"a\" [label=\"3\"];\n    \
+             \"say \\\"hi\\\"\" -> \"a\" [label=\"1\"];\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut graph = UndirectedGraph::new();
+        graph.add_edge(("a", "b", 5));
+        graph.add_edge(("b", "c", 10));
+        graph.add_edge(("c", "a", 7));
+        graph.add_edge(("a", "b", 2));
+        graph.add_edge(("c", "c", 4));
+        graph.add_edge(("北京", "quote \" and \\", 0));
+        graph.add_node("lonely");
+        let parsed = UndirectedGraph::from_dot(&graph.to_dot()).unwrap();
+        assert_eq!(parsed.nodes(), graph.nodes());
+        assert_eq!(sorted_edges(&parsed), sorted_edges(&graph));
+        assert_eq!(parsed.to_dot(), graph.to_dot());
+
+        let empty = UndirectedGraph::new();
+        assert_eq!(empty.to_dot(), "graph {\n}\n");
+        assert!(UndirectedGraph::from_dot(&empty.to_dot())
+            .unwrap()
+            .nodes()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_from_dot() {
+        let dot = "
+            /* a small network */
+            graph network {
+                rankdir = LR
+                node [shape=circle];
+                a -- b -- c [weight=3, color=red]
+                c -- d [label=\"-2\"]; d -- a // unlabelled
+                e
+            }";
+        let graph = UndirectedGraph::from_dot(dot).unwrap();
+        assert_eq!(graph.nodes().len(), 5);
+        assert_eq!(
+            graph.neighbours("c").unwrap(),
+            &vec![(String::from("b"), 3), (String::from("d"), -2)]
+        );
+        assert_eq!(
+            graph.neighbours("a").unwrap(),
+            &vec![(String::from("b"), 3), (String::from("d"), 1)]
+        );
+        assert!(graph.neighbours("e").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |dot: &str| UndirectedGraph::from_dot(dot).err().map(|e| e.line);
+        assert_eq!(error("digraph { a -> b }"), Some(1));
+        assert_eq!(error("graph {\n a -> b\n}"), Some(2));
+        assert_eq!(error("graph {\n\n a -- b [label=x]\n}"), Some(3));
+        assert_eq!(error("graph { subgraph s { a } }"), Some(1));
+        assert_eq!(error("graph { a -- }"), Some(1));
+        assert_eq!(error("graph { a "), Some(1));
+        assert_eq!(error("graph { \"a }"), Some(1));
+        assert_eq!(error("graph { } graph { }"), Some(1));
+        assert_eq!(error("graph { a -- b }"), None);
+        assert_eq!(
+            UndirectedGraph::from_dot("tree { }").err(),
+            Some(DotParseError {
+                line: 1,
+                reason: String::from("expected \"graph\"")
+            })
+        );
+    }
+}
+
 #[cfg(test)]
 mod test_directed_graph {
     use super::DirectedGraph;
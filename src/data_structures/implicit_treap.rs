@@ -0,0 +1,399 @@
+use crate::error::{AlgorithmError, AlgorithmResult};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::{iter::FromIterator, mem, ops::Range};
+
+type Link<T> = Option<Box<ImplicitNode<T>>>;
+
+/// An internal node of an `ImplicitTreap`.
+struct ImplicitNode<T> {
+    value: T,
+    priority: u64,
+    /// The number of nodes in the subtree of this node
+    size: usize,
+    /// Whether the subtree of this node is to be reversed, which is applied to its children
+    /// the next time they are reached
+    reversed: bool,
+    left: Link<T>,
+    right: Link<T>,
+}
+
+/// A sequence stored in a treap, with implicit keys.
+///
+/// The key of a value is its position in the sequence, which is never stored: it is the
+/// number of nodes before it, found with the sizes of the subtrees on the way down. Like the
+/// keyed `Treap`, everything is done with `split` and `merge`, so that inserting, removing,
+/// splitting and concatenating take O(log(n)) with high probability, with a recursion as deep
+/// as the tree. Reversing a range splits it out and flags its root, and the flag is only
+/// pushed down to the children of a node when the node is next visited.
+pub struct ImplicitTreap<T> {
+    root: Link<T>,
+    rng: ChaCha8Rng,
+}
+
+fn size<T>(link: &Link<T>) -> usize {
+    link.as_ref().map_or(0, |node| node.size)
+}
+
+impl<T> ImplicitNode<T> {
+    fn update_size(&mut self) {
+        self.size = 1 + size(&self.left) + size(&self.right);
+    }
+
+    /// Applies a pending reversal to the children.
+    fn push_down(&mut self) {
+        if self.reversed {
+            mem::swap(&mut self.left, &mut self.right);
+            for child in [&mut self.left, &mut self.right].into_iter().flatten() {
+                child.reversed ^= true;
+            }
+            self.reversed = false;
+        }
+    }
+}
+
+/// Splits a tree into its first `index` values and the others.
+fn split<T>(link: Link<T>, index: usize) -> (Link<T>, Link<T>) {
+    match link {
+        None => (None, None),
+        Some(mut node) => {
+            node.push_down();
+            let left_size = size(&node.left);
+            if index <= left_size {
+                let (left, middle) = split(node.left.take(), index);
+                node.left = middle;
+                node.update_size();
+                (left, Some(node))
+            } else {
+                let (middle, right) = split(node.right.take(), index - left_size - 1);
+                node.right = middle;
+                node.update_size();
+                (Some(node), right)
+            }
+        }
+    }
+}
+
+/// Concatenates two trees.
+fn merge<T>(left: Link<T>, right: Link<T>) -> Link<T> {
+    match (left, right) {
+        (None, tree) | (tree, None) => tree,
+        (Some(mut left), Some(mut right)) => {
+            if left.priority > right.priority {
+                left.push_down();
+                left.right = merge(left.right.take(), Some(right));
+                left.update_size();
+                Some(left)
+            } else {
+                right.push_down();
+                right.left = merge(Some(left), right.left.take());
+                right.update_size();
+                Some(right)
+            }
+        }
+    }
+}
+
+/// Returns the children of a node in the order of the sequence, given whether the reversals
+/// pending above it flip its subtree.
+fn children<T>(node: &ImplicitNode<T>, flipped: bool) -> (&Link<T>, &Link<T>, bool) {
+    let flipped = flipped ^ node.reversed;
+    if flipped {
+        (&node.right, &node.left, flipped)
+    } else {
+        (&node.left, &node.right, flipped)
+    }
+}
+
+impl<T> ImplicitTreap<T> {
+    /// Creates an empty sequence, drawing its priorities from a generator seeded by the system.
+    pub fn new() -> ImplicitTreap<T> {
+        Self::with_rng(ChaCha8Rng::from_entropy())
+    }
+
+    /// Creates an empty sequence whose priorities are reproducible from `seed`.
+    pub fn with_seed(seed: u64) -> ImplicitTreap<T> {
+        Self::with_rng(ChaCha8Rng::seed_from_u64(seed))
+    }
+
+    fn with_rng(rng: ChaCha8Rng) -> ImplicitTreap<T> {
+        ImplicitTreap { root: None, rng }
+    }
+
+    /// Returns the number of values in the sequence.
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+
+    /// Returns `true` if the sequence contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    fn check_index(&self, index: usize, max: usize) -> AlgorithmResult<()> {
+        if index > max {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "index",
+                reason: format!("must be at most {max}, got {index}"),
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns a reference to the value at `index`, in O(log(n)).
+    pub fn get(&self, mut index: usize) -> Option<&T> {
+        let mut current = &self.root;
+        let mut flipped = false;
+        while let Some(node) = current {
+            let (left, right, node_flipped) = children(node, flipped);
+            let left_size = size(left);
+            if index == left_size {
+                return Some(&node.value);
+            }
+            if index < left_size {
+                current = left;
+            } else {
+                index -= left_size + 1;
+                current = right;
+            }
+            flipped = node_flipped;
+        }
+        None
+    }
+
+    /// Inserts a value at `index`, shifting the values after it, in O(log(n)).
+    pub fn insert_at(&mut self, index: usize, value: T) -> AlgorithmResult<()> {
+        self.check_index(index, self.len())?;
+        let node = Box::new(ImplicitNode {
+            value,
+            priority: self.rng.gen(),
+            size: 1,
+            reversed: false,
+            left: None,
+            right: None,
+        });
+        let (left, right) = split(self.root.take(), index);
+        self.root = merge(merge(left, Some(node)), right);
+        Ok(())
+    }
+
+    /// Appends a value at the end of the sequence.
+    pub fn push(&mut self, value: T) {
+        self.insert_at(self.len(), value)
+            .expect("the end is a valid index");
+    }
+
+    /// Removes the value at `index`, shifting the values after it, in O(log(n)).
+    pub fn remove_at(&mut self, index: usize) -> Option<T> {
+        if index >= self.len() {
+            return None;
+        }
+        let (left, rest) = split(self.root.take(), index);
+        let (removed, right) = split(rest, 1);
+        self.root = merge(left, right);
+        removed.map(|node| node.value)
+    }
+
+    /// Splits the sequence into its first `index` values and the others, in O(log(n)).
+    pub fn split_at(
+        mut self,
+        index: usize,
+    ) -> AlgorithmResult<(ImplicitTreap<T>, ImplicitTreap<T>)> {
+        self.check_index(index, self.len())?;
+        let (left, right) = split(self.root.take(), index);
+        let seed = self.rng.gen();
+        Ok((
+            ImplicitTreap {
+                root: left,
+                rng: self.rng,
+            },
+            ImplicitTreap {
+                root: right,
+                rng: ChaCha8Rng::seed_from_u64(seed),
+            },
+        ))
+    }
+
+    /// Appends the values of `other` to the sequence, in O(log(n)).
+    pub fn concat(mut self, other: ImplicitTreap<T>) -> ImplicitTreap<T> {
+        self.root = merge(self.root.take(), other.root);
+        self
+    }
+
+    /// Reverses the values in `range`, in O(log(n)).
+    pub fn reverse(&mut self, range: Range<usize>) -> AlgorithmResult<()> {
+        self.check_index(range.end, self.len())?;
+        if range.start > range.end {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "range",
+                reason: format!("starts at {} after its end {}", range.start, range.end),
+            });
+        }
+        let (left, rest) = split(self.root.take(), range.start);
+        let (mut middle, right) = split(rest, range.len());
+        if let Some(node) = &mut middle {
+            node.reversed ^= true;
+        }
+        self.root = merge(merge(left, middle), right);
+        Ok(())
+    }
+
+    /// Returns an iterator over the values of the sequence, in order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        let mut iter = Iter { stack: Vec::new() };
+        iter.push_left_path(&self.root, false);
+        iter
+    }
+}
+
+impl<T> Default for ImplicitTreap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<T> for ImplicitTreap<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut sequence = ImplicitTreap::new();
+        for value in iter {
+            sequence.push(value);
+        }
+        sequence
+    }
+}
+
+/// An iterator over the values of an `ImplicitTreap`.
+///
+/// This struct is created by the `iter` method of `ImplicitTreap`.
+pub struct Iter<'a, T> {
+    /// The nodes whose values are still to visit, with whether their subtrees are flipped
+    stack: Vec<(&'a ImplicitNode<T>, bool)>,
+}
+
+impl<'a, T> Iter<'a, T> {
+    fn push_left_path(&mut self, mut link: &'a Link<T>, mut flipped: bool) {
+        while let Some(node) = link {
+            self.stack.push((node, flipped));
+            let (left, _, node_flipped) = children(node, flipped);
+            link = left;
+            flipped = node_flipped;
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let (node, flipped) = self.stack.pop()?;
+        let (_, right, node_flipped) = children(node, flipped);
+        self.push_left_path(right, node_flipped);
+        Some(&node.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ImplicitTreap, Link};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn depth<T>(link: &Link<T>) -> usize {
+        link.as_ref()
+            .map_or(0, |node| 1 + depth(&node.left).max(depth(&node.right)))
+    }
+
+    fn to_vec<T: Clone>(sequence: &ImplicitTreap<T>) -> Vec<T> {
+        sequence.iter().cloned().collect()
+    }
+
+    #[test]
+    fn sequence_operations() {
+        let mut sequence = ImplicitTreap::with_seed(120);
+        for (index, value) in [(0, 'b'), (0, 'a'), (2, 'd'), (2, 'c')] {
+            sequence.insert_at(index, value).unwrap();
+        }
+        assert_eq!(to_vec(&sequence), ['a', 'b', 'c', 'd']);
+        assert!(sequence.insert_at(5, 'x').is_err());
+        assert_eq!(sequence.get(2), Some(&'c'));
+        assert_eq!(sequence.get(4), None);
+
+        sequence.reverse(1..4).unwrap();
+        assert_eq!(to_vec(&sequence), ['a', 'd', 'c', 'b']);
+        assert_eq!(sequence.get(1), Some(&'d'));
+        sequence.reverse(0..0).unwrap();
+        assert!(sequence.reverse(2..5).is_err());
+        assert_eq!(sequence.remove_at(1), Some('d'));
+        assert_eq!(sequence.remove_at(3), None);
+        assert_eq!(to_vec(&sequence), ['a', 'c', 'b']);
+
+        let (front, back) = sequence.split_at(1).unwrap();
+        assert_eq!(to_vec(&front), ['a']);
+        assert_eq!(to_vec(&back), ['c', 'b']);
+        let sequence = back.concat(front);
+        assert_eq!(to_vec(&sequence), ['c', 'b', 'a']);
+        assert!(sequence.split_at(4).is_err());
+    }
+
+    #[test]
+    fn against_vec() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1204);
+        let mut sequence = ImplicitTreap::with_seed(1205);
+        let mut model = Vec::new();
+        for step in 0..5000 {
+            match rng.gen_range(0..5) {
+                0 | 1 => {
+                    let index = rng.gen_range(0..=model.len());
+                    sequence.insert_at(index, step).unwrap();
+                    model.insert(index, step);
+                }
+                2 => {
+                    let index = rng.gen_range(0..=model.len());
+                    let removed = (index < model.len()).then(|| model.remove(index));
+                    assert_eq!(sequence.remove_at(index), removed);
+                }
+                3 => {
+                    let start = rng.gen_range(0..=model.len());
+                    let end = rng.gen_range(start..=model.len());
+                    sequence.reverse(start..end).unwrap();
+                    model[start..end].reverse();
+                }
+                _ => {
+                    let index = rng.gen_range(0..=model.len());
+                    assert_eq!(sequence.get(index), model.get(index));
+                }
+            }
+            assert_eq!(sequence.len(), model.len());
+        }
+        assert_eq!(to_vec(&sequence), model);
+
+        let index = model.len() / 3;
+        let (front, back) = sequence.split_at(index).unwrap();
+        assert_eq!(to_vec(&front), model[..index]);
+        assert_eq!(to_vec(&back), model[index..]);
+        let rotated = back.concat(front);
+        model.rotate_left(index);
+        assert_eq!(to_vec(&rotated), model);
+    }
+
+    #[test]
+    fn large_sequences() {
+        let n = 100_000;
+        let mut sequence: ImplicitTreap<usize> = (0..n).collect();
+        assert!((depth(&sequence.root) as f64) < 3.0 * (n as f64).log2());
+        sequence.reverse(0..n).unwrap();
+        assert!(sequence.iter().copied().eq((0..n).rev()));
+        let mut rng = ChaCha8Rng::seed_from_u64(1206);
+        let mut model: Vec<usize> = (0..n).rev().collect();
+        for _ in 0..200 {
+            let start = rng.gen_range(0..n);
+            let end = rng.gen_range(start..=n);
+            sequence.reverse(start..end).unwrap();
+            model[start..end].reverse();
+        }
+        assert!(sequence.iter().eq(model.iter()));
+        assert!((depth(&sequence.root) as f64) < 3.0 * (n as f64).log2());
+        let (front, back) = sequence.split_at(n / 2).unwrap();
+        assert_eq!(front.concat(back).len(), n);
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::BTreeMap;
+
+/// A set of points made of disjoint half-open intervals `[lo, hi)`, merged as they are
+/// inserted
+/// <https://en.wikipedia.org/wiki/Interval_(mathematics)>
+///
+/// A `BTreeMap` maps the start of every interval to its end, the intervals never overlapping
+/// nor touching, so that the only one that may contain a point is that with the last start
+/// before it. An insertion merges the new interval with the one starting before it if they meet,
+/// and with all those starting within it, and a removal cuts the intervals it meets, keeping
+/// their parts outside of it. Each takes O(log(n)) besides the intervals it merges or removes,
+/// each of which was inserted once, so that a sequence of operations takes O(log(n)) amortized
+/// each.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DisjointIntervalSet<T: Ord + Copy> {
+    intervals: BTreeMap<T, T>,
+}
+
+impl<T: Ord + Copy> DisjointIntervalSet<T> {
+    pub fn new() -> Self {
+        DisjointIntervalSet {
+            intervals: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the number of disjoint intervals.
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Adds the points of `[lo, hi)`, merging the intervals overlapping it or adjacent to it,
+    /// and does nothing if `lo >= hi`.
+    pub fn insert(&mut self, mut lo: T, mut hi: T) {
+        if lo >= hi {
+            return;
+        }
+        if let Some((&start, &end)) = self.intervals.range(..=lo).next_back() {
+            if end >= lo {
+                lo = start;
+                hi = hi.max(end);
+            }
+        }
+        let merged: Vec<T> = self
+            .intervals
+            .range(lo..=hi)
+            .map(|(&start, _)| start)
+            .collect();
+        for start in merged {
+            hi = hi.max(self.intervals.remove(&start).unwrap());
+        }
+        self.intervals.insert(lo, hi);
+    }
+
+    /// Removes the points of `[lo, hi)`, splitting the interval around it if it is inside one,
+    /// and does nothing if `lo >= hi`.
+    pub fn remove(&mut self, lo: T, hi: T) {
+        if lo >= hi {
+            return;
+        }
+        if let Some((&start, &end)) = self.intervals.range(..lo).next_back() {
+            if end > lo {
+                self.intervals.insert(start, lo);
+                if end > hi {
+                    self.intervals.insert(hi, end);
+                }
+            }
+        }
+        let cut: Vec<T> = self
+            .intervals
+            .range(lo..hi)
+            .map(|(&start, _)| start)
+            .collect();
+        for start in cut {
+            let end = self.intervals.remove(&start).unwrap();
+            if end > hi {
+                self.intervals.insert(hi, end);
+            }
+        }
+    }
+
+    pub fn contains(&self, point: &T) -> bool {
+        self.intervals
+            .range(..=point)
+            .next_back()
+            .is_some_and(|(_, end)| point < end)
+    }
+
+    /// Returns the intervals `(lo, hi)` in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = (T, T)> + '_ {
+        self.intervals.iter().map(|(&lo, &hi)| (lo, hi))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    /// The intervals sorted by their start, merged again after every change
+    fn merge(mut intervals: Vec<(i32, i32)>) -> Vec<(i32, i32)> {
+        intervals.retain(|(lo, hi)| lo < hi);
+        intervals.sort();
+        let mut merged: Vec<(i32, i32)> = vec![];
+        for (lo, hi) in intervals {
+            match merged.last_mut() {
+                Some(last) if last.1 >= lo => last.1 = last.1.max(hi),
+                _ => merged.push((lo, hi)),
+            }
+        }
+        merged
+    }
+
+    #[test]
+    fn merges_and_splits() {
+        let mut set = DisjointIntervalSet::new();
+        assert!(set.is_empty());
+        set.insert(1, 3);
+        set.insert(5, 8);
+        set.insert(10, 12);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [(1, 3), (5, 8), (10, 12)]);
+        // adjacent intervals merge, and one covering several swallows them
+        set.insert(3, 4);
+        set.insert(4, 11);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [(1, 12)]);
+        set.insert(20, 20);
+        assert_eq!(set.len(), 1);
+        set.remove(5, 7);
+        set.remove(0, 2);
+        set.remove(11, 30);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [(2, 5), (7, 11)]);
+        assert!(set.contains(&2) && set.contains(&4) && set.contains(&7));
+        assert!(!set.contains(&1) && !set.contains(&5) && !set.contains(&11));
+        set.remove(0, 100);
+        assert!(set.is_empty());
+        // any ordered type
+        let mut words = DisjointIntervalSet::new();
+        words.insert("apple", "banana");
+        words.insert("b", "cherry");
+        assert!(words.contains(&"avocado") && !words.contains(&"date"));
+        assert_eq!(words.iter().collect::<Vec<_>>(), [("apple", "cherry")]);
+    }
+
+    #[test]
+    fn agrees_with_a_sorted_list() {
+        let mut rng = ChaCha8Rng::seed_from_u64(170);
+        let mut set = DisjointIntervalSet::new();
+        let mut list: Vec<(i32, i32)> = vec![];
+        for _ in 0..3000 {
+            let lo = rng.gen_range(0..200);
+            let hi = lo + rng.gen_range(-2..30);
+            if rng.gen_bool(0.6) {
+                set.insert(lo, hi);
+                list.push((lo, hi));
+            } else {
+                set.remove(lo, hi);
+                if lo < hi {
+                    // the parts of every interval before and after the removed one
+                    list = list
+                        .into_iter()
+                        .flat_map(|(a, b)| [(a, b.min(lo)), (a.max(hi), b)])
+                        .collect();
+                }
+            }
+            list = merge(list);
+            assert_eq!(set.iter().collect::<Vec<_>>(), list);
+            let point = rng.gen_range(-5..240);
+            let expected = list.iter().any(|&(a, b)| a <= point && point < b);
+            assert_eq!(set.contains(&point), expected);
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use std::cmp::Ordering;
+use std::iter::FromIterator;
+
+/// A static interval tree of closed intervals `[lo, hi]` of any ordered type, mapped to values
+/// <https://en.wikipedia.org/wiki/Interval_tree#Augmented_tree>
+///
+/// The intervals are kept in an array sorted by `(lo, hi)`, seen as a balanced binary search
+/// tree: the middle of every range of the array is the root of the range, and the halves on
+/// its sides are its subtrees. Every root also stores the largest `hi` of its range, so that a
+/// query skips the subtrees all ending before it, and the right subtrees all starting after it.
+/// A query reporting `k` intervals thus takes O(min(n, (k + 1) log(n))).
+///
+/// An insertion or a deletion shifts the array and recomputes the largest ends along it, in
+/// O(n), which suits intervals queried much more often than they change. The AVL tree of
+/// `crate::intervals::IntervalTree` takes O(log(n)) for both, for half-open integer intervals.
+pub struct IntervalTree<T: Ord, V> {
+    /// The intervals with their values, sorted by `(lo, hi)` and then by insertion
+    intervals: Vec<(T, T, V)>,
+    /// The index of the interval of largest `hi` in the range whose root is at every index
+    max_hi: Vec<usize>,
+}
+
+impl<T: Ord, V> IntervalTree<T, V> {
+    pub fn new() -> Self {
+        IntervalTree {
+            intervals: vec![],
+            max_hi: vec![],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    fn order(a: &(T, T, V), lo: &T, hi: &T) -> Ordering {
+        a.0.cmp(lo).then(a.1.cmp(hi))
+    }
+
+    /// Recomputes the largest ends of the range `[start, end)` of the array, and returns the
+    /// index of the largest one.
+    fn build(&mut self, start: usize, end: usize) -> Option<usize> {
+        if start >= end {
+            return None;
+        }
+        let root = start + (end - start) / 2;
+        let mut best = root;
+        for child in [self.build(start, root), self.build(root + 1, end)]
+            .into_iter()
+            .flatten()
+        {
+            if self.intervals[child].1 > self.intervals[best].1 {
+                best = child;
+            }
+        }
+        self.max_hi[root] = best;
+        Some(best)
+    }
+
+    fn rebuild(&mut self) {
+        self.max_hi = vec![0; self.intervals.len()];
+        self.build(0, self.intervals.len());
+    }
+
+    /// Adds the interval `[lo, hi]` with its value. Equal intervals are all kept.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lo > hi`.
+    pub fn insert(&mut self, lo: T, hi: T, value: V) {
+        assert!(lo <= hi, "intervals must have lo <= hi");
+        let position = self
+            .intervals
+            .partition_point(|a| Self::order(a, &lo, &hi) != Ordering::Greater);
+        self.intervals.insert(position, (lo, hi, value));
+        self.rebuild();
+    }
+
+    /// Removes the first inserted of the intervals `[lo, hi]`, and returns `true` if there was
+    /// one.
+    pub fn delete(&mut self, lo: &T, hi: &T) -> bool {
+        let position = self
+            .intervals
+            .partition_point(|a| Self::order(a, lo, hi) == Ordering::Less);
+        match self.intervals.get(position) {
+            Some(a) if Self::order(a, lo, hi) == Ordering::Equal => {
+                self.intervals.remove(position);
+                self.rebuild();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the values of the intervals containing `point`, by increasing `(lo, hi)`.
+    pub fn stab(&self, point: &T) -> Vec<&V> {
+        self.overlap_search(point, point)
+    }
+
+    /// Returns the values of the intervals sharing at least a point with `[lo, hi]`, by
+    /// increasing `(lo, hi)`. The result is empty if `lo > hi`.
+    pub fn overlap_search(&self, lo: &T, hi: &T) -> Vec<&V> {
+        let mut found = vec![];
+        if lo <= hi {
+            self.search(0, self.intervals.len(), lo, hi, &mut found);
+        }
+        found
+    }
+
+    fn search<'a>(&'a self, start: usize, end: usize, lo: &T, hi: &T, found: &mut Vec<&'a V>) {
+        if start >= end {
+            return;
+        }
+        let root = start + (end - start) / 2;
+        if self.intervals[self.max_hi[root]].1 < *lo {
+            return;
+        }
+        self.search(start, root, lo, hi, found);
+        let (a_lo, a_hi, value) = &self.intervals[root];
+        // the intervals from the root on start after `hi` once the root does
+        if a_lo <= hi {
+            if a_hi >= lo {
+                found.push(value);
+            }
+            self.search(root + 1, end, lo, hi, found);
+        }
+    }
+}
+
+impl<T: Ord, V> Default for IntervalTree<T, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord, V> FromIterator<(T, T, V)> for IntervalTree<T, V> {
+    /// Builds the tree in O(n log(n)), sorting the intervals once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an interval has `lo > hi`.
+    fn from_iter<I: IntoIterator<Item = (T, T, V)>>(iter: I) -> Self {
+        let mut intervals: Vec<(T, T, V)> = iter.into_iter().collect();
+        assert!(
+            intervals.iter().all(|(lo, hi, _)| lo <= hi),
+            "intervals must have lo <= hi"
+        );
+        // stable, to keep the equal intervals in their order
+        intervals.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
+        let mut tree = IntervalTree {
+            intervals,
+            max_hi: vec![],
+        };
+        tree.rebuild();
+        tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn scan(intervals: &[(i32, i32, usize)], lo: i32, hi: i32) -> Vec<&usize> {
+        let mut found: Vec<&(i32, i32, usize)> = intervals
+            .iter()
+            .filter(|&&(a, b, _)| a <= hi && lo <= b)
+            .collect();
+        found.sort_by_key(|&&(a, b, value)| (a, b, value));
+        found.into_iter().map(|(_, _, value)| value).collect()
+    }
+
+    #[test]
+    fn stabbing_overlapping_intervals() {
+        let mut tree = IntervalTree::new();
+        assert!(tree.stab(&0).is_empty());
+        tree.insert(15, 20, "a");
+        tree.insert(10, 30, "b");
+        tree.insert(17, 19, "c");
+        tree.insert(5, 20, "d");
+        tree.insert(12, 15, "e");
+        tree.insert(30, 40, "f");
+        assert_eq!(tree.len(), 6);
+        assert_eq!(tree.stab(&6), [&"d"]);
+        assert_eq!(tree.stab(&15), [&"d", &"b", &"e", &"a"]);
+        assert_eq!(tree.stab(&18), [&"d", &"b", &"a", &"c"]);
+        // the ends are in the intervals
+        assert_eq!(tree.stab(&30), [&"b", &"f"]);
+        assert_eq!(tree.stab(&40), [&"f"]);
+        assert!(tree.stab(&41).is_empty());
+        assert!(tree.stab(&4).is_empty());
+        assert_eq!(tree.overlap_search(&21, &29), [&"b"]);
+        assert_eq!(tree.overlap_search(&0, &5), [&"d"]);
+        assert!(tree.overlap_search(&20, &10).is_empty());
+    }
+
+    #[test]
+    fn other_types_and_duplicates() {
+        let mut tree: IntervalTree<&str, u32> = IntervalTree::new();
+        tree.insert("apple", "cherry", 1);
+        tree.insert("banana", "date", 2);
+        tree.insert("apple", "cherry", 3);
+        assert_eq!(tree.stab(&"b"), [&1, &3]);
+        assert_eq!(tree.stab(&"cz"), [&2]);
+        assert!(tree.delete(&"apple", &"cherry"));
+        assert_eq!(tree.stab(&"b"), [&3]);
+        assert!(tree.delete(&"apple", &"cherry"));
+        assert!(!tree.delete(&"apple", &"cherry"));
+        assert!(!tree.delete(&"banana", &"cherry"));
+        assert_eq!(tree.len(), 1);
+        // a single point
+        tree.insert("x", "x", 4);
+        assert_eq!(tree.stab(&"x"), [&4]);
+    }
+
+    #[test]
+    fn matches_linear_scan() {
+        let mut rng = ChaCha8Rng::seed_from_u64(134);
+        let mut tree = IntervalTree::new();
+        let mut intervals = vec![];
+        for value in 0..2000 {
+            match rng.gen_range(0..4) {
+                0 | 1 => {
+                    let lo = rng.gen_range(-100..100);
+                    let hi = lo + rng.gen_range(0..30);
+                    tree.insert(lo, hi, value);
+                    intervals.push((lo, hi, value));
+                }
+                2 => {
+                    let lo = rng.gen_range(-100..100);
+                    let hi = lo + rng.gen_range(0..30);
+                    // the first inserted of the equal intervals goes
+                    let first = intervals
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, &(a, b, _))| (a, b) == (lo, hi))
+                        .min_by_key(|(_, &(_, _, value))| value)
+                        .map(|(i, _)| i);
+                    if let Some(i) = first {
+                        intervals.remove(i);
+                    }
+                    assert_eq!(tree.delete(&lo, &hi), first.is_some());
+                }
+                _ => {
+                    let point = rng.gen_range(-110..140);
+                    assert_eq!(tree.stab(&point), scan(&intervals, point, point));
+                    let lo = rng.gen_range(-110..140);
+                    let hi = lo + rng.gen_range(0..20);
+                    assert_eq!(tree.overlap_search(&lo, &hi), scan(&intervals, lo, hi));
+                }
+            }
+            assert_eq!(tree.len(), intervals.len());
+        }
+        let built: IntervalTree<i32, usize> = intervals.iter().copied().collect();
+        for point in -110..140 {
+            assert_eq!(built.stab(&point), scan(&intervals, point, point));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "intervals must have lo <= hi")]
+    fn reversed_interval() {
+        IntervalTree::new().insert(2, 1, ());
+    }
+}
@@ -0,0 +1,455 @@
+use crate::error::{check_rows, AlgorithmError, AlgorithmResult};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// A node of a `KdTree`, splitting the space along one axis at its point.
+struct KdNode {
+    point: usize,
+    axis: usize,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+/// A k-d tree over points of any dimension, for nearest neighbor and radius queries
+/// <https://en.wikipedia.org/wiki/K-d_tree>
+///
+/// Every node splits the space with the plane through its point perpendicular to one axis, the
+/// points before it on that axis going to the left and the others to the right. The axes are
+/// taken in turn down the tree. A query visits the side of the query point first, and skips a
+/// side when the splitting plane is farther than the neighbors found so far.
+///
+/// The points are identified by their index, in the order given to `new` and then `insert`.
+/// Queries return `(index, distance)` pairs with Euclidean distances, sorted by distance and
+/// then by index, so that among points at the same distance the first ones win. The results
+/// are the same as those of a linear scan computing the distances in the same way.
+pub struct KdTree {
+    dimensions: usize,
+    points: Vec<Vec<f64>>,
+    /// The nodes, in an arena indexed by the `left` and `right` links
+    nodes: Vec<KdNode>,
+    root: Option<usize>,
+}
+
+/// A point found by a query, ordered by distance and then index
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Neighbor {
+    squared_distance: f64,
+    index: usize,
+}
+
+impl Eq for Neighbor {}
+
+impl PartialOrd for Neighbor {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Neighbor {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.squared_distance
+            .total_cmp(&other.squared_distance)
+            .then(self.index.cmp(&other.index))
+    }
+}
+
+pub(crate) fn squared_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
+fn check_finite(argument: &'static str, point: &[f64]) -> AlgorithmResult<()> {
+    if point.iter().all(|x| x.is_finite()) {
+        Ok(())
+    } else {
+        Err(AlgorithmError::InvalidParameter {
+            argument,
+            reason: "the coordinates must be finite".to_string(),
+        })
+    }
+}
+
+impl KdTree {
+    /// Builds a balanced tree of the rows of `points`, splitting every subtree at the median of
+    /// its points on its axis, in O(n log(n)).
+    pub fn new(points: Vec<Vec<f64>>) -> AlgorithmResult<Self> {
+        let dimensions = check_rows("points", &points)?;
+        let mut tree = KdTree {
+            dimensions,
+            nodes: Vec::with_capacity(points.len()),
+            points,
+            root: None,
+        };
+        let mut indices: Vec<usize> = (0..tree.points.len()).collect();
+        tree.root = tree.build(&mut indices, 0);
+        Ok(tree)
+    }
+
+    /// Builds the subtree of some points, whose depth is logarithmic in their number.
+    fn build(&mut self, indices: &mut [usize], axis: usize) -> Option<usize> {
+        if indices.is_empty() {
+            return None;
+        }
+        let middle = indices.len() / 2;
+        let points = &self.points;
+        indices
+            .select_nth_unstable_by(middle, |&a, &b| points[a][axis].total_cmp(&points[b][axis]));
+        let node = self.nodes.len();
+        self.nodes.push(KdNode {
+            point: indices[middle],
+            axis,
+            left: None,
+            right: None,
+        });
+        let (before, after) = indices.split_at_mut(middle);
+        let next_axis = (axis + 1) % self.dimensions;
+        self.nodes[node].left = self.build(before, next_axis);
+        self.nodes[node].right = self.build(&mut after[1..], next_axis);
+        Some(node)
+    }
+
+    /// Returns the number of dimensions of the points.
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    /// Returns the number of points in the tree.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Returns the point of the given index.
+    pub fn point(&self, index: usize) -> Option<&[f64]> {
+        self.points.get(index).map(Vec::as_slice)
+    }
+
+    fn check_query(&self, query: &[f64]) -> AlgorithmResult<()> {
+        if query.len() != self.dimensions {
+            return Err(AlgorithmError::DimensionMismatch {
+                argument: "query",
+                expected: self.dimensions,
+                found: query.len(),
+            });
+        }
+        check_finite("query", query)
+    }
+
+    /// Adds a point below the leaf where it belongs, and returns its index.
+    ///
+    /// The tree is not rebalanced, so that inserting many points in a bad order, like sorted
+    /// along the first axis, makes it deep and its queries slower, but not wrong.
+    pub fn insert(&mut self, point: Vec<f64>) -> AlgorithmResult<usize> {
+        if point.len() != self.dimensions {
+            return Err(AlgorithmError::DimensionMismatch {
+                argument: "point",
+                expected: self.dimensions,
+                found: point.len(),
+            });
+        }
+        check_finite("point", &point)?;
+        let index = self.points.len();
+        let mut axis = 0;
+        let mut parent = None;
+        let mut current = self.root;
+        while let Some(node_index) = current {
+            let node = &self.nodes[node_index];
+            let goes_left = point[node.axis] < self.points[node.point][node.axis];
+            parent = Some((node_index, goes_left));
+            axis = (node.axis + 1) % self.dimensions;
+            current = if goes_left { node.left } else { node.right };
+        }
+        let node = self.nodes.len();
+        self.nodes.push(KdNode {
+            point: index,
+            axis,
+            left: None,
+            right: None,
+        });
+        match parent {
+            None => self.root = Some(node),
+            Some((parent, true)) => self.nodes[parent].left = Some(node),
+            Some((parent, false)) => self.nodes[parent].right = Some(node),
+        }
+        self.points.push(point);
+        Ok(index)
+    }
+
+    /// Visits the points which may be closer to the query than a bound, given as a squared
+    /// distance and updated by `visit` after every point, with an explicit stack since the
+    /// depth of a tree grown by `insert` is not bounded.
+    fn search(&self, query: &[f64], mut bound: f64, mut visit: impl FnMut(Neighbor) -> f64) {
+        // the nodes to visit, with a lower bound on the squared distance to their subtrees
+        let mut stack = vec![];
+        if let Some(root) = self.root {
+            stack.push((root, 0.0));
+        }
+        while let Some((node, gap)) = stack.pop() {
+            // a subtree exactly at the bound may still have a point winning a tie
+            if gap > bound {
+                continue;
+            }
+            let node = &self.nodes[node];
+            let point = &self.points[node.point];
+            bound = visit(Neighbor {
+                squared_distance: squared_distance(query, point),
+                index: node.point,
+            });
+            let difference = query[node.axis] - point[node.axis];
+            let (near, far) = if difference < 0.0 {
+                (node.left, node.right)
+            } else {
+                (node.right, node.left)
+            };
+            // the far side is beyond the splitting plane, and popped after the near side
+            if let Some(far) = far {
+                stack.push((far, f64::max(gap, difference * difference)));
+            }
+            if let Some(near) = near {
+                stack.push((near, gap));
+            }
+        }
+    }
+
+    /// Returns the index of the point closest to `query`, and its distance.
+    pub fn nearest(&self, query: &[f64]) -> AlgorithmResult<(usize, f64)> {
+        Ok(self.k_nearest(query, 1)?[0])
+    }
+
+    /// Returns the `k` points closest to `query`, or all of them if there are fewer, from the
+    /// closest.
+    pub fn k_nearest(&self, query: &[f64], k: usize) -> AlgorithmResult<Vec<(usize, f64)>> {
+        self.check_query(query)?;
+        if k == 0 {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "k",
+                reason: "at least one neighbor must be queried".to_string(),
+            });
+        }
+        // the k best points so far, the worst on top
+        let mut best = BinaryHeap::with_capacity(k + 1);
+        self.search(query, f64::INFINITY, |neighbor| {
+            best.push(neighbor);
+            if best.len() > k {
+                best.pop();
+            }
+            if best.len() == k {
+                best.peek().unwrap().squared_distance
+            } else {
+                f64::INFINITY
+            }
+        });
+        Ok(sorted(best.into_vec()))
+    }
+
+    /// Returns the points within `radius` of `query`, boundary included, from the closest.
+    pub fn within_radius(&self, query: &[f64], radius: f64) -> AlgorithmResult<Vec<(usize, f64)>> {
+        self.check_query(query)?;
+        if radius.is_nan() || radius < 0.0 {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "radius",
+                reason: format!("must be non-negative, got {radius}"),
+            });
+        }
+        let squared_radius = radius * radius;
+        let mut found = vec![];
+        self.search(query, squared_radius, |neighbor| {
+            if neighbor.squared_distance <= squared_radius {
+                found.push(neighbor);
+            }
+            squared_radius
+        });
+        Ok(sorted(found))
+    }
+}
+
+/// Sorts the neighbors found by a query, and returns their indices and distances.
+fn sorted(mut neighbors: Vec<Neighbor>) -> Vec<(usize, f64)> {
+    neighbors.sort_unstable();
+    neighbors
+        .into_iter()
+        .map(|neighbor| (neighbor.index, neighbor.squared_distance.sqrt()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use std::time::Instant;
+
+    /// The results of a linear scan, sorted like those of the tree
+    fn brute_force(points: &[Vec<f64>], query: &[f64]) -> Vec<(usize, f64)> {
+        let neighbors = points
+            .iter()
+            .enumerate()
+            .map(|(index, point)| Neighbor {
+                squared_distance: squared_distance(query, point),
+                index,
+            })
+            .collect();
+        sorted(neighbors)
+    }
+
+    fn random_points(rng: &mut ChaCha8Rng, count: usize, dimensions: usize) -> Vec<Vec<f64>> {
+        (0..count)
+            .map(|_| {
+                (0..dimensions)
+                    .map(|_| rng.gen_range(-10.0..10.0))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn assert_same(found: &[(usize, f64)], expected: &[(usize, f64)]) {
+        assert_eq!(found.len(), expected.len());
+        for (&(i, d), &(j, e)) in found.iter().zip(expected) {
+            assert_eq!(i, j);
+            assert!((d - e).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn agrees_with_brute_force() {
+        let mut rng = ChaCha8Rng::seed_from_u64(121);
+        for dimensions in 2..=8 {
+            let points = random_points(&mut rng, 500, dimensions);
+            let tree = KdTree::new(points.clone()).unwrap();
+            for query in random_points(&mut rng, 300, dimensions) {
+                let expected = brute_force(&points, &query);
+                let nearest = tree.nearest(&query).unwrap();
+                assert_same(&[nearest], &expected[..1]);
+                let k = rng.gen_range(1..20);
+                assert_same(&tree.k_nearest(&query, k).unwrap(), &expected[..k]);
+                let radius = rng.gen_range(0.0..5.0 + dimensions as f64);
+                let inside: Vec<_> = expected
+                    .iter()
+                    .copied()
+                    .take_while(|&(_, d)| d * d <= radius * radius)
+                    .collect();
+                assert_same(&tree.within_radius(&query, radius).unwrap(), &inside);
+            }
+        }
+    }
+
+    #[test]
+    fn duplicates_and_ties() {
+        // a grid with every point twice, so that most distances are tied
+        let mut points = vec![];
+        for x in 0..10 {
+            for y in 0..10 {
+                points.push(vec![x as f64, y as f64]);
+                points.push(vec![x as f64, y as f64]);
+            }
+        }
+        let tree = KdTree::new(points.clone()).unwrap();
+        let mut rng = ChaCha8Rng::seed_from_u64(1210);
+        for _ in 0..500 {
+            // queries on the grid, and halfway between its points
+            let query = [
+                rng.gen_range(0..20) as f64 / 2.0,
+                rng.gen_range(0..20) as f64 / 2.0,
+            ];
+            let expected = brute_force(&points, &query);
+            for k in [1, 2, 3, 7, 200, 300] {
+                assert_same(&tree.k_nearest(&query, k).unwrap(), &expected[..k.min(200)]);
+            }
+            let inside: Vec<_> = expected
+                .iter()
+                .copied()
+                .filter(|&(_, d)| d <= 1.0)
+                .collect();
+            assert_same(&tree.within_radius(&query, 1.0).unwrap(), &inside);
+        }
+        // the first of two equal points wins
+        let (index, distance) = tree.nearest(&[3.0, 4.0]).unwrap();
+        assert_eq!((index, distance), (2 * (3 * 10 + 4), 0.0));
+        assert_eq!(tree.within_radius(&[3.0, 4.0], 0.0).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn insertions() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1211);
+        let mut points = random_points(&mut rng, 1, 3);
+        let mut tree = KdTree::new(points.clone()).unwrap();
+        // sorted insertions make the tree a path, deeper than recursion could go safely
+        for i in 0..2000 {
+            let point = vec![i as f64, rng.gen_range(-1.0..1.0), 0.5];
+            assert_eq!(tree.insert(point.clone()), Ok(points.len()));
+            points.push(point);
+        }
+        points.extend(random_points(&mut rng, 500, 3));
+        for point in &points[2001..] {
+            tree.insert(point.clone()).unwrap();
+        }
+        assert_eq!(tree.len(), points.len());
+        for query in random_points(&mut rng, 200, 3) {
+            let expected = brute_force(&points, &query);
+            assert_same(&tree.k_nearest(&query, 5).unwrap(), &expected[..5]);
+        }
+        assert_eq!(tree.point(1), Some(&points[1][..]));
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert_eq!(
+            KdTree::new(vec![]).err(),
+            Some(AlgorithmError::EmptyInput { argument: "points" })
+        );
+        assert!(KdTree::new(vec![vec![1.0, f64::NAN]]).is_err());
+        let mut tree = KdTree::new(vec![vec![0.0, 0.0], vec![1.0, 1.0]]).unwrap();
+        assert_eq!(
+            tree.nearest(&[1.0]),
+            Err(AlgorithmError::DimensionMismatch {
+                argument: "query",
+                expected: 2,
+                found: 1
+            })
+        );
+        assert!(tree.insert(vec![1.0, 2.0, 3.0]).is_err());
+        assert!(tree.k_nearest(&[0.0, 0.0], 0).is_err());
+        assert!(tree.within_radius(&[0.0, 0.0], -1.0).is_err());
+        assert!(tree.within_radius(&[0.0, 0.0], f64::NAN).is_err());
+        assert_eq!(tree.k_nearest(&[0.0, 0.0], 5).unwrap().len(), 2);
+    }
+
+    #[test]
+    #[ignore = "slow test for measuring time"]
+    fn k_nearest_perf() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1212);
+        let points = random_points(&mut rng, 100_000, 3);
+        let queries = random_points(&mut rng, 50, 3);
+        let tree = KdTree::new(points.clone()).unwrap();
+        let now = Instant::now();
+        let found: Vec<_> = queries
+            .iter()
+            .map(|query| tree.k_nearest(query, 10).unwrap())
+            .collect();
+        let tree_time = now.elapsed();
+        let now = Instant::now();
+        // a linear scan keeping the 10 best points in a heap, without sorting all of them
+        let expected: Vec<_> = queries
+            .iter()
+            .map(|query| {
+                let mut best = BinaryHeap::new();
+                for (index, point) in points.iter().enumerate() {
+                    best.push(Neighbor {
+                        squared_distance: squared_distance(query, point),
+                        index,
+                    });
+                    if best.len() > 10 {
+                        best.pop();
+                    }
+                }
+                sorted(best.into_vec())
+            })
+            .collect();
+        let scan_time = now.elapsed();
+        assert_eq!(found, expected);
+        println!("k-d tree {tree_time:?}, linear scan {scan_time:?}");
+        assert!(tree_time * 10 < scan_time);
+    }
+}
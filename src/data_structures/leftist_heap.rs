@@ -0,0 +1,239 @@
+use std::collections::VecDeque;
+use std::iter::FromIterator;
+use std::mem;
+
+type Link<T> = Option<Box<LeftistNode<T>>>;
+
+struct LeftistNode<T> {
+    value: T,
+    /// The length of the right spine of the subtree of this node, its null-path length
+    rank: usize,
+    left: Link<T>,
+    right: Link<T>,
+}
+
+fn rank<T>(link: &Link<T>) -> usize {
+    link.as_ref().map_or(0, |node| node.rank)
+}
+
+/// Merges two heaps along their right spines, in O(log(n)).
+///
+/// The smaller root becomes the root, and the other heap is merged into its right subtree,
+/// after which the subtrees swap if the right one got a higher rank than the left one.
+fn merge<T: Ord>(a: Link<T>, b: Link<T>) -> Link<T> {
+    match (a, b) {
+        (None, link) | (link, None) => link,
+        (Some(mut a), Some(mut b)) => {
+            if b.value < a.value {
+                mem::swap(&mut a, &mut b);
+            }
+            a.right = merge(a.right.take(), Some(b));
+            if rank(&a.left) < rank(&a.right) {
+                mem::swap(&mut a.left, &mut a.right);
+            }
+            a.rank = rank(&a.right) + 1;
+            Some(a)
+        }
+    }
+}
+
+/// A min-heap based on a leftist tree, which merges two heaps in O(log(n)).
+/// <https://en.wikipedia.org/wiki/Leftist_tree>
+///
+/// Every node is at most its children, and the rank of its left child is at least that of its
+/// right child, the rank being the length of the right spine. The right spine of a heap of `n`
+/// nodes thus has at most `log2(n + 1)` nodes, and every operation is a merge along the right
+/// spines: a push merges a single node, and a pop merges the subtrees of the root. The left
+/// spine can be as long as the heap.
+pub struct LeftistHeap<T: Ord> {
+    root: Link<T>,
+    len: usize,
+}
+
+impl<T: Ord> LeftistHeap<T> {
+    pub fn new() -> Self {
+        LeftistHeap { root: None, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push(&mut self, value: T) {
+        let node = Box::new(LeftistNode {
+            value,
+            rank: 1,
+            left: None,
+            right: None,
+        });
+        self.root = merge(self.root.take(), Some(node));
+        self.len += 1;
+    }
+
+    pub fn peek_min(&self) -> Option<&T> {
+        self.root.as_ref().map(|node| &node.value)
+    }
+
+    pub fn pop_min(&mut self) -> Option<T> {
+        let mut root = self.root.take()?;
+        self.root = merge(root.left.take(), root.right.take());
+        self.len -= 1;
+        Some(root.value)
+    }
+
+    /// Moves all the values of `other` into this heap.
+    pub fn merge(&mut self, other: LeftistHeap<T>) {
+        let mut other = other;
+        self.root = merge(self.root.take(), other.root.take());
+        self.len += other.len;
+    }
+}
+
+impl<T: Ord> Default for LeftistHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> FromIterator<T> for LeftistHeap<T> {
+    /// Builds a heap in O(n), by merging the heaps of single values two by two, in rounds
+    /// of heaps of doubling sizes.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut heaps: VecDeque<Link<T>> = iter
+            .into_iter()
+            .map(|value| {
+                Some(Box::new(LeftistNode {
+                    value,
+                    rank: 1,
+                    left: None,
+                    right: None,
+                }))
+            })
+            .collect();
+        let len = heaps.len();
+        while heaps.len() > 1 {
+            let a = heaps.pop_front().unwrap();
+            let b = heaps.pop_front().unwrap();
+            heaps.push_back(merge(a, b));
+        }
+        LeftistHeap {
+            root: heaps.pop_front().flatten(),
+            len,
+        }
+    }
+}
+
+impl<T: Ord> Drop for LeftistHeap<T> {
+    /// Drops the nodes one by one, since the left spine is too long to drop recursively.
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<LeftistNode<T>>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    /// Checks the heap order and the ranks below the node, and returns the number of nodes.
+    fn check_node<T: Ord>(link: &Link<T>) -> usize {
+        match link {
+            None => 0,
+            Some(node) => {
+                for child in [&node.left, &node.right].into_iter().flatten() {
+                    assert!(node.value <= child.value);
+                }
+                assert!(rank(&node.left) >= rank(&node.right));
+                assert_eq!(node.rank, rank(&node.right) + 1);
+                1 + check_node(&node.left) + check_node(&node.right)
+            }
+        }
+    }
+
+    fn check<T: Ord>(heap: &LeftistHeap<T>) {
+        assert_eq!(check_node(&heap.root), heap.len());
+        let spine = rank(&heap.root);
+        assert!(spine as f64 <= ((heap.len() + 1) as f64).log2());
+    }
+
+    #[test]
+    fn push_and_pop() {
+        let mut heap = LeftistHeap::new();
+        assert_eq!(heap.peek_min(), None);
+        assert_eq!(heap.pop_min(), None);
+        for value in [5, 3, 8, 1, 9, 1] {
+            heap.push(value);
+        }
+        check(&heap);
+        assert_eq!(heap.len(), 6);
+        assert_eq!(heap.peek_min(), Some(&1));
+        let popped: Vec<i32> = std::iter::from_fn(|| heap.pop_min()).collect();
+        assert_eq!(popped, [1, 1, 3, 5, 8, 9]);
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn random_values_come_out_sorted() {
+        let mut rng = ChaCha8Rng::seed_from_u64(128);
+        let values: Vec<i32> = (0..1000).map(|_| rng.gen_range(-500..500)).collect();
+        let mut heap = LeftistHeap::new();
+        for &value in &values {
+            heap.push(value);
+        }
+        check(&heap);
+        let mut sorted = values.clone();
+        sorted.sort();
+        let mut popped = vec![];
+        while let Some(value) = heap.pop_min() {
+            popped.push(value);
+            if popped.len() % 100 == 0 {
+                check(&heap);
+            }
+        }
+        assert_eq!(popped, sorted);
+
+        let mut heap: LeftistHeap<i32> = values.iter().copied().collect();
+        check(&heap);
+        let popped: Vec<i32> = std::iter::from_fn(|| heap.pop_min()).collect();
+        assert_eq!(popped, sorted);
+    }
+
+    #[test]
+    fn merges() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1280);
+        for _ in 0..50 {
+            let a: Vec<u32> = (0..rng.gen_range(0..200)).map(|_| rng.gen()).collect();
+            let b: Vec<u32> = (0..rng.gen_range(0..200)).map(|_| rng.gen()).collect();
+            let mut heap: LeftistHeap<u32> = a.iter().copied().collect();
+            heap.merge(b.iter().copied().collect());
+            check(&heap);
+            let mut expected: Vec<u32> = a.into_iter().chain(b).collect();
+            expected.sort();
+            assert_eq!(heap.peek_min(), expected.first());
+            assert_eq!(heap.len(), expected.len());
+            let popped: Vec<u32> = std::iter::from_fn(|| heap.pop_min()).collect();
+            assert_eq!(popped, expected);
+        }
+    }
+
+    #[test]
+    fn long_left_spine() {
+        // every new minimum becomes the root, with the heap so far as its left subtree
+        let mut heap = LeftistHeap::new();
+        for value in (0..200_000).rev() {
+            heap.push(value);
+        }
+        assert_eq!(rank(&heap.root), 1);
+        assert_eq!(heap.pop_min(), Some(0));
+        assert_eq!(heap.peek_min(), Some(&1));
+    }
+}
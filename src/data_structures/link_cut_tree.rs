@@ -0,0 +1,387 @@
+/// The index of no node, for the missing children and parents
+const NONE: usize = usize::MAX;
+
+struct Node {
+    /// The left child holds the nodes above on the preferred path, the right one those below
+    children: [usize; 2],
+    /// The parent in the splay tree, or the node above the top of the preferred path for the
+    /// root of a splay tree
+    parent: usize,
+    /// Whether the subtree of this node is to be reversed, which is applied to its children
+    /// the next time they are reached
+    reversed: bool,
+    value: i64,
+    /// The sum of the values in the splay subtree of this node
+    sum: i64,
+}
+
+/// A forest of rooted trees under links and cuts, with sums of values along paths
+/// <https://en.wikipedia.org/wiki/Link/cut_tree>
+///
+/// The trees are split into preferred paths, each one kept in a splay tree ordered by depth.
+/// `access` makes the path from the root of a tree to a node preferred, in O(log(n)) amortized
+/// splay steps, after which that splay tree holds the whole path with its sum at its root.
+/// Rerooting a tree at a node accesses it and reverses its path, with a flag pushed down
+/// lazily like in `ImplicitTreap`. Every operation thus takes O(log(n)) amortized.
+pub struct LinkCutTree {
+    nodes: Vec<Node>,
+}
+
+impl LinkCutTree {
+    /// Creates a forest of single nodes with the given values.
+    pub fn new(values: &[i64]) -> Self {
+        LinkCutTree {
+            nodes: values
+                .iter()
+                .map(|&value| Node {
+                    children: [NONE; 2],
+                    parent: NONE,
+                    reversed: false,
+                    value,
+                    sum: value,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    fn check(&self, u: usize) {
+        assert!(
+            u < self.nodes.len(),
+            "node {u} out of bounds for {} nodes",
+            self.nodes.len()
+        );
+    }
+
+    fn sum(&self, u: usize) -> i64 {
+        if u == NONE {
+            0
+        } else {
+            self.nodes[u].sum
+        }
+    }
+
+    fn pull(&mut self, u: usize) {
+        let [left, right] = self.nodes[u].children;
+        self.nodes[u].sum = self.nodes[u].value + self.sum(left) + self.sum(right);
+    }
+
+    /// Applies a pending reversal to the children.
+    fn push(&mut self, u: usize) {
+        if self.nodes[u].reversed {
+            self.nodes[u].children.swap(0, 1);
+            for child in self.nodes[u].children {
+                if child != NONE {
+                    self.nodes[child].reversed ^= true;
+                }
+            }
+            self.nodes[u].reversed = false;
+        }
+    }
+
+    /// Whether `u` is the root of its splay tree.
+    fn is_splay_root(&self, u: usize) -> bool {
+        let parent = self.nodes[u].parent;
+        parent == NONE || !self.nodes[parent].children.contains(&u)
+    }
+
+    /// Rotates `u` above its parent.
+    fn rotate(&mut self, u: usize) {
+        let parent = self.nodes[u].parent;
+        let grandparent = self.nodes[parent].parent;
+        let side = usize::from(self.nodes[parent].children[1] == u);
+        if !self.is_splay_root(parent) {
+            let parent_side = usize::from(self.nodes[grandparent].children[1] == parent);
+            self.nodes[grandparent].children[parent_side] = u;
+        }
+        self.nodes[u].parent = grandparent;
+        let moved = self.nodes[u].children[1 - side];
+        self.nodes[parent].children[side] = moved;
+        if moved != NONE {
+            self.nodes[moved].parent = parent;
+        }
+        self.nodes[u].children[1 - side] = parent;
+        self.nodes[parent].parent = u;
+        self.pull(parent);
+        self.pull(u);
+    }
+
+    /// Moves `u` to the root of its splay tree.
+    fn splay(&mut self, u: usize) {
+        // the pending reversals above u are pushed first, from the root down
+        let mut path = vec![u];
+        while !self.is_splay_root(*path.last().unwrap()) {
+            path.push(self.nodes[*path.last().unwrap()].parent);
+        }
+        for &v in path.iter().rev() {
+            self.push(v);
+        }
+        while !self.is_splay_root(u) {
+            let parent = self.nodes[u].parent;
+            if !self.is_splay_root(parent) {
+                let grandparent = self.nodes[parent].parent;
+                let zig_zig = (self.nodes[parent].children[0] == u)
+                    == (self.nodes[grandparent].children[0] == parent);
+                self.rotate(if zig_zig { parent } else { u });
+            }
+            self.rotate(u);
+        }
+    }
+
+    /// Makes the path from the root of the tree of `u` to `u` preferred, with `u` at the root
+    /// of its splay tree and nothing below it on the path.
+    fn access(&mut self, u: usize) {
+        let mut below = NONE;
+        let mut v = u;
+        while v != NONE {
+            self.splay(v);
+            self.nodes[v].children[1] = below;
+            self.pull(v);
+            below = v;
+            v = self.nodes[v].parent;
+        }
+        self.splay(u);
+    }
+
+    /// Makes `u` the root of its tree.
+    fn make_root(&mut self, u: usize) {
+        self.access(u);
+        self.nodes[u].reversed ^= true;
+        self.push(u);
+    }
+
+    /// Returns the root of the tree of `u`.
+    pub fn find_root(&mut self, u: usize) -> usize {
+        self.check(u);
+        self.access(u);
+        let mut root = u;
+        loop {
+            self.push(root);
+            match self.nodes[root].children[0] {
+                NONE => break,
+                left => root = left,
+            }
+        }
+        self.splay(root);
+        root
+    }
+
+    /// Returns whether `u` and `v` are in the same tree.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a node is out of bounds.
+    pub fn connected(&mut self, u: usize, v: usize) -> bool {
+        self.check(v);
+        self.find_root(u) == self.find_root(v)
+    }
+
+    /// Makes `u` a child of `v`, rerooting the tree of `u` at `u`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a node is out of bounds, or if `u` and `v` are already in the same tree.
+    pub fn link(&mut self, u: usize, v: usize) {
+        assert!(
+            !self.connected(u, v),
+            "{u} and {v} are already in the same tree"
+        );
+        self.make_root(u);
+        self.nodes[u].parent = v;
+    }
+
+    /// Removes the edge between `u` and `v`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a node is out of bounds, or if there is no edge between `u` and `v`.
+    pub fn cut(&mut self, u: usize, v: usize) {
+        self.check(u);
+        self.check(v);
+        self.make_root(u);
+        self.access(v);
+        // with u as the root, the edge is there when the path to v is u and v alone
+        let left = self.nodes[v].children[0];
+        assert!(
+            left == u && self.nodes[u].children[1] == NONE,
+            "there is no edge between {u} and {v}"
+        );
+        self.nodes[v].children[0] = NONE;
+        self.nodes[u].parent = NONE;
+        self.pull(v);
+    }
+
+    /// Returns the sum of the values on the path from `u` to `v`, both included.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a node is out of bounds, or if `u` and `v` are in different trees.
+    pub fn path_sum(&mut self, u: usize, v: usize) -> i64 {
+        assert!(self.connected(u, v), "{u} and {v} are in different trees");
+        self.make_root(u);
+        self.access(v);
+        self.nodes[v].sum
+    }
+
+    /// Changes the value of `u`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `u` is out of bounds.
+    pub fn set_value(&mut self, u: usize, value: i64) {
+        self.check(u);
+        self.access(u);
+        self.nodes[u].value = value;
+        self.pull(u);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use std::collections::{BTreeSet, VecDeque};
+
+    /// The nodes of the path from `u` to `v` in a forest, by a breadth-first search from `u`
+    fn bfs_path(adjacency: &[BTreeSet<usize>], u: usize, v: usize) -> Option<Vec<usize>> {
+        let mut previous = vec![None; adjacency.len()];
+        previous[u] = Some(u);
+        let mut queue = VecDeque::from([u]);
+        while let Some(w) = queue.pop_front() {
+            for &x in &adjacency[w] {
+                if previous[x].is_none() {
+                    previous[x] = Some(w);
+                    queue.push_back(x);
+                }
+            }
+        }
+        previous[v]?;
+        let mut path = vec![v];
+        while *path.last().unwrap() != u {
+            path.push(previous[*path.last().unwrap()].unwrap());
+        }
+        Some(path)
+    }
+
+    #[test]
+    fn small_tree() {
+        //     0
+        //    / \
+        //   1   2
+        //  / \
+        // 3   4
+        let mut tree = LinkCutTree::new(&[1, 2, 3, 4, 5]);
+        tree.link(1, 0);
+        tree.link(2, 0);
+        tree.link(3, 1);
+        tree.link(4, 1);
+        assert_eq!(tree.path_sum(3, 2), 4 + 2 + 1 + 3);
+        assert_eq!(tree.path_sum(3, 4), 4 + 2 + 5);
+        assert_eq!(tree.path_sum(0, 0), 1);
+        tree.set_value(1, 20);
+        assert_eq!(tree.path_sum(4, 2), 5 + 20 + 1 + 3);
+        assert!(tree.connected(3, 2));
+        tree.cut(1, 0);
+        assert!(!tree.connected(3, 2));
+        assert!(tree.connected(3, 4));
+        assert_eq!(tree.find_root(2), 0);
+        assert_eq!(tree.len(), 5);
+    }
+
+    #[test]
+    fn path_sums_match_bfs_on_a_static_tree() {
+        let mut rng = ChaCha8Rng::seed_from_u64(138);
+        let n = 300;
+        let values: Vec<i64> = (0..n).map(|_| rng.gen_range(-100..100)).collect();
+        let mut tree = LinkCutTree::new(&values);
+        let mut adjacency = vec![BTreeSet::new(); n];
+        for u in 1..n {
+            let parent = rng.gen_range(0..u);
+            tree.link(u, parent);
+            adjacency[u].insert(parent);
+            adjacency[parent].insert(u);
+        }
+        for _ in 0..2000 {
+            let (u, v) = (rng.gen_range(0..n), rng.gen_range(0..n));
+            let path = bfs_path(&adjacency, u, v).unwrap();
+            let expected: i64 = path.iter().map(|&w| values[w]).sum();
+            assert_eq!(tree.path_sum(u, v), expected);
+        }
+    }
+
+    #[test]
+    fn links_and_cuts_keep_connectivity() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1380);
+        let n = 60;
+        let mut values: Vec<i64> = (0..n).map(|_| rng.gen_range(0..1000)).collect();
+        let mut tree = LinkCutTree::new(&values);
+        let mut adjacency = vec![BTreeSet::new(); n];
+        let mut edges: Vec<(usize, usize)> = vec![];
+        for _ in 0..5000 {
+            let (u, v) = (rng.gen_range(0..n), rng.gen_range(0..n));
+            let path = bfs_path(&adjacency, u, v);
+            match rng.gen_range(0..4) {
+                0 if path.is_none() => {
+                    tree.link(u, v);
+                    adjacency[u].insert(v);
+                    adjacency[v].insert(u);
+                    edges.push((u, v));
+                }
+                1 if !edges.is_empty() => {
+                    let (a, b) = edges.swap_remove(rng.gen_range(0..edges.len()));
+                    // either way round
+                    if rng.gen() {
+                        tree.cut(a, b);
+                    } else {
+                        tree.cut(b, a);
+                    }
+                    adjacency[a].remove(&b);
+                    adjacency[b].remove(&a);
+                }
+                2 => {
+                    values[u] = rng.gen_range(0..1000);
+                    tree.set_value(u, values[u]);
+                }
+                _ => {
+                    assert_eq!(tree.connected(u, v), path.is_some());
+                    if let Some(path) = path {
+                        let expected: i64 = path.iter().map(|&w| values[w]).sum();
+                        assert_eq!(tree.path_sum(u, v), expected);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "0 and 2 are already in the same tree")]
+    fn link_in_the_same_tree() {
+        let mut tree = LinkCutTree::new(&[0; 3]);
+        tree.link(0, 1);
+        tree.link(1, 2);
+        tree.link(0, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "there is no edge between 0 and 2")]
+    fn cut_a_missing_edge() {
+        let mut tree = LinkCutTree::new(&[0; 3]);
+        tree.link(0, 1);
+        tree.link(1, 2);
+        tree.cut(0, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "0 and 1 are in different trees")]
+    fn path_between_trees() {
+        LinkCutTree::new(&[0; 2]).path_sum(0, 1);
+    }
+}
@@ -1,47 +1,79 @@
 mod avl_tree;
 mod b_tree;
 mod binary_search_tree;
+mod cartesian_tree;
 mod fenwick_tree;
 mod floyds_algorithm;
+mod fractional_cascade;
 mod graph;
 mod hash_table;
 mod heap;
+mod implicit_treap;
 mod infix_to_postfix;
+mod interval_set;
+mod interval_tree;
+mod kd_tree;
 mod lazy_segment_tree;
+mod leftist_heap;
+mod link_cut_tree;
 mod linked_list;
+mod patricia_trie;
+pub mod persistent;
+mod persistent_segment_tree;
 mod postfix_evaluation;
 mod probabilistic;
 mod queue;
+mod range_tree;
 mod rb_tree;
 mod segment_tree;
 mod segment_tree_recursive;
+mod sparse_table;
 mod stack_using_singly_linked_list;
+mod streaming_median;
 mod treap;
 mod trie;
 mod union_find;
 mod veb_tree;
+mod wavelet_tree;
 
 pub use self::avl_tree::AVLTree;
 pub use self::b_tree::BTree;
 pub use self::binary_search_tree::BinarySearchTree;
+pub use self::cartesian_tree::build_cartesian_tree;
 pub use self::fenwick_tree::FenwickTree;
 pub use self::floyds_algorithm::{detect_cycle, has_cycle};
+pub use self::fractional_cascade::FractionalCascade;
 pub use self::graph::DirectedGraph;
 pub use self::graph::UndirectedGraph;
+pub use self::graph::{DotParseError, Graph};
 pub use self::hash_table::HashTable;
 pub use self::heap::Heap;
+pub use self::implicit_treap::ImplicitTreap;
 pub use self::infix_to_postfix::infix_to_postfix;
+pub use self::interval_set::DisjointIntervalSet;
+pub use self::interval_tree::IntervalTree;
+pub(crate) use self::kd_tree::squared_distance;
+pub use self::kd_tree::KdTree;
 pub use self::lazy_segment_tree::LazySegmentTree;
+pub use self::leftist_heap::LeftistHeap;
+pub use self::link_cut_tree::LinkCutTree;
 pub use self::linked_list::LinkedList;
+pub use self::patricia_trie::PatriciaTrie;
+pub use self::persistent::{ArcPointer, PersistentList, PersistentStack, PointerFamily, RcPointer};
+pub use self::persistent_segment_tree::PersistentSegmentTree;
 pub use self::postfix_evaluation::evaluate_postfix;
 pub use self::probabilistic::bloom_filter;
 pub use self::probabilistic::count_min_sketch;
-pub use self::queue::Queue;
+pub use self::queue::{ArrayDeque, Queue, TwoStackQueue};
+pub use self::range_tree::{KdRangeTree, RangeTree2D};
 pub use self::rb_tree::RBTree;
 pub use self::segment_tree::SegmentTree;
 pub use self::segment_tree_recursive::SegmentTree as SegmentTreeRecursive;
+pub use self::sparse_table::SparseTable;
 pub use self::stack_using_singly_linked_list::Stack;
-pub use self::treap::Treap;
+pub use self::streaming_median::StreamingMedian;
+pub use self::treap::{OrderStatisticTree, Treap};
 pub use self::trie::Trie;
 pub use self::union_find::UnionFind;
 pub use self::veb_tree::VebTree;
+pub use self::wavelet_tree::WaveletTree;
@@ -0,0 +1,419 @@
+/// The mask of the `bits` most significant bits of a key
+fn top_mask(bits: u32) -> u64 {
+    if bits == 0 {
+        0
+    } else {
+        u64::MAX << (64 - bits)
+    }
+}
+
+enum Node<V> {
+    Leaf {
+        key: u64,
+        value: V,
+    },
+    /// The keys below share their bits above `bit`, those of `prefix`, and the keys with the
+    /// bit `bit` unset are on the left.
+    Branch {
+        prefix: u64,
+        bit: u32,
+        left: Box<Node<V>>,
+        right: Box<Node<V>>,
+    },
+}
+
+impl<V> Node<V> {
+    /// The bits shared by all the keys below this node, and how many they are
+    fn shared(&self) -> (u64, u32) {
+        match self {
+            Node::Leaf { key, .. } => (*key, 64),
+            Node::Branch { prefix, bit, .. } => (*prefix, 63 - bit),
+        }
+    }
+
+    fn count(&self) -> usize {
+        match self {
+            Node::Leaf { .. } => 1,
+            Node::Branch { left, right, .. } => 1 + left.count() + right.count(),
+        }
+    }
+
+    fn collect<'a>(&'a self, values: &mut Vec<&'a V>) {
+        match self {
+            Node::Leaf { value, .. } => values.push(value),
+            Node::Branch { left, right, .. } => {
+                left.collect(values);
+                right.collect(values);
+            }
+        }
+    }
+}
+
+/// Joins a leaf of `key` and the node `other`, whose keys differ from `key` above the bits they
+/// share, under a branch at their highest differing bit.
+fn join<V>(key: u64, value: V, other: Box<Node<V>>) -> Box<Node<V>> {
+    let (shared, _) = other.shared();
+    let bit = 63 - (key ^ shared).leading_zeros();
+    let leaf = Box::new(Node::Leaf { key, value });
+    let (left, right) = if key >> bit & 1 == 0 {
+        (leaf, other)
+    } else {
+        (other, leaf)
+    };
+    Box::new(Node::Branch {
+        prefix: key & top_mask(63 - bit),
+        bit,
+        left,
+        right,
+    })
+}
+
+/// Inserts the value below `node`, and returns the new node with the value it replaces. A key
+/// differing from the bits shared below `node` gets a new branch above it, and otherwise goes
+/// down to the side of its bit.
+fn insert<V>(node: Box<Node<V>>, key: u64, value: V) -> (Box<Node<V>>, Option<V>) {
+    let (shared, bits) = node.shared();
+    if (key ^ shared) & top_mask(bits) != 0 {
+        return (join(key, value, node), None);
+    }
+    match *node {
+        Node::Leaf { value: old, .. } => (Box::new(Node::Leaf { key, value }), Some(old)),
+        Node::Branch {
+            prefix,
+            bit,
+            mut left,
+            mut right,
+        } => {
+            let old;
+            if key >> bit & 1 == 0 {
+                (left, old) = insert(left, key, value);
+            } else {
+                (right, old) = insert(right, key, value);
+            }
+            let branch = Node::Branch {
+                prefix,
+                bit,
+                left,
+                right,
+            };
+            (Box::new(branch), old)
+        }
+    }
+}
+
+/// Removes the key below `node`, and returns what is left of the node with the removed value.
+fn remove<V>(node: Box<Node<V>>, key: u64) -> (Option<Box<Node<V>>>, Option<V>) {
+    let (shared, bits) = node.shared();
+    if (key ^ shared) & top_mask(bits) != 0 {
+        return (Some(node), None);
+    }
+    match *node {
+        Node::Leaf { value, .. } => (None, Some(value)),
+        Node::Branch {
+            prefix,
+            bit,
+            left,
+            right,
+        } => {
+            let (left, right, removed) = if key >> bit & 1 == 0 {
+                let (left, removed) = remove(left, key);
+                (left, Some(right), removed)
+            } else {
+                let (right, removed) = remove(right, key);
+                (Some(left), right, removed)
+            };
+            match (left, right) {
+                (Some(left), Some(right)) => {
+                    let branch = Node::Branch {
+                        prefix,
+                        bit,
+                        left,
+                        right,
+                    };
+                    (Some(Box::new(branch)), removed)
+                }
+                (child, None) | (None, child) => (child, removed),
+            }
+        }
+    }
+}
+
+/// A PATRICIA trie, or crit-bit tree, mapping `u64` keys to values by their bits, from the most
+/// significant one
+/// <https://en.wikipedia.org/wiki/Radix_tree#PATRICIA>
+///
+/// Every branch splits its keys at their highest differing bit, and skips over the bits they
+/// share, so that a trie of `n` keys has exactly `n` leaves and `n - 1` branches, and every
+/// operation follows at most 64 branches. The values come out in the order of their keys.
+pub struct PatriciaTrie<V> {
+    root: Option<Box<Node<V>>>,
+    len: usize,
+}
+
+impl<V> PatriciaTrie<V> {
+    pub fn new() -> Self {
+        PatriciaTrie { root: None, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts a value for `key`, and returns the value it replaces, if any.
+    pub fn insert(&mut self, key: u64, value: V) -> Option<V> {
+        let (root, old) = match self.root.take() {
+            None => (Box::new(Node::Leaf { key, value }), None),
+            Some(root) => insert(root, key, value),
+        };
+        self.root = Some(root);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    pub fn get(&self, key: u64) -> Option<&V> {
+        let mut node = self.root.as_ref()?;
+        loop {
+            match node.as_ref() {
+                Node::Leaf { key: found, value } => {
+                    return (*found == key).then_some(value);
+                }
+                Node::Branch {
+                    prefix,
+                    bit,
+                    left,
+                    right,
+                } => {
+                    if (key ^ prefix) & top_mask(63 - bit) != 0 {
+                        return None;
+                    }
+                    node = if key >> bit & 1 == 0 { left } else { right };
+                }
+            }
+        }
+    }
+
+    pub fn contains_key(&self, key: u64) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Removes the value of `key`, and returns it, if any. The branch above its leaf goes
+    /// away, replaced by the other child.
+    pub fn remove(&mut self, key: u64) -> Option<V> {
+        let root = self.root.take()?;
+        let (root, removed) = remove(root, key);
+        self.root = root;
+        if removed.is_some() {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    /// Returns the values whose keys have the same `bits` most significant bits as `key`, in
+    /// the order of their keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits` is more than 64.
+    pub fn prefix_search(&self, key: u64, bits: u32) -> Vec<&V> {
+        assert!(bits <= 64, "a key has 64 bits, got a prefix of {bits}");
+        let mask = top_mask(bits);
+        let mut values = vec![];
+        let mut node = match self.root.as_ref() {
+            Some(node) => node,
+            None => return values,
+        };
+        loop {
+            let (shared, known) = node.shared();
+            if (key ^ shared) & mask & top_mask(known) != 0 {
+                return values;
+            }
+            match node.as_ref() {
+                Node::Branch {
+                    bit, left, right, ..
+                } if known < bits => {
+                    node = if key >> bit & 1 == 0 { left } else { right };
+                }
+                // all the keys below share the prefix
+                _ => {
+                    node.collect(&mut values);
+                    return values;
+                }
+            }
+        }
+    }
+
+    /// The number of leaves and branches
+    fn nodes(&self) -> usize {
+        self.root.as_ref().map_or(0, |root| root.count())
+    }
+}
+
+impl<V> Default for PatriciaTrie<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use std::collections::{BTreeMap, HashMap};
+    use std::time::Instant;
+
+    #[test]
+    fn insert_get_remove() {
+        let mut trie = PatriciaTrie::new();
+        assert_eq!(trie.get(3), None);
+        assert_eq!(trie.remove(3), None);
+        assert_eq!(trie.insert(5, "five"), None);
+        assert_eq!(trie.insert(7, "seven"), None);
+        assert_eq!(trie.insert(u64::MAX, "max"), None);
+        assert_eq!(trie.insert(0, "zero"), None);
+        assert_eq!(trie.insert(5, "FIVE"), Some("five"));
+        assert_eq!(trie.len(), 4);
+        assert_eq!(trie.get(5), Some(&"FIVE"));
+        assert_eq!(trie.get(u64::MAX), Some(&"max"));
+        assert_eq!(trie.get(0), Some(&"zero"));
+        assert_eq!(trie.get(6), None);
+        assert!(trie.contains_key(7));
+        assert_eq!(trie.remove(7), Some("seven"));
+        assert_eq!(trie.remove(7), None);
+        assert_eq!(trie.get(5), Some(&"FIVE"));
+        assert_eq!(trie.remove(5), Some("FIVE"));
+        assert_eq!(trie.remove(0), Some("zero"));
+        assert_eq!(trie.remove(u64::MAX), Some("max"));
+        assert!(trie.is_empty());
+        assert_eq!(trie.nodes(), 0);
+    }
+
+    #[test]
+    fn no_wasted_nodes() {
+        let mut trie = PatriciaTrie::new();
+        // a single long key is a single leaf, without a node for every bit
+        trie.insert(0xdead_beef_0000_0001, ());
+        assert_eq!(trie.nodes(), 1);
+        // two keys sharing 63 bits, and then any number of keys, need one branch less than leaves
+        trie.insert(0xdead_beef_0000_0000, ());
+        assert_eq!(trie.nodes(), 3);
+        let mut rng = ChaCha8Rng::seed_from_u64(132);
+        let keys: Vec<u64> = (0..1000).map(|_| rng.gen()).collect();
+        for &key in &keys {
+            trie.insert(key, ());
+            assert_eq!(trie.nodes(), 2 * trie.len() - 1);
+        }
+        for &key in &keys {
+            trie.remove(key);
+            assert_eq!(trie.nodes(), 2 * trie.len() - 1);
+        }
+        assert_eq!(trie.len(), 2);
+    }
+
+    #[test]
+    fn agrees_with_btree_map() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1320);
+        let mut trie = PatriciaTrie::new();
+        let mut map = BTreeMap::new();
+        for _ in 0..5000 {
+            // few distinct keys, some of them sharing long prefixes
+            let key = rng.gen_range(0..300u64) << (rng.gen_range(0..3) * 30);
+            match rng.gen_range(0..3) {
+                0 => assert_eq!(trie.remove(key), map.remove(&key)),
+                1 => assert_eq!(trie.get(key), map.get(&key)),
+                _ => {
+                    let value = rng.gen::<u32>();
+                    assert_eq!(trie.insert(key, value), map.insert(key, value));
+                }
+            }
+            assert_eq!(trie.len(), map.len());
+        }
+        assert_eq!(trie.nodes(), 2 * trie.len() - 1);
+        let all: Vec<&u32> = map.values().collect();
+        assert_eq!(trie.prefix_search(12345, 0), all);
+    }
+
+    #[test]
+    fn prefixes() {
+        let mut trie = PatriciaTrie::new();
+        let keys = [
+            0b1010 << 60,
+            0b1011 << 60,
+            (0b1011 << 60) | 1,
+            0b0111 << 60,
+            3,
+        ];
+        for (i, &key) in keys.iter().enumerate() {
+            trie.insert(key, i);
+        }
+        assert_eq!(trie.prefix_search(0b1000 << 60, 1), [&0, &1, &2]);
+        assert_eq!(trie.prefix_search(0b1010 << 60, 3), [&0, &1, &2]);
+        assert_eq!(trie.prefix_search(0b1011 << 60, 4), [&1, &2]);
+        assert_eq!(trie.prefix_search(0b1011 << 60, 64), [&1]);
+        assert_eq!(trie.prefix_search(0b1100 << 60, 2), Vec::<&usize>::new());
+        assert_eq!(trie.prefix_search(0, 60), [&4]);
+        assert_eq!(trie.prefix_search(0, 1), [&4, &3]);
+        assert_eq!(trie.prefix_search(u64::MAX, 0).len(), 5);
+        assert!(PatriciaTrie::<()>::new().prefix_search(0, 8).is_empty());
+        let mut rng = ChaCha8Rng::seed_from_u64(1321);
+        let mut trie = PatriciaTrie::new();
+        let mut map = BTreeMap::new();
+        for _ in 0..1000 {
+            let key = rng.gen::<u64>() & top_mask(rng.gen_range(1..=64));
+            trie.insert(key, 0);
+            map.insert(key, 0);
+        }
+        for _ in 0..500 {
+            let (key, bits) = (rng.gen::<u64>(), rng.gen_range(0..=16));
+            let expected = map
+                .iter()
+                .filter(|(k, _)| (*k ^ key) & top_mask(bits) == 0)
+                .count();
+            assert_eq!(trie.prefix_search(key, bits).len(), expected);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "a key has 64 bits, got a prefix of 65")]
+    fn prefix_too_long() {
+        PatriciaTrie::<()>::new().prefix_search(0, 65);
+    }
+
+    #[test]
+    #[ignore = "slow test for measuring time"]
+    fn lookups_against_hash_map() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1322);
+        let keys: Vec<u64> = (0..100_000).map(|_| rng.gen()).collect();
+        let mut trie = PatriciaTrie::new();
+        let mut map = HashMap::new();
+        for (i, &key) in keys.iter().enumerate() {
+            trie.insert(key, i);
+            map.insert(key, i);
+        }
+        let queries: Vec<u64> = (0..1_000_000)
+            .map(|i| {
+                if i % 2 == 0 {
+                    keys[i % keys.len()]
+                } else {
+                    rng.gen()
+                }
+            })
+            .collect();
+
+        let start = Instant::now();
+        let from_trie = queries.iter().filter_map(|&key| trie.get(key)).count();
+        let trie_time = start.elapsed();
+        let start = Instant::now();
+        let from_map = queries.iter().filter_map(|key| map.get(key)).count();
+        let map_time = start.elapsed();
+        println!("PATRICIA trie: {trie_time:?}, hash map: {map_time:?}");
+        assert_eq!(from_trie, from_map);
+        assert_eq!(from_trie, 500_000);
+    }
+}
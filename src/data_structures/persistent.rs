@@ -0,0 +1,448 @@
+//! Persistent lists and stacks
+//! <https://en.wikipedia.org/wiki/Persistent_data_structure>
+//!
+//! A persistent data structure is never modified in place: every operation returns a new
+//! version, and the old versions remain valid. The versions share the nodes they have in
+//! common through reference counting, so a new version only costs the nodes which differ, and
+//! keeping every version of an undo stack or every partial solution of a backtracking search
+//! costs no copies.
+//!
+//! The nodes are shared through `Rc` by default, which is cheaper but keeps the lists on one
+//! thread. With `ArcPointer` as the second type parameter, the nodes are shared through `Arc`
+//! instead, and the lists are `Send` and `Sync` whenever their items are.
+
+use std::fmt::{self, Debug, Formatter};
+use std::iter::FromIterator;
+use std::ops::Deref;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// A kind of reference-counted pointer sharing the nodes of persistent data structures
+pub trait PointerFamily {
+    type Pointer<U>: Deref<Target = U> + Clone;
+
+    fn new<U>(value: U) -> Self::Pointer<U>;
+
+    /// Returns the value if the pointer is the only one to it, and the pointer otherwise.
+    fn try_unwrap<U>(pointer: Self::Pointer<U>) -> Result<U, Self::Pointer<U>>;
+}
+
+/// Shares the nodes through `Rc`, within a single thread
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RcPointer;
+
+impl PointerFamily for RcPointer {
+    type Pointer<U> = Rc<U>;
+
+    fn new<U>(value: U) -> Rc<U> {
+        Rc::new(value)
+    }
+
+    fn try_unwrap<U>(pointer: Rc<U>) -> Result<U, Rc<U>> {
+        Rc::try_unwrap(pointer)
+    }
+}
+
+/// Shares the nodes through `Arc`, across threads
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ArcPointer;
+
+impl PointerFamily for ArcPointer {
+    type Pointer<U> = Arc<U>;
+
+    fn new<U>(value: U) -> Arc<U> {
+        Arc::new(value)
+    }
+
+    fn try_unwrap<U>(pointer: Arc<U>) -> Result<U, Arc<U>> {
+        Arc::try_unwrap(pointer)
+    }
+}
+
+type Link<T, P> = Option<<P as PointerFamily>::Pointer<Node<T, P>>>;
+
+struct Node<T, P: PointerFamily> {
+    value: T,
+    next: Link<T, P>,
+}
+
+/// An immutable singly linked list, whose versions share their common tails
+///
+/// `cons`, `head`, `tail` and `len` take O(1), and `update` copies only the items before the
+/// updated one.
+pub struct PersistentList<T, P: PointerFamily = RcPointer> {
+    head: Link<T, P>,
+    len: usize,
+}
+
+impl<T> PersistentList<T> {
+    /// Creates an empty list sharing its nodes through `Rc`. Use `default` for other pointers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<T, P: PointerFamily> Default for PersistentList<T, P> {
+    fn default() -> Self {
+        PersistentList { head: None, len: 0 }
+    }
+}
+
+impl<T, P: PointerFamily> Clone for PersistentList<T, P> {
+    /// Returns the same version of the list, in O(1).
+    fn clone(&self) -> Self {
+        PersistentList {
+            head: self.head.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<T, P: PointerFamily> PersistentList<T, P> {
+    /// Returns a new version of the list with `value` in front, sharing all of this one.
+    pub fn cons(&self, value: T) -> Self {
+        PersistentList {
+            head: Some(P::new(Node {
+                value,
+                next: self.head.clone(),
+            })),
+            len: self.len + 1,
+        }
+    }
+
+    pub fn head(&self) -> Option<&T> {
+        self.head.as_ref().map(|node| &node.value)
+    }
+
+    /// Returns the list without its first item, or `None` if it is empty.
+    pub fn tail(&self) -> Option<Self> {
+        self.head.as_ref().map(|node| PersistentList {
+            head: node.next.clone(),
+            len: self.len - 1,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn iter(&self) -> Iter<'_, T, P> {
+        Iter {
+            next: self.head.as_deref(),
+            remaining: self.len,
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.iter().nth(index)
+    }
+}
+
+impl<T: Clone, P: PointerFamily> PersistentList<T, P> {
+    /// Returns a new version of the list with its items in reverse order.
+    pub fn reverse(&self) -> Self {
+        self.iter()
+            .fold(Self::default(), |list, value| list.cons(value.clone()))
+    }
+
+    /// Returns a new version of the list with `value` at `index`, or `None` if the index is out
+    /// of bounds. The items after `index` are shared, and the ones before it are copied.
+    pub fn update(&self, index: usize, value: T) -> Option<Self> {
+        if index >= self.len {
+            return None;
+        }
+        let mut prefix = Vec::with_capacity(index);
+        let mut node = self.head.as_deref()?;
+        for _ in 0..index {
+            prefix.push(node.value.clone());
+            node = node.next.as_deref()?;
+        }
+        let suffix = PersistentList {
+            head: node.next.clone(),
+            len: self.len - index - 1,
+        };
+        Some(
+            prefix
+                .into_iter()
+                .rev()
+                .fold(suffix.cons(value), |list, value| list.cons(value)),
+        )
+    }
+}
+
+impl<T, P: PointerFamily> Drop for PersistentList<T, P> {
+    /// Drops the nodes no other version shares one after the other, since dropping them
+    /// recursively would overflow the stack on long lists.
+    fn drop(&mut self) {
+        let mut link = self.head.take();
+        while let Some(pointer) = link {
+            match P::try_unwrap(pointer) {
+                Ok(mut node) => link = node.next.take(),
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+impl<T, P: PointerFamily> FromIterator<T> for PersistentList<T, P> {
+    /// Builds a list of the items in the order of the iterator.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let items: Vec<T> = iter.into_iter().collect();
+        items
+            .into_iter()
+            .rev()
+            .fold(Self::default(), |list, value| list.cons(value))
+    }
+}
+
+impl<T: PartialEq, P: PointerFamily> PartialEq for PersistentList<T, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, P: PointerFamily> Eq for PersistentList<T, P> {}
+
+impl<T: Debug, P: PointerFamily> Debug for PersistentList<T, P> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T, P: PointerFamily> IntoIterator for &'a PersistentList<T, P> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T, P>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterates over the items of a persistent list, from the head
+pub struct Iter<'a, T, P: PointerFamily> {
+    next: Option<&'a Node<T, P>>,
+    remaining: usize,
+}
+
+impl<'a, T, P: PointerFamily> Iterator for Iter<'a, T, P> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.next.map(|node| {
+            self.next = node.next.as_deref();
+            self.remaining -= 1;
+            &node.value
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T, P: PointerFamily> ExactSizeIterator for Iter<'_, T, P> {}
+
+/// An immutable stack: pushing and popping return new versions, sharing the items below
+pub struct PersistentStack<T, P: PointerFamily = RcPointer> {
+    items: PersistentList<T, P>,
+}
+
+impl<T> PersistentStack<T> {
+    /// Creates an empty stack sharing its nodes through `Rc`. Use `default` for other pointers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<T, P: PointerFamily> Default for PersistentStack<T, P> {
+    fn default() -> Self {
+        PersistentStack {
+            items: PersistentList::default(),
+        }
+    }
+}
+
+impl<T, P: PointerFamily> Clone for PersistentStack<T, P> {
+    fn clone(&self) -> Self {
+        PersistentStack {
+            items: self.items.clone(),
+        }
+    }
+}
+
+impl<T, P: PointerFamily> PersistentStack<T, P> {
+    pub fn push(&self, value: T) -> Self {
+        PersistentStack {
+            items: self.items.cons(value),
+        }
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.items.head()
+    }
+
+    /// Returns the top of the stack and the stack below it, or `None` if it is empty.
+    pub fn pop(&self) -> Option<(&T, Self)> {
+        let top = self.items.head()?;
+        let below = self.items.tail()?;
+        Some((top, PersistentStack { items: below }))
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Iterates over the items from the top of the stack.
+    pub fn iter(&self) -> Iter<'_, T, P> {
+        self.items.iter()
+    }
+}
+
+impl<T: PartialEq, P: PointerFamily> PartialEq for PersistentStack<T, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.items == other.items
+    }
+}
+
+impl<T: Eq, P: PointerFamily> Eq for PersistentStack<T, P> {}
+
+impl<T: Debug, P: PointerFamily> Debug for PersistentStack<T, P> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items<T: Clone, P: PointerFamily>(list: &PersistentList<T, P>) -> Vec<T> {
+        list.iter().cloned().collect()
+    }
+
+    #[test]
+    fn old_versions_are_unchanged() {
+        let empty = PersistentList::new();
+        let one = empty.cons(1);
+        let two = one.cons(2);
+        let other = one.cons(20);
+        assert!(empty.is_empty());
+        assert_eq!(items(&one), [1]);
+        assert_eq!(items(&two), [2, 1]);
+        assert_eq!(items(&other), [20, 1]);
+        assert_eq!(two.head(), Some(&2));
+        assert_eq!(two.tail(), Some(one.clone()));
+        assert_eq!(empty.tail(), None);
+        assert_eq!(empty.head(), None);
+
+        let list: PersistentList<i32> = (1..=5).collect();
+        let updated = list.update(2, 30).unwrap();
+        assert_eq!(items(&list), [1, 2, 3, 4, 5]);
+        assert_eq!(items(&updated), [1, 2, 30, 4, 5]);
+        assert_eq!(updated.len(), 5);
+        assert_eq!(list.update(5, 0), None);
+        assert_eq!(items(&list.reverse()), [5, 4, 3, 2, 1]);
+        assert_eq!(items(&list), [1, 2, 3, 4, 5]);
+        assert_eq!(list.get(3), Some(&4));
+        assert_eq!(list.get(5), None);
+    }
+
+    #[test]
+    fn versions_share_their_tails() {
+        let base: PersistentList<i32> = (0..3).collect();
+        let node = base.head.clone().unwrap();
+        assert_eq!(Rc::strong_count(&node), 2);
+        let first = base.cons(10);
+        let second = base.cons(20);
+        assert_eq!(Rc::strong_count(&node), 4);
+        drop(first);
+        assert_eq!(Rc::strong_count(&node), 3);
+
+        // updating the third item of second shares the node after it, the last of base
+        let last = base.tail().unwrap().tail().unwrap();
+        let last_node = last.head.clone().unwrap();
+        let count = Rc::strong_count(&last_node);
+        let updated = second.update(2, 100).unwrap();
+        assert_eq!(items(&updated), [20, 0, 100, 2]);
+        assert_eq!(Rc::strong_count(&last_node), count + 1);
+        drop(second);
+        assert_eq!(items(&base), [0, 1, 2]);
+    }
+
+    #[test]
+    fn iteration_order() {
+        let list: PersistentList<char> = "abcd".chars().collect();
+        assert_eq!(list.iter().collect::<String>(), "abcd");
+        assert_eq!(list.iter().len(), 4);
+        let mut letters = vec![];
+        for &c in &list {
+            letters.push(c);
+        }
+        assert_eq!(letters, ['a', 'b', 'c', 'd']);
+    }
+
+    #[test]
+    fn long_lists_drop_without_overflow() {
+        let list: PersistentList<u32> = (0..100_000).collect();
+        let shared = list.tail().unwrap().tail().unwrap();
+        assert_eq!(list.len(), 100_000);
+        drop(list);
+        assert_eq!(shared.len(), 99_998);
+        assert_eq!(shared.head(), Some(&2));
+        drop(shared);
+
+        let mut list = PersistentList::<u32, ArcPointer>::default();
+        for i in 0..100_000 {
+            list = list.cons(i);
+        }
+        drop(list);
+    }
+
+    #[test]
+    fn equality_and_debug() {
+        let a: PersistentList<i32> = vec![1, 2, 3].into_iter().collect();
+        let b = PersistentList::new().cons(3).cons(2).cons(1);
+        assert_eq!(a, b);
+        assert_ne!(a, b.tail().unwrap());
+        assert_ne!(a, a.update(0, 0).unwrap());
+        assert_eq!(format!("{a:?}"), "[1, 2, 3]");
+        assert_eq!(format!("{:?}", PersistentList::<i32>::new()), "[]");
+        let stack = PersistentStack::new().push("x").push("y");
+        assert_eq!(format!("{stack:?}"), r#"["y", "x"]"#);
+    }
+
+    #[test]
+    fn stacks() {
+        let empty = PersistentStack::new();
+        let one = empty.push(1);
+        let two = one.push(2);
+        let (top, below) = two.pop().unwrap();
+        assert_eq!(*top, 2);
+        assert_eq!(below, one);
+        assert_eq!(two.len(), 2);
+        assert_eq!(two.peek(), Some(&2));
+        assert_eq!(two.iter().copied().collect::<Vec<_>>(), [2, 1]);
+        assert!(empty.pop().is_none());
+        assert!(empty.is_empty());
+        assert_eq!(one.len(), 1);
+    }
+
+    #[test]
+    fn arc_lists_cross_threads() {
+        let list: PersistentList<i32, ArcPointer> = (1..=4).collect();
+        let shared = list.clone();
+        let sum = std::thread::spawn(move || shared.cons(10).iter().sum::<i32>())
+            .join()
+            .unwrap();
+        assert_eq!(sum, 20);
+        assert_eq!(items(&list), [1, 2, 3, 4]);
+    }
+}
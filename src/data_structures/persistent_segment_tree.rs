@@ -0,0 +1,242 @@
+//! A persistent segment tree, answering range queries on every past version of an array
+//! <https://en.wikipedia.org/wiki/Persistent_data_structure#Path_copying>
+//!
+//! An update never modifies a node: it copies the nodes on the path from the root to the
+//! updated leaf, O(log(n)) of them, and the copies point to the untouched subtrees of the old
+//! version. Every version is thus a full segment tree and answers queries in O(log(n)), and `k`
+//! updates cost O(n + k log(n)) nodes in total. Keeping the root of every version of an array
+//! answers, for instance, queries over the first `i` items of a list for all `i` at once.
+
+use std::rc::Rc;
+
+type Link<T> = Rc<Node<T>>;
+
+struct Node<T> {
+    value: T,
+    /// The two halves of the range of the node, or none for a leaf
+    children: Option<(Link<T>, Link<T>)>,
+}
+
+/// A version of an array with range queries, over an associative `combine`
+pub struct PersistentSegmentTree<T: Clone + Default> {
+    root: Option<Rc<Node<T>>>,
+    len: usize,
+    combine: fn(T, T) -> T,
+}
+
+impl<T: Clone + Default> Clone for PersistentSegmentTree<T> {
+    /// Returns the same version of the tree, in O(1).
+    fn clone(&self) -> Self {
+        PersistentSegmentTree {
+            root: self.root.clone(),
+            len: self.len,
+            combine: self.combine,
+        }
+    }
+}
+
+impl<T: Clone + Default> PersistentSegmentTree<T> {
+    /// Builds the first version of the tree over `data`, in O(n).
+    pub fn new(data: &[T], combine: fn(T, T) -> T) -> Self {
+        PersistentSegmentTree {
+            root: (!data.is_empty()).then(|| Self::build(data, combine)),
+            len: data.len(),
+            combine,
+        }
+    }
+
+    fn build(data: &[T], combine: fn(T, T) -> T) -> Rc<Node<T>> {
+        if let [value] = data {
+            return Rc::new(Node {
+                value: value.clone(),
+                children: None,
+            });
+        }
+        let (left, right) = data.split_at(data.len() / 2);
+        let (left, right) = (Self::build(left, combine), Self::build(right, combine));
+        Rc::new(Node {
+            value: combine(left.value.clone(), right.value.clone()),
+            children: Some((left, right)),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a new version of the tree with `value` at index `i`, in O(log(n)), sharing all
+    /// but O(log(n)) nodes with this one, which stays as it was.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn update(&self, i: usize, value: T) -> Self {
+        assert!(
+            i < self.len,
+            "index {i} out of bounds for length {}",
+            self.len
+        );
+        let root = self.root.as_ref().expect("the tree is not empty");
+        PersistentSegmentTree {
+            root: Some(self.set(root, 0, self.len, i, value)),
+            len: self.len,
+            combine: self.combine,
+        }
+    }
+
+    /// Returns a copy of `node`, over `[start, end)`, with `value` at index `i`.
+    fn set(&self, node: &Node<T>, start: usize, end: usize, i: usize, value: T) -> Rc<Node<T>> {
+        let Some((left, right)) = &node.children else {
+            return Rc::new(Node {
+                value,
+                children: None,
+            });
+        };
+        let middle = start + (end - start) / 2;
+        let (left, right) = if i < middle {
+            (self.set(left, start, middle, i, value), Rc::clone(right))
+        } else {
+            (Rc::clone(left), self.set(right, middle, end, i, value))
+        };
+        Rc::new(Node {
+            value: (self.combine)(left.value.clone(), right.value.clone()),
+            children: Some((left, right)),
+        })
+    }
+
+    /// Returns the combination of the items in `[l, r)`, in O(log(n)), or `T::default()` for an
+    /// empty range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `l > r` or `r > len`.
+    pub fn query(&self, l: usize, r: usize) -> T {
+        assert!(
+            l <= r && r <= self.len,
+            "{l}..{r} is not a range of an array of length {}",
+            self.len
+        );
+        match &self.root {
+            Some(root) if l < r => self.combine_range(root, 0, self.len, l, r),
+            _ => T::default(),
+        }
+    }
+
+    /// Combines the items of `[l, r)`, within the range `[start, end)` of `node`.
+    fn combine_range(&self, node: &Node<T>, start: usize, end: usize, l: usize, r: usize) -> T {
+        if l <= start && end <= r {
+            return node.value.clone();
+        }
+        let (left, right) = node
+            .children
+            .as_ref()
+            .expect("a leaf is inside or outside the range");
+        let middle = start + (end - start) / 2;
+        if r <= middle {
+            self.combine_range(left, start, middle, l, r)
+        } else if middle <= l {
+            self.combine_range(right, middle, end, l, r)
+        } else {
+            (self.combine)(
+                self.combine_range(left, start, middle, l, r),
+                self.combine_range(right, middle, end, l, r),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use std::collections::HashSet;
+
+    /// Collects the distinct nodes of the versions.
+    fn collect<T>(node: &Rc<Node<T>>, seen: &mut HashSet<*const Node<T>>) {
+        if seen.insert(Rc::as_ptr(node)) {
+            if let Some((left, right)) = &node.children {
+                collect(left, seen);
+                collect(right, seen);
+            }
+        }
+    }
+
+    #[test]
+    fn sums_of_versions() {
+        let first = PersistentSegmentTree::new(&[1, 2, 3, 4, 5], |a, b| a + b);
+        let second = first.update(2, 10);
+        let third = second.update(0, -1);
+        assert_eq!(first.query(0, 5), 15);
+        assert_eq!(second.query(0, 5), 22);
+        assert_eq!(third.query(0, 5), 20);
+        assert_eq!(first.query(1, 3), 5);
+        assert_eq!(second.query(1, 3), 12);
+        assert_eq!(third.query(0, 1), -1);
+        assert_eq!(first.query(0, 1), 1);
+        assert_eq!(third.query(3, 3), 0);
+        assert_eq!(first.len(), 5);
+
+        let empty = PersistentSegmentTree::new(&[], |a: i32, b| a + b);
+        assert!(empty.is_empty());
+        assert_eq!(empty.query(0, 0), 0);
+    }
+
+    #[test]
+    fn historical_versions_with_shared_nodes() {
+        let mut rng = ChaCha8Rng::seed_from_u64(137);
+        let n = 1000;
+        let data: Vec<i64> = (0..n).map(|_| rng.gen_range(-1000..1000)).collect();
+        let mut arrays = vec![data.clone()];
+        let mut versions = vec![PersistentSegmentTree::new(&data, |a: i64, b| a.min(b))];
+        for _ in 0..10 {
+            let (i, value) = (rng.gen_range(0..n), rng.gen_range(-1000..1000));
+            let mut array = arrays.last().unwrap().clone();
+            array[i] = value;
+            arrays.push(array);
+            versions.push(versions.last().unwrap().update(i, value));
+        }
+        for _ in 0..2000 {
+            let version = rng.gen_range(0..versions.len());
+            let l = rng.gen_range(0..n);
+            let r = rng.gen_range(l + 1..=n);
+            let expected = *arrays[version][l..r].iter().min().unwrap();
+            assert_eq!(versions[version].query(l, r), expected);
+        }
+        // 2n - 1 nodes for the first version, and a path of at most 11 nodes for every update
+        let mut seen = HashSet::new();
+        for version in &versions {
+            collect(version.root.as_ref().unwrap(), &mut seen);
+        }
+        assert!(seen.len() <= 2 * n - 1 + 10 * 11, "{} nodes", seen.len());
+        assert!(seen.len() > 2 * n - 1);
+    }
+
+    #[test]
+    fn any_associative_combination() {
+        // concatenation is associative but not commutative
+        let letters: Vec<String> = "persistent".chars().map(String::from).collect();
+        let tree = PersistentSegmentTree::new(&letters, |a, b| a + &b);
+        let changed = tree.update(0, "P".to_string()).update(9, "T".to_string());
+        assert_eq!(tree.query(0, 10), "persistent");
+        assert_eq!(changed.query(0, 10), "PersistenT");
+        assert_eq!(changed.query(3, 7), "sist");
+        assert_eq!(tree.clone().query(7, 10), "ent");
+    }
+
+    #[test]
+    #[should_panic(expected = "index 3 out of bounds for length 3")]
+    fn update_out_of_bounds() {
+        PersistentSegmentTree::new(&[1, 2, 3], |a, b| a + b).update(3, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "2..4 is not a range of an array of length 3")]
+    fn query_out_of_bounds() {
+        PersistentSegmentTree::new(&[1, 2, 3], |a, b| a + b).query(2, 4);
+    }
+}
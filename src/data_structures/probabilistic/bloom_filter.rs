@@ -111,7 +111,9 @@ impl MultiBinaryBloomFilter {
         Self {
             filter_size,
             bytes: vec![0; bytes_count],
-            hash_builders: vec![RandomState::new(); hash_count],
+            // every hash function needs its own random keys: cloning a single `RandomState`
+            // would hash every item to the same bit `hash_count` times
+            hash_builders: (0..hash_count).map(|_| RandomState::new()).collect(),
         }
     }
 
@@ -160,10 +162,159 @@ impl<Item: Hash> BloomFilter<Item> for MultiBinaryBloomFilter {
     }
 }
 
+/// A Bloom filter can't forget an item: clearing its bits could clear those of other items.
+/// A counting Bloom filter replaces every bit by a small counter, which insertions increment and
+/// removals decrement, so that an item is present while all its counters are positive.
+/// <https://en.wikipedia.org/wiki/Counting_Bloom_filter>
+///
+/// The counters take 4 bits, two per byte. A counter reaching 15 is saturated: it stays at 15
+/// for good, since its true count is lost, so that the items behind it are never removed by
+/// mistake (at the cost of never removing them at all).
+pub struct CountingBloomFilter {
+    filter_size: usize,
+    counters: Vec<u8>,
+    hash_builders: Vec<RandomState>,
+}
+
+impl CountingBloomFilter {
+    const MAX_COUNT: u8 = 15;
+
+    pub fn with_dimensions(filter_size: usize, hash_count: usize) -> Self {
+        Self {
+            filter_size,
+            counters: vec![0; filter_size.div_ceil(2)],
+            hash_builders: (0..hash_count).map(|_| RandomState::new()).collect(),
+        }
+    }
+
+    pub fn from_estimate(
+        estimated_count_of_items: usize,
+        max_false_positive_probability: f64,
+    ) -> Self {
+        let filter = MultiBinaryBloomFilter::from_estimate(
+            estimated_count_of_items,
+            max_false_positive_probability,
+        );
+        Self::with_dimensions(filter.filter_size, filter.hash_builders.len())
+    }
+
+    /// The counters of an item, each one once even if several hash functions give it
+    fn indices(&self, item: &[u8]) -> Vec<usize> {
+        let mut indices: Vec<usize> = self
+            .hash_builders
+            .iter()
+            .map(|builder| (builder.hash_one(item) % self.filter_size as u64) as usize)
+            .collect();
+        indices.sort_unstable();
+        indices.dedup();
+        indices
+    }
+
+    fn counter(&self, index: usize) -> u8 {
+        (self.counters[index / 2] >> (4 * (index % 2))) & 0xf
+    }
+
+    fn set_counter(&mut self, index: usize, count: u8) {
+        let shift = 4 * (index % 2);
+        let byte = &mut self.counters[index / 2];
+        *byte = (*byte & !(0xf << shift)) | (count << shift);
+    }
+
+    pub fn insert(&mut self, item: &[u8]) {
+        for index in self.indices(item) {
+            let count = self.counter(index);
+            if count < Self::MAX_COUNT {
+                self.set_counter(index, count + 1);
+            }
+        }
+    }
+
+    /// Removes an item, and returns false, leaving the filter as it was, if one of its counters
+    /// is at 0, in which case the item was never inserted.
+    ///
+    /// Removing an item which was not inserted, but is a false positive, would remove other
+    /// items instead: only remove items which were inserted.
+    pub fn remove(&mut self, item: &[u8]) -> bool {
+        let indices = self.indices(item);
+        if indices.iter().any(|&index| self.counter(index) == 0) {
+            return false;
+        }
+        for index in indices {
+            let count = self.counter(index);
+            if count < Self::MAX_COUNT {
+                self.set_counter(index, count - 1);
+            }
+        }
+        true
+    }
+
+    pub fn contains(&self, item: &[u8]) -> bool {
+        self.indices(item)
+            .into_iter()
+            .all(|index| self.counter(index) > 0)
+    }
+}
+
+/// An iterator adapter yielding the items of an iterator which were (probably) not yielded
+/// before, created with `BloomDedupExt::bloom_dedup`.
+///
+/// Exact de-duplication needs to remember every distinct item, while a Bloom filter takes a
+/// fixed amount of memory. Since the filter never forgets an item, a duplicate is never yielded
+/// twice. But a false positive makes the filter believe an item was seen when it was not, and
+/// that item is wrongly dropped: this happens with about the probability the filter was sized
+/// for, as long as there are at most `capacity` distinct items.
+pub struct BloomDedup<I: Iterator> {
+    inner: I,
+    filter: MultiBinaryBloomFilter,
+}
+
+impl<I> Iterator for BloomDedup<I>
+where
+    I: Iterator,
+    I::Item: Hash,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        for item in self.inner.by_ref() {
+            if !self.filter.contains(&&item) {
+                self.filter.insert(&item);
+                return Some(item);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.inner.size_hint().1)
+    }
+}
+
+pub trait BloomDedupExt: Iterator + Sized {
+    /// De-duplicates the items with a Bloom filter sized for `capacity` distinct items and a
+    /// false positive probability of `fpr`.
+    ///
+    /// Panics if `capacity` is 0 or `fpr` is not strictly between 0 and 1.
+    fn bloom_dedup(self, capacity: usize, fpr: f64) -> BloomDedup<Self> {
+        assert!(capacity > 0, "the capacity must be positive");
+        assert!(
+            fpr > 0.0 && fpr < 1.0,
+            "the false positive rate must be between 0 and 1"
+        );
+        BloomDedup {
+            inner: self,
+            filter: MultiBinaryBloomFilter::from_estimate(capacity, fpr),
+        }
+    }
+}
+
+impl<I: Iterator> BloomDedupExt for I {}
+
 #[cfg(test)]
 mod tests {
     use crate::data_structures::probabilistic::bloom_filter::{
-        BasicBloomFilter, BloomFilter, MultiBinaryBloomFilter, SingleBinaryBloomFilter,
+        BasicBloomFilter, BloomDedupExt, BloomFilter, CountingBloomFilter, MultiBinaryBloomFilter,
+        SingleBinaryBloomFilter,
     };
     use quickcheck::{Arbitrary, Gen};
     use quickcheck_macros::quickcheck;
@@ -265,4 +416,111 @@ mod tests {
         let fp_rate = false_positives as f64 / tests as f64;
         assert!(fp_rate < 1.0); // This isn't really a test, but so that you have the `fp_rate` variable to print out, or evaluate
     }
+
+    /// 100 000 items: 90 000 distinct ones, and after every 9 of them a copy of an earlier one
+    fn stream_with_duplicates() -> impl Iterator<Item = u64> {
+        let mut distinct = 0;
+        (0..100_000u64).map(move |i| {
+            if i % 10 == 9 {
+                // an earlier item, scattered over all that were seen
+                (i * 7919) % distinct * 1_000_003
+            } else {
+                distinct += 1;
+                (distinct - 1) * 1_000_003
+            }
+        })
+    }
+
+    #[test]
+    fn bloom_dedup_drops_every_duplicate() {
+        const FPR: f64 = 0.01;
+        assert_eq!(
+            stream_with_duplicates().collect::<HashSet<_>>().len(),
+            90_000
+        );
+        let yielded: Vec<u64> = stream_with_duplicates().bloom_dedup(90_000, FPR).collect();
+        let distinct: HashSet<u64> = yielded.iter().copied().collect();
+        // no false negative: nothing is yielded twice
+        assert_eq!(distinct.len(), yielded.len());
+        // the unique items wrongly dropped as duplicates
+        let false_positive_rate = (90_000 - yielded.len()) as f64 / 90_000.0;
+        assert!(
+            false_positive_rate <= 2.0 * FPR,
+            "false positive rate {false_positive_rate}"
+        );
+    }
+
+    #[test]
+    fn bloom_dedup_keeps_the_order() {
+        let items = ["a", "b", "a", "c", "b", "d", "a"];
+        let deduped: Vec<&str> = items.into_iter().bloom_dedup(100, 0.001).collect();
+        assert_eq!(deduped, ["a", "b", "c", "d"]);
+        assert_eq!(std::iter::empty::<u8>().bloom_dedup(10, 0.1).count(), 0);
+    }
+
+    #[test]
+    fn counting_filter_insert_and_remove() {
+        let mut filter = CountingBloomFilter::with_dimensions(1000, 4);
+        assert!(!filter.contains(b"bloom"));
+        assert!(!filter.remove(b"bloom"));
+        filter.insert(b"bloom");
+        filter.insert(b"bloom");
+        assert!(filter.contains(b"bloom"));
+        // every insertion needs its own removal
+        assert!(filter.remove(b"bloom"));
+        assert!(filter.contains(b"bloom"));
+        assert!(filter.remove(b"bloom"));
+        assert!(!filter.contains(b"bloom"));
+        assert!(!filter.remove(b"bloom"));
+        assert!(filter.counters.iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn counting_filter_forgets_removed_items() {
+        let items: Vec<[u8; 4]> = (0..2000u32).map(|i| (i * 7919).to_le_bytes()).collect();
+        let mut filter = CountingBloomFilter::from_estimate(items.len(), FALSE_POSITIVE_MAX);
+        for item in &items {
+            filter.insert(item);
+        }
+        assert!(items.iter().all(|item| filter.contains(item)));
+        for item in &items[..1000] {
+            assert!(filter.remove(item));
+        }
+        // no false negatives among the items left, and about 5% false positives among the others
+        assert!(items[1000..].iter().all(|item| filter.contains(item)));
+        let found = items[..1000]
+            .iter()
+            .filter(|item| filter.contains(*item))
+            .count();
+        assert!(found < 200, "{found} removed items still found");
+        for item in &items[1000..] {
+            assert!(filter.remove(item));
+        }
+        assert!(filter.counters.iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn counting_filter_saturates_at_15_insertions() {
+        let mut filter = CountingBloomFilter::with_dimensions(100, 3);
+        for _ in 0..14 {
+            filter.insert(b"item");
+        }
+        for _ in 0..14 {
+            assert!(filter.remove(b"item"));
+        }
+        assert!(!filter.contains(b"item"));
+        // from the 15th insertion, the counters are stuck at 15 and the item stays for good
+        for _ in 0..15 {
+            filter.insert(b"item");
+        }
+        for _ in 0..100 {
+            assert!(filter.remove(b"item"));
+        }
+        assert!(filter.contains(b"item"));
+        // the neighbors of a counter in the same byte are left alone
+        assert!(filter
+            .counters
+            .iter()
+            .all(|&byte| byte == 0 || byte == 0x0f || byte == 0xf0 || byte == 0xff));
+    }
 }
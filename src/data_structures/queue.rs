@@ -39,9 +39,165 @@ impl<T> Default for Queue<T> {
     }
 }
 
+/// A FIFO queue made of two stacks, in amortized O(1) per operation: items are pushed on the
+/// inbox, and popped from the outbox, which gets the whole inbox in reverse order whenever it
+/// runs out. Every item is moved once, from the inbox to the outbox.
+#[derive(Debug)]
+pub struct TwoStackQueue<T> {
+    inbox: Vec<T>,
+    /// The oldest items, the oldest one on top
+    outbox: Vec<T>,
+}
+
+impl<T> TwoStackQueue<T> {
+    pub fn new() -> TwoStackQueue<T> {
+        TwoStackQueue {
+            inbox: Vec::new(),
+            outbox: Vec::new(),
+        }
+    }
+
+    pub fn enqueue(&mut self, item: T) {
+        self.inbox.push(item)
+    }
+
+    pub fn dequeue(&mut self) -> Option<T> {
+        if self.outbox.is_empty() {
+            self.outbox.extend(self.inbox.drain(..).rev());
+        }
+        self.outbox.pop()
+    }
+
+    /// Returns the oldest item, which is at the bottom of the inbox when the outbox is empty.
+    pub fn peek(&self) -> Option<&T> {
+        self.outbox.last().or_else(|| self.inbox.first())
+    }
+
+    pub fn len(&self) -> usize {
+        self.inbox.len() + self.outbox.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inbox.is_empty() && self.outbox.is_empty()
+    }
+}
+
+impl<T> Default for TwoStackQueue<T> {
+    fn default() -> TwoStackQueue<T> {
+        TwoStackQueue::new()
+    }
+}
+
+/// A double-ended queue in a circular buffer, which doubles when it is full, in amortized O(1)
+/// per operation.
+#[derive(Debug)]
+pub struct ArrayDeque<T> {
+    buffer: Vec<Option<T>>,
+    /// The index of the front in `buffer`
+    head: usize,
+    len: usize,
+}
+
+impl<T> ArrayDeque<T> {
+    pub fn new() -> ArrayDeque<T> {
+        ArrayDeque {
+            buffer: Vec::new(),
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// The index in `buffer` of the item `i` from the front
+    fn index(&self, i: usize) -> usize {
+        (self.head + i) % self.buffer.len()
+    }
+
+    fn grow(&mut self) {
+        let capacity = (2 * self.buffer.len()).max(4);
+        let mut buffer: Vec<Option<T>> = (0..capacity).map(|_| None).collect();
+        for (i, slot) in buffer.iter_mut().take(self.len).enumerate() {
+            let index = self.index(i);
+            *slot = self.buffer[index].take();
+        }
+        self.buffer = buffer;
+        self.head = 0;
+    }
+
+    pub fn push_back(&mut self, item: T) {
+        if self.len == self.buffer.len() {
+            self.grow();
+        }
+        let index = self.index(self.len);
+        self.buffer[index] = Some(item);
+        self.len += 1;
+    }
+
+    pub fn push_front(&mut self, item: T) {
+        if self.len == self.buffer.len() {
+            self.grow();
+        }
+        self.head = self.index(self.buffer.len() - 1);
+        self.buffer[self.head] = Some(item);
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        let item = self.buffer[self.head].take();
+        self.head = self.index(1);
+        self.len -= 1;
+        item
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let index = self.index(self.len);
+        self.buffer[index].take()
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.len.checked_sub(1).and_then(|i| self.get(i))
+    }
+
+    /// Returns the item `i` from the front.
+    pub fn get(&self, i: usize) -> Option<&T> {
+        if i < self.len {
+            self.buffer[self.index(i)].as_ref()
+        } else {
+            None
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<T> Default for ArrayDeque<T> {
+    fn default() -> ArrayDeque<T> {
+        ArrayDeque::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Queue;
+    use super::{ArrayDeque, Queue, TwoStackQueue};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use std::collections::VecDeque;
 
     #[test]
     fn test_enqueue() {
@@ -75,4 +231,82 @@ mod tests {
         queue.enqueue(16);
         assert_eq!(2, queue.len());
     }
+
+    #[test]
+    fn two_stack_queue() {
+        let mut queue = TwoStackQueue::new();
+        assert_eq!(queue.peek(), None);
+        queue.enqueue(1);
+        queue.enqueue(2);
+        assert_eq!(queue.peek(), Some(&1));
+        assert_eq!(queue.dequeue(), Some(1));
+        queue.enqueue(3);
+        // 2 is in the outbox and 3 in the inbox
+        assert_eq!(queue.peek(), Some(&2));
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.dequeue(), Some(2));
+        assert_eq!(queue.dequeue(), Some(3));
+        assert_eq!(queue.dequeue(), None);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn two_stack_queue_matches_vec_deque() {
+        let mut rng = ChaCha8Rng::seed_from_u64(127);
+        let mut queue = TwoStackQueue::new();
+        let mut expected = VecDeque::new();
+        for i in 0..1000 {
+            if rng.gen_bool(0.55) {
+                queue.enqueue(i);
+                expected.push_back(i);
+            } else {
+                assert_eq!(queue.dequeue(), expected.pop_front());
+            }
+            assert_eq!(queue.peek(), expected.front());
+            assert_eq!(queue.len(), expected.len());
+        }
+    }
+
+    #[test]
+    fn array_deque() {
+        let mut deque = ArrayDeque::new();
+        assert_eq!((deque.front(), deque.back()), (None, None));
+        deque.push_back(2);
+        deque.push_front(1);
+        deque.push_back(3);
+        assert_eq!((deque.front(), deque.back()), (Some(&1), Some(&3)));
+        assert_eq!(deque.pop_back(), Some(3));
+        assert_eq!(deque.pop_front(), Some(1));
+        assert_eq!(deque.pop_front(), Some(2));
+        assert_eq!(deque.pop_back(), None);
+        assert!(deque.is_empty());
+    }
+
+    #[test]
+    fn array_deque_matches_vec_deque() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1270);
+        let mut deque = ArrayDeque::new();
+        let mut expected = VecDeque::new();
+        for i in 0..1000 {
+            match rng.gen_range(0..5) {
+                0 => {
+                    deque.push_front(i);
+                    expected.push_front(i);
+                }
+                1 | 2 => {
+                    deque.push_back(i);
+                    expected.push_back(i);
+                }
+                3 => assert_eq!(deque.pop_front(), expected.pop_front()),
+                _ => assert_eq!(deque.pop_back(), expected.pop_back()),
+            }
+            assert_eq!(deque.len(), expected.len());
+            assert_eq!(
+                (deque.front(), deque.back()),
+                (expected.front(), expected.back())
+            );
+        }
+        assert!((0..expected.len()).all(|i| deque.get(i) == expected.get(i)));
+        assert_eq!(deque.get(expected.len()), None);
+    }
 }
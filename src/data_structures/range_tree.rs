@@ -0,0 +1,472 @@
+/// A static set of points of the plane, counting and listing those in axis-aligned rectangles
+/// in O(log^2(n)) and O(log^2(n) + k) for k points.
+/// <https://en.wikipedia.org/wiki/Range_tree>
+///
+/// The primary tree is a balanced binary tree over the points sorted by x, laid out like a
+/// segment tree, and every node keeps the points of its subtree in a secondary array sorted by
+/// y, merged from those of its children in O(n log(n)) in all. The points with x in a range are
+/// a range of leaves, covered by O(log(n)) nodes, and two binary searches in the array of every
+/// node find its points with y in range.
+pub struct RangeTree2D {
+    /// The x of the points, sorted
+    xs: Vec<i64>,
+    /// The points of every node sorted by y, the leaves at `xs.len()..`
+    nodes: Vec<Vec<(i64, i64)>>,
+}
+
+impl RangeTree2D {
+    pub fn new(points: &[(i64, i64)]) -> Self {
+        let mut sorted = points.to_vec();
+        sorted.sort_unstable();
+        let n = sorted.len();
+        let mut nodes = vec![vec![]; 2 * n];
+        for (i, &point) in sorted.iter().enumerate() {
+            nodes[n + i] = vec![point];
+        }
+        let by_y = |&(x, y): &(i64, i64)| (y, x);
+        for i in (1..n).rev() {
+            let (left, right) = (&nodes[2 * i], &nodes[2 * i + 1]);
+            let mut merged = Vec::with_capacity(left.len() + right.len());
+            let (mut a, mut b) = (0, 0);
+            while a < left.len() || b < right.len() {
+                if b == right.len() || (a < left.len() && by_y(&left[a]) <= by_y(&right[b])) {
+                    merged.push(left[a]);
+                    a += 1;
+                } else {
+                    merged.push(right[b]);
+                    b += 1;
+                }
+            }
+            nodes[i] = merged;
+        }
+        RangeTree2D {
+            xs: sorted.into_iter().map(|(x, _)| x).collect(),
+            nodes,
+        }
+    }
+
+    /// Calls `visit` with the points of every node covering the points with x in `x1..=x2`,
+    /// whose y is in `y1..=y2`
+    fn visit<'a>(
+        &'a self,
+        x1: i64,
+        x2: i64,
+        y1: i64,
+        y2: i64,
+        mut visit: impl FnMut(&'a [(i64, i64)]),
+    ) {
+        let n = self.xs.len();
+        let mut l = n + self.xs.partition_point(|&x| x < x1);
+        let mut r = n + self.xs.partition_point(|&x| x <= x2);
+        let mut in_range = |node: &'a Vec<(i64, i64)>| {
+            let start = node.partition_point(|&(_, y)| y < y1);
+            let end = node.partition_point(|&(_, y)| y <= y2);
+            // with y1 > y2, the end comes before the start
+            visit(&node[start..end.max(start)]);
+        };
+        while l < r {
+            if l % 2 == 1 {
+                in_range(&self.nodes[l]);
+                l += 1;
+            }
+            if r % 2 == 1 {
+                r -= 1;
+                in_range(&self.nodes[r]);
+            }
+            l /= 2;
+            r /= 2;
+        }
+    }
+
+    /// Returns the number of points with `x1 <= x <= x2` and `y1 <= y <= y2`.
+    pub fn count(&self, x1: i64, x2: i64, y1: i64, y2: i64) -> usize {
+        let mut count = 0;
+        self.visit(x1, x2, y1, y2, |points| count += points.len());
+        count
+    }
+
+    /// Returns the points with `x1 <= x <= x2` and `y1 <= y <= y2`, in no particular order.
+    pub fn query(&self, x1: i64, x2: i64, y1: i64, y2: i64) -> Vec<(i64, i64)> {
+        let mut found = vec![];
+        self.visit(x1, x2, y1, y2, |points| found.extend_from_slice(points));
+        found
+    }
+}
+
+/// A node of a balanced tree over the points `start..end` in the order of a coordinate, with a
+/// structure `S` over them
+struct Node<S> {
+    start: usize,
+    end: usize,
+    children: Option<(usize, usize)>,
+    secondary: S,
+}
+
+/// Builds the nodes of a balanced tree over `start..end`, children before their parent, with
+/// `secondary` making the structure of every node from those of its children, if any, and
+/// returns the index of the root.
+fn build_nodes<S>(
+    nodes: &mut Vec<Node<S>>,
+    start: usize,
+    end: usize,
+    secondary: &mut impl FnMut(usize, usize, Option<(&S, &S)>) -> S,
+) -> usize {
+    let children = (end - start > 1).then(|| {
+        let middle = (start + end) / 2;
+        let left = build_nodes(nodes, start, middle, secondary);
+        (left, build_nodes(nodes, middle, end, secondary))
+    });
+    let structure = secondary(
+        start,
+        end,
+        children.map(|(left, right)| (&nodes[left].secondary, &nodes[right].secondary)),
+    );
+    nodes.push(Node {
+        start,
+        end,
+        children,
+        secondary: structure,
+    });
+    nodes.len() - 1
+}
+
+/// The points of the last two coordinates, sorted by the first one in a tree whose every node
+/// keeps the second coordinate of its points sorted, and, for every prefix of them, how many
+/// come from its left child
+struct Cascade {
+    keys: Vec<i64>,
+    /// The sorted coordinates, and the number of those of the left child among every prefix
+    nodes: Vec<Node<(Vec<i64>, Vec<u32>)>>,
+}
+
+impl Cascade {
+    fn new<const D: usize>(points: &[[i64; D]], dim: usize) -> Self {
+        let mut nodes = vec![];
+        if !points.is_empty() {
+            build_nodes(&mut nodes, 0, points.len(), &mut |start, _, children| {
+                let Some(((left, _), (right, _))) = children else {
+                    return (vec![points[start][dim + 1]], vec![]);
+                };
+                let mut merged = Vec::with_capacity(left.len() + right.len());
+                let mut from_left = Vec::with_capacity(left.len() + right.len() + 1);
+                let (mut a, mut b) = (0, 0);
+                from_left.push(0);
+                while a < left.len() || b < right.len() {
+                    if b == right.len() || (a < left.len() && left[a] <= right[b]) {
+                        merged.push(left[a]);
+                        a += 1;
+                    } else {
+                        merged.push(right[b]);
+                        b += 1;
+                    }
+                    from_left.push(a as u32);
+                }
+                (merged, from_left)
+            });
+        }
+        Cascade {
+            keys: points.iter().map(|p| p[dim]).collect(),
+            nodes,
+        }
+    }
+
+    fn count(&self, bounds: &[(i64, i64)]) -> usize {
+        let ((x1, x2), (y1, y2)) = (bounds[0], bounds[1]);
+        let Some(root) = self.nodes.last() else {
+            return 0;
+        };
+        if y1 > y2 {
+            return 0;
+        }
+        let start = self.keys.partition_point(|&x| x < x1);
+        let end = self.keys.partition_point(|&x| x <= x2);
+        // a single pair of binary searches, at the root, and the positions follow down
+        let ys = &root.secondary.0;
+        let (low, high) = (
+            ys.partition_point(|&y| y < y1),
+            ys.partition_point(|&y| y <= y2),
+        );
+        self.visit(self.nodes.len() - 1, start, end, low, high)
+    }
+
+    /// The points of the node with an index in `start..end`, of which `low..high` is the range
+    /// of coordinates in the bounds
+    fn visit(&self, node: usize, start: usize, end: usize, low: usize, high: usize) -> usize {
+        let node = &self.nodes[node];
+        if end <= node.start || node.end <= start || low == high {
+            return 0;
+        }
+        if start <= node.start && node.end <= end {
+            return high - low;
+        }
+        let (left, right) = node.children.unwrap();
+        let from_left = &node.secondary.1;
+        let (left_low, left_high) = (from_left[low] as usize, from_left[high] as usize);
+        self.visit(left, start, end, left_low, left_high)
+            + self.visit(right, start, end, low - left_low, high - left_high)
+    }
+}
+
+/// The structure over the coordinates from `dim` on of a set of points
+enum Level {
+    /// The last coordinate, sorted
+    Sorted(Vec<i64>),
+    /// The last two coordinates
+    Cascade(Cascade),
+    /// A tree over the coordinate `dim`, every node with the structure over the next
+    /// coordinates of its points
+    Nested {
+        keys: Vec<i64>,
+        nodes: Vec<Node<Level>>,
+    },
+}
+
+impl Level {
+    fn new<const D: usize>(points: &mut [[i64; D]], dim: usize) -> Self {
+        if dim == D - 1 {
+            let mut keys: Vec<i64> = points.iter().map(|p| p[dim]).collect();
+            keys.sort_unstable();
+            return Level::Sorted(keys);
+        }
+        points.sort_unstable_by_key(|p| p[dim]);
+        if dim == D - 2 {
+            return Level::Cascade(Cascade::new(points, dim));
+        }
+        let mut nodes = vec![];
+        if !points.is_empty() {
+            build_nodes(&mut nodes, 0, points.len(), &mut |start, end, _| {
+                Level::new(&mut points[start..end].to_vec(), dim + 1)
+            });
+        }
+        Level::Nested {
+            keys: points.iter().map(|p| p[dim]).collect(),
+            nodes,
+        }
+    }
+
+    fn count(&self, bounds: &[(i64, i64)]) -> usize {
+        match self {
+            Level::Sorted(keys) => {
+                let (low, high) = bounds[0];
+                let end = keys.partition_point(|&x| x <= high);
+                end.saturating_sub(keys.partition_point(|&x| x < low))
+            }
+            Level::Cascade(cascade) => cascade.count(bounds),
+            Level::Nested { keys, nodes } => {
+                let (low, high) = bounds[0];
+                let start = keys.partition_point(|&x| x < low);
+                let end = keys.partition_point(|&x| x <= high);
+                let mut count = 0;
+                let mut stack: Vec<usize> = nodes.len().checked_sub(1).into_iter().collect();
+                while let Some(node) = stack.pop() {
+                    let node = &nodes[node];
+                    if end <= node.start || node.end <= start {
+                        continue;
+                    }
+                    if start <= node.start && node.end <= end {
+                        count += node.secondary.count(&bounds[1..]);
+                    } else if let Some((left, right)) = node.children {
+                        stack.extend([left, right]);
+                    }
+                }
+                count
+            }
+        }
+    }
+
+    /// The number of coordinates stored in all the levels
+    #[cfg(test)]
+    fn stored(&self) -> usize {
+        match self {
+            Level::Sorted(keys) => keys.len(),
+            Level::Cascade(cascade) => {
+                let ys: usize = cascade.nodes.iter().map(|n| n.secondary.0.len()).sum();
+                cascade.keys.len() + ys
+            }
+            Level::Nested { keys, nodes } => {
+                keys.len() + nodes.iter().map(|n| n.secondary.stored()).sum::<usize>()
+            }
+        }
+    }
+}
+
+/// A static set of points of `D` dimensions, counting those in axis-aligned boxes in
+/// O(log^D(n)), or O(log^(D - 1)(n)) with fractional cascading
+/// <https://en.wikipedia.org/wiki/Range_tree>
+///
+/// The tree over the first coordinate is balanced, and every node keeps a tree like it over
+/// the next coordinates of the points of its subtree, down to the last two coordinates. There,
+/// every node of the tree over the first of them keeps the last coordinates of its points
+/// sorted, along with where each of them falls among those of its children: a single pair of
+/// binary searches at the root of that tree then finds the range of the last coordinate in all
+/// the O(log(n)) nodes covering a range of the other one, by fractional cascading. Every point
+/// is stored O(log(n)) times at every level but the last one, so that the trees take
+/// O(n log^(D - 1)(n)) space, built in O(n log^(D - 1)(n)) time for `D >= 2`, by merging the
+/// sorted coordinates of the children at the last two levels.
+/// <https://en.wikipedia.org/wiki/Fractional_cascading>
+pub struct KdRangeTree<const D: usize> {
+    root: Level,
+    len: usize,
+}
+
+impl<const D: usize> KdRangeTree<D> {
+    /// # Panics
+    ///
+    /// Panics if `D` is zero.
+    pub fn new(points: &[[i64; D]]) -> Self {
+        assert!(D > 0, "the points need at least one dimension");
+        KdRangeTree {
+            root: Level::new(&mut points.to_vec(), 0),
+            len: points.len(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of points `p` with `bounds[i].0 <= p[i] <= bounds[i].1` for every
+    /// dimension `i`.
+    pub fn count(&self, bounds: &[(i64, i64); D]) -> usize {
+        self.root.count(bounds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn naive(points: &[(i64, i64)], x1: i64, x2: i64, y1: i64, y2: i64) -> Vec<(i64, i64)> {
+        points
+            .iter()
+            .copied()
+            .filter(|&(x, y)| x1 <= x && x <= x2 && y1 <= y && y <= y2)
+            .collect()
+    }
+
+    #[test]
+    fn small_set() {
+        let points = [(1, 1), (2, 5), (3, 3), (5, 2), (4, 4), (3, 3), (0, 6)];
+        let tree = RangeTree2D::new(&points);
+        assert_eq!(tree.count(0, 10, 0, 10), 7);
+        assert_eq!(tree.count(2, 4, 3, 5), 4);
+        let mut found = tree.query(2, 4, 3, 5);
+        found.sort_unstable();
+        assert_eq!(found, [(2, 5), (3, 3), (3, 3), (4, 4)]);
+        // the bounds are included, and empty rectangles hold nothing
+        assert_eq!(tree.query(5, 5, 2, 2), [(5, 2)]);
+        assert_eq!(tree.count(4, 2, 0, 10), 0);
+        assert_eq!(tree.count(0, 10, 5, 4), 0);
+        assert!(RangeTree2D::new(&[]).query(0, 1, 0, 1).is_empty());
+    }
+
+    #[test]
+    fn agrees_with_a_scan() {
+        let mut rng = ChaCha8Rng::seed_from_u64(156);
+        let points: Vec<(i64, i64)> = (0..1000)
+            .map(|_| (rng.gen_range(-500..500), rng.gen_range(-500..500)))
+            .collect();
+        let tree = RangeTree2D::new(&points);
+        for _ in 0..10_000 {
+            let (xa, xb) = (rng.gen_range(-600..600), rng.gen_range(-600..600));
+            let (ya, yb) = (rng.gen_range(-600..600), rng.gen_range(-600..600));
+            let (x1, x2, y1, y2) = (xa.min(xb), xa.max(xb), ya.min(yb), ya.max(yb));
+            let mut expected = naive(&points, x1, x2, y1, y2);
+            assert_eq!(tree.count(x1, x2, y1, y2), expected.len());
+            let mut found = tree.query(x1, x2, y1, y2);
+            found.sort_unstable();
+            expected.sort_unstable();
+            assert_eq!(found, expected);
+        }
+    }
+
+    fn random_box<const D: usize>(rng: &mut ChaCha8Rng, range: i64) -> [(i64, i64); D] {
+        std::array::from_fn(|_| {
+            let (a, b) = (rng.gen_range(-range..range), rng.gen_range(-range..range));
+            (a.min(b), a.max(b))
+        })
+    }
+
+    fn naive_count<const D: usize>(points: &[[i64; D]], bounds: &[(i64, i64); D]) -> usize {
+        points
+            .iter()
+            .filter(|p| (0..D).all(|i| bounds[i].0 <= p[i] && p[i] <= bounds[i].1))
+            .count()
+    }
+
+    #[test]
+    fn three_dimensions() {
+        let mut rng = ChaCha8Rng::seed_from_u64(168);
+        let points: Vec<[i64; 3]> = (0..10_000)
+            .map(|_| std::array::from_fn(|_| rng.gen_range(-1000..1000)))
+            .collect();
+        let tree = KdRangeTree::new(&points);
+        assert_eq!(tree.len(), 10_000);
+        for _ in 0..1000 {
+            let bounds = random_box(&mut rng, 1100);
+            assert_eq!(tree.count(&bounds), naive_count(&points, &bounds));
+        }
+        // the bounds are included
+        let p = points[17];
+        assert!(tree.count(&[(p[0], p[0]), (p[1], p[1]), (p[2], p[2])]) >= 1);
+        assert_eq!(tree.count(&[(i64::MIN, i64::MAX); 3]), 10_000);
+        assert_eq!(tree.count(&[(0, 10), (5, 4), (0, 10)]), 0);
+    }
+
+    #[test]
+    fn other_dimensions() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1680);
+        // with many equal coordinates
+        let points: Vec<[i64; 2]> = (0..2000)
+            .map(|_| [rng.gen_range(-20..20), rng.gen_range(-20..20)])
+            .collect();
+        let tree = KdRangeTree::new(&points);
+        let pairs: Vec<(i64, i64)> = points.iter().map(|p| (p[0], p[1])).collect();
+        let plane = RangeTree2D::new(&pairs);
+        for _ in 0..1000 {
+            let [(x1, x2), (y1, y2)] = random_box(&mut rng, 25);
+            assert_eq!(
+                tree.count(&[(x1, x2), (y1, y2)]),
+                plane.count(x1, x2, y1, y2)
+            );
+        }
+        let line: Vec<[i64; 1]> = (0..500).map(|_| [rng.gen_range(-100..100)]).collect();
+        let tree = KdRangeTree::new(&line);
+        let points: Vec<[i64; 4]> = (0..1500)
+            .map(|_| std::array::from_fn(|_| rng.gen_range(-50..50)))
+            .collect();
+        let space = KdRangeTree::new(&points);
+        for _ in 0..300 {
+            let bounds = random_box(&mut rng, 110);
+            assert_eq!(tree.count(&bounds), naive_count(&line, &bounds));
+            let bounds = random_box(&mut rng, 60);
+            assert_eq!(space.count(&bounds), naive_count(&points, &bounds));
+        }
+        let empty = KdRangeTree::<3>::new(&[]);
+        assert!(empty.is_empty());
+        assert_eq!(empty.count(&[(i64::MIN, i64::MAX); 3]), 0);
+    }
+
+    #[test]
+    fn size_of_the_trees() {
+        // every level but the last one stores every point once per level of its tree
+        let mut rng = ChaCha8Rng::seed_from_u64(1681);
+        for n in [100, 1000, 10_000] {
+            let levels = (n as f64).log2().ceil() as usize + 1;
+            let points: Vec<[i64; 3]> =
+                (0..n).map(|_| std::array::from_fn(|_| rng.gen())).collect();
+            let stored = KdRangeTree::new(&points).root.stored();
+            assert!(stored >= n * (levels - 1) * (levels - 1) / 2, "{stored}");
+            assert!(stored <= n * (levels + 1) * (levels + 1), "{stored}");
+            let points: Vec<[i64; 2]> = points.iter().map(|p| [p[0], p[1]]).collect();
+            let stored = KdRangeTree::new(&points).root.stored();
+            assert!(stored <= n * (levels + 1), "{stored}");
+        }
+    }
+}
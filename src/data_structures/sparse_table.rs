@@ -0,0 +1,253 @@
+use crate::error::{AlgorithmError, AlgorithmResult};
+use std::ops::Range;
+
+/// A sparse table answers range queries on a static array, in O(1) for idempotent operations.
+/// <https://en.wikipedia.org/wiki/Range_minimum_query#Solution_using_constant_time_and_linearithmic_space>
+///
+/// The level `j` of the table holds the result of the operation on every window of `2^j`
+/// values, built from two windows of the level below, in O(n log(n)) time and space. Any range
+/// of length `len` is covered by two windows of width `2^floor(log2(len))`, one from each end.
+/// They overlap, which does not change the result of an idempotent operation like min, max or
+/// gcd, so that `query` combines them in O(1).
+///
+/// The operation must also be associative. For operations which are not idempotent, like a sum,
+/// `query_disjoint` splits the range into disjoint windows instead, in O(log(n)). Unlike the
+/// segment tree, the array cannot be updated.
+pub struct SparseTable<T: Copy> {
+    /// `levels[j][i]` is the result of the operation on `data[i..i + 2^j]`
+    levels: Vec<Vec<T>>,
+    op: fn(T, T) -> T,
+}
+
+impl<T: Copy> SparseTable<T> {
+    /// Builds the table of `data` for an associative operation, idempotent for `query`.
+    pub fn new(data: &[T], op: fn(T, T) -> T) -> Self {
+        let mut levels = vec![data.to_vec()];
+        let mut width = 1;
+        while 2 * width <= data.len() {
+            let previous = levels.last().unwrap();
+            let next = (0..=data.len() - 2 * width)
+                .map(|i| op(previous[i], previous[i + width]))
+                .collect();
+            levels.push(next);
+            width *= 2;
+        }
+        SparseTable { levels, op }
+    }
+
+    /// Returns the number of values in the array.
+    pub fn len(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn check_range(&self, range: &Range<usize>) -> AlgorithmResult<()> {
+        if range.start >= range.end || range.end > self.len() {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "range",
+                reason: format!(
+                    "{range:?} is empty or out of an array of length {}",
+                    self.len()
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns the result of the operation on `data[range]` in O(1), with two overlapping
+    /// windows, for an idempotent operation.
+    pub fn query(&self, range: Range<usize>) -> AlgorithmResult<T> {
+        self.check_range(&range)?;
+        let level = (range.end - range.start).ilog2() as usize;
+        let first = self.levels[level][range.start];
+        let second = self.levels[level][range.end - (1 << level)];
+        Ok((self.op)(first, second))
+    }
+
+    /// Returns the result of the operation on `data[range]` in O(log(n)), combining disjoint
+    /// windows from left to right, which is right for any associative operation.
+    pub fn query_disjoint(&self, range: Range<usize>) -> AlgorithmResult<T> {
+        self.check_range(&range)?;
+        let mut start = range.start;
+        let mut result = None;
+        for level in (0..self.levels.len()).rev() {
+            if start + (1 << level) <= range.end {
+                let window = self.levels[level][start];
+                result = Some(result.map_or(window, |so_far| (self.op)(so_far, window)));
+                start += 1 << level;
+            }
+        }
+        Ok(result.expect("the range is not empty"))
+    }
+}
+
+impl<T: Copy + Ord> SparseTable<T> {
+    /// Builds the table of range minimums.
+    pub fn min(data: &[T]) -> Self {
+        Self::new(data, std::cmp::min)
+    }
+
+    /// Builds the table of range maximums.
+    pub fn max(data: &[T]) -> Self {
+        Self::new(data, std::cmp::max)
+    }
+}
+
+impl SparseTable<u64> {
+    /// Builds the table of range greatest common divisors.
+    pub fn gcd(data: &[u64]) -> Self {
+        Self::new(data, |mut a, mut b| {
+            while b != 0 {
+                (a, b) = (b, a % b);
+            }
+            a
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use std::time::Instant;
+
+    fn gcd(a: u64, b: u64) -> u64 {
+        if b == 0 {
+            a
+        } else {
+            gcd(b, a % b)
+        }
+    }
+
+    #[test]
+    fn all_subranges() {
+        let mut rng = ChaCha8Rng::seed_from_u64(122);
+        // multiples of small numbers, so that the gcds are not all 1
+        let data: Vec<u64> = (0..200)
+            .map(|_| rng.gen_range(1..50) * [1, 2, 6, 12][rng.gen_range(0..4)])
+            .collect();
+        let (min, max, gcds) = (
+            SparseTable::min(&data),
+            SparseTable::max(&data),
+            SparseTable::gcd(&data),
+        );
+        for start in 0..data.len() {
+            for end in start + 1..=data.len() {
+                let values = &data[start..end];
+                let expected_min = *values.iter().min().unwrap();
+                let expected_max = *values.iter().max().unwrap();
+                let expected_gcd = values.iter().copied().fold(0, gcd);
+                assert_eq!(min.query(start..end), Ok(expected_min));
+                assert_eq!(max.query(start..end), Ok(expected_max));
+                assert_eq!(gcds.query(start..end), Ok(expected_gcd));
+                assert_eq!(min.query_disjoint(start..end), Ok(expected_min));
+            }
+        }
+    }
+
+    #[test]
+    fn random_arrays() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1220);
+        for len in [1, 2, 3, 7, 64, 65, 1000] {
+            let data: Vec<i64> = (0..len).map(|_| rng.gen_range(-1000..1000)).collect();
+            let table = SparseTable::min(&data);
+            assert_eq!(table.len(), len);
+            for _ in 0..500 {
+                let start = rng.gen_range(0..len);
+                let end = rng.gen_range(start + 1..=len);
+                assert_eq!(
+                    table.query(start..end).unwrap(),
+                    *data[start..end].iter().min().unwrap()
+                );
+            }
+            assert_eq!(table.query(0..len), Ok(*data.iter().min().unwrap()));
+            for (i, &value) in data.iter().enumerate() {
+                assert_eq!(table.query(i..i + 1), Ok(value));
+            }
+        }
+    }
+
+    #[test]
+    fn sums_need_disjoint_windows() {
+        let data: Vec<u64> = (1..=100).collect();
+        let table = SparseTable::new(&data, |a, b| a + b);
+        for start in 0..data.len() {
+            for end in start + 1..=data.len() {
+                let expected: u64 = data[start..end].iter().sum();
+                assert_eq!(table.query_disjoint(start..end), Ok(expected));
+            }
+        }
+        // the overlapping windows count some values twice
+        assert_eq!(table.query(0..3), Ok(1 + 2 + 2 + 3));
+
+        // composing the maps x -> a * x + b is associative but not commutative, so that the
+        // windows must be combined in order
+        fn then(first: (u64, u64), second: (u64, u64)) -> (u64, u64) {
+            (
+                first.0 * second.0 % 1009,
+                (first.1 * second.0 + second.1) % 1009,
+            )
+        }
+        let maps: Vec<(u64, u64)> = (0..50).map(|i| (i % 7 + 1, i % 11)).collect();
+        let table = SparseTable::new(&maps, then);
+        for start in 0..maps.len() {
+            for end in start + 1..=maps.len() {
+                let expected = maps[start + 1..end]
+                    .iter()
+                    .fold(maps[start], |so_far, &map| then(so_far, map));
+                assert_eq!(table.query_disjoint(start..end), Ok(expected));
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_ranges() {
+        let table = SparseTable::max(&[3, 1, 4, 1, 5]);
+        assert!(table.query(2..2).is_err());
+        assert!(table.query(4..6).is_err());
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = 3..1;
+        assert!(table.query_disjoint(reversed).is_err());
+        let empty = SparseTable::<u64>::gcd(&[]);
+        assert!(empty.is_empty());
+        assert!(empty.query(0..1).is_err());
+        assert!(empty.query_disjoint(0..0).is_err());
+    }
+
+    #[test]
+    #[ignore = "slow test for measuring time"]
+    fn constant_time_queries() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1221);
+        let n = 1_000_000;
+        let data: Vec<u32> = (0..n).map(|_| rng.gen()).collect();
+        let now = Instant::now();
+        let table = SparseTable::min(&data);
+        let build_time = now.elapsed();
+        let ranges: Vec<Range<usize>> = (0..10_000_000)
+            .map(|_| {
+                let start = rng.gen_range(0..n);
+                start..rng.gen_range(start + 1..=n)
+            })
+            .collect();
+        let now = Instant::now();
+        let total = ranges
+            .iter()
+            .map(|range| table.query(range.clone()).unwrap() as u64)
+            .sum::<u64>();
+        let query_time = now.elapsed();
+        let now = Instant::now();
+        let disjoint_total = ranges[..100_000]
+            .iter()
+            .map(|range| table.query_disjoint(range.clone()).unwrap() as u64)
+            .sum::<u64>();
+        let disjoint_time = now.elapsed();
+        println!(
+            "built in {build_time:?}, 10^7 queries in {query_time:?}, \
+             10^5 disjoint queries in {disjoint_time:?}, {total} {disjoint_total}"
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+/// A value ordered by `f64::total_cmp`
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Value(f64);
+
+impl Eq for Value {}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Value {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// The median of a stream of values, kept up to date as they come
+/// <https://en.wikipedia.org/wiki/Median#Efficient_computation>
+///
+/// A max-heap holds the lower half of the values and a min-heap the upper half, the lower one
+/// having one value more than the upper one when there is an odd number of them. The median is
+/// then at the top of the lower heap, or halfway between both tops, in O(1), and a new value
+/// goes into the half where it belongs, the largest value of the lower half moving up or the
+/// smallest one of the upper half moving down to keep both halves balanced, in O(log(n)).
+///
+/// The values are ordered by `f64::total_cmp`, so that a `NaN` counts as larger than any other
+/// value.
+#[derive(Clone, Debug, Default)]
+pub struct StreamingMedian {
+    lower: BinaryHeap<Value>,
+    upper: BinaryHeap<Reverse<Value>>,
+}
+
+impl StreamingMedian {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lower.len() + self.upper.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lower.is_empty()
+    }
+
+    /// Adds a value to the stream, in O(log(n)).
+    pub fn push(&mut self, value: f64) {
+        let value = Value(value);
+        if self.lower.peek().is_none_or(|&top| value <= top) {
+            self.lower.push(value);
+            if self.lower.len() > self.upper.len() + 1 {
+                let top = self.lower.pop().unwrap();
+                self.upper.push(Reverse(top));
+            }
+        } else {
+            self.upper.push(Reverse(value));
+            if self.upper.len() > self.lower.len() {
+                let Reverse(bottom) = self.upper.pop().unwrap();
+                self.lower.push(bottom);
+            }
+        }
+    }
+
+    /// Returns the median of the values, the mean of the two middle ones for an even number
+    /// of them, or `None` before the first value.
+    pub fn median(&self) -> Option<f64> {
+        let &Value(low) = self.lower.peek()?;
+        if self.lower.len() > self.upper.len() {
+            return Some(low);
+        }
+        let Reverse(Value(high)) = self.upper.peek().unwrap();
+        Some((low + high) / 2.0)
+    }
+
+    /// Returns the `p`-th percentile of the values, interpolating linearly between the values
+    /// of ranks `floor(r)` and `ceil(r)` in sorted order for `r = p / 100 (n - 1)`, so that the
+    /// 50th percentile is the median.
+    /// <https://en.wikipedia.org/wiki/Percentile#The_linear_interpolation_between_closest_ranks_method>
+    ///
+    /// Every value of the lower heap comes before those of the upper one, so that only the heap
+    /// holding the rank is searched, with a selection in O(n) rather than a sort.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no values, or if `p` is not between 0 and 100.
+    pub fn percentile(&self, p: f64) -> f64 {
+        assert!(
+            (0.0..=100.0).contains(&p),
+            "the percentile must be between 0 and 100, got {p}"
+        );
+        assert!(!self.is_empty(), "there are no values");
+        let rank = p / 100.0 * (self.len() - 1) as f64;
+        let (below, fraction) = (rank.floor() as usize, rank.fract());
+        let low = self.nth(below);
+        if fraction == 0.0 {
+            return low;
+        }
+        low + fraction * (self.nth(below + 1) - low)
+    }
+
+    /// The value of rank `k` in sorted order
+    fn nth(&self, k: usize) -> f64 {
+        let (mut half, k): (Vec<Value>, usize) = if k < self.lower.len() {
+            (self.lower.iter().copied().collect(), k)
+        } else {
+            let upper = self.upper.iter().map(|&Reverse(value)| value).collect();
+            (upper, k - self.lower.len())
+        };
+        half.select_nth_unstable(k).1 .0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    /// The percentile of sorted values, interpolated linearly
+    fn sorted_percentile(sorted: &[f64], p: f64) -> f64 {
+        let rank = p / 100.0 * (sorted.len() - 1) as f64;
+        let below = rank.floor() as usize;
+        let above = (below + 1).min(sorted.len() - 1);
+        sorted[below] + rank.fract() * (sorted[above] - sorted[below])
+    }
+
+    #[test]
+    fn small_streams() {
+        let mut median = StreamingMedian::new();
+        assert_eq!(median.median(), None);
+        assert!(median.is_empty());
+        for (value, expected) in [(5.0, 5.0), (1.0, 3.0), (3.0, 3.0), (10.0, 4.0), (-2.0, 3.0)] {
+            median.push(value);
+            assert_eq!(median.median(), Some(expected));
+        }
+        assert_eq!(median.len(), 5);
+        // -2, 1, 3, 5, 10
+        assert_eq!(median.percentile(0.0), -2.0);
+        assert_eq!(median.percentile(100.0), 10.0);
+        assert_eq!(median.percentile(50.0), 3.0);
+        assert_eq!(median.percentile(75.0), 5.0);
+        assert_eq!(median.percentile(87.5), 7.5);
+        assert_eq!(median.percentile(12.5), -0.5);
+        let mut single = StreamingMedian::new();
+        single.push(4.0);
+        assert_eq!(single.percentile(30.0), 4.0);
+    }
+
+    #[test]
+    fn agrees_with_sorting() {
+        let mut rng = ChaCha8Rng::seed_from_u64(167);
+        let mut median = StreamingMedian::new();
+        let mut values = vec![];
+        for _ in 0..1000 {
+            // repeated values too
+            let value = if rng.gen_bool(0.2) {
+                rng.gen_range(0..5) as f64
+            } else {
+                rng.gen_range(-1000.0..1000.0)
+            };
+            median.push(value);
+            values.push(value);
+            let mut sorted = values.clone();
+            sorted.sort_by(f64::total_cmp);
+            let n = sorted.len();
+            let expected = if n % 2 == 1 {
+                sorted[n / 2]
+            } else {
+                (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
+            };
+            assert_eq!(median.median(), Some(expected));
+            if n % 100 == 0 {
+                for p in [0.0, 1.0, 10.0, 25.0, 49.9, 50.0, 63.3, 90.0, 99.0, 100.0] {
+                    let found = median.percentile(p);
+                    assert!((found - sorted_percentile(&sorted, p)).abs() < 1e-9);
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "there are no values")]
+    fn percentile_of_nothing() {
+        StreamingMedian::new().percentile(50.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "the percentile must be between 0 and 100, got 101")]
+    fn percentile_out_of_range() {
+        let mut median = StreamingMedian::new();
+        median.push(1.0);
+        median.percentile(101.0);
+    }
+}
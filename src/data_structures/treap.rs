@@ -1,91 +1,278 @@
-use std::{
-    cmp::Ordering,
-    iter::FromIterator,
-    mem,
-    ops::Not,
-    time::{SystemTime, UNIX_EPOCH},
-};
-
-/// An internal node of an `Treap`.
-struct TreapNode<T: Ord> {
-    value: T,
-    priority: usize,
-    left: Option<Box<TreapNode<T>>>,
-    right: Option<Box<TreapNode<T>>>,
+use crate::error::{AlgorithmError, AlgorithmResult};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::{borrow::Borrow, cmp::Ordering, iter::FromIterator};
+
+type Link<K, V> = Option<Box<TreapNode<K, V>>>;
+
+/// An internal node of a `Treap`.
+struct TreapNode<K, V> {
+    key: K,
+    value: V,
+    priority: u64,
+    /// The number of nodes in the subtree of this node
+    size: usize,
+    left: Link<K, V>,
+    right: Link<K, V>,
 }
 
-/// A set based on a Treap (Randomized Binary Search Tree).
+/// A map based on a Treap (Randomized Binary Search Tree).
 ///
 /// A Treap is a self-balancing binary search tree. It matains a priority value for each node, such
 /// that for every node, its children will have lower priority than itself. So, by just looking at
 /// the priority, it is like a heap, and this is where the name, Treap, comes from, Tree + Heap.
-pub struct Treap<T: Ord> {
-    root: Option<Box<TreapNode<T>>>,
-    length: usize,
+///
+/// With random priorities, the tree has the shape of a binary search tree built by inserting the
+/// keys in a random order, whose depth is O(log(n)) with high probability. Every operation is
+/// built on `split` and `merge`, which recurse along one path from the root, so that their
+/// recursion is as deep as the tree. The priorities come from a seedable generator, so that
+/// the shape of the tree can be reproduced.
+pub struct Treap<K: Ord, V> {
+    root: Link<K, V>,
+    rng: ChaCha8Rng,
+}
+
+fn size<K, V>(link: &Link<K, V>) -> usize {
+    link.as_ref().map_or(0, |node| node.size)
 }
 
-/// Refers to the left or right subtree of a `Treap`.
-#[derive(Clone, Copy)]
-enum Side {
-    Left,
-    Right,
+impl<K, V> TreapNode<K, V> {
+    fn update_size(&mut self) {
+        self.size = 1 + size(&self.left) + size(&self.right);
+    }
 }
 
-impl<T: Ord> Treap<T> {
-    pub fn new() -> Treap<T> {
-        Treap {
-            root: None,
-            length: 0,
+/// Splits a tree into the nodes whose keys go to the left, and the others.
+///
+/// The keys going to the left must be the lowest ones.
+fn split<K, V>(link: Link<K, V>, goes_left: &impl Fn(&K) -> bool) -> (Link<K, V>, Link<K, V>) {
+    match link {
+        None => (None, None),
+        Some(mut node) => {
+            if goes_left(&node.key) {
+                let (middle, right) = split(node.right.take(), goes_left);
+                node.right = middle;
+                node.update_size();
+                (Some(node), right)
+            } else {
+                let (left, middle) = split(node.left.take(), goes_left);
+                node.left = middle;
+                node.update_size();
+                (left, Some(node))
+            }
         }
     }
+}
 
-    /// Returns `true` if the tree contains a value.
-    pub fn contains(&self, value: &T) -> bool {
+/// Merges two trees, the keys of the first all lower than those of the second.
+fn merge<K, V>(left: Link<K, V>, right: Link<K, V>) -> Link<K, V> {
+    match (left, right) {
+        (None, tree) | (tree, None) => tree,
+        (Some(mut left), Some(mut right)) => {
+            if left.priority > right.priority {
+                left.right = merge(left.right.take(), Some(right));
+                left.update_size();
+                Some(left)
+            } else {
+                right.left = merge(Some(left), right.left.take());
+                right.update_size();
+                Some(right)
+            }
+        }
+    }
+}
+
+impl<K: Ord, V> Treap<K, V> {
+    /// Creates an empty tree, drawing its priorities from a generator seeded by the system.
+    pub fn new() -> Treap<K, V> {
+        Self::with_rng(ChaCha8Rng::from_entropy())
+    }
+
+    /// Creates an empty tree whose priorities are reproducible from `seed`.
+    pub fn with_seed(seed: u64) -> Treap<K, V> {
+        Self::with_rng(ChaCha8Rng::seed_from_u64(seed))
+    }
+
+    fn with_rng(rng: ChaCha8Rng) -> Treap<K, V> {
+        Treap { root: None, rng }
+    }
+
+    /// Returns a reference to the value of a key.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
         let mut current = &self.root;
         while let Some(node) = current {
-            current = match value.cmp(&node.value) {
-                Ordering::Equal => return true,
+            current = match key.cmp(node.key.borrow()) {
+                Ordering::Equal => return Some(&node.value),
                 Ordering::Less => &node.left,
                 Ordering::Greater => &node.right,
             }
         }
-        false
+        None
     }
 
-    /// Adds a value to the tree
+    /// Returns a mutable reference to the value of a key.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut current = &mut self.root;
+        while let Some(node) = current {
+            current = match key.cmp(node.key.borrow()) {
+                Ordering::Equal => return Some(&mut node.value),
+                Ordering::Less => &mut node.left,
+                Ordering::Greater => &mut node.right,
+            }
+        }
+        None
+    }
+
+    /// Returns `true` if the tree contains a key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Adds a key with its value to the tree, in O(log(n)).
     ///
-    /// Returns `true` if the tree did not yet contain the value.
-    pub fn insert(&mut self, value: T) -> bool {
-        let inserted = insert(&mut self.root, value);
-        if inserted {
-            self.length += 1;
+    /// Returns the previous value of the key, if the tree already contained it.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(old) = self.get_mut(&key) {
+            return Some(std::mem::replace(old, value));
         }
-        inserted
+        let node = Box::new(TreapNode {
+            priority: self.rng.gen(),
+            size: 1,
+            left: None,
+            right: None,
+            key,
+            value,
+        });
+        let (left, right) = split(self.root.take(), &|k| *k < node.key);
+        self.root = merge(merge(left, Some(node)), right);
+        None
     }
 
-    /// Removes a value from the tree.
+    /// Removes a key from the tree, in O(log(n)).
     ///
-    /// Returns `true` if the tree contained the value.
-    pub fn remove(&mut self, value: &T) -> bool {
-        let removed = remove(&mut self.root, value);
-        if removed {
-            self.length -= 1;
+    /// Returns its value, if the tree contained the key.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let (left, rest) = split(self.root.take(), &|k: &K| k.borrow() < key);
+        let (found, right) = split(rest, &|k: &K| k.borrow() == key);
+        self.root = merge(left, right);
+        found.map(|node| node.value)
+    }
+
+    /// Splits the tree into the keys lower than `key`, and the others, in O(log(n)).
+    pub fn split(mut self, key: &K) -> (Treap<K, V>, Treap<K, V>) {
+        let (left, right) = split(self.root.take(), &|k| k < key);
+        let seed = self.rng.gen();
+        (
+            Treap {
+                root: left,
+                rng: self.rng,
+            },
+            Treap {
+                root: right,
+                rng: ChaCha8Rng::seed_from_u64(seed),
+            },
+        )
+    }
+
+    /// Merges two trees in O(log(n)), if all the keys of `left` are lower than those of `right`.
+    pub fn merge(left: Treap<K, V>, right: Treap<K, V>) -> AlgorithmResult<Treap<K, V>> {
+        if let (Some((last, _)), Some((first, _))) = (left.last(), right.first()) {
+            if last >= first {
+                return Err(AlgorithmError::InvalidParameter {
+                    argument: "right",
+                    reason: "the keys must all be greater than those of the left tree".to_string(),
+                });
+            }
         }
-        removed
+        let Treap { root, rng } = left;
+        Ok(Treap {
+            root: merge(root, right.root),
+            rng,
+        })
     }
 
-    /// Returns the number of values in the tree.
+    /// Returns the entry with the lowest key.
+    pub fn first(&self) -> Option<(&K, &V)> {
+        let mut node = self.root.as_ref()?;
+        while let Some(left) = &node.left {
+            node = left;
+        }
+        Some((&node.key, &node.value))
+    }
+
+    /// Returns the entry with the greatest key.
+    pub fn last(&self) -> Option<(&K, &V)> {
+        let mut node = self.root.as_ref()?;
+        while let Some(right) = &node.right {
+            node = right;
+        }
+        Some((&node.key, &node.value))
+    }
+
+    /// Returns the number of keys lower than `key`, in O(log(n)).
+    pub fn rank<Q>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut rank = 0;
+        let mut current = &self.root;
+        while let Some(node) = current {
+            if node.key.borrow() < key {
+                rank += size(&node.left) + 1;
+                current = &node.right;
+            } else {
+                current = &node.left;
+            }
+        }
+        rank
+    }
+
+    /// Returns the entry with the `k`-th lowest key, counting from 0, in O(log(n)).
+    pub fn kth(&self, mut k: usize) -> Option<(&K, &V)> {
+        let mut current = &self.root;
+        while let Some(node) = current {
+            let left = size(&node.left);
+            current = match k.cmp(&left) {
+                Ordering::Equal => return Some((&node.key, &node.value)),
+                Ordering::Less => &node.left,
+                Ordering::Greater => {
+                    k -= left + 1;
+                    &node.right
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns the number of keys in the tree.
     pub fn len(&self) -> usize {
-        self.length
+        size(&self.root)
     }
 
-    /// Returns `true` if the tree contains no values.
+    /// Returns `true` if the tree contains no keys.
     pub fn is_empty(&self) -> bool {
-        self.length == 0
+        self.root.is_none()
     }
 
     /// Returns an iterator that visits the nodes in the tree in order.
-    fn node_iter(&self) -> NodeIter<T> {
+    fn node_iter(&self) -> NodeIter<'_, K, V> {
         let mut node_iter = NodeIter { stack: Vec::new() };
         // Initialize stack with path to leftmost child
         let mut child = &self.root;
@@ -96,154 +283,103 @@ impl<T: Ord> Treap<T> {
         node_iter
     }
 
-    /// Returns an iterator that visits the values in the tree in ascending order.
-    pub fn iter(&self) -> Iter<T> {
+    /// Returns an iterator that visits the entries in the tree in ascending order of keys.
+    pub fn iter(&self) -> Iter<'_, K, V> {
         Iter {
             node_iter: self.node_iter(),
         }
     }
 }
 
-/// Generating random number, should use rand::Rng if possible.
-fn rand() -> usize {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .subsec_nanos() as usize
+impl<K: Ord, V> Default for Treap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-/// Recursive helper function for `Treap` insertion.
-fn insert<T: Ord>(tree: &mut Option<Box<TreapNode<T>>>, value: T) -> bool {
-    if let Some(node) = tree {
-        let inserted = match value.cmp(&node.value) {
-            Ordering::Equal => false,
-            Ordering::Less => insert(&mut node.left, value),
-            Ordering::Greater => insert(&mut node.right, value),
-        };
-        if inserted {
-            node.rebalance();
+impl<K: Ord, V> FromIterator<(K, V)> for Treap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut tree = Treap::new();
+        for (key, value) in iter {
+            tree.insert(key, value);
         }
-        inserted
-    } else {
-        *tree = Some(Box::new(TreapNode {
-            value,
-            priority: rand(),
-            left: None,
-            right: None,
-        }));
-        true
+        tree
     }
 }
 
-/// Recursive helper function for `Treap` deletion
-fn remove<T: Ord>(tree: &mut Option<Box<TreapNode<T>>>, value: &T) -> bool {
-    if let Some(node) = tree {
-        let removed = match value.cmp(&node.value) {
-            Ordering::Less => remove(&mut node.left, value),
-            Ordering::Greater => remove(&mut node.right, value),
-            Ordering::Equal => {
-                *tree = match (node.left.take(), node.right.take()) {
-                    (None, None) => None,
-                    (Some(b), None) | (None, Some(b)) => Some(b),
-                    (Some(left), Some(right)) => {
-                        let side = match left.priority.cmp(&right.priority) {
-                            Ordering::Greater => Side::Right,
-                            _ => Side::Left,
-                        };
-                        node.left = Some(left);
-                        node.right = Some(right);
-                        node.rotate(side);
-                        remove(node.child_mut(side), value);
-                        Some(tree.take().unwrap())
-                    }
-                };
-                return true;
-            }
-        };
-        if removed {
-            node.rebalance();
-        }
-        removed
-    } else {
-        false
-    }
+/// A sorted set answering order statistics, over a `Treap` whose nodes know the sizes of their
+/// subtrees
+/// <https://en.wikipedia.org/wiki/Order_statistic_tree>
+///
+/// `rank` counts the values lower than a value and `select` finds the value of a rank, both
+/// walking down one path of the tree in O(log(n)) with high probability, like `insert` and
+/// `remove`.
+pub struct OrderStatisticTree<T: Ord> {
+    treap: Treap<T, ()>,
 }
 
-impl<T: Ord> TreapNode<T> {
-    /// Returns a reference to the left or right child.
-    fn child(&self, side: Side) -> &Option<Box<TreapNode<T>>> {
-        match side {
-            Side::Left => &self.left,
-            Side::Right => &self.right,
+impl<T: Ord> OrderStatisticTree<T> {
+    /// Creates an empty set, drawing its priorities from a generator seeded by the system.
+    pub fn new() -> Self {
+        OrderStatisticTree {
+            treap: Treap::new(),
         }
     }
 
-    /// Returns a mutable reference to the left or right child.
-    fn child_mut(&mut self, side: Side) -> &mut Option<Box<TreapNode<T>>> {
-        match side {
-            Side::Left => &mut self.left,
-            Side::Right => &mut self.right,
+    /// Creates an empty set whose priorities are reproducible from `seed`.
+    pub fn with_seed(seed: u64) -> Self {
+        OrderStatisticTree {
+            treap: Treap::with_seed(seed),
         }
     }
 
-    /// Returns the priority of the left or right subtree.
-    fn priority(&self, side: Side) -> usize {
-        self.child(side).as_ref().map_or(0, |n| n.priority)
+    /// Adds a value, and returns `true` if it was not in the set yet.
+    pub fn insert(&mut self, value: T) -> bool {
+        self.treap.insert(value, ()).is_none()
     }
 
-    /// Performs a left or right rotation
-    fn rotate(&mut self, side: Side) {
-        if self.child_mut(!side).is_none() {
-            return;
-        }
+    /// Removes a value, and returns `true` if it was in the set.
+    pub fn remove(&mut self, value: &T) -> bool {
+        self.treap.remove(value).is_some()
+    }
 
-        let mut subtree = self.child_mut(!side).take().unwrap();
-        *self.child_mut(!side) = subtree.child_mut(side).take();
-        // Swap root and child nodes in memory
-        mem::swap(self, subtree.as_mut());
-        // Set old root (subtree) as child of new root (self)
-        *self.child_mut(side) = Some(subtree);
+    pub fn contains(&self, value: &T) -> bool {
+        self.treap.contains_key(value)
     }
 
-    /// Performs left or right tree rotations to balance this node.
-    fn rebalance(&mut self) {
-        match (
-            self.priority,
-            self.priority(Side::Left),
-            self.priority(Side::Right),
-        ) {
-            (v, p, q) if p >= q && p > v => self.rotate(Side::Right),
-            (v, p, q) if p < q && q > v => self.rotate(Side::Left),
-            _ => (),
-        };
+    /// Returns the number of values lower than `value`, whether it is in the set or not.
+    pub fn rank(&self, value: &T) -> usize {
+        self.treap.rank(value)
     }
 
-    #[cfg(test)]
-    fn is_valid(&self) -> bool {
-        self.priority >= self.priority(Side::Left) && self.priority >= self.priority(Side::Right)
+    /// Returns the value with `rank` values lower than it, counting from 0.
+    pub fn select(&self, rank: usize) -> Option<&T> {
+        self.treap.kth(rank).map(|(value, _)| value)
     }
-}
 
-impl<T: Ord> Default for Treap<T> {
-    fn default() -> Self {
-        Self::new()
+    pub fn len(&self) -> usize {
+        self.treap.len()
     }
-}
 
-impl Not for Side {
-    type Output = Side;
+    pub fn is_empty(&self) -> bool {
+        self.treap.is_empty()
+    }
 
-    fn not(self) -> Self::Output {
-        match self {
-            Side::Left => Side::Right,
-            Side::Right => Side::Left,
-        }
+    /// Returns an iterator over the values in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.treap.iter().map(|(value, _)| value)
     }
 }
 
-impl<T: Ord> FromIterator<T> for Treap<T> {
+impl<T: Ord> Default for OrderStatisticTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> FromIterator<T> for OrderStatisticTree<T> {
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
-        let mut tree = Treap::new();
+        let mut tree = OrderStatisticTree::new();
         for value in iter {
             tree.insert(value);
         }
@@ -251,15 +387,15 @@ impl<T: Ord> FromIterator<T> for Treap<T> {
     }
 }
 
-/// An iterator over the nodes of an `Treap`.
+/// An iterator over the nodes of a `Treap`.
 ///
 /// This struct is created by the `node_iter` method of `Treap`.
-struct NodeIter<'a, T: Ord> {
-    stack: Vec<&'a TreapNode<T>>,
+struct NodeIter<'a, K, V> {
+    stack: Vec<&'a TreapNode<K, V>>,
 }
 
-impl<'a, T: Ord> Iterator for NodeIter<'a, T> {
-    type Item = &'a TreapNode<T>;
+impl<'a, K, V> Iterator for NodeIter<'a, K, V> {
+    type Item = &'a TreapNode<K, V>;
 
     fn next(&mut self) -> Option<Self::Item> {
         if let Some(node) = self.stack.pop() {
@@ -276,77 +412,219 @@ impl<'a, T: Ord> Iterator for NodeIter<'a, T> {
     }
 }
 
-/// An iterator over the items of an `Treap`.
+/// An iterator over the entries of a `Treap`.
 ///
 /// This struct is created by the `iter` method of `Treap`.
-pub struct Iter<'a, T: Ord> {
-    node_iter: NodeIter<'a, T>,
+pub struct Iter<'a, K, V> {
+    node_iter: NodeIter<'a, K, V>,
 }
 
-impl<'a, T: Ord> Iterator for Iter<'a, T> {
-    type Item = &'a T;
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
 
-    fn next(&mut self) -> Option<&'a T> {
-        match self.node_iter.next() {
-            Some(node) => Some(&node.value),
-            None => None,
-        }
+    fn next(&mut self) -> Option<Self::Item> {
+        self.node_iter.next().map(|node| (&node.key, &node.value))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Treap;
+    use super::{Link, OrderStatisticTree, Treap};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use std::collections::{BTreeMap, BTreeSet};
+
+    /// Returns `true` if all nodes in the tree have a priority at least that of their children
+    /// and the right size.
+    fn is_valid<K: Ord, V>(tree: &Treap<K, V>) -> bool {
+        tree.node_iter().all(|n| {
+            let priority = |link: &Link<K, V>| link.as_ref().map_or(0, |child| child.priority);
+            n.priority >= priority(&n.left)
+                && n.priority >= priority(&n.right)
+                && n.size == 1 + super::size(&n.left) + super::size(&n.right)
+        })
+    }
 
-    /// Returns `true` if all nodes in the tree are valid.
-    fn is_valid<T: Ord>(tree: &Treap<T>) -> bool {
-        tree.node_iter().all(|n| n.is_valid())
+    fn depth<K, V>(link: &Link<K, V>) -> usize {
+        link.as_ref()
+            .map_or(0, |node| 1 + depth(&node.left).max(depth(&node.right)))
+    }
+
+    fn set<I: IntoIterator<Item = i32>>(keys: I) -> Treap<i32, ()> {
+        let mut tree = Treap::with_seed(120);
+        for key in keys {
+            tree.insert(key, ());
+        }
+        tree
     }
 
     #[test]
     fn len() {
-        let tree: Treap<_> = (1..4).collect();
+        let tree = set(1..4);
         assert_eq!(tree.len(), 3);
     }
 
     #[test]
     fn contains() {
-        let tree: Treap<_> = (1..4).collect();
-        assert!(tree.contains(&1));
-        assert!(!tree.contains(&4));
+        let tree = set(1..4);
+        assert!(tree.contains_key(&1));
+        assert!(!tree.contains_key(&4));
     }
 
     #[test]
     fn insert() {
         let mut tree = Treap::new();
         // First insert succeeds
-        assert!(tree.insert(1));
-        // Second insert fails
-        assert!(!tree.insert(1));
+        assert_eq!(tree.insert(1, "a"), None);
+        // Second insert replaces the value
+        assert_eq!(tree.insert(1, "b"), Some("a"));
+        assert_eq!(tree.get(&1), Some(&"b"));
     }
 
     #[test]
     fn remove() {
-        let mut tree: Treap<_> = (1..8).collect();
+        let mut tree = set(1..8);
         // First remove succeeds
-        assert!(tree.remove(&4));
+        assert_eq!(tree.remove(&4), Some(()));
         // Second remove fails
-        assert!(!tree.remove(&4));
+        assert_eq!(tree.remove(&4), None);
     }
 
     #[test]
     fn sorted() {
-        let tree: Treap<_> = (1..8).rev().collect();
-        assert!((1..8).eq(tree.iter().copied()));
+        let tree = set((1..8).rev());
+        assert!((1..8).eq(tree.iter().map(|(&key, _)| key)));
     }
 
     #[test]
     fn valid() {
-        let mut tree: Treap<_> = (1..8).collect();
+        let mut tree = set(1..8);
         assert!(is_valid(&tree));
         for x in 1..8 {
             tree.remove(&x);
             assert!(is_valid(&tree));
         }
     }
+
+    #[test]
+    fn against_btree_map() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1200);
+        let mut tree = Treap::with_seed(1201);
+        let mut model = BTreeMap::new();
+        for step in 0..5000 {
+            let key = rng.gen_range(0..500);
+            match rng.gen_range(0..4) {
+                0 | 1 => assert_eq!(tree.insert(key, step), model.insert(key, step)),
+                2 => assert_eq!(tree.remove(&key), model.remove(&key)),
+                _ => assert_eq!(tree.get(&key), model.get(&key)),
+            }
+            assert_eq!(tree.len(), model.len());
+        }
+        assert!(is_valid(&tree));
+        assert!(tree.iter().eq(model.iter()));
+        assert_eq!(tree.first(), model.iter().next());
+        assert_eq!(tree.last(), model.iter().next_back());
+    }
+
+    #[test]
+    fn split_and_merge() {
+        let tree: Treap<_, _> = (0..100).map(|key| (key * 2, key)).collect();
+        let (low, high) = tree.split(&51);
+        assert!(is_valid(&low) && is_valid(&high));
+        assert!(low
+            .iter()
+            .map(|(&key, _)| key)
+            .eq((0..26).map(|key| key * 2)));
+        assert!(high
+            .iter()
+            .map(|(&key, _)| key)
+            .eq((26..100).map(|key| key * 2)));
+        let (empty, all) = high.split(&0);
+        assert!(empty.is_empty());
+        assert_eq!(all.len(), 74);
+
+        let merged = Treap::merge(low, all).unwrap();
+        assert!(is_valid(&merged));
+        assert!(merged
+            .iter()
+            .map(|(&key, &value)| (key, value))
+            .eq((0..100).map(|key| (key * 2, key))));
+        let merged = Treap::merge(empty, merged).unwrap();
+        assert_eq!(merged.len(), 100);
+
+        let (low, high) = merged.split(&100);
+        assert!(Treap::merge(high, low).is_err());
+        assert!(Treap::merge(set([1, 2]), set([2, 3])).is_err());
+    }
+
+    #[test]
+    fn rank_and_kth() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1202);
+        let mut tree = Treap::with_seed(1203);
+        for _ in 0..1000 {
+            let key = rng.gen_range(0..10_000);
+            tree.insert(key, key.to_string());
+        }
+        for (k, (key, value)) in tree.iter().enumerate() {
+            assert_eq!(tree.kth(k), Some((key, value)));
+            assert_eq!(tree.rank(key), k);
+        }
+        assert_eq!(tree.kth(tree.len()), None);
+        assert_eq!(tree.rank(&-1), 0);
+        assert_eq!(tree.rank(&10_000), tree.len());
+        // a missing key ranks right after the keys below it
+        let missing = (0..).find(|key| !tree.contains_key(key)).unwrap();
+        assert_eq!(
+            tree.rank(&missing),
+            tree.iter().filter(|(&key, _)| key < missing).count()
+        );
+    }
+
+    #[test]
+    fn order_statistics() {
+        let mut rng = ChaCha8Rng::seed_from_u64(141);
+        let mut tree = OrderStatisticTree::with_seed(1411);
+        let mut model = BTreeSet::new();
+        for _ in 0..3000 {
+            let value = rng.gen_range(0..1000);
+            if rng.gen_bool(0.7) {
+                assert_eq!(tree.insert(value), model.insert(value));
+            } else {
+                assert_eq!(tree.remove(&value), model.remove(&value));
+            }
+        }
+        assert_eq!(tree.len(), model.len());
+        for (rank, value) in model.iter().enumerate() {
+            assert_eq!(tree.rank(value), rank);
+            assert_eq!(tree.select(tree.rank(value)), Some(value));
+            assert_eq!(tree.select(rank), Some(value));
+        }
+        assert_eq!(tree.select(tree.len()), None);
+        for value in -1..1001 {
+            assert_eq!(tree.contains(&value), model.contains(&value));
+            assert_eq!(tree.rank(&value), model.range(..value).count());
+        }
+        assert!(tree.iter().eq(model.iter()));
+
+        let words: OrderStatisticTree<&str> =
+            ["pear", "apple", "fig", "apple"].into_iter().collect();
+        assert_eq!(words.len(), 3);
+        assert_eq!(words.select(1), Some(&"fig"));
+        assert_eq!(words.rank(&"banana"), 1);
+        assert!(OrderStatisticTree::<i32>::default().is_empty());
+    }
+
+    #[test]
+    fn depth_is_logarithmic() {
+        // sorted insertions would make a plain binary search tree a path
+        let n = 100_000;
+        let tree = set(0..n);
+        assert_eq!(tree.len(), n as usize);
+        assert!(is_valid(&tree));
+        let log = (n as f64).log2();
+        assert!((depth(&tree.root) as f64) < 3.0 * log);
+        let (low, high) = tree.split(&(n / 2));
+        assert!((depth(&low.root) as f64) < 3.0 * log);
+        assert_eq!(Treap::merge(low, high).unwrap().len(), n as usize);
+    }
 }
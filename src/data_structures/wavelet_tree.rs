@@ -0,0 +1,310 @@
+/// A sequence of bits packed in words, with the number of ones before every word, for ranks in
+/// O(1) and selects in O(log(n))
+struct BitVector {
+    words: Vec<u64>,
+    /// `ones_before[w]` is the number of ones in the words before `w`
+    ones_before: Vec<usize>,
+    len: usize,
+}
+
+impl BitVector {
+    fn new(bits: impl Iterator<Item = bool>) -> Self {
+        let mut words = vec![];
+        let mut len = 0;
+        for bit in bits {
+            if len % 64 == 0 {
+                words.push(0);
+            }
+            words[len / 64] |= (bit as u64) << (len % 64);
+            len += 1;
+        }
+        let mut ones_before = Vec::with_capacity(words.len() + 1);
+        ones_before.push(0);
+        for word in &words {
+            ones_before.push(ones_before.last().unwrap() + word.count_ones() as usize);
+        }
+        BitVector {
+            words,
+            ones_before,
+            len,
+        }
+    }
+
+    fn get(&self, i: usize) -> bool {
+        self.words[i / 64] >> (i % 64) & 1 == 1
+    }
+
+    /// The number of ones in the first `i` bits
+    fn rank1(&self, i: usize) -> usize {
+        let (word, bit) = (i / 64, i % 64);
+        let partial = if bit == 0 {
+            0
+        } else {
+            (self.words[word] << (64 - bit)).count_ones() as usize
+        };
+        self.ones_before[word] + partial
+    }
+
+    fn rank0(&self, i: usize) -> usize {
+        i - self.rank1(i)
+    }
+
+    /// The position of the bit of index `k` among those equal to `bit`
+    fn select(&self, bit: bool, k: usize) -> usize {
+        let before = |w: usize| {
+            if bit {
+                self.ones_before[w]
+            } else {
+                (64 * w).min(self.len) - self.ones_before[w]
+            }
+        };
+        // the first word with more than k such bits up to its end
+        let (mut word, mut last) = (0, self.words.len());
+        while word < last {
+            let middle = (word + last) / 2;
+            if before(middle + 1) <= k {
+                word = middle + 1;
+            } else {
+                last = middle;
+            }
+        }
+        let mut remaining = k - before(word);
+        let mut i = 64 * word;
+        loop {
+            if self.get(i) == bit {
+                if remaining == 0 {
+                    return i;
+                }
+                remaining -= 1;
+            }
+            i += 1;
+        }
+    }
+}
+
+/// A sequence of integers below `alphabet_size`, with rank and range counting in O(log(σ)) for
+/// an alphabet of σ values, and select in O(log(σ) log(n))
+/// <https://en.wikipedia.org/wiki/Wavelet_Tree>
+///
+/// This is the level-wise layout of the wavelet tree, also called a wavelet matrix, without
+/// pointers: there is one bit vector per bit of the values, from the highest one. At every
+/// level, the bit vector holds that bit of every value, and the values are then stably
+/// partitioned, those with a 0 before those with a 1, for the next level, so that the values
+/// sharing their high bits stay together. A position then follows a value down the levels with
+/// a rank at each one, in O(1) with the counts of ones stored every 64 bits. The bit vectors
+/// take n log(σ) bits in all, about as much as the values themselves.
+pub struct WaveletTree {
+    len: usize,
+    /// One bit vector per bit of the values, from the highest one
+    levels: Vec<BitVector>,
+    /// The number of zeros at every level
+    zeros: Vec<usize>,
+}
+
+impl WaveletTree {
+    /// Builds the tree in O(n log(σ)).
+    ///
+    /// # Panics
+    ///
+    /// Panics if a value is not below `alphabet_size`.
+    pub fn new(arr: &[u32], alphabet_size: u32) -> Self {
+        if let Some(&v) = arr.iter().find(|&&v| v >= alphabet_size) {
+            panic!("the value {v} is not below the alphabet size {alphabet_size}");
+        }
+        let bits = u32::BITS - alphabet_size.saturating_sub(1).leading_zeros();
+        let mut values = arr.to_vec();
+        let mut levels = vec![];
+        let mut zeros = vec![];
+        for level in (0..bits).rev() {
+            let bit = |v: u32| v >> level & 1 == 1;
+            levels.push(BitVector::new(values.iter().map(|&v| bit(v))));
+            let (mut next, ones): (Vec<u32>, Vec<u32>) = values.iter().partition(|&&v| !bit(v));
+            zeros.push(next.len());
+            next.extend(ones);
+            values = next;
+        }
+        WaveletTree {
+            len: arr.len(),
+            levels,
+            zeros,
+        }
+    }
+
+    /// The bit of `v` at the level of index `level`
+    fn bit(&self, v: u32, level: usize) -> bool {
+        let shift = self.levels.len() - 1 - level;
+        shift < 32 && v >> shift & 1 == 1
+    }
+
+    /// Follows the range `start..end` of a level down to the next one, on the side of `bit`
+    fn descend(&self, level: usize, bit: bool, start: usize, end: usize) -> (usize, usize) {
+        let vector = &self.levels[level];
+        if bit {
+            let zeros = self.zeros[level];
+            (zeros + vector.rank1(start), zeros + vector.rank1(end))
+        } else {
+            (vector.rank0(start), vector.rank0(end))
+        }
+    }
+
+    /// Whether `v` fits in the bits of the levels
+    fn fits(&self, v: u32) -> bool {
+        self.levels.len() >= 32 || v >> self.levels.len() == 0
+    }
+
+    /// Returns the number of occurrences of `v` in the first `i` values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is beyond the end of the sequence.
+    pub fn rank(&self, v: u32, i: usize) -> usize {
+        assert!(i <= self.len, "{i} is beyond the {} values", self.len);
+        if !self.fits(v) {
+            return 0;
+        }
+        // the values equal to v, among the first i, end up in start..end at the bottom
+        let (mut start, mut end) = (0, i);
+        for level in 0..self.levels.len() {
+            (start, end) = self.descend(level, self.bit(v, level), start, end);
+        }
+        end - start
+    }
+
+    /// Returns the position of the occurrence of index `k` of `v`, counting from 0, in
+    /// O(log(σ) log(n)), since select needs a binary search in the bit vectors.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `v` occurs at most `k` times.
+    pub fn select(&self, v: u32, k: usize) -> usize {
+        let count = self.rank(v, self.len);
+        assert!(k < count, "{v} occurs {count} times, not more than {k}");
+        // the occurrences of v are together at the bottom, from where the first one lands
+        let mut start = 0;
+        for level in 0..self.levels.len() {
+            start = self.descend(level, self.bit(v, level), start, start).0;
+        }
+        // and going back up undoes the stable partitions
+        let mut position = start + k;
+        for level in (0..self.levels.len()).rev() {
+            position = if self.bit(v, level) {
+                self.levels[level].select(true, position - self.zeros[level])
+            } else {
+                self.levels[level].select(false, position)
+            };
+        }
+        position
+    }
+
+    /// The number of values below `v` in `l..r`
+    fn count_below(&self, v: u64, l: usize, r: usize) -> usize {
+        if v >> self.levels.len() != 0 {
+            return r - l;
+        }
+        let v = v as u32;
+        let (mut start, mut end) = (l, r);
+        let mut count = 0;
+        for level in 0..self.levels.len() {
+            if self.bit(v, level) {
+                // the values with a 0 here, and the same bits above, are all below v
+                let vector = &self.levels[level];
+                count += vector.rank0(end) - vector.rank0(start);
+            }
+            (start, end) = self.descend(level, self.bit(v, level), start, end);
+        }
+        count
+    }
+
+    /// Returns the number of values in `lo..=hi` at the positions `l..r`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `l > r` or `r` is beyond the end of the sequence.
+    pub fn range_count(&self, lo: u32, hi: u32, l: usize, r: usize) -> usize {
+        assert!(
+            l <= r && r <= self.len,
+            "{l}..{r} is not a range of {} values",
+            self.len
+        );
+        if lo > hi {
+            return 0;
+        }
+        self.count_below(hi as u64 + 1, l, r) - self.count_below(lo as u64, l, r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn small_sequence() {
+        let arr = [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+        let tree = WaveletTree::new(&arr, 10);
+        assert_eq!(tree.rank(5, 11), 3);
+        assert_eq!(tree.rank(5, 8), 1);
+        assert_eq!(tree.rank(1, 0), 0);
+        assert_eq!(tree.rank(7, 11), 0);
+        assert_eq!(tree.rank(1000, 11), 0);
+        assert_eq!(tree.select(5, 0), 4);
+        assert_eq!(tree.select(5, 2), 10);
+        assert_eq!(tree.select(9, 0), 5);
+        assert_eq!(tree.range_count(3, 5, 0, 11), 6);
+        assert_eq!(tree.range_count(0, 9, 2, 7), 5);
+        assert_eq!(tree.range_count(6, 4, 0, 11), 0);
+        assert_eq!(tree.range_count(0, u32::MAX, 3, 3), 0);
+    }
+
+    #[test]
+    fn tiny_alphabets() {
+        let tree = WaveletTree::new(&[0, 0, 0], 1);
+        assert_eq!(tree.rank(0, 2), 2);
+        assert_eq!(tree.select(0, 2), 2);
+        assert_eq!(tree.range_count(0, 0, 1, 3), 2);
+        assert_eq!(WaveletTree::new(&[], 0).rank(0, 0), 0);
+        // all the 32 bits
+        let tree = WaveletTree::new(&[u32::MAX - 1, 0, u32::MAX - 1], u32::MAX);
+        assert_eq!(tree.rank(u32::MAX, 3), 0);
+        assert_eq!(tree.select(u32::MAX - 1, 1), 2);
+        assert_eq!(tree.range_count(1, u32::MAX, 0, 3), 2);
+    }
+
+    #[test]
+    fn agrees_with_a_scan() {
+        let mut rng = ChaCha8Rng::seed_from_u64(157);
+        for sigma in [1, 2, 5, 64, 100, 1000] {
+            let n = rng.gen_range(0..300);
+            let arr: Vec<u32> = (0..n).map(|_| rng.gen_range(0..sigma)).collect();
+            let tree = WaveletTree::new(&arr, sigma);
+            for _ in 0..500 {
+                let v = rng.gen_range(0..sigma);
+                let i = rng.gen_range(0..=n);
+                let count = arr[..i].iter().filter(|&&x| x == v).count();
+                assert_eq!(tree.rank(v, i), count);
+                let positions: Vec<usize> = (0..n).filter(|&p| arr[p] == v).collect();
+                for (k, &position) in positions.iter().enumerate() {
+                    assert_eq!(tree.select(v, k), position);
+                }
+                let (lo, hi) = (rng.gen_range(0..sigma), rng.gen_range(0..sigma));
+                let l = rng.gen_range(0..=n);
+                let r = rng.gen_range(l..=n);
+                let count = arr[l..r].iter().filter(|&&x| lo <= x && x <= hi).count();
+                assert_eq!(tree.range_count(lo, hi, l, r), count);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "3 occurs 2 times, not more than 2")]
+    fn select_missing_occurrence() {
+        WaveletTree::new(&[3, 1, 3], 4).select(3, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "the value 4 is not below the alphabet size 4")]
+    fn value_out_of_the_alphabet() {
+        WaveletTree::new(&[3, 4], 4);
+    }
+}
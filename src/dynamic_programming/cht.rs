@@ -0,0 +1,341 @@
+//! The convex hull trick, for dynamic programs whose transitions take the minimum or the
+//! maximum of linear functions of the state
+//! <https://cp-algorithms.com/geometry/convex_hull_trick.html>
+//!
+//! A recurrence like `dp[j] = min_i (dp[i] + a[i] * x[j] + b[i])` is a query at `x[j]` of the
+//! minimum of the lines `y = a[i] * x + (dp[i] + b[i])`, which takes O(n^2) time when every `i`
+//! is tried. The lower envelope of the lines is convex, and keeping only the lines on it, sorted
+//! by slope, every query is a binary search, or a pointer moving along the envelope when the
+//! queries come in order.
+
+use std::collections::VecDeque;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Line {
+    slope: f64,
+    intercept: f64,
+}
+
+impl Line {
+    fn eval(&self, x: f64) -> f64 {
+        self.slope * x + self.intercept
+    }
+}
+
+/// Whether `middle` is nowhere below both `left` and `right` on a lower envelope, for slopes
+/// decreasing from `left` to `right`: when `left` and `right` cross before `left` and `middle`.
+fn is_useless(left: &Line, middle: &Line, right: &Line) -> bool {
+    (right.intercept - left.intercept) * (left.slope - middle.slope)
+        <= (middle.intercept - left.intercept) * (left.slope - right.slope)
+}
+
+/// The lower envelope of lines added in any order, by decreasing slopes
+#[derive(Clone, Debug, Default)]
+struct LowerHull {
+    lines: Vec<Line>,
+}
+
+impl LowerHull {
+    fn add(&mut self, line: Line) {
+        let lines = &mut self.lines;
+        let mut position = lines.partition_point(|l| l.slope > line.slope);
+        if position < lines.len() && lines[position].slope == line.slope {
+            if lines[position].intercept <= line.intercept {
+                return;
+            }
+            lines.remove(position);
+        }
+        if position > 0
+            && position < lines.len()
+            && is_useless(&lines[position - 1], &line, &lines[position])
+        {
+            return;
+        }
+        lines.insert(position, line);
+        while position + 2 < lines.len()
+            && is_useless(&line, &lines[position + 1], &lines[position + 2])
+        {
+            lines.remove(position + 1);
+        }
+        while position >= 2 && is_useless(&lines[position - 2], &lines[position - 1], &line) {
+            lines.remove(position - 1);
+            position -= 1;
+        }
+    }
+
+    fn query(&self, x: f64) -> f64 {
+        if self.lines.is_empty() {
+            return f64::INFINITY;
+        }
+        // the lines of the envelope take values decreasing then increasing at any x
+        let (mut low, mut high) = (0, self.lines.len() - 1);
+        while low < high {
+            let middle = (low + high) / 2;
+            if self.lines[middle].eval(x) > self.lines[middle + 1].eval(x) {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+        self.lines[low].eval(x)
+    }
+}
+
+/// The minimum and the maximum at any point of lines added in any order, in O(log(n)) per
+/// query.
+///
+/// The lines are kept on two envelopes, the maximum being the opposite of the minimum of the
+/// opposite lines. An insertion is O(log(n)) comparisons, plus shifting the lines after it in
+/// their vectors, which is fast but O(n) in the worst case; `MonotoneConvexHullTrick` takes
+/// O(1) when the slopes and the queries come in order.
+#[derive(Clone, Debug, Default)]
+pub struct ConvexHullTrick {
+    lower: LowerHull,
+    /// The lower envelope of the opposite lines
+    upper: LowerHull,
+}
+
+impl ConvexHullTrick {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_line(&mut self, slope: f64, intercept: f64) {
+        self.lower.add(Line { slope, intercept });
+        self.upper.add(Line {
+            slope: -slope,
+            intercept: -intercept,
+        });
+    }
+
+    /// Returns the minimum of the lines at `x`, or infinity without lines.
+    pub fn query_min(&self, x: f64) -> f64 {
+        self.lower.query(x)
+    }
+
+    /// Returns the maximum of the lines at `x`, or minus infinity without lines.
+    pub fn query_max(&self, x: f64) -> f64 {
+        -self.upper.query(x)
+    }
+}
+
+/// The minimum of lines added by non-increasing slopes, queried at non-decreasing points, in
+/// amortized O(1) per line and per query.
+///
+/// Every new line is on the envelope at the right end, past the lines of larger slopes, so that
+/// it goes at the back of a deque, after the lines it hides. The queries move rightward, so
+/// that the lines at the front stop being the minimum for good in turn, and are popped.
+#[derive(Clone, Debug)]
+pub struct MonotoneConvexHullTrick {
+    lines: VecDeque<Line>,
+    last_query: f64,
+}
+
+impl MonotoneConvexHullTrick {
+    pub fn new() -> Self {
+        MonotoneConvexHullTrick {
+            lines: VecDeque::new(),
+            last_query: f64::NEG_INFINITY,
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the slope is larger than that of the previous line.
+    pub fn add_line(&mut self, slope: f64, intercept: f64) {
+        let line = Line { slope, intercept };
+        if let Some(last) = self.lines.back() {
+            assert!(
+                slope <= last.slope,
+                "the slopes must not increase, got {slope} after {}",
+                last.slope
+            );
+            if slope == last.slope {
+                if last.intercept <= intercept {
+                    return;
+                }
+                self.lines.pop_back();
+            }
+        }
+        while self.lines.len() >= 2
+            && is_useless(
+                &self.lines[self.lines.len() - 2],
+                &self.lines[self.lines.len() - 1],
+                &line,
+            )
+        {
+            self.lines.pop_back();
+        }
+        self.lines.push_back(line);
+    }
+
+    /// Returns the minimum of the lines at `x`, or infinity without lines.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` is smaller than the previous query.
+    pub fn query_min(&mut self, x: f64) -> f64 {
+        assert!(
+            x >= self.last_query,
+            "the queries must not decrease, got {x} after {}",
+            self.last_query
+        );
+        self.last_query = x;
+        while self.lines.len() >= 2 && self.lines[1].eval(x) <= self.lines[0].eval(x) {
+            self.lines.pop_front();
+        }
+        self.lines
+            .front()
+            .map_or(f64::INFINITY, |line| line.eval(x))
+    }
+}
+
+impl Default for MonotoneConvexHullTrick {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    /// The least cost of covering the points, in their order, with groups of consecutive
+    /// points, a group costing `penalty` plus the square of the distance between its ends, in
+    /// O(n^2): `cost[j + 1] = min_i cost[i] + (x[j] - x[i])^2 + penalty` over the last groups,
+    /// from `i` to `j`.
+    fn brute_force_clusters(x: &[f64], penalty: f64) -> f64 {
+        let n = x.len();
+        // `cost[j]` covers the points before `j`
+        let mut cost = vec![0.0; n + 1];
+        for j in 1..=n {
+            cost[j] = (0..j)
+                .map(|i| cost[i] + (x[j - 1] - x[i]).powi(2) + penalty)
+                .fold(f64::INFINITY, f64::min);
+        }
+        cost[n]
+    }
+
+    /// The same recurrence, `cost[i] + x[i]^2 - 2 x[i] x[j] + x[j]^2 + penalty`, with the line
+    /// of slope `-2 x[i]` and intercept `cost[i] + x[i]^2` for every start `i`.
+    fn clusters_with_hull(x: &[f64], penalty: f64) -> f64 {
+        let mut hull = ConvexHullTrick::new();
+        let mut cost = 0.0;
+        for &x in x {
+            hull.add_line(-2.0 * x, cost + x * x);
+            cost = hull.query_min(x) + x * x + penalty;
+        }
+        cost
+    }
+
+    /// With sorted points, the slopes decrease and the queries increase.
+    fn clusters_with_monotone_hull(x: &[f64], penalty: f64) -> f64 {
+        let mut hull = MonotoneConvexHullTrick::new();
+        let mut cost = 0.0;
+        for &x in x {
+            hull.add_line(-2.0 * x, cost + x * x);
+            cost = hull.query_min(x) + x * x + penalty;
+        }
+        cost
+    }
+
+    #[test]
+    fn clusters_of_points() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1292);
+        for _ in 0..300 {
+            let n = rng.gen_range(1..60);
+            let mut x: Vec<f64> = (0..n).map(|_| rng.gen_range(0..1000) as f64).collect();
+            let penalty = rng.gen_range(0..20000) as f64;
+            assert_eq!(
+                clusters_with_hull(&x, penalty),
+                brute_force_clusters(&x, penalty)
+            );
+            x.sort_by(f64::total_cmp);
+            let expected = brute_force_clusters(&x, penalty);
+            assert_eq!(clusters_with_hull(&x, penalty), expected);
+            assert_eq!(clusters_with_monotone_hull(&x, penalty), expected);
+        }
+        // one group for close points, one group each for distant ones
+        assert_eq!(clusters_with_hull(&[1.0, 2.0, 3.0], 100.0), 104.0);
+        assert_eq!(clusters_with_hull(&[0.0, 100.0, 200.0], 10.0), 30.0);
+    }
+
+    #[test]
+    fn random_lines() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1293);
+        for _ in 0..200 {
+            let mut hull = ConvexHullTrick::new();
+            let mut lines = vec![];
+            for _ in 0..rng.gen_range(1..40) {
+                // few slopes, to get parallel lines
+                let line = (
+                    rng.gen_range(-10..10) as f64,
+                    rng.gen_range(-100..100) as f64,
+                );
+                hull.add_line(line.0, line.1);
+                lines.push(line);
+                for _ in 0..5 {
+                    let x = rng.gen_range(-50..50) as f64;
+                    let values = lines.iter().map(|(m, b)| m * x + b);
+                    let min = values.clone().fold(f64::INFINITY, f64::min);
+                    let max = values.fold(f64::NEG_INFINITY, f64::max);
+                    assert_eq!(hull.query_min(x), min);
+                    assert_eq!(hull.query_max(x), max);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn monotone_lines() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1294);
+        for _ in 0..200 {
+            let mut slopes: Vec<i32> = (0..rng.gen_range(1..40))
+                .map(|_| rng.gen_range(-20..20))
+                .collect();
+            slopes.sort_by(|a, b| b.cmp(a));
+            let mut queries: Vec<i32> = (0..slopes.len()).map(|_| rng.gen_range(-50..50)).collect();
+            queries.sort();
+            let mut hull = MonotoneConvexHullTrick::new();
+            let mut lines = vec![];
+            for (&slope, &x) in slopes.iter().zip(&queries) {
+                let line = (slope as f64, rng.gen_range(-100..100) as f64);
+                hull.add_line(line.0, line.1);
+                lines.push(line);
+                let x = x as f64;
+                let min = lines
+                    .iter()
+                    .map(|(m, b)| m * x + b)
+                    .fold(f64::INFINITY, f64::min);
+                assert_eq!(hull.query_min(x), min);
+            }
+        }
+    }
+
+    #[test]
+    fn without_lines() {
+        let hull = ConvexHullTrick::new();
+        assert_eq!(hull.query_min(1.0), f64::INFINITY);
+        assert_eq!(hull.query_max(1.0), f64::NEG_INFINITY);
+        assert_eq!(MonotoneConvexHullTrick::new().query_min(0.0), f64::INFINITY);
+    }
+
+    #[test]
+    #[should_panic(expected = "the slopes must not increase, got 2 after 1")]
+    fn increasing_slopes() {
+        let mut hull = MonotoneConvexHullTrick::new();
+        hull.add_line(1.0, 0.0);
+        hull.add_line(2.0, 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "the queries must not decrease, got 1 after 2")]
+    fn decreasing_queries() {
+        let mut hull = MonotoneConvexHullTrick::new();
+        hull.add_line(1.0, 0.0);
+        hull.query_min(2.0);
+        hull.query_min(1.0);
+    }
+}
@@ -0,0 +1,211 @@
+//! Digit dynamic programming, to count the numbers of a range with a property of their digits
+//! <https://codeforces.com/blog/entry/53960>
+//!
+//! The property is read by an automaton going through the decimal digits of a number, from the
+//! most significant one, without its leading zeros. The numbers up to a bound `n` are counted
+//! digit by digit: as long as the digits chosen are those of `n` the next one is at most the
+//! digit of `n` (the tight case), and once a digit is smaller, the rest of the digits are free,
+//! so that the count only depends on the position and the state of the automaton, and is
+//! memoized. With `s` states, this takes O(20 * 10 * s) steps, whatever the size of the range.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+struct Counter<'a, S, F, A> {
+    digits: Vec<u8>,
+    step: &'a F,
+    accept: &'a A,
+    /// The counts of the free suffixes from every position and state
+    memo: HashMap<(usize, S), u64>,
+}
+
+impl<S, F, A> Counter<'_, S, F, A>
+where
+    S: Clone + Eq + Hash,
+    F: Fn(&S, u8) -> Option<S>,
+    A: Fn(&S) -> bool,
+{
+    /// Counts the ways to choose the digits from `position` on, from `state`, with the digits
+    /// up to those of the bound when `tight`, and `started` once a non-zero digit was read.
+    fn count(&mut self, position: usize, state: &S, tight: bool, started: bool) -> u64 {
+        if position == self.digits.len() {
+            if started {
+                return u64::from((self.accept)(state));
+            }
+            // zero is read as a single digit
+            return (self.step)(state, 0).map_or(0, |state| u64::from((self.accept)(&state)));
+        }
+        let key = (position, state.clone());
+        if started && !tight {
+            if let Some(&count) = self.memo.get(&key) {
+                return count;
+            }
+        }
+        let limit = if tight { self.digits[position] } else { 9 };
+        let mut total = 0;
+        for digit in 0..=limit {
+            let tight = tight && digit == limit;
+            if !started && digit == 0 {
+                total += self.count(position + 1, state, tight, false);
+            } else if let Some(next) = (self.step)(state, digit) {
+                total += self.count(position + 1, &next, tight, true);
+            }
+        }
+        if started && !tight {
+            self.memo.insert(key, total);
+        }
+        total
+    }
+}
+
+fn count_up_to<S, F, A>(n: u64, start: &S, step: &F, accept: &A) -> u64
+where
+    S: Clone + Eq + Hash,
+    F: Fn(&S, u8) -> Option<S>,
+    A: Fn(&S) -> bool,
+{
+    let mut counter = Counter {
+        digits: n.to_string().bytes().map(|b| b - b'0').collect(),
+        step,
+        accept,
+        memo: HashMap::new(),
+    };
+    counter.count(0, start, true, false)
+}
+
+/// Counts the numbers of `[lo, hi]` whose digits the automaton accepts: starting from the state
+/// `start`, `step` gives the state after every digit, or `None` to reject the number right away,
+/// and `accept` tells whether the state after the last digit accepts the number.
+///
+/// The digits are read without the leading zeros, and 0 as a single digit 0, so that the
+/// automaton sees the usual decimal writing of every number. The count of the whole range of
+/// `u64` overflows if the automaton accepts every number.
+pub fn digit_dp<S, F, A>(lo: u64, hi: u64, start: S, step: F, accept: A) -> u64
+where
+    S: Clone + Eq + Hash,
+    F: Fn(&S, u8) -> Option<S>,
+    A: Fn(&S) -> bool,
+{
+    if lo > hi {
+        return 0;
+    }
+    let below = match lo.checked_sub(1) {
+        Some(n) => count_up_to(n, &start, &step, &accept),
+        None => 0,
+    };
+    count_up_to(hi, &start, &step, &accept) - below
+}
+
+/// Counts the numbers of `[lo, hi]` whose digits sum to `target_sum`, with the sum of the
+/// digits so far as the state.
+pub fn count_with_digit_sum(lo: u64, hi: u64, target_sum: usize) -> u64 {
+    digit_dp(
+        lo,
+        hi,
+        0,
+        |&sum, digit| Some(sum + digit as usize).filter(|&sum| sum <= target_sum),
+        |&sum| sum == target_sum,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn digit_sum(mut n: u64) -> usize {
+        let mut sum = 0;
+        while n > 0 {
+            sum += (n % 10) as usize;
+            n /= 10;
+        }
+        sum
+    }
+
+    #[test]
+    fn digit_sums() {
+        // 19, 28, 37, 46, 55, 64, 73, 82 and 91
+        assert_eq!(count_with_digit_sum(1, 100, 10), 9);
+        assert_eq!(count_with_digit_sum(0, 9, 0), 1);
+        assert_eq!(count_with_digit_sum(1, 9, 0), 0);
+        assert_eq!(count_with_digit_sum(0, 999, 27), 1);
+        assert_eq!(count_with_digit_sum(100, 99, 1), 0);
+        // the ten-digit numbers up to 10^10 - 1 with digit sum 1
+        assert_eq!(count_with_digit_sum(0, 9_999_999_999, 1), 10);
+        assert_eq!(
+            count_with_digit_sum(u64::MAX, u64::MAX, digit_sum(u64::MAX)),
+            1
+        );
+        let mut rng = ChaCha8Rng::seed_from_u64(130);
+        for _ in 0..100 {
+            let lo = rng.gen_range(0..5000);
+            let hi = lo + rng.gen_range(0..3000);
+            let target = rng.gen_range(0..30);
+            let expected = (lo..=hi).filter(|&n| digit_sum(n) == target).count() as u64;
+            assert_eq!(count_with_digit_sum(lo, hi, target), expected);
+        }
+    }
+
+    #[test]
+    fn leading_zeros_are_not_digits() {
+        // the numbers without a zero digit: 5 has none, 105 and 0 have one
+        let without_zero = |lo, hi| digit_dp(lo, hi, (), |_, d| (d != 0).then_some(()), |_| true);
+        assert_eq!(without_zero(0, 0), 0);
+        assert_eq!(without_zero(1, 9), 9);
+        assert_eq!(without_zero(0, 1000), 9 + 81 + 729);
+        assert_eq!(without_zero(100, 115), 5);
+        // the number of digits is not that of the bound
+        let length = |lo, hi, digits| {
+            digit_dp(
+                lo,
+                hi,
+                0,
+                |&len: &usize, _| Some(len + 1),
+                move |&len| len == digits,
+            )
+        };
+        assert_eq!(length(0, 12345, 1), 10);
+        assert_eq!(length(0, 12345, 3), 900);
+        assert_eq!(length(0, 12345, 5), 12345 - 9999);
+    }
+
+    #[test]
+    fn automata_agree_with_brute_force() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1300);
+        // no two equal adjacent digits, with the last digit as the state
+        let alternating = |lo, hi| {
+            digit_dp(
+                lo,
+                hi,
+                None,
+                |&last: &Option<u8>, d| (last != Some(d)).then_some(Some(d)),
+                |_| true,
+            )
+        };
+        // divisible by 7, with the remainder as the state
+        let multiple_of_seven = |lo, hi| {
+            digit_dp(
+                lo,
+                hi,
+                0,
+                |&r: &u64, d| Some((r * 10 + d as u64) % 7),
+                |&r| r == 0,
+            )
+        };
+        for _ in 0..100 {
+            let lo = rng.gen_range(0..20000);
+            let hi = lo + rng.gen_range(0..5000);
+            let expected = (lo..=hi)
+                .filter(|n| {
+                    let digits = n.to_string().into_bytes();
+                    digits.windows(2).all(|w| w[0] != w[1])
+                })
+                .count() as u64;
+            assert_eq!(alternating(lo, hi), expected);
+            let expected = (lo..=hi).filter(|n| n.is_multiple_of(7)).count() as u64;
+            assert_eq!(multiple_of_seven(lo, hi), expected);
+        }
+        assert_eq!(multiple_of_seven(0, u64::MAX), u64::MAX / 7 + 1);
+    }
+}
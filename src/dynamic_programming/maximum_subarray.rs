@@ -1,3 +1,6 @@
+use crate::error::{AlgorithmError, AlgorithmResult};
+use std::ops::Range;
+
 /// ## maximum subarray via Dynamic Programming
 
 /// maximum_subarray(array) find the subarray (containing at least one number) which has the largest sum
@@ -27,9 +30,100 @@ pub fn maximum_subarray(array: &[i32]) -> i32 {
     result
 }
 
+/// Finds the subarray whose sum is the best according to `better`, with Kadane's algorithm: the
+/// best subarray ending at an index extends the one ending before it if that one is better than
+/// nothing, and restarts at the index otherwise. Returns the first of the best subarrays.
+fn best_subarray(array: &[i64], better: fn(i64, i64) -> bool) -> (i64, Range<usize>) {
+    let mut best = (array[0], 0..1);
+    let mut current = 0;
+    let mut start = 0;
+    for (i, &value) in array.iter().enumerate() {
+        if i == 0 || !better(current, 0) {
+            current = value;
+            start = i;
+        } else {
+            current += value;
+        }
+        if better(current, best.0) {
+            best = (current, start..i + 1);
+        }
+    }
+    best
+}
+
+/// Returns the largest sum of a non-empty subarray, and the range of the first subarray with
+/// that sum, in O(n).
+///
+/// If all the items are negative, the best subarray is the largest item alone, never the empty
+/// one.
+pub fn max_subarray_sum(array: &[i64]) -> AlgorithmResult<(i64, Range<usize>)> {
+    if array.is_empty() {
+        return Err(AlgorithmError::EmptyInput { argument: "array" });
+    }
+    Ok(best_subarray(array, |a, b| a > b))
+}
+
+/// Returns the largest sum of a non-empty subarray of the array seen as circular, and its range
+/// in the array repeated twice: the range may end past the array, wrapping around to its start.
+///
+/// A subarray wrapping around is the whole array without a subarray in the middle, so the best
+/// one leaves out the subarray of smallest sum. If all the items are negative, this would leave
+/// out the whole array, and the best subarray is the largest item alone instead.
+pub fn max_subarray_circular(array: &[i64]) -> AlgorithmResult<(i64, Range<usize>)> {
+    let (best, range) = max_subarray_sum(array)?;
+    let n = array.len();
+    let (smallest, left_out) = best_subarray(array, |a, b| a < b);
+    if left_out.len() == n {
+        return Ok((best, range));
+    }
+    let total: i64 = array.iter().sum();
+    if total - smallest > best {
+        Ok((total - smallest, left_out.end..left_out.start + n))
+    } else {
+        Ok((best, range))
+    }
+}
+
+/// Returns the largest sum of a non-empty rectangle of a grid, and the rectangle as its top,
+/// left, bottom and right indices, all inclusive, in O(rows^2 * columns).
+///
+/// For every pair of top and bottom rows, the sums of the columns between them are a 1D array
+/// whose best subarray gives the best rectangle between these rows.
+pub fn max_submatrix_sum(
+    grid: &[Vec<i64>],
+) -> AlgorithmResult<(i64, (usize, usize, usize, usize))> {
+    let columns = match grid.first() {
+        Some(row) if !row.is_empty() => row.len(),
+        _ => return Err(AlgorithmError::EmptyInput { argument: "grid" }),
+    };
+    if let Some(row) = grid.iter().find(|row| row.len() != columns) {
+        return Err(AlgorithmError::DimensionMismatch {
+            argument: "grid",
+            expected: columns,
+            found: row.len(),
+        });
+    }
+    let mut best = (grid[0][0], (0, 0, 0, 0));
+    for top in 0..grid.len() {
+        let mut column_sums = vec![0; columns];
+        for (bottom, row) in grid.iter().enumerate().skip(top) {
+            for (sum, value) in column_sums.iter_mut().zip(row) {
+                *sum += value;
+            }
+            let (sum, range) = best_subarray(&column_sums, |a, b| a > b);
+            if sum > best.0 {
+                best = (sum, (top, range.start, bottom, range.end - 1));
+            }
+        }
+    }
+    Ok(best)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
 
     #[test]
     fn non_negative() {
@@ -59,4 +153,115 @@ mod tests {
         let array = vec![-6];
         assert_eq!(maximum_subarray(&array), -6);
     }
+
+    fn brute_force(array: &[i64]) -> i64 {
+        (0..array.len())
+            .flat_map(|i| (i + 1..=array.len()).map(move |j| array[i..j].iter().sum()))
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn sums_with_ranges() {
+        let array = [-2, 1, -3, 4, -1, 2, 1, -5, 4];
+        assert_eq!(max_subarray_sum(&array), Ok((6, 3..7)));
+        assert_eq!(max_subarray_sum(&[1, 0, 5, 8]), Ok((14, 0..4)));
+        assert_eq!(max_subarray_sum(&[-3, -1, -8, -2]), Ok((-1, 1..2)));
+        assert_eq!(max_subarray_sum(&[-6]), Ok((-6, 0..1)));
+        assert_eq!(max_subarray_sum(&[6]), Ok((6, 0..1)));
+        assert_eq!(
+            max_subarray_sum(&[]),
+            Err(AlgorithmError::EmptyInput { argument: "array" })
+        );
+    }
+
+    #[test]
+    fn ranges_sum_to_the_best_sum() {
+        let mut rng = ChaCha8Rng::seed_from_u64(116);
+        for _ in 0..300 {
+            let len = rng.gen_range(1..30);
+            let array: Vec<i64> = (0..len).map(|_| rng.gen_range(-20..20)).collect();
+            let (sum, range) = max_subarray_sum(&array).unwrap();
+            assert!(!range.is_empty());
+            assert_eq!(array[range].iter().sum::<i64>(), sum);
+            assert_eq!(sum, brute_force(&array));
+
+            let (sum, range) = max_subarray_circular(&array).unwrap();
+            let doubled: Vec<i64> = array.iter().chain(&array).copied().collect();
+            assert!(!range.is_empty() && range.len() <= len);
+            assert_eq!(doubled[range].iter().sum::<i64>(), sum);
+            let circular_brute_force = (0..len)
+                .map(|shift| brute_force(&doubled[shift..shift + len]))
+                .max()
+                .unwrap();
+            assert_eq!(sum, circular_brute_force);
+        }
+    }
+
+    #[test]
+    fn circular() {
+        // wrapping around leaves out -11 alone, for 22 instead of 12 alone
+        assert_eq!(
+            max_subarray_circular(&[8, -8, 9, -9, 10, -11, 12]),
+            Ok((22, 6..12))
+        );
+        assert_eq!(max_subarray_circular(&[5, -3, 5]), Ok((10, 2..4)));
+        // the middle beats wrapping around
+        assert_eq!(max_subarray_circular(&[-1, 4, 5, -1]), Ok((9, 1..3)));
+        assert_eq!(max_subarray_circular(&[-3, -2, -3]), Ok((-2, 1..2)));
+        assert_eq!(max_subarray_circular(&[7]), Ok((7, 0..1)));
+    }
+
+    #[test]
+    fn submatrices() {
+        let grid = vec![
+            vec![1, 2, -1, -4, -20],
+            vec![-8, -3, 4, 2, 1],
+            vec![3, 8, 10, 1, 3],
+            vec![-4, -1, 1, 7, -6],
+        ];
+        assert_eq!(max_submatrix_sum(&grid), Ok((29, (1, 1, 3, 3))));
+        assert_eq!(
+            max_submatrix_sum(&[vec![-5, -2], vec![-3, -9]]),
+            Ok((-2, (0, 1, 0, 1)))
+        );
+        assert_eq!(
+            max_submatrix_sum(&[vec![1, 2], vec![3]]),
+            Err(AlgorithmError::DimensionMismatch {
+                argument: "grid",
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            max_submatrix_sum(&[vec![]]),
+            Err(AlgorithmError::EmptyInput { argument: "grid" })
+        );
+
+        let mut rng = ChaCha8Rng::seed_from_u64(1160);
+        for _ in 0..20 {
+            let grid: Vec<Vec<i64>> = (0..10)
+                .map(|_| (0..10).map(|_| rng.gen_range(-10..10)).collect())
+                .collect();
+            let (sum, (top, left, bottom, right)) = max_submatrix_sum(&grid).unwrap();
+            let rectangle_sum = |t: usize, l: usize, b: usize, r: usize| -> i64 {
+                grid[t..=b]
+                    .iter()
+                    .map(|row| row[l..=r].iter().sum::<i64>())
+                    .sum()
+            };
+            assert_eq!(rectangle_sum(top, left, bottom, right), sum);
+            let mut best = i64::MIN;
+            for t in 0..10 {
+                for b in t..10 {
+                    for l in 0..10 {
+                        for r in l..10 {
+                            best = best.max(rectangle_sum(t, l, b, r));
+                        }
+                    }
+                }
+            }
+            assert_eq!(sum, best);
+        }
+    }
 }
@@ -1,4 +1,6 @@
+mod cht;
 mod coin_change;
+mod digit_dp;
 mod egg_dropping;
 mod fibonacci;
 mod fractional_knapsack;
@@ -12,12 +14,17 @@ mod matrix_chain_multiply;
 mod maximal_square;
 mod maximum_subarray;
 mod minimum_cost_path;
+mod optimal_bst;
 mod rod_cutting;
+mod sequence_alignment;
 mod snail;
 mod subset_generation;
+mod subset_sum;
 mod word_break;
 
+pub use self::cht::{ConvexHullTrick, MonotoneConvexHullTrick};
 pub use self::coin_change::coin_change;
+pub use self::digit_dp::{count_with_digit_sum, digit_dp};
 pub use self::egg_dropping::egg_drop;
 pub use self::fibonacci::classical_fibonacci;
 pub use self::fibonacci::fibonacci;
@@ -36,9 +43,17 @@ pub use self::longest_continuous_increasing_subsequence::longest_continuous_incr
 pub use self::longest_increasing_subsequence::longest_increasing_subsequence;
 pub use self::matrix_chain_multiply::matrix_chain_multiply;
 pub use self::maximal_square::maximal_square;
-pub use self::maximum_subarray::maximum_subarray;
+pub use self::maximum_subarray::{
+    max_subarray_circular, max_subarray_sum, max_submatrix_sum, maximum_subarray,
+};
 pub use self::minimum_cost_path::minimum_cost_path;
+pub use self::optimal_bst::{optimal_bst, optimal_bst_with_gaps, reconstruct_tree, BstNode};
 pub use self::rod_cutting::rod_cut;
+pub use self::sequence_alignment::{
+    affine_gap_alignment, needleman_wunsch, smith_waterman, ScoringMatrix, BLOSUM62,
+    BLOSUM62_ALPHABET,
+};
 pub use self::snail::snail;
 pub use self::subset_generation::list_subset;
+pub use self::subset_sum::{count_subsets_with_sum_mitm, subset_sum_mitm};
 pub use self::word_break::word_break;
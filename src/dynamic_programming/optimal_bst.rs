@@ -0,0 +1,232 @@
+//! Optimal binary search trees, with Knuth's speedup (also known as the Knuth-Yao speedup)
+//!
+//! Given the sorted keys `k_0 < k_1 < ... < k_{n-1}` and the probability of searching for each
+//! of them, the goal is to build the binary search tree minimizing the expected search cost,
+//! where searching for a key at depth `d` costs `d + 1`. In the general setting of Cormen et al.
+//! (Introduction to Algorithms, section 15.5), searches can also fail: `q[i]` is the probability
+//! of searching for a value between `k_{i-1}` and `k_i`, which ends at the `i`-th leaf.
+//!
+//! `cost[i][j]`, the optimal cost of a tree for the keys `k_i..k_{j-1}`, is the minimum over its
+//! roots `r` of `cost[i][r] + cost[r + 1][j] + weight(i, j)`, since hanging both subtrees below
+//! the root makes every search in them one step longer. The direct dynamic programming takes
+//! O(n^3), but Knuth showed that an optimal root can always be found between the roots of the
+//! two subproblems with one key less: `root[i][j - 1] <= root[i][j] <= root[i + 1][j]`.
+//! Restricting the search to that range makes the work for all the ranges of a given length
+//! telescope to O(n), hence O(n^2) in total.
+
+/// A node of the tree built by `reconstruct_tree`, holding the index of its key.
+#[derive(Debug, PartialEq, Eq)]
+pub struct BstNode {
+    pub key: usize,
+    pub left: Option<Box<BstNode>>,
+    pub right: Option<Box<BstNode>>,
+}
+
+/// Builds an optimal binary search tree for keys searched with the given `frequencies`,
+/// where every search is for one of the keys.
+///
+/// Returns the minimum expected search cost (normalized by the total frequency, so the
+/// frequencies do not need to sum to 1) and the root table: `root_table[i][j]` is the root of an
+/// optimal tree for the keys `i..=j`, and is only meaningful for `i <= j`.
+pub fn optimal_bst(frequencies: &[f64]) -> (f64, Vec<Vec<usize>>) {
+    optimal_bst_with_gaps(frequencies, &vec![0.0; frequencies.len() + 1])
+}
+
+/// Builds an optimal binary search tree where searches can also fail: `gap_frequencies[i]`
+/// is the frequency of searches for values falling between the keys `i - 1` and `i`
+/// (`gap_frequencies` has one more entry than `key_frequencies`).
+///
+/// Returns the expected cost and the root table, like `optimal_bst`. Unsuccessful searches
+/// cost the depth of the leaf they end at, plus one, as in Cormen et al.
+pub fn optimal_bst_with_gaps(
+    key_frequencies: &[f64],
+    gap_frequencies: &[f64],
+) -> (f64, Vec<Vec<usize>>) {
+    let n = key_frequencies.len();
+    assert_eq!(
+        gap_frequencies.len(),
+        n + 1,
+        "there must be one more gap than keys"
+    );
+    if n == 0 {
+        return (0.0, vec![]);
+    }
+
+    // The tables are indexed by half-open ranges of keys [i, j), so cost[i][i] is an empty tree
+    // made of the leaf for gap i.
+    let mut weight = vec![vec![0.0; n + 1]; n + 1];
+    let mut cost = vec![vec![0.0; n + 1]; n + 1];
+    let mut root = vec![vec![0; n + 1]; n + 1];
+    for i in 0..=n {
+        weight[i][i] = gap_frequencies[i];
+        cost[i][i] = gap_frequencies[i];
+    }
+
+    for length in 1..=n {
+        for i in 0..=n - length {
+            let j = i + length;
+            weight[i][j] = weight[i][j - 1] + key_frequencies[j - 1] + gap_frequencies[j];
+            // Knuth's bounds, which reduce to the single root i for a single key
+            let (low, high) = if length == 1 {
+                (i, i)
+            } else {
+                (root[i][j - 1], root[i + 1][j])
+            };
+            let mut best = f64::INFINITY;
+            for r in low..=high {
+                let candidate = cost[i][r] + cost[r + 1][j];
+                if candidate < best {
+                    best = candidate;
+                    root[i][j] = r;
+                }
+            }
+            cost[i][j] = best + weight[i][j];
+        }
+    }
+
+    let total = weight[0][n];
+    let expected_cost = if total > 0.0 { cost[0][n] / total } else { 0.0 };
+    // convert the table to inclusive ranges of keys
+    let root_table = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| if i <= j { root[i][j + 1] } else { 0 })
+                .collect()
+        })
+        .collect();
+    (expected_cost, root_table)
+}
+
+/// Builds the optimal tree for the keys `i..=j` from the root table given by `optimal_bst`.
+pub fn reconstruct_tree(root_table: &[Vec<usize>], i: usize, j: usize) -> BstNode {
+    assert!(i <= j && j < root_table.len(), "invalid range of keys");
+    let key = root_table[i][j];
+    BstNode {
+        key,
+        left: (key > i).then(|| Box::new(reconstruct_tree(root_table, i, key - 1))),
+        right: (key < j).then(|| Box::new(reconstruct_tree(root_table, key + 1, j))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    /// Plain O(n^3) dynamic programming, trying every root
+    fn cubic_cost(p: &[f64], q: &[f64]) -> f64 {
+        let n = p.len();
+        let mut cost = vec![vec![0.0; n + 1]; n + 1];
+        let mut weight = vec![vec![0.0; n + 1]; n + 1];
+        for i in 0..=n {
+            cost[i][i] = q[i];
+            weight[i][i] = q[i];
+        }
+        for length in 1..=n {
+            for i in 0..=n - length {
+                let j = i + length;
+                weight[i][j] = weight[i][j - 1] + p[j - 1] + q[j];
+                cost[i][j] = (i..j)
+                    .map(|r| cost[i][r] + cost[r + 1][j])
+                    .fold(f64::INFINITY, f64::min)
+                    + weight[i][j];
+            }
+        }
+        cost[0][n] / weight[0][n]
+    }
+
+    /// Expected cost of searching for the keys in a given tree, when all searches succeed
+    fn tree_cost(node: &BstNode, depth: usize, p: &[f64]) -> f64 {
+        let mut cost = (depth + 1) as f64 * p[node.key];
+        for child in [&node.left, &node.right].into_iter().flatten() {
+            cost += tree_cost(child, depth + 1, p);
+        }
+        cost
+    }
+
+    fn in_order(node: &BstNode, keys: &mut Vec<usize>) {
+        if let Some(left) = &node.left {
+            in_order(left, keys);
+        }
+        keys.push(node.key);
+        if let Some(right) = &node.right {
+            in_order(right, keys);
+        }
+    }
+
+    #[test]
+    fn cormen_example() {
+        // Introduction to Algorithms, figure 15.9
+        let p = [0.15, 0.10, 0.05, 0.10, 0.20];
+        let q = [0.05, 0.10, 0.05, 0.05, 0.05, 0.10];
+        let (cost, roots) = optimal_bst_with_gaps(&p, &q);
+        assert!((cost - 2.75).abs() < 1e-9);
+        // k2 at the root, k1 on its left, then k5, k4 and k3 down its right side
+        let tree = reconstruct_tree(&roots, 0, 4);
+        let leaf = |key| BstNode {
+            key,
+            left: None,
+            right: None,
+        };
+        let expected = BstNode {
+            key: 1,
+            left: Some(Box::new(leaf(0))),
+            right: Some(Box::new(BstNode {
+                key: 4,
+                left: Some(Box::new(BstNode {
+                    key: 3,
+                    left: Some(Box::new(leaf(2))),
+                    right: None,
+                })),
+                right: None,
+            })),
+        };
+        assert_eq!(tree, expected);
+    }
+
+    #[test]
+    fn only_successful_searches() {
+        // the same keys as above, without the gaps
+        let p = [0.15, 0.10, 0.05, 0.10, 0.20];
+        let (cost, roots) = optimal_bst(&p);
+        assert!((cost - cubic_cost(&p, &[0.0; 6])).abs() < 1e-9);
+        let tree = reconstruct_tree(&roots, 0, 4);
+        assert!((tree_cost(&tree, 0, &p) / 0.6 - cost).abs() < 1e-9);
+    }
+
+    #[test]
+    fn small_cases() {
+        assert_eq!(optimal_bst(&[]), (0.0, vec![]));
+        let (cost, roots) = optimal_bst(&[3.0]);
+        assert_eq!(cost, 1.0);
+        assert_eq!(reconstruct_tree(&roots, 0, 0).key, 0);
+        // the most frequent key goes to the root
+        let (cost, roots) = optimal_bst(&[1.0, 1.0, 8.0]);
+        assert_eq!(roots[0][2], 2);
+        assert!((cost - 1.3).abs() < 1e-9);
+    }
+
+    #[test]
+    fn matches_cubic_algorithm() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1052);
+        for n in 1..30 {
+            let p: Vec<f64> = (0..n).map(|_| rng.gen_range(0.0..1.0)).collect();
+            let q: Vec<f64> = (0..=n).map(|_| rng.gen_range(0.0..0.5)).collect();
+            let (cost, roots) = optimal_bst_with_gaps(&p, &q);
+            assert!((cost - cubic_cost(&p, &q)).abs() < 1e-9);
+
+            let (cost, roots_without_gaps) = optimal_bst(&p);
+            let tree = reconstruct_tree(&roots_without_gaps, 0, n - 1);
+            let total: f64 = p.iter().sum();
+            assert!((tree_cost(&tree, 0, &p) / total - cost).abs() < 1e-9);
+
+            // the reconstructed trees are search trees over all the keys
+            for table in [&roots, &roots_without_gaps] {
+                let mut keys = vec![];
+                in_order(&reconstruct_tree(table, 0, n - 1), &mut keys);
+                assert_eq!(keys, (0..n).collect::<Vec<_>>());
+            }
+        }
+    }
+}
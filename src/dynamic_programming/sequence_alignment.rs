@@ -0,0 +1,690 @@
+//! Pairwise sequence alignment, as used in bioinformatics to compare DNA or protein sequences.
+//!
+//! An alignment inserts gaps (`-`) in both sequences so that they have the same length, and is
+//! scored column by column: a [`ScoringMatrix`] gives the score of two symbols facing each
+//! other, and `gap` is the score of a symbol facing a gap. Penalties are given as negative
+//! scores, for instance `needleman_wunsch(a, b, &ScoringMatrix::simple(1, -1), -2)` for 1 per
+//! match, -1 per mismatch and -2 per gap, or `ScoringMatrix::blosum62()` for proteins.
+//!
+//! Both algorithms fill a table where `score[i][j]` is the best score of an alignment ending
+//! with the prefixes `a[..i]` and `b[..j]`, then follow back the choices that led to the best
+//! cell. They run in O(nm) time and space.
+
+use crate::error::{AlgorithmError, AlgorithmResult};
+
+/// The symbols of [`BLOSUM62`], the twenty amino acids, the ambiguous `B` (`N` or `D`), `Z` (`Q`
+/// or `E`) and `X` (any), and `*` for a stop
+pub const BLOSUM62_ALPHABET: &[u8; 24] = b"ARNDCQEGHILKMFPSTWYVBZX*";
+
+/// The BLOSUM62 substitution matrix of NCBI, row by row in the order of [`BLOSUM62_ALPHABET`]
+/// <https://en.wikipedia.org/wiki/BLOSUM>
+///
+/// Every score is a log-odds ratio, in half bits, of the frequency of the two amino acids
+/// aligned in blocks of proteins at most 62% identical, against what chance would give.
+#[rustfmt::skip]
+pub const BLOSUM62: [i32; 576] = [
+//   A   R   N   D   C   Q   E   G   H   I   L   K   M   F   P   S   T   W   Y   V   B   Z   X   *
+     4, -1, -2, -2,  0, -1, -1,  0, -2, -1, -1, -1, -1, -2, -1,  1,  0, -3, -2,  0, -2, -1,  0, -4, // A
+    -1,  5,  0, -2, -3,  1,  0, -2,  0, -3, -2,  2, -1, -3, -2, -1, -1, -3, -2, -3, -1,  0, -1, -4, // R
+    -2,  0,  6,  1, -3,  0,  0,  0,  1, -3, -3,  0, -2, -3, -2,  1,  0, -4, -2, -3,  3,  0, -1, -4, // N
+    -2, -2,  1,  6, -3,  0,  2, -1, -1, -3, -4, -1, -3, -3, -1,  0, -1, -4, -3, -3,  4,  1, -1, -4, // D
+     0, -3, -3, -3,  9, -3, -4, -3, -3, -1, -1, -3, -1, -2, -3, -1, -1, -2, -2, -1, -3, -3, -2, -4, // C
+    -1,  1,  0,  0, -3,  5,  2, -2,  0, -3, -2,  1,  0, -3, -1,  0, -1, -2, -1, -2,  0,  3, -1, -4, // Q
+    -1,  0,  0,  2, -4,  2,  5, -2,  0, -3, -3,  1, -2, -3, -1,  0, -1, -3, -2, -2,  1,  4, -1, -4, // E
+     0, -2,  0, -1, -3, -2, -2,  6, -2, -4, -4, -2, -3, -3, -2,  0, -2, -2, -3, -3, -1, -2, -1, -4, // G
+    -2,  0,  1, -1, -3,  0,  0, -2,  8, -3, -3, -1, -2, -1, -2, -1, -2, -2,  2, -3,  0,  0, -1, -4, // H
+    -1, -3, -3, -3, -1, -3, -3, -4, -3,  4,  2, -3,  1,  0, -3, -2, -1, -3, -1,  3, -3, -3, -1, -4, // I
+    -1, -2, -3, -4, -1, -2, -3, -4, -3,  2,  4, -2,  2,  0, -3, -2, -1, -2, -1,  1, -4, -3, -1, -4, // L
+    -1,  2,  0, -1, -3,  1,  1, -2, -1, -3, -2,  5, -1, -3, -1,  0, -1, -3, -2, -2,  0,  1, -1, -4, // K
+    -1, -1, -2, -3, -1,  0, -2, -3, -2,  1,  2, -1,  5,  0, -2, -1, -1, -1, -1,  1, -3, -1, -1, -4, // M
+    -2, -3, -3, -3, -2, -3, -3, -3, -1,  0,  0, -3,  0,  6, -4, -2, -2,  1,  3, -1, -3, -3, -1, -4, // F
+    -1, -2, -2, -1, -3, -1, -1, -2, -2, -3, -3, -1, -2, -4,  7, -1, -1, -4, -3, -2, -2, -1, -2, -4, // P
+     1, -1,  1,  0, -1,  0,  0,  0, -1, -2, -2,  0, -1, -2, -1,  4,  1, -3, -2, -2,  0,  0,  0, -4, // S
+     0, -1,  0, -1, -1, -1, -1, -2, -2, -1, -1, -1, -1, -2, -1,  1,  5, -2, -2,  0, -1, -1,  0, -4, // T
+    -3, -3, -4, -4, -2, -2, -3, -2, -2, -3, -2, -3, -1,  1, -4, -3, -2, 11,  2, -3, -4, -3, -2, -4, // W
+    -2, -2, -2, -3, -2, -1, -2, -3,  2, -1, -1, -2, -1,  3, -3, -2, -2,  2,  7, -1, -3, -2, -1, -4, // Y
+     0, -3, -3, -3, -1, -2, -2, -3, -3,  3,  1, -2,  1, -1, -2, -2,  0, -3, -1,  4, -3, -2, -1, -4, // V
+    -2, -1,  3,  4, -3,  0,  1, -1,  0, -3, -4,  0, -3, -3, -2,  0, -1, -4, -3, -3,  4,  1, -1, -4, // B
+    -1,  0,  0,  1, -3,  3,  4, -2,  0, -3, -3,  1, -1, -3, -1,  0, -1, -3, -2, -2,  1,  4, -1, -4, // Z
+     0, -1, -1, -1, -2, -1, -1, -1, -1, -1, -1, -1, -1, -1, -2,  0,  0, -2, -1, -1, -1, -1, -1, -4, // X
+    -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4,  1, // *
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Scores {
+    /// The same score for all matches, and another one for all mismatches
+    Simple { match_score: i32, mismatch: i32 },
+    /// A score for every pair of symbols of an alphabet
+    Table {
+        /// The position of every byte in the alphabet, if it belongs to it
+        index: Vec<Option<usize>>,
+        size: usize,
+        scores: Vec<i32>,
+    },
+}
+
+/// The score of every pair of symbols facing each other in an alignment
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScoringMatrix {
+    scores: Scores,
+}
+
+impl ScoringMatrix {
+    /// Scores `match_score` for two equal bytes and `mismatch` for two different ones.
+    pub fn simple(match_score: i32, mismatch: i32) -> Self {
+        ScoringMatrix {
+            scores: Scores::Simple {
+                match_score,
+                mismatch,
+            },
+        }
+    }
+
+    /// Scores the symbols `alphabet[i]` and `alphabet[j]` with `scores[i * k + j]`, for an
+    /// alphabet of k symbols, as substitution matrices are usually written row by row.
+    pub fn new(alphabet: &[u8], scores: &[i32]) -> AlgorithmResult<Self> {
+        let size = alphabet.len();
+        if scores.len() != size * size {
+            return Err(AlgorithmError::DimensionMismatch {
+                argument: "scores",
+                expected: size * size,
+                found: scores.len(),
+            });
+        }
+        let mut index = vec![None; 256];
+        for (i, &symbol) in alphabet.iter().enumerate() {
+            if index[symbol as usize].replace(i).is_some() {
+                return Err(AlgorithmError::InvalidParameter {
+                    argument: "alphabet",
+                    reason: format!("the symbol {:?} appears twice", symbol as char),
+                });
+            }
+        }
+        Ok(ScoringMatrix {
+            scores: Scores::Table {
+                index,
+                size,
+                scores: scores.to_vec(),
+            },
+        })
+    }
+
+    /// The [`BLOSUM62`] matrix, for the upper case letters of amino acids.
+    pub fn blosum62() -> Self {
+        Self::new(BLOSUM62_ALPHABET, &BLOSUM62).unwrap()
+    }
+
+    /// Returns the score of `x` facing `y`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` or `y` is not in the alphabet of the matrix.
+    pub fn score(&self, x: u8, y: u8) -> i32 {
+        match &self.scores {
+            Scores::Simple {
+                match_score,
+                mismatch,
+            } => {
+                if x == y {
+                    *match_score
+                } else {
+                    *mismatch
+                }
+            }
+            Scores::Table {
+                index,
+                size,
+                scores,
+            } => {
+                let position = |symbol: u8| {
+                    index[symbol as usize].unwrap_or_else(|| {
+                        panic!("the symbol {:?} is not in the alphabet", symbol as char)
+                    })
+                };
+                scores[position(x) * size + position(y)]
+            }
+        }
+    }
+}
+
+/// Where a cell of the score table got its value from
+#[derive(Clone, Copy, PartialEq)]
+enum Step {
+    Diagonal,
+    Up,
+    Left,
+    Start,
+}
+
+/// Fills the score table, with `local` selecting Smith-Waterman (scores floored at zero and
+/// free starting points) over Needleman-Wunsch.
+fn fill_table(
+    a: &[u8],
+    b: &[u8],
+    matrix: &ScoringMatrix,
+    gap: i32,
+    local: bool,
+) -> (Vec<Vec<i32>>, Vec<Vec<Step>>) {
+    let (n, m) = (a.len(), b.len());
+    let mut score = vec![vec![0; m + 1]; n + 1];
+    let mut steps = vec![vec![Step::Start; m + 1]; n + 1];
+    if !local {
+        for i in 1..=n {
+            score[i][0] = score[i - 1][0] + gap;
+            steps[i][0] = Step::Up;
+        }
+        for j in 1..=m {
+            score[0][j] = score[0][j - 1] + gap;
+            steps[0][j] = Step::Left;
+        }
+    }
+
+    for i in 1..=n {
+        for j in 1..=m {
+            let pair = matrix.score(a[i - 1], b[j - 1]);
+            // on ties, prefer the diagonal, then a gap in b, then a gap in a
+            let mut best = (score[i - 1][j - 1] + pair, Step::Diagonal);
+            for candidate in [
+                (score[i - 1][j] + gap, Step::Up),
+                (score[i][j - 1] + gap, Step::Left),
+            ] {
+                if candidate.0 > best.0 {
+                    best = candidate;
+                }
+            }
+            if local && best.0 <= 0 {
+                best = (0, Step::Start);
+            }
+            score[i][j] = best.0;
+            steps[i][j] = best.1;
+        }
+    }
+    (score, steps)
+}
+
+/// Follows the steps back from cell `(i, j)` until a starting cell, building both aligned strings.
+fn trace_back(
+    a: &[u8],
+    b: &[u8],
+    steps: &[Vec<Step>],
+    mut i: usize,
+    mut j: usize,
+) -> (String, String) {
+    let mut aligned_a = vec![];
+    let mut aligned_b = vec![];
+    loop {
+        match steps[i][j] {
+            Step::Diagonal => {
+                i -= 1;
+                j -= 1;
+                aligned_a.push(a[i]);
+                aligned_b.push(b[j]);
+            }
+            Step::Up => {
+                i -= 1;
+                aligned_a.push(a[i]);
+                aligned_b.push(b'-');
+            }
+            Step::Left => {
+                j -= 1;
+                aligned_a.push(b'-');
+                aligned_b.push(b[j]);
+            }
+            Step::Start => break,
+        }
+    }
+    into_strings(aligned_a, aligned_b)
+}
+
+/// Turns the columns of an alignment, collected backwards, into the two aligned strings.
+fn into_strings(mut aligned_a: Vec<u8>, mut aligned_b: Vec<u8>) -> (String, String) {
+    aligned_a.reverse();
+    aligned_b.reverse();
+    (
+        String::from_utf8_lossy(&aligned_a).into_owned(),
+        String::from_utf8_lossy(&aligned_b).into_owned(),
+    )
+}
+
+/// Global alignment of the whole sequences `a` and `b` (Needleman-Wunsch).
+///
+/// Returns the best score and the two aligned sequences, with `-` for the gaps.
+pub fn needleman_wunsch(
+    a: &[u8],
+    b: &[u8],
+    matrix: &ScoringMatrix,
+    gap: i32,
+) -> (i32, String, String) {
+    let (score, steps) = fill_table(a, b, matrix, gap, false);
+    let (aligned_a, aligned_b) = trace_back(a, b, &steps, a.len(), b.len());
+    (score[a.len()][b.len()], aligned_a, aligned_b)
+}
+
+/// Local alignment of the most similar substrings of `a` and `b` (Smith-Waterman).
+///
+/// Scores never go below zero: a prefix with a negative score is dropped and the alignment
+/// starts afresh. Returns the best score and the two aligned substrings, which are empty
+/// when no pair of symbols matches.
+pub fn smith_waterman(
+    a: &[u8],
+    b: &[u8],
+    matrix: &ScoringMatrix,
+    gap: i32,
+) -> (i32, String, String) {
+    let (score, steps) = fill_table(a, b, matrix, gap, true);
+    // the alignment ends at the best cell, the first one found in row-major order
+    let mut end = (0, 0);
+    for (i, row) in score.iter().enumerate() {
+        for (j, &value) in row.iter().enumerate() {
+            if value > score[end.0][end.1] {
+                end = (i, j);
+            }
+        }
+    }
+    let (aligned_a, aligned_b) = trace_back(a, b, &steps, end.0, end.1);
+    (score[end.0][end.1], aligned_a, aligned_b)
+}
+
+/// The states of an alignment with affine gaps: ending with two symbols, with a symbol of `a`
+/// facing a gap, or with a symbol of `b` facing a gap
+const PAIR: usize = 0;
+const GAP_IN_B: usize = 1;
+const GAP_IN_A: usize = 2;
+
+/// Global alignment with affine gap scores (Gotoh), where a gap of length `L` scores
+/// `gap_open + (L - 1) * gap_extend`, so that a long gap costs less than as many short ones
+/// when `gap_extend` is the smaller penalty.
+/// <https://en.wikipedia.org/wiki/Gap_penalty#Affine>
+///
+/// A single table can't tell whether the gap facing a symbol opens or extends one, so that
+/// there are three: `M[i][j]`, `X[i][j]` and `Y[i][j]` are the best scores of the alignments of
+/// `a[..i]` and `b[..j]` ending with two symbols, with `a[i - 1]` facing a gap, and with
+/// `b[j - 1]` facing a gap. A gap extends from the same table and opens from the other two, in
+/// O(nm) time and space like the other alignments.
+///
+/// Returns the best score and the two aligned sequences, with `-` for the gaps.
+pub fn affine_gap_alignment(
+    a: &[u8],
+    b: &[u8],
+    matrix: &ScoringMatrix,
+    gap_open: i32,
+    gap_extend: i32,
+) -> (i32, String, String) {
+    // low enough to never be chosen, and far enough from i32::MIN to take a few penalties
+    const IMPOSSIBLE: i32 = i32::MIN / 4;
+    let (n, m) = (a.len(), b.len());
+    // score[state][i][j], and the state of the cell it came from
+    let mut score = vec![vec![vec![IMPOSSIBLE; m + 1]; n + 1]; 3];
+    let mut from = vec![vec![vec![PAIR; m + 1]; n + 1]; 3];
+    score[PAIR][0][0] = 0;
+    for i in 1..=n {
+        score[GAP_IN_B][i][0] = gap_open + (i as i32 - 1) * gap_extend;
+        from[GAP_IN_B][i][0] = if i == 1 { PAIR } else { GAP_IN_B };
+    }
+    for j in 1..=m {
+        score[GAP_IN_A][0][j] = gap_open + (j as i32 - 1) * gap_extend;
+        from[GAP_IN_A][0][j] = if j == 1 { PAIR } else { GAP_IN_A };
+    }
+
+    // the best of the previous cell over the states, each with the score of the move to it,
+    // preferring the pairs on ties, then the gaps in b
+    let best = |score: &[Vec<Vec<i32>>], i: usize, j: usize, moves: [i32; 3]| {
+        (0..3)
+            .map(|state| (score[state][i][j] + moves[state], state))
+            .fold((IMPOSSIBLE, PAIR), |best, candidate| {
+                if candidate.0 > best.0 {
+                    candidate
+                } else {
+                    best
+                }
+            })
+    };
+    for i in 1..=n {
+        for j in 1..=m {
+            let (value, state) = best(&score, i - 1, j - 1, [0; 3]);
+            score[PAIR][i][j] = value + matrix.score(a[i - 1], b[j - 1]);
+            from[PAIR][i][j] = state;
+            let (value, state) = best(&score, i - 1, j, [gap_open, gap_extend, gap_open]);
+            score[GAP_IN_B][i][j] = value;
+            from[GAP_IN_B][i][j] = state;
+            let (value, state) = best(&score, i, j - 1, [gap_open, gap_open, gap_extend]);
+            score[GAP_IN_A][i][j] = value;
+            from[GAP_IN_A][i][j] = state;
+        }
+    }
+
+    let (total, mut state) = best(&score, n, m, [0; 3]);
+    let (mut i, mut j) = (n, m);
+    let mut aligned_a = vec![];
+    let mut aligned_b = vec![];
+    while i > 0 || j > 0 {
+        let previous = from[state][i][j];
+        match state {
+            PAIR => {
+                i -= 1;
+                j -= 1;
+                aligned_a.push(a[i]);
+                aligned_b.push(b[j]);
+            }
+            GAP_IN_B => {
+                i -= 1;
+                aligned_a.push(a[i]);
+                aligned_b.push(b'-');
+            }
+            _ => {
+                j -= 1;
+                aligned_a.push(b'-');
+                aligned_b.push(b[j]);
+            }
+        }
+        state = previous;
+    }
+    let (aligned_a, aligned_b) = into_strings(aligned_a, aligned_b);
+    (total, aligned_a, aligned_b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Scores an alignment column by column
+    fn alignment_score(aligned_a: &str, aligned_b: &str, matrix: &ScoringMatrix, gap: i32) -> i32 {
+        affine_score(aligned_a, aligned_b, matrix, gap, gap)
+    }
+
+    /// Scores an alignment column by column, every gap opening or extending one
+    fn affine_score(
+        aligned_a: &str,
+        aligned_b: &str,
+        matrix: &ScoringMatrix,
+        gap_open: i32,
+        gap_extend: i32,
+    ) -> i32 {
+        assert_eq!(aligned_a.len(), aligned_b.len());
+        let mut previous = (b'x', b'x');
+        aligned_a
+            .bytes()
+            .zip(aligned_b.bytes())
+            .map(|(x, y)| {
+                let score = match (x, y) {
+                    (b'-', b'-') => panic!("two gaps facing each other"),
+                    (b'-', _) if previous.0 == b'-' => gap_extend,
+                    (_, b'-') if previous.1 == b'-' => gap_extend,
+                    (b'-', _) | (_, b'-') => gap_open,
+                    _ => matrix.score(x, y),
+                };
+                previous = (x, y);
+                score
+            })
+            .sum()
+    }
+
+    /// Every alignment of `a` and `b`, as pairs of aligned strings
+    fn all_alignments(a: &[u8], b: &[u8]) -> Vec<(String, String)> {
+        if a.is_empty() && b.is_empty() {
+            return vec![(String::new(), String::new())];
+        }
+        let mut alignments = vec![];
+        let mut extend = |x: u8, y: u8, rest: Vec<(String, String)>| {
+            for (rest_a, rest_b) in rest {
+                alignments.push((
+                    format!("{}{rest_a}", x as char),
+                    format!("{}{rest_b}", y as char),
+                ));
+            }
+        };
+        if !a.is_empty() && !b.is_empty() {
+            extend(a[0], b[0], all_alignments(&a[1..], &b[1..]));
+        }
+        if !a.is_empty() {
+            extend(a[0], b'-', all_alignments(&a[1..], b));
+        }
+        if !b.is_empty() {
+            extend(b'-', b[0], all_alignments(a, &b[1..]));
+        }
+        alignments
+    }
+
+    /// The part of BLOSUM50 used by the examples of Durbin, Eddy, Krogh and Mitchison,
+    /// Biological Sequence Analysis, chapter 2
+    fn blosum50_excerpt() -> ScoringMatrix {
+        #[rustfmt::skip]
+        let scores = [
+        //   A   E   G   H   P   W
+             5, -1,  0, -2, -1, -3, // A
+            -1,  6, -3,  0, -1, -3, // E
+             0, -3,  8, -2, -2, -3, // G
+            -2,  0, -2, 10, -2, -3, // H
+            -1, -1, -2, -2, 10, -4, // P
+            -3, -3, -3, -3, -4, 15, // W
+        ];
+        ScoringMatrix::new(b"AEGHPW", &scores).unwrap()
+    }
+
+    fn without_gaps(aligned: &str) -> String {
+        aligned.replace('-', "")
+    }
+
+    #[test]
+    fn needleman_wunsch_textbook_example() {
+        let matrix = ScoringMatrix::simple(1, -1);
+        let (score, aligned_a, aligned_b) = needleman_wunsch(b"GATTACA", b"GCATGCU", &matrix, -1);
+        assert_eq!(score, 0);
+        assert_eq!(aligned_a, "G-ATTACA");
+        assert_eq!(aligned_b, "GCA-TGCU");
+        assert_eq!(alignment_score(&aligned_a, &aligned_b, &matrix, -1), score);
+    }
+
+    #[test]
+    fn needleman_wunsch_dna() {
+        let matrix = ScoringMatrix::simple(2, -1);
+        let (a, b) = (b"AGTACGCA", b"TATGC");
+        let (score, aligned_a, aligned_b) = needleman_wunsch(a, b, &matrix, -2);
+        // AGTACGCA
+        // --TATGC-
+        assert_eq!(score, 1);
+        assert_eq!(alignment_score(&aligned_a, &aligned_b, &matrix, -2), score);
+        assert_eq!(without_gaps(&aligned_a), "AGTACGCA");
+        assert_eq!(without_gaps(&aligned_b), "TATGC");
+    }
+
+    #[test]
+    fn needleman_wunsch_identical_and_empty() {
+        let matrix = ScoringMatrix::simple(1, -1);
+        assert_eq!(
+            needleman_wunsch(b"ACGT", b"ACGT", &matrix, -1),
+            (4, "ACGT".to_string(), "ACGT".to_string())
+        );
+        assert_eq!(
+            needleman_wunsch(b"ACG", b"", &matrix, -2),
+            (-6, "ACG".to_string(), "---".to_string())
+        );
+        assert_eq!(
+            needleman_wunsch(b"", b"", &matrix, -2),
+            (0, String::new(), String::new())
+        );
+    }
+
+    #[test]
+    fn smith_waterman_textbook_example() {
+        let matrix = ScoringMatrix::simple(3, -3);
+        let (score, aligned_a, aligned_b) = smith_waterman(b"TGTTACGG", b"GGTTGACTA", &matrix, -2);
+        assert_eq!(score, 13);
+        assert_eq!(aligned_a, "GTT-AC");
+        assert_eq!(aligned_b, "GTTGAC");
+        assert_eq!(alignment_score(&aligned_a, &aligned_b, &matrix, -2), score);
+    }
+
+    #[test]
+    fn smith_waterman_finds_common_substring() {
+        let (score, aligned_a, aligned_b) = smith_waterman(
+            b"TTTTGATTACATTTT",
+            b"CCGATTACACC",
+            &ScoringMatrix::simple(1, -2),
+            -2,
+        );
+        assert_eq!(score, 7);
+        assert_eq!(aligned_a, "GATTACA");
+        assert_eq!(aligned_b, "GATTACA");
+    }
+
+    #[test]
+    fn smith_waterman_no_similarity() {
+        let matrix = ScoringMatrix::simple(1, -1);
+        assert_eq!(
+            smith_waterman(b"AAAA", b"CCCC", &matrix, -1),
+            (0, String::new(), String::new())
+        );
+        assert_eq!(
+            smith_waterman(b"", b"ACGT", &matrix, -1),
+            (0, String::new(), String::new())
+        );
+    }
+
+    #[test]
+    fn local_score_is_at_least_global_score() {
+        let matrix = ScoringMatrix::simple(2, -1);
+        let sequences: [&[u8]; 4] = [b"ACGTTGCA", b"TTGACCA", b"GGGACGT", b"CATCAT"];
+        for a in sequences {
+            for b in sequences {
+                let (global, global_a, global_b) = needleman_wunsch(a, b, &matrix, -1);
+                let (local, local_a, local_b) = smith_waterman(a, b, &matrix, -1);
+                assert!(local >= global && local >= 0);
+                assert_eq!(alignment_score(&global_a, &global_b, &matrix, -1), global);
+                assert_eq!(alignment_score(&local_a, &local_b, &matrix, -1), local);
+                // a local alignment is made of substrings of the sequences
+                let contains = |seq: &[u8], sub: &str| {
+                    String::from_utf8_lossy(seq).contains(&without_gaps(sub))
+                };
+                assert!(contains(a, &local_a) && contains(b, &local_b));
+            }
+        }
+    }
+
+    #[test]
+    fn durbin_examples() {
+        let blosum50 = blosum50_excerpt();
+        let (score, aligned_a, aligned_b) =
+            needleman_wunsch(b"HEAGAWGHEE", b"PAWHEAE", &blosum50, -8);
+        assert_eq!(score, 1);
+        assert_eq!(
+            alignment_score(&aligned_a, &aligned_b, &blosum50, -8),
+            score
+        );
+        let (score, aligned_a, aligned_b) =
+            smith_waterman(b"HEAGAWGHEE", b"PAWHEAE", &blosum50, -8);
+        assert_eq!(
+            (score, aligned_a.as_str(), aligned_b.as_str()),
+            (28, "AWGHE", "AW-HE")
+        );
+    }
+
+    #[test]
+    fn blosum62() {
+        let blosum62 = ScoringMatrix::blosum62();
+        for &x in BLOSUM62_ALPHABET {
+            for &y in BLOSUM62_ALPHABET {
+                assert_eq!(blosum62.score(x, y), blosum62.score(y, x));
+            }
+        }
+        assert_eq!(blosum62.score(b'W', b'W'), 11);
+        assert_eq!(blosum62.score(b'C', b'C'), 9);
+        assert_eq!(blosum62.score(b'A', b'R'), -1);
+        assert_eq!(blosum62.score(b'*', b'*'), 1);
+        // a single substitution of H for Y, which BLOSUM62 finds likely
+        let (score, aligned_a, aligned_b) =
+            needleman_wunsch(b"MKTAYIAKQR", b"MKTAHIAKQR", &blosum62, -4);
+        assert_eq!(score, 5 + 5 + 5 + 4 + 2 + 4 + 4 + 5 + 5 + 5);
+        assert_eq!(
+            (aligned_a.as_str(), aligned_b.as_str()),
+            ("MKTAYIAKQR", "MKTAHIAKQR")
+        );
+    }
+
+    #[test]
+    fn invalid_matrices() {
+        assert_eq!(
+            ScoringMatrix::new(b"AC", &[1, 0, 0]),
+            Err(AlgorithmError::DimensionMismatch {
+                argument: "scores",
+                expected: 4,
+                found: 3
+            })
+        );
+        assert!(matches!(
+            ScoringMatrix::new(b"ACA", &[0; 9]),
+            Err(AlgorithmError::InvalidParameter {
+                argument: "alphabet",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "the symbol 'J' is not in the alphabet")]
+    fn symbol_out_of_the_alphabet() {
+        needleman_wunsch(b"ACJ", b"AC", &ScoringMatrix::blosum62(), -4);
+    }
+
+    #[test]
+    fn affine_gaps_prefer_a_single_gap() {
+        let matrix = ScoringMatrix::simple(2, -1);
+        let (score, aligned_a, aligned_b) =
+            affine_gap_alignment(b"AAAACCCCGGGG", b"AAAAGGGG", &matrix, -5, -1);
+        assert_eq!(score, 16 - 5 - 3);
+        assert_eq!(
+            (aligned_a.as_str(), aligned_b.as_str()),
+            ("AAAACCCCGGGG", "AAAA----GGGG")
+        );
+        assert_eq!(
+            affine_gap_alignment(b"", b"ACG", &matrix, -5, -1),
+            (-7, "---".to_string(), "ACG".to_string())
+        );
+        assert_eq!(
+            affine_gap_alignment(b"", b"", &matrix, -5, -1),
+            (0, String::new(), String::new())
+        );
+    }
+
+    #[test]
+    fn affine_gaps_agree_with_linear_gaps() {
+        let sequences: [&[u8]; 5] = [b"HEAGAWGHEE", b"PAWHEAE", b"MKTAYIAKQR", b"KTAYQ", b""];
+        let blosum62 = ScoringMatrix::blosum62();
+        for a in sequences {
+            for b in sequences {
+                let (affine, affine_a, affine_b) = affine_gap_alignment(a, b, &blosum62, -6, -6);
+                assert_eq!(affine, needleman_wunsch(a, b, &blosum62, -6).0);
+                assert_eq!(
+                    affine_score(&affine_a, &affine_b, &blosum62, -6, -6),
+                    affine
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn affine_gaps_agree_with_brute_force() {
+        let sequences: [&[u8]; 6] = [b"HEAGAW", b"PAWHE", b"WGHEE", b"AW", b"MKTA", b"C"];
+        let blosum62 = ScoringMatrix::blosum62();
+        for a in sequences {
+            for b in sequences {
+                for (gap_open, gap_extend) in [(-10, -1), (-4, -2), (-3, -3)] {
+                    let best = all_alignments(a, b)
+                        .iter()
+                        .map(|(x, y)| affine_score(x, y, &blosum62, gap_open, gap_extend))
+                        .max()
+                        .unwrap();
+                    let (score, aligned_a, aligned_b) =
+                        affine_gap_alignment(a, b, &blosum62, gap_open, gap_extend);
+                    assert_eq!(score, best);
+                    assert_eq!(
+                        affine_score(&aligned_a, &aligned_b, &blosum62, gap_open, gap_extend),
+                        score
+                    );
+                    assert_eq!(aligned_a.replace('-', "").as_bytes(), a);
+                    assert_eq!(aligned_b.replace('-', "").as_bytes(), b);
+                }
+            }
+        }
+    }
+}
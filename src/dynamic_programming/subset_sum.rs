@@ -0,0 +1,123 @@
+//! Subset sums by meet in the middle
+//! <https://en.wikipedia.org/wiki/Subset_sum_problem#Horowitz_and_Sahni>
+//!
+//! The dynamic programming over the sums reachable so far takes O(n target) time and a table
+//! as large as the target, which rules it out for large numbers. Meet in the middle splits
+//! the numbers in two halves instead, and lists the 2^(n/2) sums of the subsets of each one:
+//! a subset of all the numbers is a subset of either half, and its sum is the target when the
+//! sum of its right part is the target minus that of its left part. Sorting the sums of the
+//! right half, a binary search finds the complement of every sum of the left half, in
+//! O(2^(n/2) n) time and O(2^(n/2)) space, whatever the size of the numbers. That is about a
+//! million sums for n = 40.
+//!
+//! The numbers may be negative, and every sum of a subset must fit in an `i64`. The empty
+//! subset counts, with a sum of 0.
+
+/// The sums of all the subsets of `nums`, doubling the list with every number
+fn subset_sums(nums: &[i64]) -> Vec<i64> {
+    let mut sums = Vec::with_capacity(1 << nums.len());
+    sums.push(0);
+    for &num in nums {
+        for i in 0..sums.len() {
+            sums.push(sums[i] + num);
+        }
+    }
+    sums
+}
+
+/// The sums of the subsets of the left half, and the sorted ones of the right half
+fn halves(nums: &[i64]) -> (Vec<i64>, Vec<i64>) {
+    let (left, right) = nums.split_at(nums.len() / 2);
+    let mut right = subset_sums(right);
+    right.sort_unstable();
+    (subset_sums(left), right)
+}
+
+/// Tells whether some subset of `nums` adds up to `target`.
+pub fn subset_sum_mitm(nums: &[i64], target: i64) -> bool {
+    let (left, right) = halves(nums);
+    left.iter().any(|&sum| {
+        target
+            .checked_sub(sum)
+            .is_some_and(|complement| right.binary_search(&complement).is_ok())
+    })
+}
+
+/// Returns the number of subsets of `nums` adding up to `target`, equal numbers at different
+/// positions making different subsets.
+pub fn count_subsets_with_sum_mitm(nums: &[i64], target: i64) -> u64 {
+    let (left, right) = halves(nums);
+    left.iter()
+        .filter_map(|&sum| target.checked_sub(sum))
+        .map(|complement| {
+            let start = right.partition_point(|&x| x < complement);
+            let end = right.partition_point(|&x| x <= complement);
+            (end - start) as u64
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    /// The number of subsets of every sum up to `target`, for nonnegative numbers
+    fn dynamic_programming(nums: &[i64], target: i64) -> Vec<u64> {
+        let mut ways = vec![0u64; target as usize + 1];
+        ways[0] = 1;
+        for &num in nums {
+            for sum in (num as usize..=target as usize).rev() {
+                ways[sum] += ways[sum - num as usize];
+            }
+        }
+        ways
+    }
+
+    #[test]
+    fn small_sets() {
+        let nums = [3, 34, 4, 12, 5, 2];
+        assert!(subset_sum_mitm(&nums, 9));
+        assert!(!subset_sum_mitm(&nums, 30));
+        assert_eq!(count_subsets_with_sum_mitm(&nums, 9), 2);
+        // the empty subset
+        assert!(subset_sum_mitm(&[], 0));
+        assert!(!subset_sum_mitm(&[], 1));
+        assert_eq!(count_subsets_with_sum_mitm(&[5], 0), 1);
+        assert_eq!(count_subsets_with_sum_mitm(&[5], 5), 1);
+        // negative numbers, and sums close to the bounds of i64
+        assert!(subset_sum_mitm(&[-7, 3, 10, -1], 2));
+        assert_eq!(count_subsets_with_sum_mitm(&[-7, 3, 10, -1], 2), 2);
+        assert!(subset_sum_mitm(&[i64::MAX, -1], i64::MAX - 1));
+        assert!(!subset_sum_mitm(&[i64::MIN + 1, 2], i64::MAX));
+        assert_eq!(count_subsets_with_sum_mitm(&[i64::MIN], i64::MAX), 0);
+    }
+
+    #[test]
+    fn agrees_with_dynamic_programming() {
+        let mut rng = ChaCha8Rng::seed_from_u64(155);
+        for _ in 0..25 {
+            let n = rng.gen_range(0..=20);
+            let nums: Vec<i64> = (0..n).map(|_| rng.gen_range(0..50)).collect();
+            let ways = dynamic_programming(&nums, 300);
+            for (target, &count) in ways.iter().enumerate() {
+                assert_eq!(count_subsets_with_sum_mitm(&nums, target as i64), count);
+                assert_eq!(subset_sum_mitm(&nums, target as i64), count > 0);
+            }
+        }
+    }
+
+    #[test]
+    fn forty_large_numbers() {
+        // a table as large as these sums is out of the question
+        let mut rng = ChaCha8Rng::seed_from_u64(1550);
+        let nums: Vec<i64> = (0..40)
+            .map(|_| rng.gen_range(1_000_000_000_000..2_000_000_000_000))
+            .collect();
+        let chosen: i64 = nums.iter().filter(|_| rng.gen_bool(0.5)).sum();
+        assert!(subset_sum_mitm(&nums, chosen));
+        assert!(!subset_sum_mitm(&nums, 999_999_999_999));
+        assert_eq!(count_subsets_with_sum_mitm(&[1; 40], 20), 137_846_528_820);
+    }
+}
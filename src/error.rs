@@ -0,0 +1,167 @@
+//! Errors returned by the algorithms of the crate on invalid input.
+//!
+//! Every variant names the offending argument, so that a caller can tell which of the inputs
+//! was rejected. Functions returning these errors check their input up front instead of
+//! panicking in the middle of a computation.
+
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AlgorithmError {
+    /// An input does not have the size required by the other inputs
+    DimensionMismatch {
+        argument: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    /// An input is empty, but the computation needs at least one element
+    EmptyInput { argument: &'static str },
+    /// A parameter is out of its valid range, like a negative learning rate or `k = 0`
+    InvalidParameter {
+        argument: &'static str,
+        reason: String,
+    },
+    /// A matrix derived from an input cannot be inverted or decomposed
+    SingularMatrix { argument: &'static str },
+    /// A computation on an input produced an infinite or NaN value
+    NonFiniteValue { argument: &'static str },
+}
+
+pub type AlgorithmResult<T> = Result<T, AlgorithmError>;
+
+impl fmt::Display for AlgorithmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AlgorithmError::DimensionMismatch {
+                argument,
+                expected,
+                found,
+            } => write!(
+                f,
+                "`{argument}` has size {found}, but size {expected} was expected"
+            ),
+            AlgorithmError::EmptyInput { argument } => write!(f, "`{argument}` is empty"),
+            AlgorithmError::InvalidParameter { argument, reason } => {
+                write!(f, "invalid `{argument}`: {reason}")
+            }
+            AlgorithmError::SingularMatrix { argument } => {
+                write!(f, "the matrix given by `{argument}` is singular")
+            }
+            AlgorithmError::NonFiniteValue { argument } => {
+                write!(f, "a non-finite value was computed from `{argument}`")
+            }
+        }
+    }
+}
+
+impl Error for AlgorithmError {}
+
+/// Checks that a parameter like a learning rate is finite and strictly positive.
+pub(crate) fn check_positive(argument: &'static str, value: f64) -> AlgorithmResult<()> {
+    if value.is_finite() && value > 0.0 {
+        Ok(())
+    } else {
+        Err(AlgorithmError::InvalidParameter {
+            argument,
+            reason: format!("must be finite and positive, got {value}"),
+        })
+    }
+}
+
+/// Checks that two inputs going together have the same length, and are not empty.
+pub(crate) fn check_same_length(
+    first: (&'static str, usize),
+    second: (&'static str, usize),
+) -> AlgorithmResult<()> {
+    if first.1 == 0 {
+        return Err(AlgorithmError::EmptyInput { argument: first.0 });
+    }
+    if first.1 != second.1 {
+        return Err(AlgorithmError::DimensionMismatch {
+            argument: second.0,
+            expected: first.1,
+            found: second.1,
+        });
+    }
+    Ok(())
+}
+
+/// Checks that a data set has at least one row, that all rows have the same positive number of
+/// dimensions, and that all values are finite. Returns the number of dimensions.
+pub(crate) fn check_rows(argument: &'static str, rows: &[Vec<f64>]) -> AlgorithmResult<usize> {
+    let dimensions = match rows.first() {
+        Some(first) if !first.is_empty() => first.len(),
+        _ => return Err(AlgorithmError::EmptyInput { argument }),
+    };
+    if let Some(row) = rows.iter().find(|row| row.len() != dimensions) {
+        return Err(AlgorithmError::DimensionMismatch {
+            argument,
+            expected: dimensions,
+            found: row.len(),
+        });
+    }
+    if rows.iter().flatten().any(|x| !x.is_finite()) {
+        return Err(AlgorithmError::NonFiniteValue { argument });
+    }
+    Ok(dimensions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let error = AlgorithmError::DimensionMismatch {
+            argument: "actual",
+            expected: 3,
+            found: 2,
+        };
+        assert_eq!(
+            error.to_string(),
+            "`actual` has size 2, but size 3 was expected"
+        );
+        let error = AlgorithmError::InvalidParameter {
+            argument: "learning_rate",
+            reason: "must be finite and positive, got -1".to_string(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "invalid `learning_rate`: must be finite and positive, got -1"
+        );
+        // usable as a boxed error
+        let boxed: Box<dyn Error> = Box::new(AlgorithmError::EmptyInput { argument: "inputs" });
+        assert_eq!(boxed.to_string(), "`inputs` is empty");
+    }
+
+    #[test]
+    fn checks() {
+        assert!(check_positive("rate", 0.5).is_ok());
+        for value in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(check_positive("rate", value).is_err());
+        }
+        assert!(check_same_length(("a", 2), ("b", 2)).is_ok());
+        assert_eq!(
+            check_same_length(("a", 0), ("b", 0)),
+            Err(AlgorithmError::EmptyInput { argument: "a" })
+        );
+        assert_eq!(
+            check_same_length(("a", 2), ("b", 3)),
+            Err(AlgorithmError::DimensionMismatch {
+                argument: "b",
+                expected: 2,
+                found: 3
+            })
+        );
+        assert_eq!(check_rows("data", &[vec![1.0, 2.0], vec![3.0, 4.0]]), Ok(2));
+        assert_eq!(
+            check_rows("data", &[vec![]]),
+            Err(AlgorithmError::EmptyInput { argument: "data" })
+        );
+        assert_eq!(
+            check_rows("data", &[vec![1.0], vec![f64::NAN]]),
+            Err(AlgorithmError::NonFiniteValue { argument: "data" })
+        );
+    }
+}
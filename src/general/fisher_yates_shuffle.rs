@@ -1,25 +1,25 @@
-use std::time::{SystemTime, UNIX_EPOCH};
-
-use crate::math::PCG32;
-
-const DEFAULT: u64 = 4294967296;
-
-fn gen_range(range: usize, generator: &mut PCG32) -> usize {
-    generator.get_u64() as usize % range
-}
-
+/// Shuffles `array` in place, uniformly, with the thread-local random generator.
+///
+/// This is `algorithms::fisher_yates_shuffle`, which takes any slice and random generator.
 pub fn fisher_yates_shuffle(array: &mut [i32]) {
-    let seed = match SystemTime::now().duration_since(UNIX_EPOCH) {
-        Ok(duration) => duration.as_millis() as u64,
-        Err(_) => DEFAULT,
-    };
-
-    let mut random_generator = PCG32::new_default(seed);
+    crate::algorithms::fisher_yates_shuffle(array, &mut rand::thread_rng());
+}
 
-    let len = array.len();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    for i in 0..(len - 2) {
-        let r = gen_range(len - i, &mut random_generator);
-        array.swap(i, i + r);
+    #[test]
+    fn short_and_long_slices() {
+        // slices shorter than 2 used to underflow
+        let mut empty: [i32; 0] = [];
+        fisher_yates_shuffle(&mut empty);
+        let mut one = [7];
+        fisher_yates_shuffle(&mut one);
+        assert_eq!(one, [7]);
+        let mut array: Vec<i32> = (0..100).collect();
+        fisher_yates_shuffle(&mut array);
+        array.sort_unstable();
+        assert_eq!(array, (0..100).collect::<Vec<i32>>());
     }
 }
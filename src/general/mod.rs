@@ -1,6 +1,5 @@
 mod convex_hull;
 mod fisher_yates_shuffle;
-mod genetic;
 mod hanoi;
 mod huffman_encoding;
 mod kadane_algorithm;
@@ -11,7 +10,6 @@ mod two_sum;
 
 pub use self::convex_hull::convex_hull_graham;
 pub use self::fisher_yates_shuffle::fisher_yates_shuffle;
-pub use self::genetic::GeneticAlgorithm;
 pub use self::hanoi::hanoi;
 pub use self::huffman_encoding::{HuffmanDictionary, HuffmanEncoding};
 pub use self::kadane_algorithm::max_sub_array;
@@ -4,6 +4,7 @@ mod jarvis_scan;
 mod point;
 mod polygon_points;
 mod segment;
+mod sweep_line;
 
 pub use self::closest_points::closest_points;
 pub use self::graham_scan::graham_scan;
@@ -11,3 +12,4 @@ pub use self::jarvis_scan::jarvis_march;
 pub use self::point::Point;
 pub use self::polygon_points::lattice_points;
 pub use self::segment::Segment;
+pub use self::sweep_line::find_all_intersections;
@@ -0,0 +1,429 @@
+use super::{Point, Segment};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashSet};
+
+/// Points and coordinates closer than this are the same.
+const EPSILON: f64 = 1e-9;
+
+/// A point of the event queue, ordered by `x` and then by `y`
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Event {
+    x: f64,
+    y: f64,
+}
+
+impl Eq for Event {}
+
+impl Ord for Event {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.x.total_cmp(&other.x).then(self.y.total_cmp(&other.y))
+    }
+}
+
+impl PartialOrd for Event {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Event {
+    fn is_after(&self, other: &Event) -> bool {
+        self.x > other.x + EPSILON
+            || ((self.x - other.x).abs() <= EPSILON && self.y > other.y + EPSILON)
+    }
+
+    fn is_near(&self, other: &Event) -> bool {
+        (self.x - other.x).abs() <= EPSILON && (self.y - other.y).abs() <= EPSILON
+    }
+}
+
+/// A segment from its smallest end to its largest one, by `x` and then by `y`
+struct Oriented {
+    left: Event,
+    right: Event,
+}
+
+impl Oriented {
+    fn is_vertical(&self) -> bool {
+        self.left.x == self.right.x
+    }
+
+    fn slope(&self) -> f64 {
+        if self.is_vertical() {
+            f64::INFINITY
+        } else {
+            (self.right.y - self.left.y) / (self.right.x - self.left.x)
+        }
+    }
+
+    /// The height of the segment on the sweep line at the event `p`, `p.y` for a vertical
+    /// segment through `p`
+    fn y_at(&self, p: &Event) -> f64 {
+        if self.is_vertical() {
+            p.y.clamp(self.left.y, self.right.y)
+        } else {
+            self.left.y + (p.x - self.left.x) * self.slope()
+        }
+    }
+
+    /// The single intersection point with `other`, if they cross or touch at one point
+    fn intersection(&self, other: &Oriented) -> Option<Event> {
+        let (dx, dy) = (self.right.x - self.left.x, self.right.y - self.left.y);
+        let (ex, ey) = (other.right.x - other.left.x, other.right.y - other.left.y);
+        let denominator = dx * ey - dy * ex;
+        if denominator == 0.0 {
+            return None;
+        }
+        let (fx, fy) = (other.left.x - self.left.x, other.left.y - self.left.y);
+        let t = (fx * ey - fy * ex) / denominator;
+        let u = (fx * dy - fy * dx) / denominator;
+        let range = -EPSILON..=1.0 + EPSILON;
+        (range.contains(&t) && range.contains(&u)).then_some(Event {
+            x: self.left.x + t * dx,
+            y: self.left.y + t * dy,
+        })
+    }
+}
+
+struct Sweep {
+    segments: Vec<Oriented>,
+    /// The segments crossing the sweep line, from the lowest
+    status: Vec<usize>,
+    /// The events to come, with the segments starting at them
+    events: BTreeMap<Event, Vec<usize>>,
+    reported: HashSet<(usize, usize)>,
+    intersections: Vec<(usize, usize, Point)>,
+}
+
+impl Sweep {
+    /// Adds an event, merged with an event already queued at the same point.
+    fn add_event(&mut self, point: Event) -> &mut Vec<usize> {
+        let low = Event {
+            x: point.x - EPSILON,
+            y: f64::NEG_INFINITY,
+        };
+        let high = Event {
+            x: point.x + EPSILON,
+            y: f64::INFINITY,
+        };
+        let near = self
+            .events
+            .range(low..=high)
+            .map(|(event, _)| *event)
+            .find(|event| event.is_near(&point));
+        self.events.entry(near.unwrap_or(point)).or_default()
+    }
+
+    /// Queues the crossing of two neighbors on the sweep line if it is still to come.
+    fn check(&mut self, below: usize, above: usize, p: &Event) {
+        if let Some(point) = self.segments[below].intersection(&self.segments[above]) {
+            if point.is_after(p) {
+                self.add_event(point);
+            }
+        }
+    }
+
+    fn handle(&mut self, p: Event, starting: Vec<usize>) {
+        // the segments through p are together on the sweep line
+        let start = self
+            .status
+            .partition_point(|&s| self.segments[s].y_at(&p) < p.y - EPSILON);
+        let mut end = start;
+        while end < self.status.len() && self.segments[self.status[end]].y_at(&p) <= p.y + EPSILON {
+            end += 1;
+        }
+        let through: Vec<usize> = self.status.drain(start..end).collect();
+        let mut all: Vec<usize> = through.iter().chain(&starting).copied().collect();
+        all.sort_unstable();
+        for (k, &i) in all.iter().enumerate() {
+            for &j in &all[k + 1..] {
+                if self.reported.insert((i, j)) {
+                    self.intersections.push((i, j, Point::new(p.x, p.y)));
+                }
+            }
+        }
+        // the segments going on after p, from the lowest just after it
+        let mut after: Vec<usize> = through
+            .into_iter()
+            .filter(|&s| self.segments[s].right.is_after(&p))
+            .chain(starting)
+            .collect();
+        after.sort_by(|&a, &b| {
+            let (a_slope, b_slope) = (self.segments[a].slope(), self.segments[b].slope());
+            a_slope.total_cmp(&b_slope).then(a.cmp(&b))
+        });
+        let below = start.checked_sub(1).map(|i| self.status[i]);
+        let above = self.status.get(start).copied();
+        match (after.first(), after.last()) {
+            (Some(&lowest), Some(&highest)) => {
+                if let Some(below) = below {
+                    self.check(below, lowest, &p);
+                }
+                if let Some(above) = above {
+                    self.check(highest, above, &p);
+                }
+            }
+            _ => {
+                if let (Some(below), Some(above)) = (below, above) {
+                    self.check(below, above, &p);
+                }
+            }
+        }
+        self.status.splice(start..start, after);
+    }
+}
+
+/// Finds every pair of intersecting segments, with the smallest point they share by `x` and then
+/// by `y`, with the Bentley-Ottmann algorithm: `(i, j, point)` for the segments `i < j`, sorted
+/// by `(i, j)`.
+/// <https://en.wikipedia.org/wiki/Bentley%E2%80%93Ottmann_algorithm>
+///
+/// A vertical line sweeps the plane from left to right, with the segments crossing it sorted by
+/// height. Two segments crossing at a point are next to each other on the line just before it,
+/// so that only the neighbors on the line are checked, when a segment starts or ends and when
+/// two segments cross and swap. The events are the ends of the segments and the crossings of
+/// neighbors, and every event reports the segments through it. The line is a sorted vector,
+/// searched in O(log(n)), whose shifts are fast but O(n) in the worst case, where a balanced
+/// tree takes O((n + k) log(n)) for `k` intersections.
+///
+/// Vertical segments, shared ends, several segments through a point and collinear segments
+/// overlapping along a part are all fine, the overlapping ones reported once, at the start of
+/// their overlap. Points closer than 1e-9 are the same.
+///
+/// # Panics
+///
+/// Panics if a segment is a single point.
+pub fn find_all_intersections(segments: &[Segment]) -> Vec<(usize, usize, Point)> {
+    let segments: Vec<Oriented> = segments
+        .iter()
+        .map(|segment| {
+            let a = Event {
+                x: segment.a.x,
+                y: segment.a.y,
+            };
+            let b = Event {
+                x: segment.b.x,
+                y: segment.b.y,
+            };
+            assert!(
+                !a.is_near(&b),
+                "the segment ({}, {}) is a single point",
+                a.x,
+                a.y
+            );
+            let (left, right) = if a < b { (a, b) } else { (b, a) };
+            Oriented { left, right }
+        })
+        .collect();
+    let mut sweep = Sweep {
+        segments,
+        status: vec![],
+        events: BTreeMap::new(),
+        reported: HashSet::new(),
+        intersections: vec![],
+    };
+    for i in 0..sweep.segments.len() {
+        let (left, right) = (sweep.segments[i].left, sweep.segments[i].right);
+        sweep.add_event(left).push(i);
+        sweep.add_event(right);
+    }
+    while let Some((p, starting)) = sweep.events.pop_first() {
+        sweep.handle(p, starting);
+    }
+    let mut intersections = sweep.intersections;
+    intersections.sort_by_key(|&(i, j, _)| (i, j));
+    intersections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    /// The smallest point of the intersection of the two segments, from all pairs of ends and
+    /// crossings in O(1)
+    fn naive_intersection(s: &Segment, t: &Segment) -> Option<Point> {
+        let (s, t) = (oriented(s), oriented(t));
+        // the ends lying on the other segment, for touching and overlapping segments
+        let on = |p: &Event, segment: &Oriented| {
+            let cross = (segment.right.x - segment.left.x) * (p.y - segment.left.y)
+                - (segment.right.y - segment.left.y) * (p.x - segment.left.x);
+            let length = ((segment.right.x - segment.left.x).powi(2)
+                + (segment.right.y - segment.left.y).powi(2))
+            .sqrt();
+            cross.abs() <= EPSILON * length
+                && segment.left.x.min(segment.right.x) - EPSILON <= p.x
+                && p.x <= segment.left.x.max(segment.right.x) + EPSILON
+                && segment.left.y.min(segment.right.y) - EPSILON <= p.y
+                && p.y <= segment.left.y.max(segment.right.y) + EPSILON
+        };
+        let mut candidates: Vec<Event> =
+            [(&s.left, &t), (&s.right, &t), (&t.left, &s), (&t.right, &s)]
+                .into_iter()
+                .filter(|(p, segment)| on(p, segment))
+                .map(|(p, _)| *p)
+                .collect();
+        candidates.extend(s.intersection(&t));
+        candidates.into_iter().min().map(|p| Point::new(p.x, p.y))
+    }
+
+    fn oriented(segment: &Segment) -> Oriented {
+        let a = Event {
+            x: segment.a.x,
+            y: segment.a.y,
+        };
+        let b = Event {
+            x: segment.b.x,
+            y: segment.b.y,
+        };
+        let (left, right) = if a < b { (a, b) } else { (b, a) };
+        Oriented { left, right }
+    }
+
+    fn naive(segments: &[Segment]) -> Vec<(usize, usize, Point)> {
+        let mut found = vec![];
+        for i in 0..segments.len() {
+            for j in i + 1..segments.len() {
+                if let Some(point) = naive_intersection(&segments[i], &segments[j]) {
+                    found.push((i, j, point));
+                }
+            }
+        }
+        found
+    }
+
+    fn assert_same(found: &[(usize, usize, Point)], expected: &[(usize, usize, Point)]) {
+        let pairs = |list: &[(usize, usize, Point)]| -> Vec<(usize, usize)> {
+            list.iter().map(|&(i, j, _)| (i, j)).collect()
+        };
+        assert_eq!(pairs(found), pairs(expected));
+        for ((_, _, p), (_, _, q)) in found.iter().zip(expected) {
+            assert!(p.euclidean_distance(q) < 1e-9, "{p:?} {q:?}");
+        }
+    }
+
+    #[test]
+    fn crossings() {
+        let segments = [
+            Segment::new(0.0, 0.0, 4.0, 4.0),
+            Segment::new(0.0, 4.0, 4.0, 0.0),
+            Segment::new(5.0, 5.0, 6.0, 6.0),
+            Segment::new(0.0, 1.0, 4.0, 1.0),
+        ];
+        let found = find_all_intersections(&segments);
+        let expected = [
+            (0, 1, Point::new(2.0, 2.0)),
+            (0, 3, Point::new(1.0, 1.0)),
+            (1, 3, Point::new(3.0, 1.0)),
+        ];
+        assert_same(&found, &expected);
+        assert!(find_all_intersections(&[]).is_empty());
+    }
+
+    #[test]
+    fn degenerate_cases() {
+        // parallel segments, and collinear ones apart
+        let parallel = [
+            Segment::new(-5.0, 0.0, 5.0, 0.0),
+            Segment::new(-5.0, 1.0, 5.0, 1.0),
+            Segment::new(6.0, 0.0, 8.0, 0.0),
+        ];
+        assert!(find_all_intersections(&parallel).is_empty());
+        // shared ends, and an end inside another segment
+        let shared = [
+            Segment::new(0.0, 0.0, 2.0, 2.0),
+            Segment::new(2.0, 2.0, 4.0, 0.0),
+            Segment::new(0.0, 0.0, 3.0, 0.0),
+            Segment::new(1.0, 0.0, 1.0, -3.0),
+        ];
+        let expected = [
+            (0, 1, Point::new(2.0, 2.0)),
+            (0, 2, Point::new(0.0, 0.0)),
+            (2, 3, Point::new(1.0, 0.0)),
+        ];
+        assert_same(&find_all_intersections(&shared), &expected);
+        // overlapping collinear segments, reported at the start of the overlap
+        let collinear = [
+            Segment::new(0.0, 0.0, 4.0, 2.0),
+            Segment::new(6.0, 3.0, 2.0, 1.0),
+            Segment::new(3.0, 0.0, 3.0, 4.0),
+        ];
+        let expected = [
+            (0, 1, Point::new(2.0, 1.0)),
+            (0, 2, Point::new(3.0, 1.5)),
+            (1, 2, Point::new(3.0, 1.5)),
+        ];
+        assert_same(&find_all_intersections(&collinear), &expected);
+        // vertical segments crossing, overlapping and touching horizontal ones
+        let vertical = [
+            Segment::new(1.0, 0.0, 1.0, 5.0),
+            Segment::new(0.0, 1.0, 2.0, 1.0),
+            Segment::new(0.0, 3.0, 1.0, 3.0),
+            Segment::new(1.0, 4.0, 1.0, 6.0),
+            Segment::new(1.0, 6.0, 3.0, 6.0),
+        ];
+        assert_same(&find_all_intersections(&vertical), &naive(&vertical));
+        assert_eq!(find_all_intersections(&vertical).len(), 4);
+    }
+
+    #[test]
+    fn many_segments_through_a_point() {
+        let segments: Vec<Segment> = (0..8)
+            .map(|k| {
+                let angle = k as f64 * std::f64::consts::PI / 8.0;
+                Segment::new(angle.cos(), angle.sin(), -angle.cos(), -angle.sin())
+            })
+            .collect();
+        let found = find_all_intersections(&segments);
+        assert_eq!(found.len(), 28);
+        assert!(found
+            .iter()
+            .all(|(_, _, p)| p.euclidean_distance(&Point::new(0.0, 0.0)) < 1e-9));
+    }
+
+    #[test]
+    fn random_segments() {
+        let mut rng = ChaCha8Rng::seed_from_u64(135);
+        for _ in 0..100 {
+            let segments: Vec<Segment> = (0..50)
+                .map(|_| {
+                    Segment::new(
+                        rng.gen_range(0.0..100.0),
+                        rng.gen_range(0.0..100.0),
+                        rng.gen_range(0.0..100.0),
+                        rng.gen_range(0.0..100.0),
+                    )
+                })
+                .collect();
+            assert_same(&find_all_intersections(&segments), &naive(&segments));
+        }
+    }
+
+    #[test]
+    fn random_segments_on_a_grid() {
+        // small integer coordinates, with many shared ends, vertical and collinear segments
+        let mut rng = ChaCha8Rng::seed_from_u64(1350);
+        for _ in 0..300 {
+            let mut segments = vec![];
+            while segments.len() < 15 {
+                let (x1, y1, x2, y2) = (
+                    rng.gen_range(0..6) as f64,
+                    rng.gen_range(0..6) as f64,
+                    rng.gen_range(0..6) as f64,
+                    rng.gen_range(0..6) as f64,
+                );
+                if (x1, y1) != (x2, y2) {
+                    segments.push(Segment::new(x1, y1, x2, y2));
+                }
+            }
+            assert_same(&find_all_intersections(&segments), &naive(&segments));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the segment (1, 2) is a single point")]
+    fn single_point() {
+        find_all_intersections(&[Segment::new(1.0, 2.0, 1.0, 2.0)]);
+    }
+}
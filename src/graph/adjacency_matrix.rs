@@ -0,0 +1,266 @@
+use std::collections::BTreeMap;
+
+type Graph<V, E> = BTreeMap<V, BTreeMap<V, E>>;
+
+/// A weighted, directed graph on the vertices `0..n`, stored as an n x n matrix.
+///
+/// The weight of the edge from `u` to `v` is in the cell `u * n + v`, so that finding whether
+/// an edge exists takes O(1), against O(log(n)) in the adjacency maps of the other algorithms of
+/// this module. Listing the neighbors of a vertex takes O(n) instead, and the matrix takes
+/// O(n^2) memory however few edges there are, which suits dense graphs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AdjacencyMatrix {
+    vertices: usize,
+    weights: Vec<Option<u64>>,
+}
+
+impl AdjacencyMatrix {
+    /// Creates a graph of `n` vertices without edges.
+    pub fn new(n: usize) -> Self {
+        AdjacencyMatrix {
+            vertices: n,
+            weights: vec![None; n * n],
+        }
+    }
+
+    /// Returns the number of vertices.
+    pub fn vertices(&self) -> usize {
+        self.vertices
+    }
+
+    fn cell(&self, u: usize, v: usize) -> usize {
+        assert!(
+            u < self.vertices && v < self.vertices,
+            "the edge ({u}, {v}) is out of a graph of {} vertices",
+            self.vertices
+        );
+        u * self.vertices + v
+    }
+
+    /// Adds an edge from `u` to `v`, or changes its weight if it exists.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `u` or `v` is not a vertex of the graph.
+    pub fn add_edge(&mut self, u: usize, v: usize, weight: u64) {
+        let cell = self.cell(u, v);
+        self.weights[cell] = Some(weight);
+    }
+
+    /// Removes the edge from `u` to `v`, and returns its weight if it existed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `u` or `v` is not a vertex of the graph.
+    pub fn remove_edge(&mut self, u: usize, v: usize) -> Option<u64> {
+        let cell = self.cell(u, v);
+        self.weights[cell].take()
+    }
+
+    /// Returns the weight of the edge from `u` to `v`, if it exists.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `u` or `v` is not a vertex of the graph.
+    pub fn weight(&self, u: usize, v: usize) -> Option<u64> {
+        self.weights[self.cell(u, v)]
+    }
+
+    /// Tells whether there is an edge from `u` to `v`, in O(1).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `u` or `v` is not a vertex of the graph.
+    pub fn has_edge(&self, u: usize, v: usize) -> bool {
+        self.weight(u, v).is_some()
+    }
+
+    /// Returns the vertices which `v` has an edge to, in increasing order, in O(n).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `v` is not a vertex of the graph.
+    pub fn neighbors(&self, v: usize) -> impl Iterator<Item = usize> + '_ {
+        let row = self.cell(v, 0);
+        self.weights[row..row + self.vertices]
+            .iter()
+            .enumerate()
+            .filter_map(|(u, weight)| weight.map(|_| u))
+    }
+
+    /// Returns the graph as adjacency maps, in which every vertex is a key, even without edges.
+    pub fn to_adjacency_list(&self) -> Graph<usize, u64> {
+        (0..self.vertices)
+            .map(|u| {
+                let edges = self
+                    .neighbors(u)
+                    .map(|v| (v, self.weights[u * self.vertices + v].unwrap()))
+                    .collect();
+                (u, edges)
+            })
+            .collect()
+    }
+
+    /// Builds the matrix of a graph given as adjacency maps on the vertices `0..n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a vertex of the graph is not below `n`.
+    pub fn from_adjacency_list(n: usize, graph: &Graph<usize, u64>) -> Self {
+        let mut matrix = AdjacencyMatrix::new(n);
+        for (&u, edges) in graph {
+            for (&v, &weight) in edges {
+                matrix.add_edge(u, v, weight);
+            }
+        }
+        matrix
+    }
+
+    /// Returns the distance of the shortest path from every vertex to every other with the
+    /// Floyd-Warshall algorithm, in O(n^3), `None` when there is no path.
+    ///
+    /// `distances[u][v]` is the distance from `u` to `v`, 0 when they are the same, since the
+    /// weights are not negative.
+    pub fn floyd_warshall(&self) -> Vec<Vec<Option<u64>>> {
+        let n = self.vertices;
+        let mut distances = self.weights.clone();
+        for v in 0..n {
+            distances[v * n + v] = Some(0);
+        }
+        for k in 0..n {
+            for i in 0..n {
+                let Some(to_k) = distances[i * n + k] else {
+                    continue;
+                };
+                for j in 0..n {
+                    let Some(from_k) = distances[k * n + j] else {
+                        continue;
+                    };
+                    let through_k = to_k.saturating_add(from_k);
+                    if distances[i * n + j].is_none_or(|direct| through_k < direct) {
+                        distances[i * n + j] = Some(through_k);
+                    }
+                }
+            }
+        }
+        distances.chunks(n.max(1)).map(<[_]>::to_vec).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::floyd_warshall;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use std::time::Instant;
+
+    fn random_graph(rng: &mut ChaCha8Rng, n: usize, density: f64) -> AdjacencyMatrix {
+        let mut matrix = AdjacencyMatrix::new(n);
+        for u in 0..n {
+            for v in 0..n {
+                if u != v && rng.gen_bool(density) {
+                    matrix.add_edge(u, v, rng.gen_range(1..100));
+                }
+            }
+        }
+        matrix
+    }
+
+    #[test]
+    fn edges() {
+        let mut matrix = AdjacencyMatrix::new(4);
+        matrix.add_edge(0, 1, 5);
+        matrix.add_edge(0, 3, 2);
+        matrix.add_edge(2, 0, 7);
+        matrix.add_edge(0, 1, 4);
+        assert!(matrix.has_edge(0, 1));
+        assert!(!matrix.has_edge(1, 0));
+        assert_eq!(matrix.weight(0, 1), Some(4));
+        assert_eq!(matrix.neighbors(0).collect::<Vec<_>>(), [1, 3]);
+        assert_eq!(matrix.neighbors(1).count(), 0);
+        assert_eq!(matrix.remove_edge(0, 3), Some(2));
+        assert_eq!(matrix.remove_edge(0, 3), None);
+        assert_eq!(matrix.neighbors(0).collect::<Vec<_>>(), [1]);
+        assert_eq!(
+            AdjacencyMatrix::new(0).floyd_warshall(),
+            Vec::<Vec<_>>::new()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "the edge (1, 4) is out of a graph of 4 vertices")]
+    fn out_of_range() {
+        AdjacencyMatrix::new(4).has_edge(1, 4);
+    }
+
+    #[test]
+    fn agrees_with_the_adjacency_list() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1215);
+        for (n, density) in [(1, 0.5), (10, 0.2), (30, 0.1), (30, 0.8)] {
+            let matrix = random_graph(&mut rng, n, density);
+            let list = matrix.to_adjacency_list();
+            assert_eq!(list.len(), n);
+            assert_eq!(AdjacencyMatrix::from_adjacency_list(n, &list), matrix);
+            for u in 0..n {
+                for v in 0..n {
+                    assert_eq!(matrix.has_edge(u, v), list[&u].contains_key(&v));
+                }
+                assert!(matrix.neighbors(u).eq(list[&u].keys().copied()));
+            }
+
+            let distances = matrix.floyd_warshall();
+            let expected = floyd_warshall(&list);
+            for (u, row) in distances.iter().enumerate() {
+                for (v, &distance) in row.iter().enumerate() {
+                    assert_eq!(distance, expected[&u].get(&v).copied(), "{u} {v}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn shortest_paths() {
+        let mut matrix = AdjacencyMatrix::new(5);
+        matrix.add_edge(0, 1, 4);
+        matrix.add_edge(0, 2, 1);
+        matrix.add_edge(2, 1, 2);
+        matrix.add_edge(1, 3, 1);
+        matrix.add_edge(3, 0, 10);
+        let distances = matrix.floyd_warshall();
+        assert_eq!(distances[0], [Some(0), Some(3), Some(1), Some(4), None]);
+        assert_eq!(distances[3], [Some(10), Some(13), Some(11), Some(0), None]);
+        assert_eq!(distances[4], [None, None, None, None, Some(0)]);
+    }
+
+    #[test]
+    #[ignore = "slow test for measuring time"]
+    fn has_edge_perf() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1216);
+        let queries = 1_000_000;
+        for n in [10, 100, 1000] {
+            let matrix = random_graph(&mut rng, n, 0.5);
+            let list = matrix.to_adjacency_list();
+            let pairs: Vec<(usize, usize)> = (0..queries)
+                .map(|_| (rng.gen_range(0..n), rng.gen_range(0..n)))
+                .collect();
+            let now = Instant::now();
+            let in_matrix = pairs
+                .iter()
+                .filter(|&&(u, v)| matrix.has_edge(u, v))
+                .count();
+            let matrix_time = now.elapsed();
+            let now = Instant::now();
+            let in_list = pairs
+                .iter()
+                .filter(|&&(u, v)| list[&u].contains_key(&v))
+                .count();
+            let list_time = now.elapsed();
+            assert_eq!(in_matrix, in_list);
+            println!("{n} vertices: matrix {matrix_time:?}, adjacency list {list_time:?}");
+            if n == 1000 {
+                assert!(matrix_time < list_time);
+            }
+        }
+    }
+}
@@ -1,5 +1,5 @@
 // Adjacency List
-use std::collections::VecDeque;
+use super::hopcroft_karp;
 type Graph = Vec<Vec<usize>>;
 
 pub struct BipartiteMatching {
@@ -58,81 +58,22 @@ impl BipartiteMatching {
             println!("Vertex {} in grp1 matched with {} grp2", self.mt2[i], i)
         }
     }
-    fn bfs(&self, dist: &mut [i32]) -> bool {
-        let mut q = VecDeque::new();
-        for (u, d_i) in dist
-            .iter_mut()
-            .enumerate()
-            .skip(1)
-            .take(self.num_vertices_grp1)
-        {
-            if self.mt1[u] == 0 {
-                // u is not matched
-                *d_i = 0;
-                q.push_back(u);
-            } else {
-                // else set the vertex distance as infinite because it is matched
-                // this will be considered the next time
-
-                *d_i = i32::max_value();
-            }
-        }
-        dist[0] = i32::max_value();
-        while !q.is_empty() {
-            let u = *q.front().unwrap();
-            q.pop_front();
-            if dist[u] < dist[0] {
-                for i in 0..self.adj[u].len() {
-                    let v = self.adj[u][i];
-                    if dist[self.mt2[v] as usize] == i32::max_value() {
-                        dist[self.mt2[v] as usize] = dist[u] + 1;
-                        q.push_back(self.mt2[v] as usize);
-                    }
-                }
-            }
-        }
-        dist[0] != i32::max_value()
-    }
-    fn dfs(&mut self, u: i32, dist: &mut Vec<i32>) -> bool {
-        if u == 0 {
-            return true;
-        }
-        for i in 0..self.adj[u as usize].len() {
-            let v = self.adj[u as usize][i];
-            if dist[self.mt2[v] as usize] == dist[u as usize] + 1 && self.dfs(self.mt2[v], dist) {
-                self.mt2[v] = u;
-                self.mt1[u as usize] = v as i32;
-                return true;
-            }
-        }
-        dist[u as usize] = i32::max_value();
-        false
-    }
+    /// Computes a maximum matching with `graph::hopcroft_karp`, filling `mt1` and `mt2`, and
+    /// returns its size.
     pub fn hopcroft_karp(&mut self) -> i32 {
-        // NOTE: how to use: https://cses.fi/paste/7558dba8d00436a847eab8/
-        self.mt2 = vec![0; self.num_vertices_grp2 + 1];
-        self.mt1 = vec![0; self.num_vertices_grp1 + 1];
-        let mut dist = vec![i32::max_value(); self.num_vertices_grp1 + 1];
-        let mut res = 0;
-        while self.bfs(&mut dist) {
-            for u in 1..self.num_vertices_grp1 + 1 {
-                if self.mt1[u] == 0 && self.dfs(u as i32, &mut dist) {
-                    res += 1;
-                }
-            }
-        }
-        // for x in self.mt2 change x to -1 if it is 0
-        for x in self.mt2.iter_mut() {
-            if *x == 0 {
-                *x = -1;
-            }
-        }
-        for x in self.mt1.iter_mut() {
-            if *x == 0 {
-                *x = -1;
-            }
-        }
-        res
+        // the vertices are numbered from 1 here, and from 0 there
+        let edges: Vec<(usize, usize)> = (1..=self.num_vertices_grp1)
+            .flat_map(|u| self.adj[u].iter().map(move |&v| (u - 1, v - 1)))
+            .collect();
+        let matching = hopcroft_karp(self.num_vertices_grp1, self.num_vertices_grp2, &edges);
+        let one_based = |m: &Option<usize>| m.map_or(-1, |m| m as i32 + 1);
+        self.mt1 = std::iter::once(-1)
+            .chain(matching.left_match.iter().map(one_based))
+            .collect();
+        self.mt2 = std::iter::once(-1)
+            .chain(matching.right_match.iter().map(one_based))
+            .collect();
+        matching.size as i32
     }
 }
 #[cfg(test)]
@@ -267,4 +208,34 @@ mod tests {
             assert!(g.mt2[i] == -1);
         }
     }
+    #[test]
+    fn hopcroft_karp_agrees_with_kuhn() {
+        use rand::{Rng, SeedableRng};
+        use rand_chacha::ChaCha8Rng;
+
+        let mut rng = ChaCha8Rng::seed_from_u64(101);
+        for _ in 0..100 {
+            let (n1, n2) = (rng.gen_range(1..15), rng.gen_range(1..15));
+            let mut g = BipartiteMatching::new(n1, n2);
+            for u in 1..=n1 {
+                for v in 1..=n2 {
+                    if rng.gen_bool(0.2) {
+                        g.add_edge(u, v);
+                    }
+                }
+            }
+            let size = g.hopcroft_karp();
+            for u in 1..=n1 {
+                if g.mt1[u] != -1 {
+                    assert_eq!(g.mt2[g.mt1[u] as usize], u as i32);
+                    assert!(g.adj[u].contains(&(g.mt1[u] as usize)));
+                }
+            }
+            g.kuhn();
+            assert_eq!(
+                g.mt2.iter().skip(1).filter(|&&m| m != -1).count(),
+                size as usize
+            );
+        }
+    }
 }
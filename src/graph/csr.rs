@@ -0,0 +1,232 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, VecDeque};
+
+type Graph<V, E> = BTreeMap<V, BTreeMap<V, E>>;
+
+/// A directed weighted graph in compressed sparse row form, on the vertices 0..n
+/// <https://en.wikipedia.org/wiki/Sparse_matrix#Compressed_sparse_row_(CSR,_CRS_or_Yale_format)>
+///
+/// The edges leaving every vertex lie side by side in a single array, vertex after vertex, and
+/// `offsets[v]..offsets[v + 1]` is the range of those of `v`. Two allocations hold the whole
+/// graph, instead of one per vertex for a list of lists or two levels of nodes for a map of
+/// maps, and a traversal reads the edges in order of memory. The graph can't change once
+/// built.
+pub struct CsrGraph {
+    offsets: Vec<usize>,
+    neighbors: Vec<(usize, u64)>,
+}
+
+impl CsrGraph {
+    /// Builds the graph with an edge from `u` to `v` of weight `w` for every `(u, v, w)` of
+    /// `edges`, in O(n + m) with a counting sort. An undirected graph needs both directions of
+    /// every edge. The neighbors of every vertex keep the order of `edges`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an edge has an end out of `0..n`.
+    pub fn from_edges(n: usize, edges: &[(usize, usize, u64)]) -> Self {
+        let mut offsets = vec![0; n + 1];
+        for &(u, v, _) in edges {
+            assert!(
+                u < n && v < n,
+                "the edge ({u}, {v}) is out of the {n} vertices"
+            );
+            offsets[u + 1] += 1;
+        }
+        for v in 0..n {
+            offsets[v + 1] += offsets[v];
+        }
+        // where the next edge of every vertex goes
+        let mut next = offsets.clone();
+        let mut neighbors = vec![(0, 0); edges.len()];
+        for &(u, v, w) in edges {
+            neighbors[next[u]] = (v, w);
+            next[u] += 1;
+        }
+        CsrGraph { offsets, neighbors }
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.neighbors.len()
+    }
+
+    /// Returns the edges leaving `v`, as pairs of their other end and their weight.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `v` is out of the graph.
+    pub fn neighbors(&self, v: usize) -> &[(usize, u64)] {
+        &self.neighbors[self.offsets[v]..self.offsets[v + 1]]
+    }
+
+    /// Returns the graph as the maps which the other algorithms of this module take, with every
+    /// vertex as a key. Of parallel edges, only the lightest one is kept.
+    pub fn to_adjacency_list(&self) -> Graph<usize, u64> {
+        (0..self.vertex_count())
+            .map(|u| {
+                let mut targets = BTreeMap::new();
+                for &(v, w) in self.neighbors(u) {
+                    targets
+                        .entry(v)
+                        .and_modify(|weight: &mut u64| *weight = (*weight).min(w))
+                        .or_insert(w);
+                }
+                (u, targets)
+            })
+            .collect()
+    }
+
+    /// Returns the number of edges on a shortest path from `start` to every vertex, ignoring
+    /// the weights, or `None` for the vertices out of reach, in O(n + m).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start` is out of the graph.
+    pub fn bfs(&self, start: usize) -> Vec<Option<usize>> {
+        let mut distance = vec![None; self.vertex_count()];
+        distance[start] = Some(0);
+        let mut queue = VecDeque::from([start]);
+        while let Some(u) = queue.pop_front() {
+            let next = distance[u].map(|d| d + 1);
+            for &(v, _) in self.neighbors(u) {
+                if distance[v].is_none() {
+                    distance[v] = next;
+                    queue.push_back(v);
+                }
+            }
+        }
+        distance
+    }
+
+    /// Returns the weight of a lightest path from `start` to every vertex, or `None` for the
+    /// vertices out of reach, with Dijkstra's algorithm in O((n + m) log(n)).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start` is out of the graph.
+    pub fn dijkstra(&self, start: usize) -> Vec<Option<u64>> {
+        let mut distance = vec![None; self.vertex_count()];
+        distance[start] = Some(0);
+        let mut heap = BinaryHeap::from([Reverse((0, start))]);
+        while let Some(Reverse((d, u))) = heap.pop() {
+            if distance[u].is_some_and(|best| d > best) {
+                continue;
+            }
+            for &(v, w) in self.neighbors(u) {
+                let next = d + w;
+                if distance[v].is_none_or(|best| next < best) {
+                    distance[v] = Some(next);
+                    heap.push(Reverse((next, v)));
+                }
+            }
+        }
+        distance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::dijkstra;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn random_edges(rng: &mut ChaCha8Rng, n: usize, m: usize) -> Vec<(usize, usize, u64)> {
+        (0..m)
+            .map(|_| {
+                (
+                    rng.gen_range(0..n),
+                    rng.gen_range(0..n),
+                    rng.gen_range(0..20),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn small_graph() {
+        let edges = [
+            (0, 1, 4),
+            (2, 0, 1),
+            (0, 2, 7),
+            (1, 2, 2),
+            (0, 1, 3),
+            (3, 3, 5),
+        ];
+        let graph = CsrGraph::from_edges(5, &edges);
+        assert_eq!(graph.vertex_count(), 5);
+        assert_eq!(graph.edge_count(), 6);
+        assert_eq!(graph.neighbors(0), [(1, 4), (2, 7), (1, 3)]);
+        assert_eq!(graph.neighbors(1), [(2, 2)]);
+        assert_eq!(graph.neighbors(3), [(3, 5)]);
+        assert!(graph.neighbors(4).is_empty());
+        assert_eq!(graph.bfs(0), [Some(0), Some(1), Some(1), None, None]);
+        assert_eq!(graph.dijkstra(0), [Some(0), Some(3), Some(5), None, None]);
+        assert_eq!(graph.dijkstra(2), [Some(1), Some(4), Some(0), None, None]);
+        let adjacency = graph.to_adjacency_list();
+        assert_eq!(adjacency[&0], BTreeMap::from([(1, 3), (2, 7)]));
+        assert!(adjacency[&4].is_empty());
+        assert_eq!(CsrGraph::from_edges(0, &[]).vertex_count(), 0);
+    }
+
+    #[test]
+    fn matches_adjacency_lists() {
+        let mut rng = ChaCha8Rng::seed_from_u64(150);
+        for _ in 0..50 {
+            let n = rng.gen_range(1..100);
+            let m = rng.gen_range(0..5 * n);
+            let edges = random_edges(&mut rng, n, m);
+            let graph = CsrGraph::from_edges(n, &edges);
+            let mut lists = vec![vec![]; n];
+            for &(u, v, w) in &edges {
+                lists[u].push((v, w));
+            }
+            for (v, list) in lists.iter().enumerate() {
+                assert_eq!(graph.neighbors(v), list);
+            }
+        }
+    }
+
+    #[test]
+    fn searches_agree_with_the_adjacency_list() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1500);
+        for _ in 0..30 {
+            let n = rng.gen_range(1..60);
+            let m = rng.gen_range(0..4 * n);
+            // the dijkstra of the maps takes a loop at the start as a path to it
+            let mut edges = random_edges(&mut rng, n, m);
+            edges.retain(|&(u, v, _)| u != v);
+            let graph = CsrGraph::from_edges(n, &edges);
+            let adjacency = graph.to_adjacency_list();
+            let start = rng.gen_range(0..n);
+            let expected = dijkstra(&adjacency, start);
+            let distances = graph.dijkstra(start);
+            for (v, &distance) in distances.iter().enumerate() {
+                let reference = expected.get(&v).map(|path| path.map_or(0, |(_, d)| d));
+                assert_eq!(distance, reference, "{start} to {v}");
+            }
+            // weights of 1 turn the lightest paths into the shortest ones
+            let unit: Vec<(usize, usize, u64)> = adjacency
+                .iter()
+                .flat_map(|(&u, targets)| targets.keys().map(move |&v| (u, v, 1)))
+                .collect();
+            let distances = CsrGraph::from_edges(n, &unit).dijkstra(start);
+            let hops: Vec<Option<u64>> = graph
+                .bfs(start)
+                .into_iter()
+                .map(|d| d.map(|d| d as u64))
+                .collect();
+            assert_eq!(hops, distances);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the edge (1, 3) is out of the 3 vertices")]
+    fn edge_out_of_range() {
+        CsrGraph::from_edges(3, &[(0, 1, 1), (1, 3, 1)]);
+    }
+}
@@ -0,0 +1,281 @@
+use std::collections::VecDeque;
+
+/// An arc of the residual network: an edge, or the reverse of an edge right before it
+struct Arc {
+    to: usize,
+    /// The capacity left, the flow of the edge for a reverse arc
+    residual: u64,
+}
+
+/// A flow network on the vertices `0..n`, with a flow on every directed edge.
+///
+/// Every edge is stored with its reverse arc, of capacity zero, which lets an augmenting path
+/// cancel flow already sent along the edge. Parallel edges and edges in both directions between
+/// two vertices are allowed.
+pub struct FlowGraph {
+    /// The arcs leaving every vertex, as indices in `arcs`
+    adjacency: Vec<Vec<usize>>,
+    /// The edge `e` is the arc `2 * e`, and its reverse the arc `2 * e + 1`
+    arcs: Vec<Arc>,
+    capacities: Vec<u64>,
+}
+
+impl FlowGraph {
+    /// Creates a network of `n` vertices without edges.
+    pub fn new(n: usize) -> Self {
+        FlowGraph {
+            adjacency: vec![vec![]; n],
+            arcs: vec![],
+            capacities: vec![],
+        }
+    }
+
+    pub fn vertices(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    /// Adds an edge from `from` to `to`, without flow, and returns its number, counted from 0
+    /// in the order of the calls.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from` or `to` is not a vertex of the network.
+    pub fn add_edge(&mut self, from: usize, to: usize, capacity: u64) -> usize {
+        let n = self.vertices();
+        assert!(
+            from < n && to < n,
+            "the edge ({from}, {to}) is out of a network of {n} vertices"
+        );
+        let edge = self.capacities.len();
+        self.adjacency[from].push(2 * edge);
+        self.arcs.push(Arc {
+            to,
+            residual: capacity,
+        });
+        self.adjacency[to].push(2 * edge + 1);
+        self.arcs.push(Arc {
+            to: from,
+            residual: 0,
+        });
+        self.capacities.push(capacity);
+        edge
+    }
+
+    /// Returns the flow along the edge numbered `edge`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no such edge.
+    pub fn flow(&self, edge: usize) -> u64 {
+        self.arcs[2 * edge + 1].residual
+    }
+
+    /// Returns every edge as its source, destination, capacity and flow, in the order they
+    /// were added.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize, u64, u64)> + '_ {
+        self.capacities.iter().enumerate().map(|(edge, &capacity)| {
+            let reverse = &self.arcs[2 * edge + 1];
+            (
+                reverse.to,
+                self.arcs[2 * edge].to,
+                capacity,
+                reverse.residual,
+            )
+        })
+    }
+}
+
+/// Sends the maximum flow from `source` to `sink` with the Edmonds-Karp algorithm, in O(VE^2),
+/// and returns the amount sent.
+/// <https://en.wikipedia.org/wiki/Edmonds%E2%80%93Karp_algorithm>
+///
+/// It is the Ford-Fulkerson method with every augmenting path found by a breadth-first search,
+/// so that it is a shortest path of the residual network. The length of the shortest paths never
+/// decreases, and every edge can be the bottleneck of O(V) paths, which bounds the number of
+/// augmentations by O(VE) whatever the capacities, while arbitrary paths can take as many
+/// augmentations as the value of the flow.
+///
+/// The flow is added to the one already in the network, and the flow of every edge is left in
+/// `graph`, so that on a network without flow, the result is the maximum flow.
+///
+/// # Panics
+///
+/// Panics if `source` or `sink` is not a vertex of the network, or if they are the same.
+pub fn edmonds_karp(graph: &mut FlowGraph, source: usize, sink: usize) -> u64 {
+    let n = graph.vertices();
+    assert!(
+        source < n && sink < n,
+        "the source and the sink must be vertices of the network"
+    );
+    assert_ne!(source, sink, "the source and the sink must differ");
+
+    let mut total = 0;
+    // the arc through which a breadth-first search reached every vertex
+    let mut reached_by: Vec<Option<usize>> = vec![None; n];
+    loop {
+        reached_by.fill(None);
+        let mut queue = VecDeque::from([source]);
+        while let Some(u) = queue.pop_front() {
+            for &arc in &graph.adjacency[u] {
+                let Arc { to, residual } = graph.arcs[arc];
+                if residual > 0 && to != source && reached_by[to].is_none() {
+                    reached_by[to] = Some(arc);
+                    queue.push_back(to);
+                }
+            }
+            if reached_by[sink].is_some() {
+                break;
+            }
+        }
+        if reached_by[sink].is_none() {
+            return total;
+        }
+
+        // the path back from the sink, through the arcs `a` whose reverses `a ^ 1` lead back
+        let path =
+            || std::iter::successors(reached_by[sink], |&arc| reached_by[graph.arcs[arc ^ 1].to]);
+        let bottleneck = path().map(|arc| graph.arcs[arc].residual).min().unwrap();
+        let arcs: Vec<usize> = path().collect();
+        for arc in arcs {
+            graph.arcs[arc].residual -= bottleneck;
+            graph.arcs[arc ^ 1].residual += bottleneck;
+        }
+        total += bottleneck;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{ford_fulkerson, DinicMaxFlow};
+    use quickcheck_macros::quickcheck;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    /// Checks the capacities, and that every vertex but the source and the sink lets out as much
+    /// flow as it gets, and returns the flow out of the source
+    fn check_flow(graph: &FlowGraph, source: usize, sink: usize) -> i64 {
+        let mut balance = vec![0i64; graph.vertices()];
+        for (from, to, capacity, flow) in graph.edges() {
+            assert!(flow <= capacity);
+            balance[from] -= flow as i64;
+            balance[to] += flow as i64;
+        }
+        for (v, &net) in balance.iter().enumerate() {
+            if v != source && v != sink {
+                assert_eq!(net, 0, "the flow is not conserved at {v}");
+            }
+        }
+        assert_eq!(balance[source], -balance[sink]);
+        -balance[source]
+    }
+
+    #[test]
+    fn textbook_network() {
+        let mut graph = FlowGraph::new(6);
+        let edges = [
+            (0, 1, 16),
+            (0, 3, 13),
+            (1, 2, 12),
+            (2, 3, 9),
+            (2, 5, 20),
+            (3, 1, 4),
+            (3, 4, 14),
+            (4, 2, 7),
+            (4, 5, 4),
+        ];
+        for (from, to, capacity) in edges {
+            graph.add_edge(from, to, capacity);
+        }
+        assert_eq!(edmonds_karp(&mut graph, 0, 5), 23);
+        assert_eq!(check_flow(&graph, 0, 5), 23);
+        // the flow reaches the sink along its two edges
+        assert_eq!((graph.flow(4), graph.flow(8)), (19, 4));
+        // there is nothing left to send
+        assert_eq!(edmonds_karp(&mut graph, 0, 5), 0);
+    }
+
+    #[test]
+    fn agrees_with_ford_fulkerson_on_matrices() {
+        let mut matrix = vec![
+            vec![0, 10, 0, 10, 0, 0],
+            vec![0, 0, 4, 2, 8, 0],
+            vec![0, 0, 0, 0, 0, 10],
+            vec![0, 0, 0, 0, 9, 0],
+            vec![0, 0, 6, 0, 0, 10],
+            vec![0, 0, 0, 0, 0, 0],
+        ];
+        let mut graph = FlowGraph::new(6);
+        for (u, row) in matrix.iter().enumerate() {
+            for (v, &capacity) in row.iter().enumerate() {
+                if capacity > 0 {
+                    graph.add_edge(u, v, capacity as u64);
+                }
+            }
+        }
+        let expected = ford_fulkerson(&mut matrix, 0, 5);
+        assert_eq!(edmonds_karp(&mut graph, 0, 5), expected as u64);
+    }
+
+    #[test]
+    fn agrees_with_dinic() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1252);
+        for _ in 0..200 {
+            let n = rng.gen_range(2..30);
+            let edges: Vec<(usize, usize, u64)> = (0..rng.gen_range(0..4 * n))
+                .map(|_| {
+                    (
+                        rng.gen_range(0..n),
+                        rng.gen_range(0..n),
+                        rng.gen_range(0..100),
+                    )
+                })
+                .collect();
+            let (source, sink) = (0, n - 1);
+            let mut graph = FlowGraph::new(n);
+            // Dinic numbers the vertices from 1
+            let mut dinic = DinicMaxFlow::new(source + 1, sink + 1, n);
+            for &(from, to, capacity) in &edges {
+                graph.add_edge(from, to, capacity);
+                dinic.add_edge(from + 1, to + 1, capacity as i64);
+            }
+            let flow = edmonds_karp(&mut graph, source, sink);
+            assert_eq!(flow as i64, dinic.find_maxflow(i64::MAX));
+            assert_eq!(check_flow(&graph, source, sink), flow as i64);
+        }
+    }
+
+    #[test]
+    fn parallel_and_opposite_edges() {
+        let mut graph = FlowGraph::new(3);
+        graph.add_edge(0, 1, 3);
+        graph.add_edge(0, 1, 4);
+        graph.add_edge(1, 0, 10);
+        graph.add_edge(1, 2, 5);
+        graph.add_edge(0, 0, 8);
+        assert_eq!(edmonds_karp(&mut graph, 0, 2), 5);
+        assert_eq!(check_flow(&graph, 0, 2), 5);
+        // the sink cannot be reached
+        let mut graph = FlowGraph::new(3);
+        graph.add_edge(0, 1, 3);
+        graph.add_edge(2, 1, 3);
+        assert_eq!(edmonds_karp(&mut graph, 0, 2), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "the edge (1, 3) is out of a network of 3 vertices")]
+    fn edge_out_of_range() {
+        FlowGraph::new(3).add_edge(1, 3, 1);
+    }
+
+    #[quickcheck]
+    fn flow_is_conserved(n: u8, edges: Vec<(u8, u8, u16)>) -> bool {
+        let n = n as usize % 12 + 2;
+        let mut graph = FlowGraph::new(n);
+        for (from, to, capacity) in edges {
+            graph.add_edge(from as usize % n, to as usize % n, capacity as u64);
+        }
+        let flow = edmonds_karp(&mut graph, 0, 1);
+        check_flow(&graph, 0, 1) == flow as i64
+    }
+}
@@ -0,0 +1,255 @@
+use crate::error::{AlgorithmError, AlgorithmResult};
+use rand::Rng;
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+type Graph<V, E> = BTreeMap<V, BTreeMap<V, E>>;
+
+// The generators build undirected graphs on the vertices 0..n, every edge of weight 1 being in
+// the maps of both its vertices, and every vertex being a key, even without edges, like the
+// graphs which the other algorithms of this module take.
+
+fn empty_graph(n: usize) -> Graph<usize, u64> {
+    (0..n).map(|v| (v, BTreeMap::new())).collect()
+}
+
+fn add_edge(graph: &mut Graph<usize, u64>, u: usize, v: usize) {
+    graph.get_mut(&u).unwrap().insert(v, 1);
+    graph.get_mut(&v).unwrap().insert(u, 1);
+}
+
+/// Returns the complete graph K_n, with an edge between any two vertices.
+pub fn complete_graph(n: usize) -> Graph<usize, u64> {
+    let mut graph = empty_graph(n);
+    for u in 0..n {
+        for v in u + 1..n {
+            add_edge(&mut graph, u, v);
+        }
+    }
+    graph
+}
+
+/// Returns the cycle C_n, with an edge from every vertex to the next one and from the last one
+/// to 0. Below 3 vertices, there is no cycle without repeated edges, and the graph is a path.
+pub fn cycle_graph(n: usize) -> Graph<usize, u64> {
+    let mut graph = empty_graph(n);
+    for v in 1..n {
+        add_edge(&mut graph, v - 1, v);
+    }
+    if n >= 3 {
+        add_edge(&mut graph, n - 1, 0);
+    }
+    graph
+}
+
+/// Returns the star of `n` vertices, with an edge from the center 0 to every other vertex.
+pub fn star_graph(n: usize) -> Graph<usize, u64> {
+    let mut graph = empty_graph(n);
+    for v in 1..n {
+        add_edge(&mut graph, 0, v);
+    }
+    graph
+}
+
+/// Returns a random graph of the Erdős–Rényi model G(n, p), in which every edge exists with
+/// the probability `p`, independently of the others, in O(n^2).
+/// <https://en.wikipedia.org/wiki/Erd%C5%91s%E2%80%93R%C3%A9nyi_model>
+pub fn erdos_renyi<R: Rng + ?Sized>(
+    n: usize,
+    p: f64,
+    rng: &mut R,
+) -> AlgorithmResult<Graph<usize, u64>> {
+    if !(0.0..=1.0).contains(&p) {
+        return Err(AlgorithmError::InvalidParameter {
+            argument: "p",
+            reason: format!("must be a probability, got {p}"),
+        });
+    }
+    let mut graph = empty_graph(n);
+    for u in 0..n {
+        for v in u + 1..n {
+            if rng.gen_bool(p) {
+                add_edge(&mut graph, u, v);
+            }
+        }
+    }
+    Ok(graph)
+}
+
+/// Returns a random graph of the Barabási–Albert model, in O(nm): from the complete graph of
+/// `m + 1` vertices, every new vertex gets edges to `m` distinct vertices, each picked with a
+/// probability proportional to its degree.
+/// <https://en.wikipedia.org/wiki/Barab%C3%A1si%E2%80%93Albert_model>
+///
+/// The preferential attachment makes the rich get richer: the degrees follow a power law, with
+/// a fraction of the vertices of degree `k` falling like `k^-3`, so that some vertices become
+/// hubs, unlike in the Erdős–Rényi model. A vertex is picked with the right probability by
+/// picking an end of a random edge, from a list of the ends of all the edges.
+pub fn barabasi_albert<R: Rng + ?Sized>(
+    n: usize,
+    m: usize,
+    rng: &mut R,
+) -> AlgorithmResult<Graph<usize, u64>> {
+    if m == 0 || m >= n {
+        return Err(AlgorithmError::InvalidParameter {
+            argument: "m",
+            reason: format!(
+                "must be between 1 and n - 1 = {}, got {m}",
+                n.saturating_sub(1)
+            ),
+        });
+    }
+    let mut graph = complete_graph(m + 1);
+    graph.extend((m + 1..n).map(|v| (v, BTreeMap::new())));
+    // every vertex appears once for every edge it is an end of
+    let mut ends: Vec<usize> = (0..=m).flat_map(|v| std::iter::repeat_n(v, m)).collect();
+    let mut targets = Vec::with_capacity(m);
+    for v in m + 1..n {
+        targets.clear();
+        while targets.len() < m {
+            let target = ends[rng.gen_range(0..ends.len())];
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+        for &target in &targets {
+            add_edge(&mut graph, v, target);
+            ends.push(v);
+            ends.push(target);
+        }
+    }
+    Ok(graph)
+}
+
+/// Gives every edge of an undirected graph a weight drawn from `weights`, the same both ways.
+pub fn randomize_weights<R: Rng + ?Sized>(
+    graph: &mut Graph<usize, u64>,
+    weights: Range<u64>,
+    rng: &mut R,
+) {
+    let edges: Vec<(usize, usize)> = graph
+        .iter()
+        .flat_map(|(&u, edges)| edges.keys().filter(move |&&v| u <= v).map(move |&v| (u, v)))
+        .collect();
+    for (u, v) in edges {
+        let weight = rng.gen_range(weights.clone());
+        graph.get_mut(&u).unwrap().insert(v, weight);
+        graph.get_mut(&v).unwrap().insert(u, weight);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::prim;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn edge_count(graph: &Graph<usize, u64>) -> usize {
+        graph.values().map(BTreeMap::len).sum::<usize>() / 2
+    }
+
+    fn degrees(graph: &Graph<usize, u64>) -> Vec<usize> {
+        graph.values().map(BTreeMap::len).collect()
+    }
+
+    fn is_undirected(graph: &Graph<usize, u64>) -> bool {
+        graph.iter().all(|(u, edges)| {
+            edges
+                .iter()
+                .all(|(v, weight)| u != v && graph[v].get(u) == Some(weight))
+        })
+    }
+
+    #[test]
+    fn deterministic_graphs() {
+        let complete = complete_graph(6);
+        assert_eq!(edge_count(&complete), 15);
+        assert!(degrees(&complete).iter().all(|&d| d == 5));
+        let cycle = cycle_graph(5);
+        assert_eq!(edge_count(&cycle), 5);
+        assert!(cycle[&4].contains_key(&0));
+        assert!(degrees(&cycle).iter().all(|&d| d == 2));
+        let star = star_graph(5);
+        assert_eq!(degrees(&star), [4, 1, 1, 1, 1]);
+        for graph in [&complete, &cycle, &star] {
+            assert!(is_undirected(graph));
+        }
+        assert_eq!(edge_count(&cycle_graph(2)), 1);
+        assert_eq!(cycle_graph(1).len(), 1);
+        assert!(complete_graph(0).is_empty());
+        assert_eq!(star_graph(1)[&0].len(), 0);
+    }
+
+    #[test]
+    fn erdos_renyi_extremes() {
+        let mut rng = ChaCha8Rng::seed_from_u64(126);
+        let empty = erdos_renyi(30, 0.0, &mut rng).unwrap();
+        assert_eq!(empty.len(), 30);
+        assert_eq!(edge_count(&empty), 0);
+        assert_eq!(erdos_renyi(30, 1.0, &mut rng).unwrap(), complete_graph(30));
+        // about p * n * (n - 1) / 2 = 2475 edges
+        let half = erdos_renyi(100, 0.5, &mut rng).unwrap();
+        assert!(is_undirected(&half));
+        assert!((edge_count(&half) as i64 - 2475).abs() < 200);
+        for p in [-0.1, 1.1, f64::NAN] {
+            assert!(erdos_renyi(10, p, &mut rng).is_err());
+        }
+    }
+
+    #[test]
+    fn barabasi_albert_structure() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1260);
+        let (n, m) = (500, 3);
+        let graph = barabasi_albert(n, m, &mut rng).unwrap();
+        assert_eq!(graph.len(), n);
+        assert!(is_undirected(&graph));
+        // the complete graph of m + 1 vertices, then m edges for every other vertex
+        assert_eq!(edge_count(&graph), m * (m + 1) / 2 + (n - m - 1) * m);
+        assert!(degrees(&graph).iter().all(|&d| d >= m));
+        assert_eq!(barabasi_albert(4, 3, &mut rng).unwrap(), complete_graph(4));
+        for (n, m) in [(5, 0), (3, 3), (0, 1)] {
+            assert!(barabasi_albert(n, m, &mut rng).is_err());
+        }
+    }
+
+    #[test]
+    fn barabasi_albert_power_law() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1261);
+        let (n, m) = (20_000, 3);
+        let graph = barabasi_albert(n, m, &mut rng).unwrap();
+        let degrees = degrees(&graph);
+        // the fraction of the vertices of degree at least k falls like k^-2
+        let at_least = |k: usize| degrees.iter().filter(|&&d| d >= k).count() as f64 / n as f64;
+        let exponent = (at_least(48).ln() - at_least(6).ln()) / (48f64.ln() - 6f64.ln());
+        assert!((-2.5..-1.5).contains(&exponent), "{exponent}");
+
+        // with the same mean degree, the degrees of a random graph stay close to the mean
+        let random = erdos_renyi(2000, 6.0 / 1999.0, &mut rng).unwrap();
+        let hub = |degrees: &[usize]| *degrees.iter().max().unwrap();
+        assert!(hub(&degrees) > 100);
+        assert!(hub(&super::tests::degrees(&random)) < 30);
+    }
+
+    #[test]
+    fn weighted_graphs() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1262);
+        let mut graph = barabasi_albert(200, 2, &mut rng).unwrap();
+        randomize_weights(&mut graph, 1..100, &mut rng);
+        assert!(is_undirected(&graph));
+        assert!(graph
+            .values()
+            .flat_map(BTreeMap::values)
+            .all(|w| (1..100).contains(w)));
+        // the graph is connected, so that a spanning tree reaches every vertex
+        let tree = prim(&graph);
+        assert_eq!(tree.len(), 200);
+        assert_eq!(edge_count(&tree), 199);
+        let mut unit = graph.clone();
+        randomize_weights(&mut unit, 1..2, &mut rng);
+        assert_eq!(
+            unit,
+            barabasi_albert(200, 2, &mut ChaCha8Rng::seed_from_u64(1262)).unwrap()
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use std::collections::VecDeque;
+
+// Graphs are undirected, with vertices numbered from 0 to n - 1 and given as a list of edges.
+
+fn adjacency_list(n: usize, edges: &[(usize, usize)]) -> Vec<Vec<usize>> {
+    let mut adj = vec![vec![]; n];
+    for &(u, v) in edges {
+        adj[u].push(v);
+        if u != v {
+            adj[v].push(u);
+        }
+    }
+    adj
+}
+
+/// Runs a BFS 2-coloring over every connected component.
+/// Returns the coloring on success, or the vertices of an odd cycle on failure.
+fn two_color(n: usize, edges: &[(usize, usize)]) -> Result<Vec<u8>, Vec<usize>> {
+    let adj = adjacency_list(n, edges);
+    let mut color: Vec<Option<u8>> = vec![None; n];
+    let mut parent = vec![usize::MAX; n];
+    let mut depth = vec![0; n];
+
+    for start in 0..n {
+        if color[start].is_some() {
+            continue;
+        }
+        color[start] = Some(0);
+        let mut queue = VecDeque::from([start]);
+        while let Some(u) = queue.pop_front() {
+            for &v in &adj[u] {
+                match color[v] {
+                    None => {
+                        color[v] = Some(1 - color[u].unwrap());
+                        parent[v] = u;
+                        depth[v] = depth[u] + 1;
+                        queue.push_back(v);
+                    }
+                    Some(c) if c == color[u].unwrap() => {
+                        return Err(odd_cycle_through(u, v, &parent, &depth));
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+    }
+
+    Ok(color.into_iter().map(|c| c.unwrap()).collect())
+}
+
+/// `u` and `v` are adjacent and have the same depth parity in the BFS tree, so the tree paths
+/// from both of them up to their lowest common ancestor, closed by the edge (u, v), form an odd cycle.
+fn odd_cycle_through(u: usize, v: usize, parent: &[usize], depth: &[usize]) -> Vec<usize> {
+    let (mut a, mut b) = (u, v);
+    let mut from_u = vec![];
+    let mut from_v = vec![];
+    while depth[a] > depth[b] {
+        from_u.push(a);
+        a = parent[a];
+    }
+    while depth[b] > depth[a] {
+        from_v.push(b);
+        b = parent[b];
+    }
+    while a != b {
+        from_u.push(a);
+        from_v.push(b);
+        a = parent[a];
+        b = parent[b];
+    }
+    from_u.push(a);
+    from_u.extend(from_v.into_iter().rev());
+    from_u
+}
+
+/// Returns a 2-coloring (every vertex gets 0 or 1 and every edge joins different colors)
+/// if the graph is bipartite, and `None` otherwise.
+/// Use `find_odd_cycle` to obtain a witness when the graph is not bipartite.
+pub fn is_bipartite(n: usize, edges: &[(usize, usize)]) -> Option<Vec<u8>> {
+    two_color(n, edges).ok()
+}
+
+/// Returns the vertices of an odd cycle, in cycle order, if the graph has one,
+/// i.e. if and only if the graph is not bipartite. A self-loop is reported as a cycle of length 1.
+pub fn find_odd_cycle(n: usize, edges: &[(usize, usize)]) -> Option<Vec<usize>> {
+    two_color(n, edges).err()
+}
+
+/// Greedy coloring using the Welsh–Powell ordering: vertices are visited by decreasing degree,
+/// and each one gets the smallest color not already used by one of its neighbours.
+/// Self-loops are ignored since no proper coloring could satisfy them.
+///
+/// Returns the color of every vertex and the number of colors used.
+/// The number of colors is at most `1 + max_degree`.
+pub fn greedy_coloring(n: usize, edges: &[(usize, usize)]) -> (Vec<usize>, usize) {
+    let loopless: Vec<(usize, usize)> = edges.iter().copied().filter(|(u, v)| u != v).collect();
+    let adj = adjacency_list(n, &loopless);
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by_key(|&v| std::cmp::Reverse(adj[v].len()));
+
+    let mut color = vec![usize::MAX; n];
+    let mut num_colors = 0;
+    // used[c] == v means that color c is taken by a neighbour of v
+    let mut used = vec![usize::MAX; n + 1];
+    for v in order {
+        for &u in &adj[v] {
+            if color[u] != usize::MAX {
+                used[color[u]] = v;
+            }
+        }
+        let c = (0..).find(|&c| used[c] != v).unwrap();
+        color[v] = c;
+        num_colors = num_colors.max(c + 1);
+    }
+
+    (color, num_colors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn cycle(n: usize) -> Vec<(usize, usize)> {
+        (0..n).map(|i| (i, (i + 1) % n)).collect()
+    }
+
+    fn random_graph(rng: &mut ChaCha8Rng, n: usize, m: usize) -> Vec<(usize, usize)> {
+        (0..m)
+            .map(|_| (rng.gen_range(0..n), rng.gen_range(0..n)))
+            .collect()
+    }
+
+    fn assert_odd_cycle(edges: &[(usize, usize)], cycle: &[usize]) {
+        assert_eq!(cycle.len() % 2, 1);
+        for i in 0..cycle.len() {
+            let (u, v) = (cycle[i], cycle[(i + 1) % cycle.len()]);
+            assert!(edges.contains(&(u, v)) || edges.contains(&(v, u)));
+        }
+    }
+
+    #[test]
+    fn even_cycles_are_bipartite() {
+        for n in [2, 4, 6, 10, 100] {
+            let edges = cycle(n);
+            let color = is_bipartite(n, &edges).unwrap();
+            for &(u, v) in &edges {
+                assert_ne!(color[u], color[v]);
+            }
+            assert_eq!(find_odd_cycle(n, &edges), None);
+        }
+    }
+
+    #[test]
+    fn odd_cycles_are_not_bipartite() {
+        for n in [3, 5, 7, 11, 99] {
+            let edges = cycle(n);
+            assert_eq!(is_bipartite(n, &edges), None);
+            let witness = find_odd_cycle(n, &edges).unwrap();
+            assert_eq!(witness.len(), n);
+            assert_odd_cycle(&edges, &witness);
+        }
+    }
+
+    #[test]
+    fn odd_cycle_in_larger_graph() {
+        // a square with a pendant path, plus a separate triangle
+        let edges = vec![
+            (0, 1),
+            (1, 2),
+            (2, 3),
+            (3, 0),
+            (3, 4),
+            (5, 6),
+            (6, 7),
+            (7, 5),
+        ];
+        assert_eq!(is_bipartite(8, &edges), None);
+        let witness = find_odd_cycle(8, &edges).unwrap();
+        assert_odd_cycle(&edges, &witness);
+        assert_eq!(witness.len(), 3);
+    }
+
+    #[test]
+    fn self_loop() {
+        let edges = vec![(0, 1), (1, 1)];
+        assert_eq!(is_bipartite(2, &edges), None);
+        assert_eq!(find_odd_cycle(2, &edges), Some(vec![1]));
+    }
+
+    #[test]
+    fn disconnected_and_isolated() {
+        let edges = vec![(0, 1), (2, 3), (3, 4)];
+        let color = is_bipartite(7, &edges).unwrap();
+        assert_eq!(color.len(), 7);
+        for &(u, v) in &edges {
+            assert_ne!(color[u], color[v]);
+        }
+        assert_eq!(is_bipartite(0, &[]), Some(vec![]));
+
+        let (color, num_colors) = greedy_coloring(7, &edges);
+        assert_eq!(num_colors, 2);
+        assert_eq!(color[5], 0);
+        assert_eq!(color[6], 0);
+    }
+
+    #[test]
+    fn random_graphs_bipartiteness() {
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        for _ in 0..200 {
+            let n = rng.gen_range(1..20);
+            let m = rng.gen_range(0..2 * n);
+            let edges = random_graph(&mut rng, n, m);
+            match is_bipartite(n, &edges) {
+                Some(color) => {
+                    for &(u, v) in &edges {
+                        assert_ne!(color[u], color[v]);
+                    }
+                }
+                None => assert_odd_cycle(&edges, &find_odd_cycle(n, &edges).unwrap()),
+            }
+        }
+    }
+
+    #[test]
+    fn greedy_coloring_known_graphs() {
+        assert_eq!(greedy_coloring(0, &[]), (vec![], 0));
+        assert_eq!(greedy_coloring(3, &[]).1, 1);
+        assert_eq!(greedy_coloring(6, &cycle(6)).1, 2);
+        assert_eq!(greedy_coloring(5, &cycle(5)).1, 3);
+
+        let complete: Vec<(usize, usize)> = (0..6)
+            .flat_map(|u| (u + 1..6).map(move |v| (u, v)))
+            .collect();
+        assert_eq!(greedy_coloring(6, &complete).1, 6);
+    }
+
+    #[test]
+    fn greedy_coloring_random_graphs_are_valid() {
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        for _ in 0..200 {
+            let n = rng.gen_range(1..30);
+            let m = rng.gen_range(0..4 * n);
+            let edges = random_graph(&mut rng, n, m);
+            let (color, num_colors) = greedy_coloring(n, &edges);
+
+            let mut degree = vec![0; n];
+            for &(u, v) in &edges {
+                if u != v {
+                    assert_ne!(color[u], color[v]);
+                    degree[u] += 1;
+                    degree[v] += 1;
+                }
+            }
+            assert_eq!(num_colors, color.iter().max().unwrap() + 1);
+            assert!(num_colors <= degree.iter().max().unwrap() + 1);
+        }
+    }
+}
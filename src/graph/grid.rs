@@ -0,0 +1,294 @@
+use crate::error::{AlgorithmError, AlgorithmResult};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::f64::consts::SQRT_2;
+
+/// A map of cells, walkable or blocked, searched with Dijkstra's algorithm
+///
+/// The cells are `(x, y)` pairs, with `x < width` and `y < height`. A move to one of the four
+/// cardinal neighbors costs 1, and a diagonal move, when allowed, costs √2. A diagonal move
+/// may not cut a corner: both cardinal cells it passes between must be walkable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GridGraph {
+    width: usize,
+    height: usize,
+    /// Whether every cell is walkable, row by row
+    walkable: Vec<bool>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Candidate {
+    distance: f64,
+    cell: usize,
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // inverted, so that the closest candidate has the highest priority
+        other.distance.total_cmp(&self.distance)
+    }
+}
+
+impl GridGraph {
+    /// Creates the grid whose walkable cells are given row by row.
+    pub fn new(width: usize, height: usize, walkable: Vec<bool>) -> AlgorithmResult<Self> {
+        if walkable.len() != width * height {
+            return Err(AlgorithmError::DimensionMismatch {
+                argument: "walkable",
+                expected: width * height,
+                found: walkable.len(),
+            });
+        }
+        Ok(GridGraph {
+            width,
+            height,
+            walkable,
+        })
+    }
+
+    /// Creates a grid from lines of text, where `#` is a blocked cell and anything else a
+    /// walkable one.
+    pub fn from_rows(rows: &[&str]) -> AlgorithmResult<Self> {
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        if let Some(row) = rows.iter().find(|row| row.chars().count() != width) {
+            return Err(AlgorithmError::DimensionMismatch {
+                argument: "rows",
+                expected: width,
+                found: row.chars().count(),
+            });
+        }
+        let walkable = rows.iter().flat_map(|row| row.chars().map(|c| c != '#'));
+        Self::new(width, rows.len(), walkable.collect())
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_walkable(&self, (x, y): (usize, usize)) -> bool {
+        x < self.width && y < self.height && self.walkable[y * self.width + x]
+    }
+
+    /// Returns the cells reachable in one move from a walkable cell, with the cost of the move.
+    fn neighbors(&self, cell: usize, diagonal: bool) -> Vec<(usize, f64)> {
+        let (x, y) = ((cell % self.width) as isize, (cell / self.width) as isize);
+        let open = |dx: isize, dy: isize| {
+            let (nx, ny) = (x + dx, y + dy);
+            nx >= 0 && ny >= 0 && self.is_walkable((nx as usize, ny as usize))
+        };
+        let mut result = vec![];
+        for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+            if open(dx, dy) {
+                result.push(((y + dy) as usize * self.width + (x + dx) as usize, 1.0));
+            }
+        }
+        if diagonal {
+            for (dx, dy) in [(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+                if open(dx, dy) && open(dx, 0) && open(0, dy) {
+                    result.push(((y + dy) as usize * self.width + (x + dx) as usize, SQRT_2));
+                }
+            }
+        }
+        result
+    }
+
+    /// Runs Dijkstra's algorithm from `start`, until `goal` is settled if given, and returns
+    /// the distances and the predecessors of the cells.
+    fn dijkstra(
+        &self,
+        start: (usize, usize),
+        goal: Option<usize>,
+        diagonal: bool,
+    ) -> (Vec<Option<f64>>, Vec<Option<usize>>) {
+        let cells = self.walkable.len();
+        let mut distances = vec![None; cells];
+        let mut previous = vec![None; cells];
+        if !self.is_walkable(start) {
+            return (distances, previous);
+        }
+        let start = start.1 * self.width + start.0;
+        let mut settled = vec![false; cells];
+        let mut queue = BinaryHeap::new();
+        distances[start] = Some(0.0);
+        queue.push(Candidate {
+            distance: 0.0,
+            cell: start,
+        });
+        while let Some(Candidate { distance, cell }) = queue.pop() {
+            if settled[cell] {
+                continue;
+            }
+            settled[cell] = true;
+            if Some(cell) == goal {
+                break;
+            }
+            for (next, cost) in self.neighbors(cell, diagonal) {
+                let candidate = distance + cost;
+                if distances[next].is_none_or(|known| candidate < known) {
+                    distances[next] = Some(candidate);
+                    previous[next] = Some(cell);
+                    queue.push(Candidate {
+                        distance: candidate,
+                        cell: next,
+                    });
+                }
+            }
+        }
+        (distances, previous)
+    }
+
+    /// Returns a shortest path from `start` to `goal`, both included, or `None` if there is
+    /// none or one of them is blocked or out of the grid.
+    pub fn shortest_path(
+        &self,
+        start: (usize, usize),
+        goal: (usize, usize),
+        diagonal: bool,
+    ) -> Option<Vec<(usize, usize)>> {
+        if !self.is_walkable(goal) {
+            return None;
+        }
+        let goal = goal.1 * self.width + goal.0;
+        let (distances, previous) = self.dijkstra(start, Some(goal), diagonal);
+        distances[goal]?;
+        let mut path = vec![goal];
+        while let Some(cell) = previous[*path.last().unwrap()] {
+            path.push(cell);
+        }
+        path.reverse();
+        Some(
+            path.into_iter()
+                .map(|cell| (cell % self.width, cell / self.width))
+                .collect(),
+        )
+    }
+
+    /// Returns the distance from `start` to every cell, row by row, `None` for the cells which
+    /// cannot be reached.
+    pub fn distance_map(&self, start: (usize, usize), diagonal: bool) -> Vec<Option<f64>> {
+        self.dijkstra(start, None, diagonal).0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rustfmt::skip]
+    const MAZE: [&str; 5] = [
+        "..#....",
+        "..#.##.",
+        "..#..#.",
+        "....#..",
+        "###....",
+    ];
+
+    /// Returns the length of a path of neighboring cells.
+    fn path_length(path: &[(usize, usize)]) -> f64 {
+        path.windows(2)
+            .map(|pair| {
+                if pair[0].0 != pair[1].0 && pair[0].1 != pair[1].1 {
+                    SQRT_2
+                } else {
+                    1.0
+                }
+            })
+            .sum()
+    }
+
+    #[test]
+    fn maze_path() {
+        let grid = GridGraph::from_rows(&MAZE).unwrap();
+        let path = grid.shortest_path((0, 0), (6, 0), false).unwrap();
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(6, 0)));
+        assert!(path.iter().all(|&cell| grid.is_walkable(cell)));
+        for pair in path.windows(2) {
+            let (dx, dy) = (pair[0].0.abs_diff(pair[1].0), pair[0].1.abs_diff(pair[1].1));
+            assert_eq!(dx + dy, 1);
+        }
+        // down to the gap in the wall, up the corridor behind it and along the top, since the
+        // way around the bottom takes two more moves
+        assert_eq!(path.len(), 13);
+        assert_eq!(path[6..10], [(3, 3), (3, 2), (3, 1), (3, 0)]);
+        let distances = grid.distance_map((0, 0), false);
+        assert_eq!(distances[6], Some(12.0));
+        assert_eq!(path_length(&path), 12.0);
+        assert_eq!(distances[7 * 4 + 6], Some(10.0));
+    }
+
+    #[test]
+    fn diagonal_moves_shorten_paths() {
+        let open = GridGraph::new(5, 5, vec![true; 25]).unwrap();
+        let straight = open.shortest_path((0, 0), (4, 4), false).unwrap();
+        let diagonal = open.shortest_path((0, 0), (4, 4), true).unwrap();
+        assert_eq!(straight.len(), 9);
+        assert_eq!(diagonal, [(0, 0), (1, 1), (2, 2), (3, 3), (4, 4)]);
+        assert_eq!(path_length(&straight), 8.0);
+        assert!((path_length(&diagonal) - 4.0 * SQRT_2).abs() < 1e-12);
+
+        let grid = GridGraph::from_rows(&MAZE).unwrap();
+        let with_diagonals = grid.shortest_path((0, 0), (6, 0), true).unwrap();
+        let length = path_length(&with_diagonals);
+        assert!(length < 12.0);
+        let distance = grid.distance_map((0, 0), true)[6].unwrap();
+        assert!((distance - length).abs() < 1e-12);
+        for (with, without) in grid
+            .distance_map((0, 0), true)
+            .iter()
+            .zip(grid.distance_map((0, 0), false))
+        {
+            assert_eq!(with.is_some(), without.is_some());
+            if let (Some(with), Some(without)) = (with, without) {
+                assert!(*with <= without + 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn no_corner_cutting() {
+        // the only way between the open corners is diagonal, between two walls
+        let grid = GridGraph::from_rows(&[".#", "#."]).unwrap();
+        assert_eq!(grid.shortest_path((0, 0), (1, 1), true), None);
+        let grid = GridGraph::from_rows(&["..", "#."]).unwrap();
+        assert_eq!(
+            grid.shortest_path((0, 0), (1, 1), true),
+            Some(vec![(0, 0), (1, 0), (1, 1)])
+        );
+    }
+
+    #[test]
+    fn unreachable_cells() {
+        let grid = GridGraph::from_rows(&["..#..", "..#..", "..#.."]).unwrap();
+        assert_eq!(grid.shortest_path((0, 0), (4, 2), true), None);
+        assert_eq!(grid.shortest_path((0, 0), (2, 0), false), None);
+        assert_eq!(grid.shortest_path((2, 0), (0, 0), false), None);
+        assert_eq!(grid.shortest_path((0, 0), (9, 0), false), None);
+        assert_eq!(grid.shortest_path((1, 1), (1, 1), true), Some(vec![(1, 1)]));
+        let distances = grid.distance_map((0, 0), false);
+        assert_eq!(distances[..3], [Some(0.0), Some(1.0), None]);
+        assert_eq!(distances[4], None);
+        assert_eq!(distances[5], Some(1.0));
+        assert!(grid.distance_map((2, 0), false).iter().all(Option::is_none));
+        assert_eq!(
+            GridGraph::new(3, 2, vec![true; 5]),
+            Err(AlgorithmError::DimensionMismatch {
+                argument: "walkable",
+                expected: 6,
+                found: 5
+            })
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use std::collections::VecDeque;
+
+// Left vertices are numbered from 0 to left_n - 1, right vertices from 0 to right_n - 1,
+// and every edge (l, r) joins left vertex l to right vertex r.
+
+const UNMATCHED: usize = usize::MAX;
+const INFINITY: usize = usize::MAX;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matching {
+    /// Number of matched pairs
+    pub size: usize,
+    /// The matched (left, right) pairs, sorted by left vertex
+    pub pairs: Vec<(usize, usize)>,
+    /// left_match[l] is the right vertex matched with l, if any
+    pub left_match: Vec<Option<usize>>,
+    /// right_match[r] is the left vertex matched with r, if any
+    pub right_match: Vec<Option<usize>>,
+}
+
+struct HopcroftKarp {
+    adj: Vec<Vec<usize>>,
+    left_match: Vec<usize>,
+    right_match: Vec<usize>,
+    dist: Vec<usize>,
+}
+
+impl HopcroftKarp {
+    /// Layers the free left vertices and everything reachable from them by alternating paths.
+    /// Returns true if some augmenting path exists.
+    fn bfs(&mut self) -> bool {
+        let mut queue = VecDeque::new();
+        for (l, d) in self.dist.iter_mut().enumerate() {
+            if self.left_match[l] == UNMATCHED {
+                *d = 0;
+                queue.push_back(l);
+            } else {
+                *d = INFINITY;
+            }
+        }
+        let mut found = false;
+        while let Some(l) = queue.pop_front() {
+            for &r in &self.adj[l] {
+                let next = self.right_match[r];
+                if next == UNMATCHED {
+                    found = true;
+                } else if self.dist[next] == INFINITY {
+                    self.dist[next] = self.dist[l] + 1;
+                    queue.push_back(next);
+                }
+            }
+        }
+        found
+    }
+
+    /// Looks for an augmenting path from l that follows the BFS layers, and flips it if found.
+    fn dfs(&mut self, l: usize) -> bool {
+        for i in 0..self.adj[l].len() {
+            let r = self.adj[l][i];
+            let next = self.right_match[r];
+            if next == UNMATCHED || (self.dist[next] == self.dist[l] + 1 && self.dfs(next)) {
+                self.left_match[l] = r;
+                self.right_match[r] = l;
+                return true;
+            }
+        }
+        // no augmenting path through l in this phase
+        self.dist[l] = INFINITY;
+        false
+    }
+}
+
+/// Computes a maximum matching of a bipartite graph in O(E * sqrt(V)).
+///
+/// Each phase finds, with one BFS, the length of the shortest augmenting paths,
+/// then augments along a maximal set of vertex-disjoint shortest paths with DFS.
+/// There are at most O(sqrt(V)) phases.
+pub fn hopcroft_karp(left_n: usize, right_n: usize, edges: &[(usize, usize)]) -> Matching {
+    let mut adj = vec![vec![]; left_n];
+    for &(l, r) in edges {
+        assert!(r < right_n, "right vertex {r} is out of range");
+        adj[l].push(r);
+    }
+    let mut hk = HopcroftKarp {
+        adj,
+        left_match: vec![UNMATCHED; left_n],
+        right_match: vec![UNMATCHED; right_n],
+        dist: vec![INFINITY; left_n],
+    };
+
+    let mut size = 0;
+    while hk.bfs() {
+        for l in 0..left_n {
+            if hk.left_match[l] == UNMATCHED && hk.dfs(l) {
+                size += 1;
+            }
+        }
+    }
+
+    let to_option = |m: usize| if m == UNMATCHED { None } else { Some(m) };
+    let left_match: Vec<Option<usize>> = hk.left_match.into_iter().map(to_option).collect();
+    let right_match = hk.right_match.into_iter().map(to_option).collect();
+    let pairs = left_match
+        .iter()
+        .enumerate()
+        .filter_map(|(l, r)| r.map(|r| (l, r)))
+        .collect();
+
+    Matching {
+        size,
+        pairs,
+        left_match,
+        right_match,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::DinicMaxFlow;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn assert_valid(left_n: usize, right_n: usize, edges: &[(usize, usize)], m: &Matching) {
+        assert_eq!(m.pairs.len(), m.size);
+        let mut right_used = vec![false; right_n];
+        for &(l, r) in &m.pairs {
+            assert!(edges.contains(&(l, r)));
+            assert!(!right_used[r]);
+            right_used[r] = true;
+            assert_eq!(m.left_match[l], Some(r));
+            assert_eq!(m.right_match[r], Some(l));
+        }
+        assert_eq!(m.left_match.iter().flatten().count(), m.size);
+        assert_eq!(m.left_match.len(), left_n);
+    }
+
+    fn max_flow_matching_size(left_n: usize, right_n: usize, edges: &[(usize, usize)]) -> usize {
+        // DinicMaxFlow numbers vertices from 1: source, left side, right side, sink
+        let source = 1;
+        let sink = left_n + right_n + 2;
+        let mut flow = DinicMaxFlow::new(source, sink, sink);
+        for l in 0..left_n {
+            flow.add_edge(source, l + 2, 1);
+        }
+        for r in 0..right_n {
+            flow.add_edge(left_n + r + 2, sink, 1);
+        }
+        for &(l, r) in edges {
+            flow.add_edge(l + 2, left_n + r + 2, 1);
+        }
+        flow.find_maxflow(i64::MAX) as usize
+    }
+
+    fn minimum_vertex_cover_size(left_n: usize, right_n: usize, edges: &[(usize, usize)]) -> usize {
+        let n = left_n + right_n;
+        (0u32..1 << n)
+            .filter(|mask| {
+                edges
+                    .iter()
+                    .all(|&(l, r)| mask >> l & 1 == 1 || mask >> (left_n + r) & 1 == 1)
+            })
+            .map(|mask| mask.count_ones() as usize)
+            .min()
+            .unwrap()
+    }
+
+    #[test]
+    fn empty_graphs() {
+        let m = hopcroft_karp(0, 0, &[]);
+        assert_eq!(m.size, 0);
+        let m = hopcroft_karp(3, 4, &[]);
+        assert_eq!(m.size, 0);
+        assert_eq!(m.left_match, vec![None; 3]);
+        assert_eq!(m.right_match, vec![None; 4]);
+    }
+
+    #[test]
+    fn small_graphs() {
+        let edges = vec![(0, 0)];
+        assert_eq!(hopcroft_karp(1, 1, &edges).pairs, vec![(0, 0)]);
+
+        // every left vertex is only connected to right vertex 0
+        let edges: Vec<(usize, usize)> = (0..10).map(|l| (l, 0)).collect();
+        let m = hopcroft_karp(10, 10, &edges);
+        assert_eq!(m.size, 1);
+        assert_valid(10, 10, &edges, &m);
+
+        // the greedy choice (0, 0) must be undone to reach the perfect matching
+        let edges = vec![(0, 0), (0, 1), (1, 0)];
+        let m = hopcroft_karp(2, 2, &edges);
+        assert_eq!(m.pairs, vec![(0, 1), (1, 0)]);
+
+        // a 6x6 graph with isolated vertices on both sides
+        let edges = vec![
+            (0, 1),
+            (0, 2),
+            (2, 3),
+            (2, 0),
+            (3, 2),
+            (4, 2),
+            (4, 3),
+            (5, 5),
+        ];
+        let m = hopcroft_karp(6, 6, &edges);
+        assert_eq!(m.size, 5);
+        assert_eq!(m.left_match[1], None);
+        assert_eq!(m.right_match[4], None);
+        assert_valid(6, 6, &edges, &m);
+    }
+
+    #[test]
+    fn complete_bipartite() {
+        let edges: Vec<(usize, usize)> = (0..5).flat_map(|l| (0..8).map(move |r| (l, r))).collect();
+        let m = hopcroft_karp(5, 8, &edges);
+        assert_eq!(m.size, 5);
+        assert_valid(5, 8, &edges, &m);
+    }
+
+    #[test]
+    fn agrees_with_max_flow() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2024);
+        for _ in 0..100 {
+            let left_n = rng.gen_range(1..25);
+            let right_n = rng.gen_range(1..25);
+            let m = rng.gen_range(0..3 * (left_n + right_n));
+            let edges: Vec<(usize, usize)> = (0..m)
+                .map(|_| (rng.gen_range(0..left_n), rng.gen_range(0..right_n)))
+                .collect();
+            let matching = hopcroft_karp(left_n, right_n, &edges);
+            assert_valid(left_n, right_n, &edges, &matching);
+            assert_eq!(
+                matching.size,
+                max_flow_matching_size(left_n, right_n, &edges)
+            );
+        }
+    }
+
+    #[test]
+    fn konig_theorem() {
+        // matching size equals minimum vertex cover size on bipartite graphs
+        let instances = vec![
+            (3, 3, vec![(0, 0), (0, 1), (0, 2), (1, 0), (2, 0)]),
+            (4, 3, vec![(0, 0), (1, 0), (1, 1), (2, 1), (2, 2), (3, 2)]),
+            (
+                5,
+                5,
+                vec![
+                    (0, 0),
+                    (0, 1),
+                    (1, 1),
+                    (1, 2),
+                    (2, 2),
+                    (3, 3),
+                    (4, 3),
+                    (4, 4),
+                ],
+            ),
+        ];
+        for (left_n, right_n, edges) in instances {
+            assert_eq!(
+                hopcroft_karp(left_n, right_n, &edges).size,
+                minimum_vertex_cover_size(left_n, right_n, &edges)
+            );
+        }
+    }
+}
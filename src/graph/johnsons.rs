@@ -0,0 +1,205 @@
+use crate::data_structures::{DirectedGraph, Graph};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::error::Error;
+use std::fmt;
+
+/// The graph has a cycle of negative weight, so that some distances are unbounded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NegativeCycleError;
+
+impl fmt::Display for NegativeCycleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the graph has a cycle of negative weight")
+    }
+}
+
+impl Error for NegativeCycleError {}
+
+/// Computes the distances between all pairs of nodes with Johnson's algorithm, in
+/// O(V E log(V)), which beats the O(V^3) of Floyd-Warshall on sparse graphs.
+/// <https://en.wikipedia.org/wiki/Johnson%27s_algorithm>
+///
+/// The rows and columns of the result follow the nodes sorted by name, with `None` for the
+/// nodes out of reach. Negative weights are fine unless they make a negative cycle.
+///
+/// Bellman-Ford from a virtual source, with an edge of weight 0 to every node, gives every node
+/// `v` a potential `h(v) <= 0` such that the reweighted edges `w(u, v) + h(u) - h(v)` are all
+/// nonnegative. Reweighting adds `h(s) - h(t)` to every path from `s` to `t`, so that the
+/// shortest paths stay the same, and Dijkstra from every node finds them.
+pub fn johnsons_algorithm(
+    graph: &DirectedGraph,
+) -> Result<Vec<Vec<Option<i64>>>, NegativeCycleError> {
+    let mut nodes: Vec<&String> = graph.nodes().into_iter().collect();
+    nodes.sort();
+    let index: HashMap<&String, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, &node)| (node, i))
+        .collect();
+    let mut adjacency: Vec<Vec<(usize, i64)>> = vec![vec![]; nodes.len()];
+    for (from, to, weight) in graph.edges() {
+        adjacency[index[from]].push((index[to], weight as i64));
+    }
+
+    // the virtual source is at distance 0 from every node, so that Bellman-Ford starts with
+    // all the potentials at 0, and a relaxation still possible after V rounds is a cycle
+    let mut potential = vec![0i64; nodes.len()];
+    for round in 0..=nodes.len() {
+        let mut relaxed = false;
+        for (u, edges) in adjacency.iter().enumerate() {
+            for &(v, weight) in edges {
+                if potential[u] + weight < potential[v] {
+                    potential[v] = potential[u] + weight;
+                    relaxed = true;
+                }
+            }
+        }
+        if !relaxed {
+            break;
+        }
+        if round == nodes.len() {
+            return Err(NegativeCycleError);
+        }
+    }
+
+    Ok((0..nodes.len())
+        .map(|source| {
+            let mut distance: Vec<Option<i64>> = vec![None; nodes.len()];
+            let mut heap = BinaryHeap::from([Reverse((0, source))]);
+            distance[source] = Some(0);
+            while let Some(Reverse((d, u))) = heap.pop() {
+                if distance[u].is_some_and(|best| d > best) {
+                    continue;
+                }
+                for &(v, weight) in &adjacency[u] {
+                    let next = d + weight + potential[u] - potential[v];
+                    if distance[v].is_none_or(|best| next < best) {
+                        distance[v] = Some(next);
+                        heap.push(Reverse((next, v)));
+                    }
+                }
+            }
+            // back to the original weights
+            (0..nodes.len())
+                .map(|target| distance[target].map(|d| d - potential[source] + potential[target]))
+                .collect()
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::floyd_warshall;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use std::collections::BTreeMap;
+
+    fn name(i: usize) -> String {
+        format!("v{i:03}")
+    }
+
+    /// A random graph without negative cycles: nonnegative weights, shifted by the differences
+    /// of random potentials, which adds the same amount to every path between two nodes
+    fn random_graph(
+        rng: &mut ChaCha8Rng,
+        n: usize,
+        m: usize,
+    ) -> (DirectedGraph, BTreeMap<usize, BTreeMap<usize, i64>>) {
+        let shift: Vec<i32> = (0..n).map(|_| rng.gen_range(-50..50)).collect();
+        let mut edges: BTreeMap<usize, BTreeMap<usize, i64>> =
+            (0..n).map(|u| (u, BTreeMap::new())).collect();
+        for _ in 0..m {
+            let (u, v) = (rng.gen_range(0..n), rng.gen_range(0..n));
+            if u != v && !edges[&u].contains_key(&v) {
+                let weight = rng.gen_range(0..100) + shift[u] - shift[v];
+                edges.get_mut(&u).unwrap().insert(v, weight as i64);
+            }
+        }
+        let mut graph = DirectedGraph::new();
+        for (&u, targets) in &edges {
+            graph.add_node(&name(u));
+            for (&v, &weight) in targets {
+                graph.add_edge((&name(u), &name(v), weight as i32));
+            }
+        }
+        (graph, edges)
+    }
+
+    #[test]
+    fn small_graph_with_negative_edges() {
+        let mut graph = DirectedGraph::new();
+        graph.add_edge(("a", "b", 3));
+        graph.add_edge(("a", "c", 8));
+        graph.add_edge(("b", "c", -2));
+        graph.add_edge(("c", "a", 1));
+        graph.add_edge(("c", "d", -4));
+        graph.add_node("e");
+        let distances = johnsons_algorithm(&graph).unwrap();
+        assert_eq!(distances[0], [Some(0), Some(3), Some(1), Some(-3), None]);
+        assert_eq!(distances[2], [Some(1), Some(4), Some(0), Some(-4), None]);
+        assert_eq!(distances[3], [None, None, None, Some(0), None]);
+        assert_eq!(distances[4], [None, None, None, None, Some(0)]);
+        assert!(johnsons_algorithm(&DirectedGraph::new())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn negative_cycles() {
+        let mut graph = DirectedGraph::new();
+        graph.add_edge(("a", "b", 1));
+        graph.add_edge(("b", "c", -2));
+        graph.add_edge(("c", "a", 1));
+        // a cycle of weight 0 is fine
+        assert!(johnsons_algorithm(&graph).is_ok());
+        graph.add_edge(("c", "d", 5));
+        graph.add_edge(("d", "b", -5));
+        let error = johnsons_algorithm(&graph).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "the graph has a cycle of negative weight"
+        );
+        // even out of reach of the other nodes, and as a single loop
+        let mut graph = DirectedGraph::new();
+        graph.add_edge(("a", "b", 1));
+        graph.add_edge(("x", "x", -1));
+        assert_eq!(johnsons_algorithm(&graph), Err(NegativeCycleError));
+    }
+
+    #[test]
+    fn agrees_with_floyd_warshall() {
+        let mut rng = ChaCha8Rng::seed_from_u64(143);
+        for _ in 0..30 {
+            let n = rng.gen_range(1..30);
+            let m = rng.gen_range(0..4 * n);
+            let (graph, edges) = random_graph(&mut rng, n, m);
+            let expected = floyd_warshall(&edges);
+            let distances = johnsons_algorithm(&graph).unwrap();
+            for (u, row) in distances.iter().enumerate() {
+                for (v, &distance) in row.iter().enumerate() {
+                    assert_eq!(distance, expected[&u].get(&v).copied(), "{u} {v}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[ignore = "slow test for measuring time"]
+    fn faster_than_floyd_warshall_on_sparse_graphs() {
+        use std::time::Instant;
+        let mut rng = ChaCha8Rng::seed_from_u64(1430);
+        for (n, m) in [(50, 150), (100, 300), (200, 600)] {
+            let (graph, edges) = random_graph(&mut rng, n, m);
+            let start = Instant::now();
+            let distances = johnsons_algorithm(&graph).unwrap();
+            let johnson = start.elapsed();
+            let start = Instant::now();
+            let expected = floyd_warshall(&edges);
+            let floyd = start.elapsed();
+            assert_eq!(distances[0][1], expected[&0].get(&1).copied());
+            println!("{n} nodes, {m} edges: Johnson {johnson:?}, Floyd-Warshall {floyd:?}");
+        }
+    }
+}
@@ -0,0 +1,541 @@
+/*
+Lowest common ancestors in a rooted tree, answered online after a preprocessing.
+
+`LcaTree` uses binary lifting: for every vertex, its ancestors 1, 2, 4, 8, ... levels above.
+Any ancestor is reached by jumping along the binary representation of its distance, and the
+lowest common ancestor of two vertices at the same depth by jumping both as long as they land
+on different vertices. Preprocessing takes O(n log n), and every query O(log n).
+
+`EulerTourLca` lists the vertices in the order a depth-first search enters and comes back to
+them. Between the first occurrences of two vertices, the tour goes up to their lowest common
+ancestor and no higher, so it is the shallowest vertex in that range, found with a sparse table
+of range minima. Preprocessing takes O(n log n) too, but every query only O(1).
+
+Unlike `LowestCommonAncestorOnline`, vertices are numbered from 0, and the trees are built
+without recursion, so that deep trees do not overflow the stack. A forest with several roots is
+rejected: add a virtual root linked to every root to handle forests.
+*/
+
+use crate::error::{AlgorithmError, AlgorithmResult};
+use std::collections::VecDeque;
+
+/// A tree validated by the constructors, with every vertex listed after its parent
+struct RootedTree {
+    root: usize,
+    /// The root is its own parent
+    parent: Vec<usize>,
+    /// The weight of the edge to the parent, 0 for the root
+    parent_weight: Vec<u64>,
+    children: Vec<Vec<usize>>,
+    depth: Vec<usize>,
+    /// The vertices in breadth-first order, from the root
+    order: Vec<usize>,
+}
+
+fn invalid_tree(argument: &'static str, reason: String) -> AlgorithmError {
+    AlgorithmError::InvalidParameter { argument, reason }
+}
+
+impl RootedTree {
+    fn from_parents(parents: &[Option<usize>]) -> AlgorithmResult<Self> {
+        let n = parents.len();
+        let mut roots = (0..n).filter(|&v| parents[v].is_none());
+        let root = roots.next().ok_or_else(|| {
+            invalid_tree(
+                "parents",
+                "there is no root, so there is a cycle".to_string(),
+            )
+        })?;
+        if let Some(other) = roots.next() {
+            return Err(invalid_tree(
+                "parents",
+                format!("{root} and {other} are both roots"),
+            ));
+        }
+        let mut edges = Vec::with_capacity(n - 1);
+        for (v, parent) in parents.iter().enumerate() {
+            if let Some(parent) = *parent {
+                edges.push((parent, v, 1));
+            }
+        }
+        Self::from_weighted_edges(n, &edges, root).map_err(|error| match error {
+            AlgorithmError::InvalidParameter { reason, .. } => invalid_tree("parents", reason),
+            error => error,
+        })
+    }
+
+    fn from_weighted_edges(
+        n: usize,
+        edges: &[(usize, usize, u64)],
+        root: usize,
+    ) -> AlgorithmResult<Self> {
+        if n == 0 {
+            return Err(AlgorithmError::EmptyInput { argument: "n" });
+        }
+        if root >= n {
+            return Err(invalid_tree(
+                "root",
+                format!("{root} is not a vertex of a tree of {n} vertices"),
+            ));
+        }
+        if edges.len() != n - 1 {
+            return Err(invalid_tree(
+                "edges",
+                format!(
+                    "a tree of {n} vertices has {} edges, got {}",
+                    n - 1,
+                    edges.len()
+                ),
+            ));
+        }
+        let mut adjacency = vec![vec![]; n];
+        for &(u, v, weight) in edges {
+            if u >= n || v >= n {
+                return Err(invalid_tree(
+                    "edges",
+                    format!("the edge ({u}, {v}) has a vertex out of 0..{n}"),
+                ));
+            }
+            adjacency[u].push((v, weight));
+            adjacency[v].push((u, weight));
+        }
+        let mut tree = RootedTree {
+            root,
+            parent: vec![usize::MAX; n],
+            parent_weight: vec![0; n],
+            children: vec![vec![]; n],
+            depth: vec![0; n],
+            order: Vec::with_capacity(n),
+        };
+        tree.parent[root] = root;
+        let mut queue = VecDeque::from([root]);
+        while let Some(u) = queue.pop_front() {
+            tree.order.push(u);
+            for &(v, weight) in &adjacency[u] {
+                if tree.parent[v] == usize::MAX {
+                    tree.parent[v] = u;
+                    tree.parent_weight[v] = weight;
+                    tree.depth[v] = tree.depth[u] + 1;
+                    tree.children[u].push(v);
+                    queue.push_back(v);
+                }
+            }
+        }
+        // with n - 1 edges, the tree is connected if and only if it has no cycle
+        if tree.order.len() != n {
+            return Err(invalid_tree(
+                "edges",
+                "the edges have a cycle and leave vertices disconnected".to_string(),
+            ));
+        }
+        Ok(tree)
+    }
+}
+
+/// Lowest common ancestors, ancestors and paths in a rooted tree, with binary lifting.
+pub struct LcaTree {
+    /// `ancestors[j][v]` is the ancestor `2^j` levels above `v`, or the root
+    ancestors: Vec<Vec<usize>>,
+    depth: Vec<usize>,
+    /// The sum of the weights of the edges from the root
+    weighted_depth: Vec<u64>,
+}
+
+impl LcaTree {
+    /// Builds the tree where `parents[v]` is the parent of `v`, and `None` for the root only.
+    /// Every edge has weight 1.
+    ///
+    /// Returns an error if there is not exactly one root, or if the parents have a cycle.
+    pub fn from_parents(parents: &[Option<usize>]) -> AlgorithmResult<Self> {
+        Ok(Self::build(RootedTree::from_parents(parents)?))
+    }
+
+    /// Builds the tree of `n` vertices with the undirected `edges`, rooted at `root`. Every
+    /// edge has weight 1.
+    ///
+    /// Returns an error unless the edges form a single tree spanning all vertices.
+    pub fn from_edges(n: usize, edges: &[(usize, usize)], root: usize) -> AlgorithmResult<Self> {
+        let edges: Vec<(usize, usize, u64)> = edges.iter().map(|&(u, v)| (u, v, 1)).collect();
+        Self::from_weighted_edges(n, &edges, root)
+    }
+
+    /// Same as `from_edges`, for edges `(u, v, weight)`.
+    pub fn from_weighted_edges(
+        n: usize,
+        edges: &[(usize, usize, u64)],
+        root: usize,
+    ) -> AlgorithmResult<Self> {
+        Ok(Self::build(RootedTree::from_weighted_edges(
+            n, edges, root,
+        )?))
+    }
+
+    fn build(tree: RootedTree) -> Self {
+        let n = tree.parent.len();
+        let levels = (usize::BITS - n.leading_zeros()).max(1) as usize;
+        let mut ancestors = vec![tree.parent];
+        for j in 1..levels {
+            let previous = &ancestors[j - 1];
+            let next = (0..n).map(|v| previous[previous[v]]).collect();
+            ancestors.push(next);
+        }
+        let mut weighted_depth = vec![0; n];
+        for &v in &tree.order[1..] {
+            weighted_depth[v] = weighted_depth[ancestors[0][v]] + tree.parent_weight[v];
+        }
+        LcaTree {
+            ancestors,
+            depth: tree.depth,
+            weighted_depth,
+        }
+    }
+
+    /// Returns the number of vertices.
+    pub fn len(&self) -> usize {
+        self.depth.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.depth.is_empty()
+    }
+
+    /// Returns the number of edges between `u` and the root.
+    pub fn depth(&self, u: usize) -> usize {
+        self.depth[u]
+    }
+
+    /// Returns the ancestor `k` levels above `u`, `u` itself for `k = 0`, or `None` past the
+    /// root.
+    pub fn kth_ancestor(&self, mut u: usize, k: usize) -> Option<usize> {
+        if k > self.depth[u] {
+            return None;
+        }
+        for (j, level) in self.ancestors.iter().enumerate() {
+            if k >> j & 1 == 1 {
+                u = level[u];
+            }
+        }
+        Some(u)
+    }
+
+    /// Returns the lowest common ancestor of `u` and `v`.
+    ///
+    /// Panics if `u` or `v` is not a vertex of the tree, like the queries below.
+    pub fn lca(&self, u: usize, v: usize) -> usize {
+        let (deeper, other) = if self.depth[u] >= self.depth[v] {
+            (u, v)
+        } else {
+            (v, u)
+        };
+        let mut u = self
+            .kth_ancestor(deeper, self.depth[deeper] - self.depth[other])
+            .unwrap();
+        let mut v = other;
+        if u == v {
+            return u;
+        }
+        for level in self.ancestors.iter().rev() {
+            if level[u] != level[v] {
+                u = level[u];
+                v = level[v];
+            }
+        }
+        self.ancestors[0][u]
+    }
+
+    /// Returns the number of edges on the path between `u` and `v`.
+    pub fn distance(&self, u: usize, v: usize) -> usize {
+        self.depth[u] + self.depth[v] - 2 * self.depth[self.lca(u, v)]
+    }
+
+    /// Returns the sum of the weights of the edges on the path between `u` and `v`.
+    pub fn weighted_distance(&self, u: usize, v: usize) -> u64 {
+        self.weighted_depth[u] + self.weighted_depth[v] - 2 * self.weighted_depth[self.lca(u, v)]
+    }
+
+    /// Returns the vertex `k` edges away from `u` on the path from `u` to `v`: `u` for `k = 0`,
+    /// `v` for `k = distance(u, v)`, and `None` past `v`.
+    pub fn jump_on_path(&self, u: usize, v: usize, k: usize) -> Option<usize> {
+        let lca = self.lca(u, v);
+        let up = self.depth[u] - self.depth[lca];
+        let down = self.depth[v] - self.depth[lca];
+        if k <= up {
+            self.kth_ancestor(u, k)
+        } else if k <= up + down {
+            self.kth_ancestor(v, up + down - k)
+        } else {
+            None
+        }
+    }
+}
+
+/// Lowest common ancestors in a rooted tree, with an Euler tour and a sparse table.
+pub struct EulerTourLca {
+    /// The vertices in the order the depth-first search enters them and comes back to them
+    tour: Vec<usize>,
+    first_occurrence: Vec<usize>,
+    depth: Vec<usize>,
+    /// `sparse_table[j][i]` is the shallowest vertex in `tour[i..i + 2^j]`
+    sparse_table: Vec<Vec<usize>>,
+}
+
+impl EulerTourLca {
+    /// Builds the tree where `parents[v]` is the parent of `v`, and `None` for the root only,
+    /// with the same errors as `LcaTree::from_parents`.
+    pub fn from_parents(parents: &[Option<usize>]) -> AlgorithmResult<Self> {
+        Ok(Self::build(RootedTree::from_parents(parents)?))
+    }
+
+    /// Builds the tree of `n` vertices with the undirected `edges`, rooted at `root`, with the
+    /// same errors as `LcaTree::from_edges`.
+    pub fn from_edges(n: usize, edges: &[(usize, usize)], root: usize) -> AlgorithmResult<Self> {
+        let edges: Vec<(usize, usize, u64)> = edges.iter().map(|&(u, v)| (u, v, 1)).collect();
+        Ok(Self::build(RootedTree::from_weighted_edges(
+            n, &edges, root,
+        )?))
+    }
+
+    fn build(tree: RootedTree) -> Self {
+        let n = tree.parent.len();
+        let mut tour = Vec::with_capacity(2 * n - 1);
+        let mut first_occurrence = vec![0; n];
+        // the vertices being visited, with the index of their next child to visit
+        let mut stack = vec![(tree.root, 0)];
+        first_occurrence[tree.root] = 0;
+        tour.push(tree.root);
+        while let Some((u, next_child)) = stack.last_mut() {
+            let u = *u;
+            if let Some(&child) = tree.children[u].get(*next_child) {
+                *next_child += 1;
+                first_occurrence[child] = tour.len();
+                tour.push(child);
+                stack.push((child, 0));
+            } else {
+                stack.pop();
+                if let Some(&(parent, _)) = stack.last() {
+                    tour.push(parent);
+                }
+            }
+        }
+
+        let shallowest = |a: usize, b: usize| {
+            if tree.depth[a] <= tree.depth[b] {
+                a
+            } else {
+                b
+            }
+        };
+        let mut sparse_table = vec![tour.clone()];
+        let mut width = 1;
+        while 2 * width <= tour.len() {
+            let previous = sparse_table.last().unwrap();
+            let next = (0..=tour.len() - 2 * width)
+                .map(|i| shallowest(previous[i], previous[i + width]))
+                .collect();
+            sparse_table.push(next);
+            width *= 2;
+        }
+        EulerTourLca {
+            tour,
+            first_occurrence,
+            depth: tree.depth,
+            sparse_table,
+        }
+    }
+
+    /// Returns the Euler tour of the tree, of length `2n - 1`.
+    pub fn tour(&self) -> &[usize] {
+        &self.tour
+    }
+
+    /// Returns the lowest common ancestor of `u` and `v`, in O(1).
+    pub fn lca(&self, u: usize, v: usize) -> usize {
+        let (a, b) = (self.first_occurrence[u], self.first_occurrence[v]);
+        let (start, end) = (a.min(b), a.max(b) + 1);
+        // two ranges of width 2^level cover start..end
+        let level = (usize::BITS - 1 - (end - start).leading_zeros()) as usize;
+        let first = self.sparse_table[level][start];
+        let second = self.sparse_table[level][end - (1 << level)];
+        if self.depth[first] <= self.depth[second] {
+            first
+        } else {
+            second
+        }
+    }
+
+    /// Returns the number of edges on the path between `u` and `v`.
+    pub fn distance(&self, u: usize, v: usize) -> usize {
+        self.depth[u] + self.depth[v] - 2 * self.depth[self.lca(u, v)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    //          0
+    //        / | \
+    //       1  2  3
+    //      / \     \
+    //     4   5     6
+    //    /         / \
+    //   7         8   9
+    fn small_tree() -> Vec<Option<usize>> {
+        vec![
+            None,
+            Some(0),
+            Some(0),
+            Some(0),
+            Some(1),
+            Some(1),
+            Some(3),
+            Some(4),
+            Some(6),
+            Some(6),
+        ]
+    }
+
+    #[test]
+    fn hand_checked_queries() {
+        let lifting = LcaTree::from_parents(&small_tree()).unwrap();
+        let euler = EulerTourLca::from_parents(&small_tree()).unwrap();
+        for (u, v, lca, distance) in [
+            (7, 5, 1, 3),
+            (7, 9, 0, 6),
+            (8, 9, 6, 2),
+            (4, 7, 4, 1),
+            (2, 2, 2, 0),
+            (0, 8, 0, 3),
+            (5, 2, 0, 3),
+        ] {
+            assert_eq!(lifting.lca(u, v), lca, "lca({u}, {v})");
+            assert_eq!(lifting.lca(v, u), lca);
+            assert_eq!(euler.lca(u, v), lca);
+            assert_eq!(lifting.distance(u, v), distance);
+            assert_eq!(euler.distance(u, v), distance);
+        }
+        assert_eq!(euler.tour().len(), 19);
+        assert_eq!(lifting.len(), 10);
+    }
+
+    #[test]
+    fn ancestors_and_jumps() {
+        let tree = LcaTree::from_parents(&small_tree()).unwrap();
+        assert_eq!(tree.kth_ancestor(7, 0), Some(7));
+        assert_eq!(tree.kth_ancestor(7, 1), Some(4));
+        assert_eq!(tree.kth_ancestor(7, 2), Some(1));
+        assert_eq!(tree.kth_ancestor(7, 3), Some(0));
+        assert_eq!(tree.kth_ancestor(7, 4), None);
+        assert_eq!(tree.kth_ancestor(0, 1), None);
+        // the path 7 4 1 0 3 6 9
+        let path: Vec<Option<usize>> = (0..=7).map(|k| tree.jump_on_path(7, 9, k)).collect();
+        let expected = [7, 4, 1, 0, 3, 6, 9].map(Some);
+        assert_eq!(path[..7], expected);
+        assert_eq!(path[7], None);
+        assert_eq!(tree.jump_on_path(9, 7, 0), Some(9));
+        assert_eq!(tree.jump_on_path(9, 7, 6), Some(7));
+        assert_eq!(tree.jump_on_path(5, 5, 0), Some(5));
+        assert_eq!(tree.jump_on_path(5, 5, 1), None);
+    }
+
+    #[test]
+    fn weighted_distances() {
+        let edges = [(0, 1, 5), (1, 2, 3), (1, 3, 10), (0, 4, 1)];
+        let tree = LcaTree::from_weighted_edges(5, &edges, 0).unwrap();
+        assert_eq!(tree.weighted_distance(2, 3), 13);
+        assert_eq!(tree.weighted_distance(2, 4), 9);
+        assert_eq!(tree.weighted_distance(4, 4), 0);
+        assert_eq!(tree.distance(2, 4), 3);
+        // rooted elsewhere, the same tree has the same distances
+        let tree = LcaTree::from_weighted_edges(5, &edges, 3).unwrap();
+        assert_eq!(tree.lca(2, 4), 1);
+        assert_eq!(tree.weighted_distance(2, 4), 9);
+    }
+
+    #[test]
+    fn random_trees_agree() {
+        let mut rng = ChaCha8Rng::seed_from_u64(113);
+        let n: usize = 10_000;
+        // every vertex hangs from a random earlier one, possibly far up
+        let parents: Vec<Option<usize>> = (0..n)
+            .map(|v| (v > 0).then(|| rng.gen_range(v.saturating_sub(20)..v)))
+            .collect();
+        let lifting = LcaTree::from_parents(&parents).unwrap();
+        let euler = EulerTourLca::from_parents(&parents).unwrap();
+        for _ in 0..5000 {
+            let (u, v) = (rng.gen_range(0..n), rng.gen_range(0..n));
+            let lca = lifting.lca(u, v);
+            assert_eq!(euler.lca(u, v), lca);
+            // the ancestor is an ancestor of both vertices
+            assert_eq!(
+                lifting.kth_ancestor(u, lifting.depth(u) - lifting.depth(lca)),
+                Some(lca)
+            );
+            assert_eq!(
+                lifting.kth_ancestor(v, lifting.depth(v) - lifting.depth(lca)),
+                Some(lca)
+            );
+            let distance = lifting.distance(u, v);
+            assert_eq!(lifting.jump_on_path(u, v, distance), Some(v));
+        }
+    }
+
+    #[test]
+    fn deep_path_graph() {
+        let n = 100_000;
+        let edges: Vec<(usize, usize)> = (1..n).map(|v| (v - 1, v)).collect();
+        let lifting = LcaTree::from_edges(n, &edges, 0).unwrap();
+        let euler = EulerTourLca::from_edges(n, &edges, 0).unwrap();
+        assert_eq!(lifting.lca(n - 1, 12_345), 12_345);
+        assert_eq!(euler.lca(n - 1, 12_345), 12_345);
+        assert_eq!(lifting.kth_ancestor(n - 1, n - 1), Some(0));
+        assert_eq!(lifting.kth_ancestor(n - 1, 65_537), Some(n - 1 - 65_537));
+        assert_eq!(lifting.kth_ancestor(n - 1, n), None);
+        assert_eq!(lifting.jump_on_path(3, 99_000, 50_000), Some(50_003));
+        // rooted in the middle
+        let lifting = LcaTree::from_edges(n, &edges, n / 2).unwrap();
+        assert_eq!(lifting.lca(0, n - 1), n / 2);
+        assert_eq!(lifting.distance(0, n - 1), n - 1);
+    }
+
+    #[test]
+    fn single_vertex() {
+        let tree = LcaTree::from_parents(&[None]).unwrap();
+        assert_eq!(tree.lca(0, 0), 0);
+        assert_eq!(tree.kth_ancestor(0, 1), None);
+        let euler = EulerTourLca::from_edges(1, &[], 0).unwrap();
+        assert_eq!(euler.lca(0, 0), 0);
+    }
+
+    #[test]
+    fn rejects_inputs_that_are_not_trees() {
+        assert!(matches!(
+            LcaTree::from_parents(&[Some(1), Some(0)]),
+            Err(AlgorithmError::InvalidParameter {
+                argument: "parents",
+                ..
+            })
+        ));
+        // two roots: a forest
+        assert_eq!(
+            LcaTree::from_parents(&[None, Some(0), None]).err(),
+            Some(AlgorithmError::InvalidParameter {
+                argument: "parents",
+                reason: "0 and 2 are both roots".to_string(),
+            })
+        );
+        // a cycle away from the root
+        assert!(EulerTourLca::from_parents(&[None, Some(2), Some(1)]).is_err());
+        // a cycle, so one vertex is left out
+        assert!(LcaTree::from_edges(4, &[(0, 1), (1, 2), (2, 0)], 0).is_err());
+        assert!(LcaTree::from_edges(3, &[(0, 1)], 0).is_err());
+        assert!(LcaTree::from_edges(2, &[(0, 5)], 0).is_err());
+        assert!(LcaTree::from_edges(2, &[(0, 1)], 2).is_err());
+        assert_eq!(
+            LcaTree::from_edges(0, &[], 0).err(),
+            Some(AlgorithmError::EmptyInput { argument: "n" })
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use crate::error::{AlgorithmError, AlgorithmResult};
+
+/// Solves the assignment problem with the Hungarian algorithm, in O(n^3): finds the assignment
+/// of rows to distinct columns of a cost matrix with the lowest total cost.
+/// <https://en.wikipedia.org/wiki/Hungarian_algorithm>
+///
+/// A rectangular matrix is padded with zeros to a square one, so that every row is assigned to a
+/// column when there are at least as many columns, and that only as many rows as there are
+/// columns are otherwise. Returns the total cost, and the column of every row, if any.
+///
+/// The rows are added one at a time, each along the shortest augmenting path for the reduced
+/// costs `cost[i][j] - u[i] - v[j]`, which the potentials `u` and `v` keep non-negative, and
+/// zero on the assigned cells.
+pub fn hungarian(cost: &[Vec<i64>]) -> AlgorithmResult<(i64, Vec<Option<usize>>)> {
+    let rows = cost.len();
+    let columns = cost.first().map_or(0, Vec::len);
+    if let Some(row) = cost.iter().find(|row| row.len() != columns) {
+        return Err(AlgorithmError::DimensionMismatch {
+            argument: "cost",
+            expected: columns,
+            found: row.len(),
+        });
+    }
+    let n = rows.max(columns);
+    let at = |i: usize, j: usize| {
+        if i < rows && j < columns {
+            cost[i][j]
+        } else {
+            0
+        }
+    };
+
+    // 1-based, with the row 0 and the column 0 standing for the row being added
+    let mut u = vec![0; n + 1];
+    let mut v = vec![0; n + 1];
+    // `row_of[j]` is the row assigned to the column `j`, 0 for none
+    let mut row_of = vec![0; n + 1];
+    for i in 1..=n {
+        row_of[0] = i;
+        let mut column = 0;
+        // the shortest reduced distance to every column, and the column before it on the path
+        let mut distance = vec![i64::MAX; n + 1];
+        let mut previous = vec![0; n + 1];
+        let mut used = vec![false; n + 1];
+        while row_of[column] != 0 {
+            used[column] = true;
+            let row = row_of[column];
+            let mut delta = i64::MAX;
+            let mut next = 0;
+            for j in 1..=n {
+                if used[j] {
+                    continue;
+                }
+                let reduced = at(row - 1, j - 1) - u[row] - v[j];
+                if reduced < distance[j] {
+                    distance[j] = reduced;
+                    previous[j] = column;
+                }
+                if distance[j] < delta {
+                    delta = distance[j];
+                    next = j;
+                }
+            }
+            for j in 0..=n {
+                if used[j] {
+                    u[row_of[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    distance[j] -= delta;
+                }
+            }
+            column = next;
+        }
+        // flip the assignments along the path
+        while column != 0 {
+            let before = previous[column];
+            row_of[column] = row_of[before];
+            column = before;
+        }
+    }
+
+    let mut assignment = vec![None; rows];
+    let mut total = 0;
+    for (j, &row) in row_of.iter().enumerate().skip(1) {
+        if row <= rows && j <= columns {
+            assignment[row - 1] = Some(j - 1);
+            total += cost[row - 1][j - 1];
+        }
+    }
+    Ok((total, assignment))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use std::time::Instant;
+
+    /// Returns the lowest total cost of assigning every row to a distinct column, for at most as
+    /// many rows as columns, by trying all the assignments
+    fn brute_force(cost: &[Vec<i64>], row: usize, used: &mut Vec<bool>) -> i64 {
+        if row == cost.len() {
+            return 0;
+        }
+        let mut best = i64::MAX;
+        for j in 0..used.len() {
+            if !used[j] {
+                used[j] = true;
+                best = best.min(cost[row][j] + brute_force(cost, row + 1, used));
+                used[j] = false;
+            }
+        }
+        best
+    }
+
+    fn transpose(cost: &[Vec<i64>]) -> Vec<Vec<i64>> {
+        (0..cost[0].len())
+            .map(|j| cost.iter().map(|row| row[j]).collect())
+            .collect()
+    }
+
+    /// Checks that the assignment is valid, and adds up to the total
+    fn check(cost: &[Vec<i64>], total: i64, assignment: &[Option<usize>]) {
+        let columns = cost[0].len();
+        let mut used = vec![false; columns];
+        let mut sum = 0;
+        for (i, column) in assignment.iter().enumerate() {
+            if let Some(j) = *column {
+                assert!(!std::mem::replace(&mut used[j], true));
+                sum += cost[i][j];
+            }
+        }
+        let assigned = assignment.iter().flatten().count();
+        assert_eq!(assigned, cost.len().min(columns));
+        assert_eq!(sum, total);
+    }
+
+    #[test]
+    fn small_matrix() {
+        let cost = vec![vec![4, 1, 3], vec![2, 0, 5], vec![3, 2, 2]];
+        assert_eq!(hungarian(&cost), Ok((5, vec![Some(1), Some(0), Some(2)])));
+        let workers = vec![
+            vec![90, 75, 75, 80],
+            vec![35, 85, 55, 65],
+            vec![125, 95, 90, 105],
+            vec![45, 110, 95, 115],
+        ];
+        let (total, assignment) = hungarian(&workers).unwrap();
+        assert_eq!(total, 275);
+        check(&workers, total, &assignment);
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1232);
+        for _ in 0..300 {
+            let rows = rng.gen_range(1..=7);
+            let columns = rng.gen_range(1..=7);
+            // a narrow range of values makes ties likely
+            let range = if rng.gen() { -5..5 } else { -1000..1000 };
+            let cost: Vec<Vec<i64>> = (0..rows)
+                .map(|_| (0..columns).map(|_| rng.gen_range(range.clone())).collect())
+                .collect();
+            let (total, assignment) = hungarian(&cost).unwrap();
+            check(&cost, total, &assignment);
+            let expected = if rows <= columns {
+                brute_force(&cost, 0, &mut vec![false; columns])
+            } else {
+                brute_force(&transpose(&cost), 0, &mut vec![false; rows])
+            };
+            assert_eq!(total, expected, "{cost:?}");
+        }
+    }
+
+    #[test]
+    fn edge_cases() {
+        assert_eq!(hungarian(&[]), Ok((0, vec![])));
+        assert_eq!(hungarian(&[vec![], vec![]]), Ok((0, vec![None, None])));
+        assert_eq!(hungarian(&[vec![-7]]), Ok((-7, vec![Some(0)])));
+        // only the cheapest row gets the single column
+        assert_eq!(
+            hungarian(&[vec![1], vec![5], vec![2]]),
+            Ok((1, vec![Some(0), None, None]))
+        );
+        assert_eq!(
+            hungarian(&[vec![1, 2], vec![3]]),
+            Err(AlgorithmError::DimensionMismatch {
+                argument: "cost",
+                expected: 2,
+                found: 1
+            })
+        );
+    }
+
+    #[test]
+    #[ignore = "slow test for measuring time"]
+    fn large_matrix() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1233);
+        let n = 200;
+        let cost: Vec<Vec<i64>> = (0..n)
+            .map(|_| (0..n).map(|_| rng.gen_range(0..1_000_000)).collect())
+            .collect();
+        let now = Instant::now();
+        let (total, assignment) = hungarian(&cost).unwrap();
+        let elapsed = now.elapsed();
+        check(&cost, total, &assignment);
+        println!("200 x 200 in {elapsed:?}, total cost {total}");
+        assert!(elapsed.as_secs() < 1);
+    }
+}
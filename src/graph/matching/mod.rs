@@ -0,0 +1,7 @@
+mod hungarian;
+mod stable_marriage;
+mod tutte;
+
+pub use self::hungarian::hungarian;
+pub use self::stable_marriage::{is_stable, stable_marriage};
+pub use self::tutte::has_perfect_matching_randomized;
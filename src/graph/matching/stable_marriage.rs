@@ -0,0 +1,244 @@
+use crate::error::{AlgorithmError, AlgorithmResult};
+
+/// Checks that every list of preferences ranks all of `0..n`, once each.
+fn check_preferences(argument: &'static str, prefs: &[Vec<usize>]) -> AlgorithmResult<()> {
+    let n = prefs.len();
+    for (i, list) in prefs.iter().enumerate() {
+        let mut seen = vec![false; n];
+        let complete = list.len() == n
+            && list
+                .iter()
+                .all(|&j| j < n && !std::mem::replace(&mut seen[j], true));
+        if !complete {
+            return Err(AlgorithmError::InvalidParameter {
+                argument,
+                reason: format!("the preferences of {i} are not a permutation of 0..{n}"),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// `rank[b][a]` is the position of `a` in the preferences of `b`, lower being better
+fn ranks(prefs: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    prefs
+        .iter()
+        .map(|list| {
+            let mut rank = vec![0; list.len()];
+            for (position, &a) in list.iter().enumerate() {
+                rank[a] = position;
+            }
+            rank
+        })
+        .collect()
+}
+
+/// Returns a stable matching of `n` proposers with `n` acceptors, with the Gale-Shapley
+/// algorithm, in O(n^2).
+/// <https://en.wikipedia.org/wiki/Gale%E2%80%93Shapley_algorithm>
+///
+/// `proposer_prefs[a]` lists the acceptors from the one `a` likes best to the one it likes least,
+/// and `acceptor_prefs[b]` the proposers in the same way. No proposer and acceptor prefer each
+/// other to the partners they are given: the matching is stable. Every free proposer proposes to
+/// the next acceptor down its list, who keeps the best proposal so far, so that every proposer
+/// ends with the best partner it has in any stable matching, and every acceptor with the worst.
+///
+/// `matching[a]` is the acceptor of the proposer `a`. The lists must be permutations of `0..n`.
+pub fn stable_marriage(
+    proposer_prefs: &[Vec<usize>],
+    acceptor_prefs: &[Vec<usize>],
+) -> AlgorithmResult<Vec<usize>> {
+    let n = proposer_prefs.len();
+    if acceptor_prefs.len() != n {
+        return Err(AlgorithmError::DimensionMismatch {
+            argument: "acceptor_prefs",
+            expected: n,
+            found: acceptor_prefs.len(),
+        });
+    }
+    check_preferences("proposer_prefs", proposer_prefs)?;
+    check_preferences("acceptor_prefs", acceptor_prefs)?;
+
+    let acceptor_rank = ranks(acceptor_prefs);
+    // the position in its list of the next acceptor every proposer proposes to
+    let mut next = vec![0; n];
+    let mut partner_of_acceptor: Vec<Option<usize>> = vec![None; n];
+    let mut free: Vec<usize> = (0..n).rev().collect();
+    while let Some(proposer) = free.pop() {
+        let acceptor = proposer_prefs[proposer][next[proposer]];
+        next[proposer] += 1;
+        match partner_of_acceptor[acceptor] {
+            None => partner_of_acceptor[acceptor] = Some(proposer),
+            Some(current)
+                if acceptor_rank[acceptor][proposer] < acceptor_rank[acceptor][current] =>
+            {
+                partner_of_acceptor[acceptor] = Some(proposer);
+                free.push(current);
+            }
+            Some(_) => free.push(proposer),
+        }
+    }
+
+    let mut matching = vec![0; n];
+    for (acceptor, proposer) in partner_of_acceptor.into_iter().enumerate() {
+        matching[proposer.expect("every acceptor gets a proposal")] = acceptor;
+    }
+    Ok(matching)
+}
+
+/// Tells whether `matching`, which gives the acceptor of every proposer, is a perfect matching
+/// in which no proposer and acceptor prefer each other to their partners, in O(n^2).
+///
+/// It is false as well when `matching` or the preferences are not valid.
+pub fn is_stable(matching: &[usize], prefs_a: &[Vec<usize>], prefs_b: &[Vec<usize>]) -> bool {
+    let n = matching.len();
+    if prefs_a.len() != n
+        || prefs_b.len() != n
+        || check_preferences("prefs_a", prefs_a).is_err()
+        || check_preferences("prefs_b", prefs_b).is_err()
+    {
+        return false;
+    }
+    let mut partner_of_b = vec![None; n];
+    for (a, &b) in matching.iter().enumerate() {
+        if b >= n || partner_of_b[b].replace(a).is_some() {
+            return false;
+        }
+    }
+
+    let rank_b = ranks(prefs_b);
+    // a blocking pair is an `a` and a `b` which `a` lists before its partner, and which prefers
+    // `a` to its own partner
+    matching.iter().enumerate().all(|(a, &partner)| {
+        prefs_a[a]
+            .iter()
+            .take_while(|&&b| b != partner)
+            .all(|&b| rank_b[b][partner_of_b[b].unwrap()] < rank_b[b][a])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::seq::SliceRandom;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn random_prefs(rng: &mut ChaCha8Rng, n: usize) -> Vec<Vec<usize>> {
+        (0..n)
+            .map(|_| {
+                let mut list: Vec<usize> = (0..n).collect();
+                list.shuffle(rng);
+                list
+            })
+            .collect()
+    }
+
+    /// Returns every stable matching, by trying all the permutations
+    fn all_stable(prefs_a: &[Vec<usize>], prefs_b: &[Vec<usize>]) -> Vec<Vec<usize>> {
+        fn permutations(prefix: &mut Vec<usize>, n: usize, out: &mut Vec<Vec<usize>>) {
+            if prefix.len() == n {
+                out.push(prefix.clone());
+                return;
+            }
+            for b in 0..n {
+                if !prefix.contains(&b) {
+                    prefix.push(b);
+                    permutations(prefix, n, out);
+                    prefix.pop();
+                }
+            }
+        }
+        let mut matchings = vec![];
+        permutations(&mut vec![], prefs_a.len(), &mut matchings);
+        matchings.retain(|matching| is_stable(matching, prefs_a, prefs_b));
+        matchings
+    }
+
+    #[test]
+    fn textbook_instance() {
+        // men 0..4 propose to women 0..4
+        let men = vec![
+            vec![1, 0, 3, 2],
+            vec![2, 1, 0, 3],
+            vec![1, 2, 0, 3],
+            vec![0, 3, 2, 1],
+        ];
+        let women = vec![
+            vec![0, 2, 1, 3],
+            vec![2, 3, 0, 1],
+            vec![3, 1, 2, 0],
+            vec![2, 1, 0, 3],
+        ];
+        let matching = stable_marriage(&men, &women).unwrap();
+        assert_eq!(matching, [0, 2, 1, 3]);
+        assert!(is_stable(&matching, &men, &women));
+        // when the women propose, they get the other stable matching, worse for the men 1 and 3
+        let matching = stable_marriage(&women, &men).unwrap();
+        assert_eq!(matching, [0, 2, 3, 1]);
+        assert!(is_stable(&matching, &women, &men));
+        assert!(is_stable(&[0, 3, 1, 2], &men, &women));
+        // the man 0 and the woman 1 prefer each other
+        assert!(!is_stable(&[2, 0, 1, 3], &men, &women));
+    }
+
+    #[test]
+    fn random_instances_are_stable() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1230);
+        for n in [0, 1, 2, 5, 20, 100] {
+            let (a, b) = (random_prefs(&mut rng, n), random_prefs(&mut rng, n));
+            let matching = stable_marriage(&a, &b).unwrap();
+            assert!(is_stable(&matching, &a, &b));
+        }
+    }
+
+    #[test]
+    fn proposers_get_their_best_stable_partner() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1231);
+        for _ in 0..30 {
+            let n = 6;
+            let (a, b) = (random_prefs(&mut rng, n), random_prefs(&mut rng, n));
+            let matching = stable_marriage(&a, &b).unwrap();
+            let rank_a = ranks(&a);
+            let rank_b = ranks(&b);
+            let stable = all_stable(&a, &b);
+            assert!(stable.contains(&matching));
+            for other in &stable {
+                for proposer in 0..n {
+                    assert!(
+                        rank_a[proposer][matching[proposer]] <= rank_a[proposer][other[proposer]]
+                    );
+                    // and every acceptor gets its worst
+                    let acceptor = other[proposer];
+                    let given = matching.iter().position(|&b| b == acceptor).unwrap();
+                    assert!(rank_b[acceptor][given] >= rank_b[acceptor][proposer]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_preferences() {
+        let full = vec![vec![0, 1], vec![1, 0]];
+        assert_eq!(
+            stable_marriage(&full, &full[..1]),
+            Err(AlgorithmError::DimensionMismatch {
+                argument: "acceptor_prefs",
+                expected: 2,
+                found: 1
+            })
+        );
+        for wrong in [
+            vec![vec![0, 1], vec![1]],
+            vec![vec![0, 1], vec![1, 1]],
+            vec![vec![0, 2], vec![1, 0]],
+        ] {
+            assert!(stable_marriage(&wrong, &full).is_err());
+            assert!(stable_marriage(&full, &wrong).is_err());
+            assert!(!is_stable(&[0, 1], &wrong, &full));
+        }
+        assert!(!is_stable(&[0, 0], &full, &full));
+        assert!(!is_stable(&[0, 2], &full, &full));
+        assert!(!is_stable(&[0], &full, &full));
+    }
+}
@@ -0,0 +1,201 @@
+use crate::math::mod_pow;
+use rand::Rng;
+use std::collections::BTreeMap;
+
+type Graph<V, E> = BTreeMap<V, BTreeMap<V, E>>;
+
+fn mul_mod(a: u64, b: u64, modulus: u64) -> u64 {
+    (a as u128 * b as u128 % modulus as u128) as u64
+}
+
+/// The determinant of a square matrix modulo a prime, by Gaussian elimination in O(n^3)
+fn determinant_mod(mut matrix: Vec<Vec<u64>>, modulus: u64) -> u64 {
+    let n = matrix.len();
+    let mut determinant = 1 % modulus;
+    for column in 0..n {
+        let Some(pivot) = (column..n).find(|&row| matrix[row][column] != 0) else {
+            return 0;
+        };
+        if pivot != column {
+            matrix.swap(pivot, column);
+            determinant = (modulus - determinant) % modulus;
+        }
+        determinant = mul_mod(determinant, matrix[column][column], modulus);
+        // the prime modulus makes every nonzero pivot invertible, by Fermat's little theorem
+        let inverse = mod_pow(matrix[column][column], modulus - 2, modulus);
+        let (above, below) = matrix.split_at_mut(column + 1);
+        let pivot_row = &above[column];
+        for row in below {
+            let factor = mul_mod(row[column], inverse, modulus);
+            if factor == 0 {
+                continue;
+            }
+            for (item, &pivot_item) in row.iter_mut().zip(pivot_row).skip(column) {
+                let subtracted = mul_mod(factor, pivot_item, modulus);
+                *item = (*item + modulus - subtracted) % modulus;
+            }
+        }
+    }
+    determinant
+}
+
+/// Tells whether an undirected graph has a perfect matching, a set of edges covering every
+/// vertex exactly once, with the Tutte matrix and the Schwartz-Zippel lemma.
+/// <https://en.wikipedia.org/wiki/Tutte_matrix>
+///
+/// The Tutte matrix has a variable `x_uv` at `(u, v)` and `-x_uv` at `(v, u)` for every edge
+/// `{u, v}`, and zeros elsewhere. Its determinant, a polynomial of degree n, is nonzero exactly
+/// when the graph has a perfect matching. Every round gives the variables random values modulo
+/// the prime `modulus` and computes the determinant there, in O(n^3): a nonzero value proves
+/// that there is a perfect matching, while a zero one may be a root of a nonzero determinant,
+/// with a probability at most `n / modulus` by the Schwartz-Zippel lemma. A false answer is
+/// then wrong with a probability at most `(n / modulus)^rounds`, below `2^-rounds` as soon as
+/// `modulus >= 2n`.
+/// <https://en.wikipedia.org/wiki/Schwartz%E2%80%93Zippel_lemma>
+///
+/// The graph maps every vertex to its neighbors, in both directions, like the other algorithms
+/// of the module. Loops are ignored, and so are the weights.
+///
+/// # Panics
+///
+/// Panics if `modulus` is below 2. It must also be a prime, which is not checked.
+pub fn has_perfect_matching_randomized<R: Rng + ?Sized>(
+    graph: &Graph<usize, u64>,
+    modulus: u64,
+    rng: &mut R,
+    rounds: usize,
+) -> bool {
+    assert!(modulus >= 2, "the modulus must be a prime, got {modulus}");
+    let vertices: Vec<usize> = graph.keys().copied().collect();
+    let n = vertices.len();
+    if n % 2 == 1 {
+        return false;
+    }
+    let index: BTreeMap<usize, usize> = vertices.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+    // the edges {i, j} with i < j, once each whatever the directions listed
+    let mut edges = vec![];
+    for (&u, neighbors) in graph {
+        for &v in neighbors.keys() {
+            if let Some(&j) = index.get(&v) {
+                let i = index[&u];
+                if i != j {
+                    edges.push((i.min(j), i.max(j)));
+                }
+            }
+        }
+    }
+    edges.sort_unstable();
+    edges.dedup();
+    (0..rounds).any(|_| {
+        let mut tutte = vec![vec![0; n]; n];
+        for &(i, j) in &edges {
+            let x = rng.gen_range(1..modulus);
+            tutte[i][j] = x;
+            tutte[j][i] = modulus - x;
+        }
+        determinant_mod(tutte, modulus) != 0
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{complete_graph, cycle_graph, erdos_renyi, star_graph};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    const PRIME: u64 = 1_000_000_007;
+
+    /// Tries every way of matching the first unmatched vertex
+    fn brute_force(graph: &Graph<usize, u64>, matched: &mut Vec<bool>) -> bool {
+        let Some(u) = matched.iter().position(|&m| !m) else {
+            return true;
+        };
+        matched[u] = true;
+        let found = graph[&u].keys().any(|&v| {
+            if v == u || matched[v] {
+                return false;
+            }
+            matched[v] = true;
+            let found = brute_force(graph, matched);
+            matched[v] = false;
+            found
+        });
+        matched[u] = false;
+        found
+    }
+
+    #[test]
+    fn complete_graphs() {
+        let mut rng = ChaCha8Rng::seed_from_u64(151);
+        for n in 0..12 {
+            let answer = has_perfect_matching_randomized(&complete_graph(n), PRIME, &mut rng, 5);
+            assert_eq!(answer, n % 2 == 0, "K_{n}");
+        }
+    }
+
+    #[test]
+    fn odd_numbers_of_vertices() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1510);
+        for n in [1, 3, 5, 9] {
+            for graph in [complete_graph(n), cycle_graph(n), star_graph(n)] {
+                assert!(!has_perfect_matching_randomized(&graph, PRIME, &mut rng, 5));
+            }
+        }
+    }
+
+    #[test]
+    fn small_graphs() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1511);
+        let mut answer = |graph: &Graph<usize, u64>, rounds: usize| {
+            has_perfect_matching_randomized(graph, PRIME, &mut rng, rounds)
+        };
+        // even cycles have two perfect matchings, while a star only matches its center once
+        assert!(answer(&cycle_graph(6), 5));
+        assert!(!answer(&star_graph(4), 5));
+        // two triangles joined by an edge, given in one direction only and with a loop
+        let mut graph: Graph<usize, u64> = (0..6).map(|v| (v, BTreeMap::new())).collect();
+        let edges = [
+            (0, 1),
+            (1, 2),
+            (2, 0),
+            (3, 4),
+            (4, 5),
+            (5, 3),
+            (2, 3),
+            (4, 4),
+        ];
+        for (u, v) in edges {
+            graph.get_mut(&u).unwrap().insert(v, 1);
+        }
+        assert!(answer(&graph, 5));
+        graph.get_mut(&2).unwrap().remove(&3);
+        assert!(!answer(&graph, 5));
+        // without any round, no matching is ever proven
+        assert!(!answer(&complete_graph(4), 0));
+    }
+
+    #[test]
+    fn agrees_with_brute_force() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1512);
+        for _ in 0..100 {
+            let n = 2 * rng.gen_range(1..6);
+            let graph = erdos_renyi(n, rng.gen_range(0.1..0.6), &mut rng).unwrap();
+            let expected = brute_force(&graph, &mut vec![false; n]);
+            // a small prime makes mistakes likely in a single round, but not in twenty
+            let answer = has_perfect_matching_randomized(&graph, 101, &mut rng, 20);
+            assert_eq!(answer, expected);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the modulus must be a prime, got 1")]
+    fn modulus_too_small() {
+        has_perfect_matching_randomized(
+            &complete_graph(2),
+            1,
+            &mut ChaCha8Rng::seed_from_u64(0),
+            1,
+        );
+    }
+}
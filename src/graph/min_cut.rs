@@ -0,0 +1,316 @@
+//! Global minimum cuts of undirected weighted graphs: the least total weight of edges whose
+//! removal splits the vertices in two non-empty sides, without fixed source and sink.
+//!
+//! The vertices are numbered from 0 to n - 1, and every edge (u, v, w) joins u and v with the
+//! weight w. Parallel edges add up, and loops are ignored.
+
+use super::DisjointSetUnion;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+fn check_vertices(n: usize, edges: &[(usize, usize, u64)]) {
+    assert!(n >= 2, "a cut needs at least two vertices, got {n}");
+    for &(u, v, _) in edges {
+        assert!(
+            u < n && v < n,
+            "the edge ({u}, {v}) is out of the {n} vertices"
+        );
+    }
+}
+
+/// The total weight of the edges with exactly one end in `side`
+fn cut_weight(n: usize, edges: &[(usize, usize, u64)], side: &[usize]) -> u64 {
+    let mut inside = vec![false; n];
+    for &v in side {
+        inside[v] = true;
+    }
+    edges
+        .iter()
+        .filter(|&&(u, v, _)| inside[u] != inside[v])
+        .map(|&(_, _, w)| w)
+        .sum()
+}
+
+/// Returns the weight of a minimum cut and the sorted vertices of one of its sides, in O(V^3).
+/// <https://en.wikipedia.org/wiki/Stoer%E2%80%93Wagner_algorithm>
+///
+/// Every phase adds the vertices one by one to a growing set, always the vertex most tightly
+/// connected to the set. The last vertex `t` and the one before it `s` are such that the edges
+/// of `t` are a minimum cut between `s` and `t`, so that the minimum cut either separates them
+/// and is at most this cut of the phase, or keeps them together, and they are merged for the
+/// next phases. A disconnected graph has a cut of weight 0, between a component and the rest.
+///
+/// # Panics
+///
+/// Panics if there are less than two vertices, or if an edge has an end out of them.
+pub fn stoer_wagner(n: usize, edges: &[(usize, usize, u64)]) -> (u64, Vec<usize>) {
+    check_vertices(n, edges);
+    let mut weights = vec![vec![0; n]; n];
+    for &(u, v, w) in edges {
+        if u != v {
+            weights[u][v] += w;
+            weights[v][u] += w;
+        }
+    }
+    // the original vertices merged into every vertex, and the vertices not merged yet
+    let mut groups: Vec<Vec<usize>> = (0..n).map(|v| vec![v]).collect();
+    let mut active: Vec<usize> = (0..n).collect();
+    let mut best: Option<(u64, Vec<usize>)> = None;
+    while active.len() > 1 {
+        let mut added = vec![false; n];
+        let mut connection = vec![0; n];
+        let (mut s, mut t) = (active[0], active[0]);
+        for _ in 0..active.len() {
+            let next = *active
+                .iter()
+                .filter(|&&v| !added[v])
+                .max_by_key(|&&v| connection[v])
+                .unwrap();
+            added[next] = true;
+            s = t;
+            t = next;
+            for &v in &active {
+                connection[v] += weights[next][v];
+            }
+        }
+        if best
+            .as_ref()
+            .is_none_or(|(weight, _)| connection[t] < *weight)
+        {
+            best = Some((connection[t], groups[t].clone()));
+        }
+        let merged = std::mem::take(&mut groups[t]);
+        groups[s].extend(merged);
+        for &v in &active {
+            weights[s][v] += weights[t][v];
+            weights[v][s] = weights[s][v];
+        }
+        weights[s][s] = 0;
+        active.retain(|&v| v != t);
+    }
+    let (weight, mut side) = best.unwrap();
+    side.sort_unstable();
+    (weight, side)
+}
+
+/// Returns the lightest cut found by `iterations` random contractions, with the sorted
+/// vertices of one of its sides.
+/// <https://en.wikipedia.org/wiki/Karger%27s_algorithm>
+///
+/// A contraction merges the ends of random edges, drawn with probabilities proportional to
+/// their weights, until two groups of vertices remain. It finds a given minimum cut with a
+/// probability of at least `2 / (n (n - 1))`, when none of its edges is drawn, so that
+/// `n^2 ln(n)` iterations miss every minimum cut with a probability of about `1 / n`. Drawing
+/// every edge with the key `-ln(u) / w` for a uniform `u` and contracting them by increasing
+/// keys, as Kruskal's algorithm does with a union-find, draws them in the same order, in
+/// O(E log(E)) per iteration.
+///
+/// # Panics
+///
+/// Panics if there are less than two vertices, if an edge has an end out of them, or if
+/// `iterations` is 0.
+pub fn karger(
+    n: usize,
+    edges: &[(usize, usize, u64)],
+    iterations: usize,
+    seed: u64,
+) -> (u64, Vec<usize>) {
+    check_vertices(n, edges);
+    assert!(iterations > 0, "at least one iteration is needed");
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let edges: Vec<(usize, usize, u64)> = edges
+        .iter()
+        .copied()
+        .filter(|&(u, v, w)| u != v && w > 0)
+        .collect();
+    let mut best: Option<(u64, Vec<usize>)> = None;
+    for _ in 0..iterations {
+        let mut keyed: Vec<(f64, usize, usize)> = edges
+            .iter()
+            .map(|&(u, v, w)| (-(1.0 - rng.gen::<f64>()).ln() / w as f64, u, v))
+            .collect();
+        keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut groups = DisjointSetUnion::new(n);
+        let mut remaining = n;
+        for &(_, u, v) in &keyed {
+            if remaining == 2 {
+                break;
+            }
+            if groups.merge(u, v) != usize::MAX {
+                remaining -= 1;
+            }
+        }
+        // the group of the vertex 0 against the rest, which also holds for a disconnected
+        // graph with more than two groups left
+        let root = groups.find_set(0);
+        let side: Vec<usize> = (0..n).filter(|&v| groups.find_set(v) == root).collect();
+        let weight = cut_weight(n, &edges, &side);
+        if best.as_ref().is_none_or(|(best, _)| weight < *best) {
+            best = Some((weight, side));
+        }
+    }
+    best.unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The minimum cut over the 2^(n - 1) - 1 sides that hold the vertex 0.
+    fn brute_force(n: usize, edges: &[(usize, usize, u64)]) -> u64 {
+        (1..1usize << (n - 1))
+            .map(|mask| {
+                let side: Vec<usize> = (0..n)
+                    .filter(|&v| v == 0 || mask >> (v - 1) & 1 == 0)
+                    .collect();
+                cut_weight(n, edges, &side)
+            })
+            .min()
+            .unwrap()
+    }
+
+    fn check_side(n: usize, edges: &[(usize, usize, u64)], (weight, side): &(u64, Vec<usize>)) {
+        assert!(!side.is_empty() && side.len() < n);
+        assert!(side.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(cut_weight(n, edges, side), *weight);
+    }
+
+    fn random_graph(rng: &mut ChaCha8Rng, n: usize) -> Vec<(usize, usize, u64)> {
+        let density = rng.gen_range(0.2..1.0);
+        let mut edges = vec![];
+        for u in 0..n {
+            for v in u + 1..n {
+                if rng.gen_bool(density) {
+                    edges.push((u, v, rng.gen_range(0..10)));
+                }
+            }
+        }
+        edges
+    }
+
+    #[test]
+    fn small_graphs() {
+        // two triangles joined by a light edge
+        let edges = [
+            (0, 1, 3),
+            (1, 2, 3),
+            (0, 2, 3),
+            (3, 4, 3),
+            (4, 5, 3),
+            (3, 5, 3),
+            (2, 3, 1),
+        ];
+        let cut = stoer_wagner(6, &edges);
+        assert!(cut == (1, vec![0, 1, 2]) || cut == (1, vec![3, 4, 5]));
+        // the side of karger holds the vertex 0
+        assert_eq!(karger(6, &edges, 100, 1301), (1, vec![0, 1, 2]));
+        // the lightest cut of a cycle cuts its two lightest edges
+        let cycle = [(0, 1, 5), (1, 2, 2), (2, 3, 5), (3, 0, 3)];
+        let cut = stoer_wagner(4, &cycle);
+        assert!(cut == (5, vec![0, 1]) || cut == (5, vec![2, 3]));
+        // or a single vertex, in the example of Stoer and Wagner
+        let example = [
+            (0, 1, 2),
+            (0, 4, 3),
+            (1, 2, 3),
+            (1, 4, 2),
+            (1, 5, 2),
+            (2, 3, 4),
+            (2, 6, 2),
+            (3, 6, 2),
+            (3, 7, 2),
+            (4, 5, 3),
+            (5, 6, 1),
+            (6, 7, 3),
+        ];
+        let cut = stoer_wagner(8, &example);
+        assert_eq!(cut.0, 4);
+        check_side(8, &example, &cut);
+        assert_eq!(stoer_wagner(2, &[(0, 1, 7)]).0, 7);
+    }
+
+    #[test]
+    fn unit_weights() {
+        let complete = [
+            (0, 1, 1),
+            (0, 2, 1),
+            (0, 3, 1),
+            (1, 2, 1),
+            (1, 3, 1),
+            (2, 3, 1),
+        ];
+        let cut = stoer_wagner(4, &complete);
+        assert_eq!(cut.0, 3);
+        check_side(4, &complete, &cut);
+        let cycle: Vec<(usize, usize, u64)> = (0..7).map(|v| (v, (v + 1) % 7, 1)).collect();
+        let cut = stoer_wagner(7, &cycle);
+        assert_eq!(cut.0, 2);
+        check_side(7, &cycle, &cut);
+        // the vertex 4 hangs from a clique of 4 by two edges, the least degree of the graph
+        let mut pendant = complete.to_vec();
+        pendant.extend([(4, 0, 1), (4, 1, 1)]);
+        let cut = stoer_wagner(5, &pendant);
+        assert!(cut == (2, vec![4]) || cut == (2, vec![0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn parallel_edges_and_loops() {
+        // the edges between 0 and 1 weigh 5 together, and the loop nothing
+        let edges = [(0, 1, 2), (1, 0, 3), (1, 2, 4), (2, 2, 100), (0, 2, 1)];
+        let cut = stoer_wagner(3, &edges);
+        assert!(cut == (5, vec![2]) || cut == (5, vec![0, 1]));
+        assert_eq!(karger(3, &edges, 50, 1302), (5, vec![0, 1]));
+        let edges = [(0, 1, 1), (0, 1, 1), (0, 1, 1), (1, 2, 2)];
+        let cut = stoer_wagner(3, &edges);
+        assert!(cut == (2, vec![2]) || cut == (2, vec![0, 1]));
+        assert_eq!(karger(3, &edges, 50, 1303), (2, vec![0, 1]));
+    }
+
+    #[test]
+    fn disconnected_graphs() {
+        let edges = [(0, 1, 5), (2, 3, 5)];
+        let cut = stoer_wagner(4, &edges);
+        assert_eq!(cut.0, 0);
+        check_side(4, &edges, &cut);
+        let cut = karger(4, &edges, 1, 1304);
+        assert_eq!(cut.0, 0);
+        check_side(4, &edges, &cut);
+        // isolated vertices, and weights of 0
+        for cut in [
+            stoer_wagner(5, &[(0, 1, 0)]),
+            karger(5, &[(0, 1, 0)], 3, 1305),
+        ] {
+            assert_eq!(cut.0, 0);
+            check_side(5, &[], &cut);
+        }
+    }
+
+    #[test]
+    fn agree_with_brute_force() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1306);
+        for round in 0..60 {
+            let n = rng.gen_range(2..=12);
+            let edges = random_graph(&mut rng, n);
+            let expected = brute_force(n, &edges);
+            let cut = stoer_wagner(n, &edges);
+            assert_eq!(cut.0, expected);
+            check_side(n, &edges, &cut);
+            // n^2 ln(n) iterations and some more
+            let cut = karger(n, &edges, 1500, round);
+            assert_eq!(cut.0, expected);
+            check_side(n, &edges, &cut);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "a cut needs at least two vertices, got 1")]
+    fn single_vertex() {
+        stoer_wagner(1, &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "the edge (0, 3) is out of the 3 vertices")]
+    fn edge_out_of_range() {
+        karger(3, &[(0, 3, 1)], 1, 0);
+    }
+}
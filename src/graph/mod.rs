@@ -1,51 +1,80 @@
+mod adjacency_matrix;
 mod astar;
 mod bellman_ford;
 mod bipartite_matching;
 mod breadth_first_search;
 mod centroid_decomposition;
+mod csr;
 mod depth_first_search;
 mod depth_first_search_tic_tac_toe;
 mod dijkstra;
 mod dinic_maxflow;
 mod disjoint_set_union;
+mod edmonds_karp;
 mod eulerian_path;
 mod floyd_warshall;
 mod ford_fulkerson;
+mod generators;
+mod graph_coloring;
 mod graph_enumeration;
+mod grid;
 mod heavy_light_decomposition;
+mod hopcroft_karp;
+mod johnsons;
 mod kosaraju;
+mod lca;
 mod lee_breadth_first_search;
 mod lowest_common_ancestor;
+pub mod matching;
+mod min_cut;
 mod minimum_spanning_tree;
+mod page_rank;
+mod planarity;
 mod prim;
 mod prufer_code;
 mod strongly_connected_components;
 mod tarjans_ssc;
 mod topological_sort;
+mod traversal;
 mod two_satisfiability;
 
+pub use self::adjacency_matrix::AdjacencyMatrix;
 pub use self::astar::astar;
 pub use self::bellman_ford::bellman_ford;
 pub use self::bipartite_matching::BipartiteMatching;
 pub use self::breadth_first_search::breadth_first_search;
 pub use self::centroid_decomposition::CentroidDecomposition;
+pub use self::csr::CsrGraph;
 pub use self::depth_first_search::depth_first_search;
 pub use self::depth_first_search_tic_tac_toe::minimax;
 pub use self::dijkstra::dijkstra;
 pub use self::dinic_maxflow::DinicMaxFlow;
 pub use self::disjoint_set_union::DisjointSetUnion;
+pub use self::edmonds_karp::{edmonds_karp, FlowGraph};
 pub use self::eulerian_path::EulerianPath;
 pub use self::floyd_warshall::floyd_warshall;
 pub use self::ford_fulkerson::ford_fulkerson;
+pub use self::generators::{
+    barabasi_albert, complete_graph, cycle_graph, erdos_renyi, randomize_weights, star_graph,
+};
+pub use self::graph_coloring::{find_odd_cycle, greedy_coloring, is_bipartite};
 pub use self::graph_enumeration::enumerate_graph;
+pub use self::grid::GridGraph;
 pub use self::heavy_light_decomposition::HeavyLightDecomposition;
+pub use self::hopcroft_karp::{hopcroft_karp, Matching};
+pub use self::johnsons::{johnsons_algorithm, NegativeCycleError};
 pub use self::kosaraju::kosaraju;
+pub use self::lca::{EulerTourLca, LcaTree};
 pub use self::lee_breadth_first_search::lee;
 pub use self::lowest_common_ancestor::{LowestCommonAncestorOffline, LowestCommonAncestorOnline};
+pub use self::min_cut::{karger, stoer_wagner};
 pub use self::minimum_spanning_tree::kruskal;
-pub use self::prim::{prim, prim_with_start};
+pub use self::page_rank::page_rank;
+pub use self::planarity::{is_planar, is_planar_kuratowski, KuratowskiKind, KuratowskiSubgraph};
+pub use self::prim::{prim, prim_eager_with_start, prim_with_start};
 pub use self::prufer_code::{prufer_decode, prufer_encode};
 pub use self::strongly_connected_components::StronglyConnectedComponents;
 pub use self::tarjans_ssc::tarjan_scc;
 pub use self::topological_sort::topological_sort;
+pub use self::traversal::{bfs_iter, dfs_iter, Bfs, Dfs};
 pub use self::two_satisfiability::solve_two_satisfiability;
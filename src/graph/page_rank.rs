@@ -0,0 +1,147 @@
+use crate::data_structures::{DirectedGraph, Graph};
+use crate::error::{check_positive, AlgorithmError, AlgorithmResult};
+use std::collections::HashMap;
+
+/// Returns the PageRank of the nodes of a directed graph, with the power method.
+/// <https://en.wikipedia.org/wiki/PageRank>
+///
+/// The rank of a node is the probability of being on it for a surfer who follows an edge out of
+/// the current node with the probability `damping`, picked uniformly, and jumps to a random node
+/// otherwise. A node without edges out, a dangling node, leads to every node with the same
+/// probability. Parallel edges count as many times as they appear, and weights are ignored.
+///
+/// From the uniform distribution, every iteration takes one step of the surfer, in O(V + E),
+/// until no rank changes by `tolerance` or more, or after `max_iterations`. The ranks are in the
+/// order of the sorted node names, and always add up to 1.
+pub fn page_rank(
+    graph: &DirectedGraph,
+    damping: f64,
+    max_iterations: usize,
+    tolerance: f64,
+) -> AlgorithmResult<Vec<f64>> {
+    if !(0.0..=1.0).contains(&damping) {
+        return Err(AlgorithmError::InvalidParameter {
+            argument: "damping",
+            reason: format!("must be between 0 and 1, got {damping}"),
+        });
+    }
+    check_positive("tolerance", tolerance)?;
+
+    let mut nodes: Vec<&String> = graph.nodes().into_iter().collect();
+    nodes.sort();
+    let index: HashMap<&String, usize> = nodes.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+    let out_edges: Vec<Vec<usize>> = nodes
+        .iter()
+        .map(|&node| {
+            graph.adjacency_table()[node]
+                .iter()
+                .map(|(to, _)| index[to])
+                .collect()
+        })
+        .collect();
+
+    let n = nodes.len();
+    let mut ranks = vec![1.0 / n as f64; n];
+    for _ in 0..max_iterations {
+        let dangling: f64 = (0..n)
+            .filter(|&v| out_edges[v].is_empty())
+            .map(|v| ranks[v])
+            .sum();
+        let base = (1.0 - damping + damping * dangling) / n as f64;
+        let mut next = vec![base; n];
+        for (v, edges) in out_edges.iter().enumerate() {
+            let share = damping * ranks[v] / edges.len() as f64;
+            for &to in edges {
+                next[to] += share;
+            }
+        }
+        let change = next
+            .iter()
+            .zip(&ranks)
+            .map(|(new, old)| (new - old).abs())
+            .fold(0.0, f64::max);
+        ranks = next;
+        if change < tolerance {
+            break;
+        }
+    }
+    Ok(ranks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(edges: &[(&str, &str)]) -> DirectedGraph {
+        let mut graph = DirectedGraph::new();
+        for &(from, to) in edges {
+            graph.add_edge((from, to, 1));
+        }
+        graph
+    }
+
+    fn assert_close(ranks: &[f64], expected: &[f64]) {
+        assert_eq!(ranks.len(), expected.len());
+        for (rank, expected) in ranks.iter().zip(expected) {
+            assert!((rank - expected).abs() < 1e-6, "{ranks:?} {expected:?}");
+        }
+    }
+
+    #[test]
+    fn small_graph() {
+        let graph = graph(&[("a", "b"), ("a", "c"), ("b", "c"), ("c", "a")]);
+        // the solution of r = 0.05 + 0.85 * M r for the transitions M of the graph
+        let ranks = page_rank(&graph, 0.85, 100, 1e-10).unwrap();
+        assert_close(&ranks, &[0.387_789_71, 0.214_810_63, 0.397_399_66]);
+        assert!((ranks.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn dangling_nodes() {
+        // b has no edges out, and gives its rank to both nodes
+        let graph = graph(&[("a", "b")]);
+        let ranks = page_rank(&graph, 0.85, 100, 1e-10).unwrap();
+        assert_close(&ranks, &[20.0 / 57.0, 37.0 / 57.0]);
+        // with all nodes dangling, nothing favors any of them
+        let mut isolated = DirectedGraph::new();
+        for node in ["x", "y", "z", "w"] {
+            isolated.add_node(node);
+        }
+        assert_close(&page_rank(&isolated, 0.85, 100, 1e-10).unwrap(), &[0.25; 4]);
+    }
+
+    #[test]
+    fn popular_nodes() {
+        let mut graph = graph(&[
+            ("a", "hub"),
+            ("b", "hub"),
+            ("c", "hub"),
+            ("d", "hub"),
+            ("hub", "a"),
+        ]);
+        graph.add_node("lonely");
+        let ranks = page_rank(&graph, 0.85, 1000, 1e-12).unwrap();
+        // a, b, c, d, hub, lonely
+        assert!(ranks[4] > ranks[5]);
+        assert!(ranks[4] > ranks[0] && ranks[0] > ranks[1]);
+        assert!((ranks[1] - ranks[2]).abs() < 1e-12);
+        assert!((ranks.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn iterations_and_parameters() {
+        let graph = graph(&[("a", "b"), ("b", "c"), ("c", "a"), ("c", "b")]);
+        // no iteration leaves the uniform distribution
+        assert_close(&page_rank(&graph, 0.85, 0, 1e-10).unwrap(), &[1.0 / 3.0; 3]);
+        // without damping, the surfer always jumps
+        assert_close(&page_rank(&graph, 0.0, 10, 1e-10).unwrap(), &[1.0 / 3.0; 3]);
+        let rough = page_rank(&graph, 0.85, 3, 1e-10).unwrap();
+        assert!((rough.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(page_rank(&DirectedGraph::new(), 0.85, 10, 1e-6)
+            .unwrap()
+            .is_empty());
+        assert!(page_rank(&graph, 1.5, 10, 1e-6).is_err());
+        assert!(page_rank(&graph, f64::NAN, 10, 1e-6).is_err());
+        assert!(page_rank(&graph, 0.85, 10, 0.0).is_err());
+    }
+}
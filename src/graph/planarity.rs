@@ -0,0 +1,552 @@
+use std::collections::BTreeMap;
+
+type Graph<V, E> = BTreeMap<V, BTreeMap<V, E>>;
+
+/// The two kinds of minimal non-planar graphs of Kuratowski's theorem
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KuratowskiKind {
+    /// The complete graph on five vertices
+    K5,
+    /// The complete bipartite graph on two sets of three vertices
+    K33,
+}
+
+/// A subgraph which is a subdivision of K5 or K3,3, and proves that a graph is not planar
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KuratowskiSubgraph<V> {
+    pub kind: KuratowskiKind,
+    /// The vertices of degree more than 2 in the subgraph, those of K5 or K3,3
+    pub branch_vertices: Vec<V>,
+    /// The edges of the subgraph, the edges of K5 or K3,3 being paths through the other
+    /// vertices
+    pub edges: Vec<(V, V)>,
+}
+
+/// The vertices and the edges of an undirected graph, numbered from 0, without self-loops
+fn numbered<V: Ord + Copy, E>(graph: &Graph<V, E>) -> (Vec<V>, Vec<(usize, usize)>) {
+    let mut vertices: Vec<V> = graph.keys().copied().collect();
+    vertices.extend(graph.values().flat_map(|edges| edges.keys().copied()));
+    vertices.sort();
+    vertices.dedup();
+    let index = |v: &V| vertices.binary_search(v).unwrap();
+    let mut edges: Vec<(usize, usize)> = graph
+        .iter()
+        .flat_map(|(u, edges)| edges.keys().map(move |v| (u, v)))
+        .filter(|(u, v)| u != v)
+        .map(|(u, v)| (index(u).min(index(v)), index(u).max(index(v))))
+        .collect();
+    edges.sort();
+    edges.dedup();
+    (vertices, edges)
+}
+
+/// An interval of return edges on one side, from the lowest one to the highest one
+#[derive(Clone, Copy, Debug, Default)]
+struct Interval {
+    low: Option<usize>,
+    high: Option<usize>,
+}
+
+impl Interval {
+    fn is_empty(&self) -> bool {
+        self.low.is_none() && self.high.is_none()
+    }
+}
+
+/// Return edges that must be on opposite sides
+#[derive(Clone, Copy, Debug, Default)]
+struct ConflictPair {
+    left: Interval,
+    right: Interval,
+}
+
+impl ConflictPair {
+    fn swap(&mut self) {
+        std::mem::swap(&mut self.left, &mut self.right);
+    }
+}
+
+/// The state of the left-right planarity test, every edge being oriented away from the root
+/// of the depth-first search for tree edges, and towards it for back edges
+struct LeftRight {
+    adjacency: Vec<Vec<(usize, usize)>>,
+    oriented: Vec<bool>,
+    target: Vec<usize>,
+    source: Vec<usize>,
+    height: Vec<Option<usize>>,
+    parent_edge: Vec<Option<usize>>,
+    /// The lowest and the second lowest heights reached by the return edges of every edge
+    lowpt: Vec<usize>,
+    lowpt2: Vec<usize>,
+    nesting_depth: Vec<usize>,
+    /// The outgoing edges of every vertex, sorted by nesting depth
+    outgoing: Vec<Vec<usize>>,
+    stack: Vec<ConflictPair>,
+    stack_bottom: Vec<usize>,
+    lowpt_edge: Vec<usize>,
+    reference: Vec<Option<usize>>,
+}
+
+impl LeftRight {
+    fn new(n: usize, edges: &[(usize, usize)]) -> Self {
+        let mut adjacency = vec![vec![]; n];
+        for (e, &(u, v)) in edges.iter().enumerate() {
+            adjacency[u].push((v, e));
+            adjacency[v].push((u, e));
+        }
+        let m = edges.len();
+        LeftRight {
+            adjacency,
+            oriented: vec![false; m],
+            target: vec![0; m],
+            source: vec![0; m],
+            height: vec![None; n],
+            parent_edge: vec![None; n],
+            lowpt: vec![0; m],
+            lowpt2: vec![0; m],
+            nesting_depth: vec![0; m],
+            outgoing: vec![vec![]; n],
+            stack: vec![],
+            stack_bottom: vec![0; m],
+            lowpt_edge: vec![0; m],
+            reference: vec![None; m],
+        }
+    }
+
+    fn run(mut self) -> bool {
+        let n = self.height.len();
+        let mut roots = vec![];
+        for v in 0..n {
+            if self.height[v].is_none() {
+                self.height[v] = Some(0);
+                roots.push(v);
+                self.orient(v);
+            }
+        }
+        for v in 0..n {
+            let mut outgoing = std::mem::take(&mut self.outgoing[v]);
+            outgoing.sort_by_key(|&e| self.nesting_depth[e]);
+            self.outgoing[v] = outgoing;
+        }
+        roots.into_iter().all(|root| self.test(root))
+    }
+
+    /// Orients the edges by a depth-first search from `v`, computing their low points and
+    /// nesting depths.
+    fn orient(&mut self, v: usize) {
+        let height = self.height[v].unwrap();
+        let parent = self.parent_edge[v];
+        for i in 0..self.adjacency[v].len() {
+            let (w, e) = self.adjacency[v][i];
+            if self.oriented[e] {
+                continue;
+            }
+            self.oriented[e] = true;
+            (self.source[e], self.target[e]) = (v, w);
+            self.outgoing[v].push(e);
+            self.lowpt[e] = height;
+            self.lowpt2[e] = height;
+            match self.height[w] {
+                None => {
+                    // a tree edge
+                    self.parent_edge[w] = Some(e);
+                    self.height[w] = Some(height + 1);
+                    self.orient(w);
+                }
+                Some(back) => self.lowpt[e] = back,
+            }
+            // the edges with return edges below their lowest one first, chordal ones after
+            self.nesting_depth[e] = 2 * self.lowpt[e] + usize::from(self.lowpt2[e] < height);
+            if let Some(p) = parent {
+                let (lowpt, lowpt2) = (self.lowpt[e], self.lowpt2[e]);
+                if lowpt < self.lowpt[p] {
+                    self.lowpt2[p] = self.lowpt[p].min(lowpt2);
+                    self.lowpt[p] = lowpt;
+                } else if lowpt > self.lowpt[p] {
+                    self.lowpt2[p] = self.lowpt2[p].min(lowpt);
+                } else {
+                    self.lowpt2[p] = self.lowpt2[p].min(lowpt2);
+                }
+            }
+        }
+    }
+
+    fn conflicting(&self, interval: &Interval, e: usize) -> bool {
+        interval
+            .high
+            .is_some_and(|high| self.lowpt[high] > self.lowpt[e])
+    }
+
+    fn lowest(&self, pair: &ConflictPair) -> usize {
+        match (pair.left.low, pair.right.low) {
+            (None, Some(right)) => self.lowpt[right],
+            (Some(left), None) => self.lowpt[left],
+            (Some(left), Some(right)) => self.lowpt[left].min(self.lowpt[right]),
+            (None, None) => unreachable!("conflict pairs are not empty"),
+        }
+    }
+
+    /// Checks that the return edges of the descendants of `v` can be placed on the two sides
+    /// of the tree, left and right.
+    fn test(&mut self, v: usize) -> bool {
+        let parent = self.parent_edge[v];
+        let height = self.height[v].unwrap();
+        for i in 0..self.outgoing[v].len() {
+            let e = self.outgoing[v][i];
+            let w = self.target[e];
+            self.stack_bottom[e] = self.stack.len();
+            if self.parent_edge[w] == Some(e) {
+                if !self.test(w) {
+                    return false;
+                }
+            } else {
+                self.lowpt_edge[e] = e;
+                self.stack.push(ConflictPair {
+                    left: Interval::default(),
+                    right: Interval {
+                        low: Some(e),
+                        high: Some(e),
+                    },
+                });
+            }
+            if self.lowpt[e] < height {
+                let p = parent.unwrap();
+                if i == 0 {
+                    self.lowpt_edge[p] = self.lowpt_edge[e];
+                } else if !self.add_constraints(e, p) {
+                    return false;
+                }
+            }
+        }
+        if let Some(p) = parent {
+            let u = self.source[p];
+            self.trim_back_edges(u);
+            // the side of the parent edge is that of its highest return edge
+            if self.lowpt[p] < self.height[u].unwrap() {
+                let top = self.stack.last().unwrap();
+                let (left, right) = (top.left.high, top.right.high);
+                self.reference[p] = match (left, right) {
+                    (Some(l), Some(r)) if self.lowpt[l] > self.lowpt[r] => left,
+                    (Some(_), None) => left,
+                    _ => right,
+                };
+            }
+        }
+        true
+    }
+
+    /// Adds the constraints of the return edges of `e`, which come after its siblings from the
+    /// same vertex, whose parent edge is `parent`.
+    fn add_constraints(&mut self, e: usize, parent: usize) -> bool {
+        let mut merged = ConflictPair::default();
+        // the return edges of e all go to the same side
+        loop {
+            let mut pair = self.stack.pop().unwrap();
+            if !pair.left.is_empty() {
+                pair.swap();
+            }
+            if !pair.left.is_empty() {
+                return false;
+            }
+            let low = pair.right.low.unwrap();
+            if self.lowpt[low] > self.lowpt[parent] {
+                if merged.right.is_empty() {
+                    merged.right.high = pair.right.high;
+                } else {
+                    self.reference[merged.right.low.unwrap()] = pair.right.high;
+                }
+                merged.right.low = pair.right.low;
+            } else {
+                self.reference[low] = Some(self.lowpt_edge[parent]);
+            }
+            if self.stack.len() == self.stack_bottom[e] {
+                break;
+            }
+        }
+        // the return edges of the siblings conflicting with those of e go to the other side
+        while let Some(top) = self.stack.last() {
+            if !self.conflicting(&top.left, e) && !self.conflicting(&top.right, e) {
+                break;
+            }
+            let mut pair = self.stack.pop().unwrap();
+            if self.conflicting(&pair.right, e) {
+                pair.swap();
+            }
+            if self.conflicting(&pair.right, e) {
+                return false;
+            }
+            if let Some(low) = merged.right.low {
+                self.reference[low] = pair.right.high;
+            }
+            if pair.right.low.is_some() {
+                merged.right.low = pair.right.low;
+            }
+            if merged.left.is_empty() {
+                merged.left.high = pair.left.high;
+            } else {
+                self.reference[merged.left.low.unwrap()] = pair.left.high;
+            }
+            merged.left.low = pair.left.low;
+        }
+        if !merged.left.is_empty() || !merged.right.is_empty() {
+            self.stack.push(merged);
+        }
+        true
+    }
+
+    /// Drops the return edges ending at `u`, which the edge from `u` being done leaves behind.
+    fn trim_back_edges(&mut self, u: usize) {
+        let height = self.height[u].unwrap();
+        while self
+            .stack
+            .last()
+            .is_some_and(|pair| self.lowest(pair) == height)
+        {
+            self.stack.pop();
+        }
+        let Some(mut pair) = self.stack.pop() else {
+            return;
+        };
+        while let Some(high) = pair.left.high.filter(|&high| self.target[high] == u) {
+            pair.left.high = self.reference[high];
+        }
+        if pair.left.high.is_none() {
+            if let Some(low) = pair.left.low.take() {
+                self.reference[low] = pair.right.low;
+            }
+        }
+        while let Some(high) = pair.right.high.filter(|&high| self.target[high] == u) {
+            pair.right.high = self.reference[high];
+        }
+        if pair.right.high.is_none() {
+            if let Some(low) = pair.right.low.take() {
+                self.reference[low] = pair.left.low;
+            }
+        }
+        self.stack.push(pair);
+    }
+}
+
+/// Tells whether the vertices `0..n` with these edges make a planar graph, with the
+/// left-right test alone.
+fn left_right_test(n: usize, edges: &[(usize, usize)]) -> bool {
+    LeftRight::new(n, edges).run()
+}
+
+/// Tells whether an undirected graph can be drawn in the plane without crossing edges, with
+/// the left-right planarity test of de Fraysseix and Rosenstiehl
+/// <https://en.wikipedia.org/wiki/Left-right_planarity_test>
+///
+/// A first depth-first search orients the edges, away from the root for the tree edges and
+/// towards it for the back edges, and computes the lowest heights their return edges reach. A
+/// graph is planar exactly when the back edges can be put on the left or the right of the tree,
+/// so that two return edges of the same side are nested. A second search runs through the
+/// edges of every vertex, those returning lowest first, keeping a stack of conflict pairs:
+/// intervals of return edges on the left, that must be on the other side from those of the
+/// interval on the right. The return edges of a new child all go to one side, and those of the
+/// earlier children conflicting with them to the other one. A pair with return edges on both
+/// sides conflicting with the same ones means that the graph is not planar, and the return
+/// edges ending at a vertex are trimmed once the search goes back up to it. Both searches
+/// take O(V + E), and a graph with more than `3 V - 6` edges is not planar by Euler's formula.
+/// <https://arxiv.org/abs/0911.0781>
+///
+/// The directions of the edges, their weights and the self-loops do not change the answer.
+pub fn is_planar<V: Ord + Copy, E>(graph: &Graph<V, E>) -> bool {
+    let (vertices, edges) = numbered(graph);
+    let n = vertices.len();
+    if n >= 3 && edges.len() > 3 * n - 6 {
+        return false;
+    }
+    left_right_test(n, &edges)
+}
+
+/// Returns a subdivision of K5 or K3,3 in an undirected graph that is not planar, as a proof of
+/// Kuratowski's theorem, or `None` for a planar graph
+/// <https://en.wikipedia.org/wiki/Kuratowski%27s_theorem>
+///
+/// Every edge is removed in turn, and put back if the graph left is planar, so that the edges
+/// kept make a non-planar graph that loses its non-planarity without any of them. Such a graph
+/// is a subdivision of K5 or K3,3, whose branch vertices are those of degree 4 or 3. That takes
+/// E planarity tests, O(E (V + E)), much slower than `is_planar`.
+pub fn is_planar_kuratowski<V: Ord + Copy, E>(
+    graph: &Graph<V, E>,
+) -> Option<KuratowskiSubgraph<V>> {
+    let (vertices, mut edges) = numbered(graph);
+    let n = vertices.len();
+    if left_right_test(n, &edges) {
+        return None;
+    }
+    let mut i = 0;
+    while i < edges.len() {
+        let edge = edges.remove(i);
+        if left_right_test(n, &edges) {
+            edges.insert(i, edge);
+            i += 1;
+        }
+    }
+    let mut degrees = vec![0; n];
+    for &(u, v) in &edges {
+        degrees[u] += 1;
+        degrees[v] += 1;
+    }
+    let branch_vertices: Vec<V> = (0..n)
+        .filter(|&v| degrees[v] > 2)
+        .map(|v| vertices[v])
+        .collect();
+    let kind = if branch_vertices.len() == 5 {
+        KuratowskiKind::K5
+    } else {
+        KuratowskiKind::K33
+    };
+    Some(KuratowskiSubgraph {
+        kind,
+        branch_vertices,
+        edges: edges
+            .into_iter()
+            .map(|(u, v)| (vertices[u], vertices[v]))
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{complete_graph, cycle_graph};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn from_edges(n: usize, edges: &[(usize, usize)]) -> Graph<usize, ()> {
+        let mut graph: Graph<usize, ()> = (0..n).map(|v| (v, BTreeMap::new())).collect();
+        for &(u, v) in edges {
+            graph.get_mut(&u).unwrap().insert(v, ());
+            graph.get_mut(&v).unwrap().insert(u, ());
+        }
+        graph
+    }
+
+    fn complete_bipartite(a: usize, b: usize) -> Vec<(usize, usize)> {
+        (0..a)
+            .flat_map(|u| (a..a + b).map(move |v| (u, v)))
+            .collect()
+    }
+
+    /// A triangulation of the plane, every new vertex put in a triangle and joined to its
+    /// three corners, with `3 n - 6` edges
+    fn triangulation(n: usize, rng: &mut ChaCha8Rng) -> Vec<(usize, usize)> {
+        let mut edges = vec![(0, 1), (1, 2), (0, 2)];
+        let mut faces = vec![[0, 1, 2], [0, 1, 2]];
+        for v in 3..n {
+            let [a, b, c] = faces.swap_remove(rng.gen_range(0..faces.len()));
+            edges.extend([(a, v), (b, v), (c, v)]);
+            faces.extend([[a, b, v], [b, c, v], [a, c, v]]);
+        }
+        edges
+    }
+
+    /// Checks that the subgraph is made of edges of the graph, is not planar, and is a
+    /// subdivision of K5 or K3,3.
+    fn check_kuratowski(n: usize, edges: &[(usize, usize)], subgraph: &KuratowskiSubgraph<usize>) {
+        for &(u, v) in &subgraph.edges {
+            assert!(edges.contains(&(u, v)) || edges.contains(&(v, u)));
+        }
+        assert!(!is_planar(&from_edges(n, &subgraph.edges)));
+        let mut degrees = vec![0; n];
+        for &(u, v) in &subgraph.edges {
+            degrees[u] += 1;
+            degrees[v] += 1;
+        }
+        let (count, degree) = match subgraph.kind {
+            KuratowskiKind::K5 => (5, 4),
+            KuratowskiKind::K33 => (6, 3),
+        };
+        assert_eq!(subgraph.branch_vertices.len(), count);
+        for (v, &found) in degrees.iter().enumerate() {
+            let expected = if subgraph.branch_vertices.contains(&v) {
+                degree
+            } else {
+                found.min(2)
+            };
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn small_graphs() {
+        assert!(is_planar(&complete_graph(4)));
+        assert!(!is_planar(&complete_graph(5)));
+        assert!(!is_planar(&from_edges(6, &complete_bipartite(3, 3))));
+        assert!(is_planar(&from_edges(5, &complete_bipartite(2, 3))));
+        assert!(is_planar(&cycle_graph(12)));
+        assert!(is_planar(&complete_graph(0)));
+        assert!(is_planar(&complete_graph(1)));
+        // the Petersen graph contains a subdivision of K3,3
+        let petersen: Vec<(usize, usize)> = (0..5)
+            .flat_map(|i| [(i, (i + 1) % 5), (i, i + 5), (i + 5, (i + 2) % 5 + 5)])
+            .collect();
+        assert!(!is_planar(&from_edges(10, &petersen)));
+        let subgraph = is_planar_kuratowski(&from_edges(10, &petersen)).unwrap();
+        assert_eq!(subgraph.kind, KuratowskiKind::K33);
+        check_kuratowski(10, &petersen, &subgraph);
+        let k5 = is_planar_kuratowski(&complete_graph(5)).unwrap();
+        assert_eq!(k5.kind, KuratowskiKind::K5);
+        assert_eq!(k5.edges.len(), 10);
+        assert_eq!(is_planar_kuratowski(&complete_graph(4)), None);
+    }
+
+    #[test]
+    fn triangulations() {
+        let mut rng = ChaCha8Rng::seed_from_u64(176);
+        for n in [10, 50, 500] {
+            let edges = triangulation(n, &mut rng);
+            assert_eq!(edges.len(), 3 * n - 6);
+            assert!(is_planar(&from_edges(n, &edges)));
+            // a triangulation with any edge more is not planar, with too many edges to be
+            let mut more = edges.clone();
+            let (u, v) = loop {
+                let (u, v) = (rng.gen_range(0..n), rng.gen_range(0..n));
+                if u != v && !edges.contains(&(u, v)) && !edges.contains(&(v, u)) {
+                    break (u, v);
+                }
+            };
+            more.push((u, v));
+            assert!(!is_planar(&from_edges(n, &more)));
+            // which the left-right test finds without counting
+            let (_, numbered_edges) = numbered(&from_edges(n, &more));
+            assert!(!left_right_test(n, &numbered_edges));
+            if n == 10 {
+                let subgraph = is_planar_kuratowski(&from_edges(n, &more)).unwrap();
+                check_kuratowski(n, &more, &subgraph);
+            }
+        }
+        // and a subdivided K3,3, in a sparse graph
+        let mut edges = vec![];
+        for (k, (u, v)) in complete_bipartite(3, 3).into_iter().enumerate() {
+            edges.extend([(u, 6 + k), (6 + k, v)]);
+        }
+        assert!(!is_planar(&from_edges(15, &edges)));
+    }
+
+    #[test]
+    fn all_graphs_on_six_vertices() {
+        // 32071 of the 32768 labeled graphs on six vertices are planar
+        let pairs: Vec<(usize, usize)> = (0..6)
+            .flat_map(|u| (u + 1..6).map(move |v| (u, v)))
+            .collect();
+        let mut planar = 0;
+        for mask in 0..1u32 << pairs.len() {
+            let edges: Vec<(usize, usize)> = (0..pairs.len())
+                .filter(|&i| mask >> i & 1 == 1)
+                .map(|i| pairs[i])
+                .collect();
+            let found = left_right_test(6, &edges);
+            assert_eq!(is_planar(&from_edges(6, &edges)), found);
+            if found {
+                planar += 1;
+            } else if mask.is_multiple_of(7) {
+                let subgraph = is_planar_kuratowski(&from_edges(6, &edges)).unwrap();
+                check_kuratowski(6, &edges, &subgraph);
+            }
+        }
+        assert_eq!(planar, 32071);
+    }
+}
@@ -21,6 +21,10 @@ pub fn prim<V: Ord + Copy + std::fmt::Debug, E: Ord + Add + Copy + std::fmt::Deb
 
 // only works for a connected graph
 // if the given graph is not connected it will return the MST of the connected subgraph
+//
+// this is the lazy variant: every edge out of the tree goes into the heap, and the edges which
+// lead back into the tree once their destination is reached stay there until they are popped and
+// skipped, so that the heap holds up to E edges; see prim_eager_with_start
 pub fn prim_with_start<V: Ord + Copy, E: Ord + Add + Copy>(
     graph: &Graph<V, E>,
     start: V,
@@ -57,10 +61,68 @@ pub fn prim_with_start<V: Ord + Copy, E: Ord + Add + Copy>(
     mst
 }
 
+// the eager variant of prim_with_start, with the same result
+//
+// it keeps the cheapest known edge from the tree to every vertex around it, and an edge only
+// goes into the heap when it is cheaper than that one, so that fewer edges go through the heap;
+// it takes more bookkeeping, but less time in practice, above all on dense graphs, in which most
+// edges do not improve on the best known one
+pub fn prim_eager_with_start<V: Ord + Copy, E: Ord + Add + Copy>(
+    graph: &Graph<V, E>,
+    start: V,
+) -> Graph<V, E> {
+    let mut mst: Graph<V, E> = Graph::new();
+    // the cheapest known edge to every vertex out of the tree: its cost and its source
+    let mut best: BTreeMap<V, (E, V)> = BTreeMap::new();
+    let mut prio = BinaryHeap::new();
+
+    mst.insert(start, BTreeMap::new());
+    let mut reached = start;
+    loop {
+        for (v, c) in &graph[&reached] {
+            if mst.contains_key(v) {
+                continue;
+            }
+            if best.get(v).is_none_or(|&(cost, _)| *c < cost) {
+                best.insert(*v, (*c, reached));
+                prio.push(Reverse((*c, *v)));
+            }
+        }
+
+        // an edge replaced by a cheaper one is still in the heap, and comes after it
+        let next = loop {
+            match prio.pop() {
+                Some(Reverse((_, t))) if mst.contains_key(&t) => continue,
+                next => break next,
+            }
+        };
+        let Some(Reverse((dist, t))) = next else {
+            break;
+        };
+        let (_, prev) = best.remove(&t).unwrap();
+        add_edge(&mut mst, prev, t, dist);
+        reached = t;
+    }
+
+    mst
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{add_edge, prim, Graph};
+    use super::{add_edge, prim, prim_eager_with_start, prim_with_start, Graph};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
     use std::collections::BTreeMap;
+    use std::time::Instant;
+
+    fn weight(mst: &Graph<usize, i32>) -> i32 {
+        // every edge is in the maps of both its vertices
+        mst.values().flat_map(BTreeMap::values).sum::<i32>() / 2
+    }
+
+    fn edges(mst: &Graph<usize, i32>) -> usize {
+        mst.values().map(BTreeMap::len).sum::<usize>() / 2
+    }
 
     #[test]
     fn empty() {
@@ -196,4 +258,73 @@ mod tests {
 
         assert_eq!(prim(&graph), ans);
     }
+
+    #[test]
+    fn lazy_and_eager_agree() {
+        let mut rng = ChaCha8Rng::seed_from_u64(124);
+        for (n, density, max_weight) in [(2, 1.0, 10), (10, 0.5, 10), (50, 0.2, 3), (50, 0.9, 1000)]
+        {
+            let mut graph = BTreeMap::new();
+            for u in 0..n {
+                graph.entry(u).or_insert_with(BTreeMap::new);
+                for v in u + 1..n {
+                    if rng.gen_bool(density) {
+                        add_edge(&mut graph, u, v, rng.gen_range(-max_weight..max_weight));
+                    }
+                }
+            }
+            for start in [0, n - 1] {
+                let lazy = prim_with_start(&graph, start);
+                let eager = prim_eager_with_start(&graph, start);
+                assert_eq!(weight(&lazy), weight(&eager));
+                assert_eq!(lazy.len(), eager.len());
+                assert_eq!(edges(&eager), eager.len() - 1);
+            }
+        }
+    }
+
+    #[test]
+    fn eager_graph_1() {
+        let mut graph = BTreeMap::new();
+        for (u, v, c) in [
+            (1, 2, 6),
+            (1, 3, 1),
+            (1, 4, 5),
+            (2, 3, 5),
+            (2, 5, 3),
+            (3, 4, 5),
+            (3, 5, 6),
+            (3, 6, 4),
+            (4, 6, 2),
+            (5, 6, 6),
+        ] {
+            add_edge(&mut graph, u, v, c);
+        }
+        assert_eq!(prim_eager_with_start(&graph, 1), prim(&graph));
+        // the part of the graph reached from the start
+        add_edge(&mut graph, 7, 8, 1);
+        assert_eq!(prim_eager_with_start(&graph, 8).len(), 2);
+        assert_eq!(prim_eager_with_start(&graph, 7), prim_with_start(&graph, 7));
+    }
+
+    #[test]
+    #[ignore = "slow test for measuring time"]
+    fn complete_graph_perf() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1240);
+        let n = 200;
+        let mut graph = BTreeMap::new();
+        for u in 0..n {
+            for v in u + 1..n {
+                add_edge(&mut graph, u, v, rng.gen_range(0..1_000_000));
+            }
+        }
+        let now = Instant::now();
+        let lazy = prim_with_start(&graph, 0);
+        let lazy_time = now.elapsed();
+        let now = Instant::now();
+        let eager = prim_eager_with_start(&graph, 0);
+        let eager_time = now.elapsed();
+        assert_eq!(weight(&lazy), weight(&eager));
+        println!("K_{n}: lazy {lazy_time:?}, eager {eager_time:?}");
+    }
 }
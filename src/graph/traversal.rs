@@ -0,0 +1,207 @@
+use crate::bit_manipulation::BitSet;
+use std::collections::VecDeque;
+
+/// Returns a lazy iterator over the vertices reachable from `start`, in breadth-first order.
+///
+/// The graph is given by the adjacency lists of its vertices `0..n`, and the neighbors of each
+/// vertex are visited in the order of its list. The iterator does the work of the search as it
+/// is advanced, so that stopping it early, with `take_while` or `find`, skips the rest.
+///
+/// # Panics
+///
+/// Panics if `start` or a neighbor is not a vertex of the graph.
+pub fn bfs_iter(graph: &[Vec<usize>], start: usize) -> Bfs<'_> {
+    assert!(
+        start < graph.len(),
+        "the start is not a vertex of the graph"
+    );
+    let mut visited = BitSet::with_capacity(graph.len());
+    visited.set(start);
+    Bfs {
+        graph,
+        queue: VecDeque::from([start]),
+        visited,
+    }
+}
+
+/// Returns a lazy iterator over the vertices reachable from `start`, in depth-first order.
+///
+/// Every vertex comes right before the vertices first reached through it, in the order of the
+/// adjacency lists, like the preorder of a recursive search, but with an explicit stack.
+///
+/// # Panics
+///
+/// Panics if `start` or a neighbor is not a vertex of the graph.
+pub fn dfs_iter(graph: &[Vec<usize>], start: usize) -> Dfs<'_> {
+    assert!(
+        start < graph.len(),
+        "the start is not a vertex of the graph"
+    );
+    Dfs {
+        graph,
+        stack: vec![],
+        visited: BitSet::with_capacity(graph.len()),
+        start: Some(start),
+    }
+}
+
+/// A breadth-first traversal, created by `bfs_iter`
+pub struct Bfs<'a> {
+    graph: &'a [Vec<usize>],
+    /// The vertices reached but not yet returned, in the order they were reached
+    queue: VecDeque<usize>,
+    /// The vertices reached so far
+    visited: BitSet,
+}
+
+impl Iterator for Bfs<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let vertex = self.queue.pop_front()?;
+        for &neighbor in &self.graph[vertex] {
+            if !self.visited.test(neighbor) {
+                self.visited.set(neighbor);
+                self.queue.push_back(neighbor);
+            }
+        }
+        Some(vertex)
+    }
+}
+
+/// A depth-first traversal, created by `dfs_iter`
+pub struct Dfs<'a> {
+    graph: &'a [Vec<usize>],
+    /// The path from the start to the last vertex returned, with the position of the next
+    /// neighbor to look at in the list of every vertex
+    stack: Vec<(usize, usize)>,
+    /// The vertices returned so far
+    visited: BitSet,
+    /// The start, until it is returned
+    start: Option<usize>,
+}
+
+impl Dfs<'_> {
+    fn enter(&mut self, vertex: usize) -> Option<usize> {
+        self.visited.set(vertex);
+        self.stack.push((vertex, 0));
+        Some(vertex)
+    }
+}
+
+impl Iterator for Dfs<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if let Some(start) = self.start.take() {
+            return self.enter(start);
+        }
+        while let Some((vertex, next)) = self.stack.last_mut() {
+            match self.graph[*vertex].get(*next) {
+                Some(&neighbor) => {
+                    *next += 1;
+                    if !self.visited.test(neighbor) {
+                        return self.enter(neighbor);
+                    }
+                }
+                // every vertex below this one was visited, back to its parent
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The tree
+    ///
+    /// ```text
+    ///         0
+    ///       / | \
+    ///      1  2  3
+    ///     / \     \
+    ///    4   5     6
+    ///        |
+    ///        7
+    /// ```
+    ///
+    /// with its edges both ways.
+    fn tree() -> Vec<Vec<usize>> {
+        let edges = [(0, 1), (0, 2), (0, 3), (1, 4), (1, 5), (3, 6), (5, 7)];
+        let mut graph = vec![vec![]; 8];
+        for (u, v) in edges {
+            graph[u].push(v);
+            graph[v].push(u);
+        }
+        graph
+    }
+
+    #[test]
+    fn level_order() {
+        let graph = tree();
+        assert_eq!(
+            bfs_iter(&graph, 0).collect::<Vec<_>>(),
+            [0, 1, 2, 3, 4, 5, 6, 7]
+        );
+        assert_eq!(
+            bfs_iter(&graph, 5).collect::<Vec<_>>(),
+            [5, 1, 7, 0, 4, 2, 3, 6]
+        );
+    }
+
+    #[test]
+    fn depth_first_order() {
+        let graph = tree();
+        assert_eq!(
+            dfs_iter(&graph, 0).collect::<Vec<_>>(),
+            [0, 1, 4, 5, 7, 2, 3, 6]
+        );
+        assert_eq!(
+            dfs_iter(&graph, 6).collect::<Vec<_>>(),
+            [6, 3, 0, 1, 4, 5, 7, 2]
+        );
+    }
+
+    #[test]
+    fn combinators() {
+        let graph = tree();
+        let mut visited = vec![];
+        for vertex in dfs_iter(&graph, 0) {
+            visited.push(vertex);
+        }
+        assert_eq!(visited.len(), 8);
+        let even: Vec<usize> = bfs_iter(&graph, 0).filter(|v| v % 2 == 0).collect();
+        assert_eq!(even, [0, 2, 4, 6]);
+        let before_five: Vec<usize> = dfs_iter(&graph, 0).take_while(|&v| v != 5).collect();
+        assert_eq!(before_five, [0, 1, 4]);
+        let doubled: Vec<usize> = bfs_iter(&graph, 3).map(|v| 2 * v).take(3).collect();
+        assert_eq!(doubled, [6, 0, 12]);
+        assert_eq!(bfs_iter(&graph, 0).position(|v| v == 7), Some(7));
+    }
+
+    #[test]
+    fn cycles_and_unreachable_vertices() {
+        // a directed cycle 0 -> 1 -> 2 -> 0 with a chord 0 -> 2, and 3 -> 0 out of reach
+        let graph = vec![vec![1, 2], vec![2], vec![0], vec![0]];
+        assert_eq!(bfs_iter(&graph, 0).collect::<Vec<_>>(), [0, 1, 2]);
+        assert_eq!(dfs_iter(&graph, 0).collect::<Vec<_>>(), [0, 1, 2]);
+        assert_eq!(dfs_iter(&graph, 3).collect::<Vec<_>>(), [3, 0, 1, 2]);
+        let lonely = vec![vec![]];
+        assert_eq!(bfs_iter(&lonely, 0).collect::<Vec<_>>(), [0]);
+        assert_eq!(dfs_iter(&lonely, 0).collect::<Vec<_>>(), [0]);
+    }
+
+    #[test]
+    fn long_paths() {
+        // a path deep enough to overflow the stack of a recursive search
+        let n = 200_000;
+        let graph: Vec<Vec<usize>> = (0..n).map(|v| vec![(v + 1) % n]).collect();
+        assert!(dfs_iter(&graph, 0).eq(0..n));
+        assert!(bfs_iter(&graph, 0).eq(0..n));
+    }
+}
@@ -0,0 +1,269 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::hash::{BuildHasher, BuildHasherDefault, Hash};
+
+/// A consistent hashing ring mapping keys to nodes <https://en.wikipedia.org/wiki/Consistent_hashing>
+///
+/// Nodes are placed on a ring of `u64` hash values through several virtual points each, and a key
+/// belongs to the first virtual point found clockwise from its own hash. Adding or removing a
+/// node only moves the keys between that node and its neighbours on the ring: every other key
+/// keeps its owner. A node of weight `w` gets `w * vnodes_per_unit` virtual points, so it owns a
+/// share of the keys proportional to its weight.
+///
+/// The hasher is configurable through `S`. The default one hashes with `DefaultHasher::new()`,
+/// which is deterministic, so the same ring always assigns keys the same way.
+pub struct ConsistentHashRing<N, S = BuildHasherDefault<DefaultHasher>> {
+    ring: BTreeMap<u64, N>,
+    weights: HashMap<N, usize>,
+    vnodes_per_unit: usize,
+    hash_builder: S,
+}
+
+impl<N: Clone + Hash + Eq> ConsistentHashRing<N> {
+    /// Creates an empty ring placing `vnodes_per_unit` virtual points per unit of weight.
+    pub fn new(vnodes_per_unit: usize) -> Self {
+        Self::with_hasher(vnodes_per_unit, BuildHasherDefault::default())
+    }
+}
+
+impl<N: Clone + Hash + Eq, S: BuildHasher> ConsistentHashRing<N, S> {
+    /// Creates an empty ring hashing with `hash_builder`.
+    pub fn with_hasher(vnodes_per_unit: usize, hash_builder: S) -> Self {
+        assert!(vnodes_per_unit > 0, "nodes need at least one virtual point");
+        ConsistentHashRing {
+            ring: BTreeMap::new(),
+            weights: HashMap::new(),
+            vnodes_per_unit,
+            hash_builder,
+        }
+    }
+
+    fn vnode_hash(&self, node: &N, replica: usize) -> u64 {
+        self.hash_builder.hash_one((node, replica))
+    }
+
+    /// Adds a node with `weight * vnodes_per_unit` virtual points.
+    /// Adding a node that is already in the ring updates its weight.
+    pub fn add_node(&mut self, node: N, weight: usize) {
+        self.remove_node(&node);
+        for replica in 0..weight * self.vnodes_per_unit {
+            // on the (unlikely) collision of two virtual points, the first one keeps its place
+            let hash = self.vnode_hash(&node, replica);
+            self.ring.entry(hash).or_insert_with(|| node.clone());
+        }
+        self.weights.insert(node, weight);
+    }
+
+    /// Removes a node and its virtual points from the ring.
+    ///
+    /// Returns `true` if the node was in the ring.
+    pub fn remove_node(&mut self, node: &N) -> bool {
+        let weight = match self.weights.remove(node) {
+            Some(weight) => weight,
+            None => return false,
+        };
+        for replica in 0..weight * self.vnodes_per_unit {
+            let hash = self.vnode_hash(node, replica);
+            if self.ring.get(&hash) == Some(node) {
+                self.ring.remove(&hash);
+            }
+        }
+        true
+    }
+
+    /// Returns the number of nodes in the ring.
+    pub fn len(&self) -> usize {
+        self.weights.len()
+    }
+
+    /// Returns `true` if the ring has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+
+    /// Iterates over the virtual points clockwise, starting from `hash` and wrapping around
+    /// the top of the ring.
+    fn clockwise_from(&self, hash: u64) -> impl Iterator<Item = &N> {
+        self.ring
+            .range(hash..)
+            .chain(self.ring.range(..hash))
+            .map(|(_, node)| node)
+    }
+
+    /// Returns the node owning `key`, or `None` if the ring has no virtual points.
+    pub fn get_node<K: Hash>(&self, key: &K) -> Option<&N> {
+        self.clockwise_from(self.hash_builder.hash_one(key)).next()
+    }
+
+    /// Returns up to `n` distinct nodes for `key`, in clockwise order from its hash.
+    /// The first one is the owner given by `get_node`, the others can hold replicas.
+    pub fn get_nodes<K: Hash>(&self, key: &K, n: usize) -> Vec<&N> {
+        let mut seen = HashSet::new();
+        let mut nodes = vec![];
+        for node in self.clockwise_from(self.hash_builder.hash_one(key)) {
+            if nodes.len() == n.min(self.weights.len()) {
+                break;
+            }
+            if seen.insert(node) {
+                nodes.push(node);
+            }
+        }
+        nodes
+    }
+
+    /// Counts how many of the sample keys every node owns, to measure how balanced the ring is.
+    /// Nodes owning none of the keys are reported with a count of zero.
+    pub fn key_distribution<K, I>(&self, sample_keys: I) -> HashMap<&N, usize>
+    where
+        K: Hash,
+        I: IntoIterator<Item = K>,
+    {
+        let mut distribution: HashMap<&N, usize> =
+            self.weights.keys().map(|node| (node, 0)).collect();
+        for key in sample_keys {
+            if let Some(node) = self.get_node(&key) {
+                *distribution.get_mut(node).unwrap() += 1;
+            }
+        }
+        distribution
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_KEYS: u64 = 100_000;
+
+    fn ring_with_nodes(count: usize) -> ConsistentHashRing<String> {
+        let mut ring = ConsistentHashRing::new(100);
+        for i in 0..count {
+            ring.add_node(format!("node-{i}"), 1);
+        }
+        ring
+    }
+
+    #[test]
+    fn empty_ring() {
+        let ring: ConsistentHashRing<String> = ConsistentHashRing::new(10);
+        assert!(ring.is_empty());
+        assert_eq!(ring.get_node(&"key"), None);
+        assert!(ring.get_nodes(&"key", 3).is_empty());
+    }
+
+    #[test]
+    fn single_node_owns_everything() {
+        let mut ring = ConsistentHashRing::new(10);
+        ring.add_node("only", 1);
+        for key in 0..1000 {
+            assert_eq!(ring.get_node(&key), Some(&"only"));
+        }
+        assert_eq!(ring.get_nodes(&42, 3), vec![&"only"]);
+        assert_eq!(ring.key_distribution(0..1000)[&"only"], 1000);
+    }
+
+    #[test]
+    fn removing_a_node_only_moves_its_keys() {
+        let mut ring = ring_with_nodes(10);
+        let before: Vec<String> = (0..SAMPLE_KEYS)
+            .map(|key| ring.get_node(&key).unwrap().clone())
+            .collect();
+
+        let removed = "node-3".to_string();
+        assert!(ring.remove_node(&removed));
+        assert!(!ring.remove_node(&removed));
+        assert_eq!(ring.len(), 9);
+
+        let mut moved = 0;
+        for (key, owner) in (0..SAMPLE_KEYS).zip(&before) {
+            let new_owner = ring.get_node(&key).unwrap();
+            if *owner == removed {
+                assert_ne!(*new_owner, removed);
+                moved += 1;
+            } else {
+                assert_eq!(new_owner, owner);
+            }
+        }
+        // the removed node owned roughly a tenth of the keys
+        assert!(moved > SAMPLE_KEYS / 20 && moved < SAMPLE_KEYS / 5);
+    }
+
+    #[test]
+    fn adding_a_node_only_takes_keys_for_itself() {
+        let mut ring = ring_with_nodes(5);
+        let before: Vec<String> = (0..SAMPLE_KEYS / 10)
+            .map(|key| ring.get_node(&key).unwrap().clone())
+            .collect();
+        ring.add_node("new".to_string(), 1);
+        for (key, owner) in (0..SAMPLE_KEYS / 10).zip(&before) {
+            let new_owner = ring.get_node(&key).unwrap();
+            assert!(new_owner == owner || new_owner == "new");
+        }
+    }
+
+    #[test]
+    fn wrap_around_the_top_of_the_ring() {
+        let ring = ring_with_nodes(3);
+        let (&last_point, _) = ring.ring.iter().next_back().unwrap();
+        let (_, first_owner) = ring.ring.iter().next().unwrap();
+        // find a key hashing past the last virtual point: it belongs to the first one
+        let key = (0u64..)
+            .find(|key| ring.hash_builder.hash_one(key) > last_point)
+            .unwrap();
+        assert_eq!(ring.get_node(&key), Some(first_owner));
+    }
+
+    #[test]
+    fn replicas_are_distinct_successors() {
+        let ring = ring_with_nodes(5);
+        for key in 0..1000 {
+            let nodes = ring.get_nodes(&key, 3);
+            assert_eq!(nodes.len(), 3);
+            assert_eq!(nodes[0], ring.get_node(&key).unwrap());
+            assert_ne!(nodes[0], nodes[1]);
+            assert_ne!(nodes[1], nodes[2]);
+            assert_ne!(nodes[0], nodes[2]);
+        }
+        // asking for more nodes than the ring has returns all of them
+        assert_eq!(ring.get_nodes(&7, 10).len(), 5);
+    }
+
+    #[test]
+    fn weighted_nodes() {
+        let mut ring = ConsistentHashRing::new(200);
+        ring.add_node("heavy", 3);
+        ring.add_node("light", 1);
+        let distribution = ring.key_distribution(0..SAMPLE_KEYS);
+        let heavy_share = distribution[&"heavy"] as f64 / SAMPLE_KEYS as f64;
+        assert!((heavy_share - 0.75).abs() < 0.05, "share was {heavy_share}");
+
+        // updating the weight moves the balance
+        ring.add_node("heavy", 1);
+        let distribution = ring.key_distribution(0..SAMPLE_KEYS);
+        let heavy_share = distribution[&"heavy"] as f64 / SAMPLE_KEYS as f64;
+        assert!((heavy_share - 0.5).abs() < 0.05, "share was {heavy_share}");
+    }
+
+    #[test]
+    fn balanced_distribution() {
+        let ring = ring_with_nodes(10);
+        let distribution = ring.key_distribution(0..SAMPLE_KEYS);
+        assert_eq!(distribution.len(), 10);
+        for &count in distribution.values() {
+            assert!(count > 6_000 && count < 14_000, "count was {count}");
+        }
+    }
+
+    #[test]
+    fn deterministic_for_a_fixed_hasher() {
+        let first = ring_with_nodes(10);
+        // insertion order does not matter either
+        let mut second = ConsistentHashRing::new(100);
+        for i in (0..10).rev() {
+            second.add_node(format!("node-{i}"), 1);
+        }
+        for key in 0..10_000 {
+            assert_eq!(first.get_node(&key), second.get_node(&key));
+        }
+    }
+}
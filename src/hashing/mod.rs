@@ -0,0 +1,3 @@
+mod consistent_hashing;
+
+pub use self::consistent_hashing::ConsistentHashRing;
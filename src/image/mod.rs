@@ -0,0 +1,10 @@
+//! Image processing on grayscale images given as their rows of pixels.
+//!
+//! The pixel of row `i` and column `j` is `image[i][j]`, and all the rows of an image have
+//! the same length.
+
+mod processing;
+
+pub use self::processing::{
+    convolution2d, equalize_histogram, GAUSSIAN_3X3, LAPLACIAN, SOBEL_X, SOBEL_Y,
+};
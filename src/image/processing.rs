@@ -0,0 +1,252 @@
+/// A blur weighting every pixel and its neighbors with a discrete Gaussian, 1 2 1 in each
+/// direction, normalized to add up to 1
+pub const GAUSSIAN_3X3: [[f64; 3]; 3] = [
+    [1.0 / 16.0, 2.0 / 16.0, 1.0 / 16.0],
+    [2.0 / 16.0, 4.0 / 16.0, 2.0 / 16.0],
+    [1.0 / 16.0, 2.0 / 16.0, 1.0 / 16.0],
+];
+
+/// The horizontal derivative of the Sobel operator, positive where the intensity grows to the
+/// right once convolved
+/// <https://en.wikipedia.org/wiki/Sobel_operator>
+pub const SOBEL_X: [[f64; 3]; 3] = [[1.0, 0.0, -1.0], [2.0, 0.0, -2.0], [1.0, 0.0, -1.0]];
+
+/// The vertical derivative of the Sobel operator, positive where the intensity grows downwards
+/// once convolved
+pub const SOBEL_Y: [[f64; 3]; 3] = [[1.0, 2.0, 1.0], [0.0, 0.0, 0.0], [-1.0, -2.0, -1.0]];
+
+/// The discrete Laplacian over the four neighbors, zero where the intensity is constant or
+/// changes linearly
+/// <https://en.wikipedia.org/wiki/Discrete_Laplace_operator>
+pub const LAPLACIAN: [[f64; 3]; 3] = [[0.0, 1.0, 0.0], [1.0, -4.0, 1.0], [0.0, 1.0, 0.0]];
+
+/// Returns the number of columns of an image.
+///
+/// # Panics
+///
+/// Panics if the rows do not all have the same length.
+fn width<R: AsRef<[T]>, T>(image: &[R]) -> usize {
+    let width = image.first().map_or(0, |row| row.as_ref().len());
+    if let Some(i) = image.iter().position(|row| row.as_ref().len() != width) {
+        panic!("the row {i} does not have the {width} pixels of the first one");
+    }
+    width
+}
+
+/// Spreads the intensities of an image over the whole range of `u8`, so that their cumulative
+/// distribution becomes about uniform.
+/// <https://en.wikipedia.org/wiki/Histogram_equalization>
+///
+/// Every intensity `v` becomes `round(255 (cdf(v) - cdf_min) / (n - cdf_min))`, where
+/// `cdf(v)` is the number of pixels at most `v`, and `cdf_min` that of the darkest intensity
+/// present, which becomes 0 while the brightest one becomes 255. The intensities keep their
+/// order, and those crowded together in the histogram are moved apart, which raises the
+/// contrast. An image of a single intensity is returned as it is.
+///
+/// # Panics
+///
+/// Panics if the rows do not all have the same length.
+pub fn equalize_histogram(image: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    width(image);
+    let mut cdf = [0usize; 256];
+    for &pixel in image.iter().flatten() {
+        cdf[pixel as usize] += 1;
+    }
+    for v in 1..256 {
+        cdf[v] += cdf[v - 1];
+    }
+    let n = cdf[255];
+    let cdf_min = cdf.iter().copied().find(|&count| count > 0).unwrap_or(0);
+    if n == cdf_min {
+        return image.to_vec();
+    }
+    let map: Vec<u8> = cdf
+        .iter()
+        .map(|&count| {
+            let scaled = 255.0 * count.saturating_sub(cdf_min) as f64 / (n - cdf_min) as f64;
+            scaled.round() as u8
+        })
+        .collect();
+    image
+        .iter()
+        .map(|row| row.iter().map(|&pixel| map[pixel as usize]).collect())
+        .collect()
+}
+
+/// Returns the convolution of an image with a kernel, of the same size as the image, the
+/// pixels beyond its edges being zeros.
+/// <https://en.wikipedia.org/wiki/Kernel_(image_processing)#Convolution>
+///
+/// The kernel is centered on every pixel, at its row `kernel.len() / 2` and its column
+/// `kernel[0].len() / 2`, and the pixel becomes the sum of the products of the kernel with the
+/// pixels under it, the kernel flipped in both directions as in a true convolution. Flipping
+/// makes no difference for symmetric kernels such as blurs, and changes the sign of
+/// derivatives such as `SOBEL_X`. That takes O(h w k l) for an h x w image and a k x l kernel.
+///
+/// # Panics
+///
+/// Panics if the rows of the image, or those of the kernel, do not all have the same length.
+pub fn convolution2d<R: AsRef<[f64]>>(image: &[Vec<f64>], kernel: &[R]) -> Vec<Vec<f64>> {
+    let (height, image_width) = (image.len(), width(image));
+    let (kernel_height, kernel_width) = (kernel.len(), width(kernel));
+    let (center_row, center_column) = (kernel_height / 2, kernel_width / 2);
+    let mut output = vec![vec![0.0; image_width]; height];
+    for (i, output_row) in output.iter_mut().enumerate() {
+        for (j, pixel) in output_row.iter_mut().enumerate() {
+            let mut sum = 0.0;
+            for (a, kernel_row) in kernel.iter().enumerate() {
+                // the image row under the kernel row a, flipped
+                let Some(row) = (i + center_row)
+                    .checked_sub(a)
+                    .and_then(|row| image.get(row))
+                else {
+                    continue;
+                };
+                for (b, &weight) in kernel_row.as_ref().iter().enumerate() {
+                    if let Some(&value) =
+                        (j + center_column).checked_sub(b).and_then(|c| row.get(c))
+                    {
+                        sum += weight * value;
+                    }
+                }
+            }
+            *pixel = sum;
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    /// The largest gap between the cumulative distribution of the intensities and the uniform
+    /// one over 0..=255
+    fn distance_to_uniform(image: &[Vec<u8>]) -> f64 {
+        let n = image.iter().map(Vec::len).sum::<usize>() as f64;
+        let mut counts = [0usize; 256];
+        for &pixel in image.iter().flatten() {
+            counts[pixel as usize] += 1;
+        }
+        let mut cumulative = 0;
+        let mut distance = 0.0f64;
+        for (v, count) in counts.iter().enumerate() {
+            cumulative += count;
+            distance = distance.max((cumulative as f64 / n - (v + 1) as f64 / 256.0).abs());
+        }
+        distance
+    }
+
+    fn assert_close(found: &[Vec<f64>], expected: &[Vec<f64>]) {
+        for (found_row, expected_row) in found.iter().zip(expected) {
+            for (a, b) in found_row.iter().zip(expected_row) {
+                assert!((a - b).abs() < 1e-12, "{found:?} is not {expected:?}");
+            }
+        }
+        assert_eq!(found.len(), expected.len());
+    }
+
+    #[test]
+    fn equalization_spreads_a_low_contrast_image() {
+        let mut rng = ChaCha8Rng::seed_from_u64(164);
+        let image: Vec<Vec<u8>> = (0..64)
+            .map(|_| (0..64).map(|_| rng.gen_range(100..=130)).collect())
+            .collect();
+        let equalized = equalize_histogram(&image);
+        assert!(distance_to_uniform(&equalized) < distance_to_uniform(&image) / 4.0);
+        let pixels = || equalized.iter().flatten();
+        assert_eq!(pixels().min(), Some(&0));
+        assert_eq!(pixels().max(), Some(&255));
+        // the intensities keep their order
+        for (row, equalized_row) in image.iter().zip(&equalized) {
+            for (j, k) in [(0, 1), (5, 40), (63, 2)] {
+                if row[j] <= row[k] {
+                    assert!(equalized_row[j] <= equalized_row[k]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn equalization_of_small_images() {
+        // two intensities become the two ends of the range
+        let image = vec![vec![50, 60], vec![60, 50]];
+        assert_eq!(equalize_histogram(&image), [[0, 255], [255, 0]]);
+        let image = vec![vec![10, 20, 30, 40]];
+        assert_eq!(equalize_histogram(&image), [[0, 85, 170, 255]]);
+        // nothing to spread
+        assert_eq!(equalize_histogram(&vec![vec![7; 3]; 2]), [[7; 3]; 2]);
+        assert!(equalize_histogram(&[]).is_empty());
+    }
+
+    #[test]
+    fn box_blur() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1640);
+        let image: Vec<Vec<f64>> = (0..12)
+            .map(|_| (0..9).map(|_| rng.gen_range(0.0..255.0)).collect())
+            .collect();
+        for n in [1, 3, 5] {
+            let kernel = vec![vec![1.0 / (n * n) as f64; n]; n];
+            let blurred = convolution2d(&image, &kernel);
+            let r = n / 2;
+            for (i, blurred_row) in blurred.iter().enumerate() {
+                for (j, &pixel) in blurred_row.iter().enumerate() {
+                    // the average of the window, the pixels out of the image counting as zeros
+                    let mut sum = 0.0;
+                    for row in image.iter().take(i + r + 1).skip(i.saturating_sub(r)) {
+                        sum += row[j.saturating_sub(r)..(j + r + 1).min(9)]
+                            .iter()
+                            .sum::<f64>();
+                    }
+                    assert!((pixel - sum / (n * n) as f64).abs() < 1e-9);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn kernels() {
+        // an impulse gives the kernel itself, where a correlation would flip it
+        let mut impulse = vec![vec![0.0; 5]; 5];
+        impulse[2][2] = 1.0;
+        let response = convolution2d(&impulse, &SOBEL_X);
+        assert_eq!(response[1][1..4], [1.0, 0.0, -1.0]);
+        assert_eq!(response[2][1..4], [2.0, 0.0, -2.0]);
+        let blurred = convolution2d(&impulse, &GAUSSIAN_3X3);
+        assert_eq!(blurred.iter().flatten().sum::<f64>(), 1.0);
+        assert_eq!(blurred[2][2], 0.25);
+        // an intensity growing to the right, then downwards
+        let ramp: Vec<Vec<f64>> = (0..5)
+            .map(|i| (0..5).map(|j| (i + 2 * j) as f64).collect())
+            .collect();
+        assert_eq!(convolution2d(&ramp, &SOBEL_X)[2][2], 16.0);
+        assert_eq!(convolution2d(&ramp, &SOBEL_Y)[2][2], 8.0);
+        assert_eq!(convolution2d(&ramp, &LAPLACIAN)[2][2], 0.0);
+        // the zeros beyond the edges
+        let constant = vec![vec![1.0; 3]; 3];
+        assert_close(
+            &convolution2d(&constant, &LAPLACIAN),
+            &[
+                vec![-2.0, -1.0, -2.0],
+                vec![-1.0, 0.0, -1.0],
+                vec![-2.0, -1.0, -2.0],
+            ],
+        );
+        // a kernel larger than the image, and an even one centered on its row 1 and column 1
+        assert_eq!(convolution2d(&[vec![2.0]], &[[1.0; 5]; 5]), [[2.0]]);
+        let even = [[1.0, 2.0], [3.0, 4.0]];
+        assert_eq!(
+            convolution2d(&[vec![1.0, 0.0], vec![0.0, 0.0]], &even),
+            [[4.0, 0.0], [0.0, 0.0]]
+        );
+        assert!(convolution2d(&[], &GAUSSIAN_3X3).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "the row 1 does not have the 2 pixels of the first one")]
+    fn ragged_image() {
+        equalize_histogram(&[vec![1, 2], vec![3]]);
+    }
+}
@@ -0,0 +1,106 @@
+/// Selects a maximum size set of pairwise non-overlapping intervals and returns their indices,
+/// ordered by end point.
+///
+/// This is the classic earliest-finish-time greedy: among the intervals that start after the
+/// last chosen one ends, always choose the one that ends first. Runs in O(n log n).
+pub fn max_non_overlapping(intervals: &[(i64, i64)]) -> Vec<usize> {
+    assert!(
+        intervals.iter().all(|(start, end)| start <= end),
+        "intervals must have start <= end"
+    );
+    let mut order: Vec<usize> = (0..intervals.len()).collect();
+    order.sort_by_key(|&i| (intervals[i].1, intervals[i].0));
+
+    let mut chosen = vec![];
+    let mut last_end = i64::MIN;
+    for i in order {
+        let (start, end) = intervals[i];
+        if start >= last_end {
+            chosen.push(i);
+            last_end = end;
+        }
+    }
+    chosen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn overlap(a: (i64, i64), b: (i64, i64)) -> bool {
+        a.0 < b.1 && b.0 < a.1
+    }
+
+    fn is_valid_schedule(intervals: &[(i64, i64)], chosen: &[usize]) -> bool {
+        chosen.iter().enumerate().all(|(k, &i)| {
+            chosen[k + 1..]
+                .iter()
+                .all(|&j| !overlap(intervals[i], intervals[j]))
+        })
+    }
+
+    fn brute_force(intervals: &[(i64, i64)]) -> usize {
+        (0u32..1 << intervals.len())
+            .filter(|mask| {
+                let chosen: Vec<usize> = (0..intervals.len())
+                    .filter(|i| mask >> i & 1 == 1)
+                    .collect();
+                is_valid_schedule(intervals, &chosen)
+            })
+            .map(|mask| mask.count_ones() as usize)
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(max_non_overlapping(&[]), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn classic_example() {
+        let intervals = [
+            (1, 4),
+            (3, 5),
+            (0, 6),
+            (5, 7),
+            (3, 9),
+            (5, 9),
+            (6, 10),
+            (8, 11),
+        ];
+        assert_eq!(max_non_overlapping(&intervals), vec![0, 3, 7]);
+    }
+
+    #[test]
+    fn touching_intervals_are_compatible() {
+        assert_eq!(
+            max_non_overlapping(&[(3, 5), (1, 3), (5, 7)]),
+            vec![1, 0, 2]
+        );
+    }
+
+    #[test]
+    fn long_interval_is_skipped() {
+        assert_eq!(max_non_overlapping(&[(0, 100), (1, 2), (2, 3)]), vec![1, 2]);
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        for _ in 0..300 {
+            let n = rng.gen_range(0..11);
+            let intervals: Vec<(i64, i64)> = (0..n)
+                .map(|_| {
+                    let start = rng.gen_range(0..20);
+                    (start, start + rng.gen_range(0..8))
+                })
+                .collect();
+            let chosen = max_non_overlapping(&intervals);
+            assert!(is_valid_schedule(&intervals, &chosen));
+            assert_eq!(chosen.len(), brute_force(&intervals));
+        }
+    }
+}
@@ -0,0 +1,369 @@
+use std::cmp::{max, Ordering};
+use std::mem;
+
+/// An internal node of an `IntervalTree`.
+struct Node {
+    interval: (i64, i64),
+    /// Number of copies of `interval` stored in the tree
+    count: usize,
+    height: usize,
+    /// Largest end point in the subtree rooted at this node
+    max_end: i64,
+    left: Option<Box<Node>>,
+    right: Option<Box<Node>>,
+}
+
+/// A multiset of half-open intervals supporting stabbing queries.
+///
+/// The intervals are kept in an AVL tree ordered by `(start, end)`, where every node also
+/// stores the largest end point of its subtree. A query skips every subtree whose largest end
+/// point is too small and, since the tree is ordered by start, every right subtree whose
+/// starts are too large. Insertion and removal take O(log n), and a query reporting k
+/// intervals takes O(min(n, (k + 1) log n)).
+pub struct IntervalTree {
+    root: Option<Box<Node>>,
+    length: usize,
+}
+
+impl IntervalTree {
+    /// Creates an empty `IntervalTree`.
+    pub fn new() -> Self {
+        IntervalTree {
+            root: None,
+            length: 0,
+        }
+    }
+
+    /// Returns the number of intervals in the tree, counting duplicates.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns `true` if the tree contains no intervals.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Adds the interval `[start, end)` to the tree. Duplicates are kept.
+    pub fn insert(&mut self, start: i64, end: i64) {
+        assert!(start <= end, "intervals must have start <= end");
+        insert(&mut self.root, (start, end));
+        self.length += 1;
+    }
+
+    /// Removes one copy of the interval `[start, end)` from the tree.
+    ///
+    /// Returns `true` if the tree contained the interval.
+    pub fn remove(&mut self, start: i64, end: i64) -> bool {
+        let removed = remove(&mut self.root, (start, end));
+        if removed {
+            self.length -= 1;
+        }
+        removed
+    }
+
+    /// Returns all intervals containing `point`, sorted by `(start, end)`.
+    pub fn query_point(&self, point: i64) -> Vec<(i64, i64)> {
+        // [start, end) contains point if and only if it overlaps [point, point + 1)
+        self.query_range(point, point.saturating_add(1))
+    }
+
+    /// Returns all intervals overlapping `[start, end)`, sorted by `(start, end)`.
+    pub fn query_range(&self, start: i64, end: i64) -> Vec<(i64, i64)> {
+        let mut result = vec![];
+        if start < end {
+            query(&self.root, start, end, &mut result);
+        }
+        result
+    }
+}
+
+impl Default for IntervalTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Collects the intervals of the subtree overlapping `[start, end)`, in order.
+fn query(tree: &Option<Box<Node>>, start: i64, end: i64, result: &mut Vec<(i64, i64)>) {
+    let node = match tree {
+        Some(node) if node.max_end > start => node,
+        _ => return,
+    };
+    query(&node.left, start, end, result);
+    if node.interval.0 < end {
+        if node.interval.1 > start && node.interval.0 < node.interval.1 {
+            result.extend(std::iter::repeat_n(node.interval, node.count));
+        }
+        query(&node.right, start, end, result);
+    }
+}
+
+/// Recursive helper function for `IntervalTree` insertion.
+fn insert(tree: &mut Option<Box<Node>>, interval: (i64, i64)) {
+    if let Some(node) = tree {
+        match interval.cmp(&node.interval) {
+            Ordering::Equal => node.count += 1,
+            Ordering::Less => insert(&mut node.left, interval),
+            Ordering::Greater => insert(&mut node.right, interval),
+        }
+        node.rebalance();
+    } else {
+        *tree = Some(Box::new(Node {
+            interval,
+            count: 1,
+            height: 1,
+            max_end: interval.1,
+            left: None,
+            right: None,
+        }));
+    }
+}
+
+/// Recursive helper function for `IntervalTree` deletion.
+fn remove(tree: &mut Option<Box<Node>>, interval: (i64, i64)) -> bool {
+    let node = match tree {
+        Some(node) => node,
+        None => return false,
+    };
+    let removed = match interval.cmp(&node.interval) {
+        Ordering::Less => remove(&mut node.left, interval),
+        Ordering::Greater => remove(&mut node.right, interval),
+        Ordering::Equal if node.count > 1 => {
+            node.count -= 1;
+            true
+        }
+        Ordering::Equal => {
+            *tree = match (node.left.take(), node.right.take()) {
+                (None, None) => None,
+                (Some(child), None) | (None, Some(child)) => Some(child),
+                (Some(left), Some(right)) => {
+                    let mut right = Some(right);
+                    // Guaranteed not to panic since right has at least one node
+                    let mut root = take_min(&mut right).unwrap();
+                    root.left = Some(left);
+                    root.right = right;
+                    root.rebalance();
+                    Some(root)
+                }
+            };
+            return true;
+        }
+    };
+    if removed {
+        node.rebalance();
+    }
+    removed
+}
+
+/// Removes the smallest node from the tree, if one exists.
+fn take_min(tree: &mut Option<Box<Node>>) -> Option<Box<Node>> {
+    let mut node = tree.take()?;
+    if let Some(small) = take_min(&mut node.left) {
+        node.rebalance();
+        *tree = Some(node);
+        Some(small)
+    } else {
+        *tree = node.right.take();
+        Some(node)
+    }
+}
+
+fn height(tree: &Option<Box<Node>>) -> usize {
+    tree.as_ref().map_or(0, |node| node.height)
+}
+
+fn max_end(tree: &Option<Box<Node>>) -> i64 {
+    tree.as_ref().map_or(i64::MIN, |node| node.max_end)
+}
+
+impl Node {
+    /// Recomputes `height` and `max_end` from the children.
+    fn update(&mut self) {
+        self.height = 1 + max(height(&self.left), height(&self.right));
+        self.max_end = max(
+            self.interval.1,
+            max(max_end(&self.left), max_end(&self.right)),
+        );
+    }
+
+    fn balance_factor(&self) -> isize {
+        height(&self.right) as isize - height(&self.left) as isize
+    }
+
+    /// Brings the right child up, making this node its left child.
+    fn rotate_left(&mut self) {
+        let mut subtree = self.right.take().unwrap();
+        self.right = subtree.left.take();
+        self.update();
+        mem::swap(self, subtree.as_mut());
+        self.left = Some(subtree);
+        self.update();
+    }
+
+    /// Brings the left child up, making this node its right child.
+    fn rotate_right(&mut self) {
+        let mut subtree = self.left.take().unwrap();
+        self.left = subtree.right.take();
+        self.update();
+        mem::swap(self, subtree.as_mut());
+        self.right = Some(subtree);
+        self.update();
+    }
+
+    /// Performs tree rotations to balance this node.
+    fn rebalance(&mut self) {
+        self.update();
+        match self.balance_factor() {
+            -2 => {
+                let left = self.left.as_mut().unwrap();
+                if left.balance_factor() > 0 {
+                    left.rotate_left();
+                }
+                self.rotate_right();
+            }
+            2 => {
+                let right = self.right.as_mut().unwrap();
+                if right.balance_factor() < 0 {
+                    right.rotate_right();
+                }
+                self.rotate_left();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn is_balanced(tree: &Option<Box<Node>>) -> bool {
+        match tree {
+            None => true,
+            Some(node) => {
+                node.balance_factor().abs() <= 1
+                    && is_balanced(&node.left)
+                    && is_balanced(&node.right)
+            }
+        }
+    }
+
+    fn linear_scan(intervals: &[(i64, i64)], start: i64, end: i64) -> Vec<(i64, i64)> {
+        let mut result: Vec<(i64, i64)> = intervals
+            .iter()
+            .copied()
+            .filter(|&(s, e)| start < end && s < end && start < e && s < e)
+            .collect();
+        result.sort_unstable();
+        result
+    }
+
+    #[test]
+    fn empty() {
+        let mut tree = IntervalTree::new();
+        assert!(tree.is_empty());
+        assert_eq!(tree.query_point(0), vec![]);
+        assert!(!tree.remove(0, 1));
+    }
+
+    #[test]
+    fn point_queries_respect_half_open_ends() {
+        let mut tree = IntervalTree::new();
+        tree.insert(1, 3);
+        tree.insert(3, 5);
+        tree.insert(0, 10);
+        tree.insert(4, 4);
+        assert_eq!(tree.len(), 4);
+        assert_eq!(tree.query_point(3), vec![(0, 10), (3, 5)]);
+        assert_eq!(tree.query_point(1), vec![(0, 10), (1, 3)]);
+        // the empty interval (4, 4) never contains anything
+        assert_eq!(tree.query_point(4), vec![(0, 10), (3, 5)]);
+        assert_eq!(tree.query_point(10), vec![]);
+        assert_eq!(tree.query_point(-1), vec![]);
+    }
+
+    #[test]
+    fn range_queries() {
+        let mut tree = IntervalTree::new();
+        for &(s, e) in &[(15, 20), (10, 30), (17, 19), (5, 20), (12, 15), (30, 40)] {
+            tree.insert(s, e);
+        }
+        assert_eq!(tree.query_range(6, 7), vec![(5, 20)]);
+        assert_eq!(
+            tree.query_range(14, 16),
+            vec![(5, 20), (10, 30), (12, 15), (15, 20)]
+        );
+        assert_eq!(tree.query_range(30, 31), vec![(30, 40)]);
+        assert_eq!(tree.query_range(20, 20), vec![]);
+    }
+
+    #[test]
+    fn duplicates_and_removal() {
+        let mut tree = IntervalTree::new();
+        tree.insert(1, 5);
+        tree.insert(1, 5);
+        assert_eq!(tree.query_point(2), vec![(1, 5), (1, 5)]);
+        assert!(tree.remove(1, 5));
+        assert_eq!(tree.query_point(2), vec![(1, 5)]);
+        assert!(tree.remove(1, 5));
+        assert!(!tree.remove(1, 5));
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn extreme_values() {
+        let mut tree = IntervalTree::new();
+        tree.insert(i64::MIN, i64::MAX);
+        assert_eq!(tree.query_point(i64::MIN), vec![(i64::MIN, i64::MAX)]);
+        assert_eq!(tree.query_point(i64::MAX), vec![]);
+    }
+
+    #[test]
+    fn matches_linear_scan() {
+        let mut rng = ChaCha8Rng::seed_from_u64(11);
+        let mut tree = IntervalTree::new();
+        let mut intervals: Vec<(i64, i64)> = vec![];
+        for _ in 0..5000 {
+            match rng.gen_range(0..4) {
+                0 | 1 => {
+                    let start = rng.gen_range(-100..100);
+                    let end = start + rng.gen_range(0..30);
+                    tree.insert(start, end);
+                    intervals.push((start, end));
+                }
+                2 => {
+                    if !intervals.is_empty() && rng.gen_bool(0.8) {
+                        let (s, e) = intervals.swap_remove(rng.gen_range(0..intervals.len()));
+                        assert!(tree.remove(s, e));
+                    } else {
+                        let start = rng.gen_range(-100..100);
+                        let end = start + rng.gen_range(0..30);
+                        let present = intervals.iter().position(|&x| x == (start, end));
+                        if let Some(i) = present {
+                            intervals.swap_remove(i);
+                        }
+                        assert_eq!(tree.remove(start, end), present.is_some());
+                    }
+                }
+                _ => {
+                    let point = rng.gen_range(-110..140);
+                    assert_eq!(
+                        tree.query_point(point),
+                        linear_scan(&intervals, point, point + 1)
+                    );
+                    let start = rng.gen_range(-110..140);
+                    let end = start + rng.gen_range(0..20);
+                    assert_eq!(
+                        tree.query_range(start, end),
+                        linear_scan(&intervals, start, end)
+                    );
+                }
+            }
+            assert_eq!(tree.len(), intervals.len());
+        }
+        assert!(is_balanced(&tree.root));
+    }
+}
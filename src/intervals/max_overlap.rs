@@ -0,0 +1,85 @@
+/// Returns the largest number of intervals that contain a common point, together with
+/// one such point.
+///
+/// Sweeps over the sorted endpoints, keeping track of how many intervals are open. Since the
+/// intervals are half-open, an interval ending at `x` is closed before one starting at `x`
+/// is opened. If no point is covered at all, every point has zero overlap and `(0, 0)` is returned.
+pub fn max_overlap(intervals: &[(i64, i64)]) -> (usize, i64) {
+    assert!(
+        intervals.iter().all(|(start, end)| start <= end),
+        "intervals must have start <= end"
+    );
+    // (coordinate, delta) with -1 sorting before +1 at the same coordinate;
+    // empty intervals contain no points and are skipped
+    let mut events: Vec<(i64, i32)> = intervals
+        .iter()
+        .filter(|(start, end)| start < end)
+        .flat_map(|&(start, end)| [(start, 1), (end, -1)])
+        .collect();
+    events.sort_unstable();
+
+    let mut open = 0usize;
+    let mut best = (0, 0);
+    for (x, delta) in events {
+        if delta > 0 {
+            open += 1;
+            if open > best.0 {
+                best = (open, x);
+            }
+        } else {
+            open -= 1;
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn count_at(intervals: &[(i64, i64)], x: i64) -> usize {
+        intervals.iter().filter(|&&(s, e)| s <= x && x < e).count()
+    }
+
+    #[test]
+    fn no_intervals() {
+        assert_eq!(max_overlap(&[]), (0, 0));
+        assert_eq!(max_overlap(&[(4, 4)]), (0, 0));
+    }
+
+    #[test]
+    fn simple() {
+        assert_eq!(max_overlap(&[(1, 5), (2, 6), (3, 4)]), (3, 3));
+        assert_eq!(max_overlap(&[(0, 10)]), (1, 0));
+    }
+
+    #[test]
+    fn touching_intervals_do_not_overlap() {
+        assert_eq!(max_overlap(&[(1, 3), (3, 5), (5, 7)]).0, 1);
+    }
+
+    #[test]
+    fn duplicates() {
+        assert_eq!(max_overlap(&[(2, 4), (2, 4), (2, 4), (0, 1)]), (3, 2));
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let mut rng = ChaCha8Rng::seed_from_u64(5);
+        for _ in 0..300 {
+            let n = rng.gen_range(0..30);
+            let intervals: Vec<(i64, i64)> = (0..n)
+                .map(|_| {
+                    let start = rng.gen_range(-20..20);
+                    (start, start + rng.gen_range(0..10))
+                })
+                .collect();
+            let (peak, point) = max_overlap(&intervals);
+            let expected = (-20..30).map(|x| count_at(&intervals, x)).max().unwrap();
+            assert_eq!(peak, expected);
+            assert_eq!(count_at(&intervals, point), peak);
+        }
+    }
+}
@@ -0,0 +1,89 @@
+/// Merges overlapping intervals and returns the result sorted by start.
+///
+/// If `merge_touching` is true, intervals that only touch, like `(1, 3)` and `(3, 5)`,
+/// are merged as well, otherwise they are kept separate.
+pub fn merge_intervals(intervals: &[(i64, i64)], merge_touching: bool) -> Vec<(i64, i64)> {
+    assert!(
+        intervals.iter().all(|(start, end)| start <= end),
+        "intervals must have start <= end"
+    );
+    let mut sorted = intervals.to_vec();
+    sorted.sort_unstable();
+
+    let mut merged: Vec<(i64, i64)> = Vec::with_capacity(sorted.len());
+    for (start, end) in sorted {
+        match merged.last_mut() {
+            Some(last) if start < last.1 || (merge_touching && start == last.1) => {
+                last.1 = last.1.max(end);
+            }
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        assert_eq!(merge_intervals(&[], true), vec![]);
+    }
+
+    #[test]
+    fn disjoint_intervals_are_sorted() {
+        assert_eq!(
+            merge_intervals(&[(8, 10), (1, 2), (4, 6)], true),
+            vec![(1, 2), (4, 6), (8, 10)]
+        );
+    }
+
+    #[test]
+    fn overlapping() {
+        assert_eq!(
+            merge_intervals(&[(1, 3), (2, 6), (8, 10), (15, 18), (9, 12)], false),
+            vec![(1, 6), (8, 12), (15, 18)]
+        );
+    }
+
+    #[test]
+    fn nested() {
+        assert_eq!(
+            merge_intervals(&[(1, 10), (2, 3), (4, 8), (5, 6)], false),
+            vec![(1, 10)]
+        );
+        assert_eq!(
+            merge_intervals(&[(2, 3), (-5, 20), (0, 1)], false),
+            vec![(-5, 20)]
+        );
+    }
+
+    #[test]
+    fn touching() {
+        let intervals = [(1, 3), (3, 5), (5, 7), (8, 9)];
+        assert_eq!(merge_intervals(&intervals, true), vec![(1, 7), (8, 9)]);
+        assert_eq!(
+            merge_intervals(&intervals, false),
+            vec![(1, 3), (3, 5), (5, 7), (8, 9)]
+        );
+    }
+
+    #[test]
+    fn duplicates() {
+        assert_eq!(
+            merge_intervals(&[(1, 4), (1, 4), (1, 4)], false),
+            vec![(1, 4)]
+        );
+        assert_eq!(
+            merge_intervals(&[(2, 2), (2, 2), (0, 1)], true),
+            vec![(0, 1), (2, 2)]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn reversed_interval() {
+        merge_intervals(&[(3, 1)], true);
+    }
+}
@@ -0,0 +1,16 @@
+//! Algorithms on intervals of integers.
+//!
+//! Every interval is given as a `(start, end)` pair and is half-open: it contains the points
+//! `x` with `start <= x < end`. Two intervals overlap if they share at least one point, so
+//! `(1, 3)` and `(3, 5)` do not overlap, they only touch. An interval with `start == end`
+//! is empty, and intervals with `start > end` are rejected.
+
+mod interval_scheduling;
+mod interval_tree;
+mod max_overlap;
+mod merge_intervals;
+
+pub use self::interval_scheduling::max_non_overlapping;
+pub use self::interval_tree::IntervalTree;
+pub use self::max_overlap::max_overlap;
+pub use self::merge_intervals::merge_intervals;
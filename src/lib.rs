@@ -1,5 +1,6 @@
 #[macro_use]
 extern crate lazy_static;
+pub mod algorithms;
 pub mod backtracking;
 pub mod big_integer;
 pub mod bit_manipulation;
@@ -8,15 +9,20 @@ pub mod compression;
 pub mod conversions;
 pub mod data_structures;
 pub mod dynamic_programming;
+pub mod error;
 pub mod general;
 pub mod geometry;
 pub mod graph;
+pub mod hashing;
+pub mod image;
+pub mod intervals;
 pub mod machine_learning;
 pub mod math;
 pub mod navigation;
 pub mod number_theory;
 pub mod searching;
 pub mod sorting;
+pub mod statistics;
 pub mod string;
 
 #[cfg(test)]
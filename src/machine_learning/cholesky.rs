@@ -1,6 +1,17 @@
-pub fn cholesky(mat: Vec<f64>, n: usize) -> Vec<f64> {
-    if (mat.is_empty()) || (n == 0) {
-        return vec![];
+use crate::error::{AlgorithmError, AlgorithmResult};
+
+/// Computes the lower triangular factor `L` of the Cholesky decomposition `mat = L * L^T`,
+/// for a symmetric matrix of size `n * n` stored row by row.
+pub fn cholesky(mat: Vec<f64>, n: usize) -> AlgorithmResult<Vec<f64>> {
+    if mat.len() != n * n {
+        return Err(AlgorithmError::DimensionMismatch {
+            argument: "mat",
+            expected: n * n,
+            found: mat.len(),
+        });
+    }
+    if n == 0 {
+        return Ok(vec![]);
     }
     let mut res = vec![0.0; mat.len()];
     for i in 0..n {
@@ -27,7 +38,7 @@ pub fn cholesky(mat: Vec<f64>, n: usize) -> Vec<f64> {
             res[i * n + j] = value;
         }
     }
-    res
+    Ok(res)
 }
 
 #[cfg(test)]
@@ -38,7 +49,7 @@ mod tests {
     fn test_cholesky() {
         // Test case 1
         let mat1 = vec![25.0, 15.0, -5.0, 15.0, 18.0, 0.0, -5.0, 0.0, 11.0];
-        let res1 = cholesky(mat1.clone(), 3);
+        let res1 = cholesky(mat1.clone(), 3).unwrap();
 
         // The expected Cholesky decomposition values
         #[allow(clippy::useless_vec)]
@@ -85,15 +96,35 @@ mod tests {
     #[test]
     fn empty_matrix() {
         let mat = vec![];
-        let res = cholesky(mat, 0);
+        let res = cholesky(mat, 0).unwrap();
         assert_eq!(res, vec![]);
     }
 
     #[test]
     fn matrix_with_all_zeros() {
         let mat3 = vec![0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
-        let res3 = cholesky(mat3.clone(), 3);
+        let res3 = cholesky(mat3.clone(), 3).unwrap();
         let expected3 = vec![0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
         assert_eq!(res3, expected3);
     }
+
+    #[test]
+    fn mismatched_size() {
+        assert_eq!(
+            cholesky(vec![1.0, 0.0, 0.0], 2),
+            Err(AlgorithmError::DimensionMismatch {
+                argument: "mat",
+                expected: 4,
+                found: 3
+            })
+        );
+        assert_eq!(
+            cholesky(vec![], 3),
+            Err(AlgorithmError::DimensionMismatch {
+                argument: "mat",
+                expected: 9,
+                found: 0
+            })
+        );
+    }
 }
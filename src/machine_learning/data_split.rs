@@ -0,0 +1,124 @@
+use crate::error::{AlgorithmError, AlgorithmResult};
+use crate::math::sampling::{sample_without_replacement, WeightedAlias};
+use rand::Rng;
+
+/// Splits the rows of a data set into a training set and a test set, holding a random
+/// `test_fraction` of the rows (rounded to the nearest row). The training rows keep their order.
+pub fn train_test_split<T: Clone>(
+    data: &[T],
+    test_fraction: f64,
+    rng: &mut impl Rng,
+) -> AlgorithmResult<(Vec<T>, Vec<T>)> {
+    if data.is_empty() {
+        return Err(AlgorithmError::EmptyInput { argument: "data" });
+    }
+    if !(0.0..=1.0).contains(&test_fraction) {
+        return Err(AlgorithmError::InvalidParameter {
+            argument: "test_fraction",
+            reason: format!("must be between 0 and 1, got {test_fraction}"),
+        });
+    }
+    let test_size = (test_fraction * data.len() as f64).round() as usize;
+    let test_indices = sample_without_replacement(data.len(), test_size, rng)?;
+    let mut in_test = vec![false; data.len()];
+    for &index in &test_indices {
+        in_test[index] = true;
+    }
+    let train = data
+        .iter()
+        .zip(&in_test)
+        .filter(|(_, &test)| !test)
+        .map(|(row, _)| row.clone())
+        .collect();
+    let test = test_indices
+        .iter()
+        .map(|&index| data[index].clone())
+        .collect();
+    Ok((train, test))
+}
+
+/// Draws a bootstrap sample: as many rows as the data set has, uniformly with replacement.
+pub fn bootstrap_sample<T: Clone>(data: &[T], rng: &mut impl Rng) -> AlgorithmResult<Vec<T>> {
+    if data.is_empty() {
+        return Err(AlgorithmError::EmptyInput { argument: "data" });
+    }
+    Ok((0..data.len())
+        .map(|_| data[rng.gen_range(0..data.len())].clone())
+        .collect())
+}
+
+/// Draws a bootstrap sample where every row is drawn with a probability proportional to its
+/// weight, as in boosting.
+pub fn weighted_bootstrap_sample<T: Clone>(
+    data: &[T],
+    weights: &[f64],
+    rng: &mut impl Rng,
+) -> AlgorithmResult<Vec<T>> {
+    if data.len() != weights.len() {
+        return Err(AlgorithmError::DimensionMismatch {
+            argument: "weights",
+            expected: data.len(),
+            found: weights.len(),
+        });
+    }
+    let alias = WeightedAlias::new(weights)?;
+    Ok((0..data.len())
+        .map(|_| data[alias.sample(rng)].clone())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn split() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let data: Vec<usize> = (0..100).collect();
+        let (train, test) = train_test_split(&data, 0.25, &mut rng).unwrap();
+        assert_eq!((train.len(), test.len()), (75, 25));
+        assert!(train.windows(2).all(|pair| pair[0] < pair[1]));
+        let mut all: Vec<usize> = train.into_iter().chain(test).collect();
+        all.sort_unstable();
+        assert_eq!(all, data);
+
+        let (train, test) = train_test_split(&data, 0.0, &mut rng).unwrap();
+        assert_eq!((train.len(), test.len()), (100, 0));
+        assert!(train_test_split(&data, 1.5, &mut rng).is_err());
+        assert!(train_test_split::<u8>(&[], 0.5, &mut rng).is_err());
+    }
+
+    #[test]
+    fn bootstrap() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let data: Vec<usize> = (0..1000).collect();
+        let sample = bootstrap_sample(&data, &mut rng).unwrap();
+        assert_eq!(sample.len(), 1000);
+        // about 1 - 1/e = 63% of the rows are drawn at least once
+        let mut distinct = sample.clone();
+        distinct.sort_unstable();
+        distinct.dedup();
+        assert!((580..680).contains(&distinct.len()), "{}", distinct.len());
+        assert!(bootstrap_sample::<u8>(&[], &mut rng).is_err());
+    }
+
+    #[test]
+    fn weighted_bootstrap() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        let data = ["never", "often", "sometimes"];
+        let sample = weighted_bootstrap_sample(&data, &[0.0, 3.0, 1.0], &mut rng).unwrap();
+        assert_eq!(sample.len(), 3);
+        assert!(!sample.contains(&"never"));
+        assert_eq!(
+            weighted_bootstrap_sample(&data, &[1.0], &mut rng),
+            Err(AlgorithmError::DimensionMismatch {
+                argument: "weights",
+                expected: 3,
+                found: 1
+            })
+        );
+        assert!(weighted_bootstrap_sample(&data, &[0.0; 3], &mut rng).is_err());
+    }
+}
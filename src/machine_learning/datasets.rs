@@ -0,0 +1,243 @@
+//! Synthetic data sets with a known structure, to try the models on.
+//!
+//! Every generator returns the samples as rows of features, with their labels or targets apart,
+//! like the models take them, and draws them from a generator seeded with `seed`.
+
+use crate::error::{AlgorithmError, AlgorithmResult};
+use crate::math::distributions::{Distribution, Normal, Uniform};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// The rows of features of the samples, and their labels or targets
+pub type Dataset<T> = (Vec<Vec<f64>>, Vec<T>);
+
+fn check_samples(n_samples: usize) -> AlgorithmResult<()> {
+    if n_samples == 0 {
+        return Err(AlgorithmError::InvalidParameter {
+            argument: "n_samples",
+            reason: "at least one sample is needed".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Draws `n_samples` points around the centers, the sample `i` around the center
+/// `i % centers.len()` with a normal noise of standard deviation `std` on every coordinate, and
+/// returns the points with the index of their centers.
+pub fn make_blobs(
+    n_samples: usize,
+    centers: &[Vec<f64>],
+    std: f64,
+    seed: u64,
+) -> AlgorithmResult<Dataset<usize>> {
+    check_samples(n_samples)?;
+    let dimensions = centers
+        .first()
+        .ok_or(AlgorithmError::EmptyInput {
+            argument: "centers",
+        })?
+        .len();
+    if let Some(center) = centers.iter().find(|center| center.len() != dimensions) {
+        return Err(AlgorithmError::DimensionMismatch {
+            argument: "centers",
+            expected: dimensions,
+            found: center.len(),
+        });
+    }
+    let noise = Normal::new(0.0, std)?;
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let labels: Vec<usize> = (0..n_samples).map(|i| i % centers.len()).collect();
+    let points = labels
+        .iter()
+        .map(|&label| {
+            centers[label]
+                .iter()
+                .map(|x| x + noise.sample(&mut rng))
+                .collect()
+        })
+        .collect();
+    Ok((points, labels))
+}
+
+/// Draws `n_samples` points of two classes 0 and 1, from standard normal distributions whose
+/// means are `separation` apart along a random direction, the class 0 for the even samples and
+/// the class 1 for the odd ones. The classes overlap less as the separation grows.
+pub fn make_classification(
+    n_samples: usize,
+    n_features: usize,
+    separation: f64,
+    seed: u64,
+) -> AlgorithmResult<Dataset<usize>> {
+    check_samples(n_samples)?;
+    if n_features == 0 {
+        return Err(AlgorithmError::InvalidParameter {
+            argument: "n_features",
+            reason: "at least one feature is needed".to_string(),
+        });
+    }
+    if !separation.is_finite() || separation < 0.0 {
+        return Err(AlgorithmError::InvalidParameter {
+            argument: "separation",
+            reason: format!("must be finite and not negative, got {separation}"),
+        });
+    }
+    let standard = Normal::new(0.0, 1.0)?;
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let direction = standard.sample_n(n_features, &mut rng);
+    let norm = direction.iter().map(|x| x * x).sum::<f64>().sqrt();
+    let labels: Vec<usize> = (0..n_samples).map(|i| i % 2).collect();
+    let points = labels
+        .iter()
+        .map(|&label| {
+            let side = if label == 0 { -0.5 } else { 0.5 };
+            direction
+                .iter()
+                .map(|d| side * separation * d / norm + standard.sample(&mut rng))
+                .collect()
+        })
+        .collect();
+    Ok((points, labels))
+}
+
+/// Draws `n_samples` points of standard normal features, with targets that are a linear
+/// combination of the features plus a normal noise of standard deviation `noise`, and returns
+/// them with the coefficients of the combination, drawn uniformly in [-5, 5).
+pub fn make_regression(
+    n_samples: usize,
+    n_features: usize,
+    noise: f64,
+    seed: u64,
+) -> AlgorithmResult<(Dataset<f64>, Vec<f64>)> {
+    check_samples(n_samples)?;
+    if n_features == 0 {
+        return Err(AlgorithmError::InvalidParameter {
+            argument: "n_features",
+            reason: "at least one feature is needed".to_string(),
+        });
+    }
+    if !noise.is_finite() || noise < 0.0 {
+        return Err(AlgorithmError::InvalidParameter {
+            argument: "noise",
+            reason: format!("must be finite and not negative, got {noise}"),
+        });
+    }
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let coefficients = Uniform::new(-5.0, 5.0)?.sample_n(n_features, &mut rng);
+    let standard = Normal::new(0.0, 1.0)?;
+    let features: Vec<Vec<f64>> = (0..n_samples)
+        .map(|_| standard.sample_n(n_features, &mut rng))
+        .collect();
+    let targets = features
+        .iter()
+        .map(|row| {
+            let signal: f64 = row.iter().zip(&coefficients).map(|(x, c)| x * c).sum();
+            signal + noise * standard.sample(&mut rng)
+        })
+        .collect();
+    Ok(((features, targets), coefficients))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::machine_learning::{ActivationFunction, Dbscan, KnnClassifier, Perceptron};
+    use std::collections::HashMap;
+
+    fn distance(a: &[f64], b: &[f64]) -> f64 {
+        a.iter()
+            .zip(b)
+            .map(|(x, y)| (x - y).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    #[test]
+    fn blobs_around_their_centers() {
+        let centers = vec![vec![0.0, 0.0], vec![10.0, 0.0], vec![0.0, 10.0]];
+        let (points, labels) = make_blobs(300, &centers, 1.0, 1298).unwrap();
+        assert_eq!((points.len(), labels.len()), (300, 300));
+        assert!(points.iter().all(|point| point.len() == 2));
+        // every point is closest to its own center
+        for (point, &label) in points.iter().zip(&labels) {
+            let nearest = (0..centers.len())
+                .min_by(|&a, &b| {
+                    distance(point, &centers[a]).total_cmp(&distance(point, &centers[b]))
+                })
+                .unwrap();
+            assert_eq!(nearest, label);
+        }
+        // and DBSCAN finds the same clusters, up to their numbering and a few outliers
+        let clusters = Dbscan::new(2.0, 5).unwrap().fit(&points).unwrap();
+        let mut numbering = HashMap::new();
+        for (&cluster, &label) in clusters.iter().zip(&labels) {
+            if let Some(cluster) = cluster {
+                assert_eq!(*numbering.entry(cluster).or_insert(label), label);
+            }
+        }
+        assert_eq!(numbering.len(), 3);
+        assert!(clusters.iter().filter(|cluster| cluster.is_none()).count() < 10);
+        // the same seed gives the same data
+        assert_eq!(make_blobs(300, &centers, 1.0, 1298).unwrap().0, points);
+    }
+
+    #[test]
+    fn separated_classes() {
+        let (mut points, mut labels) = make_classification(500, 5, 8.0, 1299).unwrap();
+        assert!(points.iter().all(|point| point.len() == 5));
+        assert_eq!(labels.iter().filter(|&&label| label == 1).count(), 250);
+        let (test_points, test_labels) = (points.split_off(400), labels.split_off(400));
+        let knn = KnnClassifier::new(5, points, labels).unwrap();
+        let correct = test_points
+            .iter()
+            .zip(&test_labels)
+            .filter(|(point, &label)| knn.predict(point).unwrap() == label)
+            .count();
+        assert!(correct >= 95, "{correct}");
+    }
+
+    #[test]
+    fn perceptron_recovers_coefficients() {
+        let ((features, targets), coefficients) = make_regression(500, 3, 0.0, 12910).unwrap();
+        assert!(coefficients.iter().all(|c| (-5.0..5.0).contains(c)));
+        let rows: Vec<Vec<f64>> = features
+            .iter()
+            .zip(&targets)
+            .map(|(row, &target)| row.iter().copied().chain([target]).collect())
+            .collect();
+        let mut perceptron =
+            Perceptron::with_weights(vec![0.0; 3], 0.01, ActivationFunction::None).unwrap();
+        perceptron
+            .train_with_rng(&rows, 30, &mut ChaCha8Rng::seed_from_u64(12911))
+            .unwrap();
+        for (row, &target) in features.iter().zip(&targets).take(20) {
+            assert!((perceptron.predict(row).unwrap() - target).abs() < 1e-3);
+        }
+        // the predictions of the unit vectors are the learned weights
+        for (i, &coefficient) in coefficients.iter().enumerate() {
+            let mut unit = vec![0.0; 3];
+            unit[i] = 1.0;
+            assert!((perceptron.predict(&unit).unwrap() - coefficient).abs() < 1e-3);
+        }
+        // the noise moves the targets by about its standard deviation
+        let ((_, noisy), _) = make_regression(500, 3, 2.0, 12910).unwrap();
+        let spread = noisy
+            .iter()
+            .zip(&targets)
+            .map(|(a, b)| (a - b).powi(2))
+            .sum::<f64>()
+            / 500.0;
+        assert!((spread.sqrt() - 2.0).abs() < 0.3, "{spread}");
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        assert!(make_blobs(0, &[vec![0.0]], 1.0, 0).is_err());
+        assert!(make_blobs(10, &[], 1.0, 0).is_err());
+        assert!(make_blobs(10, &[vec![0.0], vec![0.0, 1.0]], 1.0, 0).is_err());
+        assert!(make_blobs(10, &[vec![0.0]], 0.0, 0).is_err());
+        assert!(make_classification(10, 0, 1.0, 0).is_err());
+        assert!(make_classification(10, 2, -1.0, 0).is_err());
+        assert!(make_regression(10, 2, f64::NAN, 0).is_err());
+        assert!(make_regression(0, 2, 0.0, 0).is_err());
+    }
+}
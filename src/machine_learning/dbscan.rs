@@ -0,0 +1,245 @@
+use super::NeighborSearch;
+use crate::data_structures::{squared_distance, KdTree};
+use crate::error::{check_positive, check_rows, AlgorithmError, AlgorithmResult};
+
+/// Density-based spatial clustering of applications with noise
+/// <https://en.wikipedia.org/wiki/DBSCAN>
+///
+/// A point is a core point if at least `min_samples` points, itself included, lie within
+/// `epsilon` of it. The clusters are the groups of core points connected through each other's
+/// neighborhoods, along with the other points in their neighborhoods, and the points in no
+/// cluster are noise. Unlike k-means, the clusters may have any shape, and their number is
+/// discovered from the data.
+///
+/// The neighborhoods are found by comparing every pair of points, in O(n^2 d) for n points of d
+/// dimensions, or with radius queries on a k-d tree, which is much faster for large data sets
+/// of few dimensions. Both find the same clusters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dbscan {
+    epsilon: f64,
+    min_samples: usize,
+    search: NeighborSearch,
+    /// The cluster of every point of the last fit, `None` for the noise
+    labels: Vec<Option<usize>>,
+    core: Vec<bool>,
+}
+
+impl Dbscan {
+    pub fn new(epsilon: f64, min_samples: usize) -> AlgorithmResult<Self> {
+        Self::with_search(epsilon, min_samples, NeighborSearch::Auto)
+    }
+
+    /// Creates a clustering finding the neighborhoods of the points with `search`.
+    pub fn with_search(
+        epsilon: f64,
+        min_samples: usize,
+        search: NeighborSearch,
+    ) -> AlgorithmResult<Self> {
+        check_positive("epsilon", epsilon)?;
+        if min_samples == 0 {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "min_samples",
+                reason: "at least one sample is needed".to_string(),
+            });
+        }
+        Ok(Dbscan {
+            epsilon,
+            min_samples,
+            search,
+            labels: vec![],
+            core: vec![],
+        })
+    }
+
+    /// Clusters the rows of `data`, and returns the cluster of every row, `None` for the noise.
+    ///
+    /// The clusters are numbered in the order of their first core point. A border point within
+    /// `epsilon` of the core points of several clusters goes to the first one reaching it.
+    pub fn fit(&mut self, data: &[Vec<f64>]) -> AlgorithmResult<Vec<Option<usize>>> {
+        check_rows("data", data)?;
+        let neighbors = self.neighborhoods(data)?;
+        self.core = neighbors
+            .iter()
+            .map(|n| n.len() >= self.min_samples)
+            .collect();
+        self.labels = vec![None; data.len()];
+        let mut clusters = 0;
+        for start in 0..data.len() {
+            if !self.core[start] || self.labels[start].is_some() {
+                continue;
+            }
+            self.labels[start] = Some(clusters);
+            let mut stack = vec![start];
+            while let Some(point) = stack.pop() {
+                for &neighbor in &neighbors[point] {
+                    if self.labels[neighbor].is_none() {
+                        self.labels[neighbor] = Some(clusters);
+                        if self.core[neighbor] {
+                            stack.push(neighbor);
+                        }
+                    }
+                }
+            }
+            clusters += 1;
+        }
+        Ok(self.labels.clone())
+    }
+
+    /// Returns the points within `epsilon` of every point, in increasing order.
+    fn neighborhoods(&self, data: &[Vec<f64>]) -> AlgorithmResult<Vec<Vec<usize>>> {
+        if self.search.uses_tree(data.len()) {
+            let tree = KdTree::new(data.to_vec())?;
+            return data
+                .iter()
+                .map(|point| {
+                    let mut neighbors: Vec<usize> = tree
+                        .within_radius(point, self.epsilon)?
+                        .into_iter()
+                        .map(|(index, _)| index)
+                        .collect();
+                    neighbors.sort_unstable();
+                    Ok(neighbors)
+                })
+                .collect();
+        }
+        let squared_epsilon = self.epsilon * self.epsilon;
+        Ok(data
+            .iter()
+            .map(|a| {
+                (0..data.len())
+                    .filter(|&j| squared_distance(a, &data[j]) <= squared_epsilon)
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// Returns the indices of the core points of the last fit.
+    pub fn core_points(&self) -> Vec<usize> {
+        (0..self.core.len()).filter(|&i| self.core[i]).collect()
+    }
+
+    /// Returns the indices of the points in no cluster in the last fit.
+    pub fn noise_points(&self) -> Vec<usize> {
+        (0..self.labels.len())
+            .filter(|&i| self.labels[i].is_none())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::machine_learning::k_means;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    /// Two concentric rings of radii 1 and 5, with 40 and 100 evenly spaced points
+    fn rings() -> Vec<Vec<f64>> {
+        let ring = |radius: f64, count: usize| {
+            (0..count).map(move |i| {
+                let angle = std::f64::consts::TAU * i as f64 / count as f64;
+                vec![radius * angle.cos(), radius * angle.sin()]
+            })
+        };
+        ring(1.0, 40).chain(ring(5.0, 100)).collect()
+    }
+
+    #[test]
+    fn separates_concentric_rings() {
+        let data = rings();
+        let mut dbscan = Dbscan::new(0.5, 3).unwrap();
+        let labels = dbscan.fit(&data).unwrap();
+        assert!(labels[..40].iter().all(|&label| label == Some(0)));
+        assert!(labels[40..].iter().all(|&label| label == Some(1)));
+        assert_eq!(dbscan.core_points().len(), 140);
+        assert!(dbscan.noise_points().is_empty());
+
+        // the clusters of k-means are separated by a line, which cannot split the rings
+        let points = data.iter().map(|p| (p[0], p[1])).collect();
+        let k_labels = k_means(points, 2, 100).unwrap();
+        assert!(k_labels[..40].iter().any(|&l| k_labels[40..].contains(&l)));
+    }
+
+    #[test]
+    fn tree_agrees_with_brute_force() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1214);
+        let mut data = rings();
+        // noise around the rings, and points on the exact distance of others
+        data.extend((0..300).map(|_| vec![rng.gen_range(-7.0..7.0), rng.gen_range(-7.0..7.0)]));
+        data.extend((0..20).map(|i| vec![10.0 + 0.5 * i as f64, 10.0]));
+        for (epsilon, min_samples) in [(0.5, 3), (0.5, 5), (1.0, 4), (0.3, 2)] {
+            let mut brute_force =
+                Dbscan::with_search(epsilon, min_samples, NeighborSearch::BruteForce).unwrap();
+            let mut tree =
+                Dbscan::with_search(epsilon, min_samples, NeighborSearch::KdTree).unwrap();
+            assert_eq!(tree.fit(&data), brute_force.fit(&data));
+            assert_eq!(tree.core_points(), brute_force.core_points());
+        }
+    }
+
+    #[test]
+    fn epsilon_changes_the_clusters() {
+        // two pairs of points 1 apart, 3 from each other, and an outlier
+        let data = vec![
+            vec![0.0, 0.0],
+            vec![1.0, 0.0],
+            vec![4.0, 0.0],
+            vec![5.0, 0.0],
+            vec![20.0, 20.0],
+        ];
+        let mut dbscan = Dbscan::new(0.5, 2).unwrap();
+        assert_eq!(dbscan.fit(&data).unwrap(), [None; 5]);
+        assert_eq!(dbscan.noise_points(), [0, 1, 2, 3, 4]);
+        assert!(dbscan.core_points().is_empty());
+
+        let mut dbscan = Dbscan::new(1.0, 2).unwrap();
+        assert_eq!(
+            dbscan.fit(&data).unwrap(),
+            [Some(0), Some(0), Some(1), Some(1), None]
+        );
+        assert_eq!(dbscan.noise_points(), [4]);
+
+        let mut dbscan = Dbscan::new(3.0, 2).unwrap();
+        assert_eq!(
+            dbscan.fit(&data).unwrap(),
+            [Some(0), Some(0), Some(0), Some(0), None]
+        );
+    }
+
+    #[test]
+    fn border_points() {
+        // only the middle point has three points around it, the ends are border points
+        let data = vec![vec![0.0], vec![1.0], vec![2.0], vec![3.5]];
+        let mut dbscan = Dbscan::new(1.0, 3).unwrap();
+        assert_eq!(
+            dbscan.fit(&data).unwrap(),
+            [Some(0), Some(0), Some(0), None]
+        );
+        assert_eq!(dbscan.core_points(), [1]);
+        assert_eq!(dbscan.noise_points(), [3]);
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert!(matches!(
+            Dbscan::new(0.0, 3),
+            Err(AlgorithmError::InvalidParameter {
+                argument: "epsilon",
+                ..
+            })
+        ));
+        assert!(matches!(
+            Dbscan::new(1.0, 0),
+            Err(AlgorithmError::InvalidParameter {
+                argument: "min_samples",
+                ..
+            })
+        ));
+        let mut dbscan = Dbscan::new(1.0, 2).unwrap();
+        assert_eq!(
+            dbscan.fit(&[]),
+            Err(AlgorithmError::EmptyInput { argument: "data" })
+        );
+        assert!(dbscan.core_points().is_empty());
+    }
+}
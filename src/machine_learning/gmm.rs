@@ -0,0 +1,444 @@
+use crate::error::{check_rows, AlgorithmError, AlgorithmResult};
+use crate::math::sampling::weighted_choice;
+use nalgebra::{DMatrix, DVector};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::f64::consts::PI;
+
+/// Added to the diagonal of the covariances, so that they stay invertible when a component
+/// collapses onto a few points
+const REGULARIZATION: f64 = 1e-6;
+
+/// A Gaussian mixture model fitted with the expectation-maximization algorithm
+/// <https://en.wikipedia.org/wiki/Mixture_model#Expectation_maximization_(EM)>
+///
+/// The data is modeled as drawn from one of several Gaussian distributions, each with its own
+/// mean, full covariance matrix and mixing weight. Starting from means picked like k-means++,
+/// every iteration computes the probability that each point comes from each component (the
+/// E-step, with Bayes' theorem), then the weights, means and covariances maximizing the
+/// likelihood of the data given these responsibilities (the M-step). Unlike k-means, the
+/// clusters can have different shapes and sizes, and every point gets a probability of
+/// belonging to each of them.
+pub struct GaussianMixture {
+    n_components: usize,
+    max_iterations: usize,
+    tolerance: f64,
+    seed: u64,
+    weights: Vec<f64>,
+    means: Vec<DVector<f64>>,
+    covariances: Vec<DMatrix<f64>>,
+    converged: bool,
+}
+
+/// The Cholesky factor of a covariance, to evaluate the log-density of its component
+struct Density {
+    mean: DVector<f64>,
+    lower: DMatrix<f64>,
+    log_normalization: f64,
+}
+
+impl Density {
+    fn new(mean: &DVector<f64>, covariance: &DMatrix<f64>) -> AlgorithmResult<Self> {
+        let lower = covariance
+            .clone()
+            .cholesky()
+            .ok_or(AlgorithmError::SingularMatrix { argument: "data" })?
+            .unpack();
+        let log_determinant: f64 = 2.0 * lower.diagonal().iter().map(|x| x.ln()).sum::<f64>();
+        let dimensions = mean.len() as f64;
+        Ok(Density {
+            mean: mean.clone(),
+            lower,
+            log_normalization: -0.5 * (dimensions * (2.0 * PI).ln() + log_determinant),
+        })
+    }
+
+    fn log_pdf(&self, x: &DVector<f64>) -> f64 {
+        // the Mahalanobis distance is |L^-1 (x - mean)|^2 for the covariance L L^T
+        let centered = x - &self.mean;
+        let solved = self
+            .lower
+            .solve_lower_triangular(&centered)
+            .expect("the Cholesky factor has a positive diagonal");
+        self.log_normalization - 0.5 * solved.norm_squared()
+    }
+}
+
+fn log_sum_exp(values: &[f64]) -> f64 {
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    max + values.iter().map(|v| (v - max).exp()).sum::<f64>().ln()
+}
+
+impl GaussianMixture {
+    /// Creates an unfitted mixture of `n_components` Gaussians. Fitting stops when the
+    /// log-likelihood of the data improves by less than `tolerance`, or after `max_iterations`
+    /// iterations.
+    pub fn new(
+        n_components: usize,
+        max_iterations: usize,
+        tolerance: f64,
+    ) -> AlgorithmResult<Self> {
+        if n_components == 0 {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "n_components",
+                reason: "at least one component is needed".to_string(),
+            });
+        }
+        if !tolerance.is_finite() || tolerance < 0.0 {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "tolerance",
+                reason: format!("must be finite and not negative, got {tolerance}"),
+            });
+        }
+        Ok(GaussianMixture {
+            n_components,
+            max_iterations,
+            tolerance,
+            seed: 0,
+            weights: vec![],
+            means: vec![],
+            covariances: vec![],
+            converged: false,
+        })
+    }
+
+    /// Sets the seed of the random choice of the initial means.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Returns the mixing weights of the components, summing to 1.
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// Returns the means of the components.
+    pub fn means(&self) -> Vec<Vec<f64>> {
+        self.means
+            .iter()
+            .map(|mean| mean.iter().copied().collect())
+            .collect()
+    }
+
+    /// Returns whether the last fit stopped because the log-likelihood converged, rather than
+    /// after `max_iterations` iterations.
+    pub fn converged(&self) -> bool {
+        self.converged
+    }
+
+    /// Fits the mixture to the rows of `data`, which needs at least one row per component.
+    pub fn fit(&mut self, data: &[Vec<f64>]) -> AlgorithmResult<()> {
+        let dimensions = check_rows("data", data)?;
+        if data.len() < self.n_components {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "n_components",
+                reason: format!(
+                    "cannot fit {} components to {} points",
+                    self.n_components,
+                    data.len()
+                ),
+            });
+        }
+        let points: Vec<DVector<f64>> = data
+            .iter()
+            .map(|row| DVector::from_column_slice(row))
+            .collect();
+        self.initialize(&points, dimensions);
+        self.converged = false;
+        let mut log_likelihood = f64::NEG_INFINITY;
+        for _ in 0..self.max_iterations {
+            let (responsibilities, new_log_likelihood) = self.expectation(&points)?;
+            self.maximization(&points, &responsibilities);
+            let change = new_log_likelihood - log_likelihood;
+            log_likelihood = new_log_likelihood;
+            if change.abs() < self.tolerance {
+                self.converged = true;
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Picks the initial means among the points like k-means++, every next one with a
+    /// probability proportional to the squared distance to the nearest mean picked so far. All
+    /// components start with the covariance of the whole data set.
+    fn initialize(&mut self, points: &[DVector<f64>], dimensions: usize) {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        let n = points.len();
+        let mut means = vec![points[rng.gen_range(0..n)].clone()];
+        while means.len() < self.n_components {
+            let distances: Vec<f64> = points
+                .iter()
+                .map(|point| {
+                    means
+                        .iter()
+                        .map(|mean| (point - mean).norm_squared())
+                        .fold(f64::INFINITY, f64::min)
+                })
+                .collect();
+            // all points are already picked when all distances are 0
+            let index =
+                weighted_choice(&distances, &mut rng).unwrap_or_else(|_| rng.gen_range(0..n));
+            means.push(points[index].clone());
+        }
+        let overall_mean = points.iter().sum::<DVector<f64>>() / n as f64;
+        let covariance = points
+            .iter()
+            .map(|point| {
+                let centered = point - &overall_mean;
+                &centered * centered.transpose()
+            })
+            .sum::<DMatrix<f64>>()
+            / n as f64
+            + DMatrix::identity(dimensions, dimensions) * REGULARIZATION;
+        self.weights = vec![1.0 / self.n_components as f64; self.n_components];
+        self.means = means;
+        self.covariances = vec![covariance; self.n_components];
+    }
+
+    fn densities(&self) -> AlgorithmResult<Vec<Density>> {
+        self.means
+            .iter()
+            .zip(&self.covariances)
+            .map(|(mean, covariance)| Density::new(mean, covariance))
+            .collect()
+    }
+
+    /// Returns the logarithms of `weight * pdf(x)` for every component.
+    fn weighted_log_pdfs(&self, densities: &[Density], x: &DVector<f64>) -> Vec<f64> {
+        densities
+            .iter()
+            .zip(&self.weights)
+            .map(|(density, weight)| weight.ln() + density.log_pdf(x))
+            .collect()
+    }
+
+    /// The E-step: returns the responsibilities of the components for every point, and the
+    /// log-likelihood of the data.
+    fn expectation(&self, points: &[DVector<f64>]) -> AlgorithmResult<(Vec<Vec<f64>>, f64)> {
+        let densities = self.densities()?;
+        let mut log_likelihood = 0.0;
+        let responsibilities = points
+            .iter()
+            .map(|point| {
+                let log_pdfs = self.weighted_log_pdfs(&densities, point);
+                let total = log_sum_exp(&log_pdfs);
+                log_likelihood += total;
+                log_pdfs.iter().map(|p| (p - total).exp()).collect()
+            })
+            .collect();
+        Ok((responsibilities, log_likelihood))
+    }
+
+    /// The M-step: updates the weights, means and covariances from the responsibilities.
+    fn maximization(&mut self, points: &[DVector<f64>], responsibilities: &[Vec<f64>]) {
+        let dimensions = points[0].len();
+        for k in 0..self.n_components {
+            // kept away from 0, for a component no point is responsible for
+            let total: f64 =
+                responsibilities.iter().map(|r| r[k]).sum::<f64>() + 10.0 * f64::EPSILON;
+            let mean = points
+                .iter()
+                .zip(responsibilities)
+                .map(|(point, r)| point * r[k])
+                .sum::<DVector<f64>>()
+                / total;
+            let covariance = points
+                .iter()
+                .zip(responsibilities)
+                .map(|(point, r)| {
+                    let centered = point - &mean;
+                    &centered * centered.transpose() * r[k]
+                })
+                .sum::<DMatrix<f64>>()
+                / total
+                + DMatrix::identity(dimensions, dimensions) * REGULARIZATION;
+            self.weights[k] = total / points.len() as f64;
+            self.means[k] = mean;
+            self.covariances[k] = covariance;
+        }
+    }
+
+    fn check_fitted(&self, argument: &'static str, x: &[f64]) -> AlgorithmResult<()> {
+        if self.means.is_empty() {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "self",
+                reason: "the mixture is not fitted".to_string(),
+            });
+        }
+        if x.len() != self.means[0].len() {
+            return Err(AlgorithmError::DimensionMismatch {
+                argument,
+                expected: self.means[0].len(),
+                found: x.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns the probability that `x` comes from each of the components.
+    pub fn predict_proba(&self, x: &[f64]) -> AlgorithmResult<Vec<f64>> {
+        self.check_fitted("x", x)?;
+        let log_pdfs = self.weighted_log_pdfs(&self.densities()?, &DVector::from_column_slice(x));
+        let total = log_sum_exp(&log_pdfs);
+        Ok(log_pdfs.iter().map(|p| (p - total).exp()).collect())
+    }
+
+    /// Returns the log-likelihood of `data` under the mixture.
+    pub fn log_likelihood(&self, data: &[Vec<f64>]) -> AlgorithmResult<f64> {
+        check_rows("data", data)?;
+        self.check_fitted("data", &data[0])?;
+        let densities = self.densities()?;
+        Ok(data
+            .iter()
+            .map(|row| {
+                log_sum_exp(&self.weighted_log_pdfs(&densities, &DVector::from_column_slice(row)))
+            })
+            .sum())
+    }
+
+    /// Returns the Bayesian information criterion of the mixture on `data`:
+    /// `p ln(n) - 2 ln(L)` for `p` free parameters, `n` points and the likelihood `L`. Among
+    /// mixtures with different numbers of components, the one with the lowest criterion
+    /// balances best how well it fits against how many parameters it needs.
+    pub fn bic(&self, data: &[Vec<f64>]) -> AlgorithmResult<f64> {
+        let log_likelihood = self.log_likelihood(data)?;
+        let (k, d) = (self.n_components, self.means[0].len());
+        let parameters = k * d + k * d * (d + 1) / 2 + k - 1;
+        Ok(parameters as f64 * (data.len() as f64).ln() - 2.0 * log_likelihood)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Points drawn from a Gaussian with the given mean and standard deviation, with the
+    /// Box-Muller transform
+    fn gaussian_points(
+        rng: &mut ChaCha8Rng,
+        mean: &[f64],
+        deviation: f64,
+        count: usize,
+    ) -> Vec<Vec<f64>> {
+        (0..count)
+            .map(|_| {
+                mean.iter()
+                    .map(|m| {
+                        let (u, v): (f64, f64) = (1.0 - rng.gen::<f64>(), rng.gen());
+                        m + deviation * (-2.0 * u.ln()).sqrt() * (2.0 * PI * v).cos()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn two_blobs() -> Vec<Vec<f64>> {
+        let mut rng = ChaCha8Rng::seed_from_u64(112);
+        let mut data = gaussian_points(&mut rng, &[0.0, 0.0], 1.0, 300);
+        data.extend(gaussian_points(&mut rng, &[8.0, 6.0], 0.5, 200));
+        data
+    }
+
+    #[test]
+    fn recovers_two_separated_gaussians() {
+        let data = two_blobs();
+        let mut gmm = GaussianMixture::new(2, 200, 1e-8).unwrap().with_seed(1);
+        gmm.fit(&data).unwrap();
+        assert!(gmm.converged());
+        let mut components: Vec<(Vec<f64>, f64)> = gmm
+            .means()
+            .into_iter()
+            .zip(gmm.weights().to_vec())
+            .collect();
+        components.sort_by(|a, b| a.0[0].total_cmp(&b.0[0]));
+        for ((mean, weight), (true_mean, true_weight)) in components
+            .iter()
+            .zip([([0.0, 0.0], 0.6), ([8.0, 6.0], 0.4)])
+        {
+            assert!((mean[0] - true_mean[0]).abs() < 0.2, "{mean:?}");
+            assert!((mean[1] - true_mean[1]).abs() < 0.2, "{mean:?}");
+            assert!((weight - true_weight).abs() < 0.01, "{weight}");
+        }
+
+        let near_origin = gmm.predict_proba(&[0.5, -0.5]).unwrap();
+        assert!((near_origin.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        let origin_component = if components[0].0 == gmm.means()[0] {
+            0
+        } else {
+            1
+        };
+        assert!(near_origin[origin_component] > 0.999);
+        let far = gmm.predict_proba(&[8.0, 6.5]).unwrap();
+        assert!(far[1 - origin_component] > 0.999);
+    }
+
+    #[test]
+    fn bic_prefers_the_right_number_of_components() {
+        let data = two_blobs();
+        let bic = |n_components| {
+            let mut gmm = GaussianMixture::new(n_components, 200, 1e-8).unwrap();
+            gmm.fit(&data).unwrap();
+            gmm.bic(&data).unwrap()
+        };
+        let (one, two, three) = (bic(1), bic(2), bic(3));
+        assert!(two < one, "{two} >= {one}");
+        assert!(two < three, "{two} >= {three}");
+    }
+
+    #[test]
+    fn single_component_is_the_sample_distribution() {
+        let data = vec![vec![1.0], vec![2.0], vec![3.0], vec![6.0]];
+        let mut gmm = GaussianMixture::new(1, 10, 1e-10).unwrap();
+        gmm.fit(&data).unwrap();
+        assert!((gmm.weights()[0] - 1.0).abs() < 1e-12);
+        assert!((gmm.means()[0][0] - 3.0).abs() < 1e-12);
+        // the variance is 3.5, so the log-likelihood is -n/2 (ln(2 pi 3.5) + 1)
+        let expected = -2.0 * ((2.0 * PI * 3.5).ln() + 1.0);
+        assert!((gmm.log_likelihood(&data).unwrap() - expected).abs() < 1e-5);
+    }
+
+    #[test]
+    fn duplicate_points_do_not_break_the_fit() {
+        let data = vec![vec![1.0, 1.0]; 5];
+        let mut gmm = GaussianMixture::new(2, 20, 1e-6).unwrap();
+        gmm.fit(&data).unwrap();
+        assert!(gmm.means().iter().flatten().all(|x| (x - 1.0).abs() < 1e-9));
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert!(GaussianMixture::new(0, 10, 1e-3).is_err());
+        assert!(GaussianMixture::new(2, 10, -1.0).is_err());
+        let mut gmm = GaussianMixture::new(3, 10, 1e-3).unwrap();
+        assert_eq!(
+            gmm.predict_proba(&[1.0]),
+            Err(AlgorithmError::InvalidParameter {
+                argument: "self",
+                reason: "the mixture is not fitted".to_string(),
+            })
+        );
+        assert_eq!(
+            gmm.fit(&[]),
+            Err(AlgorithmError::EmptyInput { argument: "data" })
+        );
+        assert!(matches!(
+            gmm.fit(&[vec![1.0], vec![2.0]]),
+            Err(AlgorithmError::InvalidParameter {
+                argument: "n_components",
+                ..
+            })
+        ));
+        gmm.fit(&[vec![1.0, 0.0], vec![2.0, 1.0], vec![5.0, 3.0]])
+            .unwrap();
+        assert_eq!(
+            gmm.predict_proba(&[1.0]),
+            Err(AlgorithmError::DimensionMismatch {
+                argument: "x",
+                expected: 2,
+                found: 1
+            })
+        );
+    }
+}
@@ -0,0 +1,554 @@
+use crate::error::{AlgorithmError, AlgorithmResult};
+
+/// The largest difference from 1 accepted for the sum of a probability distribution
+const TOLERANCE: f64 = 1e-6;
+
+/// A hidden Markov model with discrete observations
+/// <https://en.wikipedia.org/wiki/Hidden_Markov_model>
+///
+/// The model moves between hidden states following a Markov chain, the first state drawn from
+/// `initial` and every next one from the row of `transition` of the current state. In every
+/// state, an observation is drawn from the row of `emission` of that state. Only the
+/// observations are seen: the model answers how likely they are, and which states most likely
+/// produced them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HiddenMarkovModel {
+    /// `transition[i][j]` is the probability to move from state `i` to state `j`
+    transition: Vec<Vec<f64>>,
+    /// `emission[i][k]` is the probability to observe `k` in state `i`
+    emission: Vec<Vec<f64>>,
+    initial: Vec<f64>,
+}
+
+fn check_distribution(argument: &'static str, distribution: &[f64]) -> AlgorithmResult<()> {
+    if distribution.iter().any(|p| !p.is_finite() || *p < 0.0)
+        || (distribution.iter().sum::<f64>() - 1.0).abs() > TOLERANCE
+    {
+        return Err(AlgorithmError::InvalidParameter {
+            argument,
+            reason: format!("{distribution:?} is not a probability distribution"),
+        });
+    }
+    Ok(())
+}
+
+fn check_matrix(
+    argument: &'static str,
+    matrix: &[Vec<f64>],
+    rows: usize,
+    columns: usize,
+) -> AlgorithmResult<()> {
+    if matrix.len() != rows {
+        return Err(AlgorithmError::DimensionMismatch {
+            argument,
+            expected: rows,
+            found: matrix.len(),
+        });
+    }
+    for row in matrix {
+        if row.len() != columns {
+            return Err(AlgorithmError::DimensionMismatch {
+                argument,
+                expected: columns,
+                found: row.len(),
+            });
+        }
+        check_distribution(argument, row)?;
+    }
+    Ok(())
+}
+
+/// The scaled forward and backward variables of a sequence of observations
+struct ForwardBackward {
+    /// `alpha[t][i]` is the probability of state `i` at time `t` given the observations up to
+    /// `t`
+    alpha: Vec<Vec<f64>>,
+    /// `beta[t][i]` is the probability of the observations after `t` given state `i` at time
+    /// `t`, divided by the scales after `t`
+    beta: Vec<Vec<f64>>,
+    /// `scales[t]` is the probability of observation `t` given the ones before
+    scales: Vec<f64>,
+}
+
+impl HiddenMarkovModel {
+    /// Creates a model with `n_states` hidden states and `n_observations` possible
+    /// observations. The rows of `transition` and `emission`, and `initial`, are probability
+    /// distributions.
+    pub fn new(
+        n_states: usize,
+        n_observations: usize,
+        transition: Vec<Vec<f64>>,
+        emission: Vec<Vec<f64>>,
+        initial: Vec<f64>,
+    ) -> AlgorithmResult<Self> {
+        if n_states == 0 {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "n_states",
+                reason: "at least one state is needed".to_string(),
+            });
+        }
+        if n_observations == 0 {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "n_observations",
+                reason: "at least one observation is needed".to_string(),
+            });
+        }
+        check_matrix("transition", &transition, n_states, n_states)?;
+        check_matrix("emission", &emission, n_states, n_observations)?;
+        if initial.len() != n_states {
+            return Err(AlgorithmError::DimensionMismatch {
+                argument: "initial",
+                expected: n_states,
+                found: initial.len(),
+            });
+        }
+        check_distribution("initial", &initial)?;
+        Ok(HiddenMarkovModel {
+            transition,
+            emission,
+            initial,
+        })
+    }
+
+    pub fn transition(&self) -> &[Vec<f64>] {
+        &self.transition
+    }
+
+    pub fn emission(&self) -> &[Vec<f64>] {
+        &self.emission
+    }
+
+    pub fn initial(&self) -> &[f64] {
+        &self.initial
+    }
+
+    fn n_states(&self) -> usize {
+        self.initial.len()
+    }
+
+    fn check_observations(
+        &self,
+        argument: &'static str,
+        observations: &[usize],
+    ) -> AlgorithmResult<()> {
+        let n_observations = self.emission[0].len();
+        match observations.iter().find(|&&o| o >= n_observations) {
+            Some(o) => Err(AlgorithmError::InvalidParameter {
+                argument,
+                reason: format!("{o} is not one of the {n_observations} observations"),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the most likely sequence of states to produce `observations`, with the
+    /// logarithm of the joint probability of the states and the observations, with the Viterbi
+    /// algorithm <https://en.wikipedia.org/wiki/Viterbi_algorithm>
+    ///
+    /// For every time and state, it keeps the most likely path ending in that state, and the
+    /// state before it: the best path ending in a state at time `t + 1` extends one of the best
+    /// paths at time `t`. This takes O(T n^2) for T observations and n states. The computation
+    /// is done with logarithms, so that long sequences do not underflow. If no sequence of
+    /// states can produce the observations, the probability is 0 and its logarithm -inf.
+    pub fn viterbi(&self, observations: &[usize]) -> AlgorithmResult<(f64, Vec<usize>)> {
+        self.check_observations("observations", observations)?;
+        let Some((&first, rest)) = observations.split_first() else {
+            return Ok((0.0, vec![]));
+        };
+        let n = self.n_states();
+        let mut scores: Vec<f64> = (0..n)
+            .map(|i| self.initial[i].ln() + self.emission[i][first].ln())
+            .collect();
+        let mut back_pointers: Vec<Vec<usize>> = Vec::with_capacity(rest.len());
+        for &observation in rest {
+            let mut next_scores = vec![f64::NEG_INFINITY; n];
+            let mut pointers = vec![0; n];
+            for j in 0..n {
+                for (i, previous) in scores.iter().enumerate() {
+                    let score = previous + self.transition[i][j].ln();
+                    if score > next_scores[j] {
+                        next_scores[j] = score;
+                        pointers[j] = i;
+                    }
+                }
+                next_scores[j] += self.emission[j][observation].ln();
+            }
+            scores = next_scores;
+            back_pointers.push(pointers);
+        }
+        let (mut state, &log_probability) = scores
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .unwrap();
+        let mut path = vec![state];
+        for pointers in back_pointers.iter().rev() {
+            state = pointers[state];
+            path.push(state);
+        }
+        path.reverse();
+        Ok((log_probability, path))
+    }
+
+    /// Runs the forward and backward recursions, scaling the forward variables at every step
+    /// so that they sum to 1. Returns `None` if the observations have probability 0.
+    fn forward_backward(&self, observations: &[usize]) -> Option<ForwardBackward> {
+        let n = self.n_states();
+        let mut alpha: Vec<Vec<f64>> = Vec::with_capacity(observations.len());
+        let mut scales = Vec::with_capacity(observations.len());
+        for (t, &observation) in observations.iter().enumerate() {
+            let mut current: Vec<f64> = (0..n)
+                .map(|j| {
+                    let prior = if t == 0 {
+                        self.initial[j]
+                    } else {
+                        (0..n)
+                            .map(|i| alpha[t - 1][i] * self.transition[i][j])
+                            .sum()
+                    };
+                    prior * self.emission[j][observation]
+                })
+                .collect();
+            let scale: f64 = current.iter().sum();
+            if scale == 0.0 {
+                return None;
+            }
+            current.iter_mut().for_each(|a| *a /= scale);
+            alpha.push(current);
+            scales.push(scale);
+        }
+        let mut beta = vec![vec![1.0; n]; observations.len()];
+        for t in (0..observations.len().saturating_sub(1)).rev() {
+            for i in 0..n {
+                beta[t][i] = (0..n)
+                    .map(|j| {
+                        self.transition[i][j]
+                            * self.emission[j][observations[t + 1]]
+                            * beta[t + 1][j]
+                    })
+                    .sum::<f64>()
+                    / scales[t + 1];
+            }
+        }
+        Some(ForwardBackward {
+            alpha,
+            beta,
+            scales,
+        })
+    }
+
+    /// Returns the logarithm of the probability of `observations`, summed over all sequences
+    /// of states, with the forward algorithm in O(T n^2).
+    pub fn forward(&self, observations: &[usize]) -> AlgorithmResult<f64> {
+        self.check_observations("observations", observations)?;
+        Ok(match self.forward_backward(observations) {
+            Some(variables) => variables.scales.iter().map(|s| s.ln()).sum(),
+            None => f64::NEG_INFINITY,
+        })
+    }
+
+    /// Estimates the parameters of the model from `sequences` of observations with the
+    /// Baum-Welch algorithm <https://en.wikipedia.org/wiki/Baum%E2%80%93Welch_algorithm>, and
+    /// returns the log-likelihood of the sequences under the model before the last update.
+    ///
+    /// This is expectation-maximization, starting from the current parameters: the
+    /// forward-backward algorithm gives the expected number of times every state is visited
+    /// and every transition and emission is used, and the parameters are replaced by their
+    /// normalized counts. Every iteration can only increase the likelihood of the sequences,
+    /// and the iterations stop early once it stops increasing. The result is a local maximum,
+    /// which depends on the starting parameters.
+    pub fn baum_welch(
+        &mut self,
+        sequences: &[Vec<usize>],
+        max_iterations: usize,
+    ) -> AlgorithmResult<f64> {
+        if sequences.iter().all(|sequence| sequence.is_empty()) {
+            return Err(AlgorithmError::EmptyInput {
+                argument: "sequences",
+            });
+        }
+        for sequence in sequences {
+            self.check_observations("sequences", sequence)?;
+        }
+        let (n, n_observations) = (self.n_states(), self.emission[0].len());
+        let mut log_likelihood = f64::NEG_INFINITY;
+        for _ in 0..max_iterations {
+            let mut initial = vec![0.0; n];
+            let mut transitions = vec![vec![0.0; n]; n];
+            let mut emissions = vec![vec![0.0; n_observations]; n];
+            let mut new_log_likelihood = 0.0;
+            for sequence in sequences.iter().filter(|sequence| !sequence.is_empty()) {
+                let ForwardBackward {
+                    alpha,
+                    beta,
+                    scales,
+                } = self
+                    .forward_backward(sequence)
+                    .ok_or(AlgorithmError::NonFiniteValue {
+                        argument: "sequences",
+                    })?;
+                new_log_likelihood += scales.iter().map(|s| s.ln()).sum::<f64>();
+                for (t, &observation) in sequence.iter().enumerate() {
+                    for i in 0..n {
+                        // the probability of state i at time t given the whole sequence
+                        let gamma = alpha[t][i] * beta[t][i];
+                        if t == 0 {
+                            initial[i] += gamma;
+                        }
+                        emissions[i][observation] += gamma;
+                        if let Some(&next) = sequence.get(t + 1) {
+                            for j in 0..n {
+                                transitions[i][j] += alpha[t][i]
+                                    * self.transition[i][j]
+                                    * self.emission[j][next]
+                                    * beta[t + 1][j]
+                                    / scales[t + 1];
+                            }
+                        }
+                    }
+                }
+            }
+            let improved = new_log_likelihood > log_likelihood + 1e-12;
+            log_likelihood = new_log_likelihood;
+            if !improved {
+                break;
+            }
+            normalize(&mut initial);
+            self.initial = initial;
+            // a state which is never left, or never visited, keeps its distributions
+            for (row, counts) in self.transition.iter_mut().zip(transitions) {
+                update_row(row, counts);
+            }
+            for (row, counts) in self.emission.iter_mut().zip(emissions) {
+                update_row(row, counts);
+            }
+        }
+        Ok(log_likelihood)
+    }
+}
+
+fn normalize(row: &mut [f64]) {
+    let total: f64 = row.iter().sum();
+    row.iter_mut().for_each(|x| *x /= total);
+}
+
+fn update_row(row: &mut Vec<f64>, mut counts: Vec<f64>) {
+    if counts.iter().sum::<f64>() > 0.0 {
+        normalize(&mut counts);
+        *row = counts;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    const HEALTHY: usize = 0;
+    const FEVER: usize = 1;
+    const NORMAL: usize = 0;
+    const COLD: usize = 1;
+    const DIZZY: usize = 2;
+
+    /// The doctor example of Wikipedia
+    fn doctor() -> HiddenMarkovModel {
+        HiddenMarkovModel::new(
+            2,
+            3,
+            vec![vec![0.7, 0.3], vec![0.4, 0.6]],
+            vec![vec![0.5, 0.4, 0.1], vec![0.1, 0.3, 0.6]],
+            vec![0.6, 0.4],
+        )
+        .unwrap()
+    }
+
+    /// The probabilities of every sequence of states jointly with the observations
+    fn joint_probabilities(
+        hmm: &HiddenMarkovModel,
+        observations: &[usize],
+    ) -> Vec<(f64, Vec<usize>)> {
+        let n = hmm.n_states();
+        let t = observations.len() as u32;
+        (0..n.pow(t))
+            .map(|code| {
+                let states: Vec<usize> = (0..t).map(|k| code / n.pow(k) % n).collect();
+                let mut p = hmm.initial[states[0]] * hmm.emission[states[0]][observations[0]];
+                for k in 1..states.len() {
+                    p *= hmm.transition[states[k - 1]][states[k]]
+                        * hmm.emission[states[k]][observations[k]];
+                }
+                (p, states)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn viterbi_on_the_doctor_example() {
+        let hmm = doctor();
+        let (log_probability, path) = hmm.viterbi(&[NORMAL, COLD, DIZZY]).unwrap();
+        assert_eq!(path, vec![HEALTHY, HEALTHY, FEVER]);
+        assert!((log_probability.exp() - 0.01512).abs() < 1e-12);
+    }
+
+    #[test]
+    fn viterbi_agrees_with_brute_force() {
+        let hmm = doctor();
+        let mut rng = ChaCha8Rng::seed_from_u64(113);
+        for _ in 0..50 {
+            let observations: Vec<usize> = (0..rng.gen_range(1..8))
+                .map(|_| rng.gen_range(0..3))
+                .collect();
+            let joint = joint_probabilities(&hmm, &observations);
+            let (best, _) = joint.iter().max_by(|a, b| a.0.total_cmp(&b.0)).unwrap();
+            let (log_probability, path) = hmm.viterbi(&observations).unwrap();
+            assert!((log_probability.exp() - best).abs() < 1e-12);
+            let path_probability = joint.iter().find(|(_, states)| *states == path).unwrap().0;
+            assert!((path_probability - best).abs() < 1e-12);
+
+            let total: f64 = joint.iter().map(|(p, _)| p).sum();
+            assert!((hmm.forward(&observations).unwrap() - total.ln()).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn impossible_observations() {
+        let hmm = HiddenMarkovModel::new(
+            2,
+            2,
+            vec![vec![0.0, 1.0], vec![0.0, 1.0]],
+            vec![vec![1.0, 0.0], vec![0.0, 1.0]],
+            vec![1.0, 0.0],
+        )
+        .unwrap();
+        // the first state is left at once, and is the only one observing 0
+        assert_eq!(hmm.viterbi(&[0, 1, 1]).unwrap(), (0.0, vec![0, 1, 1]));
+        assert_eq!(hmm.forward(&[0, 1, 1]), Ok(0.0));
+        assert_eq!(hmm.viterbi(&[0, 0]).unwrap().0, f64::NEG_INFINITY);
+        assert_eq!(hmm.forward(&[0, 0]), Ok(f64::NEG_INFINITY));
+        assert_eq!(hmm.viterbi(&[]), Ok((0.0, vec![])));
+    }
+
+    /// Draws a sequence of observations from the model
+    fn sample(hmm: &HiddenMarkovModel, length: usize, rng: &mut ChaCha8Rng) -> Vec<usize> {
+        let draw = |distribution: &[f64], rng: &mut ChaCha8Rng| {
+            let mut target = rng.gen::<f64>();
+            distribution
+                .iter()
+                .position(|&p| {
+                    target -= p;
+                    target < 0.0
+                })
+                .unwrap_or(distribution.len() - 1)
+        };
+        let mut state = draw(&hmm.initial, rng);
+        (0..length)
+            .map(|_| {
+                let observation = draw(&hmm.emission[state], rng);
+                state = draw(&hmm.transition[state], rng);
+                observation
+            })
+            .collect()
+    }
+
+    #[test]
+    fn baum_welch_increases_the_likelihood() {
+        let truth = doctor();
+        let mut rng = ChaCha8Rng::seed_from_u64(1130);
+        let sequences: Vec<Vec<usize>> = (0..20).map(|_| sample(&truth, 50, &mut rng)).collect();
+        let mut hmm = HiddenMarkovModel::new(
+            2,
+            3,
+            vec![vec![0.5, 0.5], vec![0.5, 0.5]],
+            vec![vec![0.4, 0.3, 0.3], vec![0.2, 0.3, 0.5]],
+            vec![0.5, 0.5],
+        )
+        .unwrap();
+        let likelihood = |hmm: &HiddenMarkovModel| -> f64 {
+            sequences.iter().map(|s| hmm.forward(s).unwrap()).sum()
+        };
+        let before = likelihood(&hmm);
+        let mut previous = before;
+        for _ in 0..10 {
+            hmm.baum_welch(&sequences, 1).unwrap();
+            let current = likelihood(&hmm);
+            assert!(current >= previous - 1e-9, "{current} < {previous}");
+            previous = current;
+        }
+        hmm.baum_welch(&sequences, 200).unwrap();
+        let after = likelihood(&hmm);
+        assert!(after > before);
+        // the parameters are still distributions, and fit the data at least as well as the
+        // parameters the data was drawn from
+        for row in hmm.transition().iter().chain(hmm.emission()) {
+            assert!((row.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        }
+        assert!(after > likelihood(&truth) - 1.0);
+        // state 1 still observes dizzy the most
+        assert!(hmm.emission()[1][DIZZY] > hmm.emission()[0][DIZZY]);
+    }
+
+    #[test]
+    fn invalid_models() {
+        let valid_transition = vec![vec![0.5, 0.5], vec![0.5, 0.5]];
+        let valid_emission = vec![vec![1.0], vec![1.0]];
+        assert!(matches!(
+            HiddenMarkovModel::new(
+                2,
+                1,
+                valid_transition.clone(),
+                valid_emission.clone(),
+                vec![0.3, 0.3]
+            ),
+            Err(AlgorithmError::InvalidParameter {
+                argument: "initial",
+                ..
+            })
+        ));
+        assert_eq!(
+            HiddenMarkovModel::new(
+                2,
+                1,
+                vec![vec![1.0]],
+                valid_emission.clone(),
+                vec![0.5, 0.5]
+            ),
+            Err(AlgorithmError::DimensionMismatch {
+                argument: "transition",
+                expected: 2,
+                found: 1
+            })
+        );
+        assert!(matches!(
+            HiddenMarkovModel::new(
+                2,
+                1,
+                vec![vec![1.5, -0.5], vec![0.5, 0.5]],
+                valid_emission.clone(),
+                vec![0.5, 0.5]
+            ),
+            Err(AlgorithmError::InvalidParameter {
+                argument: "transition",
+                ..
+            })
+        ));
+        assert!(HiddenMarkovModel::new(0, 1, vec![], vec![], vec![]).is_err());
+        let mut hmm =
+            HiddenMarkovModel::new(2, 1, valid_transition, valid_emission, vec![0.5, 0.5]).unwrap();
+        assert_eq!(
+            hmm.viterbi(&[0, 1]),
+            Err(AlgorithmError::InvalidParameter {
+                argument: "observations",
+                reason: "1 is not one of the 1 observations".to_string(),
+            })
+        );
+        assert!(hmm.forward(&[3]).is_err());
+        assert_eq!(
+            hmm.baum_welch(&[vec![]], 10),
+            Err(AlgorithmError::EmptyInput {
+                argument: "sequences"
+            })
+        );
+        assert!(hmm.baum_welch(&[vec![0, 2]], 10).is_err());
+    }
+}
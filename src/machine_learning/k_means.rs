@@ -1,3 +1,4 @@
+use crate::error::{AlgorithmError, AlgorithmResult};
 use rand::prelude::random;
 
 fn get_distance(p1: &(f64, f64), p2: &(f64, f64)) -> f64 {
@@ -22,9 +23,39 @@ fn find_nearest(data_point: &(f64, f64), centroids: &[(f64, f64)]) -> u32 {
     cluster
 }
 
-pub fn k_means(data_points: Vec<(f64, f64)>, n_clusters: usize, max_iter: i32) -> Option<Vec<u32>> {
-    if data_points.len() < n_clusters {
-        return None;
+/// Clusters the points around `n_clusters` centroids, returning the cluster of every point.
+pub fn k_means(
+    data_points: Vec<(f64, f64)>,
+    n_clusters: usize,
+    max_iter: i32,
+) -> AlgorithmResult<Vec<u32>> {
+    if data_points.is_empty() {
+        return Err(AlgorithmError::EmptyInput {
+            argument: "data_points",
+        });
+    }
+    if n_clusters == 0 || data_points.len() < n_clusters {
+        return Err(AlgorithmError::InvalidParameter {
+            argument: "n_clusters",
+            reason: format!(
+                "must be between 1 and the number of points {}, got {n_clusters}",
+                data_points.len()
+            ),
+        });
+    }
+    if max_iter < 0 {
+        return Err(AlgorithmError::InvalidParameter {
+            argument: "max_iter",
+            reason: format!("must not be negative, got {max_iter}"),
+        });
+    }
+    if data_points
+        .iter()
+        .any(|p| !p.0.is_finite() || !p.1.is_finite())
+    {
+        return Err(AlgorithmError::NonFiniteValue {
+            argument: "data_points",
+        });
     }
 
     let mut centroids: Vec<(f64, f64)> = Vec::new();
@@ -66,7 +97,7 @@ pub fn k_means(data_points: Vec<(f64, f64)>, n_clusters: usize, max_iter: i32) -
         count_iter += 1;
     }
 
-    Some(labels)
+    Ok(labels)
 }
 
 #[cfg(test)]
@@ -85,6 +116,41 @@ mod test {
             data_points.push((x, y));
         }
 
-        println!("{:?}", k_means(data_points, 10, 100).unwrap_or_default());
+        let labels = k_means(data_points, 10, 100).unwrap();
+        assert_eq!(labels.len(), n_points);
+        assert!(labels.iter().all(|&label| label < 10));
+    }
+
+    #[test]
+    fn test_k_means_invalid_input() {
+        let points = vec![(0.0, 0.0), (1.0, 1.0)];
+        assert_eq!(
+            k_means(vec![], 1, 10),
+            Err(AlgorithmError::EmptyInput {
+                argument: "data_points"
+            })
+        );
+        for n_clusters in [0, 3] {
+            assert!(matches!(
+                k_means(points.clone(), n_clusters, 10),
+                Err(AlgorithmError::InvalidParameter {
+                    argument: "n_clusters",
+                    ..
+                })
+            ));
+        }
+        assert!(matches!(
+            k_means(points, 2, -1),
+            Err(AlgorithmError::InvalidParameter {
+                argument: "max_iter",
+                ..
+            })
+        ));
+        assert_eq!(
+            k_means(vec![(f64::INFINITY, 0.0)], 1, 10),
+            Err(AlgorithmError::NonFiniteValue {
+                argument: "data_points"
+            })
+        );
     }
 }
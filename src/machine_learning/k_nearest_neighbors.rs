@@ -0,0 +1,332 @@
+use super::lsh::RandomProjectionLsh;
+use crate::data_structures::{squared_distance, KdTree};
+use crate::error::{check_rows, check_same_length, AlgorithmError, AlgorithmResult};
+use std::collections::HashMap;
+
+/// Below this number of points, a linear scan is faster than building a k-d tree and
+/// searching it.
+const KD_TREE_THRESHOLD: usize = 1000;
+
+/// How the neighbors of a point are found among the points of a data set
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum NeighborSearch {
+    /// A k-d tree for data sets of at least 1000 points, a linear scan for smaller ones
+    #[default]
+    Auto,
+    /// Scanning all the points, in O(n) for every query, the reference for the others
+    BruteForce,
+    /// Querying a k-d tree, in O(log(n)) for every query on well spread data
+    KdTree,
+}
+
+impl NeighborSearch {
+    /// Tells whether a data set of `points` points is searched with a k-d tree.
+    pub(crate) fn uses_tree(self, points: usize) -> bool {
+        match self {
+            NeighborSearch::Auto => points >= KD_TREE_THRESHOLD,
+            NeighborSearch::BruteForce => false,
+            NeighborSearch::KdTree => true,
+        }
+    }
+}
+
+/// The training points of a classifier, in a k-d tree, as they are, or centered on their mean
+/// in an index of random projections
+enum Points {
+    Scan(Vec<Vec<f64>>),
+    Tree(KdTree),
+    Hashed {
+        lsh: RandomProjectionLsh,
+        mean: Vec<f64>,
+    },
+}
+
+/// The k-nearest neighbors classifier
+/// <https://en.wikipedia.org/wiki/K-nearest_neighbors_algorithm>
+///
+/// A point gets the most common class among the `k` training points closest to it, in
+/// Euclidean distance. Among training points at the same distance, the first ones are the
+/// closest, and among classes with the same number of votes, the one of the closest neighbor
+/// wins.
+pub struct KnnClassifier {
+    k: usize,
+    dimensions: usize,
+    points: Points,
+    labels: Vec<usize>,
+}
+
+impl KnnClassifier {
+    /// Creates a classifier of the rows of `points`, whose classes are `labels`.
+    pub fn new(k: usize, points: Vec<Vec<f64>>, labels: Vec<usize>) -> AlgorithmResult<Self> {
+        Self::with_search(k, points, labels, NeighborSearch::Auto)
+    }
+
+    /// Creates a classifier finding the neighbors of the points with `search`.
+    pub fn with_search(
+        k: usize,
+        points: Vec<Vec<f64>>,
+        labels: Vec<usize>,
+        search: NeighborSearch,
+    ) -> AlgorithmResult<Self> {
+        let dimensions = check_input(k, &points, &labels)?;
+        let points = if search.uses_tree(points.len()) {
+            Points::Tree(KdTree::new(points)?)
+        } else {
+            Points::Scan(points)
+        };
+        Ok(KnnClassifier {
+            k,
+            dimensions,
+            points,
+            labels,
+        })
+    }
+
+    /// Creates a classifier finding approximate neighbors, among the training points sharing a
+    /// bucket with the query in one of the `n_tables` tables of hashes of `n_bits` random
+    /// projections, drawn from `seed`. See `RandomProjectionLsh` for the parameters.
+    ///
+    /// The points are centered on their mean, so that their angles around the origin follow
+    /// their distances. The neighbors are the closest candidates, which may miss some of the
+    /// true neighbors, and a query with less than `k` candidates scans all the points.
+    pub fn approximate(
+        k: usize,
+        points: Vec<Vec<f64>>,
+        labels: Vec<usize>,
+        n_tables: usize,
+        n_bits: usize,
+        seed: u64,
+    ) -> AlgorithmResult<Self> {
+        let dimensions = check_input(k, &points, &labels)?;
+        let mean: Vec<f64> = (0..dimensions)
+            .map(|d| points.iter().map(|point| point[d]).sum::<f64>() / points.len() as f64)
+            .collect();
+        let mut lsh = RandomProjectionLsh::new(dimensions, n_tables, n_bits, seed)?;
+        lsh.index(points.iter().map(|point| centered(point, &mean)).collect())?;
+        Ok(KnnClassifier {
+            k,
+            dimensions,
+            points: Points::Hashed { lsh, mean },
+            labels,
+        })
+    }
+
+    fn check_query(&self, query: &[f64]) -> AlgorithmResult<()> {
+        if query.len() != self.dimensions {
+            return Err(AlgorithmError::DimensionMismatch {
+                argument: "query",
+                expected: self.dimensions,
+                found: query.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns the indices of the `k` training points closest to `query`, with their
+    /// distances, from the closest.
+    pub fn neighbors(&self, query: &[f64]) -> AlgorithmResult<Vec<(usize, f64)>> {
+        match &self.points {
+            Points::Tree(tree) => tree.k_nearest(query, self.k),
+            Points::Scan(points) => {
+                self.check_query(query)?;
+                Ok(self.closest(query, points, 0..points.len()))
+            }
+            Points::Hashed { lsh, mean } => {
+                self.check_query(query)?;
+                let query = centered(query, mean);
+                let candidates = lsh.candidates(&query)?;
+                if candidates.len() < self.k {
+                    return Ok(self.closest(&query, lsh.items(), 0..lsh.len()));
+                }
+                Ok(self.closest(&query, lsh.items(), candidates))
+            }
+        }
+    }
+
+    /// Returns the `k` points of `candidates` closest to `query`, with their distances.
+    fn closest(
+        &self,
+        query: &[f64],
+        points: &[Vec<f64>],
+        candidates: impl IntoIterator<Item = usize>,
+    ) -> Vec<(usize, f64)> {
+        let mut distances: Vec<(f64, usize)> = candidates
+            .into_iter()
+            .map(|index| (squared_distance(query, &points[index]), index))
+            .collect();
+        distances.sort_unstable_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        distances
+            .into_iter()
+            .take(self.k)
+            .map(|(squared, index)| (index, squared.sqrt()))
+            .collect()
+    }
+
+    /// Returns the predicted class of `query`.
+    pub fn predict(&self, query: &[f64]) -> AlgorithmResult<usize> {
+        let neighbors = self.neighbors(query)?;
+        let mut votes = HashMap::new();
+        for &(index, _) in &neighbors {
+            *votes.entry(self.labels[index]).or_insert(0) += 1;
+        }
+        let most = votes.values().copied().max().unwrap_or(0);
+        Ok(neighbors
+            .iter()
+            .map(|&(index, _)| self.labels[index])
+            .find(|label| votes[label] == most)
+            .expect("there is at least one neighbor"))
+    }
+}
+
+/// Checks the classifier parameters, and returns the number of dimensions of the points.
+fn check_input(k: usize, points: &[Vec<f64>], labels: &[usize]) -> AlgorithmResult<usize> {
+    if k == 0 {
+        return Err(AlgorithmError::InvalidParameter {
+            argument: "k",
+            reason: "at least one neighbor must vote".to_string(),
+        });
+    }
+    let dimensions = check_rows("points", points)?;
+    check_same_length(("points", points.len()), ("labels", labels.len()))?;
+    Ok(dimensions)
+}
+
+fn centered(point: &[f64], mean: &[f64]) -> Vec<f64> {
+    point.iter().zip(mean).map(|(x, m)| x - m).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::machine_learning::datasets::make_blobs;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    /// Points around the corners of a square, labelled by their corner
+    fn corners(rng: &mut ChaCha8Rng, count: usize) -> (Vec<Vec<f64>>, Vec<usize>) {
+        let centers = [[0.0, 0.0], [10.0, 0.0], [0.0, 10.0], [10.0, 10.0]];
+        (0..count)
+            .map(|_| {
+                let label = rng.gen_range(0..4);
+                let center = centers[label];
+                let point = vec![
+                    center[0] + rng.gen_range(-3.0..3.0),
+                    center[1] + rng.gen_range(-3.0..3.0),
+                ];
+                (point, label)
+            })
+            .unzip()
+    }
+
+    #[test]
+    fn classifies_clusters() {
+        let mut rng = ChaCha8Rng::seed_from_u64(121);
+        let (points, labels) = corners(&mut rng, 200);
+        let knn = KnnClassifier::new(5, points, labels).unwrap();
+        assert_eq!(knn.predict(&[1.0, 1.0]), Ok(0));
+        assert_eq!(knn.predict(&[9.0, -1.0]), Ok(1));
+        assert_eq!(knn.predict(&[-2.0, 12.0]), Ok(2));
+        assert_eq!(knn.predict(&[11.0, 9.0]), Ok(3));
+    }
+
+    #[test]
+    fn votes_and_ties() {
+        let points = vec![vec![0.0], vec![1.0], vec![-1.0], vec![2.0], vec![3.0]];
+        let knn = KnnClassifier::new(3, points.clone(), vec![7, 3, 3, 7, 7]).unwrap();
+        // the neighbors of 0.4 are 0, 1 and -1
+        assert_eq!(knn.predict(&[0.4]), Ok(3));
+        // 1 and -1 are both at distance 1 of 0, the first of them wins the tie
+        let knn = KnnClassifier::new(2, points, vec![5, 1, 2, 1, 1]).unwrap();
+        assert_eq!(knn.neighbors(&[0.0]), Ok(vec![(0, 0.0), (1, 1.0)]));
+        // one vote each, and the closest neighbor is 0
+        assert_eq!(knn.predict(&[0.0]), Ok(5));
+    }
+
+    #[test]
+    fn tree_agrees_with_brute_force() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1213);
+        let (points, labels) = corners(&mut rng, 2000);
+        let brute_force = KnnClassifier::with_search(
+            7,
+            points.clone(),
+            labels.clone(),
+            NeighborSearch::BruteForce,
+        )
+        .unwrap();
+        let tree = KnnClassifier::new(7, points, labels).unwrap();
+        assert!(matches!(tree.points, Points::Tree(_)));
+        for _ in 0..500 {
+            let query = [rng.gen_range(-5.0..15.0), rng.gen_range(-5.0..15.0)];
+            assert_eq!(tree.neighbors(&query), brute_force.neighbors(&query));
+            assert_eq!(tree.predict(&query), brute_force.predict(&query));
+        }
+    }
+
+    #[test]
+    fn approximate_neighbors() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1326);
+        let centers: Vec<Vec<f64>> = (0..10)
+            .map(|_| (0..64).map(|_| rng.gen_range(-5.0..5.0)).collect())
+            .collect();
+        let (points, labels) = make_blobs(3000, &centers, 1.0, 1327).unwrap();
+        let (queries, expected) = make_blobs(200, &centers, 1.0, 1328).unwrap();
+        let exact = KnnClassifier::with_search(
+            5,
+            points.clone(),
+            labels.clone(),
+            NeighborSearch::BruteForce,
+        )
+        .unwrap();
+        let approximate = KnnClassifier::approximate(5, points, labels, 8, 10, 1329).unwrap();
+        for (query, &label) in queries.iter().zip(&expected) {
+            assert_eq!(approximate.predict(query), Ok(label));
+            // the points of a cluster are all at about the same distance in 64 dimensions, and
+            // the approximate neighbors are about as close as the exact ones
+            let nearest = exact.neighbors(query).unwrap()[0].1;
+            let found = approximate.neighbors(query).unwrap()[0].1;
+            assert!(found <= 1.1 * nearest, "{found} {nearest}");
+        }
+        // points in four directions fall in different buckets, and the queries with less than
+        // three candidates scan them
+        let square = vec![
+            vec![1.0, 0.0],
+            vec![0.0, 1.0],
+            vec![-1.0, 0.0],
+            vec![0.0, -1.0],
+        ];
+        let exact = KnnClassifier::with_search(
+            3,
+            square.clone(),
+            vec![0, 1, 2, 3],
+            NeighborSearch::BruteForce,
+        )
+        .unwrap();
+        let approximate =
+            KnnClassifier::approximate(3, square, vec![0, 1, 2, 3], 1, 16, 1330).unwrap();
+        for query in [[0.9, 0.2], [-0.5, -0.6], [0.0, 0.0]] {
+            assert_eq!(approximate.neighbors(&query), exact.neighbors(&query));
+        }
+        assert!(KnnClassifier::approximate(5, vec![vec![0.0]], vec![0], 0, 4, 0).is_err());
+        assert!(approximate.predict(&[0.0]).is_err());
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert!(KnnClassifier::new(0, vec![vec![0.0]], vec![0]).is_err());
+        assert_eq!(
+            KnnClassifier::new(1, vec![vec![0.0], vec![1.0]], vec![0]).err(),
+            Some(AlgorithmError::DimensionMismatch {
+                argument: "labels",
+                expected: 2,
+                found: 1
+            })
+        );
+        for search in [NeighborSearch::BruteForce, NeighborSearch::KdTree] {
+            let knn = KnnClassifier::with_search(1, vec![vec![0.0, 0.0]], vec![0], search).unwrap();
+            assert!(matches!(
+                knn.predict(&[0.0]),
+                Err(AlgorithmError::DimensionMismatch { .. })
+            ));
+        }
+    }
+}
@@ -1,7 +1,22 @@
-/// Returns the parameters of the line after performing simple linear regression on the input data.
-pub fn linear_regression(data_points: Vec<(f64, f64)>) -> Option<(f64, f64)> {
+use crate::error::{AlgorithmError, AlgorithmResult};
+
+/// Fits the line `y = a + b * x` to the data points by least squares, returning `(a, b)`.
+///
+/// Fails if there are no points, or if all of them have the same `x`, which leaves the slope
+/// undetermined.
+pub fn linear_regression(data_points: Vec<(f64, f64)>) -> AlgorithmResult<(f64, f64)> {
     if data_points.is_empty() {
-        return None;
+        return Err(AlgorithmError::EmptyInput {
+            argument: "data_points",
+        });
+    }
+    if data_points
+        .iter()
+        .any(|p| !p.0.is_finite() || !p.1.is_finite())
+    {
+        return Err(AlgorithmError::NonFiniteValue {
+            argument: "data_points",
+        });
     }
 
     let count = data_points.len() as f64;
@@ -18,6 +33,16 @@ pub fn linear_regression(data_points: Vec<(f64, f64)>) -> Option<(f64, f64)> {
         std_dev_sqr_y += (data_point.1 - mean_y).powi(2);
     }
 
+    if std_dev_sqr_x == 0.0 {
+        return Err(AlgorithmError::SingularMatrix {
+            argument: "data_points",
+        });
+    }
+    if std_dev_sqr_y == 0.0 {
+        // a horizontal line, whose correlation constant is undefined
+        return Ok((mean_y, 0.0));
+    }
+
     let std_dev_x = std_dev_sqr_x.sqrt();
     let std_dev_y = std_dev_sqr_y.sqrt();
     let std_dev_prod = std_dev_x * std_dev_y;
@@ -26,7 +51,7 @@ pub fn linear_regression(data_points: Vec<(f64, f64)>) -> Option<(f64, f64)> {
     let b = pcc * (std_dev_y / std_dev_x); //Slope of the line
     let a = mean_y - b * mean_x; //Y-Intercept of the line
 
-    Some((a, b))
+    Ok((a, b))
 }
 
 #[cfg(test)]
@@ -37,12 +62,38 @@ mod test {
     fn test_linear_regression() {
         assert_eq!(
             linear_regression(vec![(0.0, 0.0), (1.0, 1.0), (2.0, 2.0)]),
-            Some((2.220446049250313e-16, 0.9999999999999998))
+            Ok((2.220446049250313e-16, 0.9999999999999998))
         );
     }
 
     #[test]
     fn test_empty_list_linear_regression() {
-        assert_eq!(linear_regression(vec![]), None);
+        assert_eq!(
+            linear_regression(vec![]),
+            Err(AlgorithmError::EmptyInput {
+                argument: "data_points"
+            })
+        );
+    }
+
+    #[test]
+    fn test_degenerate_linear_regression() {
+        // a vertical line has no slope
+        assert_eq!(
+            linear_regression(vec![(1.0, 0.0), (1.0, 2.0)]),
+            Err(AlgorithmError::SingularMatrix {
+                argument: "data_points"
+            })
+        );
+        assert_eq!(
+            linear_regression(vec![(1.0, f64::NAN), (2.0, 2.0)]),
+            Err(AlgorithmError::NonFiniteValue {
+                argument: "data_points"
+            })
+        );
+        assert_eq!(
+            linear_regression(vec![(0.0, 3.0), (1.0, 3.0), (5.0, 3.0)]),
+            Ok((3.0, 0.0))
+        );
     }
 }
@@ -13,26 +13,47 @@
 //! It returns the average loss by dividing the `total_loss` by total no. of
 //! elements.
 //!
-pub fn hng_loss(y_true: &[f64], y_pred: &[f64]) -> f64 {
+
+use crate::error::{check_same_length, AlgorithmResult};
+
+pub fn hng_loss(y_true: &[f64], y_pred: &[f64]) -> AlgorithmResult<f64> {
+    check_same_length(("y_true", y_true.len()), ("y_pred", y_pred.len()))?;
     let mut total_loss: f64 = 0.0;
     for (p, a) in y_pred.iter().zip(y_true.iter()) {
         let loss: f64 = (1.0 - a * p).max(0.0);
         total_loss += loss;
     }
-    total_loss / (y_pred.len() as f64)
+    Ok(total_loss / (y_pred.len() as f64))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::AlgorithmError;
 
     #[test]
     fn test_hinge_loss() {
         let predicted_values: Vec<f64> = vec![-1.0, 1.0, 1.0];
         let actual_values: Vec<f64> = vec![-1.0, -1.0, 1.0];
         assert_eq!(
-            hng_loss(&predicted_values, &actual_values),
+            hng_loss(&predicted_values, &actual_values).unwrap(),
             0.6666666666666666
         );
     }
+
+    #[test]
+    fn test_hng_loss_invalid_input() {
+        assert_eq!(
+            hng_loss(&[], &[]),
+            Err(AlgorithmError::EmptyInput { argument: "y_true" })
+        );
+        assert_eq!(
+            hng_loss(&[0.5, 0.5], &[0.5]),
+            Err(AlgorithmError::DimensionMismatch {
+                argument: "y_pred",
+                expected: 2,
+                found: 1
+            })
+        );
+    }
 }
@@ -9,8 +9,14 @@
 //! ## KL divergence Loss Function Implementation
 //!
 //! This implementation takes two references to vectors of f64 values, `actual` and `predicted`, and returns the KL divergence loss between them.
+//! The vectors must have the same length, and the loss must be finite: a negative value in the
+//! distributions makes the logarithm undefined.
 //!
-pub fn kld_loss(actual: &[f64], predicted: &[f64]) -> f64 {
+
+use crate::error::{check_same_length, AlgorithmError, AlgorithmResult};
+
+pub fn kld_loss(actual: &[f64], predicted: &[f64]) -> AlgorithmResult<f64> {
+    check_same_length(("actual", actual.len()), ("predicted", predicted.len()))?;
     // epsilon to handle if any of the elements are zero
     let eps = 0.00001f64;
     let loss: f64 = actual
@@ -18,7 +24,13 @@ pub fn kld_loss(actual: &[f64], predicted: &[f64]) -> f64 {
         .zip(predicted.iter())
         .map(|(&a, &p)| ((a + eps) * ((a + eps) / (p + eps)).ln()))
         .sum();
-    loss
+    if loss.is_finite() {
+        Ok(loss)
+    } else {
+        Err(AlgorithmError::NonFiniteValue {
+            argument: "predicted",
+        })
+    }
 }
 
 #[cfg(test)]
@@ -30,8 +42,34 @@ mod tests {
         let test_vector_actual = vec![1.346112, 1.337432, 1.246655];
         let test_vector = vec![1.033836, 1.082015, 1.117323];
         assert_eq!(
-            kld_loss(&test_vector_actual, &test_vector),
+            kld_loss(&test_vector_actual, &test_vector).unwrap(),
             0.7752789394328498
         );
     }
+
+    #[test]
+    fn test_kld_loss_invalid_input() {
+        assert_eq!(
+            kld_loss(&[], &[]),
+            Err(AlgorithmError::EmptyInput { argument: "actual" })
+        );
+        assert_eq!(
+            kld_loss(&[0.5, 0.5], &[0.5]),
+            Err(AlgorithmError::DimensionMismatch {
+                argument: "predicted",
+                expected: 2,
+                found: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_kld_loss_negative_probability() {
+        assert_eq!(
+            kld_loss(&[0.5, 0.5], &[-0.5, 0.5]),
+            Err(AlgorithmError::NonFiniteValue {
+                argument: "predicted"
+            })
+        );
+    }
 }
@@ -13,23 +13,46 @@
 //! It returns the average loss by dividing the `total_loss` by total no. of
 //! elements.
 //!
-pub fn mse_loss(predicted: &[f64], actual: &[f64]) -> f64 {
+
+use crate::error::{check_same_length, AlgorithmResult};
+
+pub fn mse_loss(predicted: &[f64], actual: &[f64]) -> AlgorithmResult<f64> {
+    check_same_length(("predicted", predicted.len()), ("actual", actual.len()))?;
     let mut total_loss: f64 = 0.0;
     for (p, a) in predicted.iter().zip(actual.iter()) {
         let diff: f64 = p - a;
         total_loss += diff * diff;
     }
-    total_loss / (predicted.len() as f64)
+    Ok(total_loss / (predicted.len() as f64))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::AlgorithmError;
 
     #[test]
     fn test_mse_loss() {
         let predicted_values: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0];
         let actual_values: Vec<f64> = vec![1.0, 3.0, 3.5, 4.5];
-        assert_eq!(mse_loss(&predicted_values, &actual_values), 0.375);
+        assert_eq!(mse_loss(&predicted_values, &actual_values).unwrap(), 0.375);
+    }
+
+    #[test]
+    fn test_mse_loss_invalid_input() {
+        assert_eq!(
+            mse_loss(&[], &[]),
+            Err(AlgorithmError::EmptyInput {
+                argument: "predicted"
+            })
+        );
+        assert_eq!(
+            mse_loss(&[0.5, 0.5], &[0.5]),
+            Err(AlgorithmError::DimensionMismatch {
+                argument: "actual",
+                expected: 2,
+                found: 1
+            })
+        );
     }
 }
@@ -0,0 +1,579 @@
+//! Locality-sensitive hashing, to find the approximate nearest neighbors of a point among many
+//! points of many dimensions, where k-d trees end up scanning most of their leaves.
+//! <https://en.wikipedia.org/wiki/Locality-sensitive_hashing>
+//!
+//! Every hash table puts an item in the bucket of its hash, a combination of `n_bits` hash
+//! functions that agree on two similar items more often than on two dissimilar ones. The
+//! candidate neighbors of a query are the items sharing its bucket in at least one of the
+//! `n_tables` tables: more bits make the buckets hold fewer dissimilar items, and more tables
+//! miss fewer similar items. With the probability `p` that one function agrees on two items,
+//! they collide in some table with the probability `1 - (1 - p^n_bits)^n_tables`, which both
+//! indices compute. The candidates are then ranked by their exact similarity.
+
+use crate::error::{check_rows, AlgorithmError, AlgorithmResult};
+use crate::math::distributions::{Distribution, Normal};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+fn check_tables(n_tables: usize, n_bits: usize, max_bits: usize) -> AlgorithmResult<()> {
+    if n_tables == 0 {
+        return Err(AlgorithmError::InvalidParameter {
+            argument: "n_tables",
+            reason: "at least one hash table is needed".to_string(),
+        });
+    }
+    if n_bits == 0 || n_bits > max_bits {
+        return Err(AlgorithmError::InvalidParameter {
+            argument: "n_bits",
+            reason: format!("must be between 1 and {max_bits}, got {n_bits}"),
+        });
+    }
+    Ok(())
+}
+
+/// The probability for two items to collide in at least one table, when every hash function
+/// agrees on them with the probability `p`.
+fn any_table_collides(p: f64, n_tables: usize, n_bits: usize) -> f64 {
+    1.0 - (1.0 - p.powi(n_bits as i32)).powi(n_tables as i32)
+}
+
+/// Sorts the candidates by decreasing similarity, then by index, and keeps the first ones.
+fn rank(mut candidates: Vec<(usize, f64)>, max_candidates: usize) -> Vec<(usize, f64)> {
+    candidates.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    candidates.truncate(max_candidates);
+    candidates
+}
+
+/// The cosine of the angle between two vectors, or 0 if one of them is zero
+fn cosine_similarity(a: &[f64], b: &[f64]) -> f64 {
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norms =
+        a.iter().map(|x| x * x).sum::<f64>().sqrt() * b.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if norms == 0.0 {
+        0.0
+    } else {
+        (dot / norms).clamp(-1.0, 1.0)
+    }
+}
+
+/// An index of vectors by random hyperplanes, for their cosine similarity (SimHash).
+///
+/// Every bit of a hash is the side of a random hyperplane through the origin where the vector
+/// is, so that the bits of two vectors at an angle `theta` differ with the probability
+/// `theta / pi`. The vectors are compared by their angles around the origin, and data
+/// compared in Euclidean distance is better centered first.
+pub struct RandomProjectionLsh {
+    dimensions: usize,
+    n_bits: usize,
+    /// The normals of the hyperplanes of every table
+    hyperplanes: Vec<Vec<Vec<f64>>>,
+    tables: Vec<HashMap<u64, Vec<usize>>>,
+    items: Vec<Vec<f64>>,
+}
+
+impl RandomProjectionLsh {
+    /// Creates an empty index of vectors of `dimensions` coordinates, with `n_tables` tables of
+    /// hashes of `n_bits` bits, at most 64, whose hyperplanes are drawn from `seed`.
+    pub fn new(
+        dimensions: usize,
+        n_tables: usize,
+        n_bits: usize,
+        seed: u64,
+    ) -> AlgorithmResult<Self> {
+        if dimensions == 0 {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "dimensions",
+                reason: "the vectors need at least one dimension".to_string(),
+            });
+        }
+        check_tables(n_tables, n_bits, 64)?;
+        // any direction is as likely, with normal coordinates
+        let normal = Normal::new(0.0, 1.0)?;
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let hyperplanes = (0..n_tables)
+            .map(|_| {
+                (0..n_bits)
+                    .map(|_| normal.sample_n(dimensions, &mut rng))
+                    .collect()
+            })
+            .collect();
+        Ok(RandomProjectionLsh {
+            dimensions,
+            n_bits,
+            hyperplanes,
+            tables: vec![HashMap::new(); n_tables],
+            items: vec![],
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The indexed vectors, in their order of indexing
+    pub fn items(&self) -> &[Vec<f64>] {
+        &self.items
+    }
+
+    fn hash(&self, table: usize, item: &[f64]) -> u64 {
+        self.hyperplanes[table]
+            .iter()
+            .enumerate()
+            .filter(|(_, normal)| normal.iter().zip(item).map(|(a, x)| a * x).sum::<f64>() >= 0.0)
+            .fold(0, |hash, (bit, _)| hash | 1 << bit)
+    }
+
+    fn check_item(&self, item: &[f64]) -> AlgorithmResult<()> {
+        if item.len() != self.dimensions {
+            return Err(AlgorithmError::DimensionMismatch {
+                argument: "item",
+                expected: self.dimensions,
+                found: item.len(),
+            });
+        }
+        if item.iter().any(|x| !x.is_finite()) {
+            return Err(AlgorithmError::NonFiniteValue { argument: "item" });
+        }
+        Ok(())
+    }
+
+    /// Adds the vectors to the index, numbered after those already in it.
+    pub fn index(&mut self, items: Vec<Vec<f64>>) -> AlgorithmResult<()> {
+        if items.is_empty() {
+            return Ok(());
+        }
+        let dimensions = check_rows("items", &items)?;
+        if dimensions != self.dimensions {
+            return Err(AlgorithmError::DimensionMismatch {
+                argument: "items",
+                expected: self.dimensions,
+                found: dimensions,
+            });
+        }
+        for item in items {
+            let index = self.items.len();
+            for table in 0..self.tables.len() {
+                let hash = self.hash(table, &item);
+                self.tables[table].entry(hash).or_default().push(index);
+            }
+            self.items.push(item);
+        }
+        Ok(())
+    }
+
+    /// Returns the sorted indices of the vectors sharing a bucket with `item` in some table.
+    ///
+    /// When no bucket of `item` holds any vector, the buckets whose hashes differ from those of
+    /// `item` by one bit are probed instead, and the result is empty only if they are empty too.
+    pub fn candidates(&self, item: &[f64]) -> AlgorithmResult<Vec<usize>> {
+        self.check_item(item)?;
+        let hashes: Vec<u64> = (0..self.tables.len())
+            .map(|table| self.hash(table, item))
+            .collect();
+        let mut found: Vec<usize> = self
+            .tables
+            .iter()
+            .zip(&hashes)
+            .filter_map(|(buckets, hash)| buckets.get(hash))
+            .flatten()
+            .copied()
+            .collect();
+        if found.is_empty() {
+            for (buckets, hash) in self.tables.iter().zip(&hashes) {
+                for bit in 0..self.n_bits {
+                    found.extend(buckets.get(&(hash ^ 1 << bit)).into_iter().flatten());
+                }
+            }
+        }
+        found.sort_unstable();
+        found.dedup();
+        Ok(found)
+    }
+
+    /// Returns at most `max_candidates` of the candidate neighbors of `item`, with their
+    /// cosine similarities to it, from the most similar.
+    pub fn query(&self, item: &[f64], max_candidates: usize) -> AlgorithmResult<Vec<(usize, f64)>> {
+        let candidates = self
+            .candidates(item)?
+            .into_iter()
+            .map(|index| (index, cosine_similarity(item, &self.items[index])))
+            .collect();
+        Ok(rank(candidates, max_candidates))
+    }
+
+    /// The probability for a vector of cosine similarity `similarity` to a query to be among
+    /// its candidates, without the probes of the neighboring buckets.
+    pub fn collision_probability(&self, similarity: f64) -> f64 {
+        let p = 1.0 - similarity.clamp(-1.0, 1.0).acos() / PI;
+        any_table_collides(p, self.tables.len(), self.n_bits)
+    }
+}
+
+/// A mix of the bits of `x`, the finalizer of SplitMix64
+fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// The Jaccard similarity of two sorted sets without duplicates, 1 for two empty sets
+fn jaccard_similarity(a: &[u64], b: &[u64]) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    let (mut i, mut j, mut common) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                common += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    common as f64 / (a.len() + b.len() - common) as f64
+}
+
+fn sorted_set(set: &[u64]) -> Vec<u64> {
+    let mut set = set.to_vec();
+    set.sort_unstable();
+    set.dedup();
+    set
+}
+
+/// An index of sets of integers by their min-hashes, for their Jaccard similarity.
+/// <https://en.wikipedia.org/wiki/MinHash>
+///
+/// A min-hash of a set is the least hash of its elements, for a random hash function, which is
+/// the same for two sets with the probability that the element of least hash of their union is
+/// in both, their Jaccard similarity. Every table hashes a set by `n_bits` min-hashes, a band of
+/// its signature, and the fraction of equal min-hashes in the signatures of two sets estimates
+/// their similarity.
+pub struct MinHashLsh {
+    n_bits: usize,
+    /// The seeds of the hash functions, `n_bits` for every table
+    seeds: Vec<u64>,
+    tables: Vec<HashMap<Vec<u64>, Vec<usize>>>,
+    items: Vec<Vec<u64>>,
+}
+
+impl MinHashLsh {
+    /// Creates an empty index with `n_tables` tables of `n_bits` min-hashes, whose hash
+    /// functions are drawn from `seed`.
+    pub fn new(n_tables: usize, n_bits: usize, seed: u64) -> AlgorithmResult<Self> {
+        check_tables(n_tables, n_bits, usize::MAX)?;
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        Ok(MinHashLsh {
+            n_bits,
+            seeds: (0..n_tables * n_bits).map(|_| rng.gen()).collect(),
+            tables: vec![HashMap::new(); n_tables],
+            items: vec![],
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns the min-hashes of the set, `n_bits` for every table, `u64::MAX` for an empty set.
+    pub fn signature(&self, set: &[u64]) -> Vec<u64> {
+        self.seeds
+            .iter()
+            .map(|&seed| set.iter().map(|&x| mix(x ^ seed)).min().unwrap_or(u64::MAX))
+            .collect()
+    }
+
+    /// Estimates the Jaccard similarity of two sets by the fraction of their equal min-hashes.
+    pub fn estimate_similarity(&self, a: &[u64], b: &[u64]) -> f64 {
+        let equal = self
+            .signature(a)
+            .iter()
+            .zip(self.signature(b))
+            .filter(|(x, y)| **x == *y)
+            .count();
+        equal as f64 / self.seeds.len() as f64
+    }
+
+    /// Adds the sets to the index, numbered after those already in it. The repeated elements
+    /// of a set count once.
+    pub fn index(&mut self, items: Vec<Vec<u64>>) {
+        for item in items {
+            let item = sorted_set(&item);
+            let index = self.items.len();
+            let signature = self.signature(&item);
+            for (buckets, band) in self.tables.iter_mut().zip(signature.chunks(self.n_bits)) {
+                buckets.entry(band.to_vec()).or_default().push(index);
+            }
+            self.items.push(item);
+        }
+    }
+
+    /// Returns at most `max_candidates` of the sets sharing a bucket with `set` in some table,
+    /// with their exact Jaccard similarities to it, from the most similar. The result is empty
+    /// if no set shares any bucket with `set`.
+    pub fn query(&self, set: &[u64], max_candidates: usize) -> Vec<(usize, f64)> {
+        let set = sorted_set(set);
+        let signature = self.signature(&set);
+        let mut found: Vec<usize> = self
+            .tables
+            .iter()
+            .zip(signature.chunks(self.n_bits))
+            .filter_map(|(buckets, band)| buckets.get(band))
+            .flatten()
+            .copied()
+            .collect();
+        found.sort_unstable();
+        found.dedup();
+        let candidates = found
+            .into_iter()
+            .map(|index| (index, jaccard_similarity(&set, &self.items[index])))
+            .collect();
+        rank(candidates, max_candidates)
+    }
+
+    /// The probability for a set of Jaccard similarity `similarity` to a query to be among its
+    /// candidates.
+    pub fn collision_probability(&self, similarity: f64) -> f64 {
+        any_table_collides(similarity.clamp(0.0, 1.0), self.tables.len(), self.n_bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::machine_learning::datasets::make_blobs;
+    use std::time::Instant;
+
+    fn brute_force_cosine(items: &[Vec<f64>], query: &[f64]) -> usize {
+        (0..items.len())
+            .max_by(|&a, &b| {
+                cosine_similarity(query, &items[a]).total_cmp(&cosine_similarity(query, &items[b]))
+            })
+            .unwrap()
+    }
+
+    fn random_centers(rng: &mut ChaCha8Rng, count: usize, dimensions: usize) -> Vec<Vec<f64>> {
+        (0..count)
+            .map(|_| {
+                (0..dimensions)
+                    .map(|_| rng.gen_range(-10.0..10.0))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn finds_nearest_neighbors() {
+        let mut rng = ChaCha8Rng::seed_from_u64(131);
+        let centers = random_centers(&mut rng, 20, 64);
+        let (items, _) = make_blobs(5000, &centers, 1.0, 1311).unwrap();
+        let (queries, _) = make_blobs(200, &centers, 1.0, 1312).unwrap();
+        let mut lsh = RandomProjectionLsh::new(64, 10, 12, 1313).unwrap();
+        lsh.index(items.clone()).unwrap();
+        assert_eq!(lsh.len(), 5000);
+        let mut found = 0;
+        let mut candidates = 0;
+        for query in &queries {
+            let neighbors = lsh.query(query, 10).unwrap();
+            assert!(neighbors.windows(2).all(|w| w[0].1 >= w[1].1));
+            if neighbors.first().map(|n| n.0) == Some(brute_force_cosine(&items, query)) {
+                found += 1;
+            }
+            candidates += lsh.candidates(query).unwrap().len();
+        }
+        assert!(found >= 180, "{found}");
+        // the candidates are mostly the 250 points of the cluster of the query
+        assert!(candidates / queries.len() < 1000, "{candidates}");
+    }
+
+    #[test]
+    fn collision_probabilities() {
+        let lsh = RandomProjectionLsh::new(3, 4, 8, 0).unwrap();
+        assert_eq!(lsh.collision_probability(1.0), 1.0);
+        assert_eq!(lsh.collision_probability(-1.0), 0.0);
+        // orthogonal vectors agree on every bit with the probability 1/2
+        let expected = 1.0 - (1.0 - 0.5f64.powi(8)).powi(4);
+        assert!((lsh.collision_probability(0.0) - expected).abs() < 1e-12);
+        assert!(lsh.collision_probability(0.9) > lsh.collision_probability(0.5));
+        let minhash = MinHashLsh::new(20, 5, 0).unwrap();
+        assert_eq!(minhash.collision_probability(1.0), 1.0);
+        assert_eq!(minhash.collision_probability(0.0), 0.0);
+        // the threshold of the S-curve is about (1/20)^(1/5) = 0.55
+        assert!(minhash.collision_probability(0.3) < 0.05);
+        assert!(minhash.collision_probability(0.8) > 0.99);
+    }
+
+    #[test]
+    fn minhash_estimates_jaccard() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1314);
+        let minhash = MinHashLsh::new(64, 4, 1315).unwrap();
+        for _ in 0..50 {
+            let a: Vec<u64> = (0..rng.gen_range(1..300))
+                .map(|_| rng.gen_range(0..500))
+                .collect();
+            let b: Vec<u64> = (0..rng.gen_range(1..300))
+                .map(|_| rng.gen_range(0..500))
+                .collect();
+            let exact = jaccard_similarity(&sorted_set(&a), &sorted_set(&b));
+            let estimate = minhash.estimate_similarity(&a, &b);
+            // 256 min-hashes, three standard deviations of at most 0.03
+            assert!((estimate - exact).abs() < 0.1, "{estimate} {exact}");
+        }
+        assert_eq!(minhash.estimate_similarity(&[1, 2, 3], &[3, 2, 1, 1]), 1.0);
+        assert_eq!(jaccard_similarity(&[1, 2, 3], &[2, 3, 4]), 0.5);
+        assert_eq!(jaccard_similarity(&[], &[]), 1.0);
+        assert_eq!(jaccard_similarity(&[1], &[]), 0.0);
+    }
+
+    #[test]
+    fn minhash_finds_near_duplicates() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1316);
+        // documents as sets of words, each one an edited copy of one of ten originals
+        let originals: Vec<Vec<u64>> = (0..10)
+            .map(|_| (0..100).map(|_| rng.gen_range(0..100_000)).collect())
+            .collect();
+        let edit = |rng: &mut ChaCha8Rng, set: &[u64]| -> Vec<u64> {
+            set.iter()
+                .map(|&x| {
+                    if rng.gen_bool(0.05) {
+                        rng.gen_range(0..100_000)
+                    } else {
+                        x
+                    }
+                })
+                .collect()
+        };
+        let copies: Vec<Vec<u64>> = (0..500)
+            .map(|i| edit(&mut rng, &originals[i % 10]))
+            .collect();
+        let mut minhash = MinHashLsh::new(20, 5, 1317).unwrap();
+        minhash.index(copies.clone());
+        for (original, set) in originals.iter().enumerate() {
+            let found = minhash.query(&edit(&mut rng, set), usize::MAX);
+            // the 50 copies of the same original, and no other
+            assert!(found.len() >= 48, "{}", found.len());
+            assert!(found
+                .iter()
+                .all(|&(index, similarity)| index % 10 == original && similarity > 0.6));
+            assert!(found.windows(2).all(|w| w[0].1 >= w[1].1));
+            assert_eq!(minhash.query(set, 3).len(), 3);
+        }
+    }
+
+    #[test]
+    fn deterministic_for_a_seed() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1318);
+        let items: Vec<Vec<f64>> = (0..300)
+            .map(|_| (0..16).map(|_| rng.gen_range(-1.0..1.0)).collect())
+            .collect();
+        let query: Vec<f64> = (0..16).map(|_| rng.gen_range(-1.0..1.0)).collect();
+        let results: Vec<Vec<(usize, f64)>> = [5, 5, 6]
+            .into_iter()
+            .map(|seed| {
+                let mut lsh = RandomProjectionLsh::new(16, 4, 6, seed).unwrap();
+                lsh.index(items.clone()).unwrap();
+                lsh.query(&query, 20).unwrap()
+            })
+            .collect();
+        assert_eq!(results[0], results[1]);
+        assert_ne!(results[0], results[2]);
+        let sets = [vec![1, 2, 3], vec![2, 3, 4], vec![7, 8]];
+        let signatures: Vec<Vec<u64>> = [9, 9]
+            .into_iter()
+            .map(|seed| MinHashLsh::new(8, 2, seed).unwrap().signature(&sets[0]))
+            .collect();
+        assert_eq!(signatures[0], signatures[1]);
+    }
+
+    #[test]
+    fn queries_without_collisions() {
+        let mut lsh = RandomProjectionLsh::new(3, 2, 8, 1319).unwrap();
+        assert_eq!(lsh.query(&[1.0, 2.0, 3.0], 5), Ok(vec![]));
+        lsh.index(vec![vec![1.0, 2.0, 3.0]]).unwrap();
+        // the opposite vector is on the other side of every hyperplane
+        assert_eq!(lsh.query(&[-1.0, -2.0, -3.0], 5), Ok(vec![]));
+        // the buckets one bit away are probed, and no others
+        let mut lsh = RandomProjectionLsh::new(2, 1, 8, 1320).unwrap();
+        lsh.index(vec![vec![1.0, 0.0]]).unwrap();
+        let mut distances = vec![];
+        for degree in 0..360 {
+            let query = [
+                (degree as f64).to_radians().cos(),
+                (degree as f64).to_radians().sin(),
+            ];
+            let bits = (lsh.hash(0, &query) ^ lsh.hash(0, &[1.0, 0.0])).count_ones();
+            assert_eq!(lsh.query(&query, 1).unwrap().len(), usize::from(bits <= 1));
+            distances.push(bits);
+        }
+        assert!(distances.contains(&1) && distances.contains(&2));
+        let mut minhash = MinHashLsh::new(4, 4, 1321).unwrap();
+        assert!(minhash.query(&[1, 2], 5).is_empty());
+        minhash.index(vec![vec![1, 2, 3]]);
+        assert!(minhash.query(&[4, 5, 6], 5).is_empty());
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        assert!(RandomProjectionLsh::new(0, 1, 1, 0).is_err());
+        assert!(RandomProjectionLsh::new(2, 0, 1, 0).is_err());
+        assert!(RandomProjectionLsh::new(2, 1, 65, 0).is_err());
+        assert!(MinHashLsh::new(1, 0, 0).is_err());
+        let mut lsh = RandomProjectionLsh::new(2, 1, 4, 0).unwrap();
+        assert_eq!(
+            lsh.index(vec![vec![1.0, 2.0, 3.0]]),
+            Err(AlgorithmError::DimensionMismatch {
+                argument: "items",
+                expected: 2,
+                found: 3
+            })
+        );
+        assert!(lsh.index(vec![vec![f64::NAN, 0.0]]).is_err());
+        assert!(lsh.query(&[1.0], 1).is_err());
+        assert!(lsh.index(vec![]).is_ok());
+        assert!(lsh.is_empty());
+    }
+
+    #[test]
+    #[ignore = "slow test for measuring time"]
+    fn faster_than_brute_force() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1322);
+        let centers = random_centers(&mut rng, 200, 64);
+        let (items, _) = make_blobs(100_000, &centers, 1.0, 1323).unwrap();
+        let (queries, _) = make_blobs(100, &centers, 1.0, 1324).unwrap();
+        let mut lsh = RandomProjectionLsh::new(64, 8, 16, 1325).unwrap();
+        lsh.index(items.clone()).unwrap();
+
+        let start = Instant::now();
+        let from_index: Vec<Option<usize>> = queries
+            .iter()
+            .map(|query| lsh.query(query, 1).unwrap().first().map(|n| n.0))
+            .collect();
+        let index_time = start.elapsed();
+        let start = Instant::now();
+        let from_scan: Vec<usize> = queries
+            .iter()
+            .map(|query| brute_force_cosine(&items, query))
+            .collect();
+        let scan_time = start.elapsed();
+        println!("index: {index_time:?}, brute force: {scan_time:?}");
+        let found = from_index
+            .iter()
+            .zip(&from_scan)
+            .filter(|(a, b)| **a == Some(**b))
+            .count();
+        assert!(found >= 90, "{found}");
+        assert!(index_time * 10 < scan_time);
+    }
+}
@@ -0,0 +1,136 @@
+use crate::math::sampling::weighted_choice;
+use rand::Rng;
+use std::collections::HashMap;
+
+/// A word-level Markov chain text generator <https://en.wikipedia.org/wiki/Markov_chain>
+///
+/// A chain of order n assumes that the next word of a text only depends on the n words before
+/// it. Training counts, for every n consecutive words of the text, the words following them.
+/// Generating then repeatedly draws the next word among the successors of the last n words,
+/// with probabilities proportional to their counts.
+#[derive(Clone, Debug, Default)]
+pub struct MarkovChain {
+    order: usize,
+    transitions: HashMap<Vec<String>, HashMap<String, u32>>,
+}
+
+impl MarkovChain {
+    /// Creates an untrained chain where the next word depends on the `order` previous ones. A
+    /// chain of order 0 draws every word from the overall word frequencies.
+    pub fn new(order: usize) -> Self {
+        MarkovChain {
+            order,
+            transitions: HashMap::new(),
+        }
+    }
+
+    /// Adds the word n-grams of `text` to the transitions. The words are separated by
+    /// whitespace, and several texts can be learned one after the other.
+    pub fn train(&mut self, text: &str) {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        for window in words.windows(self.order + 1) {
+            let (next, state) = window.split_last().unwrap();
+            let state = state.iter().map(|word| word.to_string()).collect();
+            *self
+                .transitions
+                .entry(state)
+                .or_default()
+                .entry(next.to_string())
+                .or_default() += 1;
+        }
+    }
+
+    /// Generates up to `length` words following `seed`, which should hold at least `order`
+    /// words. The generation stops early when the last words were never followed by any word
+    /// in the training texts. The seed itself is not part of the result.
+    pub fn generate(&self, seed: &[&str], length: usize, rng: &mut impl Rng) -> Vec<String> {
+        let mut state: Vec<String> = seed.iter().map(|word| word.to_string()).collect();
+        if state.len() < self.order {
+            return vec![];
+        }
+        state.drain(..state.len() - self.order);
+        let mut generated = Vec::with_capacity(length);
+        while generated.len() < length {
+            let Some(successors) = self.transitions.get(&state) else {
+                break;
+            };
+            // sorted, so that a seeded generator gives the same text on every run
+            let mut successors: Vec<(&String, &u32)> = successors.iter().collect();
+            successors.sort_unstable();
+            let weights: Vec<f64> = successors.iter().map(|(_, &count)| count as f64).collect();
+            let index = weighted_choice(&weights, rng).expect("counts are positive");
+            let next = successors[index].0.clone();
+            if self.order > 0 {
+                state.remove(0);
+                state.push(next.clone());
+            }
+            generated.push(next);
+        }
+        generated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use std::collections::HashSet;
+
+    const CORPUS: &str = "the cat sat on the mat . the dog sat on the log . \
+                          the cat saw the dog . the dog saw the cat on the mat .";
+
+    fn trained(order: usize) -> MarkovChain {
+        let mut chain = MarkovChain::new(order);
+        chain.train(CORPUS);
+        chain
+    }
+
+    #[test]
+    fn transition_counts() {
+        let chain = trained(1);
+        let after_the = &chain.transitions[&vec!["the".to_string()]];
+        assert_eq!(after_the["cat"], 3);
+        assert_eq!(after_the["dog"], 3);
+        assert_eq!(after_the["mat"], 2);
+        assert_eq!(after_the["log"], 1);
+        let chain = trained(2);
+        let after_sat_on = &chain.transitions[&vec!["sat".to_string(), "on".to_string()]];
+        assert_eq!(after_sat_on.len(), 1);
+        assert_eq!(after_sat_on["the"], 2);
+    }
+
+    #[test]
+    fn seeded_generation() {
+        let chain = trained(2);
+        let mut rng = ChaCha8Rng::seed_from_u64(111);
+        let text = chain.generate(&["the", "dog"], 12, &mut rng);
+        assert_eq!(text.join(" "), "saw the dog sat on the log . the dog . the");
+    }
+
+    #[test]
+    fn only_words_of_the_corpus() {
+        let vocabulary: HashSet<&str> = CORPUS.split_whitespace().collect();
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for order in 0..3 {
+            let chain = trained(order);
+            let seed = ["on", "the", "cat"];
+            let text = chain.generate(&seed[3 - order..], 200, &mut rng);
+            assert_eq!(text.len(), 200);
+            assert!(text.iter().all(|word| vocabulary.contains(word.as_str())));
+        }
+    }
+
+    #[test]
+    fn generation_stops_at_dead_ends() {
+        let mut chain = MarkovChain::new(1);
+        chain.train("a b c");
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        assert_eq!(chain.generate(&["a"], 10, &mut rng), ["b", "c"]);
+        assert!(chain.generate(&["z"], 10, &mut rng).is_empty());
+        assert!(chain.generate(&[], 10, &mut rng).is_empty());
+        assert!(MarkovChain::new(3)
+            .generate(&["a", "b", "c"], 5, &mut rng)
+            .is_empty());
+    }
+}
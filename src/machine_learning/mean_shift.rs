@@ -0,0 +1,362 @@
+use crate::error::{check_positive, check_rows, AlgorithmError, AlgorithmResult};
+
+/// How the neighbors of a point weigh in the mean it is shifted to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kernel {
+    /// The points within the bandwidth all have the same weight, and the others none
+    Flat,
+    /// Every point has the weight `exp(-d^2 / (2 h^2))`, for a distance `d` and a bandwidth `h`
+    Gaussian,
+}
+
+impl Kernel {
+    fn weight(&self, squared_distance: f64, bandwidth: f64) -> f64 {
+        match self {
+            Kernel::Flat => {
+                if squared_distance <= bandwidth * bandwidth {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Kernel::Gaussian => (-squared_distance / (2.0 * bandwidth * bandwidth)).exp(),
+        }
+    }
+}
+
+/// Mean-shift clustering <https://en.wikipedia.org/wiki/Mean_shift>
+///
+/// Every point is moved to the weighted mean of its neighbors, over and over, until it stops
+/// moving: it then sits on a mode, a local maximum of the density of the data. The modes closer
+/// than the bandwidth to each other are merged, keeping the ones with the most points around
+/// them, and the remaining modes are the centers of the clusters. Unlike k-means, the number of
+/// clusters is not given, but discovered from the data and the bandwidth.
+///
+/// Every iteration takes O(n^2 d) for n points of d dimensions.
+pub struct MeanShift;
+
+impl MeanShift {
+    /// Clusters the rows of `data` with a flat kernel. A point stops moving once it moves less
+    /// than `tol` in an iteration, or after `max_iters` iterations.
+    pub fn fit(
+        data: &[Vec<f64>],
+        bandwidth: f64,
+        max_iters: usize,
+        tol: f64,
+    ) -> AlgorithmResult<MeanShiftModel> {
+        Self::fit_with_kernel(data, Kernel::Flat, bandwidth, max_iters, tol)
+    }
+
+    /// Same as `fit`, weighing the neighbors of the points with the given kernel.
+    pub fn fit_with_kernel(
+        data: &[Vec<f64>],
+        kernel: Kernel,
+        bandwidth: f64,
+        max_iters: usize,
+        tol: f64,
+    ) -> AlgorithmResult<MeanShiftModel> {
+        check_rows("data", data)?;
+        check_positive("bandwidth", bandwidth)?;
+        if max_iters == 0 {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "max_iters",
+                reason: "at least one iteration is needed".to_string(),
+            });
+        }
+        if !tol.is_finite() || tol < 0.0 {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "tol",
+                reason: format!("must be finite and not negative, got {tol}"),
+            });
+        }
+
+        let modes: Vec<Vec<f64>> = data
+            .iter()
+            .map(|point| shift_to_mode(data, point, kernel, bandwidth, max_iters, tol))
+            .collect();
+
+        // the modes with the most points around them come first, the coordinates breaking ties
+        // so that the result does not depend on the order of the data
+        let mut ranked: Vec<(usize, Vec<f64>)> = modes
+            .into_iter()
+            .map(|mode| {
+                let strength = data
+                    .iter()
+                    .filter(|point| squared_distance(point, &mode) <= bandwidth * bandwidth)
+                    .count();
+                (strength, mode)
+            })
+            .collect();
+        ranked.sort_by(|(s1, m1), (s2, m2)| s2.cmp(s1).then_with(|| compare(m1, m2)));
+
+        let mut centers: Vec<Vec<f64>> = vec![];
+        for (_, mode) in ranked {
+            if centers
+                .iter()
+                .all(|center| squared_distance(center, &mode) >= bandwidth * bandwidth)
+            {
+                centers.push(mode);
+            }
+        }
+        centers.sort_by(|a, b| compare(a, b));
+
+        let labels = data.iter().map(|point| nearest(&centers, point)).collect();
+        Ok(MeanShiftModel { centers, labels })
+    }
+}
+
+/// The clusters found by `MeanShift::fit`
+#[derive(Clone, Debug, PartialEq)]
+pub struct MeanShiftModel {
+    centers: Vec<Vec<f64>>,
+    labels: Vec<usize>,
+}
+
+impl MeanShiftModel {
+    /// Returns the centers of the clusters, in lexicographic order.
+    pub fn centers(&self) -> &[Vec<f64>] {
+        &self.centers
+    }
+
+    /// Returns the index of the cluster of every point of the data the model was fitted on.
+    pub fn labels(&self) -> &[usize] {
+        &self.labels
+    }
+
+    /// Returns the index of the cluster whose center is the nearest to `row`.
+    pub fn predict(&self, row: &[f64]) -> AlgorithmResult<usize> {
+        let expected = self.centers[0].len();
+        if row.len() != expected {
+            return Err(AlgorithmError::DimensionMismatch {
+                argument: "row",
+                expected,
+                found: row.len(),
+            });
+        }
+        Ok(nearest(&self.centers, row))
+    }
+}
+
+/// Estimates a bandwidth for `data`: the mean over all points of the distance to their
+/// `quantile * n`-th nearest point, the point itself included.
+///
+/// A small quantile gives a small bandwidth and many clusters, a quantile of 1 the distance to
+/// the farthest point.
+pub fn estimate_bandwidth(data: &[Vec<f64>], quantile: f64) -> AlgorithmResult<f64> {
+    check_rows("data", data)?;
+    if !(quantile > 0.0 && quantile <= 1.0) {
+        return Err(AlgorithmError::InvalidParameter {
+            argument: "quantile",
+            reason: format!("must be in (0, 1], got {quantile}"),
+        });
+    }
+    let n = data.len();
+    let k = ((quantile * n as f64).ceil() as usize).clamp(1, n);
+    let total: f64 = data
+        .iter()
+        .map(|point| {
+            let mut distances: Vec<f64> = data
+                .iter()
+                .map(|other| squared_distance(point, other))
+                .collect();
+            distances.sort_by(f64::total_cmp);
+            distances[k - 1].sqrt()
+        })
+        .sum();
+    Ok(total / n as f64)
+}
+
+fn squared_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
+fn compare(a: &[f64], b: &[f64]) -> std::cmp::Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| x.total_cmp(y))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(std::cmp::Ordering::Equal)
+}
+
+fn nearest(centers: &[Vec<f64>], point: &[f64]) -> usize {
+    (0..centers.len())
+        .min_by(|&i, &j| {
+            squared_distance(&centers[i], point).total_cmp(&squared_distance(&centers[j], point))
+        })
+        .unwrap()
+}
+
+/// Moves `start` to the weighted mean of its neighbors until it moves less than `tol`.
+fn shift_to_mode(
+    data: &[Vec<f64>],
+    start: &[f64],
+    kernel: Kernel,
+    bandwidth: f64,
+    max_iters: usize,
+    tol: f64,
+) -> Vec<f64> {
+    let mut mode = start.to_vec();
+    for _ in 0..max_iters {
+        let mut mean = vec![0.0; mode.len()];
+        let mut total_weight = 0.0;
+        for point in data {
+            let weight = kernel.weight(squared_distance(point, &mode), bandwidth);
+            if weight > 0.0 {
+                total_weight += weight;
+                for (m, x) in mean.iter_mut().zip(point) {
+                    *m += weight * x;
+                }
+            }
+        }
+        // no neighbor left, which only happens when the weights underflow
+        if total_weight == 0.0 {
+            break;
+        }
+        mean.iter_mut().for_each(|m| *m /= total_weight);
+        let moved = squared_distance(&mean, &mode).sqrt();
+        mode = mean;
+        if moved < tol {
+            break;
+        }
+    }
+    mode
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    const MEANS: [[f64; 2]; 3] = [[0.0, 0.0], [10.0, 10.0], [-10.0, 10.0]];
+
+    /// Points scattered uniformly in a square of side 2 around every mean
+    fn blobs(means: &[[f64; 2]], points_per_blob: usize, seed: u64) -> Vec<Vec<f64>> {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        means
+            .iter()
+            .flat_map(|mean| {
+                (0..points_per_blob)
+                    .map(|_| {
+                        mean.iter()
+                            .map(|x| x + rng.gen_range(-1.0..1.0))
+                            .collect::<Vec<f64>>()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    fn assert_near(center: &[f64], expected: &[f64], tolerance: f64) {
+        assert!(
+            squared_distance(center, expected).sqrt() < tolerance,
+            "{center:?} is not near {expected:?}"
+        );
+    }
+
+    #[test]
+    fn three_blobs() {
+        let data = blobs(&MEANS, 50, 0);
+        for kernel in [Kernel::Flat, Kernel::Gaussian] {
+            let model = MeanShift::fit_with_kernel(&data, kernel, 3.0, 300, 1e-6).unwrap();
+            let centers = model.centers();
+            assert_eq!(centers.len(), 3, "{kernel:?}");
+            // lexicographic order
+            assert_near(&centers[0], &MEANS[2], 0.5);
+            assert_near(&centers[1], &MEANS[0], 0.5);
+            assert_near(&centers[2], &MEANS[1], 0.5);
+            // the points of a blob share a label
+            for (blob, label) in [(0, 1), (1, 2), (2, 0)] {
+                let labels = &model.labels()[blob * 50..(blob + 1) * 50];
+                assert!(labels.iter().all(|&l| l == label));
+            }
+            assert_eq!(model.predict(&[9.0, 11.0]), Ok(2));
+            assert_eq!(model.predict(&[-0.5, 1.0]), Ok(1));
+        }
+    }
+
+    #[test]
+    fn single_blob() {
+        let data = blobs(&[[5.0, -5.0]], 60, 1);
+        let model = MeanShift::fit(&data, 2.0, 300, 1e-6).unwrap();
+        assert_eq!(model.centers().len(), 1);
+        assert_near(&model.centers()[0], &[5.0, -5.0], 0.5);
+        assert!(model.labels().iter().all(|&label| label == 0));
+    }
+
+    #[test]
+    fn independent_of_the_order_of_the_data() {
+        let data = blobs(&MEANS, 30, 2);
+        let mut shuffled = data.clone();
+        shuffled.reverse();
+        shuffled.rotate_left(17);
+        let model = MeanShift::fit(&data, 3.0, 300, 1e-9).unwrap();
+        let shuffled_model = MeanShift::fit(&shuffled, 3.0, 300, 1e-9).unwrap();
+        assert_eq!(model.centers().len(), shuffled_model.centers().len());
+        for (a, b) in model.centers().iter().zip(shuffled_model.centers()) {
+            assert_near(a, b, 1e-6);
+        }
+    }
+
+    #[test]
+    fn tiny_bandwidth() {
+        let data = blobs(&MEANS, 20, 3);
+        let model = MeanShift::fit(&data, 1e-6, 1000, 0.0).unwrap();
+        // every point is its own cluster
+        assert_eq!(model.centers().len(), 60);
+        let model = MeanShift::fit_with_kernel(&data, Kernel::Gaussian, 1e-6, 1000, 0.0).unwrap();
+        assert_eq!(model.centers().len(), 60);
+    }
+
+    #[test]
+    fn bandwidth_estimate() {
+        let data = blobs(&MEANS, 40, 4);
+        let bandwidth = estimate_bandwidth(&data, 0.2).unwrap();
+        assert!(bandwidth > 0.0 && bandwidth < 5.0, "bandwidth {bandwidth}");
+        let model = MeanShift::fit(&data, bandwidth, 300, 1e-6).unwrap();
+        assert_eq!(model.centers().len(), 3);
+        assert_eq!(estimate_bandwidth(&[vec![0.0], vec![3.0]], 1.0), Ok(3.0));
+    }
+
+    #[test]
+    fn invalid_input() {
+        let data = vec![vec![0.0, 1.0], vec![1.0, 0.0]];
+        assert_eq!(
+            MeanShift::fit(&[], 1.0, 10, 1e-3),
+            Err(AlgorithmError::EmptyInput { argument: "data" })
+        );
+        assert_eq!(
+            MeanShift::fit(&[vec![0.0, 1.0], vec![1.0]], 1.0, 10, 1e-3),
+            Err(AlgorithmError::DimensionMismatch {
+                argument: "data",
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            MeanShift::fit(&[vec![f64::NAN]], 1.0, 10, 1e-3),
+            Err(AlgorithmError::NonFiniteValue { argument: "data" })
+        );
+        for bandwidth in [0.0, -1.0] {
+            assert!(matches!(
+                MeanShift::fit(&data, bandwidth, 10, 1e-3),
+                Err(AlgorithmError::InvalidParameter {
+                    argument: "bandwidth",
+                    ..
+                })
+            ));
+        }
+        assert!(MeanShift::fit(&data, 1.0, 0, 1e-3).is_err());
+        assert!(MeanShift::fit(&data, 1.0, 10, -1.0).is_err());
+        assert!(estimate_bandwidth(&data, 0.0).is_err());
+        let model = MeanShift::fit(&data, 1.0, 10, 1e-3).unwrap();
+        assert_eq!(
+            model.predict(&[1.0]),
+            Err(AlgorithmError::DimensionMismatch {
+                argument: "row",
+                expected: 2,
+                found: 1
+            })
+        );
+    }
+}
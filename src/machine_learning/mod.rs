@@ -1,17 +1,40 @@
 mod cholesky;
+mod data_split;
+pub mod datasets;
+mod dbscan;
+mod gmm;
+mod hmm;
 mod k_means;
+mod k_nearest_neighbors;
 mod linear_regression;
 mod loss_function;
+pub mod lsh;
+mod markov_chain;
+mod mean_shift;
 mod optimization;
 mod perceptron;
+mod polynomial_regression;
+mod ransac;
+pub mod reinforcement;
+pub mod streaming;
+pub mod tuning;
 
 pub use self::cholesky::cholesky;
+pub use self::data_split::{bootstrap_sample, train_test_split, weighted_bootstrap_sample};
+pub use self::dbscan::Dbscan;
+pub use self::gmm::GaussianMixture;
+pub use self::hmm::HiddenMarkovModel;
 pub use self::k_means::k_means;
+pub use self::k_nearest_neighbors::{KnnClassifier, NeighborSearch};
 pub use self::linear_regression::linear_regression;
 pub use self::loss_function::hng_loss;
 pub use self::loss_function::kld_loss;
 pub use self::loss_function::mae_loss;
 pub use self::loss_function::mse_loss;
+pub use self::markov_chain::MarkovChain;
+pub use self::mean_shift::{estimate_bandwidth, Kernel, MeanShift, MeanShiftModel};
 pub use self::optimization::gradient_descent;
 pub use self::optimization::Adam;
 pub use self::perceptron::{ActivationFunction, Perceptron};
+pub use self::polynomial_regression::PolynomialRegression;
+pub use self::ransac::{ransac, LinearModel, LinearModelFitter, Model, ModelFitter};
@@ -38,6 +38,9 @@
 //!   - PyTorch Adam optimizer:
 //!       - [https://pytorch.org/docs/stable/generated/torch.optim.Adam.html#torch.optim.Adam]
 //!
+
+use crate::error::{check_positive, AlgorithmError, AlgorithmResult};
+
 pub struct Adam {
     learning_rate: f64, // alpha: initial step size for iterative optimization
     betas: (f64, f64),  // betas: exponential decay rates for moment estimates
@@ -48,23 +51,49 @@ pub struct Adam {
 }
 
 impl Adam {
+    /// Creates an optimizer for `params_len` parameters, with the usual defaults for the
+    /// parameters left to `None`.
+    ///
+    /// The learning rate and epsilon must be finite and positive, and both betas must be in
+    /// `[0, 1)`: a beta of 1 would make the bias correction divide by zero.
     pub fn new(
         learning_rate: Option<f64>,
         betas: Option<(f64, f64)>,
         epsilon: Option<f64>,
         params_len: usize,
-    ) -> Self {
-        Adam {
-            learning_rate: learning_rate.unwrap_or(1e-3), // typical good default lr
-            betas: betas.unwrap_or((0.9, 0.999)),         // typical good default decay rates
-            epsilon: epsilon.unwrap_or(1e-8),             // typical good default epsilon
+    ) -> AlgorithmResult<Self> {
+        let learning_rate = learning_rate.unwrap_or(1e-3); // typical good default lr
+        let betas = betas.unwrap_or((0.9, 0.999)); // typical good default decay rates
+        let epsilon = epsilon.unwrap_or(1e-8); // typical good default epsilon
+        check_positive("learning_rate", learning_rate)?;
+        check_positive("epsilon", epsilon)?;
+        for beta in [betas.0, betas.1] {
+            if !(0.0..1.0).contains(&beta) {
+                return Err(AlgorithmError::InvalidParameter {
+                    argument: "betas",
+                    reason: format!("must be in [0, 1), got {beta}"),
+                });
+            }
+        }
+        Ok(Adam {
+            learning_rate,
+            betas,
+            epsilon,
             m: vec![0.0; params_len], // first moment vector elements all initialized to zero
             v: vec![0.0; params_len], // second moment vector elements all initialized to zero
             t: 0,                     // time step initialized to zero
-        }
+        })
     }
 
-    pub fn step(&mut self, gradients: &[f64]) -> Vec<f64> {
+    pub fn step(&mut self, gradients: &[f64]) -> AlgorithmResult<Vec<f64>> {
+        // there must be one gradient per parameter given to new
+        if gradients.len() != self.m.len() {
+            return Err(AlgorithmError::DimensionMismatch {
+                argument: "gradients",
+                expected: self.m.len(),
+                found: gradients.len(),
+            });
+        }
         let mut model_params = vec![0.0; gradients.len()];
         self.t += 1;
 
@@ -80,7 +109,7 @@ impl Adam {
             // update model parameters
             model_params[i] -= self.learning_rate * m_hat / (v_hat.sqrt() + self.epsilon);
         }
-        model_params // return updated model parameters
+        Ok(model_params) // return updated model parameters
     }
 }
 
@@ -90,7 +119,7 @@ mod tests {
 
     #[test]
     fn test_adam_init_default_values() {
-        let optimizer = Adam::new(None, None, None, 1);
+        let optimizer = Adam::new(None, None, None, 1).unwrap();
 
         assert_eq!(optimizer.learning_rate, 0.001);
         assert_eq!(optimizer.betas, (0.9, 0.999));
@@ -102,7 +131,7 @@ mod tests {
 
     #[test]
     fn test_adam_init_custom_lr_value() {
-        let optimizer = Adam::new(Some(0.9), None, None, 2);
+        let optimizer = Adam::new(Some(0.9), None, None, 2).unwrap();
 
         assert_eq!(optimizer.learning_rate, 0.9);
         assert_eq!(optimizer.betas, (0.9, 0.999));
@@ -114,7 +143,7 @@ mod tests {
 
     #[test]
     fn test_adam_init_custom_betas_value() {
-        let optimizer = Adam::new(None, Some((0.8, 0.899)), None, 3);
+        let optimizer = Adam::new(None, Some((0.8, 0.899)), None, 3).unwrap();
 
         assert_eq!(optimizer.learning_rate, 0.001);
         assert_eq!(optimizer.betas, (0.8, 0.899));
@@ -126,7 +155,7 @@ mod tests {
 
     #[test]
     fn test_adam_init_custom_epsilon_value() {
-        let optimizer = Adam::new(None, None, Some(1e-10), 4);
+        let optimizer = Adam::new(None, None, Some(1e-10), 4).unwrap();
 
         assert_eq!(optimizer.learning_rate, 0.001);
         assert_eq!(optimizer.betas, (0.9, 0.999));
@@ -138,7 +167,7 @@ mod tests {
 
     #[test]
     fn test_adam_init_all_custom_values() {
-        let optimizer = Adam::new(Some(1.0), Some((0.001, 0.099)), Some(1e-1), 5);
+        let optimizer = Adam::new(Some(1.0), Some((0.001, 0.099)), Some(1e-1), 5).unwrap();
 
         assert_eq!(optimizer.learning_rate, 1.0);
         assert_eq!(optimizer.betas, (0.001, 0.099));
@@ -152,8 +181,8 @@ mod tests {
     fn test_adam_step_default_params() {
         let gradients = vec![-1.0, 2.0, -3.0, 4.0, -5.0, 6.0, -7.0, 8.0];
 
-        let mut optimizer = Adam::new(None, None, None, 8);
-        let updated_params = optimizer.step(&gradients);
+        let mut optimizer = Adam::new(None, None, None, 8).unwrap();
+        let updated_params = optimizer.step(&gradients).unwrap();
 
         assert_eq!(
             updated_params,
@@ -174,8 +203,8 @@ mod tests {
     fn test_adam_step_custom_params() {
         let gradients = vec![9.0, -8.0, 7.0, -6.0, 5.0, -4.0, 3.0, -2.0, 1.0];
 
-        let mut optimizer = Adam::new(Some(0.005), Some((0.5, 0.599)), Some(1e-5), 9);
-        let updated_params = optimizer.step(&gradients);
+        let mut optimizer = Adam::new(Some(0.005), Some((0.5, 0.599)), Some(1e-5), 9).unwrap();
+        let updated_params = optimizer.step(&gradients).unwrap();
 
         assert_eq!(
             updated_params,
@@ -197,22 +226,63 @@ mod tests {
     fn test_adam_step_empty_gradients_array() {
         let gradients = vec![];
 
-        let mut optimizer = Adam::new(None, None, None, 0);
-        let updated_params = optimizer.step(&gradients);
+        let mut optimizer = Adam::new(None, None, None, 0).unwrap();
+        let updated_params = optimizer.step(&gradients).unwrap();
 
         assert_eq!(updated_params, vec![]);
     }
 
+    #[test]
+    fn test_adam_step_mismatched_gradients() {
+        let mut optimizer = Adam::new(None, None, None, 3).unwrap();
+
+        assert_eq!(
+            optimizer.step(&[1.0, 2.0]),
+            Err(AlgorithmError::DimensionMismatch {
+                argument: "gradients",
+                expected: 3,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_adam_init_invalid_values() {
+        let invalid = |argument: &'static str, reason: &str| {
+            Some(AlgorithmError::InvalidParameter {
+                argument,
+                reason: reason.to_string(),
+            })
+        };
+        assert_eq!(
+            Adam::new(Some(-0.001), None, None, 1).err(),
+            invalid("learning_rate", "must be finite and positive, got -0.001")
+        );
+        assert_eq!(
+            Adam::new(None, None, Some(0.0), 1).err(),
+            invalid("epsilon", "must be finite and positive, got 0")
+        );
+        assert_eq!(
+            Adam::new(None, Some((0.9, 1.0)), None, 1).err(),
+            invalid("betas", "must be in [0, 1), got 1")
+        );
+        assert_eq!(
+            Adam::new(None, Some((-0.1, 0.999)), None, 1).err(),
+            invalid("betas", "must be in [0, 1), got -0.1")
+        );
+        assert!(Adam::new(None, Some((0.0, 0.0)), None, 1).is_ok());
+    }
+
     #[ignore]
     #[test]
     fn test_adam_step_iteratively_until_convergence_with_default_params() {
         const CONVERGENCE_THRESHOLD: f64 = 1e-5;
         let gradients = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
 
-        let mut optimizer = Adam::new(None, None, None, 6);
+        let mut optimizer = Adam::new(None, None, None, 6).unwrap();
 
         let mut model_params = vec![0.0; 6];
-        let mut updated_params = optimizer.step(&gradients);
+        let mut updated_params = optimizer.step(&gradients).unwrap();
 
         while (updated_params
             .iter()
@@ -226,7 +296,7 @@ mod tests {
             > CONVERGENCE_THRESHOLD
         {
             model_params = updated_params;
-            updated_params = optimizer.step(&gradients);
+            updated_params = optimizer.step(&gradients).unwrap();
         }
 
         assert!(updated_params < vec![CONVERGENCE_THRESHOLD; 6]);
@@ -250,10 +320,10 @@ mod tests {
         const CONVERGENCE_THRESHOLD: f64 = 1e-7;
         let gradients = vec![7.0, -8.0, 9.0, -10.0, 11.0, -12.0, 13.0];
 
-        let mut optimizer = Adam::new(Some(0.005), Some((0.8, 0.899)), Some(1e-5), 7);
+        let mut optimizer = Adam::new(Some(0.005), Some((0.8, 0.899)), Some(1e-5), 7).unwrap();
 
         let mut model_params = vec![0.0; 7];
-        let mut updated_params = optimizer.step(&gradients);
+        let mut updated_params = optimizer.step(&gradients).unwrap();
 
         while (updated_params
             .iter()
@@ -267,7 +337,7 @@ mod tests {
             > CONVERGENCE_THRESHOLD
         {
             model_params = updated_params;
-            updated_params = optimizer.step(&gradients);
+            updated_params = optimizer.step(&gradients).unwrap();
         }
 
         assert!(updated_params < vec![CONVERGENCE_THRESHOLD; 7]);
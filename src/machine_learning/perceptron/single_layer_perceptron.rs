@@ -1,7 +1,11 @@
 // Perceptron model implementation.
 // A perceptron is a basic unit of a neural network, capable of binary classification.
 // It takes inputs, applies weights, and passes the result through an activation function to produce an output.
+//
+// The data sets are given as rows holding the input features followed by the target value.
+// Every public method checks its input and returns an AlgorithmError instead of panicking.
 
+use crate::error::{check_positive, check_same_length, AlgorithmError, AlgorithmResult};
 use crate::machine_learning::perceptron::ActivationFunction;
 use rand::prelude::*;
 
@@ -13,13 +17,59 @@ pub struct Perceptron {
 
 impl Perceptron {
     // Initialize a new perceptron with random weights, learning rate, and activation function
-    pub fn new(input_size: usize, learning_rate: f64, activation_fn: ActivationFunction) -> Self {
+    pub fn new(
+        input_size: usize,
+        learning_rate: f64,
+        activation_fn: ActivationFunction,
+    ) -> AlgorithmResult<Self> {
+        if input_size == 0 {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "input_size",
+                reason: "a perceptron needs at least one input".to_string(),
+            });
+        }
         let mut rng = thread_rng();
         let weights: Vec<f64> = (0..input_size).map(|_| rng.gen_range(-1.0..1.0)).collect();
-        Perceptron {
+        Self::with_weights(weights, learning_rate, activation_fn)
+    }
+
+    // Initialize a new perceptron with the given initial weights, one per input feature
+    pub fn with_weights(
+        weights: Vec<f64>,
+        learning_rate: f64,
+        activation_fn: ActivationFunction,
+    ) -> AlgorithmResult<Self> {
+        if weights.is_empty() {
+            return Err(AlgorithmError::EmptyInput {
+                argument: "weights",
+            });
+        }
+        if weights.iter().any(|w| !w.is_finite()) {
+            return Err(AlgorithmError::NonFiniteValue {
+                argument: "weights",
+            });
+        }
+        check_positive("learning_rate", learning_rate)?;
+        Ok(Perceptron {
             weights,
             learning_rate,
             activation_fn,
+        })
+    }
+
+    // Check that a data set is not empty, and that every row holds one value per weight plus the target
+    fn check_rows(&self, argument: &'static str, rows: &[Vec<f64>]) -> AlgorithmResult<()> {
+        if rows.is_empty() {
+            return Err(AlgorithmError::EmptyInput { argument });
+        }
+        let expected = self.weights.len() + 1;
+        match rows.iter().find(|row| row.len() != expected) {
+            Some(row) => Err(AlgorithmError::DimensionMismatch {
+                argument,
+                expected,
+                found: row.len(),
+            }),
+            None => Ok(()),
         }
     }
 
@@ -36,15 +86,72 @@ impl Perceptron {
         }
     }
 
+    // Check that a single sample holds one finite value per weight
+    fn check_features(&self, features: &[f64]) -> AlgorithmResult<()> {
+        if features.len() != self.weights.len() {
+            return Err(AlgorithmError::DimensionMismatch {
+                argument: "features",
+                expected: self.weights.len(),
+                found: features.len(),
+            });
+        }
+        if features.iter().any(|x| !x.is_finite()) {
+            return Err(AlgorithmError::NonFiniteValue {
+                argument: "features",
+            });
+        }
+        Ok(())
+    }
+
+    // Compute the output of the perceptron for the features of a single sample, without a target
+    pub fn predict(&self, features: &[f64]) -> AlgorithmResult<f64> {
+        self.check_features(features)?;
+        Ok(self.feedforward(features))
+    }
+
+    // Update the weights on a single sample as it arrives, for online learning on a stream,
+    // the same step that train takes for every row of every epoch
+    pub fn partial_fit(&mut self, features: &[f64], target: f64) -> AlgorithmResult<()> {
+        self.check_features(features)?;
+        if !target.is_finite() {
+            return Err(AlgorithmError::NonFiniteValue { argument: "target" });
+        }
+        let error = target - self.feedforward(features);
+        self.update_weights(features, error);
+        Ok(())
+    }
+
     // Train the perceptron using the provided inputs and target outputs for a specified number of epochs
-    pub fn train(&mut self, inputs: &[Vec<f64>], epochs: usize) {
-        let mut rng = thread_rng();
+    pub fn train(&mut self, inputs: &[Vec<f64>], epochs: usize) -> AlgorithmResult<()> {
+        self.train_with_rng(inputs, epochs, &mut thread_rng())
+    }
+
+    // Same as train, drawing the order of the samples in every epoch from the given random number
+    // generator, so that training with a seeded generator is reproducible
+    pub fn train_with_rng<R: Rng + ?Sized>(
+        &mut self,
+        inputs: &[Vec<f64>],
+        epochs: usize,
+        rng: &mut R,
+    ) -> AlgorithmResult<()> {
+        self.check_rows("inputs", inputs)?;
+        self.train_unchecked(inputs, epochs, rng);
+        Ok(())
+    }
+
+    // Training loop without the input checks, for callers training many times on data they
+    // already validated. Panics if a row is empty.
+    pub fn train_unchecked<R: Rng + ?Sized>(
+        &mut self,
+        inputs: &[Vec<f64>],
+        epochs: usize,
+        rng: &mut R,
+    ) {
         for _ in 0..epochs {
             let mut shuffled_inputs = inputs.to_vec();
-            shuffled_inputs.shuffle(&mut rng);
+            shuffled_inputs.shuffle(rng);
             for input in &shuffled_inputs {
-                let target = input.last().expect("No target value provided");
-                let features = &input[..input.len() - 1];
+                let (&target, features) = input.split_last().expect("No target value provided");
                 let prediction = self.feedforward(features);
                 let error = target - prediction;
                 self.update_weights(features, error);
@@ -53,7 +160,9 @@ impl Perceptron {
     }
 
     // Test the perceptron with the provided inputs and expected outputs, returning accuracy
-    pub fn test(&self, inputs: &[Vec<f64>], outputs: &[f64]) -> f64 {
+    pub fn test(&self, inputs: &[Vec<f64>], outputs: &[f64]) -> AlgorithmResult<f64> {
+        check_same_length(("inputs", inputs.len()), ("outputs", outputs.len()))?;
+        self.check_rows("inputs", inputs)?;
         let mut correct_predictions = 0;
         for (input, &output) in inputs.iter().zip(outputs) {
             let features = &input[..input.len() - 1];
@@ -62,7 +171,28 @@ impl Perceptron {
                 correct_predictions += 1;
             }
         }
-        correct_predictions as f64 / inputs.len() as f64
+        Ok(correct_predictions as f64 / inputs.len() as f64)
+    }
+
+    // Compute the mean squared error of the predictions on rows laid out like the training data,
+    // with the target value as the last element
+    pub fn mean_squared_error(&self, inputs: &[Vec<f64>]) -> AlgorithmResult<f64> {
+        self.check_rows("inputs", inputs)?;
+        Ok(self.mean_squared_error_unchecked(inputs))
+    }
+
+    // Mean squared error without the input checks. Panics if a row is empty, and returns NaN
+    // for an empty data set.
+    pub fn mean_squared_error_unchecked(&self, inputs: &[Vec<f64>]) -> f64 {
+        let total: f64 = inputs
+            .iter()
+            .map(|input| {
+                let (&target, features) = input.split_last().expect("No target value provided");
+                let prediction = self.feedforward(features);
+                (target - prediction).powi(2)
+            })
+            .sum();
+        total / inputs.len() as f64
     }
 }
 
@@ -73,7 +203,7 @@ mod tests {
     #[test]
     fn test_perceptron() {
         // Create a Perceptron with the None activation function
-        let mut perceptron = Perceptron::new(1, 0.1, ActivationFunction::None);
+        let mut perceptron = Perceptron::new(1, 0.1, ActivationFunction::None).unwrap();
 
         // Generate some training data: pairs of input-output for f(x) = 2x
         let training_data: Vec<Vec<f64>> = vec![
@@ -85,7 +215,7 @@ mod tests {
         ];
 
         // Train the perceptron on the training data
-        perceptron.train(&training_data, 100);
+        perceptron.train(&training_data, 100).unwrap();
 
         // Test the perceptron
         let testing_data: Vec<Vec<f64>> = vec![
@@ -96,8 +226,111 @@ mod tests {
             vec![4.0, 8.0],
         ];
 
-        let accuracy = perceptron.test(&testing_data, &[0.0, 2.0, 4.0, 6.0, 8.0]);
+        let accuracy = perceptron
+            .test(&testing_data, &[0.0, 2.0, 4.0, 6.0, 8.0])
+            .unwrap();
 
         assert_eq!(accuracy, 1.0);
     }
+
+    #[test]
+    fn test_invalid_parameters() {
+        assert_eq!(
+            Perceptron::new(0, 0.1, ActivationFunction::None).err(),
+            Some(AlgorithmError::InvalidParameter {
+                argument: "input_size",
+                reason: "a perceptron needs at least one input".to_string(),
+            })
+        );
+        for learning_rate in [-0.1, 0.0, f64::NAN] {
+            let error = Perceptron::new(2, learning_rate, ActivationFunction::Step).err();
+            assert!(matches!(
+                error,
+                Some(AlgorithmError::InvalidParameter {
+                    argument: "learning_rate",
+                    ..
+                })
+            ));
+        }
+        assert_eq!(
+            Perceptron::with_weights(vec![], 0.1, ActivationFunction::None).err(),
+            Some(AlgorithmError::EmptyInput {
+                argument: "weights"
+            })
+        );
+        assert_eq!(
+            Perceptron::with_weights(vec![f64::INFINITY], 0.1, ActivationFunction::None).err(),
+            Some(AlgorithmError::NonFiniteValue {
+                argument: "weights"
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_data() {
+        let mut perceptron =
+            Perceptron::with_weights(vec![0.5, 0.5], 0.1, ActivationFunction::None).unwrap();
+        assert_eq!(
+            perceptron.train(&[], 10),
+            Err(AlgorithmError::EmptyInput { argument: "inputs" })
+        );
+        // an empty row, and a row missing its target
+        for bad_row in [vec![], vec![1.0, 2.0]] {
+            let data = vec![vec![1.0, 2.0, 3.0], bad_row.clone()];
+            let expected = AlgorithmError::DimensionMismatch {
+                argument: "inputs",
+                expected: 3,
+                found: bad_row.len(),
+            };
+            assert_eq!(perceptron.train(&data, 10), Err(expected.clone()));
+            assert_eq!(perceptron.mean_squared_error(&data), Err(expected.clone()));
+            assert_eq!(perceptron.test(&data, &[3.0, 3.0]), Err(expected));
+        }
+        assert_eq!(
+            perceptron.test(&[], &[]),
+            Err(AlgorithmError::EmptyInput { argument: "inputs" })
+        );
+        assert_eq!(
+            perceptron.test(&[vec![1.0, 2.0, 3.0]], &[3.0, 4.0]),
+            Err(AlgorithmError::DimensionMismatch {
+                argument: "outputs",
+                expected: 1,
+                found: 2
+            })
+        );
+        assert_eq!(
+            perceptron.mean_squared_error(&[]),
+            Err(AlgorithmError::EmptyInput { argument: "inputs" })
+        );
+    }
+
+    #[test]
+    fn test_online_learning() {
+        let mut perceptron =
+            Perceptron::with_weights(vec![0.0, 0.0], 0.05, ActivationFunction::None).unwrap();
+        // f(x, 1) = 3x - 1, one sample at a time
+        for step in 0..10_000 {
+            let x = (step % 10) as f64 / 10.0;
+            perceptron.partial_fit(&[x, 1.0], 3.0 * x - 1.0).unwrap();
+        }
+        assert!((perceptron.predict(&[0.5, 1.0]).unwrap() - 0.5).abs() < 1e-3);
+        assert_eq!(
+            perceptron.predict(&[0.5]),
+            Err(AlgorithmError::DimensionMismatch {
+                argument: "features",
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            perceptron.partial_fit(&[0.5, f64::NAN], 1.0),
+            Err(AlgorithmError::NonFiniteValue {
+                argument: "features"
+            })
+        );
+        assert_eq!(
+            perceptron.partial_fit(&[0.5, 1.0], f64::INFINITY),
+            Err(AlgorithmError::NonFiniteValue { argument: "target" })
+        );
+    }
 }
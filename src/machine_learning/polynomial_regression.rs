@@ -0,0 +1,161 @@
+use crate::error::{check_same_length, AlgorithmError, AlgorithmResult};
+use nalgebra::{DMatrix, DVector};
+
+/// Least squares regression with a polynomial of a fixed degree
+/// <https://en.wikipedia.org/wiki/Polynomial_regression>
+///
+/// Every input `x` is expanded into the features `[1, x, x^2, ..., x^degree]`, the rows of a
+/// Vandermonde matrix, and the coefficients are the least squares solution of the linear model
+/// over these features. The system is solved with a singular value decomposition rather than
+/// the normal equations, which square the already poor conditioning of Vandermonde matrices.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PolynomialRegression {
+    degree: usize,
+    /// The coefficients from the constant term up, empty until fitted
+    coefficients: Vec<f64>,
+}
+
+impl PolynomialRegression {
+    pub fn new(degree: usize) -> Self {
+        PolynomialRegression {
+            degree,
+            coefficients: vec![],
+        }
+    }
+
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    /// Returns the coefficients of the fitted polynomial, from the constant term up.
+    pub fn coefficients(&self) -> &[f64] {
+        &self.coefficients
+    }
+
+    /// Fits the polynomial to the points `(x[i], y[i])`.
+    ///
+    /// Fails if there are fewer distinct values in `x` than coefficients, since the polynomial
+    /// is not determined then.
+    pub fn fit(&mut self, x: &[f64], y: &[f64]) -> AlgorithmResult<()> {
+        check_same_length(("x", x.len()), ("y", y.len()))?;
+        if x.iter().any(|v| !v.is_finite()) {
+            return Err(AlgorithmError::NonFiniteValue { argument: "x" });
+        }
+        if y.iter().any(|v| !v.is_finite()) {
+            return Err(AlgorithmError::NonFiniteValue { argument: "y" });
+        }
+        let columns = self.degree + 1;
+        let vandermonde =
+            DMatrix::from_fn(x.len(), columns, |row, column| x[row].powi(column as i32));
+        let svd = vandermonde.svd(true, true);
+        let largest = svd.singular_values.max();
+        let rank = svd
+            .singular_values
+            .iter()
+            .filter(|&&s| s > largest * f64::EPSILON * x.len().max(columns) as f64)
+            .count();
+        if rank < columns {
+            return Err(AlgorithmError::SingularMatrix { argument: "x" });
+        }
+        let coefficients = svd
+            .solve(&DVector::from_column_slice(y), 0.0)
+            .expect("both singular vectors were computed");
+        self.coefficients = coefficients.iter().copied().collect();
+        Ok(())
+    }
+
+    /// Evaluates the fitted polynomial at `x`, with Horner's method.
+    pub fn predict(&self, x: f64) -> AlgorithmResult<f64> {
+        if self.coefficients.is_empty() {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "self",
+                reason: "the regression is not fitted".to_string(),
+            });
+        }
+        Ok(self
+            .coefficients
+            .iter()
+            .rev()
+            .fold(0.0, |value, coefficient| value * x + coefficient))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cubic(x: f64) -> f64 {
+        2.0 * x.powi(3) - 3.0 * x + 1.0
+    }
+
+    #[test]
+    fn fits_a_cubic() {
+        let x: Vec<f64> = (0..41).map(|i| -4.0 + 0.2 * i as f64).collect();
+        let y: Vec<f64> = x.iter().map(|&x| cubic(x)).collect();
+        let mut model = PolynomialRegression::new(3);
+        model.fit(&x, &y).unwrap();
+        assert_eq!(model.degree(), 3);
+        for (coefficient, expected) in model.coefficients().iter().zip([1.0, -3.0, 0.0, 2.0]) {
+            assert!((coefficient - expected).abs() < 1e-9, "{coefficient}");
+        }
+        for x in [-4.0, -1.3, 0.0, 0.5, 2.7, 5.0] {
+            assert!((model.predict(x).unwrap() - cubic(x)).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn a_higher_degree_finds_the_same_cubic() {
+        let x: Vec<f64> = (0..20).map(|i| i as f64 / 4.0 - 2.0).collect();
+        let y: Vec<f64> = x.iter().map(|&x| cubic(x)).collect();
+        let mut model = PolynomialRegression::new(5);
+        model.fit(&x, &y).unwrap();
+        for x in [-2.0, 0.3, 1.9] {
+            assert!((model.predict(x).unwrap() - cubic(x)).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn least_squares_line() {
+        // the best line through (0, 0), (1, 1), (2, 0) is y = 1 / 3
+        let mut model = PolynomialRegression::new(1);
+        model.fit(&[0.0, 1.0, 2.0], &[0.0, 1.0, 0.0]).unwrap();
+        assert!((model.coefficients()[0] - 1.0 / 3.0).abs() < 1e-12);
+        assert!(model.coefficients()[1].abs() < 1e-12);
+        let mut constant = PolynomialRegression::new(0);
+        constant.fit(&[1.0, 2.0, 3.0], &[2.0, 4.0, 9.0]).unwrap();
+        assert!((constant.predict(100.0).unwrap() - 5.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn invalid_input() {
+        let mut model = PolynomialRegression::new(2);
+        assert_eq!(
+            model.predict(1.0),
+            Err(AlgorithmError::InvalidParameter {
+                argument: "self",
+                reason: "the regression is not fitted".to_string(),
+            })
+        );
+        assert_eq!(
+            model.fit(&[], &[]),
+            Err(AlgorithmError::EmptyInput { argument: "x" })
+        );
+        assert_eq!(
+            model.fit(&[1.0, 2.0], &[1.0]),
+            Err(AlgorithmError::DimensionMismatch {
+                argument: "y",
+                expected: 2,
+                found: 1
+            })
+        );
+        // a parabola is not determined by two distinct points
+        assert_eq!(
+            model.fit(&[1.0, 2.0, 2.0, 1.0], &[1.0, 2.0, 2.0, 1.0]),
+            Err(AlgorithmError::SingularMatrix { argument: "x" })
+        );
+        assert_eq!(
+            model.fit(&[1.0, f64::NAN, 3.0], &[1.0, 2.0, 3.0]),
+            Err(AlgorithmError::NonFiniteValue { argument: "x" })
+        );
+    }
+}
@@ -0,0 +1,300 @@
+use crate::error::{check_positive, check_rows, AlgorithmError, AlgorithmResult};
+use nalgebra::{DMatrix, DVector};
+use rand::seq::index;
+use rand::Rng;
+
+/// A model fitted to some points, which tells how far every point is from it.
+pub trait Model {
+    /// Returns the distance from `point` to the model, `f64::INFINITY` for a point it cannot
+    /// measure, like an empty one.
+    fn error(&self, point: &[f64]) -> f64;
+}
+
+/// Fits a model of type `M` to a set of points, or returns `None` if they do not determine one.
+pub trait ModelFitter<M> {
+    fn fit(&self, samples: &[Vec<f64>]) -> Option<M>;
+}
+
+/// The hyperplane `y = intercept + weights . x` fitted by `LinearModelFitter`, in which every
+/// point is the features `x` followed by the target `y`
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinearModel {
+    pub intercept: f64,
+    pub weights: Vec<f64>,
+}
+
+impl LinearModel {
+    pub fn predict(&self, features: &[f64]) -> f64 {
+        self.weights
+            .iter()
+            .zip(features)
+            .fold(self.intercept, |sum, (w, x)| sum + w * x)
+    }
+}
+
+impl Model for LinearModel {
+    /// The absolute vertical distance, along the target, from the point to the hyperplane, or
+    /// `f64::INFINITY` for an empty point, without a target.
+    fn error(&self, point: &[f64]) -> f64 {
+        match point.split_last() {
+            Some((target, features)) => (target - self.predict(features)).abs(),
+            None => f64::INFINITY,
+        }
+    }
+}
+
+/// Fits a `LinearModel` by least squares, returning `None` when the features of the samples
+/// leave the hyperplane undetermined, as with a single point, or two points with the same `x`
+/// for a line, and for samples that are empty or of different lengths.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LinearModelFitter;
+
+impl ModelFitter<LinearModel> for LinearModelFitter {
+    fn fit(&self, samples: &[Vec<f64>]) -> Option<LinearModel> {
+        let columns = samples.first()?.len();
+        if columns == 0 || samples.iter().any(|s| s.len() != columns) {
+            return None;
+        }
+        // a column of ones for the intercept, and the features
+        let design = DMatrix::from_fn(samples.len(), columns, |row, column| {
+            if column == 0 {
+                1.0
+            } else {
+                samples[row][column - 1]
+            }
+        });
+        let targets = DVector::from_iterator(samples.len(), samples.iter().map(|s| s[columns - 1]));
+        let svd = design.svd(true, true);
+        let largest = svd.singular_values.max();
+        let rank = svd
+            .singular_values
+            .iter()
+            .filter(|&&s| s > largest * f64::EPSILON * samples.len().max(columns) as f64)
+            .count();
+        if rank < columns {
+            return None;
+        }
+        let solution = svd.solve(&targets, 0.0).ok()?;
+        Some(LinearModel {
+            intercept: solution[0],
+            weights: solution.iter().skip(1).copied().collect(),
+        })
+    }
+}
+
+/// Fits a model to data with outliers by random sample consensus
+/// <https://en.wikipedia.org/wiki/Random_sample_consensus>
+///
+/// Every iteration fits a model to `min_samples` points drawn at random, and counts its
+/// inliers, the points within `threshold` of it. A least squares fit to all the points is
+/// dragged away by the outliers, while a sample free of them gives a model close to the true
+/// one, whose inliers are about all the points that are not outliers. The model with the most
+/// inliers, the smallest sum of their errors breaking ties, is then fitted again to all its
+/// inliers. With a proportion `w` of inliers, a sample is free of outliers with a probability
+/// `w^min_samples`, so that `log(1 - p) / log(1 - w^min_samples)` iterations find one with a
+/// probability `p`: 17 iterations for a line through 70% of the points, with `p = 0.99`.
+///
+/// Returns `None` if no sample gives a model. Fails if `data` is not a set of rows of the same
+/// dimensions, if `min_samples` is not between 1 and the number of points, or if `threshold`
+/// is not positive.
+pub fn ransac<M: Model>(
+    data: &[Vec<f64>],
+    model: &dyn ModelFitter<M>,
+    min_samples: usize,
+    threshold: f64,
+    iterations: usize,
+    rng: &mut impl Rng,
+) -> AlgorithmResult<Option<M>> {
+    check_rows("data", data)?;
+    if min_samples == 0 || min_samples > data.len() {
+        return Err(AlgorithmError::InvalidParameter {
+            argument: "min_samples",
+            reason: format!(
+                "must be between 1 and the number of points {}, got {min_samples}",
+                data.len()
+            ),
+        });
+    }
+    check_positive("threshold", threshold)?;
+    // the inliers of the best model so far, and the sum of their errors
+    let mut best: Option<(M, Vec<usize>, f64)> = None;
+    for _ in 0..iterations {
+        let samples: Vec<Vec<f64>> = index::sample(rng, data.len(), min_samples)
+            .into_iter()
+            .map(|i| data[i].clone())
+            .collect();
+        let Some(candidate) = model.fit(&samples) else {
+            continue;
+        };
+        let mut inliers = vec![];
+        let mut total_error = 0.0;
+        for (i, point) in data.iter().enumerate() {
+            let error = candidate.error(point);
+            if error <= threshold {
+                inliers.push(i);
+                total_error += error;
+            }
+        }
+        let better = best.as_ref().is_none_or(|(_, best_inliers, best_error)| {
+            inliers.len() > best_inliers.len()
+                || (inliers.len() == best_inliers.len() && total_error < *best_error)
+        });
+        if better {
+            best = Some((candidate, inliers, total_error));
+        }
+    }
+    Ok(best.map(|(candidate, inliers, _)| {
+        let consensus: Vec<Vec<f64>> = inliers.iter().map(|&i| data[i].clone()).collect();
+        model.fit(&consensus).unwrap_or(candidate)
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::machine_learning::linear_regression;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    /// 70 points close to the line `y = 2x + 1`, and 30 outliers away from it
+    fn line_with_outliers(rng: &mut ChaCha8Rng) -> Vec<Vec<f64>> {
+        let mut data: Vec<Vec<f64>> = (0..70)
+            .map(|_| {
+                let x = rng.gen_range(0.0..10.0);
+                vec![x, 2.0 * x + 1.0 + rng.gen_range(-0.1..0.1)]
+            })
+            .collect();
+        for _ in 0..30 {
+            let x = rng.gen_range(0.0..10.0);
+            data.push(vec![x, rng.gen_range(-40.0..-5.0)]);
+        }
+        data
+    }
+
+    #[test]
+    fn line_through_outliers() {
+        let mut rng = ChaCha8Rng::seed_from_u64(161);
+        let data = line_with_outliers(&mut rng);
+        let line = ransac(&data, &LinearModelFitter, 2, 0.3, 50, &mut rng)
+            .unwrap()
+            .unwrap();
+        assert!((line.weights[0] - 2.0).abs() <= 0.05 * 2.0, "{line:?}");
+        assert!((line.intercept - 1.0).abs() <= 0.05 * 1.0, "{line:?}");
+        assert_eq!(data.iter().filter(|p| line.error(p) <= 0.3).count(), 70);
+        // while least squares over all the points is far off
+        let points = data.iter().map(|p| (p[0], p[1])).collect();
+        let (intercept, slope) = linear_regression(points).unwrap();
+        assert!((slope - 2.0).abs() > 1.0 && (intercept - 1.0).abs() > 3.0);
+    }
+
+    #[test]
+    fn plane_through_outliers() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1610);
+        let mut data: Vec<Vec<f64>> = (0..200)
+            .map(|i| {
+                let (x, y) = (rng.gen_range(-5.0..5.0), rng.gen_range(-5.0..5.0));
+                let z = if i % 3 == 0 {
+                    rng.gen_range(-100.0..100.0)
+                } else {
+                    3.0 - x + 0.5 * y
+                };
+                vec![x, y, z]
+            })
+            .collect();
+        data.push(vec![0.0, 0.0, 3.0]);
+        let plane = ransac(&data, &LinearModelFitter, 3, 1e-6, 100, &mut rng)
+            .unwrap()
+            .unwrap();
+        for (found, expected) in [plane.intercept, plane.weights[0], plane.weights[1]]
+            .into_iter()
+            .zip([3.0, -1.0, 0.5])
+        {
+            assert!((found - expected).abs() < 1e-9, "{plane:?}");
+        }
+    }
+
+    /// A constant value, for one-dimensional points
+    struct Constant(f64);
+
+    impl Model for Constant {
+        fn error(&self, point: &[f64]) -> f64 {
+            (point[0] - self.0).abs()
+        }
+    }
+
+    /// The median of the samples
+    struct MedianFitter;
+
+    impl ModelFitter<Constant> for MedianFitter {
+        fn fit(&self, samples: &[Vec<f64>]) -> Option<Constant> {
+            let mut values: Vec<f64> = samples.iter().map(|s| s[0]).collect();
+            values.sort_by(f64::total_cmp);
+            Some(Constant(values[values.len() / 2]))
+        }
+    }
+
+    #[test]
+    fn custom_models() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1611);
+        let data: Vec<Vec<f64>> = [5.0, 5.1, 4.9, 5.0, 100.0, -20.0, 5.05, 0.0]
+            .iter()
+            .map(|&v| vec![v])
+            .collect();
+        let constant = ransac(&data, &MedianFitter, 1, 0.2, 30, &mut rng)
+            .unwrap()
+            .unwrap();
+        assert_eq!(constant.0, 5.0);
+    }
+
+    #[test]
+    fn no_model() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1612);
+        // a vertical line has no slope, and no iteration gives none either
+        let data = vec![vec![1.0, 0.0], vec![1.0, 2.0], vec![1.0, 5.0]];
+        let found = ransac(&data, &LinearModelFitter, 2, 0.1, 20, &mut rng).unwrap();
+        assert_eq!(found, None);
+        let data = line_with_outliers(&mut rng);
+        assert_eq!(
+            ransac(&data, &LinearModelFitter, 2, 0.1, 0, &mut rng).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1613);
+        let data = vec![vec![0.0, 1.0], vec![1.0, 3.0]];
+        for min_samples in [0, 3] {
+            assert_eq!(
+                ransac(&data, &LinearModelFitter, min_samples, 0.1, 10, &mut rng),
+                Err(AlgorithmError::InvalidParameter {
+                    argument: "min_samples",
+                    reason: format!(
+                        "must be between 1 and the number of points 2, got {min_samples}"
+                    ),
+                })
+            );
+        }
+        assert!(ransac(&data, &LinearModelFitter, 2, -1.0, 10, &mut rng).is_err());
+        assert_eq!(
+            ransac(&[], &LinearModelFitter, 2, 0.1, 10, &mut rng),
+            Err(AlgorithmError::EmptyInput { argument: "data" })
+        );
+    }
+
+    #[test]
+    fn fit_and_error_without_panics() {
+        let fitter = LinearModelFitter;
+        assert_eq!(fitter.fit(&[]), None);
+        assert_eq!(fitter.fit(&[vec![], vec![]]), None);
+        // rows of different lengths
+        let ragged = vec![vec![0.0, 1.0], vec![1.0, 3.0, 4.0], vec![2.0]];
+        assert_eq!(fitter.fit(&ragged), None);
+        let line = fitter
+            .fit(&[vec![0.0, 1.0], vec![1.0, 3.0], vec![2.0, 5.0]])
+            .unwrap();
+        assert!((line.weights[0] - 2.0).abs() < 1e-9);
+        assert_eq!(line.error(&[]), f64::INFINITY);
+        assert!((line.error(&[3.0, 8.0]) - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,534 @@
+//! Tabular reinforcement learning: an agent learns by trial and error which action to take in
+//! every state of an environment, from the rewards it gets.
+//! <https://en.wikipedia.org/wiki/Q-learning>
+
+use crate::error::{AlgorithmError, AlgorithmResult};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// An environment of finitely many states and actions, numbered from 0, in which an agent
+/// acts until the end of an episode.
+pub trait Environment {
+    /// Starts a new episode and returns its first state.
+    fn reset(&mut self) -> usize;
+    /// Takes the action, and returns the new state, the reward, and whether the episode is over.
+    fn step(&mut self, action: usize) -> (usize, f64, bool);
+    fn n_states(&self) -> usize;
+    fn n_actions(&self) -> usize;
+}
+
+fn check_probability(argument: &'static str, value: f64) -> AlgorithmResult<()> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(())
+    } else {
+        Err(AlgorithmError::InvalidParameter {
+            argument,
+            reason: format!("must be between 0 and 1, got {value}"),
+        })
+    }
+}
+
+/// The moves of the actions 0 to 3 of a `GridWorld`, as changes of row and column
+const MOVES: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+/// A grid in which the agent moves up (the action 0), right (1), down (2) or left (3) from a
+/// start cell to a goal cell, around walls. Every move costs a step reward, usually negative,
+/// and reaching the goal earns the goal reward and ends the episode. A move into a wall or out
+/// of the grid leaves the agent where it is.
+///
+/// The state of the cell `(row, column)` is `row * columns + column`. With a slip probability,
+/// the agent makes a random move instead of the one it chose with this probability.
+pub struct GridWorld {
+    rows: usize,
+    columns: usize,
+    walls: Vec<bool>,
+    start: usize,
+    goal: usize,
+    step_reward: f64,
+    goal_reward: f64,
+    slip: f64,
+    rng: ChaCha8Rng,
+    position: usize,
+}
+
+impl GridWorld {
+    /// Creates a grid without walls, a step reward of -1 and a goal reward of 10.
+    pub fn new(
+        rows: usize,
+        columns: usize,
+        start: (usize, usize),
+        goal: (usize, usize),
+    ) -> AlgorithmResult<Self> {
+        if rows == 0 || columns == 0 {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "rows",
+                reason: format!("the grid must have cells, got {rows}x{columns}"),
+            });
+        }
+        for (argument, (row, column)) in [("start", start), ("goal", goal)] {
+            if row >= rows || column >= columns {
+                return Err(AlgorithmError::InvalidParameter {
+                    argument,
+                    reason: format!("({row}, {column}) is out of the {rows}x{columns} grid"),
+                });
+            }
+        }
+        let start = start.0 * columns + start.1;
+        Ok(GridWorld {
+            rows,
+            columns,
+            walls: vec![false; rows * columns],
+            start,
+            goal: goal.0 * columns + goal.1,
+            step_reward: -1.0,
+            goal_reward: 10.0,
+            slip: 0.0,
+            rng: ChaCha8Rng::seed_from_u64(0),
+            position: start,
+        })
+    }
+
+    /// Puts walls on the cells, which may not be the start or the goal.
+    pub fn with_walls(mut self, walls: &[(usize, usize)]) -> AlgorithmResult<Self> {
+        for &(row, column) in walls {
+            if row >= self.rows || column >= self.columns {
+                return Err(AlgorithmError::InvalidParameter {
+                    argument: "walls",
+                    reason: format!("({row}, {column}) is out of the grid"),
+                });
+            }
+            let state = self.state(row, column);
+            if state == self.start || state == self.goal {
+                return Err(AlgorithmError::InvalidParameter {
+                    argument: "walls",
+                    reason: format!("({row}, {column}) is the start or the goal"),
+                });
+            }
+            self.walls[state] = true;
+        }
+        Ok(self)
+    }
+
+    pub fn with_rewards(mut self, step_reward: f64, goal_reward: f64) -> Self {
+        self.step_reward = step_reward;
+        self.goal_reward = goal_reward;
+        self
+    }
+
+    /// Makes the moves random with the probability `slip`, drawn from a generator seeded with
+    /// `seed`.
+    pub fn with_slip(mut self, slip: f64, seed: u64) -> AlgorithmResult<Self> {
+        check_probability("slip", slip)?;
+        self.slip = slip;
+        self.rng = ChaCha8Rng::seed_from_u64(seed);
+        Ok(self)
+    }
+
+    pub fn state(&self, row: usize, column: usize) -> usize {
+        row * self.columns + column
+    }
+
+    pub fn cell(&self, state: usize) -> (usize, usize) {
+        (state / self.columns, state % self.columns)
+    }
+
+    pub fn is_wall(&self, state: usize) -> bool {
+        self.walls[state]
+    }
+
+    pub fn goal(&self) -> usize {
+        self.goal
+    }
+
+    /// Returns the state reached from `state` by the action, without slipping.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the action is not between 0 and 3.
+    pub fn neighbor(&self, state: usize, action: usize) -> usize {
+        let (row, column) = self.cell(state);
+        let (d_row, d_column) = MOVES[action];
+        match (
+            row.checked_add_signed(d_row),
+            column.checked_add_signed(d_column),
+        ) {
+            (Some(row), Some(column)) if row < self.rows && column < self.columns => {
+                let next = self.state(row, column);
+                if self.walls[next] {
+                    state
+                } else {
+                    next
+                }
+            }
+            _ => state,
+        }
+    }
+}
+
+impl Environment for GridWorld {
+    fn reset(&mut self) -> usize {
+        self.position = self.start;
+        self.position
+    }
+
+    fn step(&mut self, mut action: usize) -> (usize, f64, bool) {
+        if self.slip > 0.0 && self.rng.gen_bool(self.slip) {
+            action = self.rng.gen_range(0..MOVES.len());
+        }
+        self.position = self.neighbor(self.position, action);
+        if self.position == self.goal {
+            (self.position, self.goal_reward, true)
+        } else {
+            (self.position, self.step_reward, false)
+        }
+    }
+
+    fn n_states(&self) -> usize {
+        self.rows * self.columns
+    }
+
+    fn n_actions(&self) -> usize {
+        MOVES.len()
+    }
+}
+
+/// A Q-learning agent, which learns the value `Q(s, a)` of taking the action `a` in the state
+/// `s`, the expected discounted sum of the rewards to come if it then acts at best.
+///
+/// After every step from `s` by `a` to `s'` with the reward `r`, the value moves toward the
+/// Bellman target:
+///
+/// `Q(s, a) += learning_rate * (r + discount * max_a' Q(s', a') - Q(s, a))`
+///
+/// the maximum being 0 when the episode is over. The agent follows an epsilon-greedy policy,
+/// taking a random action with the probability epsilon, and the best one known otherwise, with
+/// epsilon multiplied by a decay after every episode, down to a minimum. The target does not
+/// depend on the action the agent takes next, so that the values converge to those of the best
+/// policy, even while the agent explores.
+pub struct QLearning {
+    /// The values of the actions of every state
+    q: Vec<Vec<f64>>,
+    learning_rate: f64,
+    discount: f64,
+    epsilon: f64,
+    epsilon_decay: f64,
+    min_epsilon: f64,
+    max_steps: usize,
+    rng: ChaCha8Rng,
+}
+
+impl QLearning {
+    /// Creates an agent for `n_states` states and `n_actions` actions, with every value at 0,
+    /// a learning rate in (0, 1], and a discount and an epsilon in [0, 1]. Epsilon does not
+    /// decay, and episodes stop after 1000 steps, until said otherwise.
+    pub fn new(
+        n_states: usize,
+        n_actions: usize,
+        learning_rate: f64,
+        discount: f64,
+        epsilon: f64,
+    ) -> AlgorithmResult<Self> {
+        if n_states == 0 || n_actions == 0 {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "n_actions",
+                reason: "there must be states and actions".to_string(),
+            });
+        }
+        if !(learning_rate > 0.0 && learning_rate <= 1.0) {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "learning_rate",
+                reason: format!("must be in (0, 1], got {learning_rate}"),
+            });
+        }
+        check_probability("discount", discount)?;
+        check_probability("epsilon", epsilon)?;
+        Ok(QLearning {
+            q: vec![vec![0.0; n_actions]; n_states],
+            learning_rate,
+            discount,
+            epsilon,
+            epsilon_decay: 1.0,
+            min_epsilon: 0.0,
+            max_steps: 1000,
+            rng: ChaCha8Rng::seed_from_u64(0),
+        })
+    }
+
+    /// Multiplies epsilon by `decay` after every episode, without going below `min_epsilon`.
+    pub fn with_epsilon_decay(mut self, decay: f64, min_epsilon: f64) -> AlgorithmResult<Self> {
+        check_probability("decay", decay)?;
+        check_probability("min_epsilon", min_epsilon)?;
+        self.epsilon_decay = decay;
+        self.min_epsilon = min_epsilon;
+        Ok(self)
+    }
+
+    /// Sets the seed of the random choice of the exploring actions.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = ChaCha8Rng::seed_from_u64(seed);
+        self
+    }
+
+    /// Ends the episodes which last `max_steps` steps without getting to their end.
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    pub fn epsilon(&self) -> f64 {
+        self.epsilon
+    }
+
+    /// Returns the values of the actions in the state.
+    pub fn q_values(&self, state: usize) -> &[f64] {
+        &self.q[state]
+    }
+
+    /// Returns the action of the largest value in the state, the first one for a tie.
+    pub fn best_action(&self, state: usize) -> usize {
+        let values = &self.q[state];
+        (1..values.len()).fold(0, |best, a| if values[a] > values[best] { a } else { best })
+    }
+
+    /// Returns the best action of every state.
+    pub fn policy(&self) -> Vec<usize> {
+        (0..self.q.len()).map(|s| self.best_action(s)).collect()
+    }
+
+    /// Moves `Q(state, action)` toward the Bellman target of a step to `next` with `reward`.
+    pub fn update(&mut self, state: usize, action: usize, reward: f64, next: usize, done: bool) {
+        let future = if done {
+            0.0
+        } else {
+            self.q[next]
+                .iter()
+                .copied()
+                .fold(f64::NEG_INFINITY, f64::max)
+        };
+        let target = reward + self.discount * future;
+        let value = &mut self.q[state][action];
+        *value += self.learning_rate * (target - *value);
+    }
+
+    /// Runs `episodes` episodes in the environment, learning from every step, and returns the
+    /// sum of the rewards of every episode.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the environment has more states or actions than the agent.
+    pub fn train<E: Environment>(&mut self, env: &mut E, episodes: usize) -> Vec<f64> {
+        assert!(
+            env.n_states() <= self.q.len() && env.n_actions() <= self.q[0].len(),
+            "the environment has more states or actions than the agent"
+        );
+        let mut rewards = Vec::with_capacity(episodes);
+        for _ in 0..episodes {
+            let mut state = env.reset();
+            let mut total = 0.0;
+            for _ in 0..self.max_steps {
+                let action = if self.rng.gen_bool(self.epsilon) {
+                    self.rng.gen_range(0..env.n_actions())
+                } else {
+                    self.best_action(state)
+                };
+                let (next, reward, done) = env.step(action);
+                self.update(state, action, reward, next, done);
+                total += reward;
+                state = next;
+                if done {
+                    break;
+                }
+            }
+            rewards.push(total);
+            self.epsilon = (self.epsilon * self.epsilon_decay).max(self.min_epsilon);
+        }
+        rewards
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// A 5x5 grid from the top left corner to the bottom right one, around two walls
+    fn grid() -> GridWorld {
+        GridWorld::new(5, 5, (0, 0), (4, 4))
+            .unwrap()
+            .with_walls(&[(1, 1), (1, 2), (1, 3), (3, 1), (3, 2), (3, 3)])
+            .unwrap()
+    }
+
+    fn agent(env: &GridWorld, seed: u64) -> QLearning {
+        QLearning::new(env.n_states(), env.n_actions(), 0.5, 0.95, 1.0)
+            .unwrap()
+            .with_epsilon_decay(0.995, 0.05)
+            .unwrap()
+            .with_max_steps(200)
+            .with_seed(seed)
+    }
+
+    /// The number of moves from every cell to the goal, by a breadth-first search from it
+    fn distances(env: &GridWorld) -> Vec<Option<usize>> {
+        let mut distances = vec![None; env.n_states()];
+        distances[env.goal()] = Some(0);
+        let mut queue = VecDeque::from([env.goal()]);
+        while let Some(state) = queue.pop_front() {
+            // the moves are symmetric, so that the neighbors of a cell can reach it
+            for action in 0..4 {
+                let next = env.neighbor(state, action);
+                if distances[next].is_none() {
+                    distances[next] = Some(distances[state].unwrap() + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+        distances
+    }
+
+    /// Returns the number of greedy moves from the state to the goal, if it gets there.
+    fn greedy_path(env: &GridWorld, agent: &QLearning, mut state: usize) -> Option<usize> {
+        for steps in 0..env.n_states() {
+            if state == env.goal() {
+                return Some(steps);
+            }
+            state = env.neighbor(state, agent.best_action(state));
+        }
+        None
+    }
+
+    fn mean(values: &[f64]) -> f64 {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+
+    #[test]
+    fn grid_moves() {
+        let mut env = grid();
+        assert_eq!(env.reset(), 0);
+        // the edge and the walls block the moves
+        assert_eq!(env.step(0), (0, -1.0, false));
+        assert_eq!(env.step(3), (0, -1.0, false));
+        assert_eq!(env.step(1), (1, -1.0, false));
+        assert_eq!(env.step(2), (1, -1.0, false));
+        assert_eq!(env.cell(env.neighbor(env.state(2, 2), 0)), (2, 2));
+        assert_eq!(env.neighbor(env.state(3, 4), 2), env.goal());
+        let mut env = GridWorld::new(1, 2, (0, 0), (0, 1))
+            .unwrap()
+            .with_rewards(-0.5, 3.0);
+        env.reset();
+        assert_eq!(env.step(1), (1, 3.0, true));
+
+        assert!(GridWorld::new(0, 3, (0, 0), (0, 0)).is_err());
+        assert!(GridWorld::new(3, 3, (3, 0), (0, 0)).is_err());
+        assert!(grid().with_walls(&[(4, 4)]).is_err());
+        assert!(grid().with_walls(&[(5, 0)]).is_err());
+        assert!(grid().with_slip(1.5, 0).is_err());
+    }
+
+    #[test]
+    fn learns_shortest_paths() {
+        let mut env = grid();
+        let mut agent = agent(&env, 1271);
+        agent.train(&mut env, 2000);
+        let distances = distances(&env);
+        for (state, &distance) in distances.iter().enumerate() {
+            if let Some(distance) = distance {
+                assert_eq!(
+                    greedy_path(&env, &agent, state),
+                    Some(distance),
+                    "from {:?}",
+                    env.cell(state)
+                );
+            }
+        }
+        // the walls cannot be reached
+        assert_eq!(distances.iter().filter(|d| d.is_none()).count(), 6);
+    }
+
+    #[test]
+    fn rewards_improve() {
+        let mut env = grid();
+        let mut agent = agent(&env, 1272);
+        let rewards = agent.train(&mut env, 1000);
+        let (first, last) = (mean(&rewards[..100]), mean(&rewards[900..]));
+        assert!(last > first + 20.0, "{first} {last}");
+        // the shortest path takes 8 moves, 7 steps before the goal
+        assert!(last > 10.0 - 7.0 - 2.0, "{last}");
+        assert!((agent.epsilon() - 0.05).abs() < 1e-12);
+    }
+
+    #[test]
+    fn converges_with_slips() {
+        let mut env = grid().with_slip(0.2, 1273).unwrap();
+        let mut agent = agent(&env, 1274);
+        let rewards = agent.train(&mut env, 3000);
+        assert!(mean(&rewards[2900..]) > mean(&rewards[..100]));
+        // the moves the agent means to make still take it to the goal, about as fast
+        let distances = distances(&env);
+        for (state, &distance) in distances.iter().enumerate() {
+            if let Some(distance) = distance {
+                let steps = greedy_path(&env, &agent, state);
+                assert!(steps.is_some_and(|steps| steps <= distance + 2), "{state}");
+            }
+        }
+    }
+
+    /// A chain of three states, through which every action moves forward
+    struct Chain {
+        state: usize,
+    }
+
+    impl Environment for Chain {
+        fn reset(&mut self) -> usize {
+            self.state = 0;
+            0
+        }
+
+        fn step(&mut self, _action: usize) -> (usize, f64, bool) {
+            self.state += 1;
+            (self.state, self.state as f64, self.state == 2)
+        }
+
+        fn n_states(&self) -> usize {
+            3
+        }
+
+        fn n_actions(&self) -> usize {
+            2
+        }
+    }
+
+    #[test]
+    fn bellman_updates() {
+        let (alpha, gamma) = (0.5, 0.9);
+        // without exploring, the agent always takes the first of the tied actions
+        let mut agent = QLearning::new(3, 2, alpha, gamma, 0.0).unwrap();
+        let mut env = Chain { state: 0 };
+        assert_eq!(agent.train(&mut env, 1), [3.0]);
+        // the first step sees no value in the state 1 yet, and the last one ends the episode
+        assert_eq!(agent.q_values(0), [alpha * 1.0, 0.0]);
+        assert_eq!(agent.q_values(1), [alpha * 2.0, 0.0]);
+
+        agent.train(&mut env, 1);
+        let q01 = alpha + alpha * (1.0 + gamma * alpha * 2.0 - alpha);
+        let q11 = alpha * 2.0 + alpha * (2.0 - alpha * 2.0);
+        assert!((agent.q_values(0)[0] - q01).abs() < 1e-12);
+        assert!((agent.q_values(1)[0] - q11).abs() < 1e-12);
+        assert_eq!(agent.q_values(2), [0.0, 0.0]);
+        assert_eq!(agent.policy(), [0, 0, 0]);
+
+        agent.update(0, 1, 5.0, 1, false);
+        assert!((agent.q_values(0)[1] - alpha * (5.0 + gamma * q11)).abs() < 1e-12);
+        assert_eq!(agent.best_action(0), 1);
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        assert!(QLearning::new(0, 2, 0.5, 0.9, 0.1).is_err());
+        assert!(QLearning::new(3, 2, 0.0, 0.9, 0.1).is_err());
+        assert!(QLearning::new(3, 2, 0.5, 1.1, 0.1).is_err());
+        assert!(QLearning::new(3, 2, 0.5, 0.9, -0.1).is_err());
+        let agent = QLearning::new(3, 2, 0.5, 0.9, 0.1).unwrap();
+        assert!(agent.with_epsilon_decay(1.5, 0.0).is_err());
+    }
+}
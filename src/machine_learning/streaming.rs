@@ -0,0 +1,445 @@
+use crate::error::{AlgorithmError, AlgorithmResult};
+use std::collections::VecDeque;
+
+fn check_value(value: f64) -> AlgorithmResult<()> {
+    if value.is_finite() {
+        Ok(())
+    } else {
+        Err(AlgorithmError::NonFiniteValue { argument: "value" })
+    }
+}
+
+/// The statistics of the last `capacity` values of a stream, updated in O(1) for every value.
+///
+/// The values are kept in a ring buffer, together with:
+/// - their mean and the sum of their squared deviations from it, with Welford's updates for the
+///   value coming in and the one going out, which cancel less than sums of squares,
+/// - the sum of the values weighted by their position, for the slope of their regression line,
+/// - two monotonic deques, of the values which can still become the minimum or the maximum as
+///   older ones expire, with the current one at the front.
+///
+/// Until the window is full, the statistics are those of the values pushed so far, and they are
+/// `None` only for an empty window.
+pub struct SlidingWindow {
+    buffer: Vec<f64>,
+    /// The position in `buffer` of the oldest value
+    start: usize,
+    len: usize,
+    /// The number of values pushed since the creation, which numbers every value in the deques
+    pushed: usize,
+    mean: f64,
+    /// The sum of the squared deviations from the mean
+    squares: f64,
+    /// `sum(k * x_k)` over the values `x_0` (the oldest) to `x_{len - 1}`
+    weighted_sum: f64,
+    /// Increasing values, each with its number
+    minimums: VecDeque<(usize, f64)>,
+    /// Decreasing values, each with its number
+    maximums: VecDeque<(usize, f64)>,
+}
+
+impl SlidingWindow {
+    /// Creates an empty window of the last `capacity` values.
+    pub fn new(capacity: usize) -> AlgorithmResult<Self> {
+        if capacity == 0 {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "capacity",
+                reason: "a window holds at least one value".to_string(),
+            });
+        }
+        Ok(SlidingWindow {
+            buffer: vec![0.0; capacity],
+            start: 0,
+            len: 0,
+            pushed: 0,
+            mean: 0.0,
+            squares: 0.0,
+            weighted_sum: 0.0,
+            minimums: VecDeque::new(),
+            maximums: VecDeque::new(),
+        })
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == self.capacity()
+    }
+
+    /// Adds a value to the window, and removes the oldest one if the window is full, in
+    /// amortized O(1).
+    pub fn push(&mut self, value: f64) -> AlgorithmResult<()> {
+        check_value(value)?;
+        if self.is_full() {
+            self.remove_oldest();
+        }
+        let end = (self.start + self.len) % self.capacity();
+        self.buffer[end] = value;
+        self.weighted_sum += self.len as f64 * value;
+        self.len += 1;
+        let deviation = value - self.mean;
+        self.mean += deviation / self.len as f64;
+        self.squares += deviation * (value - self.mean);
+
+        let number = self.pushed;
+        self.pushed += 1;
+        while self.minimums.back().is_some_and(|&(_, x)| x >= value) {
+            self.minimums.pop_back();
+        }
+        self.minimums.push_back((number, value));
+        while self.maximums.back().is_some_and(|&(_, x)| x <= value) {
+            self.maximums.pop_back();
+        }
+        self.maximums.push_back((number, value));
+        Ok(())
+    }
+
+    fn remove_oldest(&mut self) {
+        let oldest = self.buffer[self.start];
+        let sum = self.mean * self.len as f64;
+        // every other value moves down one position
+        self.weighted_sum -= sum - oldest;
+        self.start = (self.start + 1) % self.capacity();
+        self.len -= 1;
+        if self.len == 0 {
+            self.mean = 0.0;
+            self.squares = 0.0;
+        } else {
+            let deviation = oldest - self.mean;
+            self.mean -= deviation / self.len as f64;
+            self.squares = (self.squares - deviation * (oldest - self.mean)).max(0.0);
+        }
+
+        let number = self.pushed - self.len - 1;
+        if self.minimums.front().is_some_and(|&(n, _)| n == number) {
+            self.minimums.pop_front();
+        }
+        if self.maximums.front().is_some_and(|&(n, _)| n == number) {
+            self.maximums.pop_front();
+        }
+    }
+
+    /// Returns the values of the window, from the oldest to the newest.
+    pub fn values(&self) -> impl Iterator<Item = f64> + '_ {
+        (0..self.len).map(|k| self.buffer[(self.start + k) % self.capacity()])
+    }
+
+    /// Returns the newest value.
+    pub fn last(&self) -> Option<f64> {
+        (!self.is_empty()).then(|| self.buffer[(self.start + self.len - 1) % self.capacity()])
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        (!self.is_empty()).then_some(self.mean)
+    }
+
+    /// Returns the population variance of the values, divided by their number.
+    pub fn variance(&self) -> Option<f64> {
+        (!self.is_empty()).then(|| self.squares / self.len as f64)
+    }
+
+    pub fn std_dev(&self) -> Option<f64> {
+        self.variance().map(f64::sqrt)
+    }
+
+    pub fn min(&self) -> Option<f64> {
+        self.minimums.front().map(|&(_, x)| x)
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        self.maximums.front().map(|&(_, x)| x)
+    }
+
+    /// Returns the slope of the least-squares line through the values, against their positions
+    /// `0, 1, ...` in the window, which is `None` for fewer than two values.
+    pub fn slope(&self) -> Option<f64> {
+        if self.len < 2 {
+            return None;
+        }
+        let n = self.len as f64;
+        // the positions have the mean (n - 1) / 2 and the variance (n^2 - 1) / 12
+        let covariance = self.weighted_sum / n - (n - 1.0) / 2.0 * self.mean;
+        Some(covariance / ((n * n - 1.0) / 12.0))
+    }
+}
+
+/// The exponentially weighted moving average of a stream, `s = alpha * x + (1 - alpha) * s`,
+/// which starts at the first value.
+/// <https://en.wikipedia.org/wiki/Exponential_smoothing>
+///
+/// Unlike the statistics of a window, it remembers every value, with weights decreasing
+/// geometrically with their age, so that it only needs its current value.
+pub struct Ewma {
+    alpha: f64,
+    value: Option<f64>,
+}
+
+impl Ewma {
+    /// Creates the average with the weight `alpha` of the newest value, in (0, 1].
+    pub fn new(alpha: f64) -> AlgorithmResult<Self> {
+        if !(alpha > 0.0 && alpha <= 1.0) {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "alpha",
+                reason: format!("must be in (0, 1], got {alpha}"),
+            });
+        }
+        Ok(Ewma { alpha, value: None })
+    }
+
+    /// Adds a value to the average, and returns the new average.
+    pub fn push(&mut self, value: f64) -> AlgorithmResult<f64> {
+        check_value(value)?;
+        let average = match self.value {
+            None => value,
+            Some(average) => self.alpha * value + (1.0 - self.alpha) * average,
+        };
+        self.value = Some(average);
+        Ok(average)
+    }
+
+    /// Returns the average, `None` before the first value.
+    pub fn value(&self) -> Option<f64> {
+        self.value
+    }
+}
+
+/// The number of features of `WindowFeaturizer`
+pub const WINDOW_FEATURES: usize = 6;
+
+/// Turns a stream of readings into feature vectors for online learning, with the statistics of a
+/// sliding window: `[mean, std_dev, min, max, last, slope]`.
+///
+/// The statistics of a partial window would not mean the same as those of a full one for a
+/// model, so that no features come out before the window is full, and then one vector for every
+/// reading. It suits `Perceptron::predict` and `Perceptron::partial_fit`, with a perceptron of
+/// `WINDOW_FEATURES` inputs.
+pub struct WindowFeaturizer {
+    window: SlidingWindow,
+}
+
+impl WindowFeaturizer {
+    /// Creates a featurizer of windows of `size` readings, at least 2 for the slope.
+    pub fn new(size: usize) -> AlgorithmResult<Self> {
+        if size < 2 {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "size",
+                reason: "the slope needs windows of at least 2 readings".to_string(),
+            });
+        }
+        Ok(WindowFeaturizer {
+            window: SlidingWindow::new(size)?,
+        })
+    }
+
+    pub fn window(&self) -> &SlidingWindow {
+        &self.window
+    }
+
+    /// Adds a reading, and returns the features of the window ending with it, once the window
+    /// is full.
+    pub fn push(&mut self, reading: f64) -> AlgorithmResult<Option<[f64; WINDOW_FEATURES]>> {
+        self.window.push(reading)?;
+        if !self.window.is_full() {
+            return Ok(None);
+        }
+        let window = &self.window;
+        Ok(Some([
+            window.mean().unwrap(),
+            window.std_dev().unwrap(),
+            window.min().unwrap(),
+            window.max().unwrap(),
+            window.last().unwrap(),
+            window.slope().unwrap(),
+        ]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::machine_learning::{ActivationFunction, Perceptron};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn assert_close(found: f64, expected: f64) {
+        assert!(
+            (found - expected).abs() <= 1e-9 * expected.abs().max(1.0),
+            "{found} {expected}"
+        );
+    }
+
+    /// The slope of the least-squares line, from its definition
+    fn slope(values: &[f64]) -> f64 {
+        let n = values.len() as f64;
+        let mean_k = (n - 1.0) / 2.0;
+        let mean_x = values.iter().sum::<f64>() / n;
+        let covariance: f64 = values
+            .iter()
+            .enumerate()
+            .map(|(k, x)| (k as f64 - mean_k) * (x - mean_x))
+            .sum();
+        let variance: f64 = (0..values.len()).map(|k| (k as f64 - mean_k).powi(2)).sum();
+        covariance / variance
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let mut rng = ChaCha8Rng::seed_from_u64(125);
+        for capacity in [1, 2, 7, 50] {
+            let mut window = SlidingWindow::new(capacity).unwrap();
+            let mut values: VecDeque<f64> = VecDeque::new();
+            for step in 0..5000 {
+                // a drifting signal, with runs of equal values
+                let value = if rng.gen_bool(0.1) {
+                    values.back().copied().unwrap_or(0.0)
+                } else {
+                    (step as f64 / 100.0).sin() * 100.0 + rng.gen_range(-10.0..10.0)
+                };
+                window.push(value).unwrap();
+                values.push_back(value);
+                if values.len() > capacity {
+                    values.pop_front();
+                }
+
+                let contents: Vec<f64> = values.iter().copied().collect();
+                assert!(window.values().eq(contents.iter().copied()));
+                let n = contents.len() as f64;
+                let mean = contents.iter().sum::<f64>() / n;
+                let variance = contents.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+                assert_close(window.mean().unwrap(), mean);
+                assert!((window.variance().unwrap() - variance).abs() < 1e-6);
+                let min = contents.iter().copied().fold(f64::INFINITY, f64::min);
+                let max = contents.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+                assert_eq!(window.min(), Some(min));
+                assert_eq!(window.max(), Some(max));
+                assert_eq!(window.last(), Some(value));
+                match window.slope() {
+                    Some(found) => assert!((found - slope(&contents)).abs() < 1e-6),
+                    None => assert_eq!(contents.len(), 1),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn extremes_expire() {
+        let mut window = SlidingWindow::new(3).unwrap();
+        assert_eq!(
+            (window.min(), window.mean(), window.slope()),
+            (None, None, None)
+        );
+        let mut extremes = vec![];
+        for value in [5.0, 1.0, 3.0, 4.0, 9.0, 2.0, 2.0, 8.0] {
+            window.push(value).unwrap();
+            extremes.push((window.min().unwrap(), window.max().unwrap()));
+        }
+        assert_eq!(
+            extremes,
+            [
+                (5.0, 5.0),
+                (1.0, 5.0),
+                (1.0, 5.0),
+                (1.0, 4.0),
+                (3.0, 9.0),
+                (2.0, 9.0),
+                (2.0, 9.0),
+                (2.0, 8.0)
+            ]
+        );
+        // partial windows give the statistics of what they hold
+        let mut window = SlidingWindow::new(10).unwrap();
+        window.push(1.0).unwrap();
+        window.push(3.0).unwrap();
+        assert!(!window.is_full());
+        assert_eq!(window.mean(), Some(2.0));
+        assert_eq!(window.variance(), Some(1.0));
+        assert_eq!(window.slope(), Some(2.0));
+        assert_eq!(
+            window.push(f64::NAN),
+            Err(AlgorithmError::NonFiniteValue { argument: "value" })
+        );
+        assert_eq!(window.len(), 2);
+        assert!(SlidingWindow::new(0).is_err());
+    }
+
+    #[test]
+    fn ewma_recurrence() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1250);
+        for alpha in [0.1, 0.5, 1.0] {
+            let mut ewma = Ewma::new(alpha).unwrap();
+            assert_eq!(ewma.value(), None);
+            let mut expected: Option<f64> = None;
+            for _ in 0..1000 {
+                let x: f64 = rng.gen_range(-1.0..1.0);
+                expected = Some(expected.map_or(x, |s| alpha * x + (1.0 - alpha) * s));
+                assert_close(ewma.push(x).unwrap(), expected.unwrap());
+            }
+            assert_eq!(ewma.value(), expected);
+        }
+        for alpha in [0.0, -0.5, 1.5, f64::NAN] {
+            assert!(Ewma::new(alpha).is_err());
+        }
+    }
+
+    #[test]
+    fn featurizer() {
+        let mut featurizer = WindowFeaturizer::new(4).unwrap();
+        for reading in [1.0, 2.0, 3.0] {
+            assert_eq!(featurizer.push(reading), Ok(None));
+        }
+        let features = featurizer.push(6.0).unwrap().unwrap();
+        let expected = [3.0, (14.0f64 / 4.0).sqrt(), 1.0, 6.0, 6.0, 1.6];
+        for (found, expected) in features.into_iter().zip(expected) {
+            assert_close(found, expected);
+        }
+        assert!(featurizer.push(7.0).unwrap().is_some());
+        assert!(WindowFeaturizer::new(1).is_err());
+    }
+
+    #[test]
+    fn online_perceptron_beats_a_constant() {
+        // predict whether a noisy wave goes up at the next reading
+        let mut rng = ChaCha8Rng::seed_from_u64(1251);
+        let signal: Vec<f64> = (0..20_000)
+            .map(|t| (t as f64 / 20.0).sin() + rng.gen_range(-0.005..0.005))
+            .collect();
+        let mut featurizer = WindowFeaturizer::new(8).unwrap();
+        let mut perceptron = Perceptron::with_weights(
+            vec![0.0; WINDOW_FEATURES + 1],
+            0.01,
+            ActivationFunction::Step,
+        )
+        .unwrap();
+        let (mut correct, mut rises, mut total) = (0, 0, 0);
+        for (t, pair) in signal.windows(2).enumerate() {
+            let Some(features) = featurizer.push(pair[0]).unwrap() else {
+                continue;
+            };
+            // a constant input as the bias
+            let mut inputs = features.to_vec();
+            inputs.push(1.0);
+            let target = if pair[1] > pair[0] { 1.0 } else { -1.0 };
+            // the second half of the stream measures the accuracy of the predictions made before
+            // every update
+            if t >= signal.len() / 2 {
+                total += 1;
+                rises += (target > 0.0) as usize;
+                correct += (perceptron.predict(&inputs).unwrap() == target) as usize;
+            }
+            perceptron.partial_fit(&inputs, target).unwrap();
+        }
+        let accuracy = correct as f64 / total as f64;
+        let constant = rises.max(total - rises) as f64 / total as f64;
+        assert!(accuracy > constant + 0.2, "{accuracy} {constant}");
+    }
+}
@@ -0,0 +1,203 @@
+use crate::error::{check_positive, AlgorithmError, AlgorithmResult};
+use crate::machine_learning::Perceptron;
+use crate::math::optimization::{anneal, CoolingSchedule, RngLike};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::cell::RefCell;
+
+/// Most epochs a candidate configuration can train for
+pub const MAX_EPOCHS: usize = 1000;
+
+/// Bounds of the learning rates explored
+const MIN_LEARNING_RATE: f64 = 1e-8;
+const MAX_LEARNING_RATE: f64 = 10.0;
+
+/// Hyperparameters of a perceptron training run
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PerceptronConfig {
+    pub learning_rate: f64,
+    pub epochs: usize,
+}
+
+/// Moves the learning rate by a factor between 1/e and e, and the epochs by up to 10
+fn neighbor(config: &PerceptronConfig, rng: &mut dyn RngLike) -> PerceptronConfig {
+    let learning_rate = config.learning_rate * rng.gen_range(-1.0f64..1.0).exp();
+    let epochs = config.epochs as i64 + rng.gen_range(-10..=10);
+    PerceptronConfig {
+        learning_rate: learning_rate.clamp(MIN_LEARNING_RATE, MAX_LEARNING_RATE),
+        epochs: epochs.clamp(1, MAX_EPOCHS as i64) as usize,
+    }
+}
+
+/// Tunes the learning rate and the number of epochs of a perceptron with simulated annealing.
+///
+/// The energy of a configuration is the mean squared error on `validation` of a perceptron
+/// built by `perceptron_factory` with its learning rate, then trained on `training` for its
+/// number of epochs. Diverging runs get an infinite error. Every run shuffles the training
+/// samples with a generator seeded from `seed`, so as long as the factory is deterministic
+/// (for instance with `Perceptron::with_weights`), so is the whole search.
+///
+/// Returns the best configuration found and its validation error, or the first error returned
+/// by the factory, the training or the validation, for instance when the data sets do not
+/// match the perceptron.
+pub fn anneal_perceptron_config<F>(
+    perceptron_factory: F,
+    training: &[Vec<f64>],
+    validation: &[Vec<f64>],
+    initial: PerceptronConfig,
+    schedule: CoolingSchedule,
+    iterations: usize,
+    seed: u64,
+) -> AlgorithmResult<(PerceptronConfig, f64)>
+where
+    F: Fn(f64) -> AlgorithmResult<Perceptron>,
+{
+    check_positive("initial.learning_rate", initial.learning_rate)?;
+    if !(1..=MAX_EPOCHS).contains(&initial.epochs) {
+        return Err(AlgorithmError::InvalidParameter {
+            argument: "initial.epochs",
+            reason: format!("must be between 1 and {MAX_EPOCHS}, got {}", initial.epochs),
+        });
+    }
+    let first_error = RefCell::new(None);
+    let validation_error = |config: &PerceptronConfig| {
+        let error = perceptron_factory(config.learning_rate).and_then(|mut perceptron| {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            perceptron.train_with_rng(training, config.epochs, &mut rng)?;
+            perceptron.mean_squared_error(validation)
+        });
+        match error {
+            Ok(error) if error.is_finite() => error,
+            Ok(_) => f64::INFINITY,
+            Err(err) => {
+                first_error.borrow_mut().get_or_insert(err);
+                f64::INFINITY
+            }
+        }
+    };
+    let best = anneal(
+        initial,
+        validation_error,
+        neighbor,
+        schedule,
+        iterations,
+        seed,
+    );
+    match first_error.into_inner() {
+        Some(err) => Err(err),
+        None => Ok(best),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::machine_learning::ActivationFunction;
+
+    fn line(xs: &[f64]) -> Vec<Vec<f64>> {
+        xs.iter().map(|&x| vec![x, 1.0, 3.0 * x - 2.0]).collect()
+    }
+
+    fn factory(learning_rate: f64) -> AlgorithmResult<Perceptron> {
+        // a constant feature of 1 lets the perceptron learn the intercept
+        Perceptron::with_weights(vec![0.0, 0.0], learning_rate, ActivationFunction::None)
+    }
+
+    const SCHEDULE: CoolingSchedule = CoolingSchedule::Exponential {
+        initial_temperature: 1.0,
+        cooling_rate: 0.97,
+    };
+
+    #[test]
+    fn beats_the_initial_guess() {
+        let training = line(&[0.0, 0.5, 1.0, 1.5, 2.0, 3.0]);
+        let validation = line(&[0.25, 1.25, 2.5]);
+        let initial = PerceptronConfig {
+            learning_rate: 1e-4,
+            epochs: 5,
+        };
+        let (config, error) =
+            anneal_perceptron_config(factory, &training, &validation, initial, SCHEDULE, 200, 106)
+                .unwrap();
+
+        let mut perceptron = factory(initial.learning_rate).unwrap();
+        perceptron.train(&training, initial.epochs).unwrap();
+        let initial_error = perceptron.mean_squared_error(&validation).unwrap();
+        assert!(error < initial_error / 100.0, "error was {error}");
+        assert!((MIN_LEARNING_RATE..=MAX_LEARNING_RATE).contains(&config.learning_rate));
+        assert!((1..=MAX_EPOCHS).contains(&config.epochs));
+    }
+
+    #[test]
+    fn deterministic_for_a_seed() {
+        let training = line(&[0.0, 1.0, 2.0, 4.0]);
+        let validation = line(&[3.0]);
+        let initial = PerceptronConfig {
+            learning_rate: 0.01,
+            epochs: 20,
+        };
+        let run =
+            || anneal_perceptron_config(factory, &training, &validation, initial, SCHEDULE, 50, 5);
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn invalid_input() {
+        let training = line(&[0.0, 1.0]);
+        let initial = PerceptronConfig {
+            learning_rate: 0.01,
+            epochs: 20,
+        };
+        let run = |initial, validation: &[Vec<f64>]| {
+            anneal_perceptron_config(factory, &training, validation, initial, SCHEDULE, 10, 0)
+        };
+        assert_eq!(
+            run(initial, &[]),
+            Err(AlgorithmError::EmptyInput { argument: "inputs" })
+        );
+        assert_eq!(
+            run(initial, &[vec![1.0, 1.0]]),
+            Err(AlgorithmError::DimensionMismatch {
+                argument: "inputs",
+                expected: 3,
+                found: 2
+            })
+        );
+        let negative_rate = PerceptronConfig {
+            learning_rate: -1.0,
+            epochs: 20,
+        };
+        assert!(matches!(
+            run(negative_rate, &training),
+            Err(AlgorithmError::InvalidParameter {
+                argument: "initial.learning_rate",
+                ..
+            })
+        ));
+        let no_epochs = PerceptronConfig {
+            learning_rate: 0.01,
+            epochs: 0,
+        };
+        assert!(matches!(
+            run(no_epochs, &training),
+            Err(AlgorithmError::InvalidParameter {
+                argument: "initial.epochs",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn neighbors_stay_in_bounds() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut config = PerceptronConfig {
+            learning_rate: MAX_LEARNING_RATE,
+            epochs: 1,
+        };
+        for _ in 0..1000 {
+            config = neighbor(&config, &mut rng);
+            assert!((MIN_LEARNING_RATE..=MAX_LEARNING_RATE).contains(&config.learning_rate));
+            assert!((1..=MAX_EPOCHS).contains(&config.epochs));
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use crate::error::{check_positive, AlgorithmError, AlgorithmResult};
+use crate::machine_learning::Perceptron;
+use crate::math::optimization::ternary_search_min;
+use std::cell::RefCell;
+
+/// Number of epochs every candidate perceptron is trained for
+pub const TUNING_EPOCHS: usize = 100;
+
+/// Number of ternary search iterations over the learning rate
+const SEARCH_ITERATIONS: usize = 40;
+
+/// Searches for the learning rate in `[lo, hi]` minimizing the training error of a perceptron.
+///
+/// For every candidate rate, a fresh perceptron is built with `perceptron_factory`, trained on
+/// `data` for `TUNING_EPOCHS` epochs, and scored with its mean squared error on `data`. Since
+/// good learning rates span several orders of magnitude, the search runs over the logarithm of
+/// the rate, using ternary search. A training run that diverges gets an infinite error.
+///
+/// The search assumes that the error is unimodal in the log of the learning rate: too small a
+/// rate learns too slowly, too big a rate diverges.
+///
+/// Returns the best learning rate found and its training error, or the first error returned
+/// by the factory or the training, for instance when `data` is empty.
+pub fn tune_learning_rate<F>(
+    perceptron_factory: F,
+    data: &[Vec<f64>],
+    lo: f64,
+    hi: f64,
+) -> AlgorithmResult<(f64, f64)>
+where
+    F: Fn(f64) -> AlgorithmResult<Perceptron>,
+{
+    check_positive("lo", lo)?;
+    check_positive("hi", hi)?;
+    if lo > hi {
+        return Err(AlgorithmError::InvalidParameter {
+            argument: "hi",
+            reason: format!("must not be below lo = {lo}, got {hi}"),
+        });
+    }
+    let first_error = RefCell::new(None);
+    let training_error = |log_rate: f64| {
+        let error = perceptron_factory(log_rate.exp()).and_then(|mut perceptron| {
+            perceptron.train(data, TUNING_EPOCHS)?;
+            perceptron.mean_squared_error(data)
+        });
+        match error {
+            Ok(error) if error.is_finite() => error,
+            Ok(_) => f64::INFINITY,
+            Err(err) => {
+                first_error.borrow_mut().get_or_insert(err);
+                f64::INFINITY
+            }
+        }
+    };
+    let (log_rate, error) = ternary_search_min(training_error, lo.ln(), hi.ln(), SEARCH_ITERATIONS);
+    match first_error.into_inner() {
+        Some(err) => Err(err),
+        None => Ok((log_rate.exp(), error)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::machine_learning::ActivationFunction;
+
+    fn doubling_data() -> Vec<Vec<f64>> {
+        vec![
+            vec![0.0, 0.0],
+            vec![1.0, 2.0],
+            vec![2.0, 4.0],
+            vec![3.0, 6.0],
+            vec![8.0, 16.0],
+        ]
+    }
+
+    fn factory(learning_rate: f64) -> AlgorithmResult<Perceptron> {
+        Perceptron::new(1, learning_rate, ActivationFunction::None)
+    }
+
+    fn error_with_rate(learning_rate: f64, data: &[Vec<f64>]) -> f64 {
+        let mut perceptron = factory(learning_rate).unwrap();
+        perceptron.train(data, TUNING_EPOCHS).unwrap();
+        perceptron.mean_squared_error(data).unwrap()
+    }
+
+    #[test]
+    fn better_than_both_ends_of_the_bracket() {
+        let data = doubling_data();
+        let (lo, hi) = (1e-6, 10.0);
+        let (rate, error) = tune_learning_rate(factory, &data, lo, hi).unwrap();
+        assert!((lo..=hi).contains(&rate));
+        assert!(error < 1e-6);
+
+        // too small a rate barely moves the initial weight, which is at most 1 instead of 2
+        assert!(error < error_with_rate(lo, &data));
+        // too big a rate diverges
+        let high_error = error_with_rate(hi, &data);
+        assert!(!high_error.is_finite() || error < high_error);
+    }
+
+    #[test]
+    fn single_candidate() {
+        let data = doubling_data();
+        let (rate, error) = tune_learning_rate(factory, &data, 0.01, 0.01).unwrap();
+        assert!((rate - 0.01).abs() < 1e-12);
+        assert!(error < 1e-6);
+    }
+
+    #[test]
+    fn invalid_input() {
+        let data = doubling_data();
+        assert!(matches!(
+            tune_learning_rate(factory, &data, -1.0, 1.0),
+            Err(AlgorithmError::InvalidParameter { argument: "lo", .. })
+        ));
+        assert!(matches!(
+            tune_learning_rate(factory, &data, 1.0, 0.1),
+            Err(AlgorithmError::InvalidParameter { argument: "hi", .. })
+        ));
+        assert_eq!(
+            tune_learning_rate(factory, &[], 0.01, 1.0),
+            Err(AlgorithmError::EmptyInput { argument: "inputs" })
+        );
+        // the factory builds perceptrons with two inputs for rows holding one
+        let two_inputs = |rate| Perceptron::new(2, rate, ActivationFunction::None);
+        assert_eq!(
+            tune_learning_rate(two_inputs, &data, 0.01, 1.0),
+            Err(AlgorithmError::DimensionMismatch {
+                argument: "inputs",
+                expected: 3,
+                found: 2
+            })
+        );
+    }
+}
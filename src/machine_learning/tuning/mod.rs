@@ -0,0 +1,5 @@
+mod annealing;
+mod learning_rate;
+
+pub use self::annealing::{anneal_perceptron_config, PerceptronConfig, MAX_EPOCHS};
+pub use self::learning_rate::tune_learning_rate;
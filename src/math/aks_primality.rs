@@ -0,0 +1,218 @@
+// The AKS primality test
+// https://en.wikipedia.org/wiki/AKS_primality_test
+//
+// The first deterministic primality test proven to run in polynomial time,
+// based on the fact that n is prime if and only if (X + a)^n = X^n + a modulo n
+// for every a coprime to n. Checking this identity is exponential, but it is
+// enough to check it modulo X^r - 1 for a small r and few values of a.
+
+use super::{greatest_common_divisor_stein as gcd, is_prime_deterministic};
+
+/// Above this, the polynomial identities take too long to check, and the test falls back to
+/// `is_prime_deterministic`, the Miller-Rabin test that is deterministic for 64-bit numbers.
+const AKS_LIMIT: u64 = 1 << 12;
+
+/// Returns the base and the exponent of `n` if it is `base^exponent` for some exponent of at
+/// least 2, with the largest such exponent.
+pub fn is_perfect_power(n: u64) -> Option<(u64, u32)> {
+    if n < 2 {
+        return Some((n, 2));
+    }
+    (2..=n.ilog2()).rev().find_map(|exponent| {
+        let base = integer_root(n, exponent);
+        (base.checked_pow(exponent) == Some(n)).then_some((base, exponent))
+    })
+}
+
+/// Returns the largest integer whose `exponent`-th power is at most `n`.
+fn integer_root(n: u64, exponent: u32) -> u64 {
+    let mut root = (n as f64).powf(1.0 / exponent as f64).round() as u64;
+    // the floating point estimate may be off by one either way
+    while root.checked_pow(exponent).is_none_or(|power| power > n) {
+        root -= 1;
+    }
+    while (root + 1)
+        .checked_pow(exponent)
+        .is_some_and(|power| power <= n)
+    {
+        root += 1;
+    }
+    root
+}
+
+/// Returns the smallest `k > 0` such that `n^k = 1` modulo `r`, if it is at most `limit`.
+fn multiplicative_order(n: u64, r: u64, limit: u64) -> Option<u64> {
+    let mut power = 1;
+    for k in 1..=limit {
+        power = power * n % r;
+        if power == 1 {
+            return Some(k);
+        }
+    }
+    None
+}
+
+fn euler_totient(mut r: u64) -> u64 {
+    let mut result = r;
+    let mut p = 2;
+    while p * p <= r {
+        if r.is_multiple_of(p) {
+            while r.is_multiple_of(p) {
+                r /= p;
+            }
+            result -= result / p;
+        }
+        p += 1;
+    }
+    if r > 1 {
+        result -= result / r;
+    }
+    result
+}
+
+fn non_zero_terms(p: &[u64]) -> Vec<(usize, u64)> {
+    p.iter()
+        .copied()
+        .enumerate()
+        .filter(|&(_, c)| c != 0)
+        .collect()
+}
+
+/// Multiplies two polynomials of degree less than `r`, modulo `X^r - 1` and `n`, which must be
+/// small enough for the sums of `r` products to fit in 64 bits.
+fn multiply(a: &[u64], b: &[u64], n: u64) -> Vec<u64> {
+    let r = a.len();
+    let mut product = vec![0; r];
+    // the first powers are sparse, so only the non-zero terms are multiplied
+    let b_terms = non_zero_terms(b);
+    for (i, x) in non_zero_terms(a) {
+        for &(j, y) in &b_terms {
+            // X^(i + j) wraps around to X^(i + j - r)
+            let k = if i + j < r { i + j } else { i + j - r };
+            product[k] += x * y;
+        }
+    }
+    product.into_iter().map(|c| c % n).collect()
+}
+
+/// Squares a polynomial like `multiply`, computing every cross product once.
+fn square(a: &[u64], n: u64) -> Vec<u64> {
+    let r = a.len();
+    let mut product = vec![0; r];
+    let terms = non_zero_terms(a);
+    for (t, &(i, x)) in terms.iter().enumerate() {
+        product[2 * i % r] += x * x;
+        for &(j, y) in &terms[t + 1..] {
+            let k = if i + j < r { i + j } else { i + j - r };
+            product[k] += 2 * x * y;
+        }
+    }
+    product.into_iter().map(|c| c % n).collect()
+}
+
+/// Checks whether `(X + a)^n = X^n + a` modulo `X^r - 1` and `n`.
+fn satisfies_identity(n: u64, r: usize, a: u64) -> bool {
+    let mut result = vec![0; r];
+    result[0] = 1;
+    let mut base = vec![0; r];
+    base[0] = a % n;
+    base[1 % r] += 1;
+    let mut exponent = n;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = multiply(&result, &base, n);
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            base = square(&base, n);
+        }
+    }
+    let mut expected = vec![0; r];
+    expected[(n % r as u64) as usize] = 1;
+    expected[0] = (expected[0] + a) % n;
+    result == expected
+}
+
+/// Tells whether `n` is prime with the AKS test, for `n` up to 2^12, and with Miller-Rabin
+/// above.
+///
+/// The test follows the version of Agrawal, Kayal and Saxena:
+/// 1. a perfect power is composite,
+/// 2. `r` is the smallest number such that the order of `n` modulo `r` exceeds `log2(n)^2`,
+/// 3. a factor of `n` up to `r` makes it composite, and otherwise `n <= r` is prime,
+/// 4. `n` is prime if and only if `(X + a)^n = X^n + a` modulo `X^r - 1` and `n`, for every
+///    `a` up to `sqrt(phi(r)) log2(n)`.
+pub fn aks_primality_test(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    if n > AKS_LIMIT {
+        return is_prime_deterministic(n);
+    }
+    if is_perfect_power(n).is_some() {
+        return false;
+    }
+    let log = (n as f64).log2();
+    let order_bound = (log * log).floor() as u64;
+    let r = (2..)
+        .find(|&r| gcd(n, r) == 1 && multiplicative_order(n % r, r, order_bound).is_none())
+        .expect("some r of O(log(n)^5) has a large enough order");
+    if (2..=r.min(n - 1)).any(|a| gcd(a, n) > 1) {
+        return false;
+    }
+    if n <= r {
+        return true;
+    }
+    let a_bound = ((euler_totient(r) as f64).sqrt() * log).floor() as u64;
+    (1..=a_bound).all(|a| satisfies_identity(n, r as usize, a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::sieve_of_eratosthenes;
+
+    #[test]
+    fn perfect_powers() {
+        assert_eq!(is_perfect_power(8), Some((2, 3)));
+        assert_eq!(is_perfect_power(12), None);
+        assert_eq!(is_perfect_power(64), Some((2, 6)));
+        assert_eq!(is_perfect_power(36), Some((6, 2)));
+        assert_eq!(is_perfect_power(2), None);
+        assert_eq!(is_perfect_power(1), Some((1, 2)));
+        assert_eq!(is_perfect_power(3u64.pow(40)), Some((3, 40)));
+        assert_eq!(is_perfect_power(u64::MAX), None);
+        assert_eq!(
+            is_perfect_power(4_294_967_295 * 4_294_967_295),
+            Some((4_294_967_295, 2))
+        );
+        assert_eq!(is_perfect_power(1 << 63), Some((2, 63)));
+        for n in 2..10_000u64 {
+            let brute_force = (2..n.ilog2() + 1).rev().find_map(|exponent| {
+                (2..=n)
+                    .take_while(|b| b.pow(exponent) <= n)
+                    .find(|b| b.pow(exponent) == n)
+                    .map(|b| (b, exponent))
+            });
+            assert_eq!(is_perfect_power(n), brute_force, "{n}");
+        }
+    }
+
+    #[test]
+    fn agrees_with_the_sieve() {
+        let primes = sieve_of_eratosthenes(1000);
+        for n in 0..=1000u64 {
+            assert_eq!(aks_primality_test(n), primes.contains(&(n as usize)), "{n}");
+        }
+    }
+
+    #[test]
+    fn large_numbers_use_miller_rabin() {
+        assert!(aks_primality_test(65_537));
+        assert!(!aks_primality_test(65_537 * 65_539));
+        assert!(aks_primality_test(1_000_000_007));
+        assert!(aks_primality_test(18_446_744_073_709_551_557));
+        // the smallest strong pseudoprime to the bases 2, 3, 5 and 7
+        assert!(!aks_primality_test(3_215_031_751));
+    }
+}
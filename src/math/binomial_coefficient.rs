@@ -1,34 +1,11 @@
-extern crate num_bigint;
-extern crate num_traits;
-
 use num_bigint::BigInt;
-use num_traits::FromPrimitive;
 
-/// Calculate binomial coefficient (n choose k).
-///
-/// This function computes the binomial coefficient C(n, k) using BigInt
-/// for arbitrary precision arithmetic.
+/// Calculate binomial coefficient (n choose k), or 0 if `k > n`.
 ///
-/// Formula:
-/// C(n, k) = n! / (k! * (n - k)!)
-///
-/// Reference:
+/// This is `combinatorics::binomial`, which builds a row of Pascal's triangle.
 /// [Binomial Coefficient - Wikipedia](https://en.wikipedia.org/wiki/Binomial_coefficient)
-///
-/// # Arguments
-///
-/// * `n` - The total number of items.
-/// * `k` - The number of items to choose from `n`.
-///
-/// # Returns
-///
-/// Returns the binomial coefficient C(n, k) as a BigInt.
 pub fn binom(n: u64, k: u64) -> BigInt {
-    let mut res = BigInt::from_u64(1).unwrap();
-    for i in 0..k {
-        res = (res * BigInt::from_u64(n - i).unwrap()) / BigInt::from_u64(i + 1).unwrap();
-    }
-    res
+    super::binomial(n, k)
 }
 
 #[cfg(test)]
@@ -21,6 +21,82 @@ pub fn chinese_remainder_theorem(residues: &[i32], modulli: &[i32]) -> Option<i3
     Some(sum % prod)
 }
 
+/// Returns the inverse of `a` modulo `m`, if they are coprime, with the extended Euclidean
+/// algorithm over `i128`.
+fn inverse_mod(a: u64, m: u64) -> Option<u64> {
+    let (mut old_r, mut r) = ((a % m) as i128, m as i128);
+    let (mut old_s, mut s) = (1i128, 0i128);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_s, s) = (s, old_s - quotient * s);
+    }
+    (old_r == 1 || m == 1).then(|| old_s.rem_euclid(m as i128) as u64)
+}
+
+/// Combines `x = r1 mod m1` and `x = r2 mod m2` into `x = r mod m1 m2`, with `r < m1 m2`.
+///
+/// Returns `None` if the moduli are not coprime, or if their product does not fit in a `u64`.
+///
+/// # Panics
+///
+/// Panics if a modulus is 0.
+pub fn crt_combine(r1: u64, m1: u64, r2: u64, m2: u64) -> Option<(u64, u64)> {
+    assert!(m1 > 0 && m2 > 0, "the moduli must be positive");
+    let modulus = m1.checked_mul(m2)?;
+    let inverse = inverse_mod(m1, m2)?;
+    // x = r1 + m1 k, with k = (r2 - r1) / m1 modulo m2, in u128 for moduli past 2^63
+    let (r1, m2) = ((r1 % m1) as u128, m2 as u128);
+    let difference = (r2 as u128 % m2 + m2 - r1 % m2) % m2;
+    let k = difference * inverse as u128 % m2;
+    let remainder = (r1 + m1 as u128 * k) as u64;
+    Some((remainder, modulus))
+}
+
+/// Returns the smallest `x >= 0` with `x = r mod m` for every `(r, m)` of `remainders`, whose
+/// moduli must be pairwise coprime, with Garner's algorithm.
+/// <https://cp-algorithms.com/algebra/garner-algorithm.html>
+///
+/// Instead of the sum of products of the classic formula, which overflows with the product of
+/// the moduli, the solution is built in mixed radix, `x = a0 + a1 m0 + a2 m0 m1 + ...` with
+/// `a_i < m_i`. Every digit `a_i` only needs the digits before it reduced modulo `m_i`, so that
+/// all the products stay below `m_i^2` in `u128`, in O(k^2) operations for `k` congruences.
+/// Only the final sum needs the full size of the solution.
+///
+/// # Panics
+///
+/// Panics if a modulus is 0, if two moduli are not coprime, or if the solution does not fit
+/// in a `u64`.
+pub fn garner_crt(remainders: &[(u64, u64)]) -> u64 {
+    let mut digits: Vec<u64> = Vec::with_capacity(remainders.len());
+    for (i, &(r, m)) in remainders.iter().enumerate() {
+        assert!(m > 0, "the moduli must be positive");
+        // the digits so far, and the product of the moduli so far, modulo m
+        let (mut value, mut radix) = (0u128, 1 % m as u128);
+        for (&digit, &(_, previous)) in digits.iter().zip(&remainders[..i]) {
+            value = (value + digit as u128 * radix) % m as u128;
+            radix = radix * (previous % m) as u128 % m as u128;
+        }
+        let inverse = inverse_mod(radix as u64, m)
+            .unwrap_or_else(|| panic!("the modulus {m} is not coprime with the ones before it"));
+        let difference = ((r % m) as u128 + m as u128 - value) % m as u128;
+        digits.push((difference * inverse as u128 % m as u128) as u64);
+    }
+    // the radix stays None once it overflows, which is fine as long as no digit needs it
+    let too_large = || panic!("the solution does not fit in a u64");
+    let (mut x, mut radix) = (0u64, Some(1u64));
+    for (&digit, &(_, m)) in digits.iter().zip(remainders) {
+        if digit > 0 {
+            let term = radix
+                .and_then(|radix| radix.checked_mul(digit))
+                .unwrap_or_else(too_large);
+            x = x.checked_add(term).unwrap_or_else(too_large);
+        }
+        radix = radix.and_then(|radix| radix.checked_mul(m));
+    }
+    x
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -32,4 +108,87 @@ mod tests {
         assert_eq!(chinese_remainder_theorem(&[1, 4, 6], &[1, 2, 0]), None);
         assert_eq!(chinese_remainder_theorem(&[2, 5, 7], &[6, 9, 15]), None);
     }
+
+    #[test]
+    fn sun_tzu() {
+        // "There are certain things whose number is unknown. If we count them by threes, we have
+        // two left over; by fives, we have three left over; and by sevens, two are left over."
+        assert_eq!(garner_crt(&[(2, 3), (3, 5), (2, 7)]), 23);
+        assert_eq!(crt_combine(2, 3, 3, 5), Some((8, 15)));
+        assert_eq!(crt_combine(8, 15, 2, 7), Some((23, 105)));
+    }
+
+    #[test]
+    fn garner_agrees_with_the_classic_formula() {
+        let moduli = [3, 4, 5, 7, 11, 13, 17];
+        for a in 0..3 {
+            for b in 0..4 {
+                for c in 0..5 {
+                    for d in [0, 3, 6] {
+                        let residues = [a, b, c, d];
+                        let expected = chinese_remainder_theorem(&residues, &moduli[..4]);
+                        let pairs: Vec<(u64, u64)> = residues
+                            .iter()
+                            .zip(&moduli)
+                            .map(|(&r, &m)| (r as u64, m as u64))
+                            .collect();
+                        assert_eq!(Some(garner_crt(&pairs) as i32), expected);
+                    }
+                }
+            }
+        }
+        assert_eq!(garner_crt(&[]), 0);
+        assert_eq!(garner_crt(&[(5, 1), (3, 4)]), 3);
+        // remainders past their moduli are reduced
+        assert_eq!(garner_crt(&[(5, 3), (13, 5)]), 8);
+    }
+
+    #[test]
+    fn large_moduli() {
+        // the two largest primes below 2^32, whose product is close to 2^64
+        let moduli = [4_294_967_291u64, 4_294_967_279];
+        let x = 18_000_000_000_000_000_000u64;
+        let pairs: Vec<(u64, u64)> = moduli.iter().map(|&m| (x % m, m)).collect();
+        assert_eq!(garner_crt(&pairs), x);
+        assert_eq!(
+            crt_combine(pairs[0].0, moduli[0], pairs[1].0, moduli[1]),
+            Some((x, moduli[0] * moduli[1]))
+        );
+        // and three primes just below 2^21
+        let moduli = [2_097_143u64, 2_097_133, 2_097_131];
+        let x = 9_223_372_036_854_775_000u64 % (moduli[0] * moduli[1] * moduli[2]);
+        let pairs: Vec<(u64, u64)> = moduli.iter().map(|&m| (x % m, m)).collect();
+        assert_eq!(garner_crt(&pairs), x);
+        // a product of moduli past 2^64 is fine when the solution is small
+        let huge = [(5, u64::MAX), (5, u64::MAX - 1)];
+        assert_eq!(garner_crt(&huge), 5);
+        assert_eq!(crt_combine(5, u64::MAX, 5, u64::MAX - 1), None);
+        // a modulus past 2^63 with m1 = 1, whose difference of remainders overflows a u64
+        assert_eq!(
+            crt_combine(0, 1, u64::MAX - 1, u64::MAX),
+            Some((u64::MAX - 1, u64::MAX))
+        );
+        assert_eq!(crt_combine(3, 1, 1, u64::MAX), Some((1, u64::MAX)));
+        // a remainder past its modulus is reduced by it first
+        assert_eq!(crt_combine(7, 5, 1, 3), Some((7, 15)));
+    }
+
+    #[test]
+    fn moduli_not_coprime() {
+        assert_eq!(crt_combine(1, 4, 3, 6), None);
+        assert_eq!(crt_combine(1, 4, 1, 4), None);
+        assert_eq!(crt_combine(0, 1, 3, 6), Some((3, 6)));
+    }
+
+    #[test]
+    #[should_panic(expected = "the modulus 6 is not coprime with the ones before it")]
+    fn garner_with_moduli_not_coprime() {
+        garner_crt(&[(1, 5), (1, 4), (3, 6)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "the solution does not fit in a u64")]
+    fn garner_solution_too_large() {
+        garner_crt(&[(1, u64::MAX), (2, u64::MAX - 1)]);
+    }
 }
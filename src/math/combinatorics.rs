@@ -0,0 +1,210 @@
+//! Exact counting numbers as big integers
+//! <https://en.wikipedia.org/wiki/Enumerative_combinatorics>
+//!
+//! The binomial coefficients and the Stirling numbers are built row by row from their
+//! recurrences, with additions and small multiplications only, and no division. The Catalan
+//! numbers then divide a central binomial coefficient exactly.
+
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+
+/// Returns the binomial coefficient `C(n, k)`, the number of subsets of size `k` of a set of
+/// size `n`, or 0 if `k > n`.
+///
+/// The row of Pascal's triangle is built up to `min(k, n - k)` with `C(m + 1, j) = C(m, j) +
+/// C(m, j - 1)`, in O(n min(k, n - k)) additions.
+/// <https://en.wikipedia.org/wiki/Pascal%27s_triangle>
+pub fn binomial(n: u64, k: u64) -> BigInt {
+    if k > n {
+        return BigInt::zero();
+    }
+    let k = k.min(n - k) as usize;
+    // row[j] = C(m, j) for the row m
+    let mut row = vec![BigInt::zero(); k + 1];
+    row[0] = BigInt::one();
+    for m in 1..=n {
+        for j in (1..=k.min(m as usize)).rev() {
+            let previous = row[j - 1].clone();
+            row[j] += previous;
+        }
+    }
+    row.swap_remove(k)
+}
+
+/// Returns the multinomial coefficient `n! / (k_1! k_2! ... k_m!)`, the number of ways to split
+/// a set of size `n` into groups of the sizes `ks`, as a product of binomial coefficients.
+///
+/// # Panics
+///
+/// Panics if the sizes do not add up to `n`.
+pub fn multinomial(n: u64, ks: &[u64]) -> BigInt {
+    let total: u128 = ks.iter().map(|&k| k as u128).sum();
+    assert_eq!(total, n as u128, "the sizes of the groups must add up to n");
+    let mut remaining = n;
+    let mut result = BigInt::one();
+    for &k in ks {
+        result *= binomial(remaining, k);
+        remaining -= k;
+    }
+    result
+}
+
+/// Returns the n-th Catalan number `C(2n, n) / (n + 1)`, the number of binary trees with `n`
+/// nodes.
+/// <https://en.wikipedia.org/wiki/Catalan_number>
+pub fn catalan(n: u64) -> BigInt {
+    binomial(2 * n, n) / (n + 1)
+}
+
+/// Builds the rows of a triangle `T(m, j)` up to `m = n`, from `T(0, 0) = 1` and
+/// `T(m + 1, j) = factor(m, j) T(m, j) + T(m, j - 1)`, and returns `T(n, k)`.
+fn triangle(n: usize, k: usize, factor: fn(usize, usize) -> usize) -> BigInt {
+    if k > n {
+        return BigInt::zero();
+    }
+    let mut row = vec![BigInt::zero(); k + 1];
+    row[0] = BigInt::one();
+    for m in 0..n {
+        for j in (0..=k.min(m + 1)).rev() {
+            let mut next = &row[j] * factor(m, j);
+            if j > 0 {
+                next += &row[j - 1];
+            }
+            row[j] = next;
+        }
+    }
+    row.swap_remove(k)
+}
+
+/// Returns the unsigned Stirling number of the first kind `c(n, k)`, the number of
+/// permutations of `n` items with `k` cycles, from `c(n + 1, k) = n c(n, k) + c(n, k - 1)`, in
+/// O(n k) operations.
+/// <https://en.wikipedia.org/wiki/Stirling_numbers_of_the_first_kind>
+pub fn stirling_first(n: usize, k: usize) -> BigInt {
+    triangle(n, k, |m, _| m)
+}
+
+/// Returns the Stirling number of the second kind `S(n, k)`, the number of partitions of a set
+/// of `n` items into `k` nonempty parts, from `S(n + 1, k) = k S(n, k) + S(n, k - 1)`, in
+/// O(n k) operations.
+/// <https://en.wikipedia.org/wiki/Stirling_numbers_of_the_second_kind>
+pub fn stirling_second(n: usize, k: usize) -> BigInt {
+    triangle(n, k, |_, j| j)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binomial_coefficients() {
+        assert_eq!(
+            binomial(100, 50).to_string(),
+            "100891344545564193334812497256"
+        );
+        assert_eq!(binomial(0, 0), BigInt::one());
+        assert_eq!(binomial(5, 6), BigInt::zero());
+        assert_eq!(binomial(1000, 1), BigInt::from(1000));
+        assert_eq!(binomial(1000, 999), BigInt::from(1000));
+        // against the factorials
+        let factorial = |n: u64| (1..=n).map(BigInt::from).product::<BigInt>();
+        for n in 0..40 {
+            for k in 0..=n {
+                let expected = factorial(n) / (factorial(k) * factorial(n - k));
+                assert_eq!(binomial(n, k), expected, "C({n}, {k})");
+            }
+        }
+        // the sum of a row of Pascal's triangle
+        let row: BigInt = (0..=200).map(|k| binomial(200, k)).sum();
+        assert_eq!(row, BigInt::one() << 200);
+    }
+
+    #[test]
+    fn multinomial_coefficients() {
+        assert_eq!(multinomial(10, &[2, 3, 5]), BigInt::from(2520));
+        // the anagrams of MISSISSIPPI
+        assert_eq!(multinomial(11, &[1, 4, 4, 2]), BigInt::from(34650));
+        assert_eq!(multinomial(7, &[7]), BigInt::one());
+        assert_eq!(multinomial(0, &[]), BigInt::one());
+        assert_eq!(multinomial(40, &[20, 20]), binomial(40, 20));
+    }
+
+    #[test]
+    #[should_panic(expected = "the sizes of the groups must add up to n")]
+    fn multinomial_with_missing_items() {
+        multinomial(10, &[2, 3]);
+    }
+
+    #[test]
+    fn catalan_numbers() {
+        let first = [1u64, 1, 2, 5, 14, 42, 132, 429, 1430, 4862, 16796];
+        for (n, &expected) in first.iter().enumerate() {
+            assert_eq!(catalan(n as u64), BigInt::from(expected));
+        }
+        assert_eq!(catalan(20), BigInt::from(6_564_120_420u64));
+        assert_eq!(
+            catalan(100).to_string(),
+            "896519947090131496687170070074100632420837521538745909320"
+        );
+    }
+
+    #[test]
+    fn stirling_numbers() {
+        let first: [&[u64]; 11] = [
+            &[1],
+            &[0, 1],
+            &[0, 1, 1],
+            &[0, 2, 3, 1],
+            &[0, 6, 11, 6, 1],
+            &[0, 24, 50, 35, 10, 1],
+            &[0, 120, 274, 225, 85, 15, 1],
+            &[0, 720, 1764, 1624, 735, 175, 21, 1],
+            &[0, 5040, 13068, 13132, 6769, 1960, 322, 28, 1],
+            &[0, 40320, 109584, 118124, 67284, 22449, 4536, 546, 36, 1],
+            &[
+                0, 362880, 1026576, 1172700, 723680, 269325, 63273, 9450, 870, 45, 1,
+            ],
+        ];
+        let second: [&[u64]; 11] = [
+            &[1],
+            &[0, 1],
+            &[0, 1, 1],
+            &[0, 1, 3, 1],
+            &[0, 1, 7, 6, 1],
+            &[0, 1, 15, 25, 10, 1],
+            &[0, 1, 31, 90, 65, 15, 1],
+            &[0, 1, 63, 301, 350, 140, 21, 1],
+            &[0, 1, 127, 966, 1701, 1050, 266, 28, 1],
+            &[0, 1, 255, 3025, 7770, 6951, 2646, 462, 36, 1],
+            &[0, 1, 511, 9330, 34105, 42525, 22827, 5880, 750, 45, 1],
+        ];
+        for n in 0..=10 {
+            for k in 0..=n {
+                assert_eq!(
+                    stirling_first(n, k),
+                    BigInt::from(first[n][k]),
+                    "c({n}, {k})"
+                );
+                assert_eq!(
+                    stirling_second(n, k),
+                    BigInt::from(second[n][k]),
+                    "S({n}, {k})"
+                );
+            }
+            assert_eq!(stirling_first(n, n + 1), BigInt::zero());
+            assert_eq!(stirling_second(n, n + 3), BigInt::zero());
+        }
+        // the signed numbers of the first kind and those of the second kind are inverse matrices
+        for n in 0..30 {
+            for m in 0..=n {
+                let product: BigInt = (m..=n)
+                    .map(|k| {
+                        let sign = if (n - k) % 2 == 0 { 1 } else { -1 };
+                        stirling_first(n, k) * sign * stirling_second(k, m)
+                    })
+                    .sum();
+                assert_eq!(product, BigInt::from(u8::from(n == m)), "{n} {m}");
+            }
+        }
+    }
+}
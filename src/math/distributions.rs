@@ -0,0 +1,440 @@
+//! Probability distributions to draw random values from, with their densities
+//! <https://en.wikipedia.org/wiki/Probability_distribution>
+//!
+//! Every distribution checks its parameters when it is created, and draws its values from any
+//! `Rng`, so that a seeded generator makes them reproducible. The discrete distributions
+//! draw their outcomes as `f64` too, to be used like the continuous ones.
+
+use crate::error::{check_positive, AlgorithmError, AlgorithmResult};
+use crate::math::sampling::WeightedAlias;
+use rand::Rng;
+use std::f64::consts::{PI, SQRT_2};
+
+/// How far probabilities may sum from 1
+const TOLERANCE: f64 = 1e-9;
+
+pub trait Distribution {
+    /// Draws a value.
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64;
+
+    fn mean(&self) -> f64;
+
+    fn variance(&self) -> f64;
+
+    /// Draws `n` independent values.
+    fn sample_n<R: Rng + ?Sized>(&self, n: usize, rng: &mut R) -> Vec<f64> {
+        (0..n).map(|_| self.sample(rng)).collect()
+    }
+}
+
+fn check_finite(argument: &'static str, value: f64) -> AlgorithmResult<()> {
+    if value.is_finite() {
+        Ok(())
+    } else {
+        Err(AlgorithmError::NonFiniteValue { argument })
+    }
+}
+
+fn check_probability(argument: &'static str, p: f64) -> AlgorithmResult<()> {
+    if (0.0..=1.0).contains(&p) {
+        Ok(())
+    } else {
+        Err(AlgorithmError::InvalidParameter {
+            argument,
+            reason: format!("must be a probability, got {p}"),
+        })
+    }
+}
+
+/// The error function, with the approximation 7.1.26 of Abramowitz and Stegun, within 1.5e-7
+/// <https://en.wikipedia.org/wiki/Error_function#Numerical_approximations>
+fn erf(x: f64) -> f64 {
+    const A: [f64; 5] = [
+        0.254829592,
+        -0.284496736,
+        1.421413741,
+        -1.453152027,
+        1.061405429,
+    ];
+    let t = 1.0 / (1.0 + 0.3275911 * x.abs());
+    let polynomial = A.iter().rev().fold(0.0, |acc, a| acc * t + a) * t;
+    let y = 1.0 - polynomial * (-x * x).exp();
+    y.copysign(x)
+}
+
+/// The normal distribution of mean `mean` and standard deviation `std`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Normal {
+    mean: f64,
+    std: f64,
+}
+
+impl Normal {
+    pub fn new(mean: f64, std: f64) -> AlgorithmResult<Self> {
+        check_finite("mean", mean)?;
+        check_positive("std", std)?;
+        Ok(Normal { mean, std })
+    }
+
+    pub fn std(&self) -> f64 {
+        self.std
+    }
+
+    pub fn pdf(&self, x: f64) -> f64 {
+        let z = (x - self.mean) / self.std;
+        (-0.5 * z * z).exp() / (self.std * (2.0 * PI).sqrt())
+    }
+
+    /// The probability of a value at most `x`, within 1.5e-7.
+    pub fn cdf(&self, x: f64) -> f64 {
+        0.5 * (1.0 + erf((x - self.mean) / (self.std * SQRT_2)))
+    }
+}
+
+impl Distribution for Normal {
+    /// Draws a value with the Box-Muller transform, which turns two uniform values into a
+    /// standard normal one, from the distance and the angle of a point of the plane whose
+    /// coordinates are independent standard normal values.
+    /// <https://en.wikipedia.org/wiki/Box%E2%80%93Muller_transform>
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        // 1 - u is in (0, 1], so that its logarithm is finite
+        let (u, v): (f64, f64) = (1.0 - rng.gen::<f64>(), rng.gen());
+        self.mean + self.std * (-2.0 * u.ln()).sqrt() * (2.0 * PI * v).cos()
+    }
+
+    fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    fn variance(&self) -> f64 {
+        self.std * self.std
+    }
+}
+
+/// The uniform distribution on `[lo, hi)`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Uniform {
+    lo: f64,
+    hi: f64,
+}
+
+impl Uniform {
+    pub fn new(lo: f64, hi: f64) -> AlgorithmResult<Self> {
+        check_finite("lo", lo)?;
+        check_finite("hi", hi)?;
+        if lo >= hi {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "hi",
+                reason: format!("must be above lo = {lo}, got {hi}"),
+            });
+        }
+        Ok(Uniform { lo, hi })
+    }
+
+    pub fn pdf(&self, x: f64) -> f64 {
+        if (self.lo..self.hi).contains(&x) {
+            1.0 / (self.hi - self.lo)
+        } else {
+            0.0
+        }
+    }
+
+    pub fn cdf(&self, x: f64) -> f64 {
+        ((x - self.lo) / (self.hi - self.lo)).clamp(0.0, 1.0)
+    }
+}
+
+impl Distribution for Uniform {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        self.lo + (self.hi - self.lo) * rng.gen::<f64>()
+    }
+
+    fn mean(&self) -> f64 {
+        (self.lo + self.hi) / 2.0
+    }
+
+    fn variance(&self) -> f64 {
+        (self.hi - self.lo).powi(2) / 12.0
+    }
+}
+
+/// The distribution of 1 with the probability `p`, and 0 otherwise
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bernoulli {
+    p: f64,
+}
+
+impl Bernoulli {
+    pub fn new(p: f64) -> AlgorithmResult<Self> {
+        check_probability("p", p)?;
+        Ok(Bernoulli { p })
+    }
+
+    /// The probability of the outcome `k`, which is 0 for anything else than 0 and 1.
+    pub fn pmf(&self, k: usize) -> f64 {
+        match k {
+            0 => 1.0 - self.p,
+            1 => self.p,
+            _ => 0.0,
+        }
+    }
+
+    pub fn cdf(&self, x: f64) -> f64 {
+        if x < 0.0 {
+            0.0
+        } else if x < 1.0 {
+            1.0 - self.p
+        } else {
+            1.0
+        }
+    }
+}
+
+impl Distribution for Bernoulli {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        if rng.gen_bool(self.p) {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    fn mean(&self) -> f64 {
+        self.p
+    }
+
+    fn variance(&self) -> f64 {
+        self.p * (1.0 - self.p)
+    }
+}
+
+/// The exponential distribution of rate `lambda`, that of the waiting times between events
+/// happening `lambda` times per unit of time on average
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Exponential {
+    lambda: f64,
+}
+
+impl Exponential {
+    pub fn new(lambda: f64) -> AlgorithmResult<Self> {
+        check_positive("lambda", lambda)?;
+        Ok(Exponential { lambda })
+    }
+
+    pub fn pdf(&self, x: f64) -> f64 {
+        if x < 0.0 {
+            0.0
+        } else {
+            self.lambda * (-self.lambda * x).exp()
+        }
+    }
+
+    pub fn cdf(&self, x: f64) -> f64 {
+        if x < 0.0 {
+            0.0
+        } else {
+            1.0 - (-self.lambda * x).exp()
+        }
+    }
+}
+
+impl Distribution for Exponential {
+    /// Draws a value by inverting the cumulative distribution function at a uniform value.
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        -(1.0 - rng.gen::<f64>()).ln() / self.lambda
+    }
+
+    fn mean(&self) -> f64 {
+        1.0 / self.lambda
+    }
+
+    fn variance(&self) -> f64 {
+        1.0 / (self.lambda * self.lambda)
+    }
+}
+
+/// The distribution of the outcomes `0..probs.len()`, each with its probability, drawn in O(1)
+/// with the alias method
+#[derive(Clone, Debug, PartialEq)]
+pub struct Categorical {
+    probs: Vec<f64>,
+    alias: WeightedAlias,
+}
+
+impl Categorical {
+    /// Creates the distribution of the probabilities, which must sum to 1.
+    pub fn new(probs: Vec<f64>) -> AlgorithmResult<Self> {
+        if probs.is_empty() {
+            return Err(AlgorithmError::EmptyInput { argument: "probs" });
+        }
+        for &p in &probs {
+            check_probability("probs", p)?;
+        }
+        let total: f64 = probs.iter().sum();
+        if (total - 1.0).abs() > TOLERANCE {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "probs",
+                reason: format!("must sum to 1, got {total}"),
+            });
+        }
+        let alias = WeightedAlias::new(&probs)?;
+        Ok(Categorical { probs, alias })
+    }
+
+    pub fn pmf(&self, k: usize) -> f64 {
+        self.probs.get(k).copied().unwrap_or(0.0)
+    }
+
+    /// The probability of an outcome at most `k`.
+    pub fn cdf(&self, k: usize) -> f64 {
+        self.probs
+            .iter()
+            .take(k.saturating_add(1))
+            .sum::<f64>()
+            .min(1.0)
+    }
+
+    /// Draws an outcome.
+    pub fn sample_index<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        // the alias table takes a sized generator
+        let mut rng = rng;
+        self.alias.sample(&mut rng)
+    }
+}
+
+impl Distribution for Categorical {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        self.sample_index(rng) as f64
+    }
+
+    fn mean(&self) -> f64 {
+        self.probs
+            .iter()
+            .enumerate()
+            .map(|(k, p)| k as f64 * p)
+            .sum()
+    }
+
+    fn variance(&self) -> f64 {
+        let mean = self.mean();
+        self.probs
+            .iter()
+            .enumerate()
+            .map(|(k, p)| (k as f64 - mean).powi(2) * p)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    const N: usize = 200_000;
+
+    /// Checks that the mean and the variance of many draws are within about 5 standard errors of
+    /// those of the distribution.
+    fn check_moments(distribution: &impl Distribution, seed: u64) {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let values = distribution.sample_n(N, &mut rng);
+        let mean = values.iter().sum::<f64>() / N as f64;
+        let variance = values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / N as f64;
+        let (expected_mean, expected_variance) = (distribution.mean(), distribution.variance());
+        let tolerance = 5.0 * (expected_variance / N as f64).sqrt();
+        assert!((mean - expected_mean).abs() < tolerance, "mean {mean}");
+        assert!(
+            (variance - expected_variance).abs() < 0.02 * expected_variance.max(1e-3),
+            "variance {variance}"
+        );
+    }
+
+    #[test]
+    fn sample_moments() {
+        check_moments(&Normal::new(3.0, 2.0).unwrap(), 1290);
+        check_moments(&Normal::new(-1.0, 0.1).unwrap(), 1291);
+        check_moments(&Uniform::new(-2.0, 5.0).unwrap(), 1292);
+        check_moments(&Bernoulli::new(0.3).unwrap(), 1293);
+        check_moments(&Exponential::new(4.0).unwrap(), 1294);
+        check_moments(&Categorical::new(vec![0.5, 0.2, 0.0, 0.3]).unwrap(), 1295);
+    }
+
+    #[test]
+    fn normal_densities() {
+        let standard = Normal::new(0.0, 1.0).unwrap();
+        assert!((standard.pdf(0.0) - 0.3989422804014327).abs() < 1e-15);
+        assert!((standard.pdf(1.0) - 0.24197072451914337).abs() < 1e-15);
+        assert!((standard.cdf(0.0) - 0.5).abs() < 1e-9);
+        assert!((standard.cdf(1.959963984540054) - 0.975).abs() < 2e-7);
+        assert!((standard.cdf(-1.0) - 0.15865525393145707).abs() < 2e-7);
+        let normal = Normal::new(10.0, 2.0).unwrap();
+        assert!((normal.pdf(12.0) - standard.pdf(1.0) / 2.0).abs() < 1e-15);
+        assert!((normal.cdf(8.0) - standard.cdf(-1.0)).abs() < 1e-12);
+        assert_eq!(normal.std(), 2.0);
+        // the fraction of the draws below a point approaches the cdf
+        let mut rng = ChaCha8Rng::seed_from_u64(1296);
+        let below = normal
+            .sample_n(N, &mut rng)
+            .iter()
+            .filter(|&&x| x < 11.0)
+            .count();
+        assert!((below as f64 / N as f64 - normal.cdf(11.0)).abs() < 0.005);
+    }
+
+    #[test]
+    fn other_densities() {
+        let uniform = Uniform::new(1.0, 5.0).unwrap();
+        assert_eq!(uniform.pdf(2.0), 0.25);
+        assert_eq!((uniform.pdf(0.5), uniform.pdf(5.0)), (0.0, 0.0));
+        assert_eq!(
+            (uniform.cdf(0.0), uniform.cdf(2.0), uniform.cdf(9.0)),
+            (0.0, 0.25, 1.0)
+        );
+
+        let exponential = Exponential::new(2.0).unwrap();
+        assert_eq!(exponential.pdf(0.0), 2.0);
+        assert!((exponential.pdf(1.0) - 2.0 * (-2.0f64).exp()).abs() < 1e-15);
+        assert_eq!(exponential.pdf(-1.0), 0.0);
+        assert!((exponential.cdf(0.5f64.ln() / -2.0) - 0.5).abs() < 1e-15);
+
+        let bernoulli = Bernoulli::new(0.25).unwrap();
+        assert_eq!(
+            (bernoulli.pmf(0), bernoulli.pmf(1), bernoulli.pmf(2)),
+            (0.75, 0.25, 0.0)
+        );
+        assert_eq!(
+            (bernoulli.cdf(-0.5), bernoulli.cdf(0.5), bernoulli.cdf(1.0)),
+            (0.0, 0.75, 1.0)
+        );
+
+        let categorical = Categorical::new(vec![0.1, 0.6, 0.3]).unwrap();
+        assert_eq!((categorical.pmf(1), categorical.pmf(3)), (0.6, 0.0));
+        assert!((categorical.cdf(1) - 0.7).abs() < 1e-15);
+        assert_eq!(categorical.cdf(usize::MAX), 1.0);
+        let mut rng = ChaCha8Rng::seed_from_u64(1297);
+        let mut counts = [0; 3];
+        for _ in 0..N {
+            counts[categorical.sample_index(&mut rng)] += 1;
+        }
+        for (count, p) in counts.iter().zip([0.1, 0.6, 0.3]) {
+            assert!((*count as f64 / N as f64 - p).abs() < 0.005);
+        }
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        assert!(Normal::new(0.0, 0.0).is_err());
+        assert!(Normal::new(0.0, -1.0).is_err());
+        assert!(Normal::new(f64::NAN, 1.0).is_err());
+        assert!(Uniform::new(1.0, 1.0).is_err());
+        assert!(Uniform::new(0.0, f64::INFINITY).is_err());
+        assert!(Bernoulli::new(1.5).is_err());
+        assert!(Bernoulli::new(f64::NAN).is_err());
+        assert!(Exponential::new(0.0).is_err());
+        assert!(Categorical::new(vec![]).is_err());
+        assert!(Categorical::new(vec![0.5, 0.4]).is_err());
+        assert!(Categorical::new(vec![1.5, -0.5]).is_err());
+        assert!(Bernoulli::new(0.0).is_ok());
+        assert!(Categorical::new(vec![0.1; 10]).is_ok());
+    }
+}
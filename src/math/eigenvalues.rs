@@ -0,0 +1,276 @@
+use super::Matrix;
+
+/// Returns the rows of a square matrix.
+///
+/// # Panics
+///
+/// Panics if the matrix is not square.
+fn square_rows(matrix: &Matrix<f64>) -> Vec<Vec<f64>> {
+    let n = matrix.rows();
+    assert_eq!(
+        n,
+        matrix.cols(),
+        "the matrix must be square, got {n} by {}",
+        matrix.cols()
+    );
+    (0..n)
+        .map(|i| (0..n).map(|j| matrix[[i, j]]).collect())
+        .collect()
+}
+
+fn norm(v: &[f64]) -> f64 {
+    v.iter().map(|x| x * x).sum::<f64>().sqrt()
+}
+
+/// Returns the eigenvalue of largest magnitude of a square matrix and a unit eigenvector of
+/// it, with the power iteration
+/// <https://en.wikipedia.org/wiki/Power_iteration>
+///
+/// Starting from a vector of ones, every iteration multiplies the vector by the matrix and
+/// normalizes it, which multiplies its component along every eigenvector by the eigenvalue, so
+/// that the dominant one takes over at the rate of the ratio of the two largest eigenvalues.
+/// The eigenvalue is the Rayleigh quotient `v . A v`, and the iteration stops once the residual
+/// `|A v - λ v|` is within `tolerance`, or after `max_iterations` iterations. The eigenvector
+/// is scaled so that its largest component is positive. The iteration does not converge if
+/// two eigenvalues of opposite signs are both dominant, and misses the dominant eigenvalue if
+/// the vector of ones is orthogonal to its eigenvectors.
+///
+/// # Panics
+///
+/// Panics if the matrix is not square.
+pub fn power_iteration(
+    matrix: &Matrix<f64>,
+    max_iterations: usize,
+    tolerance: f64,
+) -> (f64, Vec<f64>) {
+    let a = square_rows(matrix);
+    let n = a.len();
+    let mut v = vec![1.0 / (n as f64).sqrt(); n];
+    let mut eigenvalue = 0.0;
+    for _ in 0..max_iterations {
+        let w: Vec<f64> = a
+            .iter()
+            .map(|row| row.iter().zip(&v).map(|(a, x)| a * x).sum())
+            .collect();
+        eigenvalue = v.iter().zip(&w).map(|(x, y)| x * y).sum();
+        let residual: Vec<f64> = w.iter().zip(&v).map(|(y, x)| y - eigenvalue * x).collect();
+        let length = norm(&w);
+        if norm(&residual) <= tolerance || length == 0.0 {
+            break;
+        }
+        v = w.iter().map(|y| y / length).collect();
+    }
+    let largest = v
+        .iter()
+        .copied()
+        .fold(0.0, |m: f64, x| if x.abs() > m.abs() { x } else { m });
+    if largest < 0.0 {
+        v.iter_mut().for_each(|x| *x = -*x);
+    }
+    (eigenvalue, v)
+}
+
+/// Reduces a symmetric matrix to a tridiagonal one with the same eigenvalues, by Householder
+/// reflections.
+fn tridiagonalize(a: &mut [Vec<f64>]) {
+    let n = a.len();
+    for k in 0..n.saturating_sub(2) {
+        // the reflection of the column below the diagonal onto its first axis
+        let mut v: Vec<f64> = a[k + 1..].iter().map(|row| row[k]).collect();
+        let length = norm(&v);
+        if length == 0.0 {
+            continue;
+        }
+        v[0] += if v[0] > 0.0 { length } else { -length };
+        let length = norm(&v);
+        v.iter_mut().for_each(|x| *x /= length);
+        // A = H A H with H = I - 2 v v^T on the rows and columns after k
+        for j in 0..n {
+            let dot: f64 = v.iter().zip(&a[k + 1..]).map(|(x, row)| x * row[j]).sum();
+            for (x, row) in v.iter().zip(&mut a[k + 1..]) {
+                row[j] -= 2.0 * x * dot;
+            }
+        }
+        for row in a.iter_mut() {
+            let dot: f64 = v.iter().zip(&row[k + 1..]).map(|(x, a)| x * a).sum();
+            for (x, a) in v.iter().zip(&mut row[k + 1..]) {
+                *a -= 2.0 * x * dot;
+            }
+        }
+        for row in a.iter_mut().skip(k + 2) {
+            row[k] = 0.0;
+        }
+        a[k][k + 2..].fill(0.0);
+    }
+}
+
+/// Returns all the eigenvalues of a symmetric matrix in increasing order, with the QR
+/// algorithm on its tridiagonal form
+/// <https://en.wikipedia.org/wiki/QR_algorithm>
+///
+/// Householder reflections first reduce the matrix to a tridiagonal one, in O(n^3). Every QR
+/// step `T - μ I = Q R`, `T = R Q + μ I` then keeps the matrix tridiagonal and similar, and is
+/// carried out implicitly in O(n) by chasing the bulge of a first Givens rotation down the
+/// diagonal. The Wilkinson shift `μ`, the eigenvalue of the trailing 2 x 2 block closest to its
+/// last entry, makes the last off-diagonal entry vanish cubically fast, and the matrix then
+/// splits into smaller blocks, the diagonal entries of the 1 x 1 blocks being the eigenvalues.
+/// The steps stop after `max_iterations`, leaving the diagonal entries of the blocks not split
+/// yet as approximations.
+/// <https://en.wikipedia.org/wiki/Householder_transformation#Tridiagonalization>
+///
+/// # Panics
+///
+/// Panics if the matrix is not square, or not symmetric.
+pub fn qr_algorithm(matrix: &Matrix<f64>, max_iterations: usize) -> Vec<f64> {
+    let mut t = square_rows(matrix);
+    let n = t.len();
+    let scale = t.iter().flatten().fold(0.0f64, |m, x| m.max(x.abs()));
+    for (i, row) in t.iter().enumerate() {
+        for (j, x) in row[..i].iter().enumerate() {
+            assert!(
+                (x - t[j][i]).abs() <= 1e-12 * scale,
+                "the matrix must be symmetric"
+            );
+        }
+    }
+    tridiagonalize(&mut t);
+    let negligible = |t: &[Vec<f64>], i: usize| {
+        t[i][i - 1].abs() <= f64::EPSILON * (t[i][i].abs() + t[i - 1][i - 1].abs())
+    };
+    let mut hi = n.saturating_sub(1);
+    let mut iterations = 0;
+    while hi > 0 && iterations < max_iterations {
+        if negligible(&t, hi) {
+            hi -= 1;
+            continue;
+        }
+        // the unreduced block lo..=hi
+        let mut lo = hi - 1;
+        while lo > 0 && !negligible(&t, lo) {
+            lo -= 1;
+        }
+        let (a, b, c) = (t[hi - 1][hi - 1], t[hi][hi - 1], t[hi][hi]);
+        let delta = (a - c) / 2.0;
+        let sign = if delta >= 0.0 { 1.0 } else { -1.0 };
+        let shift = c - sign * b * b / (delta.abs() + delta.hypot(b));
+        let (mut x, mut z) = (t[lo][lo] - shift, t[lo + 1][lo]);
+        for k in lo..hi {
+            let r = x.hypot(z);
+            let (cos, sin) = if r == 0.0 { (1.0, 0.0) } else { (x / r, z / r) };
+            // the rotation G^T T G in the plane of k and k + 1
+            let (upper, lower) = t.split_at_mut(k + 1);
+            let (row_k, row_next) = (&mut upper[k], &mut lower[0]);
+            for (p, q) in row_k[lo..=hi].iter_mut().zip(&mut row_next[lo..=hi]) {
+                (*p, *q) = (cos * *p + sin * *q, cos * *q - sin * *p);
+            }
+            for row in &mut t[lo..=hi] {
+                let (p, q) = (row[k], row[k + 1]);
+                (row[k], row[k + 1]) = (cos * p + sin * q, cos * q - sin * p);
+            }
+            if k + 1 < hi {
+                // the bulge below the subdiagonal, chased by the next rotation
+                (x, z) = (t[k + 1][k], t[k + 2][k]);
+            }
+        }
+        iterations += 1;
+    }
+    let mut eigenvalues: Vec<f64> = (0..n).map(|i| t[i][i]).collect();
+    eigenvalues.sort_by(f64::total_cmp);
+    eigenvalues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn assert_close(found: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (found - expected).abs() <= tolerance,
+            "{found} is not {expected}"
+        );
+    }
+
+    #[test]
+    fn dominant_eigenvalues() {
+        let matrix = Matrix::from(vec![vec![2.0, 1.0], vec![1.0, 2.0]]);
+        let (eigenvalue, eigenvector) = power_iteration(&matrix, 100, 1e-10);
+        assert_close(eigenvalue, 3.0, 1e-10);
+        for x in eigenvector {
+            assert_close(x, 0.5f64.sqrt(), 1e-10);
+        }
+        // from a vector of ones that is no eigenvector, and not symmetric
+        let matrix = Matrix::from(vec![vec![4.0, 1.0], vec![2.0, 3.0]]);
+        let (eigenvalue, v) = power_iteration(&matrix, 1000, 1e-9);
+        assert_close(eigenvalue, 5.0, 1e-8);
+        assert_close(v[0], v[1], 1e-8);
+        // a negative dominant eigenvalue, whose eigenvector flips at every iteration
+        let matrix = Matrix::from(vec![vec![-5.0, 1.0], vec![1.0, 2.0]]);
+        let (eigenvalue, v) = power_iteration(&matrix, 1000, 1e-9);
+        let expected = -1.5 - 53f64.sqrt() / 2.0;
+        assert_close(eigenvalue, expected, 1e-8);
+        assert!(v[0] > 0.0 && v[1] < 0.0);
+        assert_close(-5.0 * v[0] + v[1], expected * v[0], 1e-8);
+    }
+
+    #[test]
+    fn symmetric_eigenvalues() {
+        // the second differences, of eigenvalues 2 - 2 cos(k π / 4)
+        let matrix = Matrix::from(vec![
+            vec![2.0, -1.0, 0.0],
+            vec![-1.0, 2.0, -1.0],
+            vec![0.0, -1.0, 2.0],
+        ]);
+        let expected = [2.0 - 2f64.sqrt(), 2.0, 2.0 + 2f64.sqrt()];
+        for (found, expected) in qr_algorithm(&matrix, 100).into_iter().zip(expected) {
+            assert_close(found, expected, 1e-6);
+        }
+        // a full matrix, each eigenvalue a root of its characteristic polynomial
+        let rows = [[4.0, 1.0, -2.0], [1.0, 2.0, 0.0], [-2.0, 0.0, 3.0]];
+        let found = qr_algorithm(&Matrix::from(rows.map(Vec::from).to_vec()), 100);
+        assert_close(found.iter().sum(), 9.0, 1e-9);
+        for eigenvalue in found {
+            let [[a, b, c], [d, e, f], [g, h, i]] = rows;
+            let (a, e, i) = (a - eigenvalue, e - eigenvalue, i - eigenvalue);
+            let determinant = a * (e * i - f * h) - b * (d * i - f * g) + c * (d * h - e * g);
+            assert_close(determinant, 0.0, 1e-9);
+        }
+        let identity = &Matrix::identity(4) * 7.0;
+        assert_eq!(qr_algorithm(&identity, 100), [7.0; 4]);
+        assert!(qr_algorithm(&Matrix::zero(0, 0), 10).is_empty());
+    }
+
+    #[test]
+    fn agrees_with_nalgebra() {
+        let mut rng = ChaCha8Rng::seed_from_u64(175);
+        for n in 1..10 {
+            let lower: Vec<Vec<f64>> = (0..n)
+                .map(|i| (0..=i).map(|_| rng.gen_range(-10.0..10.0)).collect())
+                .collect();
+            let rows: Vec<Vec<f64>> = (0..n)
+                .map(|i| (0..n).map(|j| lower[i.max(j)][i.min(j)]).collect())
+                .collect();
+            let expected = nalgebra::DMatrix::from_fn(n, n, |i, j| rows[i][j]);
+            let mut expected: Vec<f64> = expected.symmetric_eigenvalues().iter().copied().collect();
+            expected.sort_by(f64::total_cmp);
+            let found = qr_algorithm(&Matrix::from(rows.clone()), 1000);
+            for (found, expected) in found.iter().zip(&expected) {
+                assert_close(*found, *expected, 1e-9);
+            }
+            // the dominant one, when it is not tied with its opposite
+            let (dominant, _) = power_iteration(&Matrix::from(rows), 100_000, 1e-12);
+            let largest = expected
+                .iter()
+                .copied()
+                .max_by(|a, b| a.abs().total_cmp(&b.abs()));
+            assert_close(dominant.abs(), largest.unwrap().abs(), 1e-6);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the matrix must be symmetric")]
+    fn asymmetric_matrix() {
+        qr_algorithm(&Matrix::from(vec![vec![1.0, 2.0], vec![0.0, 1.0]]), 10);
+    }
+}
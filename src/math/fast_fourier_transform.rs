@@ -1,3 +1,4 @@
+use crate::error::{AlgorithmError, AlgorithmResult};
 use std::ops::{Add, Mul, MulAssign, Sub};
 
 // f64 complex
@@ -166,9 +167,107 @@ pub fn inverse_fast_fourier_transform(
     result.iter().map(|x| x.re * scale).collect()
 }
 
+/// Checks that the length is a power of two, and returns the roots of unity
+/// `e^(2 pi i k / n)` for `k < n / 2`, which every butterfly takes its factor from.
+fn roots_of_unity(n: usize) -> AlgorithmResult<Vec<Complex64>> {
+    if !n.is_power_of_two() {
+        return Err(AlgorithmError::InvalidParameter {
+            argument: "signal",
+            reason: format!("the length must be a power of two, got {n}"),
+        });
+    }
+    Ok((0..n / 2)
+        .map(|k| {
+            let angle = std::f64::consts::TAU * k as f64 / n as f64;
+            Complex64::new(angle.cos(), angle.sin())
+        })
+        .collect())
+}
+
+/// Moves every entry to the index with the bits of its own in reverse order.
+fn bit_reverse_permutation(signal: &mut [Complex64]) {
+    let n = signal.len();
+    let mut reverse = 0;
+    for position in 1..n {
+        // adds 1 to the reversed number, from its highest bit
+        let mut bit = n >> 1;
+        while reverse & bit != 0 {
+            reverse ^= bit;
+            bit >>= 1;
+        }
+        reverse ^= bit;
+        if position < reverse {
+            signal.swap(position, reverse);
+        }
+    }
+}
+
+/// Combines the transforms of the two halves of `signal`, each of them first transformed
+/// recursively, with the roots of unity of order `signal.len()` at every `stride`-th root
+fn butterflies(signal: &mut [Complex64], roots: &[Complex64], stride: usize) {
+    let half = signal.len() / 2;
+    if half == 0 {
+        return;
+    }
+    let (low, high) = signal.split_at_mut(half);
+    butterflies(low, roots, 2 * stride);
+    butterflies(high, roots, 2 * stride);
+    for (k, (a, b)) in low.iter_mut().zip(high.iter_mut()).enumerate() {
+        let twisted = *b * roots[k * stride];
+        (*a, *b) = (*a + twisted, *a - twisted);
+    }
+}
+
+/// Replaces a signal by its discrete Fourier transform
+/// `X(k) = x(0) + x(1) w^k + ... + x(n - 1) w^((n - 1) k)` for `w = e^(2 pi i / n)`, the
+/// convention of `fast_fourier_transform`, with the radix-2 Cooley-Tukey algorithm in
+/// O(n log(n)), without any other allocation than the roots of unity.
+/// <https://en.wikipedia.org/wiki/Cooley%E2%80%93Tukey_FFT_algorithm>
+///
+/// The transform of n entries is made of those of its entries of even and of odd indices,
+/// `X(k) = E(k) + w^k O(k)` and `X(k + n / 2) = E(k) - w^k O(k)`. Permuting the entries into
+/// the order of their bit-reversed indices first puts the entries of even indices in the first
+/// half, and so on recursively, so that the transforms of the halves are computed in place,
+/// top-down, and combined by butterflies where they lie.
+///
+/// Fails if the length of the signal is not a power of two.
+pub fn fft_inplace(signal: &mut [Complex64]) -> AlgorithmResult<()> {
+    let roots = roots_of_unity(signal.len())?;
+    bit_reverse_permutation(signal);
+    butterflies(signal, &roots, 1);
+    Ok(())
+}
+
+/// Replaces a signal by its discrete Fourier transform, like `fft_inplace`, but combining the
+/// transforms bottom-up: all the pairs of entries, then all the blocks of 4, and so on up to
+/// the whole signal, with a loop instead of recursive calls.
+///
+/// Fails if the length of the signal is not a power of two.
+pub fn fft_iterative(signal: &mut [Complex64]) -> AlgorithmResult<()> {
+    let n = signal.len();
+    let roots = roots_of_unity(n)?;
+    bit_reverse_permutation(signal);
+    let mut length = 2;
+    while length <= n {
+        let (half, stride) = (length / 2, n / length);
+        for block in signal.chunks_exact_mut(length) {
+            let (low, high) = block.split_at_mut(half);
+            for (k, (a, b)) in low.iter_mut().zip(high.iter_mut()).enumerate() {
+                let twisted = *b * roots[k * stride];
+                (*a, *b) = (*a + twisted, *a - twisted);
+            }
+        }
+        length *= 2;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
     fn almost_equal(a: f64, b: f64, epsilon: f64) -> bool {
         (a - b).abs() < epsilon
     }
@@ -217,4 +316,99 @@ mod tests {
             assert!(almost_equal(x, y, EPSILON));
         }
     }
+
+    /// The transform straight from its definition, in O(n^2)
+    fn naive_dft(signal: &[Complex64]) -> Vec<Complex64> {
+        let n = signal.len();
+        (0..n)
+            .map(|k| {
+                signal
+                    .iter()
+                    .enumerate()
+                    .fold(Complex64::default(), |sum, (j, &x)| {
+                        let angle = std::f64::consts::TAU * (j * k % n) as f64 / n as f64;
+                        sum + x * Complex64::new(angle.cos(), angle.sin())
+                    })
+            })
+            .collect()
+    }
+
+    fn assert_close(a: &[Complex64], b: &[Complex64]) {
+        assert_eq!(a.len(), b.len());
+        for (x, y) in a.iter().zip(b) {
+            assert!((*x - *y).norm() < 1e-9, "{x:?} is not {y:?}");
+        }
+    }
+
+    #[test]
+    fn in_place_transforms() {
+        let mut rng = ChaCha8Rng::seed_from_u64(165);
+        for log in 0..=10 {
+            let n = 1 << log;
+            let real: Vec<f64> = (0..n).map(|_| rng.gen_range(-1.0..1.0)).collect();
+            let expected =
+                fast_fourier_transform(&real, &fast_fourier_transform_input_permutation(n));
+            let signal: Vec<Complex64> = real.iter().map(|&x| Complex64::new(x, 0.0)).collect();
+            let mut recursive = signal.clone();
+            fft_inplace(&mut recursive).unwrap();
+            let mut iterative = signal.clone();
+            fft_iterative(&mut iterative).unwrap();
+            assert_close(&recursive, &expected);
+            assert_close(&iterative, &expected);
+            // complex signals too
+            let signal: Vec<Complex64> = (0..n)
+                .map(|_| Complex64::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)))
+                .collect();
+            let mut recursive = signal.clone();
+            fft_inplace(&mut recursive).unwrap();
+            let mut iterative = signal.clone();
+            fft_iterative(&mut iterative).unwrap();
+            if n <= 256 {
+                assert_close(&recursive, &naive_dft(&signal));
+            }
+            assert_close(&recursive, &iterative);
+        }
+        // an impulse has a flat spectrum
+        let mut impulse = vec![Complex64::default(); 8];
+        impulse[0] = Complex64::new(1.0, 0.0);
+        fft_iterative(&mut impulse).unwrap();
+        assert_close(&impulse, &[Complex64::new(1.0, 0.0); 8]);
+    }
+
+    #[test]
+    fn lengths_other_than_powers_of_two() {
+        for n in [0, 3, 6, 12, 1000] {
+            let expected = Err(AlgorithmError::InvalidParameter {
+                argument: "signal",
+                reason: format!("the length must be a power of two, got {n}"),
+            });
+            let mut signal = vec![Complex64::new(1.0, 0.0); n];
+            assert_eq!(fft_inplace(&mut signal), expected);
+            assert_eq!(fft_iterative(&mut signal), expected);
+            // and the signal is left as it was
+            assert!(signal.iter().all(|x| x.re == 1.0 && x.im == 0.0));
+        }
+    }
+
+    #[test]
+    #[ignore = "slow test for measuring time"]
+    fn iterative_against_recursive() {
+        use std::time::Instant;
+        let mut rng = ChaCha8Rng::seed_from_u64(1650);
+        for log in [10, 14, 18] {
+            let signal: Vec<Complex64> = (0..1 << log)
+                .map(|_| Complex64::new(rng.gen_range(-1.0..1.0), 0.0))
+                .collect();
+            let mut recursive = signal.clone();
+            let start = Instant::now();
+            fft_inplace(&mut recursive).unwrap();
+            let recursion = start.elapsed();
+            let mut iterative = signal.clone();
+            let start = Instant::now();
+            fft_iterative(&mut iterative).unwrap();
+            let iteration = start.elapsed();
+            assert_close(&recursive, &iterative);
+            println!("2^{log} entries: recursive {recursion:?}, iterative {iteration:?}");
+        }
+    }
 }
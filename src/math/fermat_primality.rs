@@ -0,0 +1,172 @@
+// The Fermat primality test, and Carmichael numbers
+// https://en.wikipedia.org/wiki/Fermat_primality_test
+// https://en.wikipedia.org/wiki/Carmichael_number
+//
+// By Fermat's little theorem, a^(n - 1) = 1 modulo a prime n for every base a
+// not divisible by n. A base for which this fails proves n composite, and a
+// composite number passing for a base is a Fermat pseudoprime to that base.
+// Carmichael numbers are the composite numbers passing for every base coprime
+// to them, so that the test only catches them with a base sharing one of their
+// factors: Miller-Rabin, which also checks the square roots of 1 found along
+// the way, has no such blind spot.
+
+use super::{is_prime_deterministic, mod_pow, pollard_rho_factorize};
+use rand::Rng;
+
+/// Tells whether `n` passes the Fermat test for `base`, that is `base^(n - 1) = 1` modulo `n`.
+pub fn is_fermat_probable_prime(n: u64, base: u64) -> bool {
+    n > 1 && mod_pow(base, n - 1, n) == 1
+}
+
+/// Tells whether `n` is probably prime, with the Fermat test for `rounds` random bases.
+///
+/// A prime always passes, and most composite numbers fail with a probability of at least
+/// 1/2 for every round, but the Carmichael numbers may pass all of them.
+pub fn fermat_primality_test(n: u64, rounds: usize, rng: &mut impl Rng) -> bool {
+    if n < 4 {
+        return n == 2 || n == 3;
+    }
+    (0..rounds).all(|_| is_fermat_probable_prime(n, rng.gen_range(2..n - 1)))
+}
+
+/// Returns the prime factors of `n`, with their multiplicities, by trial division for the
+/// factors below 2^16 and Pollard's rho for the larger ones.
+fn factorize(mut n: u64) -> Vec<(u64, u32)> {
+    let mut primes = vec![];
+    let mut p = 2;
+    while p < 1 << 16 && p <= n / p {
+        while n.is_multiple_of(p) {
+            n /= p;
+            primes.push(p);
+        }
+        p += 1;
+    }
+    if n > 1 {
+        if p <= n / p && !is_prime_deterministic(n) {
+            primes.extend(pollard_rho_factorize(n, &mut 118, &[], &[]));
+        } else {
+            primes.push(n);
+        }
+    }
+    primes.sort_unstable();
+    let mut factors: Vec<(u64, u32)> = vec![];
+    for p in primes {
+        match factors.last_mut() {
+            Some((last, multiplicity)) if *last == p => *multiplicity += 1,
+            _ => factors.push((p, 1)),
+        }
+    }
+    factors
+}
+
+/// Tells whether `n` is a Carmichael number: composite, and passing the Fermat test for every
+/// base coprime to it.
+///
+/// This is checked with Korselt's criterion: `n` is a Carmichael number if and only if it is
+/// composite, square-free, and `p - 1` divides `n - 1` for every prime factor `p`. Factoring
+/// `n` takes O(2^16) divisions and O(n^(1/4)) steps of Pollard's rho.
+pub fn is_carmichael_number(n: u64) -> bool {
+    if n < 2 || is_prime_deterministic(n) {
+        return false;
+    }
+    let factors = factorize(n);
+    factors.len() > 1
+        && factors
+            .iter()
+            .all(|&(p, multiplicity)| multiplicity == 1 && (n - 1).is_multiple_of(p - 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::{miller_rabin, sieve_of_eratosthenes};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    const CARMICHAEL_NUMBERS: [u64; 7] = [561, 1105, 1729, 2465, 2821, 6601, 8911];
+
+    fn gcd(a: u64, b: u64) -> u64 {
+        if b == 0 {
+            a
+        } else {
+            gcd(b, a % b)
+        }
+    }
+
+    #[test]
+    fn primes_always_pass() {
+        let mut rng = ChaCha8Rng::seed_from_u64(118);
+        let primes = sieve_of_eratosthenes(2000);
+        for n in 0..2000 {
+            let passes = fermat_primality_test(n, 10, &mut rng);
+            if primes.contains(&(n as usize)) {
+                assert!(passes, "{n}");
+            } else if !is_carmichael_number(n) {
+                // no other composite number under 2000 passes 10 rounds with this seed
+                assert!(!passes, "{n}");
+            }
+        }
+        assert!(fermat_primality_test(1_000_000_007, 20, &mut rng));
+        assert!(fermat_primality_test(
+            18_446_744_073_709_551_557,
+            20,
+            &mut rng
+        ));
+    }
+
+    #[test]
+    fn carmichael_numbers_fool_the_fermat_test() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1180);
+        for n in CARMICHAEL_NUMBERS {
+            assert!((2..n).all(|a| gcd(a, n) > 1 || is_fermat_probable_prime(n, a)));
+            // a round passes exactly for the bases coprime to n, so more often than not
+            let trials = 2000;
+            let passed = (0..trials)
+                .filter(|_| fermat_primality_test(n, 1, &mut rng))
+                .count();
+            let coprime = (2..n - 1).filter(|&a| gcd(a, n) == 1).count();
+            let expected = coprime as f64 / (n - 3) as f64;
+            assert!(expected > 0.5);
+            assert!(
+                (passed as f64 / trials as f64 - expected).abs() < 0.05,
+                "{n}"
+            );
+            assert_ne!(miller_rabin(n, &[2, 3, 5, 7]), 0, "{n}");
+        }
+    }
+
+    #[test]
+    fn carmichael_numbers_below_10000() {
+        let found: Vec<u64> = (0..10_000).filter(|&n| is_carmichael_number(n)).collect();
+        assert_eq!(found, CARMICHAEL_NUMBERS);
+        // the definition, checked base by base, agrees with Korselt's criterion
+        let primes = sieve_of_eratosthenes(3000);
+        let by_definition: Vec<u64> = (2..3000)
+            .filter(|&n| !primes.contains(&(n as usize)))
+            .filter(|&n| (2..n).all(|a| gcd(a, n) > 1 || is_fermat_probable_prime(n, a)))
+            .collect();
+        assert_eq!(by_definition, [561, 1105, 1729, 2465, 2821]);
+        assert!(is_carmichael_number(41_041));
+        assert!(is_carmichael_number(825_265));
+        assert!(!is_carmichael_number(1_000_000_007));
+        assert!(!is_carmichael_number(561 * 3));
+    }
+
+    #[test]
+    fn large_numbers() {
+        // a prime and a product of two primes near 2^32, past the end of the trial division
+        assert!(!is_carmichael_number(18_446_744_073_709_551_557));
+        assert!(!is_carmichael_number(4_294_967_291 * 4_294_967_279));
+        assert_eq!(
+            factorize(4_294_967_291 * 4_294_967_279),
+            [(4_294_967_279, 1), (4_294_967_291, 1)]
+        );
+        // (6k + 1)(12k + 1)(18k + 1) for k = 100291, with three prime factors past 2^16
+        assert!(is_carmichael_number(601_747 * 1_203_493 * 1_805_239));
+        assert!(!is_carmichael_number(601_747 * 1_203_493 * 1_203_493));
+        assert_eq!(
+            factorize(65_537 * 65_537 * 12),
+            [(2, 2), (3, 1), (65_537, 2)]
+        );
+    }
+}
@@ -0,0 +1,761 @@
+// Linear recurrences with matrix exponentiation
+// https://en.wikipedia.org/wiki/Linear_recurrence_with_constant_coefficients
+//
+// A recurrence a(n) = c(1) a(n - 1) + ... + c(d) a(n - d) moves its last d
+// terms one step forward with a multiplication by its d x d companion matrix,
+// so raising that matrix to the k-th power by repeated squaring gives the k-th
+// term in O(d^3 log(k)), instead of O(d k) by iterating. The same powers are
+// the powers of x modulo the characteristic polynomial of the recurrence, in
+// O(d^2 log(k)), and Berlekamp-Massey finds the shortest recurrence of a
+// sequence in the first place. Over the reals, the terms are also a closed
+// form in the roots of the characteristic polynomial.
+
+use super::fast_fourier_transform::Complex64;
+use super::{mod_pow, Matrix};
+
+/// A matrix of integers, whose entries are reduced modulo some number by the functions below
+pub type IntMatrix = Matrix<u64>;
+
+/// Multiplies two matrices modulo `modulus`, with 128-bit intermediate products.
+fn multiply_mod(a: &IntMatrix, b: &IntMatrix, modulus: u64) -> IntMatrix {
+    let modulus = modulus as u128;
+    let mut product = IntMatrix::zero(a.rows(), b.cols());
+    for i in 0..a.rows() {
+        for j in 0..b.cols() {
+            let mut sum = 0;
+            for k in 0..a.cols() {
+                sum = (sum + a[[i, k]] as u128 * b[[k, j]] as u128) % modulus;
+            }
+            product[[i, j]] = sum as u64;
+        }
+    }
+    product
+}
+
+/// Raises a square matrix to the power `exp` modulo `modulus`, by repeated squaring.
+///
+/// # Panics
+///
+/// Panics if the matrix is not square, or if `modulus` is zero.
+pub fn matrix_pow_mod(m: &IntMatrix, mut exp: u64, modulus: u64) -> IntMatrix {
+    assert_eq!(m.rows(), m.cols(), "only square matrices have powers");
+    assert_ne!(modulus, 0, "the modulus must be positive");
+    let mut result = IntMatrix::identity(m.rows());
+    for i in 0..m.rows() {
+        result[[i, i]] %= modulus;
+    }
+    let mut base = multiply_mod(m, &IntMatrix::identity(m.rows()), modulus);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = multiply_mod(&result, &base, modulus);
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = multiply_mod(&base, &base, modulus);
+        }
+    }
+    result
+}
+
+/// Returns the `k`-th term modulo `modulus` of the recurrence
+/// `a(n) = coefficients[0] a(n - 1) + ... + coefficients[d - 1] a(n - d)`, whose first terms
+/// are `initial[0] = a(0)` to `initial[d - 1] = a(d - 1)`.
+///
+/// # Panics
+///
+/// Panics if there are no coefficients, if there are not as many initial terms as
+/// coefficients, or if `modulus` is zero.
+pub fn kth_term(coefficients: &[u64], initial: &[u64], k: u64, modulus: u64) -> u64 {
+    let order = coefficients.len();
+    assert!(order > 0, "the recurrence needs at least one coefficient");
+    assert_eq!(
+        initial.len(),
+        order,
+        "there must be as many initial terms as coefficients"
+    );
+    assert_ne!(modulus, 0, "the modulus must be positive");
+    if k < order as u64 {
+        return initial[k as usize] % modulus;
+    }
+    // the companion matrix maps (a(n + d - 1), ..., a(n)) to (a(n + d), ..., a(n + 1))
+    let mut companion = IntMatrix::zero(order, order);
+    for (j, &c) in coefficients.iter().enumerate() {
+        companion[[0, j]] = c;
+    }
+    for i in 1..order {
+        companion[[i, i - 1]] = 1;
+    }
+    let power = matrix_pow_mod(&companion, k - order as u64 + 1, modulus);
+    // the first entry of the state after k - d + 1 steps is a(k)
+    let modulus = modulus as u128;
+    initial.iter().rev().enumerate().fold(0, |sum, (j, &term)| {
+        (sum + power[[0, j]] as u128 * term as u128) % modulus
+    }) as u64
+}
+
+/// Reduces `value` into `[0, modulus)`.
+fn reduce(value: i128, modulus: i64) -> i64 {
+    value.rem_euclid(modulus as i128) as i64
+}
+
+/// Returns the coefficients `c` of the shortest recurrence
+/// `a(n) = c[0] a(n - 1) + ... + c[d - 1] a(n - d)` followed by the whole sequence modulo the
+/// prime `modulus`, reduced into `[0, modulus)`, with the Berlekamp-Massey algorithm in
+/// O(n^2). An all-zero sequence has the empty recurrence.
+/// <https://en.wikipedia.org/wiki/Berlekamp%E2%80%93Massey_algorithm>
+///
+/// The recurrence is fixed up term by term: when it predicts a term wrongly, a multiple of the
+/// last recurrence that failed is added to it, shifted so that it cancels the error at this
+/// term and at no earlier one. A recurrence of order `d` is thus found from its first `2d`
+/// terms.
+///
+/// # Panics
+///
+/// Panics if `modulus < 2`. The result is only meaningful for a prime modulus, for which every
+/// nonzero error can be divided by.
+pub fn berlekamp_massey(sequence: &[i64], modulus: i64) -> Vec<i64> {
+    assert!(modulus >= 2, "the modulus must be at least 2");
+    let sequence: Vec<i64> = sequence
+        .iter()
+        .map(|&term| reduce(term as i128, modulus))
+        .collect();
+    let mut current: Vec<i64> = vec![];
+    // the recurrence before the last change of order, with the error it made then and where
+    let mut previous: Vec<i64> = vec![];
+    let (mut previous_error, mut previous_index) = (1, None::<usize>);
+    for n in 0..sequence.len() {
+        let predicted = current.iter().enumerate().fold(0, |sum, (j, &c)| {
+            reduce(
+                sum as i128 + c as i128 * sequence[n - 1 - j] as i128,
+                modulus,
+            )
+        });
+        let error = reduce(sequence[n] as i128 - predicted as i128, modulus);
+        if error == 0 {
+            continue;
+        }
+        let Some(failed_at) = previous_index else {
+            // the first nonzero term needs a recurrence reaching back past the start
+            previous = current.clone();
+            current = vec![0; n + 1];
+            previous_error = error;
+            previous_index = Some(n);
+            continue;
+        };
+        // current - (error / previous_error) x^shift (1 - previous), in the coefficients
+        let inverse = mod_pow(
+            reduce(previous_error as i128, modulus) as u64,
+            modulus as u64 - 2,
+            modulus as u64,
+        );
+        let factor = reduce(error as i128 * inverse as i128, modulus);
+        let shift = n - failed_at - 1;
+        let mut next = current.clone();
+        if next.len() < shift + 1 + previous.len() {
+            next.resize(shift + 1 + previous.len(), 0);
+        }
+        next[shift] = reduce(next[shift] as i128 + factor as i128, modulus);
+        for (j, &c) in previous.iter().enumerate() {
+            next[shift + 1 + j] = reduce(
+                next[shift + 1 + j] as i128 - factor as i128 * c as i128,
+                modulus,
+            );
+        }
+        if 2 * current.len() <= n {
+            previous = std::mem::replace(&mut current, next);
+            previous_error = error;
+            previous_index = Some(n);
+        } else {
+            current = next;
+        }
+    }
+    current
+}
+
+/// Multiplies two polynomials of degree below `d = coefficients.len()` modulo the
+/// characteristic polynomial `x^d - c[0] x^(d - 1) - ... - c[d - 1]` and `modulus`.
+fn multiply_polynomials(a: &[i64], b: &[i64], coefficients: &[i64], modulus: i64) -> Vec<i64> {
+    let order = coefficients.len();
+    let mut product = vec![0i64; 2 * order - 1];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            product[i + j] = reduce(product[i + j] as i128 + x as i128 * y as i128, modulus);
+        }
+    }
+    // x^k = c[0] x^(k - 1) + ... + c[d - 1] x^(k - d), from the highest power down
+    for k in (order..product.len()).rev() {
+        let top = product[k];
+        for (j, &c) in coefficients.iter().enumerate() {
+            product[k - 1 - j] = reduce(
+                product[k - 1 - j] as i128 + top as i128 * c as i128,
+                modulus,
+            );
+        }
+    }
+    product.truncate(order);
+    product
+}
+
+/// Returns the `n`-th term modulo `modulus`, in `[0, modulus)`, of the recurrence
+/// `a(n) = coefficients[0] a(n - 1) + ... + coefficients[d - 1] a(n - d)`, whose first terms
+/// are `initial[0] = a(0)` to `initial[d - 1] = a(d - 1)`.
+///
+/// The power of the companion matrix in `kth_term` moves the first terms `n` steps, and its
+/// first row is `x^n` modulo the characteristic polynomial of the recurrence, written in the
+/// basis of the first terms: `a(n) = r[0] a(0) + ... + r[d - 1] a(d - 1)` for
+/// `x^n = r[0] + ... + r[d - 1] x^(d - 1)`. Squaring polynomials of degree below `d` instead of
+/// `d x d` matrices takes O(d^2 log(n)). The empty recurrence is the all-zero sequence.
+///
+/// # Panics
+///
+/// Panics if there are not as many initial terms as coefficients, or if `modulus` is not
+/// positive.
+pub fn linear_recurrence_nth(coefficients: &[i64], initial: &[i64], n: u64, modulus: i64) -> i64 {
+    assert_eq!(
+        initial.len(),
+        coefficients.len(),
+        "there must be as many initial terms as coefficients"
+    );
+    assert!(modulus > 0, "the modulus must be positive");
+    let order = coefficients.len();
+    if order == 0 {
+        return 0;
+    }
+    let coefficients: Vec<i64> = coefficients
+        .iter()
+        .map(|&c| reduce(c as i128, modulus))
+        .collect();
+    let mut result = vec![0; order];
+    result[0] = 1 % modulus;
+    // x modulo the characteristic polynomial, which is c[0] for a single coefficient
+    let mut base = vec![0; order];
+    if order == 1 {
+        base[0] = coefficients[0];
+    } else {
+        base[1] = 1 % modulus;
+    }
+    let mut exp = n;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = multiply_polynomials(&result, &base, &coefficients, modulus);
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = multiply_polynomials(&base, &base, &coefficients, modulus);
+        }
+    }
+    result.iter().zip(initial).fold(0, |sum, (&r, &term)| {
+        reduce(sum as i128 + r as i128 * term as i128, modulus)
+    })
+}
+
+/// Returns the `n`-th Fibonacci number modulo `modulus`, with the powers of the matrix
+/// `[[1, 1], [1, 0]]`, which are `[[F(n + 1), F(n)], [F(n), F(n - 1)]]`.
+///
+/// # Panics
+///
+/// Panics if `modulus` is zero.
+pub fn fibonacci_mod(n: u64, modulus: u64) -> u64 {
+    let companion = Matrix::from(vec![vec![1, 1], vec![1, 0]]);
+    matrix_pow_mod(&companion, n, modulus)[[0, 1]]
+}
+
+/// Returns the `n`-th Fibonacci number modulo `modulus` with the fast doubling identities
+/// `F(2k) = F(k) (2 F(k + 1) - F(k))` and `F(2k + 1) = F(k)^2 + F(k + 1)^2`, which take the
+/// same O(log(n)) steps as `fibonacci_mod` with fewer products.
+///
+/// # Panics
+///
+/// Panics if `modulus` is zero.
+pub fn fibonacci_fast_doubling(n: u64, modulus: u64) -> u64 {
+    assert_ne!(modulus, 0, "the modulus must be positive");
+    let m = modulus as u128;
+    // (F(k), F(k + 1)) for the prefix k of the bits of n read so far
+    let (mut a, mut b) = (0, 1 % m);
+    for bit in (0..64 - n.leading_zeros()).rev() {
+        let doubled = a * ((2 * b + m - a) % m) % m;
+        let next = (a * a % m + b * b % m) % m;
+        (a, b) = if n >> bit & 1 == 1 {
+            (next, (doubled + next) % m)
+        } else {
+            (doubled, next)
+        };
+    }
+    a as u64
+}
+
+/// The value at `x` of the polynomial of coefficients `poly`, from the highest degree
+fn evaluate(poly: &[f64], x: Complex64) -> Complex64 {
+    poly.iter().fold(Complex64::default(), |value, &c| {
+        value * x + Complex64::new(c, 0.0)
+    })
+}
+
+/// The derivative of the polynomial of coefficients `poly`, from the highest degree
+fn derivative(poly: &[f64]) -> Vec<f64> {
+    let degree = poly.len().saturating_sub(1);
+    poly[..degree]
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| c * (degree - i) as f64)
+        .collect()
+}
+
+/// The distinct roots of the polynomial of coefficients `poly`, from the highest degree, with
+/// their multiplicities, all refined at once by the Durand-Kerner iteration
+/// <https://en.wikipedia.org/wiki/Durand%E2%80%93Kerner_method>
+fn polynomial_roots(poly: &[f64]) -> Vec<(Complex64, usize)> {
+    let d = poly.len() - 1;
+    // distinct starting points on a circle holding all the roots, off the real axis
+    let radius = 1.0 + poly[1..].iter().fold(0.0f64, |max, c| max.max(c.abs()));
+    let mut roots: Vec<Complex64> = (0..d)
+        .map(|k| {
+            let angle = 2.0 * std::f64::consts::PI * k as f64 / d as f64 + 0.4;
+            Complex64::new(radius * angle.cos(), radius * angle.sin())
+        })
+        .collect();
+    for _ in 0..1000 {
+        let mut change = 0.0f64;
+        for i in 0..d {
+            let mut denominator = Complex64::new(1.0, 0.0);
+            for (j, &other) in roots.iter().enumerate() {
+                if j != i {
+                    denominator *= roots[i] - other;
+                }
+            }
+            let step = evaluate(poly, roots[i]) * denominator.inverse();
+            roots[i] = roots[i] - step;
+            change = change.max(step.norm());
+        }
+        if change <= 1e-15 * radius {
+            break;
+        }
+    }
+    // the iteration only gets slowly and roughly to a multiple root, so the approximations
+    // close to each other are taken for one root
+    let mut distinct: Vec<(Complex64, usize)> = vec![];
+    for root in roots {
+        let close = distinct
+            .iter_mut()
+            .find(|(other, _)| (root - *other).norm() <= 1e-3 * other.norm().max(1.0));
+        match close {
+            Some((_, m)) => *m += 1,
+            None => distinct.push((root, 1)),
+        }
+    }
+    // a root of multiplicity m is a simple root of the (m - 1)-th derivative, where the
+    // Newton iteration converges fast
+    for (root, m) in &mut distinct {
+        let mut poly = poly.to_vec();
+        for _ in 1..*m {
+            poly = derivative(&poly);
+        }
+        let slope = derivative(&poly);
+        for _ in 0..50 {
+            let step = evaluate(&poly, *root) * evaluate(&slope, *root).inverse();
+            if !(step.re.is_finite() && step.im.is_finite()) {
+                break;
+            }
+            *root = *root - step;
+            if step.norm() <= 1e-16 * root.norm() {
+                break;
+            }
+        }
+    }
+    distinct
+}
+
+/// `z^n`, through the polar form of `z`
+fn complex_power(z: Complex64, n: f64) -> Complex64 {
+    let (norm, angle) = (z.norm().powf(n), z.im.atan2(z.re) * n);
+    Complex64::new(norm * angle.cos(), norm * angle.sin())
+}
+
+/// Solves the square system `a x = b` by Gaussian elimination with partial pivoting.
+fn solve_complex(mut a: Vec<Vec<Complex64>>, mut b: Vec<Complex64>) -> Vec<Complex64> {
+    let n = b.len();
+    for column in 0..n {
+        let pivot = (column..n)
+            .max_by(|&i, &j| a[i][column].norm().total_cmp(&a[j][column].norm()))
+            .unwrap();
+        a.swap(pivot, column);
+        b.swap(pivot, column);
+        let inverse = a[column][column].inverse();
+        for row in column + 1..n {
+            let factor = a[row][column] * inverse;
+            let (above, below) = a.split_at_mut(row);
+            for (item, &pivot_item) in below[0].iter_mut().zip(&above[column]).skip(column) {
+                *item = *item - factor * pivot_item;
+            }
+            let subtracted = factor * b[column];
+            b[row] = b[row] - subtracted;
+        }
+    }
+    let mut x = vec![Complex64::default(); n];
+    for row in (0..n).rev() {
+        let mut sum = b[row];
+        for k in row + 1..n {
+            sum = sum - a[row][k] * x[k];
+        }
+        x[row] = sum * a[row][row].inverse();
+    }
+    x
+}
+
+/// Returns the `n`-th term of the real recurrence
+/// `a(n) = coefficients[0] a(n - 1) + ... + coefficients[d - 1] a(n - d)`, whose first terms
+/// are `initial[0] = a(0)` to `initial[d - 1] = a(d - 1)`, from the roots of its
+/// characteristic polynomial.
+/// <https://en.wikipedia.org/wiki/Linear_recurrence_with_constant_coefficients#Solution_to_homogeneous_case>
+///
+/// Every root `r` of `x^d - c[0] x^(d - 1) - ... - c[d - 1]` of multiplicity `m` gives the
+/// solutions `n^j r^n` for `j < m`, and the terms are the one combination of them matching the
+/// first terms. The roots are found numerically, complex ones included: those closer than
+/// `1e-3` times their size are taken for a multiple root, which a Newton iteration on the right
+/// derivative of the polynomial then refines. The weights of the solutions are then those of a
+/// d x d linear system, and their sum at `n` is real, up to rounding, for real first terms. A
+/// zero last coefficient only delays the recurrence by a term, and is dropped. That takes
+/// O(d^3) whatever `n`, but with about as many exact digits as `f64` in the roots, so that
+/// large terms are only approximate: `linear_recurrence_nth` is exact for integers.
+///
+/// # Panics
+///
+/// Panics if there are not as many initial terms as coefficients.
+pub fn solve_linear_recurrence(coefficients: &[f64], initial_values: &[f64], n: u64) -> f64 {
+    assert_eq!(
+        initial_values.len(),
+        coefficients.len(),
+        "there must be as many initial terms as coefficients"
+    );
+    if (n as usize) < initial_values.len() {
+        return initial_values[n as usize];
+    }
+    // without the last zero coefficients, the recurrence starts that many terms later
+    let order = coefficients
+        .iter()
+        .rposition(|&c| c != 0.0)
+        .map_or(0, |i| i + 1);
+    if order == 0 {
+        return 0.0;
+    }
+    let shift = coefficients.len() - order;
+    let (initial, n) = (&initial_values[shift..], (n - shift as u64) as f64);
+    // the characteristic polynomial x^d - c[0] x^(d - 1) - ... - c[d - 1]
+    let poly: Vec<f64> = std::iter::once(1.0)
+        .chain(coefficients[..order].iter().map(|&c| -c))
+        .collect();
+    let roots = polynomial_roots(&poly);
+    let basis = |i: f64| -> Vec<Complex64> {
+        roots
+            .iter()
+            .flat_map(|&(root, m)| {
+                let power = complex_power(root, i);
+                (0..m).map(move |j| power * Complex64::new(i.powi(j as i32), 0.0))
+            })
+            .collect()
+    };
+    let system = (0..order).map(|i| basis(i as f64)).collect();
+    let terms = initial.iter().map(|&a| Complex64::new(a, 0.0)).collect();
+    let weights = solve_complex(system, terms);
+    basis(n)
+        .into_iter()
+        .zip(weights)
+        .fold(Complex64::default(), |sum, (solution, weight)| {
+            sum + solution * weight
+        })
+        .re
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    const MOD: u64 = 1_000_000_007;
+
+    #[test]
+    fn fibonacci_against_a_loop() {
+        let (mut a, mut b) = (0u64, 1u64);
+        for n in 0..=90 {
+            assert_eq!(fibonacci_mod(n, u64::MAX), a, "{n}");
+            assert_eq!(fibonacci_fast_doubling(n, u64::MAX), a, "{n}");
+            assert_eq!(fibonacci_mod(n, MOD), a % MOD);
+            assert_eq!(fibonacci_fast_doubling(n, 1000), a % 1000);
+            (a, b) = (b, a + b);
+        }
+    }
+
+    #[test]
+    fn huge_indices() {
+        for n in [10u64.pow(18), u64::MAX, 123_456_789_012_345] {
+            assert_eq!(fibonacci_mod(n, MOD), fibonacci_fast_doubling(n, MOD));
+        }
+        assert_eq!(fibonacci_mod(10u64.pow(18), MOD), 209_783_453);
+        // the Pisano period modulo 10 is 60
+        assert_eq!(
+            fibonacci_mod(10u64.pow(18), 10),
+            fibonacci_mod(10u64.pow(18) % 60, 10)
+        );
+    }
+
+    #[test]
+    fn moduli_near_the_limit() {
+        let near = (1 << 63) - 25;
+        assert_eq!(
+            fibonacci_mod(10u64.pow(18), near),
+            8_380_691_390_366_880_330
+        );
+        assert_eq!(
+            fibonacci_fast_doubling(10u64.pow(18), near),
+            8_380_691_390_366_880_330
+        );
+        for modulus in [u64::MAX, u64::MAX - 58, 1 << 63] {
+            for n in [100, 1000, 10u64.pow(15)] {
+                assert_eq!(
+                    fibonacci_mod(n, modulus),
+                    fibonacci_fast_doubling(n, modulus)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn tribonacci() {
+        let mut terms = vec![0u64, 0, 1];
+        for n in 3..200 {
+            let next = (terms[n - 1] + terms[n - 2] + terms[n - 3]) % MOD;
+            terms.push(next);
+        }
+        for (k, &term) in terms.iter().enumerate() {
+            assert_eq!(kth_term(&[1, 1, 1], &[0, 0, 1], k as u64, MOD), term);
+        }
+        assert_eq!(kth_term(&[1, 1, 1], &[0, 0, 1], 100_000, MOD), 640_602_611);
+        // a(n) = 2 a(n - 1) - a(n - 2), with the coefficient -1 written as MOD - 1
+        assert_eq!(kth_term(&[2, MOD - 1], &[3, 5], 10, MOD), 23);
+        assert_eq!(
+            kth_term(&[1, 1], &[0, 1], 90, u64::MAX),
+            fibonacci_mod(90, u64::MAX)
+        );
+    }
+
+    #[test]
+    fn berlekamp_massey_finds_fibonacci() {
+        let fibonacci = [0, 1, 1, 2, 3, 5, 8, 13, 21, 34];
+        let recurrence = berlekamp_massey(&fibonacci, MOD as i64);
+        assert_eq!(recurrence, [1, 1]);
+        assert_eq!(
+            linear_recurrence_nth(&recurrence, &[0, 1], 10, MOD as i64),
+            55
+        );
+        assert_eq!(
+            linear_recurrence_nth(&[1, 1], &[0, 1], 10u64.pow(18), MOD as i64),
+            fibonacci_mod(10u64.pow(18), MOD) as i64
+        );
+    }
+
+    #[test]
+    fn berlekamp_massey_finds_random_recurrences() {
+        let mut rng = ChaCha8Rng::seed_from_u64(145);
+        let modulus = 998_244_353;
+        for _ in 0..50 {
+            let order = rng.gen_range(1..8);
+            let mut coefficients: Vec<i64> =
+                (0..order).map(|_| rng.gen_range(0..modulus)).collect();
+            // a last coefficient of 0 would make the recurrence shorter
+            coefficients[order - 1] = rng.gen_range(1..modulus);
+            let initial: Vec<i64> = (0..order).map(|_| rng.gen_range(0..modulus)).collect();
+            let terms: Vec<i64> = (0..2 * order as u64 + 5)
+                .map(|n| linear_recurrence_nth(&coefficients, &initial, n, modulus))
+                .collect();
+            let found = berlekamp_massey(&terms, modulus);
+            assert!(found.len() <= order);
+            // the recurrence found predicts the terms, and is the same one unless the initial
+            // terms happen to follow a shorter one
+            let first = &terms[..found.len()];
+            for (n, &term) in terms.iter().enumerate() {
+                assert_eq!(
+                    linear_recurrence_nth(&found, first, n as u64, modulus),
+                    term
+                );
+            }
+            if found.len() == order {
+                assert_eq!(found, coefficients);
+            }
+        }
+    }
+
+    #[test]
+    fn berlekamp_massey_edge_cases() {
+        assert!(berlekamp_massey(&[], 7).is_empty());
+        assert!(berlekamp_massey(&[0, 0, 0, 7], 7).is_empty());
+        // 2^n, and negative terms and coefficients
+        assert_eq!(berlekamp_massey(&[1, 2, 4, 8, 16, 32], 1_000_003), [2]);
+        assert_eq!(
+            berlekamp_massey(&[1, -1, 1, -1, 1, -1], 1_000_003),
+            [1_000_002]
+        );
+        // the first nonzero term after zeros needs a recurrence as long as the prefix
+        assert_eq!(berlekamp_massey(&[0, 0, 1], 101), [0, 0, 0]);
+        assert_eq!(berlekamp_massey(&[0, 0, 1, 0, 0, 0], 101).len(), 3);
+    }
+
+    #[test]
+    fn linear_recurrence_nth_matches_kth_term() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1450);
+        for _ in 0..100 {
+            let order = rng.gen_range(1..6);
+            let modulus = rng.gen_range(1..1_000_000u64);
+            let coefficients: Vec<u64> = (0..order).map(|_| rng.gen_range(0..modulus)).collect();
+            let initial: Vec<u64> = (0..order).map(|_| rng.gen_range(0..modulus)).collect();
+            let n = rng.gen_range(0..1_000_000);
+            let signed = |terms: &[u64]| terms.iter().map(|&t| t as i64).collect::<Vec<i64>>();
+            assert_eq!(
+                linear_recurrence_nth(&signed(&coefficients), &signed(&initial), n, modulus as i64),
+                kth_term(&coefficients, &initial, n, modulus) as i64
+            );
+        }
+        // a(n) = 2 a(n - 1) - a(n - 2) with a negative coefficient, and negative terms
+        assert_eq!(linear_recurrence_nth(&[2, -1], &[3, 5], 10, MOD as i64), 23);
+        assert_eq!(linear_recurrence_nth(&[1], &[-2], 5, 7), 5);
+        assert_eq!(linear_recurrence_nth(&[], &[], 5, 7), 0);
+        assert_eq!(
+            linear_recurrence_nth(&[3], &[1], 20, i64::MAX),
+            3i64.pow(20)
+        );
+    }
+
+    #[test]
+    fn edge_cases() {
+        assert_eq!(fibonacci_mod(0, MOD), 0);
+        assert_eq!(fibonacci_mod(1, MOD), 1);
+        assert_eq!(fibonacci_mod(12345, 1), 0);
+        assert_eq!(fibonacci_mod(1, 1), 0);
+        assert_eq!(fibonacci_fast_doubling(1, 1), 0);
+        assert_eq!(fibonacci_fast_doubling(0, 1), 0);
+        assert_eq!(kth_term(&[1, 1, 1], &[4, 5, 6], 1, MOD), 5);
+        assert_eq!(kth_term(&[1, 1, 1], &[4, 5, 6], 2, 4), 2);
+        assert_eq!(kth_term(&[3], &[1], 1000, 1), 0);
+        // 3^20 fits in 64 bits
+        assert_eq!(kth_term(&[3], &[1], 20, u64::MAX), 3u64.pow(20));
+        let m = Matrix::from(vec![vec![2, 0], vec![0, 3]]);
+        let power = matrix_pow_mod(&m, 10, 1000);
+        assert_eq!(power, Matrix::from(vec![vec![24, 0], vec![0, 49]]));
+        assert_eq!(matrix_pow_mod(&m, 0, 1), IntMatrix::zero(2, 2));
+        assert_eq!(matrix_pow_mod(&m, 0, 7), IntMatrix::identity(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "only square matrices have powers")]
+    fn non_square_matrices() {
+        matrix_pow_mod(&IntMatrix::zero(2, 3), 2, 10);
+    }
+
+    /// The first terms of a real recurrence, by iterating it
+    fn iterate(coefficients: &[f64], initial: &[f64], count: usize) -> Vec<f64> {
+        let mut terms = initial.to_vec();
+        while terms.len() < count {
+            let last = terms.len();
+            let next = (0..coefficients.len())
+                .map(|i| coefficients[i] * terms[last - 1 - i])
+                .sum();
+            terms.push(next);
+        }
+        terms
+    }
+
+    fn assert_close(found: f64, expected: f64) {
+        let tolerance = 1e-7 * expected.abs().max(1.0);
+        assert!(
+            (found - expected).abs() <= tolerance,
+            "{found} is not {expected}"
+        );
+    }
+
+    #[test]
+    fn closed_forms() {
+        assert_eq!(
+            solve_linear_recurrence(&[1.0, 1.0], &[0.0, 1.0], 20).round(),
+            6765.0
+        );
+        let fibonacci = iterate(&[1.0, 1.0], &[0.0, 1.0], 80);
+        let tribonacci = iterate(&[1.0, 1.0, 1.0], &[0.0, 0.0, 1.0], 60);
+        for n in 0..80 {
+            assert_close(
+                solve_linear_recurrence(&[1.0, 1.0], &[0.0, 1.0], n),
+                fibonacci[n as usize],
+            );
+        }
+        for n in 0..60 {
+            let term = solve_linear_recurrence(&[1.0, 1.0, 1.0], &[0.0, 0.0, 1.0], n);
+            assert_close(term, tribonacci[n as usize]);
+        }
+        assert_eq!(tribonacci[37], 1_132_436_852.0);
+    }
+
+    #[test]
+    fn complex_roots() {
+        // the roots of x^2 - x + 1 are the sixth roots of unity e^(+-i pi / 3), so the terms
+        // 1, 2, 1, -1, -2, -1 come back every six terms
+        let period = [1.0, 2.0, 1.0, -1.0, -2.0, -1.0];
+        for n in [0, 5, 6, 100, 1001, 123_457] {
+            let term = solve_linear_recurrence(&[1.0, -1.0], &[1.0, 2.0], n);
+            assert_close(term, period[n as usize % 6]);
+        }
+        // the roots +-i, and a spiral out of the unit circle with the roots 1 +- i
+        assert_close(solve_linear_recurrence(&[0.0, -1.0], &[1.0, 0.0], 42), -1.0);
+        let spiral = iterate(&[2.0, -2.0], &[1.0, 0.5], 40);
+        for n in 0..40 {
+            assert_close(
+                solve_linear_recurrence(&[2.0, -2.0], &[1.0, 0.5], n),
+                spiral[n as usize],
+            );
+        }
+    }
+
+    #[test]
+    fn multiple_and_zero_roots() {
+        // the double root 1 gives 1 + 2n, and the double root 2 gives (n + 1) 2^n
+        assert_close(
+            solve_linear_recurrence(&[2.0, -1.0], &[1.0, 3.0], 1000),
+            2001.0,
+        );
+        assert_close(
+            solve_linear_recurrence(&[4.0, -4.0], &[1.0, 4.0], 30),
+            31.0 * 2f64.powi(30),
+        );
+        // (x - 1)^3 gives the squares
+        let squares = |n: u64| solve_linear_recurrence(&[3.0, -3.0, 1.0], &[0.0, 1.0, 4.0], n);
+        assert_close(squares(50), 2500.0);
+        // the zero coefficient at the end makes the terms constant from a(1) on
+        assert_eq!(solve_linear_recurrence(&[1.0, 0.0], &[3.0, 5.0], 0), 3.0);
+        assert_close(solve_linear_recurrence(&[1.0, 0.0], &[3.0, 5.0], 90), 5.0);
+        assert_eq!(solve_linear_recurrence(&[0.0, 0.0], &[3.0, 5.0], 2), 0.0);
+        assert_eq!(solve_linear_recurrence(&[], &[], 7), 0.0);
+    }
+
+    #[test]
+    fn agrees_with_iterating() {
+        let mut rng = ChaCha8Rng::seed_from_u64(160);
+        for _ in 0..300 {
+            let order = rng.gen_range(1..=5);
+            let coefficients: Vec<f64> = (0..order).map(|_| rng.gen_range(-1.0..1.0)).collect();
+            let initial: Vec<f64> = (0..order).map(|_| rng.gen_range(-10.0..10.0)).collect();
+            let terms = iterate(&coefficients, &initial, 25);
+            let scale = terms.iter().fold(1.0f64, |max, t| max.max(t.abs()));
+            for (n, &term) in terms.iter().enumerate() {
+                let found = solve_linear_recurrence(&coefficients, &initial, n as u64);
+                assert!(
+                    (found - term).abs() <= 1e-6 * scale,
+                    "{found} is not {term}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "there must be as many initial terms as coefficients")]
+    fn missing_initial_values() {
+        solve_linear_recurrence(&[1.0, 1.0], &[0.0], 5);
+    }
+}
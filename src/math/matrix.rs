@@ -0,0 +1,158 @@
+//! In-place transposes of square matrices stored in row-major order in flat slices, the entry
+//! of row `i` and column `j` of an `n` by `n` matrix being at `i * n + j`.
+
+/// Blocks of at most this size are transposed directly, without more recursion.
+const BLOCK: usize = 16;
+
+fn check_square<T>(matrix: &[T], n: usize) {
+    assert_eq!(
+        matrix.len(),
+        n * n,
+        "a {n} by {n} matrix needs {} entries",
+        n * n
+    );
+}
+
+/// Transposes the matrix by swapping every entry above the diagonal with its mirror.
+///
+/// The entries below the diagonal are read along the columns, `n` entries apart, so that once
+/// the matrix is larger than the cache, almost every swap misses it.
+///
+/// # Panics
+///
+/// Panics if the slice does not have `n * n` entries.
+pub fn transpose_naive<T: Copy>(matrix: &mut [T], n: usize) {
+    check_square(matrix, n);
+    for i in 0..n {
+        for j in i + 1..n {
+            matrix.swap(i * n + j, j * n + i);
+        }
+    }
+}
+
+/// Transposes the matrix by cutting it into four quadrants, transposing the two diagonal
+/// quadrants in place and swapping the two others transposed, recursively.
+/// <https://en.wikipedia.org/wiki/Cache-oblivious_algorithm>
+///
+/// The blocks end up small enough to fit in the cache whatever its size, and the swaps of two
+/// blocks read both of them along their rows and their columns a few times. Any level of the
+/// cache thus misses O(n^2 / B) times for lines of `B` entries, against O(n^2) for the naive
+/// transpose, without knowing the size of the cache or of its lines.
+///
+/// # Panics
+///
+/// Panics if the slice does not have `n * n` entries.
+pub fn transpose_cache_oblivious<T: Copy>(matrix: &mut [T], n: usize) {
+    check_square(matrix, n);
+    transpose_diagonal(matrix, n, 0, n);
+}
+
+/// Transposes in place the block of `size` by `size` entries from row and column `start`.
+fn transpose_diagonal<T: Copy>(matrix: &mut [T], n: usize, start: usize, size: usize) {
+    if size <= BLOCK {
+        for i in start..start + size {
+            for j in i + 1..start + size {
+                matrix.swap(i * n + j, j * n + i);
+            }
+        }
+        return;
+    }
+    let half = size / 2;
+    transpose_diagonal(matrix, n, start, half);
+    transpose_diagonal(matrix, n, start + half, size - half);
+    swap_transposed(matrix, n, start, start + half, half, size - half);
+}
+
+/// Swaps the block of `rows` by `cols` entries at `(row, col)`, above the diagonal, with the
+/// transpose of its mirror at `(col, row)`, by halving the larger side.
+fn swap_transposed<T: Copy>(
+    matrix: &mut [T],
+    n: usize,
+    row: usize,
+    col: usize,
+    rows: usize,
+    cols: usize,
+) {
+    if rows <= BLOCK && cols <= BLOCK {
+        for i in row..row + rows {
+            for j in col..col + cols {
+                matrix.swap(i * n + j, j * n + i);
+            }
+        }
+    } else if rows >= cols {
+        let half = rows / 2;
+        swap_transposed(matrix, n, row, col, half, cols);
+        swap_transposed(matrix, n, row + half, col, rows - half, cols);
+    } else {
+        let half = cols / 2;
+        swap_transposed(matrix, n, row, col, rows, half);
+        swap_transposed(matrix, n, row, col + half, rows, cols - half);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use std::time::Instant;
+
+    fn transposed<T: Copy>(matrix: &[T], n: usize) -> Vec<T> {
+        (0..n * n).map(|k| matrix[(k % n) * n + k / n]).collect()
+    }
+
+    #[test]
+    fn small_matrix() {
+        let mut matrix = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+        transpose_naive(&mut matrix, 3);
+        assert_eq!(matrix, [1, 4, 7, 2, 5, 8, 3, 6, 9]);
+        transpose_cache_oblivious(&mut matrix, 3);
+        assert_eq!(matrix, [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        let mut empty: [i32; 0] = [];
+        transpose_cache_oblivious(&mut empty, 0);
+    }
+
+    #[test]
+    fn random_matrices() {
+        let mut rng = ChaCha8Rng::seed_from_u64(131);
+        // powers of two, and sizes that do not split evenly
+        for n in [1, 2, 4, 8, 16, 17, 33, 100, 128] {
+            let matrix: Vec<i64> = (0..n * n).map(|_| rng.gen()).collect();
+            let expected = transposed(&matrix, n);
+            let mut naive = matrix.clone();
+            transpose_naive(&mut naive, n);
+            assert_eq!(naive, expected);
+            let mut oblivious = matrix.clone();
+            transpose_cache_oblivious(&mut oblivious, n);
+            assert_eq!(oblivious, expected);
+            transpose_cache_oblivious(&mut oblivious, n);
+            assert_eq!(oblivious, matrix);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "a 3 by 3 matrix needs 9 entries")]
+    fn not_square() {
+        transpose_cache_oblivious(&mut [0; 8], 3);
+    }
+
+    #[test]
+    #[ignore = "slow test for measuring time"]
+    fn faster_than_naive() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1310);
+        for n in [1024, 2048, 4096] {
+            let matrix: Vec<f64> = (0..n * n).map(|_| rng.gen()).collect();
+            let mut naive = matrix.clone();
+            let start = Instant::now();
+            transpose_naive(&mut naive, n);
+            let naive_time = start.elapsed();
+            let mut oblivious = matrix;
+            let start = Instant::now();
+            transpose_cache_oblivious(&mut oblivious, n);
+            let oblivious_time = start.elapsed();
+            println!("n = {n}: naive {naive_time:?}, cache-oblivious {oblivious_time:?}");
+            assert_eq!(naive, oblivious);
+            assert!(oblivious_time < naive_time);
+        }
+    }
+}
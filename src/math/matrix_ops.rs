@@ -44,7 +44,7 @@ macro_rules! matrix_element_type_def {
     };
 }
 
-matrix_element_type_def!(i16, i32, i64, i128, u8, u16, u32, u128, f32, f64);
+matrix_element_type_def!(i16, i32, i64, i128, u8, u16, u32, u64, u128, f32, f64);
 
 #[derive(PartialEq, Eq, Debug)]
 pub struct Matrix<T: MatrixElement> {
@@ -81,6 +81,14 @@ impl<T: MatrixElement> Matrix<T> {
         identity
     }
 
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
     pub fn transpose(&self) -> Self {
         // Transpose a matrix of any size
         let mut result = Matrix::zero(self.cols, self.rows);
@@ -65,6 +65,18 @@ pub fn miller_rabin(number: u64, bases: &[u64]) -> u64 {
     0
 }
 
+/// The first 12 primes, which are no strong liars together for any composite number below
+/// 318'665'857'834'031'151'167'461 > 2^64
+/// <https://oeis.org/A014233>
+const DETERMINISTIC_BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+/// Tells whether `n` is prime, exactly for every `u64`, with Miller-Rabin for the first 12
+/// prime bases. The products are taken over `u128`, and a test takes O(log(n)) multiplications
+/// per base.
+pub fn is_prime_deterministic(n: u64) -> bool {
+    n >= 2 && miller_rabin(n, &DETERMINISTIC_BASES) == 0
+}
+
 pub fn big_miller_rabin(number_ref: &BigUint, bases: &[u64]) -> u64 {
     let number = number_ref.clone();
 
@@ -118,6 +130,7 @@ pub fn big_miller_rabin(number_ref: &BigUint, bases: &[u64]) -> u64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::math::LinearSieve;
 
     static DEFAULT_BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
 
@@ -158,6 +171,65 @@ mod tests {
         assert_ne!(miller_rabin(3486337000477823777, &DEFAULT_BASES), 0);
     }
 
+    #[test]
+    fn deterministic_up_to_a_million() {
+        let mut sieve = LinearSieve::new();
+        sieve.prepare(1_000_001).unwrap();
+        let is_prime = |n: usize| n >= 2 && sieve.minimum_prime_factor[n] == n;
+        let mut carmichael = vec![];
+        for n in 0..=1_000_000 {
+            assert_eq!(is_prime_deterministic(n as u64), is_prime(n), "{n}");
+            // Korselt's criterion: squarefree, with p - 1 dividing n - 1 for every factor p
+            if n > 2 && !is_prime(n) {
+                let factors = sieve.factorize(n).unwrap();
+                let squarefree = factors.windows(2).all(|pair| pair[0] != pair[1]);
+                if squarefree && factors.iter().all(|&p| (n - 1).is_multiple_of(p - 1)) {
+                    carmichael.push(n);
+                }
+            }
+        }
+        assert_eq!(carmichael.len(), 43);
+        assert_eq!(carmichael[..5], [561, 1105, 1729, 2465, 2821]);
+    }
+
+    #[test]
+    fn deterministic_on_strong_pseudoprimes() {
+        // the smallest strong pseudoprimes to the first 1, 2, ..., 9 prime bases
+        // https://oeis.org/A014233
+        let pseudoprimes = [
+            2047,
+            1_373_653,
+            25_326_001,
+            3_215_031_751,
+            2_152_302_898_747,
+            3_474_749_660_383,
+            341_550_071_728_321,
+            3_825_123_056_546_413_051,
+        ];
+        for (k, &n) in pseudoprimes.iter().enumerate() {
+            assert_eq!(miller_rabin(n, &DETERMINISTIC_BASES[..=k]), 0, "{n}");
+            assert!(!is_prime_deterministic(n), "{n}");
+        }
+        // 3'215'031'751 = 151 * 751 * 28'351
+        assert_eq!(151 * 751 * 28_351u64, 3_215_031_751);
+    }
+
+    #[test]
+    fn deterministic_near_the_end_of_u64() {
+        // the largest primes below 2^64 and 2^32
+        assert!(is_prime_deterministic(18_446_744_073_709_551_557));
+        assert!(!is_prime_deterministic(u64::MAX));
+        assert!(is_prime_deterministic(4_294_967_291));
+        assert!(!is_prime_deterministic(4_294_967_291 * 4_294_967_291));
+        assert!(!is_prime_deterministic(4_294_967_291 * 4_294_967_279));
+        assert!(is_prime_deterministic(6_920_153_791_723_773_023));
+        assert!(!is_prime_deterministic(0));
+        assert!(!is_prime_deterministic(1));
+        assert!(is_prime_deterministic(2));
+        assert!(is_prime_deterministic(37));
+        assert!(!is_prime_deterministic(37 * 37));
+    }
+
     #[test]
     fn big_basic() {
         assert_eq!(big_miller_rabin(&BigUint::from(3u32), &DEFAULT_BASES), 0);
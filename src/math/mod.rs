@@ -1,4 +1,5 @@
 mod abs;
+mod aks_primality;
 mod aliquot_sum;
 mod amicable_numbers;
 mod area_of_polygon;
@@ -14,9 +15,12 @@ mod ceil;
 mod chinese_remainder_theorem;
 mod collatz_sequence;
 mod combinations;
+mod combinatorics;
 mod cross_entropy_loss;
 mod decimal_to_fraction;
+pub mod distributions;
 mod doomsday;
+mod eigenvalues;
 mod elliptic_curve;
 mod euclidean_distance;
 mod exponential_linear_unit;
@@ -26,6 +30,7 @@ mod factors;
 mod fast_fourier_transform;
 mod fast_power;
 mod faster_perfect_numbers;
+mod fermat_primality;
 mod field;
 mod frizzy_number;
 mod gaussian_elimination;
@@ -41,31 +46,39 @@ mod karatsuba_multiplication;
 mod lcm_of_n_numbers;
 mod leaky_relu;
 mod least_square_approx;
+mod linear_recurrence;
 mod linear_sieve;
 mod logarithm;
 mod lucas_series;
+mod matrix;
 mod matrix_ops;
 mod mersenne_primes;
 mod miller_rabin;
 mod modular_exponential;
+mod montgomery;
 mod newton_raphson;
 mod nthprime;
+pub mod optimization;
 mod pascal_triangle;
 mod perfect_cube;
 mod perfect_numbers;
 mod perfect_square;
 mod pollard_rho;
+mod polynomial;
 mod prime_check;
 mod prime_factors;
 mod prime_numbers;
 mod quadratic_residue;
 mod random;
 mod relu;
+pub mod sampling;
 mod sieve_of_eratosthenes;
 mod sigmoid;
 mod signum;
+mod simplex;
 mod simpsons_integration;
 mod softmax;
+mod sparse;
 mod sprague_grundy_theorem;
 mod square_pyramidal_numbers;
 mod square_root;
@@ -81,6 +94,7 @@ mod vector_cross_product;
 mod zellers_congruence_algorithm;
 
 pub use self::abs::abs;
+pub use self::aks_primality::{aks_primality_test, is_perfect_power};
 pub use self::aliquot_sum::aliquot_sum;
 pub use self::amicable_numbers::amicable_pairs_under_n;
 pub use self::area_of_polygon::area_of_polygon;
@@ -93,12 +107,14 @@ pub use self::binary_exponentiation::binary_exponentiation;
 pub use self::binomial_coefficient::binom;
 pub use self::catalan_numbers::init_catalan;
 pub use self::ceil::ceil;
-pub use self::chinese_remainder_theorem::chinese_remainder_theorem;
+pub use self::chinese_remainder_theorem::{chinese_remainder_theorem, crt_combine, garner_crt};
 pub use self::collatz_sequence::sequence;
 pub use self::combinations::combinations;
+pub use self::combinatorics::{binomial, catalan, multinomial, stirling_first, stirling_second};
 pub use self::cross_entropy_loss::cross_entropy_loss;
 pub use self::decimal_to_fraction::decimal_to_fraction;
 pub use self::doomsday::get_week_day;
+pub use self::eigenvalues::{power_iteration, qr_algorithm};
 pub use self::elliptic_curve::EllipticCurve;
 pub use self::euclidean_distance::euclidean_distance;
 pub use self::exponential_linear_unit::exponential_linear_unit;
@@ -106,11 +122,14 @@ pub use self::extended_euclidean_algorithm::extended_euclidean_algorithm;
 pub use self::factorial::{factorial, factorial_bigmath, factorial_recursive};
 pub use self::factors::factors;
 pub use self::fast_fourier_transform::{
-    fast_fourier_transform, fast_fourier_transform_input_permutation,
-    inverse_fast_fourier_transform,
+    fast_fourier_transform, fast_fourier_transform_input_permutation, fft_inplace, fft_iterative,
+    inverse_fast_fourier_transform, Complex64,
 };
 pub use self::fast_power::fast_power;
 pub use self::faster_perfect_numbers::generate_perfect_numbers;
+pub use self::fermat_primality::{
+    fermat_primality_test, is_carmichael_number, is_fermat_probable_prime,
+};
 pub use self::field::{Field, PrimeField};
 pub use self::frizzy_number::get_nth_frizzy;
 pub use self::gaussian_elimination::gaussian_elimination;
@@ -129,14 +148,22 @@ pub use self::karatsuba_multiplication::multiply;
 pub use self::lcm_of_n_numbers::lcm;
 pub use self::leaky_relu::leaky_relu;
 pub use self::least_square_approx::least_square_approx;
+pub use self::linear_recurrence::{
+    berlekamp_massey, fibonacci_fast_doubling, fibonacci_mod, kth_term, linear_recurrence_nth,
+    matrix_pow_mod, solve_linear_recurrence, IntMatrix,
+};
 pub use self::linear_sieve::LinearSieve;
 pub use self::logarithm::log;
 pub use self::lucas_series::dynamic_lucas_number;
 pub use self::lucas_series::recursive_lucas_number;
+pub use self::matrix::{transpose_cache_oblivious, transpose_naive};
 pub use self::matrix_ops::Matrix;
 pub use self::mersenne_primes::{get_mersenne_primes, is_mersenne_prime};
-pub use self::miller_rabin::{big_miller_rabin, miller_rabin};
-pub use self::modular_exponential::{mod_inverse, modular_exponential};
+pub use self::miller_rabin::{big_miller_rabin, is_prime_deterministic, miller_rabin};
+pub use self::modular_exponential::{mod_inverse, mod_pow, modular_exponential};
+pub use self::montgomery::{
+    montgomery_multiply, montgomery_n_prime, montgomery_pow, montgomery_reduce,
+};
 pub use self::newton_raphson::find_root;
 pub use self::nthprime::nthprime;
 pub use self::pascal_triangle::pascal_triangle;
@@ -145,6 +172,7 @@ pub use self::perfect_numbers::perfect_numbers;
 pub use self::perfect_square::perfect_square;
 pub use self::perfect_square::perfect_square_binary_search;
 pub use self::pollard_rho::{pollard_rho_factorize, pollard_rho_get_one_factor};
+pub use self::polynomial::{Polynomial, SparsePolynomial};
 pub use self::prime_check::prime_check;
 pub use self::prime_factors::prime_factors;
 pub use self::prime_numbers::prime_numbers;
@@ -154,11 +182,15 @@ pub use self::relu::relu;
 pub use self::sieve_of_eratosthenes::sieve_of_eratosthenes;
 pub use self::sigmoid::sigmoid;
 pub use self::signum::signum;
+pub use self::simplex::{simplex, SimplexError};
 pub use self::simpsons_integration::simpsons_integration;
 pub use self::softmax::softmax;
+pub use self::sparse::{SparseMatrix, SparseVec};
 pub use self::sprague_grundy_theorem::calculate_grundy_number;
 pub use self::square_pyramidal_numbers::square_pyramidal_number;
-pub use self::square_root::{fast_inv_sqrt, square_root};
+pub use self::square_root::{
+    accuracy_comparison, fast_inv_sqrt, fast_inv_sqrt_double, square_root,
+};
 pub use self::sum_of_digits::{sum_digits_iterative, sum_digits_recursive};
 pub use self::sum_of_geometric_progression::sum_of_geometric_progression;
 pub use self::sum_of_harmonic_series::sum_of_harmonic_progression;
@@ -83,6 +83,27 @@ pub fn modular_exponential(base: i64, mut power: i64, modulus: i64) -> i64 {
     result
 }
 
+/// Raises `base` to `exponent` modulo `modulus`, for any 64-bit modulus.
+///
+/// The products are computed on 128 bits, so unlike `modular_exponential` they never overflow.
+///
+/// # Panics
+///
+/// Panics if `modulus` is zero.
+pub fn mod_pow(base: u64, mut exponent: u64, modulus: u64) -> u64 {
+    let modulus = modulus as u128;
+    let mut base = base as u128 % modulus;
+    let mut result = 1 % modulus;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % modulus;
+        }
+        exponent >>= 1;
+        base = base * base % modulus;
+    }
+    result as u64
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -124,4 +145,16 @@ mod tests {
         assert_eq!(modular_exponential(1, 1, 1), 0); // 1^1 % 1 should be 0
         assert_eq!(modular_exponential(-1, 2, 1), 0); // (-1)^2 % 1 should be 0
     }
+
+    #[test]
+    fn test_mod_pow() {
+        assert_eq!(mod_pow(2, 3, 5), 3);
+        assert_eq!(mod_pow(123, 45, 67), 62);
+        assert_eq!(mod_pow(10, 0, 1), 0);
+        assert_eq!(mod_pow(0, 0, 7), 1);
+        // Fermat's little theorem for the largest 64-bit prime
+        let p = 18_446_744_073_709_551_557;
+        assert_eq!(mod_pow(u64::MAX, p - 1, p), 1);
+        assert_eq!(mod_pow(3, 1 << 40, u64::MAX), mod_pow(9, 1 << 39, u64::MAX));
+    }
 }
@@ -0,0 +1,169 @@
+use super::mod_pow;
+
+/// The reduction of `R = 2^r` for the bits of `r`, all of them for `r = 64`
+fn mask(r: u64) -> u64 {
+    if r == 64 {
+        u64::MAX
+    } else {
+        (1 << r) - 1
+    }
+}
+
+/// Returns `n' = -n^-1 mod 2^r` for an odd `n`, the constant of the Montgomery reduction
+/// modulo `n` with `R = 2^r`, with the binary extended Euclidean algorithm.
+/// <https://en.wikipedia.org/wiki/Montgomery_modular_multiplication#The_REDC_algorithm>
+///
+/// The sum `s = 1 + n x` is made divisible by every power of two in turn: when its bit `i` is
+/// set, adding `n 2^i`, odd times `2^i`, clears it without changing the lower bits, and sets the
+/// bit `i` of `x`, so that `n x = -1 mod 2^r` after `r` steps.
+///
+/// # Panics
+///
+/// Panics if `n` is even, or if `r` is not between 1 and 64.
+pub fn montgomery_n_prime(n: u64, r: u64) -> u64 {
+    assert!(n % 2 == 1, "the modulus must be odd, got {n}");
+    assert!((1..=64).contains(&r), "r must be between 1 and 64, got {r}");
+    let (mut x, mut sum) = (0u64, 1u64);
+    for i in 0..r {
+        if (sum >> i) & 1 == 1 {
+            x |= 1 << i;
+            sum = sum.wrapping_add(n << i);
+        }
+    }
+    x & mask(r)
+}
+
+/// Returns `t R^-1 mod n` for `R = 2^r`, the Montgomery reduction of `t < n R`, with no division
+/// by `n`.
+/// <https://en.wikipedia.org/wiki/Montgomery_modular_multiplication>
+///
+/// Adding `m = t n' mod R` times `n` to `t` makes it divisible by `R`, since `n n' = -1 mod R`,
+/// so that a shift divides it by `R`, leaving a value below `2 n` that is `t R^-1` modulo `n`.
+/// The result is only meaningful for an odd `n < 2^r` and `n' = montgomery_n_prime(n, r)`.
+pub fn montgomery_reduce(t: u128, n: u64, n_prime: u64, r: u64) -> u64 {
+    let m = (t as u64).wrapping_mul(n_prime) & mask(r);
+    // t + m n may take 129 bits
+    let (sum, carry) = t.overflowing_add(m as u128 * n as u128);
+    let mut u = sum >> r;
+    if carry {
+        u |= 1 << (128 - r);
+    }
+    if u >= n as u128 {
+        u -= n as u128;
+    }
+    u as u64
+}
+
+/// Returns `a b R^-1 mod n`, the Montgomery form of the product of two numbers in Montgomery
+/// form `a = x R mod n` and `b = y R mod n`, with the same conditions as `montgomery_reduce`.
+pub fn montgomery_multiply(a: u64, b: u64, n: u64, n_prime: u64, r: u64) -> u64 {
+    montgomery_reduce(a as u128 * b as u128, n, n_prime, r)
+}
+
+/// Raises `base` to `exp` modulo `modulus` with Montgomery multiplications.
+///
+/// For an odd modulus, the base is converted to its Montgomery form `base R mod n` with
+/// `R = 2^64`, squared and multiplied by square-and-multiply, every product reduced by shifts
+/// and multiplications rather than a division by the modulus, and the result converted back by
+/// a last reduction. Montgomery forms need an odd modulus, so that an even one falls back to
+/// `mod_pow`.
+///
+/// # Panics
+///
+/// Panics if `modulus` is zero.
+pub fn montgomery_pow(base: u64, mut exp: u64, modulus: u64) -> u64 {
+    if modulus.is_multiple_of(2) {
+        return mod_pow(base, exp, modulus);
+    }
+    let n_prime = montgomery_n_prime(modulus, 64);
+    let to_montgomery = |x: u64| (((x as u128) << 64) % modulus as u128) as u64;
+    let mut base = to_montgomery(base % modulus);
+    let mut result = to_montgomery(1 % modulus);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = montgomery_multiply(result, base, modulus, n_prime, 64);
+        }
+        exp >>= 1;
+        base = montgomery_multiply(base, base, modulus, n_prime, 64);
+    }
+    montgomery_reduce(result as u128, modulus, n_prime, 64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use std::time::Instant;
+
+    #[test]
+    fn inverses_modulo_powers_of_two() {
+        let mut rng = ChaCha8Rng::seed_from_u64(171);
+        for _ in 0..1000 {
+            let n = rng.gen::<u64>() | 1;
+            let r = rng.gen_range(1..=64);
+            let n_prime = montgomery_n_prime(n, r);
+            assert_eq!(n.wrapping_mul(n_prime).wrapping_add(1) & mask(r), 0);
+            assert!(r == 64 || n_prime < 1 << r);
+        }
+        assert_eq!(montgomery_n_prime(1, 64), u64::MAX);
+        assert_eq!(montgomery_n_prime(3, 2), 1);
+    }
+
+    #[test]
+    fn small_radix() {
+        // modulo 97 with R = 2^8, 256^-1 = 36 mod 97
+        let (n, r) = (97, 8);
+        let n_prime = montgomery_n_prime(n, r);
+        for t in 0..97 * 256 {
+            assert_eq!(montgomery_reduce(t, n, n_prime, r) as u128, t * 36 % 97);
+        }
+        let form = |x: u64| x * 256 % 97;
+        for (x, y) in [(5, 7), (96, 96), (0, 42), (50, 1)] {
+            let product = montgomery_multiply(form(x), form(y), n, n_prime, r);
+            assert_eq!(product, form(x * y % 97));
+        }
+    }
+
+    #[test]
+    fn agrees_with_mod_pow() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1710);
+        for _ in 0..10_000 {
+            // moduli of every size, close to 2^64 too
+            let modulus = match rng.gen_range(0..3) {
+                0 => rng.gen_range(1..1000),
+                1 => rng.gen_range(1..=u64::MAX),
+                _ => u64::MAX - rng.gen_range(0..1000),
+            };
+            let bits = rng.gen_range(1..64);
+            let (base, exp) = (rng.gen(), rng.gen_range(0..1 << bits));
+            assert_eq!(
+                montgomery_pow(base, exp, modulus),
+                mod_pow(base, exp, modulus),
+                "{base}^{exp} mod {modulus}"
+            );
+        }
+        assert_eq!(montgomery_pow(0, 0, 7), 1);
+        assert_eq!(montgomery_pow(5, 3, 1), 0);
+        assert_eq!(montgomery_pow(2, 64, u64::MAX), 1);
+    }
+
+    #[test]
+    #[ignore = "slow test for measuring time"]
+    fn speed_against_mod_pow() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1711);
+        let inputs: Vec<(u64, u64, u64)> = (0..100_000)
+            .map(|_| (rng.gen(), rng.gen(), rng.gen::<u64>() | 1))
+            .collect();
+        let start = Instant::now();
+        let montgomery: Vec<u64> = inputs
+            .iter()
+            .map(|&(b, e, m)| montgomery_pow(b, e, m))
+            .collect();
+        println!("montgomery_pow: {:?}", start.elapsed());
+        let start = Instant::now();
+        let plain: Vec<u64> = inputs.iter().map(|&(b, e, m)| mod_pow(b, e, m)).collect();
+        println!("mod_pow: {:?}", start.elapsed());
+        assert_eq!(montgomery, plain);
+    }
+}
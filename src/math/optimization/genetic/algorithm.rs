@@ -0,0 +1,305 @@
+use crate::math::optimization::RngLike;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// An encoding of candidate solutions that can be evolved by a `GeneticAlgorithm`.
+pub trait Genome: Clone {
+    /// Draws a random genome of the given size.
+    fn random(size: usize, rng: &mut dyn RngLike) -> Self;
+
+    /// Combines the genes of two parents into a child.
+    fn crossover(&self, other: &Self, rng: &mut dyn RngLike) -> Self;
+
+    /// Mutates the genome, with `mutation_rate` being the probability to mutate each gene.
+    fn mutate(&mut self, mutation_rate: f64, rng: &mut dyn RngLike);
+}
+
+/// How parents are picked from the population.
+#[derive(Clone, Copy, Debug)]
+pub enum Selection {
+    /// The fittest of `k` genomes drawn at random, with replacement.
+    Tournament(usize),
+    /// A genome drawn with a probability proportional to its fitness. Fitnesses are shifted to
+    /// make the least fit genome of the population weigh (almost) nothing, so they can be negative.
+    Roulette,
+}
+
+/// Parameters of a `GeneticAlgorithm`.
+#[derive(Clone, Copy, Debug)]
+pub struct GeneticConfig {
+    pub population_size: usize,
+    /// Size of the random genomes in the initial population
+    pub genome_size: usize,
+    pub selection: Selection,
+    /// Number of fittest genomes copied unchanged into the next generation
+    pub elitism: usize,
+    /// Probability that two parents are recombined; otherwise the child is a copy of the first one
+    pub crossover_rate: f64,
+    /// Probability that each gene of a child mutates
+    pub mutation_rate: f64,
+    /// Number of generations bred after the initial population, at most
+    pub max_generations: usize,
+    /// Stop as soon as a genome reaches this fitness
+    pub fitness_target: Option<f64>,
+    pub seed: u64,
+}
+
+/// Statistics of the fitness of one generation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GenerationStats {
+    pub best_fitness: f64,
+    pub mean_fitness: f64,
+}
+
+/// Outcome of a run of a `GeneticAlgorithm`.
+#[derive(Clone, Debug)]
+pub struct GeneticResult<G> {
+    /// The fittest genome ever seen
+    pub best: G,
+    pub best_fitness: f64,
+    /// Statistics of every generation, starting with the initial population
+    pub history: Vec<GenerationStats>,
+}
+
+/// A genetic algorithm evolving genomes of type `G` to maximize a fitness function `F`.
+pub struct GeneticAlgorithm<G, F> {
+    config: GeneticConfig,
+    fitness: F,
+    _genome: std::marker::PhantomData<G>,
+}
+
+impl<G: Genome, F: Fn(&G) -> f64> GeneticAlgorithm<G, F> {
+    pub fn new(config: GeneticConfig, fitness: F) -> Self {
+        assert!(
+            config.population_size > 0,
+            "the population must not be empty"
+        );
+        assert!(
+            config.elitism <= config.population_size,
+            "there cannot be more elites than genomes"
+        );
+        if let Selection::Tournament(k) = config.selection {
+            assert!(k > 0, "a tournament needs at least one genome");
+        }
+        GeneticAlgorithm {
+            config,
+            fitness,
+            _genome: std::marker::PhantomData,
+        }
+    }
+
+    /// Runs the algorithm from a random initial population, until a genome reaches the fitness
+    /// target or `max_generations` generations were bred. The result is deterministic for a
+    /// given seed.
+    pub fn run(&self) -> GeneticResult<G> {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.config.seed);
+        let population = (0..self.config.population_size)
+            .map(|_| G::random(self.config.genome_size, &mut rng))
+            .collect();
+        self.run_from(population, &mut rng)
+    }
+
+    fn run_from(&self, mut population: Vec<G>, rng: &mut ChaCha8Rng) -> GeneticResult<G> {
+        let mut history = vec![];
+        let mut best: Option<(G, f64)> = None;
+        for generation in 0.. {
+            // sort the population from the fittest genome down
+            let mut rated: Vec<(G, f64)> = population
+                .into_iter()
+                .map(|genome| {
+                    let fitness = (self.fitness)(&genome);
+                    (genome, fitness)
+                })
+                .collect();
+            rated.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+            let best_fitness = rated[0].1;
+            let mean_fitness = rated.iter().map(|(_, f)| f).sum::<f64>() / rated.len() as f64;
+            history.push(GenerationStats {
+                best_fitness,
+                mean_fitness,
+            });
+            if best.as_ref().is_none_or(|(_, f)| best_fitness > *f) {
+                best = Some(rated[0].clone());
+            }
+
+            let target_reached = self
+                .config
+                .fitness_target
+                .is_some_and(|target| best_fitness >= target);
+            if target_reached || generation == self.config.max_generations {
+                break;
+            }
+            population = self.breed(&rated, rng);
+        }
+        let (best, best_fitness) = best.unwrap();
+        GeneticResult {
+            best,
+            best_fitness,
+            history,
+        }
+    }
+
+    /// Builds the next generation from a population sorted by decreasing fitness.
+    fn breed(&self, rated: &[(G, f64)], rng: &mut ChaCha8Rng) -> Vec<G> {
+        let mut next: Vec<G> = rated
+            .iter()
+            .take(self.config.elitism)
+            .map(|(genome, _)| genome.clone())
+            .collect();
+        while next.len() < self.config.population_size {
+            let first = self.select(rated, rng);
+            let second = self.select(rated, rng);
+            let mut child = if rng.gen::<f64>() < self.config.crossover_rate {
+                first.crossover(second, rng)
+            } else {
+                first.clone()
+            };
+            child.mutate(self.config.mutation_rate, rng);
+            next.push(child);
+        }
+        next
+    }
+
+    fn select<'a>(&self, rated: &'a [(G, f64)], rng: &mut ChaCha8Rng) -> &'a G {
+        match self.config.selection {
+            Selection::Tournament(k) => {
+                // the population is sorted, so the fittest contender has the lowest index
+                let winner = (0..k).map(|_| rng.gen_range(0..rated.len())).min().unwrap();
+                &rated[winner].0
+            }
+            Selection::Roulette => {
+                let lowest = rated[rated.len() - 1].1;
+                // a small offset keeps a population of equally fit genomes selectable
+                let weight = |fitness: f64| fitness - lowest + 1e-9;
+                let total: f64 = rated.iter().map(|&(_, f)| weight(f)).sum();
+                let mut spin = rng.gen_range(0.0..total);
+                for (genome, fitness) in rated {
+                    spin -= weight(*fitness);
+                    if spin < 0.0 {
+                        return genome;
+                    }
+                }
+                // only reached through rounding errors
+                &rated[rated.len() - 1].0
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::optimization::genetic::{BitString, Permutation, Uniform};
+    use crate::math::optimization::tour_length;
+    use std::f64::consts::PI;
+
+    fn one_max(genome: &BitString) -> f64 {
+        genome.bits.iter().filter(|&&bit| bit).count() as f64
+    }
+
+    fn one_max_config(selection: Selection, elitism: usize) -> GeneticConfig {
+        GeneticConfig {
+            population_size: 50,
+            genome_size: 64,
+            selection,
+            elitism,
+            crossover_rate: 0.9,
+            mutation_rate: 1.0 / 64.0,
+            max_generations: 300,
+            fitness_target: Some(64.0),
+            seed: 107,
+        }
+    }
+
+    #[test]
+    fn one_max_with_tournament() {
+        let ga = GeneticAlgorithm::new(one_max_config(Selection::Tournament(3), 2), one_max);
+        let result = ga.run();
+        assert_eq!(result.best_fitness, 64.0);
+        assert!(result.best.bits.iter().all(|&bit| bit));
+        // stopped as soon as the optimum was found
+        assert!(result.history.len() <= 301);
+        assert_eq!(result.history.last().unwrap().best_fitness, 64.0);
+        assert!(result.history[..result.history.len() - 1]
+            .iter()
+            .all(|stats| stats.best_fitness < 64.0));
+    }
+
+    #[test]
+    fn one_max_with_roulette_and_uniform_crossover() {
+        let mut config = one_max_config(Selection::Roulette, 2);
+        config.genome_size = 32;
+        config.mutation_rate = 1.0 / 32.0;
+        config.fitness_target = Some(32.0);
+        let ga = GeneticAlgorithm::new(config, |genome: &BitString<Uniform>| {
+            genome.bits.iter().filter(|&&bit| bit).count() as f64
+        });
+        let result = ga.run();
+        assert_eq!(result.best_fitness, 32.0);
+    }
+
+    #[test]
+    fn elitism_keeps_the_best_fitness_non_decreasing() {
+        let mut config = one_max_config(Selection::Tournament(2), 1);
+        // a high mutation rate destroys good genomes, only elitism keeps them
+        config.mutation_rate = 0.2;
+        config.max_generations = 100;
+        config.fitness_target = None;
+        let result = GeneticAlgorithm::new(config, one_max).run();
+        assert_eq!(result.history.len(), 101);
+        assert!(result
+            .history
+            .windows(2)
+            .all(|w| w[0].best_fitness <= w[1].best_fitness));
+        assert_eq!(result.best_fitness, result.history[100].best_fitness);
+        for stats in &result.history {
+            assert!(stats.mean_fitness <= stats.best_fitness);
+        }
+    }
+
+    #[test]
+    fn deterministic_for_a_seed() {
+        let config = one_max_config(Selection::Tournament(3), 1);
+        let first = GeneticAlgorithm::new(config, one_max).run();
+        let second = GeneticAlgorithm::new(config, one_max).run();
+        assert_eq!(first.history, second.history);
+        assert_eq!(first.best, second.best);
+    }
+
+    #[test]
+    fn small_travelling_salesman() {
+        // points around a circle, listed in a scrambled order
+        let n = 12;
+        let angle = |i: usize| 2.0 * PI * ((i * 5) % n) as f64 / n as f64;
+        let distances: Vec<Vec<f64>> = (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| {
+                        let (a, b) = (angle(i), angle(j));
+                        ((a.cos() - b.cos()).powi(2) + (a.sin() - b.sin()).powi(2)).sqrt()
+                    })
+                    .collect()
+            })
+            .collect();
+        let optimal = n as f64 * 2.0 * (PI / n as f64).sin();
+        let config = GeneticConfig {
+            population_size: 100,
+            genome_size: n,
+            selection: Selection::Tournament(3),
+            elitism: 2,
+            crossover_rate: 0.9,
+            mutation_rate: 0.02,
+            max_generations: 500,
+            fitness_target: Some(-optimal * 1.05),
+            seed: 7,
+        };
+        let result = GeneticAlgorithm::new(config, |tour: &Permutation| {
+            -tour_length(&distances, &tour.order)
+        })
+        .run();
+        let length = tour_length(&distances, &result.best.order);
+        assert!(length <= optimal * 1.05, "length was {length}");
+        assert!((result.best_fitness + length).abs() < 1e-9);
+    }
+}
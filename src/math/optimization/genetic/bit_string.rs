@@ -0,0 +1,119 @@
+use super::Genome;
+use crate::math::optimization::RngLike;
+use rand::Rng;
+use std::marker::PhantomData;
+
+/// A way to recombine two bit strings of the same length.
+pub trait BitCrossover {
+    fn cross(first: &[bool], second: &[bool], rng: &mut dyn RngLike) -> Vec<bool>;
+}
+
+/// One-point crossover: the child takes the bits of the first parent up to a random point,
+/// and the bits of the second one after it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OnePoint;
+
+impl BitCrossover for OnePoint {
+    fn cross(first: &[bool], second: &[bool], rng: &mut dyn RngLike) -> Vec<bool> {
+        let point = rng.gen_range(0..=first.len());
+        first[..point]
+            .iter()
+            .chain(&second[point..])
+            .copied()
+            .collect()
+    }
+}
+
+/// Uniform crossover: every bit of the child comes from either parent with equal probability.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Uniform;
+
+impl BitCrossover for Uniform {
+    fn cross(first: &[bool], second: &[bool], rng: &mut dyn RngLike) -> Vec<bool> {
+        first
+            .iter()
+            .zip(second)
+            .map(|(&a, &b)| if rng.gen::<bool>() { a } else { b })
+            .collect()
+    }
+}
+
+/// A fixed-length bit string genome, recombined with the crossover `C`. Mutations flip bits.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitString<C = OnePoint> {
+    pub bits: Vec<bool>,
+    crossover: PhantomData<C>,
+}
+
+impl<C> BitString<C> {
+    pub fn new(bits: Vec<bool>) -> Self {
+        BitString {
+            bits,
+            crossover: PhantomData,
+        }
+    }
+}
+
+impl<C: BitCrossover + Clone> Genome for BitString<C> {
+    fn random(size: usize, rng: &mut dyn RngLike) -> Self {
+        BitString::new((0..size).map(|_| rng.gen()).collect())
+    }
+
+    fn crossover(&self, other: &Self, rng: &mut dyn RngLike) -> Self {
+        assert_eq!(self.bits.len(), other.bits.len(), "mismatched lengths");
+        BitString::new(C::cross(&self.bits, &other.bits, rng))
+    }
+
+    fn mutate(&mut self, mutation_rate: f64, rng: &mut dyn RngLike) {
+        for bit in &mut self.bits {
+            if rng.gen::<f64>() < mutation_rate {
+                *bit = !*bit;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn one_point_crossover_is_a_prefix_and_a_suffix() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let zeros: BitString = BitString::new(vec![false; 16]);
+        let ones: BitString = BitString::new(vec![true; 16]);
+        for _ in 0..100 {
+            let child = zeros.crossover(&ones, &mut rng);
+            let point = child.bits.iter().position(|&bit| bit).unwrap_or(16);
+            assert!(child.bits[..point].iter().all(|&bit| !bit));
+            assert!(child.bits[point..].iter().all(|&bit| bit));
+        }
+    }
+
+    #[test]
+    fn uniform_crossover_mixes_bits() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let first: BitString<Uniform> = BitString::new([false, true].repeat(32));
+        let second: BitString<Uniform> = BitString::new([true, false].repeat(32));
+        let child = first.crossover(&second, &mut rng);
+        assert_eq!(child.bits.len(), 64);
+        // the genes are at the same positions, and come from both parents
+        assert_ne!(child, first);
+        assert_ne!(child, second);
+    }
+
+    #[test]
+    fn mutation_rate() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        let mut genome: BitString = BitString::new(vec![false; 10_000]);
+        genome.mutate(0.0, &mut rng);
+        assert!(genome.bits.iter().all(|&bit| !bit));
+        genome.mutate(0.1, &mut rng);
+        let flipped = genome.bits.iter().filter(|&&bit| bit).count();
+        assert!((800..1200).contains(&flipped), "flipped {flipped}");
+        genome.mutate(1.0, &mut rng);
+        assert_eq!(genome.bits.iter().filter(|&&bit| !bit).count(), flipped);
+    }
+}
@@ -2,8 +2,8 @@ use std::cmp::Ordering;
 use std::collections::BTreeSet;
 use std::fmt::Debug;
 
-/// The goal is to showcase how Genetic algorithms generically work
-/// See: https://en.wikipedia.org/wiki/Genetic_algorithm for concepts
+// The goal is to showcase how Genetic algorithms generically work
+// See: https://en.wikipedia.org/wiki/Genetic_algorithm for concepts
 
 /// This is the definition of a Chromosome for a genetic algorithm
 /// We can picture this as "one contending solution to our problem"
@@ -115,7 +115,12 @@ impl<const K: usize, Rng: rand::Rng> SelectionStrategy<Rng> for Tournament<K, Rn
 }
 
 type Comparator<T> = Box<dyn FnMut(&T, &T) -> Ordering>;
-pub struct GeneticAlgorithm<
+/// A genetic algorithm over a population given by the caller, whose chromosomes bring their own
+/// mutation, crossover and fitness, **the smaller the better**, and whose parents are picked by
+/// a `SelectionStrategy`. It stops at the first generation whose fittest chromosome is within
+/// the threshold. `GeneticAlgorithm` is the engine driven by a `GeneticConfig` instead, which
+/// breeds `Genome`s from a random population, with elitism and statistics.
+pub struct ChromosomeSolver<
     Rng: rand::Rng,
     Eval: PartialOrd,
     C: Chromosome<Rng, Eval>,
@@ -142,7 +147,7 @@ impl<
         Eval: Into<f64> + PartialOrd + Debug,
         C: Chromosome<Rng, Eval> + Clone + Debug,
         Selection: SelectionStrategy<Rng>,
-    > GeneticAlgorithm<Rng, Eval, C, Selection>
+    > ChromosomeSolver<Rng, Eval, C, Selection>
 {
     pub fn init(
         rng: Rng,
@@ -215,8 +220,8 @@ impl<
 
 #[cfg(test)]
 mod tests {
-    use crate::general::genetic::{
-        Chromosome, GenericAlgorithmParams, GeneticAlgorithm, RouletteWheel, SelectionStrategy,
+    use super::{
+        Chromosome, ChromosomeSolver, GenericAlgorithmParams, RouletteWheel, SelectionStrategy,
         Tournament,
     };
     use rand::rngs::ThreadRng;
@@ -305,7 +310,7 @@ mod tests {
             crossover_chance: 0.4,
         };
         let mut solver =
-            GeneticAlgorithm::init(rng, population, 0, params, Box::new(i32::cmp), selection);
+            ChromosomeSolver::init(rng, population, 0, params, Box::new(i32::cmp), selection);
         let res = solver.solve();
         assert!(res.is_some());
         assert_eq!(res.unwrap().genes, secret.chars().collect::<Vec<_>>())
@@ -453,7 +458,7 @@ mod tests {
         }
         let selection = RouletteWheel { rng: rng.clone() };
         let mut solver =
-            GeneticAlgorithm::init(rng, initial_pop, 0, params, Box::new(i32::cmp), selection);
+            ChromosomeSolver::init(rng, initial_pop, 0, params, Box::new(i32::cmp), selection);
         let res = solver.solve();
         assert!(res.is_some());
         assert_eq!(code, res.unwrap().guess);
@@ -0,0 +1,28 @@
+//! A genetic algorithm engine <https://en.wikipedia.org/wiki/Genetic_algorithm>
+//!
+//! Candidate solutions are encoded as genomes implementing `Genome`, and rated by a fitness
+//! function, **the higher the better**. Every generation, parents are selected according to
+//! their fitness, their genomes are recombined and mutated, and the children replace the
+//! population, except for the fittest few carried over unchanged (elitism).
+//!
+//! `ChromosomeSolver` is a leaner variant, evolving a population given by the caller whose
+//! `Chromosome`s implement their own operators and a fitness **the lower the better**, with a
+//! pluggable `SelectionStrategy`, until one is within a threshold.
+//!
+//! Two genomes are provided: `BitString`, a fixed-length string of bits, and `Permutation`,
+//! an ordering of `0..n` suited to travelling salesman-like problems.
+
+mod algorithm;
+mod bit_string;
+mod chromosome;
+mod permutation;
+
+pub use self::algorithm::{
+    GenerationStats, GeneticAlgorithm, GeneticConfig, GeneticResult, Genome, Selection,
+};
+pub use self::bit_string::{BitCrossover, BitString, OnePoint, Uniform};
+pub use self::chromosome::{
+    Chromosome, ChromosomeSolver, GenericAlgorithmParams, RouletteWheel, SelectionStrategy,
+    Tournament,
+};
+pub use self::permutation::Permutation;
@@ -0,0 +1,115 @@
+use super::Genome;
+use crate::math::optimization::RngLike;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// A genome ordering the integers `0..n`, like the cities of a tour.
+///
+/// It is recombined with order crossover (OX), and mutated by swapping genes, which both keep
+/// it a permutation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Permutation {
+    pub order: Vec<usize>,
+}
+
+impl Genome for Permutation {
+    fn random(size: usize, rng: &mut dyn RngLike) -> Self {
+        let mut order: Vec<usize> = (0..size).collect();
+        order.shuffle(rng);
+        Permutation { order }
+    }
+
+    /// Order crossover: the child keeps a random section of the first parent in place, and
+    /// gets the remaining genes in the order they appear in the second parent, starting after
+    /// the section and wrapping around.
+    fn crossover(&self, other: &Self, rng: &mut dyn RngLike) -> Self {
+        let n = self.order.len();
+        assert_eq!(n, other.order.len(), "mismatched lengths");
+        if n < 2 {
+            return self.clone();
+        }
+        let start = rng.gen_range(0..n);
+        let end = rng.gen_range(start..n);
+
+        let mut in_section = vec![false; n];
+        let mut child = vec![usize::MAX; n];
+        for i in start..=end {
+            child[i] = self.order[i];
+            in_section[self.order[i]] = true;
+        }
+        let mut remaining = (1..=n)
+            .map(|offset| other.order[(end + offset) % n])
+            .filter(|&gene| !in_section[gene]);
+        for offset in 1..n - (end - start) {
+            child[(end + offset) % n] = remaining.next().unwrap();
+        }
+        Permutation { order: child }
+    }
+
+    /// Swaps every gene, with probability `mutation_rate`, with a gene at a random position.
+    fn mutate(&mut self, mutation_rate: f64, rng: &mut dyn RngLike) {
+        let n = self.order.len();
+        for i in 0..n {
+            if rng.gen::<f64>() < mutation_rate {
+                let j = rng.gen_range(0..n);
+                self.order.swap(i, j);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn is_permutation(order: &[usize]) -> bool {
+        let mut sorted = order.to_vec();
+        sorted.sort_unstable();
+        sorted == (0..order.len()).collect::<Vec<_>>()
+    }
+
+    #[test]
+    fn order_crossover_keeps_a_section() {
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        let first = Permutation {
+            order: (0..10).collect(),
+        };
+        let second = Permutation {
+            order: (0..10).rev().collect(),
+        };
+        for _ in 0..100 {
+            let child = first.crossover(&second, &mut rng);
+            assert!(is_permutation(&child.order));
+            // at least one gene of the first parent stays in place
+            assert!(child.order.iter().enumerate().any(|(i, &gene)| i == gene));
+        }
+    }
+
+    #[test]
+    fn stays_a_permutation() {
+        let mut rng = ChaCha8Rng::seed_from_u64(4);
+        let mut population: Vec<Permutation> =
+            (0..20).map(|_| Permutation::random(15, &mut rng)).collect();
+        for _ in 0..5000 {
+            let i = rng.gen_range(0..population.len());
+            let j = rng.gen_range(0..population.len());
+            let mut child = population[i].crossover(&population[j], &mut rng);
+            child.mutate(0.1, &mut rng);
+            assert!(is_permutation(&child.order));
+            population[i] = child;
+        }
+    }
+
+    #[test]
+    fn tiny_permutations() {
+        let mut rng = ChaCha8Rng::seed_from_u64(5);
+        let empty = Permutation::random(0, &mut rng);
+        assert!(empty.crossover(&empty, &mut rng).order.is_empty());
+        let single = Permutation { order: vec![0] };
+        let mut child = single.crossover(&single, &mut rng);
+        child.mutate(1.0, &mut rng);
+        assert_eq!(child.order, vec![0]);
+    }
+}
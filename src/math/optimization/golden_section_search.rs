@@ -0,0 +1,94 @@
+/// Guards against tolerances below floating point precision, where the interval can stop shrinking.
+const MAX_ITERATIONS: usize = 10_000;
+
+/// Golden-section search for the minimum of a unimodal function on `[lo, hi]`.
+///
+/// The interval is split at the two points dividing it in the golden ratio. After comparing
+/// the function at those points, one part is dropped and one of the two points can be reused,
+/// so every iteration costs a single new evaluation and shrinks the interval by a factor of
+/// about 0.618. The search stops once the interval is no wider than `tolerance`, and returns
+/// its midpoint together with the value of the function there.
+///
+/// To find a maximum, search for the minimum of `-f`.
+pub fn golden_section_search<F: Fn(f64) -> f64>(
+    f: F,
+    mut lo: f64,
+    mut hi: f64,
+    tolerance: f64,
+) -> (f64, f64) {
+    assert!(tolerance > 0.0, "the tolerance must be positive");
+    let inv_phi = (5f64.sqrt() - 1.0) / 2.0;
+
+    let mut c = hi - inv_phi * (hi - lo);
+    let mut d = lo + inv_phi * (hi - lo);
+    let mut fc = f(c);
+    let mut fd = f(d);
+    for _ in 0..MAX_ITERATIONS {
+        if (hi - lo).abs() <= tolerance {
+            break;
+        }
+        if fc < fd {
+            hi = d;
+            d = c;
+            fd = fc;
+            c = hi - inv_phi * (hi - lo);
+            fc = f(c);
+        } else {
+            lo = c;
+            c = d;
+            fc = fd;
+            d = lo + inv_phi * (hi - lo);
+            fd = f(d);
+        }
+    }
+    let x = (lo + hi) / 2.0;
+    (x, f(x))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parabola() {
+        let (x, y) = golden_section_search(|x| (x - 3.0) * (x - 3.0), -10.0, 10.0, 1e-9);
+        assert!((x - 3.0).abs() < 1e-6);
+        assert!(y < 1e-12);
+    }
+
+    #[test]
+    fn maximum_through_negation() {
+        let (x, y) = golden_section_search(|x| -(-(x - 3.0) * (x - 3.0)), 0.0, 5.0, 1e-9);
+        assert!((x - 3.0).abs() < 1e-6);
+        assert!(-y > -1e-6);
+    }
+
+    #[test]
+    fn non_smooth() {
+        let (x, _) = golden_section_search(|x: f64| (x - 0.25).abs(), -1.0, 1.0, 1e-10);
+        assert!((x - 0.25).abs() < 1e-8);
+    }
+
+    #[test]
+    fn tiny_tolerance_terminates() {
+        let (x, _) = golden_section_search(|x| x * x, -1.0, 1.0, f64::MIN_POSITIVE);
+        assert!(x.abs() < 1e-6);
+    }
+
+    #[test]
+    fn evaluations_count() {
+        use std::cell::Cell;
+        let calls = Cell::new(0);
+        golden_section_search(
+            |x| {
+                calls.set(calls.get() + 1);
+                x * x
+            },
+            -1.0,
+            1.0,
+            1e-6,
+        );
+        // the interval shrinks by about 0.618 per evaluation: 2 * 0.618^k <= 1e-6 for k = 31
+        assert!(calls.get() <= 35);
+    }
+}
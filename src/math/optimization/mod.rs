@@ -0,0 +1,12 @@
+pub mod genetic;
+mod golden_section_search;
+mod simulated_annealing;
+mod ternary_search;
+mod travelling_salesman;
+
+pub use self::golden_section_search::golden_section_search;
+pub use self::simulated_annealing::{anneal, CoolingSchedule, RngLike};
+pub use self::ternary_search::{
+    integer_ternary_search_max, integer_ternary_search_min, ternary_search_max, ternary_search_min,
+};
+pub use self::travelling_salesman::{tour_length, travelling_salesman_annealing};
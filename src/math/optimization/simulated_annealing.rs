@@ -0,0 +1,181 @@
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// The random number generators handed to the neighbour functions of `anneal`.
+///
+/// Every `RngCore` implements it, and `Rng` methods such as `gen_range` are available on
+/// `&mut dyn RngLike`.
+pub trait RngLike: RngCore {}
+
+impl<R: RngCore> RngLike for R {}
+
+/// How the temperature decreases over the iterations of `anneal`.
+#[derive(Clone, Copy, Debug)]
+pub enum CoolingSchedule {
+    /// The temperature is multiplied by `cooling_rate` (between 0 and 1) at every iteration.
+    Exponential {
+        initial_temperature: f64,
+        cooling_rate: f64,
+    },
+    /// The temperature decreases linearly from `initial_temperature` to `final_temperature`.
+    Linear {
+        initial_temperature: f64,
+        final_temperature: f64,
+    },
+}
+
+impl CoolingSchedule {
+    /// Returns the temperature at iteration `step` out of `iterations`.
+    pub fn temperature(&self, step: usize, iterations: usize) -> f64 {
+        match *self {
+            CoolingSchedule::Exponential {
+                initial_temperature,
+                cooling_rate,
+            } => initial_temperature * cooling_rate.powi(step as i32),
+            CoolingSchedule::Linear {
+                initial_temperature,
+                final_temperature,
+            } => {
+                let progress = step as f64 / iterations.max(1) as f64;
+                initial_temperature + (final_temperature - initial_temperature) * progress
+            }
+        }
+    }
+}
+
+/// Simulated annealing: minimizes `energy` over a search space explored through `neighbor`.
+/// <https://en.wikipedia.org/wiki/Simulated_annealing>
+///
+/// Starting from `initial`, every iteration draws a neighbour of the current state. A neighbour
+/// with a lower energy is always accepted, a worse one with probability `exp(-delta / T)`,
+/// where `T` is the temperature given by `schedule`. Accepting worse states early lets the
+/// search escape local minima; as the temperature drops, it settles into a good minimum.
+///
+/// Since the current state may get worse, the best state ever visited is tracked separately:
+/// it is returned with its energy, rather than the final state. The random choices come from
+/// a generator seeded with `seed`, so the result is deterministic for a given seed.
+pub fn anneal<S, E, N>(
+    initial: S,
+    energy: E,
+    mut neighbor: N,
+    schedule: CoolingSchedule,
+    iterations: usize,
+    seed: u64,
+) -> (S, f64)
+where
+    S: Clone,
+    E: Fn(&S) -> f64,
+    N: FnMut(&S, &mut dyn RngLike) -> S,
+{
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut current_energy = energy(&initial);
+    let mut best = (initial.clone(), current_energy);
+    let mut current = initial;
+
+    for step in 0..iterations {
+        let candidate = neighbor(&current, &mut rng);
+        let candidate_energy = energy(&candidate);
+        let delta = candidate_energy - current_energy;
+        let temperature = schedule.temperature(step, iterations);
+        let accept =
+            delta <= 0.0 || (temperature > 0.0 && rng.gen::<f64>() < (-delta / temperature).exp());
+        if accept {
+            current = candidate;
+            current_energy = candidate_energy;
+            if current_energy < best.1 {
+                best = (current.clone(), current_energy);
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    fn step_neighbor(x: &f64, rng: &mut dyn RngLike) -> f64 {
+        x + rng.gen_range(-0.5..0.5)
+    }
+
+    const SCHEDULE: CoolingSchedule = CoolingSchedule::Exponential {
+        initial_temperature: 10.0,
+        cooling_rate: 0.995,
+    };
+
+    #[test]
+    fn minimizes_a_function_with_local_minima() {
+        // global minimum near x = -0.5, with many local minima around it
+        let f = |x: &f64| x * x + 10.0 * (1.0 - (2.0 * std::f64::consts::PI * x).cos()) + x;
+        let schedule = CoolingSchedule::Exponential {
+            initial_temperature: 20.0,
+            cooling_rate: 0.999,
+        };
+        let (x, energy) = anneal(8.0, f, step_neighbor, schedule, 10_000, 7);
+        assert!(x.abs() < 1.0, "x was {x}");
+        assert!((energy - f(&x)).abs() < 1e-12);
+        assert!(energy < f(&8.0));
+    }
+
+    #[test]
+    fn linear_schedule() {
+        let schedule = CoolingSchedule::Linear {
+            initial_temperature: 5.0,
+            final_temperature: 0.0,
+        };
+        assert_eq!(schedule.temperature(0, 100), 5.0);
+        assert_eq!(schedule.temperature(50, 100), 2.5);
+        let (x, _) = anneal(
+            -20.0,
+            |x: &f64| (x - 3.0).abs(),
+            step_neighbor,
+            schedule,
+            5000,
+            1,
+        );
+        assert!((x - 3.0).abs() < 0.1, "x was {x}");
+    }
+
+    #[test]
+    fn returns_the_best_state_ever_visited() {
+        let visited = RefCell::new(vec![]);
+        let energy = |x: &f64| {
+            visited.borrow_mut().push(*x);
+            x.sin() + 0.1 * x.abs()
+        };
+        // a hot schedule that never cools keeps wandering away from the best states
+        let schedule = CoolingSchedule::Linear {
+            initial_temperature: 100.0,
+            final_temperature: 100.0,
+        };
+        let (best, best_energy) = anneal(0.0, energy, step_neighbor, schedule, 1000, 3);
+        let visited = visited.into_inner();
+        // a rejected state is worse than the current one, so the best state is the lowest
+        // energy ever evaluated
+        let lowest = visited
+            .iter()
+            .map(|x| x.sin() + 0.1 * x.abs())
+            .fold(f64::INFINITY, f64::min);
+        assert_eq!(best_energy, lowest);
+        assert!((best_energy - (best.sin() + 0.1 * best.abs())).abs() < 1e-12);
+    }
+
+    #[test]
+    fn deterministic_for_a_seed() {
+        let f = |x: &f64| (x - 1.0).powi(2);
+        let first = anneal(5.0, f, step_neighbor, SCHEDULE, 1000, 42);
+        let second = anneal(5.0, f, step_neighbor, SCHEDULE, 1000, 42);
+        assert_eq!(first, second);
+        let other_seed = anneal(5.0, f, step_neighbor, SCHEDULE, 1000, 43);
+        assert_ne!(first, other_seed);
+    }
+
+    #[test]
+    fn no_iterations() {
+        assert_eq!(
+            anneal(2.0, |x: &f64| x * x, step_neighbor, SCHEDULE, 0, 0),
+            (2.0, 4.0)
+        );
+    }
+}
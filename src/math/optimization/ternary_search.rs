@@ -0,0 +1,178 @@
+/// Ternary search for the maximum of a unimodal function on `[lo, hi]`.
+///
+/// Each iteration evaluates the function at the two points splitting the interval in thirds,
+/// and discards the third that cannot contain the maximum, so the interval shrinks by a factor
+/// of 2/3 per iteration. Returns the point found and the value of the function there.
+///
+/// The function must be unimodal on `[lo, hi]` (strictly increasing then strictly decreasing).
+/// Other functions are not supported: the search still stops after `iterations` steps,
+/// but the result may be any local maximum or no extremum at all.
+pub fn ternary_search_max<F: Fn(f64) -> f64>(
+    f: F,
+    lo: f64,
+    hi: f64,
+    iterations: usize,
+) -> (f64, f64) {
+    let (x, y) = ternary_search_min(|x| -f(x), lo, hi, iterations);
+    (x, -y)
+}
+
+/// Ternary search for the minimum of a unimodal function on `[lo, hi]`.
+/// See `ternary_search_max` for details.
+pub fn ternary_search_min<F: Fn(f64) -> f64>(
+    f: F,
+    mut lo: f64,
+    mut hi: f64,
+    iterations: usize,
+) -> (f64, f64) {
+    for _ in 0..iterations {
+        let mid1 = lo + (hi - lo) / 3.0;
+        let mid2 = hi - (hi - lo) / 3.0;
+        // on ties, keep the left part
+        if f(mid1) <= f(mid2) {
+            hi = mid2;
+        } else {
+            lo = mid1;
+        }
+    }
+    let x = (lo + hi) / 2.0;
+    (x, f(x))
+}
+
+/// Ternary search for the maximum of a unimodal function over the integers in `[lo, hi]`.
+///
+/// Unlike the continuous version, this one terminates exactly: the range shrinks until at
+/// most three candidates are left, which are then compared directly. It uses O(log(hi - lo))
+/// evaluations and returns the point found and the value there.
+///
+/// The function must be strictly increasing then strictly decreasing; a plateau anywhere
+/// other than at the maximum can mislead the search.
+pub fn integer_ternary_search_max<T: PartialOrd, F: Fn(i64) -> T>(
+    f: F,
+    mut lo: i64,
+    mut hi: i64,
+) -> (i64, T) {
+    assert!(lo <= hi, "the search range must not be empty");
+    // the width of the range is taken as a u64, which does not overflow over all the i64
+    while hi.abs_diff(lo) > 2 {
+        let third = (hi.abs_diff(lo) / 3) as i64;
+        let (mid1, mid2) = (lo + third, hi - third);
+        if f(mid1) < f(mid2) {
+            lo = mid1 + 1;
+        } else {
+            hi = mid2;
+        }
+    }
+    let mut best = (lo, f(lo));
+    for x in lo + 1..=hi {
+        let y = f(x);
+        if y > best.1 {
+            best = (x, y);
+        }
+    }
+    best
+}
+
+/// Ternary search for the minimum of a unimodal function over the integers in `[lo, hi]`.
+/// See `integer_ternary_search_max` for details.
+pub fn integer_ternary_search_min<T: PartialOrd, F: Fn(i64) -> T>(
+    f: F,
+    mut lo: i64,
+    mut hi: i64,
+) -> (i64, T) {
+    assert!(lo <= hi, "the search range must not be empty");
+    // the width of the range is taken as a u64, which does not overflow over all the i64
+    while hi.abs_diff(lo) > 2 {
+        let third = (hi.abs_diff(lo) / 3) as i64;
+        let (mid1, mid2) = (lo + third, hi - third);
+        if f(mid1) > f(mid2) {
+            lo = mid1 + 1;
+        } else {
+            hi = mid2;
+        }
+    }
+    let mut best = (lo, f(lo));
+    for x in lo + 1..=hi {
+        let y = f(x);
+        if y < best.1 {
+            best = (x, y);
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn continuous_max() {
+        let (x, y) = ternary_search_max(|x| -(x - 3.0) * (x - 3.0), -10.0, 10.0, 100);
+        assert!((x - 3.0).abs() < 1e-6);
+        assert!(y.abs() < 1e-6);
+    }
+
+    #[test]
+    fn continuous_min() {
+        let (x, y) = ternary_search_min(|x| (x + 1.5) * (x + 1.5) + 2.0, -4.0, 4.0, 100);
+        assert!((x + 1.5).abs() < 1e-6);
+        assert!((y - 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn maximum_at_the_border() {
+        let (x, _) = ternary_search_max(|x| x, 0.0, 1.0, 100);
+        assert!((x - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn non_unimodal_function_terminates() {
+        // sin has many maxima on this range, so this is not supported, but it must still
+        // stop and return a point of the range
+        let (x, y) = ternary_search_max(f64::sin, -100.0, 100.0, 1000);
+        assert!((-100.0..=100.0).contains(&x));
+        assert_eq!(y, x.sin());
+        let (x, _) = integer_ternary_search_max(|x| (x % 7) * (x % 5), 0, 1000);
+        assert!((0..=1000).contains(&x));
+    }
+
+    #[test]
+    fn integer_on_discrete_array() {
+        let values = [1, 3, 7, 12, 20, 31, 33, 25, 10, 4, 0, -8];
+        let (i, v) = integer_ternary_search_max(|i| values[i as usize], 0, 11);
+        assert_eq!((i, v), (6, 33));
+
+        let valley = [9, 5, 2, 1, 4, 8, 15];
+        let (i, v) = integer_ternary_search_min(|i| valley[i as usize], 0, 6);
+        assert_eq!((i, v), (3, 1));
+    }
+
+    #[test]
+    fn integer_every_peak_position() {
+        for n in 1..40 {
+            for peak in 0..n {
+                let f = |x: i64| -(x - peak).abs();
+                assert_eq!(integer_ternary_search_max(f, 0, n - 1), (peak, 0));
+                let g = |x: i64| (x - peak) * (x - peak);
+                assert_eq!(integer_ternary_search_min(g, 0, n - 1), (peak, 0));
+            }
+        }
+    }
+
+    #[test]
+    fn integer_large_range() {
+        let (x, _) = integer_ternary_search_max(|x| -(x - 123_456_789).abs(), -1 << 40, 1 << 40);
+        assert_eq!(x, 123_456_789);
+    }
+
+    #[test]
+    fn integer_full_range() {
+        let distance = |x: i64, target: i64| (x as i128 - target as i128).abs();
+        for target in [i64::MIN, -5, 0, 987_654_321_012, i64::MAX] {
+            let (x, _) = integer_ternary_search_max(|x| -distance(x, target), i64::MIN, i64::MAX);
+            assert_eq!(x, target);
+            let (x, d) = integer_ternary_search_min(|x| distance(x, target), i64::MIN, i64::MAX);
+            assert_eq!((x, d), (target, 0));
+        }
+    }
+}
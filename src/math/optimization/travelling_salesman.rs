@@ -0,0 +1,144 @@
+use super::simulated_annealing::{anneal, CoolingSchedule, RngLike};
+use rand::Rng;
+
+/// Returns the length of the closed tour visiting the cities in the given order.
+pub fn tour_length(distances: &[Vec<f64>], tour: &[usize]) -> f64 {
+    if tour.is_empty() {
+        return 0.0;
+    }
+    tour.iter()
+        .zip(tour.iter().cycle().skip(1))
+        .map(|(&from, &to)| distances[from][to])
+        .sum()
+}
+
+/// Reverses a random section of the tour: the 2-opt move, which replaces two edges of the tour
+/// with two new ones.
+fn two_opt_neighbor(tour: &[usize], rng: &mut dyn RngLike) -> Vec<usize> {
+    let mut next = tour.to_vec();
+    if tour.len() >= 3 {
+        let i = rng.gen_range(0..tour.len() - 1);
+        let j = rng.gen_range(i + 1..tour.len());
+        next[i..=j].reverse();
+    }
+    next
+}
+
+/// Searches for a short closed tour visiting every city once, with simulated annealing.
+///
+/// `distances[i][j]` is the distance from city `i` to city `j`. The search starts from the
+/// cities in index order and explores the tours through 2-opt moves. The initial temperature
+/// is the average distance between two cities, and the temperature decays exponentially to a
+/// thousandth of it over `iterations` steps.
+///
+/// Returns the best tour found, as a permutation of the cities, and its length. The result
+/// is deterministic for a given `seed`.
+pub fn travelling_salesman_annealing(
+    distances: &[Vec<f64>],
+    iterations: usize,
+    seed: u64,
+) -> (Vec<usize>, f64) {
+    let n = distances.len();
+    let pairs = (n * n.saturating_sub(1)).max(1) as f64;
+    let average_distance = distances.iter().flatten().sum::<f64>() / pairs;
+    let schedule = CoolingSchedule::Exponential {
+        initial_temperature: average_distance,
+        cooling_rate: 0.001f64.powf(1.0 / iterations.max(1) as f64),
+    };
+    anneal(
+        (0..n).collect(),
+        |tour: &Vec<usize>| tour_length(distances, tour),
+        |tour: &Vec<usize>, rng: &mut dyn RngLike| two_opt_neighbor(tour, rng),
+        schedule,
+        iterations,
+        seed,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::seq::SliceRandom;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use std::f64::consts::PI;
+
+    fn distance_matrix(points: &[(f64, f64)]) -> Vec<Vec<f64>> {
+        points
+            .iter()
+            .map(|&(x1, y1)| {
+                points
+                    .iter()
+                    .map(|&(x2, y2)| ((x1 - x2).powi(2) + (y1 - y2).powi(2)).sqrt())
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn is_permutation(tour: &[usize], n: usize) -> bool {
+        let mut sorted = tour.to_vec();
+        sorted.sort_unstable();
+        sorted == (0..n).collect::<Vec<_>>()
+    }
+
+    /// Points on the unit circle, listed in a random order
+    fn shuffled_circle(n: usize, seed: u64) -> Vec<(f64, f64)> {
+        let mut points: Vec<(f64, f64)> = (0..n)
+            .map(|i| {
+                let angle = 2.0 * PI * i as f64 / n as f64;
+                (angle.cos(), angle.sin())
+            })
+            .collect();
+        points.shuffle(&mut ChaCha8Rng::seed_from_u64(seed));
+        points
+    }
+
+    #[test]
+    fn circle_converges_to_circumference() {
+        let n = 30;
+        let distances = distance_matrix(&shuffled_circle(n, 106));
+        // the optimal tour follows the circle: the perimeter of the regular polygon
+        let optimal = n as f64 * 2.0 * (PI / n as f64).sin();
+        let (tour, length) = travelling_salesman_annealing(&distances, 50_000, 1);
+        assert!(is_permutation(&tour, n));
+        assert!((length - tour_length(&distances, &tour)).abs() < 1e-9);
+        assert!(
+            length < optimal * 1.03,
+            "length was {length}, optimal is {optimal}"
+        );
+        // far better than the random initial order
+        assert!(length < tour_length(&distances, &(0..n).collect::<Vec<_>>()) / 2.0);
+    }
+
+    #[test]
+    fn deterministic_for_a_seed() {
+        let distances = distance_matrix(&shuffled_circle(15, 3));
+        let first = travelling_salesman_annealing(&distances, 2000, 9);
+        let second = travelling_salesman_annealing(&distances, 2000, 9);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn tiny_instances() {
+        assert_eq!(travelling_salesman_annealing(&[], 100, 0), (vec![], 0.0));
+        assert_eq!(
+            travelling_salesman_annealing(&[vec![0.0]], 100, 0),
+            (vec![0], 0.0)
+        );
+        let distances = vec![vec![0.0, 2.0], vec![2.0, 0.0]];
+        assert_eq!(
+            travelling_salesman_annealing(&distances, 100, 0),
+            (vec![0, 1], 4.0)
+        );
+    }
+
+    #[test]
+    fn tour_is_always_a_permutation() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut tour: Vec<usize> = (0..10).collect();
+        for _ in 0..1000 {
+            tour = two_opt_neighbor(&tour, &mut rng);
+            assert!(is_permutation(&tour, 10));
+        }
+    }
+}
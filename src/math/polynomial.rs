@@ -0,0 +1,264 @@
+//! Polynomials with real coefficients, dense or sparse
+//! <https://en.wikipedia.org/wiki/Polynomial>
+//!
+//! A dense polynomial stores every coefficient up to its degree, and a sparse one only its
+//! nonzero terms, so that `x^1000 + 1` takes two terms instead of 1001 coefficients, and the
+//! sums, products and evaluations of sparse polynomials take time in their numbers of terms
+//! rather than in their degrees.
+
+use std::collections::BTreeMap;
+
+/// `x^n` by squaring, in O(log(n)) multiplications
+fn power(mut x: f64, mut n: u32) -> f64 {
+    let mut result = 1.0;
+    while n > 0 {
+        if n & 1 == 1 {
+            result *= x;
+        }
+        x *= x;
+        n >>= 1;
+    }
+    result
+}
+
+/// A polynomial given by all its coefficients, that of `x^i` at the index `i`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Polynomial {
+    coefficients: Vec<f64>,
+}
+
+impl Polynomial {
+    /// Builds a polynomial from its coefficients in increasing degree, dropping the trailing
+    /// zeros.
+    pub fn new(mut coefficients: Vec<f64>) -> Self {
+        while coefficients.last() == Some(&0.0) {
+            coefficients.pop();
+        }
+        Polynomial { coefficients }
+    }
+
+    /// Returns the coefficients in increasing degree, the last one nonzero, and none for the
+    /// zero polynomial.
+    pub fn coefficients(&self) -> &[f64] {
+        &self.coefficients
+    }
+
+    /// Returns the value at `x` with Horner's method, in O(degree).
+    /// <https://en.wikipedia.org/wiki/Horner%27s_method>
+    pub fn evaluate(&self, x: f64) -> f64 {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(0.0, |value, &c| value * x + c)
+    }
+}
+
+/// A polynomial given by its nonzero terms, mapping their exponents to their coefficients
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SparsePolynomial {
+    terms: BTreeMap<u32, f64>,
+}
+
+impl SparsePolynomial {
+    /// Builds a polynomial from `(exponent, coefficient)` pairs in any order, adding up the
+    /// coefficients given for the same exponent and dropping the zeros.
+    pub fn new(terms: &[(u32, f64)]) -> Self {
+        let mut polynomial = SparsePolynomial::default();
+        for &(exponent, coefficient) in terms {
+            polynomial.add_term(exponent, coefficient);
+        }
+        polynomial
+    }
+
+    fn add_term(&mut self, exponent: u32, coefficient: f64) {
+        let sum = self.terms.get(&exponent).copied().unwrap_or(0.0) + coefficient;
+        if sum == 0.0 {
+            self.terms.remove(&exponent);
+        } else {
+            self.terms.insert(exponent, sum);
+        }
+    }
+
+    /// Keeps the nonzero coefficients of a dense polynomial.
+    pub fn from_dense(dense: &Polynomial) -> Self {
+        let terms = dense
+            .coefficients
+            .iter()
+            .enumerate()
+            .filter(|&(_, &c)| c != 0.0)
+            .map(|(exponent, &c)| (exponent as u32, c))
+            .collect();
+        SparsePolynomial { terms }
+    }
+
+    /// Returns the dense polynomial with the same terms, which takes O(degree) space.
+    pub fn to_dense(&self) -> Polynomial {
+        let mut coefficients =
+            vec![0.0; self.terms.keys().next_back().map_or(0, |&e| e as usize + 1)];
+        for (&exponent, &c) in &self.terms {
+            coefficients[exponent as usize] = c;
+        }
+        Polynomial { coefficients }
+    }
+
+    /// Returns the nonzero terms `(exponent, coefficient)` in increasing degree.
+    pub fn terms(&self) -> impl Iterator<Item = (u32, f64)> + '_ {
+        self.terms.iter().map(|(&exponent, &c)| (exponent, c))
+    }
+
+    /// Returns the number of nonzero terms.
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Returns the largest exponent of a nonzero term, or 0 for the zero polynomial.
+    pub fn degree(&self) -> u32 {
+        self.terms.keys().next_back().copied().unwrap_or(0)
+    }
+
+    /// Returns the value at `x` with Horner's method over the terms only: from the highest
+    /// one down, the value so far is multiplied by `x` to the gap between two exponents before
+    /// adding the next coefficient, which takes O(t log(degree)) for t terms.
+    pub fn evaluate(&self, x: f64) -> f64 {
+        let mut terms = self.terms.iter().rev();
+        let Some((&first, &leading)) = terms.next() else {
+            return 0.0;
+        };
+        let (mut value, mut exponent) = (leading, first);
+        for (&next, &c) in terms {
+            value = value * power(x, exponent - next) + c;
+            exponent = next;
+        }
+        value * power(x, exponent)
+    }
+
+    /// Returns the sum of two polynomials, in O(t log(t)) for t terms. The terms cancelling
+    /// each other are dropped.
+    pub fn add(&self, other: &SparsePolynomial) -> SparsePolynomial {
+        let mut sum = self.clone();
+        for (&exponent, &c) in &other.terms {
+            sum.add_term(exponent, c);
+        }
+        sum
+    }
+
+    /// Returns the difference of two polynomials, like `add`.
+    pub fn sub(&self, other: &SparsePolynomial) -> SparsePolynomial {
+        let mut difference = self.clone();
+        for (&exponent, &c) in &other.terms {
+            difference.add_term(exponent, -c);
+        }
+        difference
+    }
+
+    /// Returns the product of two polynomials, multiplying every term of one by every term of
+    /// the other, in O(n m log(n m)) for n and m terms.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the degree of the product does not fit in a `u32`.
+    pub fn mul(&self, other: &SparsePolynomial) -> SparsePolynomial {
+        let mut product = SparsePolynomial::default();
+        for (&a, &c) in &self.terms {
+            for (&b, &d) in &other.terms {
+                let exponent = a
+                    .checked_add(b)
+                    .expect("the degree of the product overflows");
+                product.add_term(exponent, c * d);
+            }
+        }
+        product
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn random_sparse(rng: &mut ChaCha8Rng, terms: usize, degree: u32) -> SparsePolynomial {
+        let terms: Vec<(u32, f64)> = (0..terms)
+            .map(|_| (rng.gen_range(0..=degree), rng.gen_range(-5.0..5.0)))
+            .collect();
+        SparsePolynomial::new(&terms)
+    }
+
+    #[test]
+    fn difference_of_squares() {
+        let plus = SparsePolynomial::new(&[(1000, 1.0), (0, 1.0)]);
+        let minus = SparsePolynomial::new(&[(1000, 1.0), (0, -1.0)]);
+        let product = plus.mul(&minus);
+        // the terms in x^1000 cancel out
+        assert_eq!(product, SparsePolynomial::new(&[(2000, 1.0), (0, -1.0)]));
+        assert_eq!(product.len(), 2);
+        assert_eq!(product.degree(), 2000);
+        assert_eq!(product.evaluate(1.0), 0.0);
+        assert_eq!(product.evaluate(-1.0), 0.0);
+        assert_eq!(product.evaluate(0.5), 0.5f64.powi(2000) - 1.0);
+        assert_eq!(plus.add(&minus), SparsePolynomial::new(&[(1000, 2.0)]));
+        assert_eq!(plus.sub(&minus), SparsePolynomial::new(&[(0, 2.0)]));
+        assert!(plus.sub(&plus).is_empty());
+    }
+
+    #[test]
+    fn agrees_with_dense_polynomials() {
+        let mut rng = ChaCha8Rng::seed_from_u64(172);
+        for terms in 0..=50 {
+            let sparse = random_sparse(&mut rng, terms, 120);
+            let dense = sparse.to_dense();
+            assert_eq!(SparsePolynomial::from_dense(&dense), sparse);
+            assert_eq!(
+                dense.coefficients().len(),
+                sparse.degree() as usize + (terms > 0) as usize
+            );
+            for _ in 0..20 {
+                let x = rng.gen_range(-1.05..1.05);
+                let (found, expected) = (sparse.evaluate(x), dense.evaluate(x));
+                assert!(
+                    (found - expected).abs() <= 1e-9 * (1.0 + expected.abs()),
+                    "{found} is not {expected}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn products_and_sums() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1720);
+        for _ in 0..50 {
+            let (a, b) = (
+                random_sparse(&mut rng, 10, 30),
+                random_sparse(&mut rng, 12, 30),
+            );
+            let (p, q) = (a.to_dense(), b.to_dense());
+            // the dense product, by convolution
+            let mut product = vec![0.0; p.coefficients().len() + q.coefficients().len()];
+            for (i, c) in p.coefficients().iter().enumerate() {
+                for (j, d) in q.coefficients().iter().enumerate() {
+                    product[i + j] += c * d;
+                }
+            }
+            let found = a.mul(&b).to_dense();
+            let expected = Polynomial::new(product);
+            assert_eq!(found.coefficients().len(), expected.coefficients().len());
+            for (x, y) in found.coefficients().iter().zip(expected.coefficients()) {
+                assert!((x - y).abs() < 1e-9);
+            }
+            let x = rng.gen_range(-1.5..1.5);
+            let sum = p.evaluate(x) + q.evaluate(x);
+            assert!((a.add(&b).evaluate(x) - sum).abs() <= 1e-9 * (1.0 + sum.abs()));
+            let difference = p.evaluate(x) - q.evaluate(x);
+            assert!((a.sub(&b).evaluate(x) - difference).abs() <= 1e-9 * (1.0 + difference.abs()));
+        }
+        let zero = SparsePolynomial::default();
+        assert_eq!(zero.degree(), 0);
+        assert_eq!(zero.evaluate(3.0), 0.0);
+        assert!(zero.mul(&SparsePolynomial::new(&[(3, 1.0)])).is_empty());
+        assert_eq!(Polynomial::new(vec![1.0, 0.0, 0.0]).coefficients(), [1.0]);
+    }
+}
@@ -0,0 +1,77 @@
+use crate::error::{AlgorithmError, AlgorithmResult};
+use rand::Rng;
+use std::collections::HashSet;
+
+/// Draws `k` distinct indices uniformly among `0..n`, with Floyd's algorithm.
+///
+/// For every `j` from `n - k` to `n - 1`, a random index `t` in `0..=j` is added, or `j` itself
+/// if `t` was already chosen. Every subset of `k` indices comes out with the same probability,
+/// with only `k` random numbers and O(k) memory whatever the size of `n`, where shuffling all
+/// indices would take O(n). The order of the indices is not uniformly random: shuffle them if
+/// it matters.
+pub fn sample_without_replacement(
+    n: usize,
+    k: usize,
+    rng: &mut impl Rng,
+) -> AlgorithmResult<Vec<usize>> {
+    if k > n {
+        return Err(AlgorithmError::InvalidParameter {
+            argument: "k",
+            reason: format!("cannot draw {k} distinct indices out of {n}"),
+        });
+    }
+    let mut chosen = HashSet::with_capacity(k);
+    let mut sample = Vec::with_capacity(k);
+    for j in n - k..n {
+        let t = rng.gen_range(0..=j);
+        let index = if chosen.contains(&t) { j } else { t };
+        chosen.insert(index);
+        sample.push(index);
+    }
+    Ok(sample)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn distinct_indices() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        for (n, k) in [(10, 10), (1000, 10), (1000, 999), (1, 1), (usize::MAX, 5)] {
+            let sample = sample_without_replacement(n, k, &mut rng).unwrap();
+            assert_eq!(sample.len(), k);
+            assert!(sample.iter().all(|&index| index < n));
+            assert_eq!(sample.iter().collect::<HashSet<_>>().len(), k);
+        }
+    }
+
+    #[test]
+    fn uniform_indices() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        let mut counts = [0; 8];
+        for _ in 0..16_000 {
+            for index in sample_without_replacement(8, 3, &mut rng).unwrap() {
+                counts[index] += 1;
+            }
+        }
+        // every index is chosen in 3 / 8 of the trials, 6000 times
+        assert!(counts.iter().all(|&count| (5700..6300).contains(&count)));
+    }
+
+    #[test]
+    fn degenerate_sizes() {
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        assert_eq!(sample_without_replacement(5, 0, &mut rng), Ok(vec![]));
+        assert_eq!(sample_without_replacement(0, 0, &mut rng), Ok(vec![]));
+        assert_eq!(
+            sample_without_replacement(3, 4, &mut rng),
+            Err(AlgorithmError::InvalidParameter {
+                argument: "k",
+                reason: "cannot draw 4 distinct indices out of 3".to_string(),
+            })
+        );
+    }
+}
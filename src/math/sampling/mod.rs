@@ -0,0 +1,7 @@
+mod floyd;
+mod reservoir;
+mod weighted;
+
+pub use self::floyd::sample_without_replacement;
+pub use self::reservoir::reservoir_sample;
+pub use self::weighted::{weighted_choice, WeightedAlias};
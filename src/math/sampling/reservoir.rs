@@ -0,0 +1,49 @@
+use crate::algorithms::reservoir_sample_l;
+use rand::Rng;
+
+/// Draws a uniform sample of `k` items from a stream of unknown length, keeping only `k` items
+/// in memory: every item of the stream ends up in the sample with probability `k / n`.
+///
+/// This is Li's Algorithm L from `algorithms::reservoir_sample_l`, which skips over the items
+/// which will not enter the sample instead of drawing a random number for each of them. If the
+/// stream has fewer than `k` items, all of them are returned, and `k = 0` gives an empty sample.
+pub fn reservoir_sample<T: Clone>(
+    iter: impl Iterator<Item = T>,
+    k: usize,
+    rng: &mut impl Rng,
+) -> Vec<T> {
+    reservoir_sample_l(iter, k, rng)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn every_item_is_chosen_with_probability_k_over_n() {
+        let mut rng = ChaCha8Rng::seed_from_u64(110);
+        let (n, k, trials) = (50, 5, 20_000);
+        let mut counts = vec![0; n];
+        for _ in 0..trials {
+            let sample = reservoir_sample(0..n, k, &mut rng);
+            assert_eq!(sample.len(), k);
+            for item in sample {
+                counts[item] += 1;
+            }
+        }
+        // k / n = 10% of the trials, 2000 times
+        let expected = (trials * k / n) as f64;
+        for count in counts {
+            assert!((count as f64 - expected).abs() < 0.1 * expected, "{count}");
+        }
+    }
+
+    #[test]
+    fn degenerate_samples() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        assert!(reservoir_sample(0..10, 0, &mut rng).is_empty());
+        assert_eq!(reservoir_sample("ab".chars(), 3, &mut rng), ['a', 'b']);
+    }
+}
@@ -0,0 +1,178 @@
+use crate::error::{AlgorithmError, AlgorithmResult};
+use rand::Rng;
+
+/// Checks that the weights are finite and non-negative, with at least one positive, and
+/// returns their sum.
+fn check_weights(weights: &[f64]) -> AlgorithmResult<f64> {
+    if weights.is_empty() {
+        return Err(AlgorithmError::EmptyInput {
+            argument: "weights",
+        });
+    }
+    if let Some(weight) = weights.iter().find(|w| !w.is_finite() || **w < 0.0) {
+        return Err(AlgorithmError::InvalidParameter {
+            argument: "weights",
+            reason: format!("must be finite and not negative, got {weight}"),
+        });
+    }
+    let total: f64 = weights.iter().sum();
+    if total <= 0.0 {
+        return Err(AlgorithmError::InvalidParameter {
+            argument: "weights",
+            reason: "at least one weight must be positive".to_string(),
+        });
+    }
+    if !total.is_finite() {
+        return Err(AlgorithmError::NonFiniteValue {
+            argument: "weights",
+        });
+    }
+    Ok(total)
+}
+
+/// Draws an index with a probability proportional to its weight, in O(n).
+///
+/// For many draws from the same weights, `WeightedAlias` takes O(1) per draw.
+pub fn weighted_choice(weights: &[f64], rng: &mut impl Rng) -> AlgorithmResult<usize> {
+    let total = check_weights(weights)?;
+    let target = rng.gen::<f64>() * total;
+    let mut cumulative = 0.0;
+    let mut last_positive = 0;
+    for (i, &weight) in weights.iter().enumerate() {
+        if weight > 0.0 {
+            cumulative += weight;
+            last_positive = i;
+            if target < cumulative {
+                return Ok(i);
+            }
+        }
+    }
+    // rounding made the cumulative sum fall just short of the target
+    Ok(last_positive)
+}
+
+/// Vose's alias method <https://en.wikipedia.org/wiki/Alias_method>, to draw indices with
+/// probabilities proportional to weights in O(1) per draw, after an O(n) setup.
+///
+/// Every index gets a column of height `n * weight / total`, so that the mean height is 1. The
+/// columns higher than 1 are cut down to fill the ones lower than 1, until all have height 1:
+/// each column is then split between its own index, with probability `prob`, and a single
+/// other index, its alias. A draw picks a column uniformly, then one of its two indices.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WeightedAlias {
+    prob: Vec<f64>,
+    alias: Vec<usize>,
+}
+
+impl WeightedAlias {
+    pub fn new(weights: &[f64]) -> AlgorithmResult<Self> {
+        let total = check_weights(weights)?;
+        let n = weights.len();
+        let mut heights: Vec<f64> = weights.iter().map(|w| w * n as f64 / total).collect();
+        let mut prob = vec![1.0; n];
+        let mut alias: Vec<usize> = (0..n).collect();
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) =
+            (0..n).partition(|&i| heights[i] < 1.0);
+        while let (Some(&low), Some(&high)) = (small.last(), large.last()) {
+            small.pop();
+            prob[low] = heights[low];
+            alias[low] = high;
+            heights[high] -= 1.0 - heights[low];
+            if heights[high] < 1.0 {
+                large.pop();
+                small.push(high);
+            }
+        }
+        // the columns left are of height 1 up to rounding errors, and keep `prob = 1`
+        Ok(WeightedAlias { prob, alias })
+    }
+
+    /// Draws an index.
+    pub fn sample(&self, rng: &mut impl Rng) -> usize {
+        let column = rng.gen_range(0..self.prob.len());
+        if rng.gen::<f64>() < self.prob[column] {
+            column
+        } else {
+            self.alias[column]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    const WEIGHTS: [f64; 5] = [1.0, 0.0, 3.0, 0.5, 5.5];
+
+    fn check_frequencies(mut draw: impl FnMut() -> usize) {
+        let trials = 100_000;
+        let mut counts = [0; WEIGHTS.len()];
+        for _ in 0..trials {
+            counts[draw()] += 1;
+        }
+        let total: f64 = WEIGHTS.iter().sum();
+        for (count, weight) in counts.iter().zip(WEIGHTS) {
+            let frequency = *count as f64 / trials as f64;
+            assert!((frequency - weight / total).abs() < 0.01, "{counts:?}");
+        }
+        // an index of weight 0 is never drawn
+        assert_eq!(counts[1], 0);
+    }
+
+    #[test]
+    fn weighted_choice_frequencies() {
+        let mut rng = ChaCha8Rng::seed_from_u64(10);
+        check_frequencies(|| weighted_choice(&WEIGHTS, &mut rng).unwrap());
+    }
+
+    #[test]
+    fn alias_frequencies() {
+        let mut rng = ChaCha8Rng::seed_from_u64(11);
+        let alias = WeightedAlias::new(&WEIGHTS).unwrap();
+        check_frequencies(|| alias.sample(&mut rng));
+    }
+
+    #[test]
+    fn single_positive_weight() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+        let alias = WeightedAlias::new(&[0.0, 0.0, 2.0]).unwrap();
+        for _ in 0..100 {
+            assert_eq!(alias.sample(&mut rng), 2);
+            assert_eq!(weighted_choice(&[0.0, 7.0, 0.0], &mut rng), Ok(1));
+        }
+    }
+
+    #[test]
+    fn invalid_weights() {
+        let mut rng = ChaCha8Rng::seed_from_u64(13);
+        let all_zero = AlgorithmError::InvalidParameter {
+            argument: "weights",
+            reason: "at least one weight must be positive".to_string(),
+        };
+        assert_eq!(
+            weighted_choice(&[0.0, 0.0], &mut rng),
+            Err(all_zero.clone())
+        );
+        assert_eq!(WeightedAlias::new(&[0.0]), Err(all_zero));
+        assert_eq!(
+            WeightedAlias::new(&[]),
+            Err(AlgorithmError::EmptyInput {
+                argument: "weights"
+            })
+        );
+        for weights in [[1.0, -1.0], [1.0, f64::NAN], [f64::INFINITY, 1.0]] {
+            assert!(matches!(
+                weighted_choice(&weights, &mut rng),
+                Err(AlgorithmError::InvalidParameter { .. })
+            ));
+        }
+        assert_eq!(
+            WeightedAlias::new(&[f64::MAX, f64::MAX]),
+            Err(AlgorithmError::NonFiniteValue {
+                argument: "weights"
+            })
+        );
+    }
+}
@@ -0,0 +1,328 @@
+use std::fmt;
+
+/// Why a linear program has no optimal solution
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SimplexError {
+    /// No point satisfies all the constraints
+    Infeasible,
+    /// The objective grows without bound over the feasible points
+    Unbounded,
+}
+
+impl fmt::Display for SimplexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SimplexError::Infeasible => write!(f, "the constraints cannot all be satisfied"),
+            SimplexError::Unbounded => write!(f, "the objective is unbounded"),
+        }
+    }
+}
+
+impl std::error::Error for SimplexError {}
+
+/// The tolerance below which a reduced cost or an entry of a column counts as zero
+const EPSILON: f64 = 1e-9;
+
+/// The equality constraints `M z = r`, `z >= 0` of the standard form, with a basis: the
+/// columns whose variables may be nonzero, the inverse of the matrix they make, and the values
+/// of their variables
+struct Revised {
+    /// The columns of `M`
+    columns: Vec<Vec<f64>>,
+    basis: Vec<usize>,
+    inverse: Vec<Vec<f64>>,
+    values: Vec<f64>,
+}
+
+impl Revised {
+    /// The column `j` in the coordinates of the basis, `B^-1 M_j`
+    fn direction(&self, j: usize) -> Vec<f64> {
+        self.inverse
+            .iter()
+            .map(|row| row.iter().zip(&self.columns[j]).map(|(a, b)| a * b).sum())
+            .collect()
+    }
+
+    /// Replaces the variable of the basis in the row `r` with the variable `j`, of direction
+    /// `u`.
+    fn pivot(&mut self, r: usize, j: usize, u: &[f64]) {
+        let scale = u[r];
+        self.inverse[r].iter_mut().for_each(|a| *a /= scale);
+        self.values[r] /= scale;
+        let (pivot_row, pivot_value) = (self.inverse[r].clone(), self.values[r]);
+        for (i, (row, value)) in self.inverse.iter_mut().zip(&mut self.values).enumerate() {
+            if i != r && u[i] != 0.0 {
+                row.iter_mut()
+                    .zip(&pivot_row)
+                    .for_each(|(a, p)| *a -= u[i] * p);
+                *value -= u[i] * pivot_value;
+            }
+        }
+        self.basis[r] = j;
+    }
+
+    /// Maximizes `costs . z` with the variables for which `allowed` holds, pivoting until no
+    /// reduced cost is positive.
+    fn optimize(
+        &mut self,
+        costs: &[f64],
+        allowed: impl Fn(usize) -> bool,
+    ) -> Result<(), SimplexError> {
+        loop {
+            // the duals y = c_B B^-1, and the first variable of positive reduced cost c_j - y M_j
+            let m = self.basis.len();
+            let duals: Vec<f64> = (0..m)
+                .map(|k| {
+                    (0..m)
+                        .map(|i| costs[self.basis[i]] * self.inverse[i][k])
+                        .sum()
+                })
+                .collect();
+            let entering = (0..self.columns.len()).find(|&j| {
+                let reduced = costs[j]
+                    - duals
+                        .iter()
+                        .zip(&self.columns[j])
+                        .map(|(y, a)| y * a)
+                        .sum::<f64>();
+                allowed(j) && !self.basis.contains(&j) && reduced > EPSILON
+            });
+            let Some(j) = entering else {
+                return Ok(());
+            };
+            // the ratio test, the ties going to the smallest leaving variable
+            let u = self.direction(j);
+            let leaving = (0..m)
+                .filter(|&i| u[i] > EPSILON)
+                .min_by(|&a, &b| {
+                    (self.values[a] / u[a])
+                        .total_cmp(&(self.values[b] / u[b]))
+                        .then(self.basis[a].cmp(&self.basis[b]))
+                })
+                .ok_or(SimplexError::Unbounded)?;
+            self.pivot(leaving, j, &u);
+        }
+    }
+}
+
+/// Maximizes `c . x` subject to `A x <= b` and `x >= 0`, returning the optimal value and a
+/// point reaching it, with the revised simplex method
+/// <https://en.wikipedia.org/wiki/Revised_simplex_method>
+///
+/// A slack variable for every row turns the inequalities into equalities, and the rows with a
+/// negative bound are negated, with an artificial variable each. A first phase maximizes minus
+/// the sum of the artificial variables from the basis of the slack and artificial ones: the
+/// program is infeasible if it cannot bring them to zero. The second phase then maximizes
+/// `c . x` from the basis found, without the artificial variables. Each pivot keeps the inverse
+/// of the basis, computing the reduced costs from the duals and the entering column in the
+/// coordinates of the basis in O(m (m + n)), rather than updating a whole tableau. Bland's rule,
+/// the first variable with a positive reduced cost entering and the first of the tied ones
+/// leaving, keeps degenerate pivots from cycling, so that it always terminates, though in the
+/// worst case after exponentially many pivots.
+/// <https://en.wikipedia.org/wiki/Bland%27s_rule>
+///
+/// # Panics
+///
+/// Panics if the rows of `a` do not have as many coefficients as `c`, or if `b` does not have
+/// as many bounds as `a` has rows.
+pub fn simplex(c: &[f64], a: &[Vec<f64>], b: &[f64]) -> Result<(f64, Vec<f64>), SimplexError> {
+    let (n, m) = (c.len(), a.len());
+    assert_eq!(b.len(), m, "there must be as many bounds as rows");
+    if let Some(i) = a.iter().position(|row| row.len() != n) {
+        panic!("the row {i} must have the {n} coefficients of the objective");
+    }
+    // the variables x, then the slacks, then the artificial variables of the negated rows
+    let negated: Vec<usize> = (0..m).filter(|&i| b[i] < 0.0).collect();
+    let sign = |i: usize| if b[i] < 0.0 { -1.0 } else { 1.0 };
+    let mut columns: Vec<Vec<f64>> = (0..n)
+        .map(|j| (0..m).map(|i| sign(i) * a[i][j]).collect())
+        .collect();
+    columns.extend((0..m).map(|k| (0..m).map(|i| if i == k { sign(i) } else { 0.0 }).collect()));
+    columns.extend(
+        negated
+            .iter()
+            .map(|&k| (0..m).map(|i| if i == k { 1.0 } else { 0.0 }).collect()),
+    );
+    let artificial = n + m;
+    let mut basis: Vec<usize> = (n..n + m).collect();
+    for (k, &i) in negated.iter().enumerate() {
+        basis[i] = artificial + k;
+    }
+    let identity = (0..m)
+        .map(|i| (0..m).map(|k| if i == k { 1.0 } else { 0.0 }).collect())
+        .collect();
+    let mut lp = Revised {
+        columns,
+        basis,
+        inverse: identity,
+        values: b.iter().map(|v| v.abs()).collect(),
+    };
+
+    if !negated.is_empty() {
+        let mut costs = vec![0.0; artificial];
+        costs.resize(artificial + negated.len(), -1.0);
+        // the first phase is bounded by zero
+        lp.optimize(&costs, |_| true)?;
+        let remaining: f64 = (0..m)
+            .filter(|&i| lp.basis[i] >= artificial)
+            .map(|i| lp.values[i])
+            .sum();
+        if remaining > EPSILON * (1.0 + b.iter().map(|v| v.abs()).sum::<f64>()) {
+            return Err(SimplexError::Infeasible);
+        }
+        // the artificial variables left in the basis are zero, and leave it for any other
+        // variable with a nonzero entry in their row, their rows being redundant otherwise
+        for r in 0..m {
+            if lp.basis[r] < artificial {
+                continue;
+            }
+            lp.values[r] = 0.0;
+            let replacement = (0..artificial)
+                .filter(|j| !lp.basis.contains(j))
+                .map(|j| (j, lp.direction(j)))
+                .find(|(_, u)| u[r].abs() > EPSILON);
+            if let Some((j, u)) = replacement {
+                lp.pivot(r, j, &u);
+            }
+        }
+    }
+
+    let mut costs = c.to_vec();
+    costs.resize(lp.columns.len(), 0.0);
+    lp.optimize(&costs, |j| j < artificial)?;
+    let mut x = vec![0.0; n];
+    for (&j, &value) in lp.basis.iter().zip(&lp.values) {
+        if j < n {
+            x[j] = value;
+        }
+    }
+    let value = c.iter().zip(&x).map(|(c, x)| c * x).sum();
+    Ok((value, x))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn assert_close(found: f64, expected: f64) {
+        assert!((found - expected).abs() < 1e-7, "{found} is not {expected}");
+    }
+
+    fn assert_feasible(a: &[Vec<f64>], b: &[f64], x: &[f64]) {
+        assert!(x.iter().all(|&v| v >= -1e-9), "{x:?}");
+        for (row, bound) in a.iter().zip(b) {
+            let lhs: f64 = row.iter().zip(x).map(|(a, x)| a * x).sum();
+            assert!(lhs <= bound + 1e-7, "{x:?}");
+        }
+    }
+
+    #[test]
+    fn textbook_problem() {
+        // the Wyndor Glass Co. problem of Hillier and Lieberman
+        let a = vec![vec![1.0, 0.0], vec![0.0, 2.0], vec![3.0, 2.0]];
+        let b = [4.0, 12.0, 18.0];
+        let (value, x) = simplex(&[3.0, 5.0], &a, &b).unwrap();
+        assert_close(value, 36.0);
+        assert_close(x[0], 2.0);
+        assert_close(x[1], 6.0);
+        // nothing to gain
+        let (value, x) = simplex(&[-1.0, -1.0], &a, &b).unwrap();
+        assert_eq!((value, x), (0.0, vec![0.0, 0.0]));
+    }
+
+    #[test]
+    fn negative_bounds() {
+        // x + y >= 2 and x <= 3, for the largest 2y - x with y <= 4
+        let a = vec![vec![-1.0, -1.0], vec![1.0, 0.0], vec![0.0, 1.0]];
+        let b = [-2.0, 3.0, 4.0];
+        let (value, x) = simplex(&[-1.0, 2.0], &a, &b).unwrap();
+        assert_close(value, 8.0);
+        assert_feasible(&a, &b, &x);
+        // an equality as two inequalities: x + 2y = 4, for the largest x
+        let a = vec![vec![1.0, 2.0], vec![-1.0, -2.0]];
+        let (value, x) = simplex(&[1.0, 0.0], &a, &[4.0, -4.0]).unwrap();
+        assert_close(value, 4.0);
+        assert_close(x[1], 0.0);
+    }
+
+    #[test]
+    fn infeasible_and_unbounded() {
+        // x <= -1 with x >= 0
+        assert_eq!(
+            simplex(&[1.0], &[vec![1.0]], &[-1.0]),
+            Err(SimplexError::Infeasible)
+        );
+        // x + y <= 2 and x + y >= 5
+        let a = vec![vec![1.0, 1.0], vec![-1.0, -1.0]];
+        assert_eq!(
+            simplex(&[1.0, 0.0], &a, &[2.0, -5.0]),
+            Err(SimplexError::Infeasible)
+        );
+        // x - y <= 1 leaves x + y unbounded
+        assert_eq!(
+            simplex(&[1.0, 1.0], &[vec![1.0, -1.0]], &[1.0]),
+            Err(SimplexError::Unbounded)
+        );
+        // and so does no constraint at all, unless the objective is zero
+        assert_eq!(simplex(&[0.0, 1.0], &[], &[]), Err(SimplexError::Unbounded));
+        assert_eq!(simplex(&[0.0, 0.0], &[], &[]).unwrap().0, 0.0);
+    }
+
+    #[test]
+    fn degenerate_cycling() {
+        // Beale's example, on which the largest reduced cost rule cycles forever
+        let a = vec![
+            vec![0.25, -60.0, -0.04, 9.0],
+            vec![0.5, -90.0, -0.02, 3.0],
+            vec![0.0, 0.0, 1.0, 0.0],
+        ];
+        let (value, x) = simplex(&[0.75, -150.0, 0.02, -6.0], &a, &[0.0, 0.0, 1.0]).unwrap();
+        assert_close(value, 0.05);
+        for (found, expected) in x.iter().zip([0.04, 0.0, 1.0, 0.0]) {
+            assert_close(*found, expected);
+        }
+    }
+
+    #[test]
+    fn strong_duality() {
+        // the dual of max c.x with A x <= b and x >= 0 is min b.y with A^T y >= c and y >= 0,
+        // with the same optimal value
+        let mut rng = ChaCha8Rng::seed_from_u64(169);
+        let mut solved = 0;
+        for _ in 0..200 {
+            let (n, m) = (rng.gen_range(1..6), rng.gen_range(1..6));
+            let a: Vec<Vec<f64>> = (0..m)
+                .map(|_| (0..n).map(|_| rng.gen_range(-5..10) as f64).collect())
+                .collect();
+            let b: Vec<f64> = (0..m).map(|_| rng.gen_range(-3..20) as f64).collect();
+            let c: Vec<f64> = (0..n).map(|_| rng.gen_range(-5..10) as f64).collect();
+            let transposed: Vec<Vec<f64>> =
+                (0..n).map(|j| (0..m).map(|i| -a[i][j]).collect()).collect();
+            let negated_b: Vec<f64> = b.iter().map(|v| -v).collect();
+            let negated_c: Vec<f64> = c.iter().map(|v| -v).collect();
+            let primal = simplex(&c, &a, &b);
+            let dual = simplex(&negated_b, &transposed, &negated_c);
+            match primal {
+                Ok((value, x)) => {
+                    assert_feasible(&a, &b, &x);
+                    assert_close(value, -dual.unwrap().0);
+                    solved += 1;
+                }
+                // an infeasible dual leaves the primal unbounded or infeasible
+                Err(SimplexError::Unbounded) => {
+                    assert_eq!(dual, Err(SimplexError::Infeasible))
+                }
+                Err(SimplexError::Infeasible) => assert!(dual.is_err()),
+            }
+        }
+        assert!(solved > 50, "{solved}");
+    }
+
+    #[test]
+    #[should_panic(expected = "the row 1 must have the 2 coefficients of the objective")]
+    fn ragged_constraints() {
+        let _ = simplex(&[1.0, 1.0], &[vec![1.0, 0.0], vec![1.0]], &[1.0, 1.0]);
+    }
+}
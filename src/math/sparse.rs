@@ -0,0 +1,338 @@
+//! Sparse vectors and matrices, which only store their nonzero entries
+//! <https://en.wikipedia.org/wiki/Sparse_matrix>
+//!
+//! A vector with k nonzero entries out of n takes O(k) space, and a dot product or a sum with
+//! it O(k) time, instead of O(n). The matrices are in compressed sparse row form, which makes
+//! the product with a dense vector O(rows + nonzero entries).
+
+/// A vector of nonzero entries sorted by index
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SparseVec {
+    entries: Vec<(usize, f64)>,
+}
+
+impl SparseVec {
+    /// Builds a vector from `(index, value)` pairs in any order, adding up the values given at
+    /// the same index and dropping the zeros.
+    pub fn new(mut entries: Vec<(usize, f64)>) -> Self {
+        entries.sort_by_key(|&(index, _)| index);
+        let mut merged: Vec<(usize, f64)> = Vec::with_capacity(entries.len());
+        for (index, value) in entries {
+            match merged.last_mut() {
+                Some(last) if last.0 == index => last.1 += value,
+                _ => merged.push((index, value)),
+            }
+        }
+        merged.retain(|&(_, value)| value != 0.0);
+        SparseVec { entries: merged }
+    }
+
+    /// Keeps the nonzero entries of a dense vector.
+    pub fn from_dense(dense: &[f64]) -> Self {
+        let entries = dense
+            .iter()
+            .enumerate()
+            .filter(|&(_, &value)| value != 0.0)
+            .map(|(index, &value)| (index, value))
+            .collect();
+        SparseVec { entries }
+    }
+
+    /// Returns the nonzero entries, sorted by index.
+    pub fn entries(&self) -> &[(usize, f64)] {
+        &self.entries
+    }
+
+    /// Returns the number of nonzero entries.
+    pub fn nnz(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns the dot product with a dense vector, in O(nonzero entries).
+    ///
+    /// # Panics
+    ///
+    /// Panics if an entry is beyond the end of `dense`.
+    pub fn dot(&self, dense: &[f64]) -> f64 {
+        self.entries
+            .iter()
+            .map(|&(index, value)| value * dense[index])
+            .sum()
+    }
+
+    /// Returns the sum of two vectors, by merging their entries in O(nonzero entries). The
+    /// entries cancelling each other are dropped.
+    pub fn add(&self, other: &SparseVec) -> SparseVec {
+        let (a, b) = (&self.entries, &other.entries);
+        let mut entries = Vec::with_capacity(a.len() + b.len());
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() || j < b.len() {
+            if j == b.len() || (i < a.len() && a[i].0 < b[j].0) {
+                entries.push(a[i]);
+                i += 1;
+            } else if i == a.len() || b[j].0 < a[i].0 {
+                entries.push(b[j]);
+                j += 1;
+            } else {
+                let sum = a[i].1 + b[j].1;
+                if sum != 0.0 {
+                    entries.push((a[i].0, sum));
+                }
+                i += 1;
+                j += 1;
+            }
+        }
+        SparseVec { entries }
+    }
+
+    /// Returns the vector with all its `len` entries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an entry is beyond `len`.
+    pub fn to_dense(&self, len: usize) -> Vec<f64> {
+        let mut dense = vec![0.0; len];
+        for &(index, value) in &self.entries {
+            assert!(index < len, "the entry {index} is beyond the {len} entries");
+            dense[index] = value;
+        }
+        dense
+    }
+}
+
+/// A matrix in compressed sparse row form
+/// <https://en.wikipedia.org/wiki/Sparse_matrix#Compressed_sparse_row_(CSR,_CRS_or_Yale_format)>
+///
+/// The nonzero entries lie row after row in two arrays of their columns and their values, and
+/// `row_starts[i]..row_starts[i + 1]` is the range of those of the row `i`, sorted by column.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SparseMatrix {
+    cols: usize,
+    row_starts: Vec<usize>,
+    columns: Vec<usize>,
+    values: Vec<f64>,
+}
+
+impl SparseMatrix {
+    /// Builds a `rows` by `cols` matrix from `(row, column, value)` triplets in any order,
+    /// adding up the values given at the same place and dropping the zeros.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a triplet is out of the matrix.
+    pub fn from_triplets(rows: usize, cols: usize, triplets: &[(usize, usize, f64)]) -> Self {
+        let mut by_row = vec![vec![]; rows];
+        for &(row, column, value) in triplets {
+            assert!(
+                row < rows && column < cols,
+                "the entry ({row}, {column}) is out of the {rows} by {cols} matrix"
+            );
+            by_row[row].push((column, value));
+        }
+        let mut matrix = SparseMatrix {
+            cols,
+            row_starts: vec![0],
+            columns: vec![],
+            values: vec![],
+        };
+        for entries in by_row {
+            for (column, value) in SparseVec::new(entries).entries {
+                matrix.columns.push(column);
+                matrix.values.push(value);
+            }
+            matrix.row_starts.push(matrix.columns.len());
+        }
+        matrix
+    }
+
+    /// Keeps the nonzero entries of a dense matrix, given as its rows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rows do not all have the same length.
+    pub fn from_dense(dense: &[Vec<f64>]) -> Self {
+        let cols = dense.first().map_or(0, Vec::len);
+        let mut triplets = vec![];
+        for (row, entries) in dense.iter().enumerate() {
+            assert_eq!(entries.len(), cols, "the row {row} has a different length");
+            for (column, &value) in entries.iter().enumerate() {
+                triplets.push((row, column, value));
+            }
+        }
+        Self::from_triplets(dense.len(), cols, &triplets)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.row_starts.len() - 1
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns the number of nonzero entries.
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns the product `A x` with a dense vector, in O(rows + nonzero entries).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` does not have as many entries as the matrix has columns.
+    pub fn multiply_vec(&self, x: &[f64]) -> Vec<f64> {
+        assert_eq!(
+            x.len(),
+            self.cols,
+            "the vector must have as many entries as the matrix has columns"
+        );
+        self.row_starts
+            .windows(2)
+            .map(|range| {
+                let (start, end) = (range[0], range[1]);
+                self.columns[start..end]
+                    .iter()
+                    .zip(&self.values[start..end])
+                    .map(|(&column, &value)| value * x[column])
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// Returns the matrix with all its entries, as its rows.
+    pub fn to_dense(&self) -> Vec<Vec<f64>> {
+        let mut dense = vec![vec![0.0; self.cols]; self.rows()];
+        for (row, range) in self.row_starts.windows(2).enumerate() {
+            for k in range[0]..range[1] {
+                dense[row][self.columns[k]] = self.values[k];
+            }
+        }
+        dense
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    /// A dense matrix with about 10% of nonzero entries
+    fn random_sparse_matrix(rng: &mut ChaCha8Rng, rows: usize, cols: usize) -> Vec<Vec<f64>> {
+        (0..rows)
+            .map(|_| {
+                (0..cols)
+                    .map(|_| {
+                        if rng.gen_bool(0.1) {
+                            rng.gen_range(-10.0..10.0)
+                        } else {
+                            0.0
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn vectors() {
+        let v = SparseVec::new(vec![(7, 2.0), (1, -1.0), (4, 0.0), (7, 1.0), (3, 5.0)]);
+        assert_eq!(v.entries(), [(1, -1.0), (3, 5.0), (7, 3.0)]);
+        assert_eq!(v.nnz(), 3);
+        assert_eq!(v.to_dense(8), [0.0, -1.0, 0.0, 5.0, 0.0, 0.0, 0.0, 3.0]);
+        assert_eq!(SparseVec::from_dense(&v.to_dense(10)), v);
+        let dense = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+        assert_eq!(v.dot(&dense), -2.0 + 20.0 + 24.0);
+        assert_eq!(SparseVec::default().dot(&[]), 0.0);
+    }
+
+    #[test]
+    fn additions() {
+        let a = SparseVec::new(vec![(0, 1.0), (4, 2.0), (9, -3.0)]);
+        // no common index
+        let b = SparseVec::new(vec![(2, 5.0), (10, 1.0)]);
+        assert_eq!(
+            a.add(&b).entries(),
+            [(0, 1.0), (2, 5.0), (4, 2.0), (9, -3.0), (10, 1.0)]
+        );
+        // common indices, one of them cancelling out
+        let c = SparseVec::new(vec![(0, 0.5), (9, 3.0), (11, 1.0)]);
+        assert_eq!(a.add(&c).entries(), [(0, 1.5), (4, 2.0), (11, 1.0)]);
+        assert_eq!(c.add(&a), a.add(&c));
+        assert_eq!(a.add(&SparseVec::default()), a);
+        let mut rng = ChaCha8Rng::seed_from_u64(162);
+        for _ in 0..100 {
+            let x: Vec<f64> = (0..50)
+                .map(|_| rng.gen_bool(0.2) as u8 as f64 * rng.gen_range(-3..=3) as f64)
+                .collect();
+            let y: Vec<f64> = (0..50)
+                .map(|_| rng.gen_bool(0.2) as u8 as f64 * rng.gen_range(-3..=3) as f64)
+                .collect();
+            let sum: Vec<f64> = x.iter().zip(&y).map(|(a, b)| a + b).collect();
+            let sparse = SparseVec::from_dense(&x).add(&SparseVec::from_dense(&y));
+            assert_eq!(sparse, SparseVec::from_dense(&sum));
+        }
+    }
+
+    #[test]
+    fn products_agree_with_dense_ones() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1620);
+        let dense = random_sparse_matrix(&mut rng, 200, 300);
+        let matrix = SparseMatrix::from_dense(&dense);
+        assert_eq!((matrix.rows(), matrix.cols()), (200, 300));
+        let nonzero = dense.iter().flatten().filter(|&&v| v != 0.0).count();
+        assert_eq!(matrix.nnz(), nonzero);
+        assert!((5000..7000).contains(&nonzero));
+        assert_eq!(matrix.to_dense(), dense);
+        for _ in 0..10 {
+            let x: Vec<f64> = (0..300).map(|_| rng.gen_range(-1.0..1.0)).collect();
+            let product = matrix.multiply_vec(&x);
+            for (row, &found) in dense.iter().zip(&product) {
+                let expected: f64 = row.iter().zip(&x).map(|(a, b)| a * b).sum();
+                assert!((found - expected).abs() < 1e-9);
+                // the product of a row with x is a dot product
+                let sparse_row = SparseVec::from_dense(row);
+                assert!((sparse_row.dot(&x) - expected).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn matrices_from_triplets() {
+        let matrix = SparseMatrix::from_triplets(
+            3,
+            4,
+            &[
+                (2, 1, 4.0),
+                (0, 3, 1.0),
+                (0, 0, 2.0),
+                (2, 1, -1.0),
+                (1, 2, 0.0),
+            ],
+        );
+        assert_eq!(matrix.nnz(), 3);
+        assert_eq!(
+            matrix.to_dense(),
+            [
+                [2.0, 0.0, 0.0, 1.0],
+                [0.0, 0.0, 0.0, 0.0],
+                [0.0, 3.0, 0.0, 0.0]
+            ]
+        );
+        assert_eq!(matrix.multiply_vec(&[1.0, 2.0, 3.0, 4.0]), [6.0, 0.0, 6.0]);
+        let empty = SparseMatrix::from_triplets(0, 5, &[]);
+        assert_eq!(empty.multiply_vec(&[0.0; 5]), Vec::<f64>::new());
+    }
+
+    #[test]
+    #[should_panic(expected = "the entry (3, 0) is out of the 3 by 4 matrix")]
+    fn triplet_out_of_the_matrix() {
+        SparseMatrix::from_triplets(3, 4, &[(3, 0, 1.0)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "the vector must have as many entries as the matrix has columns")]
+    fn product_with_a_vector_too_short() {
+        SparseMatrix::from_triplets(3, 4, &[]).multiply_vec(&[1.0; 3]);
+    }
+}
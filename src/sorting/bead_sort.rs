@@ -1,6 +1,10 @@
 //Bead sort only works for sequences of non-negative integers.
 //https://en.wikipedia.org/wiki/Bead_sort
 pub fn bead_sort(a: &mut [usize]) {
+    if a.is_empty() {
+        return;
+    }
+
     // Find the maximum element
     let mut max = a[0];
     (1..a.len()).for_each(|i| {
@@ -19,6 +23,9 @@ pub fn bead_sort(a: &mut [usize]) {
         }
     }
 
+    // rows left without beads once they fall hold zeros
+    a.fill(0);
+
     // move down the beads
     for j in 0..max {
         let mut sum = 0;
@@ -56,4 +63,18 @@ mod tests {
         bead_sort(&mut ve2);
         assert!(is_sorted(&ve2) && have_same_elements(&ve2, &cloned));
     }
+
+    #[test]
+    fn with_zeros() {
+        let mut ve3: [usize; 6] = [3, 0, 2, 0, 1, 0];
+        bead_sort(&mut ve3);
+        assert_eq!(ve3, [0, 0, 0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn empty() {
+        let mut ve4: [usize; 0] = [];
+        bead_sort(&mut ve4);
+        assert_eq!(ve4, []);
+    }
 }
@@ -15,38 +15,119 @@ fn _bitonic_merge<T: Ord>(array: &mut [T], low: usize, length: usize, ascending:
     }
 }
 
-pub fn bitonic_sort<T: Ord>(array: &mut [T], low: usize, length: usize, ascending: bool) {
+fn _bitonic_sort<T: Ord>(array: &mut [T], low: usize, length: usize, ascending: bool) {
     if length > 1 {
         let middle = length / 2;
-        bitonic_sort(array, low, middle, true);
-        bitonic_sort(array, low + middle, middle, false);
+        // sort both halves in opposite directions to get a bitonic sequence, then merge it
+        _bitonic_sort(array, low, middle, true);
+        _bitonic_sort(array, low + middle, middle, false);
         _bitonic_merge(array, low, length, ascending);
     }
 }
 
-//Note that this program works only when size of input is a power of 2.
+/// Sorts the slice in ascending order.
+///
+/// Bitonic sort is a sorting network: the sequence of comparisons does not depend on the data,
+/// which makes it well suited for parallel hardware. It performs O(n log^2 n) comparisons.
+///
+/// Panics if the length of the slice is not a power of two (an empty slice is accepted).
+pub fn bitonic_sort<T: Ord>(array: &mut [T]) {
+    let length = array.len();
+    assert!(
+        length == 0 || length.is_power_of_two(),
+        "bitonic sort requires a power of two length, got {length}"
+    );
+    _bitonic_sort(array, 0, length, true);
+}
+
+/// Sorts a slice of any length, by padding it with `T::default()` up to the next power of two,
+/// sorting with `bitonic_sort` and then removing the padding.
+pub fn bitonic_sort_padded<T: Ord + Default + Clone>(array: &mut [T]) {
+    let length = array.len();
+    let padding = length.next_power_of_two() - length;
+    if padding == 0 {
+        bitonic_sort(array);
+        return;
+    }
+
+    let mut padded = array.to_vec();
+    padded.resize(length + padding, T::default());
+    bitonic_sort(&mut padded);
+
+    // the padding values are all equal to the default, so removing any `padding` of the
+    // elements equal to the default keeps the original elements, still in sorted order
+    let default = T::default();
+    let mut to_remove = padding;
+    padded.retain(|x| {
+        if to_remove > 0 && *x == default {
+            to_remove -= 1;
+            false
+        } else {
+            true
+        }
+    });
+    array.clone_from_slice(&padded);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::sorting::have_same_elements;
     use crate::sorting::is_descending_sorted;
     use crate::sorting::is_sorted;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
 
     #[test]
     fn descending() {
         //descending
         let mut ve1 = vec![6, 5, 4, 3];
         let cloned = ve1.clone();
-        bitonic_sort(&mut ve1, 0, 4, true);
+        bitonic_sort(&mut ve1);
         assert!(is_sorted(&ve1) && have_same_elements(&ve1, &cloned));
     }
 
     #[test]
     fn ascending() {
-        //pre-sorted
+        //pre-sorted, sorted in descending order by the underlying network
         let mut ve2 = vec![1, 2, 3, 4];
         let cloned = ve2.clone();
-        bitonic_sort(&mut ve2, 0, 4, false);
+        _bitonic_sort(&mut ve2, 0, 4, false);
         assert!(is_descending_sorted(&ve2) && have_same_elements(&ve2, &cloned));
     }
+
+    #[test]
+    fn random_power_of_two_lengths() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for length in [0, 1, 2, 4, 16, 1024] {
+            let mut arr: Vec<i32> = (0..length).map(|_| rng.gen_range(-100..100)).collect();
+            let mut expected = arr.clone();
+            expected.sort();
+            bitonic_sort(&mut arr);
+            assert_eq!(arr, expected);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "power of two")]
+    fn not_a_power_of_two() {
+        bitonic_sort(&mut [3, 2, 1]);
+    }
+
+    #[test]
+    fn padded() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        for length in [0, 1, 3, 5, 17, 1000] {
+            // negative values make sure the padding does not simply end up at the front
+            let mut arr: Vec<i32> = (0..length).map(|_| rng.gen_range(-100..100)).collect();
+            let mut expected = arr.clone();
+            expected.sort();
+            bitonic_sort_padded(&mut arr);
+            assert_eq!(arr, expected);
+        }
+
+        let mut words = vec!["pear", "", "apple", "fig", ""];
+        bitonic_sort_padded(&mut words);
+        assert_eq!(words, vec!["", "", "apple", "fig", "pear"]);
+    }
 }
@@ -0,0 +1,231 @@
+use std::cmp::Ordering;
+
+/// Sorts the indices `ids` of `arr` by merge insertion, with `partner` as room for the smaller
+/// item paired with every larger one
+fn merge_insertion<T: Ord>(arr: &[T], ids: Vec<usize>, partner: &mut [usize]) -> Vec<usize> {
+    if ids.len() < 2 {
+        return ids;
+    }
+    // the larger item of every pair, remembering the smaller one
+    let pairs: Vec<(usize, usize)> = ids
+        .chunks_exact(2)
+        .map(|pair| {
+            if arr[pair[1]].cmp(&arr[pair[0]]) == Ordering::Less {
+                (pair[1], pair[0])
+            } else {
+                (pair[0], pair[1])
+            }
+        })
+        .collect();
+    let leftover = (ids.len() % 2 == 1).then(|| ids[ids.len() - 1]);
+    let larger = merge_insertion(
+        arr,
+        pairs.iter().map(|&(_, large)| large).collect(),
+        partner,
+    );
+    // the recursion pairs the larger items again, so their partners are only set now
+    for &(small, large) in &pairs {
+        partner[large] = small;
+    }
+
+    // the partner of the least larger item comes first, with nothing to compare
+    let mut chain = Vec::with_capacity(ids.len());
+    chain.push(partner[larger[0]]);
+    chain.extend_from_slice(&larger);
+    // the pending items, each one below its partner in the chain, but the leftover one
+    let pending: Vec<(usize, Option<usize>)> = larger[1..]
+        .iter()
+        .map(|&large| (partner[large], Some(large)))
+        .chain(leftover.map(|item| (item, None)))
+        .collect();
+
+    // the groups end at the Jacobsthal numbers, 3, 5, 11, 21, 43..., counting the first item
+    // inserted with its partner as 1, and every group is inserted from its end
+    let (mut done, mut group_end, mut previous_end) = (0, 3, 1);
+    while done < pending.len() {
+        let end = (group_end - 1).min(pending.len());
+        for &(item, large) in pending[done..end].iter().rev() {
+            let bound = match large {
+                Some(large) => chain.iter().position(|&id| id == large).unwrap(),
+                None => chain.len(),
+            };
+            // a plain binary search, for at most ceil(log2(bound + 1)) comparisons
+            let (mut low, mut high) = (0, bound);
+            while low < high {
+                let middle = (low + high) / 2;
+                if arr[chain[middle]].cmp(&arr[item]) == Ordering::Less {
+                    low = middle + 1;
+                } else {
+                    high = middle;
+                }
+            }
+            chain.insert(low, item);
+        }
+        done = end;
+        (group_end, previous_end) = (group_end + 2 * previous_end, group_end);
+    }
+    chain
+}
+
+/// Sorts `arr` with the Ford-Johnson merge insertion, which makes fewer comparisons in the
+/// worst case than any other known algorithm for small inputs.
+/// <https://en.wikipedia.org/wiki/Merge-insertion_sort>
+///
+/// The items are compared in pairs, and the larger items of the pairs are sorted recursively
+/// into a chain. Every smaller item is then inserted into the chain by binary search, only
+/// among the items before its larger partner. The order of the insertions makes every binary
+/// search run among 2^k - 1 items at most: the smaller items are taken in groups ending at the
+/// Jacobsthal numbers, from the last one of every group down to the first one, so that the
+/// whole group searches among the same number of items. At most
+/// `sum(ceil(log2(3k / 4)), k = 1..=n)` comparisons are made, which matches the
+/// information-theoretic lower bound `ceil(log2(n!))` up to 11 items. Moving the items takes
+/// O(n^2) time, though, so that the sort only pays off when comparisons are costly.
+pub fn ford_johnson_sort<T: Ord + Clone>(arr: &mut [T]) {
+    let mut partner = vec![0; arr.len()];
+    let order = merge_insertion(arr, (0..arr.len()).collect(), &mut partner);
+    let sorted: Vec<T> = order.into_iter().map(|id| arr[id].clone()).collect();
+    arr.clone_from_slice(&sorted);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sorting::is_sorted;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use std::cell::Cell;
+
+    /// An item counting the comparisons made between items of its kind
+    #[derive(Clone)]
+    struct Counted<'a> {
+        value: i32,
+        comparisons: &'a Cell<usize>,
+    }
+
+    impl PartialEq for Counted<'_> {
+        fn eq(&self, other: &Self) -> bool {
+            self.cmp(other) == Ordering::Equal
+        }
+    }
+
+    impl Eq for Counted<'_> {}
+
+    impl PartialOrd for Counted<'_> {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Counted<'_> {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.comparisons.set(self.comparisons.get() + 1);
+            self.value.cmp(&other.value)
+        }
+    }
+
+    /// Sorts the values and returns the number of comparisons made
+    fn comparisons(values: &[i32]) -> usize {
+        let comparisons = Cell::new(0);
+        let mut arr: Vec<Counted> = values
+            .iter()
+            .map(|&value| Counted {
+                value,
+                comparisons: &comparisons,
+            })
+            .collect();
+        ford_johnson_sort(&mut arr);
+        let mut expected = values.to_vec();
+        expected.sort_unstable();
+        let sorted: Vec<i32> = arr.iter().map(|item| item.value).collect();
+        assert_eq!(sorted, expected);
+        comparisons.get()
+    }
+
+    /// The least c with 2^c >= x
+    fn ceil_log2(x: u64) -> u32 {
+        x.next_power_of_two().trailing_zeros()
+    }
+
+    /// The worst case of merge insertion, the sum of ceil(log2(3k / 4))
+    fn ford_johnson_bound(n: u64) -> usize {
+        (1..=n)
+            .map(|k| ceil_log2((3 * k).div_ceil(4)) as usize)
+            .sum()
+    }
+
+    /// The worst case of binary insertion sort, n ceil(log2(n)) - 2^ceil(log2(n)) + 1
+    fn binary_insertion_bound(n: u64) -> usize {
+        let c = ceil_log2(n);
+        (n * c as u64 + 1 - (1 << c)) as usize
+    }
+
+    fn permutations(prefix: &mut Vec<i32>, rest: &mut Vec<i32>, out: &mut Vec<Vec<i32>>) {
+        if rest.is_empty() {
+            out.push(prefix.clone());
+        }
+        for i in 0..rest.len() {
+            let item = rest.remove(i);
+            prefix.push(item);
+            permutations(prefix, rest, out);
+            prefix.pop();
+            rest.insert(i, item);
+        }
+    }
+
+    #[test]
+    fn small_arrays() {
+        let mut arr = [5, 2, 9, 1, 5, 6, 0, 3];
+        ford_johnson_sort(&mut arr);
+        assert_eq!(arr, [0, 1, 2, 3, 5, 5, 6, 9]);
+        let mut words = vec!["pear", "fig", "apple", "kiwi", "date"];
+        ford_johnson_sort(&mut words);
+        assert_eq!(words, ["apple", "date", "fig", "kiwi", "pear"]);
+        let mut empty: [u8; 0] = [];
+        ford_johnson_sort(&mut empty);
+        let mut single = [1];
+        ford_johnson_sort(&mut single);
+        assert_eq!(single, [1]);
+    }
+
+    #[test]
+    fn worst_case_of_all_permutations() {
+        // the bounds of merge insertion for 1 to 12 items, from Knuth, TAOCP 5.3.1
+        let bounds = [0, 1, 3, 5, 7, 10, 13, 16, 19, 22, 26, 30];
+        for n in 1..=8 {
+            let mut all = vec![];
+            permutations(&mut vec![], &mut (0..n as i32).collect(), &mut all);
+            let worst = all.iter().map(|values| comparisons(values)).max().unwrap();
+            assert_eq!(worst, bounds[n - 1], "{n} items");
+        }
+        for n in 1..=12 {
+            assert_eq!(ford_johnson_bound(n as u64), bounds[n - 1]);
+            assert!(bounds[n - 1] <= binary_insertion_bound(n as u64));
+            // all the way to 11 items, no sort does better in the worst case
+            let factorial: u64 = (1..=n as u64).product();
+            if n <= 11 {
+                assert_eq!(bounds[n - 1], ceil_log2(factorial) as usize);
+            }
+        }
+        // but 12 items need 30 comparisons, one more than ceil(log2(12!)) = 29
+        assert_eq!(ceil_log2((1..=12).product()), 29);
+    }
+
+    #[test]
+    fn random_arrays_within_the_bound() {
+        let mut rng = ChaCha8Rng::seed_from_u64(159);
+        for _ in 0..200 {
+            let n = rng.gen_range(0..200);
+            let mut values: Vec<i32> = (0..n).map(|_| rng.gen_range(-50..50)).collect();
+            assert!(comparisons(&values) <= ford_johnson_bound(n as u64));
+            values.shuffle(&mut rng);
+            values.sort_unstable();
+            assert!(comparisons(&values) <= ford_johnson_bound(n as u64));
+        }
+        let mut large: Vec<i32> = (0..5000).map(|_| rng.gen()).collect();
+        let cloned = large.clone();
+        ford_johnson_sort(&mut large);
+        assert!(is_sorted(&large));
+        assert!(comparisons(&cloned) < binary_insertion_bound(5000));
+    }
+}
@@ -2,7 +2,6 @@ mod bead_sort;
 mod binary_insertion_sort;
 mod bingo_sort;
 mod bitonic_sort;
-mod bogo_sort;
 mod bubble_sort;
 mod bucket_sort;
 mod cocktail_shaker_sort;
@@ -11,11 +10,13 @@ mod counting_sort;
 mod cycle_sort;
 mod dutch_national_flag_sort;
 mod exchange_sort;
+mod ford_johnson;
 mod gnome_sort;
 mod heap_sort;
 mod insertion_sort;
 mod intro_sort;
 mod merge_sort;
+mod novelty_sorts;
 mod odd_even_sort;
 mod pancake_sort;
 mod patience_sort;
@@ -25,7 +26,6 @@ mod quick_sort_3_ways;
 mod radix_sort;
 mod selection_sort;
 mod shell_sort;
-mod sleep_sort;
 #[cfg(test)]
 mod sort_utils;
 mod stooge_sort;
@@ -37,8 +37,7 @@ mod wiggle_sort;
 pub use self::bead_sort::bead_sort;
 pub use self::binary_insertion_sort::binary_insertion_sort;
 pub use self::bingo_sort::bingo_sort;
-pub use self::bitonic_sort::bitonic_sort;
-pub use self::bogo_sort::bogo_sort;
+pub use self::bitonic_sort::{bitonic_sort, bitonic_sort_padded};
 pub use self::bubble_sort::bubble_sort;
 pub use self::bucket_sort::bucket_sort;
 pub use self::cocktail_shaker_sort::cocktail_shaker_sort;
@@ -48,22 +47,23 @@ pub use self::counting_sort::generic_counting_sort;
 pub use self::cycle_sort::cycle_sort;
 pub use self::dutch_national_flag_sort::dutch_national_flag_sort;
 pub use self::exchange_sort::exchange_sort;
+pub use self::ford_johnson::ford_johnson_sort;
 pub use self::gnome_sort::gnome_sort;
 pub use self::heap_sort::heap_sort;
 pub use self::insertion_sort::insertion_sort;
 pub use self::intro_sort::intro_sort;
 pub use self::merge_sort::bottom_up_merge_sort;
 pub use self::merge_sort::top_down_merge_sort;
+pub use self::novelty_sorts::{bogo_sort, miracle_sort, sleep_sort, stalin_sort};
 pub use self::odd_even_sort::odd_even_sort;
-pub use self::pancake_sort::pancake_sort;
+pub use self::pancake_sort::{flip, pancake_sort};
 pub use self::patience_sort::patience_sort;
 pub use self::pigeonhole_sort::pigeonhole_sort;
 pub use self::quick_sort::{partition, quick_sort};
-pub use self::quick_sort_3_ways::quick_sort_3_ways;
+pub use self::quick_sort_3_ways::{quick_sort_3_ways, three_way_partition, three_way_quicksort};
 pub use self::radix_sort::radix_sort;
 pub use self::selection_sort::selection_sort;
 pub use self::shell_sort::shell_sort;
-pub use self::sleep_sort::sleep_sort;
 pub use self::stooge_sort::stooge_sort;
 pub use self::tim_sort::tim_sort;
 pub use self::tree_sort::tree_sort;
@@ -127,3 +127,247 @@ mod tests {
         assert!(!is_sorted(&[2, 3, 1, -1, 5]));
     }
 }
+
+// Runs every general purpose sort of the module through the same checks.
+// Left out are the sorts the checks do not apply to: bogo and sleep sorts are too slow,
+// stalin and miracle sorts do not produce a sorted permutation on purpose, wave and wiggle
+// sorts produce a different order, the Dutch national flag sort only handles three values,
+// and bitonic sort is covered through its padded variant.
+#[cfg(test)]
+mod sort_tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    const RANDOM_INPUTS: usize = 100;
+
+    /// Checks that `sort_fn` sorts its input into a permutation of it, on empty and single
+    /// element inputs, sorted, reversed and constant inputs, and on random inputs generated
+    /// from a fixed seed.
+    fn verify_sort<F: Fn(&mut Vec<i32>)>(sort_fn: F) {
+        let mut rng = ChaCha8Rng::seed_from_u64(104);
+        let mut inputs: Vec<Vec<i32>> = vec![
+            vec![],
+            vec![42],
+            vec![0],
+            (0..50).collect(),
+            (0..50).rev().collect(),
+            vec![7; 20],
+            vec![3, 1, 3, 1, 3, 1],
+        ];
+        for _ in 0..RANDOM_INPUTS {
+            let len = rng.gen_range(0..100);
+            inputs.push((0..len).map(|_| rng.gen_range(-1000..=1000)).collect());
+        }
+
+        for input in inputs {
+            let mut output = input.clone();
+            sort_fn(&mut output);
+            assert!(is_sorted(&output), "{input:?} was sorted into {output:?}");
+
+            let mut expected = input.clone();
+            expected.sort_unstable();
+            let mut actual = output.clone();
+            actual.sort_unstable();
+            assert_eq!(
+                actual, expected,
+                "{output:?} is not a permutation of {input:?}"
+            );
+        }
+    }
+
+    /// Offset moving the harness values, which are at least -1000, into non-negative values
+    const OFFSET: i32 = 1000;
+
+    /// Adapts a sort over non-negative integers to the harness by shifting the values.
+    fn shifted<T, S>(arr: &mut Vec<i32>, sort: S)
+    where
+        T: TryFrom<i32> + TryInto<i32>,
+        <T as TryFrom<i32>>::Error: std::fmt::Debug,
+        <T as TryInto<i32>>::Error: std::fmt::Debug,
+        S: FnOnce(&mut Vec<T>),
+    {
+        let mut values: Vec<T> = arr
+            .iter()
+            .map(|&x| T::try_from(x + OFFSET).unwrap())
+            .collect();
+        sort(&mut values);
+        *arr = values
+            .into_iter()
+            .map(|x| x.try_into().unwrap() - OFFSET)
+            .collect();
+    }
+
+    #[test]
+    fn bead_sort() {
+        verify_sort(|arr| shifted(arr, |v: &mut Vec<usize>| super::bead_sort(v)));
+    }
+
+    #[test]
+    fn binary_insertion_sort() {
+        verify_sort(|arr| super::binary_insertion_sort(arr));
+    }
+
+    #[test]
+    fn bingo_sort() {
+        verify_sort(|arr| super::bingo_sort(arr));
+    }
+
+    #[test]
+    fn bitonic_sort_padded() {
+        verify_sort(|arr| super::bitonic_sort_padded(arr));
+    }
+
+    #[test]
+    fn bubble_sort() {
+        verify_sort(|arr| super::bubble_sort(arr));
+    }
+
+    #[test]
+    fn bucket_sort() {
+        verify_sort(|arr| shifted(arr, |v: &mut Vec<usize>| *v = super::bucket_sort(v)));
+    }
+
+    #[test]
+    fn cocktail_shaker_sort() {
+        verify_sort(|arr| super::cocktail_shaker_sort(arr));
+    }
+
+    #[test]
+    fn comb_sort() {
+        verify_sort(|arr| super::comb_sort(arr));
+    }
+
+    #[test]
+    fn counting_sort() {
+        verify_sort(|arr| {
+            shifted(arr, |v: &mut Vec<u32>| {
+                super::counting_sort(v, 2 * OFFSET as usize)
+            })
+        });
+    }
+
+    #[test]
+    fn generic_counting_sort() {
+        verify_sort(|arr| {
+            shifted(arr, |v: &mut Vec<u16>| {
+                super::generic_counting_sort(v, 2 * OFFSET as usize)
+            })
+        });
+    }
+
+    #[test]
+    fn cycle_sort() {
+        verify_sort(|arr| super::cycle_sort(arr));
+    }
+
+    #[test]
+    fn exchange_sort() {
+        verify_sort(|arr| super::exchange_sort(arr));
+    }
+
+    #[test]
+    fn ford_johnson_sort() {
+        verify_sort(|arr| super::ford_johnson_sort(arr));
+    }
+
+    #[test]
+    fn gnome_sort() {
+        verify_sort(|arr| *arr = super::gnome_sort(arr));
+    }
+
+    #[test]
+    fn heap_sort() {
+        verify_sort(|arr| super::heap_sort(arr));
+    }
+
+    #[test]
+    fn insertion_sort() {
+        verify_sort(|arr| super::insertion_sort(arr));
+    }
+
+    #[test]
+    fn intro_sort() {
+        verify_sort(|arr| super::intro_sort(arr));
+    }
+
+    #[test]
+    fn top_down_merge_sort() {
+        verify_sort(|arr| super::top_down_merge_sort(arr));
+    }
+
+    #[test]
+    fn bottom_up_merge_sort() {
+        verify_sort(|arr| super::bottom_up_merge_sort(arr));
+    }
+
+    #[test]
+    fn odd_even_sort() {
+        verify_sort(|arr| super::odd_even_sort(arr));
+    }
+
+    #[test]
+    fn pancake_sort() {
+        verify_sort(|arr| {
+            super::pancake_sort(arr);
+        });
+    }
+
+    #[test]
+    fn patience_sort() {
+        verify_sort(|arr| super::patience_sort(arr));
+    }
+
+    #[test]
+    fn pigeonhole_sort() {
+        verify_sort(|arr| super::pigeonhole_sort(arr));
+    }
+
+    #[test]
+    fn quick_sort() {
+        verify_sort(|arr| super::quick_sort(arr));
+    }
+
+    #[test]
+    fn quick_sort_3_ways() {
+        verify_sort(|arr| super::quick_sort_3_ways(arr));
+    }
+
+    #[test]
+    fn three_way_quicksort() {
+        verify_sort(|arr| super::three_way_quicksort(arr, &mut ChaCha8Rng::seed_from_u64(154)));
+    }
+
+    #[test]
+    fn radix_sort() {
+        verify_sort(|arr| shifted(arr, |v: &mut Vec<u64>| super::radix_sort(v)));
+    }
+
+    #[test]
+    fn selection_sort() {
+        verify_sort(|arr| super::selection_sort(arr));
+    }
+
+    #[test]
+    fn shell_sort() {
+        verify_sort(|arr| super::shell_sort(arr));
+    }
+
+    #[test]
+    fn stooge_sort() {
+        verify_sort(|arr| super::stooge_sort(arr));
+    }
+
+    #[test]
+    fn tim_sort() {
+        verify_sort(|arr| {
+            let n = arr.len();
+            super::tim_sort(arr, n)
+        });
+    }
+
+    #[test]
+    fn tree_sort() {
+        verify_sort(super::tree_sort);
+    }
+}
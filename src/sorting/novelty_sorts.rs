@@ -0,0 +1,138 @@
+//! Sorting algorithms that are better known as jokes than as practical tools.
+//! They are still implemented correctly, so they can be used to contrast with real sorts.
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+fn is_sorted<T: Ord>(arr: &[T]) -> bool {
+    arr.windows(2).all(|w| w[0] <= w[1])
+}
+
+/// Shuffles the slice until it happens to be sorted, and returns the number of shuffles needed.
+/// The expected number of shuffles is O(n!), so only use this on very small inputs.
+pub fn bogo_sort<T: Ord>(arr: &mut [T], rng: &mut impl Rng) -> usize {
+    let mut shuffles = 0;
+    while !is_sorted(arr) {
+        arr.shuffle(rng);
+        shuffles += 1;
+    }
+    shuffles
+}
+
+/// Keeps every element that is not smaller than the last element kept, and drops the others.
+/// The result is sorted in O(n), at the price of losing elements.
+pub fn stalin_sort<T: Ord>(arr: Vec<T>) -> Vec<T> {
+    let mut result: Vec<T> = Vec::with_capacity(arr.len());
+    for x in arr {
+        if result.last().is_none_or(|last| *last <= x) {
+            result.push(x);
+        }
+    }
+    result
+}
+
+/// Checks up to `checks` times whether the slice is sorted, in case a miracle (such as a cosmic
+/// ray flipping the right bits) sorted it in the meantime. The slice is never modified.
+/// Returns true if it was found sorted.
+pub fn miracle_sort<T: Ord>(arr: &[T], checks: usize) -> bool {
+    (0..checks).any(|_| is_sorted(arr))
+}
+
+/// Spawns one thread per element, each sleeping for `20 * element` milliseconds before sending
+/// its value back, and collects the values in the order they arrive.
+///
+/// This is a demo only: the result is only sorted if the scheduler wakes the threads up in
+/// order, which is not guaranteed for values that are close to each other.
+pub fn sleep_sort(arr: &[usize]) -> Vec<usize> {
+    let (tx, rx) = mpsc::channel();
+    for &x in arr {
+        let tx = tx.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20 * x as u64));
+            tx.send(x).unwrap();
+        });
+    }
+    (0..arr.len()).map(|_| rx.recv().unwrap()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn bogo_sort_small_arrays() {
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        let inputs: Vec<Vec<i32>> = vec![
+            vec![],
+            vec![1],
+            vec![2, 1],
+            vec![3, 1, 2],
+            vec![4, 3, 2, 1],
+            vec![2, 2, 1, 1],
+        ];
+        for mut arr in inputs {
+            let mut expected = arr.clone();
+            expected.sort();
+            bogo_sort(&mut arr, &mut rng);
+            assert_eq!(arr, expected);
+        }
+    }
+
+    #[test]
+    fn bogo_sort_sorted_array_needs_no_shuffle() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut arr = [1, 2, 3, 4];
+        assert_eq!(bogo_sort(&mut arr, &mut rng), 0);
+    }
+
+    #[test]
+    fn bogo_sort_is_deterministic_with_a_seed() {
+        let mut first = [4, 1, 3, 2];
+        let mut second = first;
+        let shuffles = bogo_sort(&mut first, &mut ChaCha8Rng::seed_from_u64(7));
+        assert!(shuffles > 0);
+        assert_eq!(
+            bogo_sort(&mut second, &mut ChaCha8Rng::seed_from_u64(7)),
+            shuffles
+        );
+    }
+
+    #[test]
+    fn stalin_sort_drops_out_of_order_elements() {
+        assert_eq!(stalin_sort(vec![3, 1, 4, 1, 5, 9, 2, 6]), vec![3, 4, 5, 9]);
+        assert_eq!(stalin_sort(vec![1, 1, 2, 2]), vec![1, 1, 2, 2]);
+        assert_eq!(stalin_sort(vec![5, 4, 3, 2, 1]), vec![5]);
+        assert_eq!(stalin_sort(Vec::<i32>::new()), vec![]);
+    }
+
+    #[test]
+    fn miracle_sort_never_changes_anything() {
+        assert!(miracle_sort(&[1, 2, 3], 1));
+        assert!(!miracle_sort(&[3, 2, 1], 100));
+        assert!(!miracle_sort(&[1, 2, 3], 0));
+    }
+
+    #[test]
+    fn sleep_sort_without_timing() {
+        assert_eq!(sleep_sort(&[]), vec![]);
+        assert_eq!(sleep_sort(&[1]), vec![1]);
+        assert_eq!(sleep_sort(&[1, 1, 1, 1]), vec![1, 1, 1, 1]);
+    }
+
+    #[test]
+    #[ignore = "depends on the scheduler waking the threads up in order"]
+    fn sleep_sort_orders_by_waking_time() {
+        assert_eq!(sleep_sort(&[1, 2, 3, 4]), vec![1, 2, 3, 4]);
+        assert_eq!(sleep_sort(&[3, 4, 2, 1]), vec![1, 2, 3, 4]);
+        assert_eq!(sleep_sort(&[3, 1, 7]), vec![1, 3, 7]);
+        assert_eq!(
+            sleep_sort(&[5, 3, 7, 10, 1, 0, 8]),
+            vec![0, 1, 3, 5, 7, 8, 10]
+        );
+    }
+}
@@ -1,14 +1,20 @@
 use std::cmp;
 
-pub fn pancake_sort<T>(arr: &mut [T]) -> Vec<T>
+/// Reverses the first `k + 1` elements of the slice, like flipping the top of a stack of pancakes.
+pub fn flip<T>(arr: &mut [T], k: usize) {
+    arr[..=k].reverse();
+}
+
+/// Sorts the slice using only prefix reversals, and returns the sizes of the flips applied, in order.
+///
+/// For every position from the end, the largest remaining element is flipped to the front,
+/// then flipped down into place. This takes O(n^2) comparisons and at most 2(n - 1) flips.
+pub fn pancake_sort<T>(arr: &mut [T]) -> Vec<usize>
 where
     T: cmp::PartialEq + cmp::Ord + cmp::PartialOrd + Clone,
 {
-    let len = arr.len();
-    if len < 2 {
-        arr.to_vec();
-    }
-    for i in (0..len).rev() {
+    let mut flips = vec![];
+    for i in (1..arr.len()).rev() {
         let max_index = arr
             .iter()
             .take(i + 1)
@@ -17,44 +23,97 @@ where
             .map(|(idx, _)| idx)
             .unwrap();
         if max_index != i {
-            arr[0..max_index + 1].reverse();
-            arr[0..i + 1].reverse();
+            if max_index != 0 {
+                flip(arr, max_index);
+                flips.push(max_index + 1);
+            }
+            flip(arr, i);
+            flips.push(i + 1);
         }
     }
-    arr.to_vec()
+    flips
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sorting::have_same_elements;
+    use crate::sorting::is_sorted;
+    use crate::sorting::sort_utils;
+
+    fn assert_flips_reproduce_sort<T: Ord + Clone + std::fmt::Debug>(original: &[T]) {
+        let mut sorted = original.to_vec();
+        let flips = pancake_sort(&mut sorted);
+        assert!(flips.len() <= 2 * original.len().saturating_sub(1));
+
+        let mut replayed = original.to_vec();
+        for size in flips {
+            flip(&mut replayed, size - 1);
+        }
+        assert_eq!(replayed, sorted);
+    }
+
+    #[test]
+    fn flip_reverses_prefix() {
+        let mut arr = [1, 2, 3, 4, 5];
+        flip(&mut arr, 2);
+        assert_eq!(arr, [3, 2, 1, 4, 5]);
+        flip(&mut arr, 0);
+        assert_eq!(arr, [3, 2, 1, 4, 5]);
+    }
 
     #[test]
     fn basic() {
-        let res = pancake_sort(&mut [6, 5, -8, 3, 2, 3]);
-        assert_eq!(res, vec![-8, 2, 3, 3, 5, 6]);
+        let mut arr = [6, 5, -8, 3, 2, 3];
+        pancake_sort(&mut arr);
+        assert_eq!(arr, [-8, 2, 3, 3, 5, 6]);
+        assert_flips_reproduce_sort(&[6, 5, -8, 3, 2, 3]);
     }
 
     #[test]
     fn already_sorted() {
-        let res = pancake_sort(&mut ["a", "b", "c"]);
-        assert_eq!(res, vec!["a", "b", "c"]);
+        let mut arr = ["a", "b", "c"];
+        assert_eq!(pancake_sort(&mut arr), vec![]);
+        assert_eq!(arr, ["a", "b", "c"]);
     }
 
     #[test]
     fn odd_number_of_elements() {
-        let res = pancake_sort(&mut ["d", "a", "c", "e", "b"]);
-        assert_eq!(res, vec!["a", "b", "c", "d", "e"]);
+        let mut arr = ["d", "a", "c", "e", "b"];
+        pancake_sort(&mut arr);
+        assert_eq!(arr, ["a", "b", "c", "d", "e"]);
+        assert_flips_reproduce_sort(&["d", "a", "c", "e", "b"]);
     }
 
     #[test]
     fn one_element() {
-        let res = pancake_sort(&mut [3]);
-        assert_eq!(res, vec![3]);
+        let mut arr = [3];
+        assert_eq!(pancake_sort(&mut arr), vec![]);
+        assert_eq!(arr, [3]);
     }
 
     #[test]
     fn empty() {
-        let res = pancake_sort(&mut [] as &mut [u8]);
-        assert_eq!(res, vec![]);
+        let mut arr: [u8; 0] = [];
+        assert_eq!(pancake_sort(&mut arr), vec![]);
+    }
+
+    #[test]
+    fn reversed() {
+        let mut arr = [5, 4, 3, 2, 1];
+        // a single flip of the whole stack is enough
+        assert_eq!(pancake_sort(&mut arr), vec![5]);
+        assert_eq!(arr, [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn random_arrays() {
+        for n in [2, 10, 100] {
+            let original = sort_utils::generate_random_vec(n, -50, 50);
+            let mut arr = original.clone();
+            pancake_sort(&mut arr);
+            assert!(is_sorted(&arr) && have_same_elements(&arr, &original));
+            assert_flips_reproduce_sort(&original);
+        }
     }
 }
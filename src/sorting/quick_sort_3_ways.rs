@@ -2,49 +2,62 @@ use std::cmp::{Ord, Ordering};
 
 use rand::Rng;
 
-fn _quick_sort_3_ways<T: Ord>(arr: &mut [T], lo: usize, hi: usize) {
-    if lo >= hi {
-        return;
-    }
-
-    let mut rng = rand::thread_rng();
-    arr.swap(lo, rng.gen_range(lo..hi + 1));
-
-    let mut lt = lo; // arr[lo+1, lt] < v
-    let mut gt = hi + 1; // arr[gt, r] > v
-    let mut i = lo + 1; // arr[lt + 1, i) == v
-
+/// Rearranges `arr` around the value at `pivot_idx`, with Dijkstra's Dutch national flag
+/// algorithm, and returns `(lt, gt)` such that the items of `arr[..lt]` are less than the pivot,
+/// those of `arr[lt..gt]` equal to it and those of `arr[gt..]` greater, in n - 1 comparisons.
+/// <https://en.wikipedia.org/wiki/Dutch_national_flag_problem>
+///
+/// The pivot goes first, and the unknown items `arr[i..gt]` shrink by one at every step: a
+/// lesser item swaps with the first equal one, at `lt`, which keeps a copy of the pivot there
+/// to compare with, and a greater item goes just before `gt`.
+///
+/// # Panics
+///
+/// Panics if `pivot_idx` is out of `arr`.
+pub fn three_way_partition<T: Ord>(arr: &mut [T], pivot_idx: usize) -> (usize, usize) {
+    arr.swap(0, pivot_idx);
+    let (mut lt, mut i, mut gt) = (0, 1, arr.len());
     while i < gt {
-        match arr[i].cmp(&arr[lo]) {
+        match arr[i].cmp(&arr[lt]) {
             Ordering::Less => {
-                arr.swap(i, lt + 1);
-                i += 1;
+                arr.swap(i, lt);
                 lt += 1;
+                i += 1;
             }
             Ordering::Greater => {
-                arr.swap(i, gt - 1);
                 gt -= 1;
+                arr.swap(i, gt);
             }
-            Ordering::Equal => {
-                i += 1;
-            }
+            Ordering::Equal => i += 1,
         }
     }
+    (lt, gt)
+}
 
-    arr.swap(lo, lt);
-
-    if lt > 1 {
-        _quick_sort_3_ways(arr, lo, lt - 1);
+/// Sorts `arr` with quick sort, splitting it in three around a random pivot with
+/// [`three_way_partition`], in O(n log(n)) expected time.
+///
+/// The items equal to the pivot are done with at once, so that many duplicates make the sort
+/// faster instead of slower: with k distinct values, it takes O(n log(k)) expected time, and
+/// O(n) when all the items are equal. The smaller side is sorted first, by recursion, and the
+/// larger one in the loop, which keeps the stack below log(n) frames.
+pub fn three_way_quicksort<T: Ord, R: Rng + ?Sized>(mut arr: &mut [T], rng: &mut R) {
+    while arr.len() > 1 {
+        let (lt, gt) = three_way_partition(arr, rng.gen_range(0..arr.len()));
+        let (less, rest) = arr.split_at_mut(lt);
+        let greater = &mut rest[gt - lt..];
+        if less.len() < greater.len() {
+            three_way_quicksort(less, rng);
+            arr = greater;
+        } else {
+            three_way_quicksort(greater, rng);
+            arr = less;
+        }
     }
-
-    _quick_sort_3_ways(arr, gt, hi);
 }
 
 pub fn quick_sort_3_ways<T: Ord>(arr: &mut [T]) {
-    let len = arr.len();
-    if len > 1 {
-        _quick_sort_3_ways(arr, 0, len - 1);
-    }
+    three_way_quicksort(arr, &mut rand::thread_rng());
 }
 
 #[cfg(test)]
@@ -53,6 +66,105 @@ mod tests {
     use crate::sorting::have_same_elements;
     use crate::sorting::is_sorted;
     use crate::sorting::sort_utils;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use std::cell::Cell;
+
+    /// An item counting the comparisons made between items of its kind
+    struct Counted<'a> {
+        value: i32,
+        comparisons: &'a Cell<usize>,
+    }
+
+    impl PartialEq for Counted<'_> {
+        fn eq(&self, other: &Self) -> bool {
+            self.cmp(other) == Ordering::Equal
+        }
+    }
+
+    impl Eq for Counted<'_> {}
+
+    impl PartialOrd for Counted<'_> {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Counted<'_> {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.comparisons.set(self.comparisons.get() + 1);
+            self.value.cmp(&other.value)
+        }
+    }
+
+    #[test]
+    fn partitions_around_the_pivot() {
+        let mut rng = ChaCha8Rng::seed_from_u64(154);
+        for _ in 0..500 {
+            let len = rng.gen_range(1..60);
+            let mut arr: Vec<i32> = (0..len).map(|_| rng.gen_range(0..10)).collect();
+            let mut expected = arr.clone();
+            let pivot_idx = rng.gen_range(0..len);
+            let pivot = arr[pivot_idx];
+            let (lt, gt) = three_way_partition(&mut arr, pivot_idx);
+            assert!(lt < gt && gt <= len);
+            assert!(arr[..lt].iter().all(|&x| x < pivot));
+            assert!(arr[lt..gt].iter().all(|&x| x == pivot));
+            assert!(arr[gt..].iter().all(|&x| x > pivot));
+            expected.sort_unstable();
+            arr.sort_unstable();
+            assert_eq!(arr, expected);
+        }
+        let mut single = [5];
+        assert_eq!(three_way_partition(&mut single, 0), (0, 1));
+    }
+
+    #[test]
+    #[should_panic]
+    fn pivot_out_of_range() {
+        three_way_partition(&mut [1, 2, 3], 3);
+    }
+
+    #[test]
+    fn sorts_duplicates_in_linear_time() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1540);
+        let comparisons = Cell::new(0);
+        let n = 100_000;
+        let mut equal: Vec<Counted> = (0..n)
+            .map(|_| Counted {
+                value: 7,
+                comparisons: &comparisons,
+            })
+            .collect();
+        three_way_quicksort(&mut equal, &mut rng);
+        // a single partition does it
+        assert_eq!(comparisons.get(), n - 1);
+
+        // a few distinct values take a few partitions each
+        comparisons.set(0);
+        let mut few: Vec<Counted> = (0..n)
+            .map(|_| Counted {
+                value: rng.gen_range(0..4),
+                comparisons: &comparisons,
+            })
+            .collect();
+        three_way_quicksort(&mut few, &mut rng);
+        assert!(few.windows(2).all(|w| w[0].value <= w[1].value));
+        assert!(
+            comparisons.get() < 8 * n,
+            "{} comparisons",
+            comparisons.get()
+        );
+    }
+
+    #[test]
+    fn sorts_with_a_given_rng() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1541);
+        let mut res = sort_utils::generate_random_vec(100_000, 0, 1_000_000);
+        let cloned = res.clone();
+        three_way_quicksort(&mut res, &mut rng);
+        assert!(is_sorted(&res) && have_same_elements(&res, &cloned));
+    }
 
     #[test]
     fn basic() {
@@ -10,8 +10,9 @@ pub fn radix_sort(arr: &mut [u64]) {
         Some(&x) => x as usize,
         None => return,
     };
-    // Make radix a power of 2 close to arr.len() for optimal runtime
-    let radix = arr.len().next_power_of_two();
+    // Make radix a power of 2 close to arr.len() for optimal runtime,
+    // but at least 2 so that the place value grows
+    let radix = arr.len().next_power_of_two().max(2);
     // Counting sort by each digit from least to most significant
     let mut place = 1;
     while place <= max {
@@ -0,0 +1,275 @@
+use std::cmp::Ordering;
+
+/// Whether the first row of the table does better than the second one, `a d` against `b c`
+fn direction(table: &[[u64; 2]; 2]) -> Ordering {
+    let [[a, b], [c, d]] = *table;
+    (a as u128 * d as u128).cmp(&(b as u128 * c as u128))
+}
+
+fn widen(table: &[[u32; 2]; 2]) -> [[u64; 2]; 2] {
+    table.map(|row| row.map(u64::from))
+}
+
+/// Tells whether the tables of some sub-groups show Simpson's paradox: the first row does
+/// better than the second one in every sub-group, but worse in the aggregate table, their sum,
+/// or the other way around.
+/// <https://en.wikipedia.org/wiki/Simpson%27s_paradox>
+///
+/// The aggregate mixes the sub-groups in different proportions in the two rows, so that a row
+/// mostly made of a sub-group where both rows do badly looks worse overall, even though it does
+/// better in every sub-group. A tie, in a sub-group or in the aggregate, is no reversal.
+pub fn simpsons_paradox_check(group_tables: &[[[u32; 2]; 2]]) -> bool {
+    let mut aggregate = [[0u64; 2]; 2];
+    for table in group_tables {
+        for (sums, row) in aggregate.iter_mut().zip(widen(table)) {
+            for (sum, count) in sums.iter_mut().zip(row) {
+                *sum += count;
+            }
+        }
+    }
+    let Some(first) = group_tables.first() else {
+        return false;
+    };
+    let within = direction(&widen(first));
+    within != Ordering::Equal
+        && group_tables
+            .iter()
+            .all(|table| direction(&widen(table)) == within)
+        && direction(&aggregate) == within.reverse()
+}
+
+/// Returns Pearson's chi-squared statistic of the independence of the rows and the columns,
+/// `n (a d - b c)^2 / ((a + b) (c + d) (a + c) (b + d))`, without continuity correction.
+/// <https://en.wikipedia.org/wiki/Pearson%27s_chi-squared_test>
+///
+/// It is the sum over the four cells of `(observed - expected)^2 / expected`, the expected
+/// counts being those of independent rows and columns with the same totals, and follows a
+/// chi-squared distribution with one degree of freedom for independent variables. The
+/// statistic is `NaN` if a row or a column is empty.
+pub fn chi_squared_statistic(table: &[[u32; 2]; 2]) -> f64 {
+    let [[a, b], [c, d]] = table.map(|row| row.map(f64::from));
+    let n = a + b + c + d;
+    n * (a * d - b * c).powi(2) / ((a + b) * (c + d) * (a + c) * (b + d))
+}
+
+/// The logarithm of the gamma function for `x > 0`, with the Lanczos approximation
+/// <https://en.wikipedia.org/wiki/Lanczos_approximation>
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        // the reflection formula
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let sum = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |sum, (i, c)| {
+            sum + c / (x + i as f64 + 1.0)
+        });
+    let t = x + 7.5;
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+/// The regularized upper incomplete gamma function `Q(s, x) = Γ(s, x) / Γ(s)`, from the series
+/// of `P(s, x) = 1 - Q(s, x)` below `x = s + 1`, and from a continued fraction above it, where
+/// each converges fast
+/// <https://en.wikipedia.org/wiki/Incomplete_gamma_function>
+fn upper_regularized_gamma(s: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let front = (s * x.ln() - x - ln_gamma(s)).exp();
+    if x < s + 1.0 {
+        // P(s, x) = x^s e^-x / Γ(s + 1) (1 + x / (s + 1) + x^2 / ((s + 1) (s + 2)) + ...)
+        let (mut term, mut sum) = (1.0 / s, 1.0 / s);
+        for n in 1..1000 {
+            term *= x / (s + n as f64);
+            sum += term;
+            if term.abs() < sum.abs() * 1e-16 {
+                break;
+            }
+        }
+        return (1.0 - front * sum).max(0.0);
+    }
+    // the continued fraction of Q(s, x), evaluated with the modified Lentz method
+    let tiny = 1e-300;
+    let mut b = x + 1.0 - s;
+    let (mut c, mut d) = (1.0 / tiny, 1.0 / b);
+    let mut fraction = d;
+    for n in 1..1000 {
+        let an = -(n as f64) * (n as f64 - s);
+        b += 2.0;
+        d = an * d + b;
+        d = if d.abs() < tiny { tiny } else { d };
+        c = b + an / c;
+        c = if c.abs() < tiny { tiny } else { c };
+        d = 1.0 / d;
+        let delta = d * c;
+        fraction *= delta;
+        if (delta - 1.0).abs() < 1e-16 {
+            break;
+        }
+    }
+    front * fraction
+}
+
+/// Returns the p-value of a chi-squared statistic with `df` degrees of freedom, the
+/// probability that a chi-squared variable is at least `chi2`,
+/// `Q(df / 2, chi2 / 2)` with the regularized upper incomplete gamma function.
+/// <https://en.wikipedia.org/wiki/Chi-squared_distribution#Cumulative_distribution_function>
+///
+/// # Panics
+///
+/// Panics if `df` is zero, or if `chi2` is negative or `NaN`.
+pub fn pearson_chi_squared_p_value(chi2: f64, df: usize) -> f64 {
+    assert!(df > 0, "there must be at least one degree of freedom");
+    assert!(
+        chi2 >= 0.0,
+        "the statistic must not be negative, got {chi2}"
+    );
+    upper_regularized_gamma(df as f64 / 2.0, chi2 / 2.0)
+}
+
+/// Returns the odds ratio `(a d) / (b c)`, the odds of success in the first row divided by
+/// those in the second one, above 1 when the first row does better.
+/// <https://en.wikipedia.org/wiki/Odds_ratio>
+///
+/// The ratio is infinite if `b` or `c` is zero while `a d` is not, and `NaN` if both products
+/// are zero.
+pub fn odds_ratio(table: &[[u32; 2]; 2]) -> f64 {
+    let [[a, b], [c, d]] = table.map(|row| row.map(f64::from));
+    (a * d) / (b * c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The kidney stone treatments of Charig et al. (1986): successes and failures of the
+    /// treatments A, then B, for small and large stones
+    const SMALL_STONES: [[u32; 2]; 2] = [[81, 6], [234, 36]];
+    const LARGE_STONES: [[u32; 2]; 2] = [[192, 71], [55, 25]];
+
+    fn assert_close(found: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (found - expected).abs() <= tolerance,
+            "{found} is not {expected}"
+        );
+    }
+
+    #[test]
+    fn kidney_stones() {
+        // A does better for both sizes, 93% against 87% and 73% against 69%, but worse
+        // overall, 78% against 83%, since it mostly treated the large stones
+        assert!(simpsons_paradox_check(&[SMALL_STONES, LARGE_STONES]));
+        assert!(odds_ratio(&SMALL_STONES) > 1.0 && odds_ratio(&LARGE_STONES) > 1.0);
+        let aggregate = [[273, 77], [289, 61]];
+        assert_close(odds_ratio(&aggregate), 273.0 * 61.0 / (77.0 * 289.0), 1e-12);
+        assert!(odds_ratio(&aggregate) < 1.0);
+        let chi2 = chi_squared_statistic(&aggregate);
+        assert_close(chi2, 2.310_588_478_002_99, 1e-9);
+        // which is not significant at 5%
+        assert_close(
+            pearson_chi_squared_p_value(chi2, 1),
+            0.128_495_394_439_975,
+            1e-9,
+        );
+    }
+
+    #[test]
+    fn no_paradox() {
+        assert!(!simpsons_paradox_check(&[]));
+        // a single group is its own aggregate
+        assert!(!simpsons_paradox_check(&[SMALL_STONES]));
+        // the same direction everywhere
+        assert!(!simpsons_paradox_check(&[
+            [[10, 5], [5, 10]],
+            [[8, 2], [6, 4]]
+        ]));
+        // the sub-groups disagree
+        assert!(!simpsons_paradox_check(&[
+            SMALL_STONES,
+            [[55, 25], [192, 71]]
+        ]));
+        // ties are no reversals
+        assert!(!simpsons_paradox_check(&[
+            [[1, 1], [1, 1]],
+            [[2, 4], [2, 4]]
+        ]));
+        // and the rows may be swapped
+        let swap = |t: [[u32; 2]; 2]| [t[1], t[0]];
+        assert!(simpsons_paradox_check(&[
+            swap(SMALL_STONES),
+            swap(LARGE_STONES)
+        ]));
+    }
+
+    #[test]
+    fn chi_squared_statistics() {
+        // independent rows and columns, and a perfect association
+        assert_eq!(chi_squared_statistic(&[[10, 20], [30, 60]]), 0.0);
+        assert_eq!(chi_squared_statistic(&[[50, 0], [0, 50]]), 100.0);
+        // the sum over the cells of (observed - expected)^2 / expected
+        let table = [[12, 5], [7, 9]];
+        let n = 33.0;
+        let rows = [17.0, 16.0];
+        let columns = [19.0, 14.0];
+        let mut expected_sum = 0.0;
+        for i in 0..2 {
+            for j in 0..2 {
+                let expected = rows[i] * columns[j] / n;
+                expected_sum += (table[i][j] as f64 - expected).powi(2) / expected;
+            }
+        }
+        assert_close(chi_squared_statistic(&table), expected_sum, 1e-12);
+        assert!(chi_squared_statistic(&[[0, 0], [3, 4]]).is_nan());
+    }
+
+    #[test]
+    fn p_values() {
+        // the critical values at 5% and 1% of the usual tables
+        let critical = [
+            (1, 3.841_458_820_694_124, 0.05),
+            (2, 5.991_464_547_107_979, 0.05),
+            (10, 18.307_038_053_275_146, 0.05),
+            (1, 6.634_896_601_021_214, 0.01),
+            (5, 15.086_272_469_388_99, 0.01),
+            (30, 50.892_181_311_517_15, 0.01),
+        ];
+        for (df, chi2, p) in critical {
+            assert_close(pearson_chi_squared_p_value(chi2, df), p, 1e-12);
+        }
+        assert_eq!(pearson_chi_squared_p_value(0.0, 3), 1.0);
+        // with two degrees of freedom, the distribution is exponential
+        for x in [0.1, 1.0, 2.5, 7.0, 40.0] {
+            assert_close(pearson_chi_squared_p_value(x, 2), (-x / 2.0).exp(), 1e-14);
+        }
+        assert!(pearson_chi_squared_p_value(1000.0, 4) < 1e-200);
+    }
+
+    #[test]
+    fn odds_ratios() {
+        assert_eq!(odds_ratio(&[[20, 10], [10, 20]]), 4.0);
+        assert_eq!(odds_ratio(&[[10, 20], [20, 10]]), 0.25);
+        assert_eq!(odds_ratio(&[[5, 0], [1, 1]]), f64::INFINITY);
+        assert!(odds_ratio(&[[0, 0], [1, 1]]).is_nan());
+    }
+
+    #[test]
+    #[should_panic(expected = "there must be at least one degree of freedom")]
+    fn no_degree_of_freedom() {
+        pearson_chi_squared_p_value(1.0, 0);
+    }
+}
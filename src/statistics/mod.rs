@@ -0,0 +1,12 @@
+//! Statistics on counts of observations.
+//!
+//! A 2x2 contingency table `[[a, b], [c, d]]` counts the observations of two binary variables:
+//! the rows are the two groups compared, such as two treatments, and the columns the two
+//! outcomes, the first one being the success. The first group does better than the second one
+//! when `a / (a + b) > c / (c + d)`, that is when `a d > b c`.
+
+mod contingency;
+
+pub use self::contingency::{
+    chi_squared_statistic, odds_ratio, pearson_chi_squared_p_value, simpsons_paradox_check,
+};
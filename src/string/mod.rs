@@ -14,7 +14,11 @@ mod palindrome;
 mod pangram;
 mod rabin_karp;
 mod reverse;
+pub mod rolling_hash;
+mod rotation;
 mod run_length_encoding;
+pub mod similarity;
+pub mod spellcheck;
 mod suffix_array;
 mod suffix_array_manber_myers;
 mod suffix_tree;
@@ -34,14 +38,23 @@ pub use self::knuth_morris_pratt::knuth_morris_pratt;
 pub use self::levenshtein_distance::levenshtein_distance;
 pub use self::lipogram::is_lipogram;
 pub use self::manacher::manacher;
-pub use self::palindrome::is_palindrome;
+pub use self::palindrome::{
+    count_palindromic_substrings, is_palindrome, is_palindrome_alnum,
+    longest_palindromic_substring, palindromic_radii,
+};
 pub use self::pangram::is_pangram;
 pub use self::pangram::PangramStatus;
 pub use self::rabin_karp::rabin_karp;
 pub use self::reverse::reverse;
+pub use self::rolling_hash::{longest_common_substring, DoubleHash, RollingHash};
+pub use self::rotation::min_rotation;
 pub use self::run_length_encoding::{run_length_decoding, run_length_encoding};
-pub use self::suffix_array::generate_suffix_array;
+pub use self::similarity::{
+    damerau_levenshtein, jaro, jaro_winkler, ngram_similarity, optimal_string_alignment,
+};
+pub use self::spellcheck::{SpellChecker, Suggestion};
+pub use self::suffix_array::{generate_suffix_array, lcp_array, LongestCommonPrefix};
 pub use self::suffix_array_manber_myers::generate_suffix_array_manber_myers;
-pub use self::suffix_tree::{Node, SuffixTree};
+pub use self::suffix_tree::SuffixTree;
 pub use self::z_algorithm::match_pattern;
-pub use self::z_algorithm::z_array;
+pub use self::z_algorithm::{find_occurrences, is_rotation, smallest_period, z_array};
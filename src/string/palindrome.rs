@@ -8,6 +8,104 @@ pub fn is_palindrome(s: &str) -> bool {
     true
 }
 
+/// Returns whether `s` reads the same backward, ignoring case and the characters which are not
+/// alphanumeric: "A man, a plan, a canal: Panama" is a palindrome.
+pub fn is_palindrome_alnum(s: &str) -> bool {
+    let mut chars = s
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase);
+    while let (Some(c1), Some(c2)) = (chars.next(), chars.next_back()) {
+        if c1 != c2 {
+            return false;
+        }
+    }
+    true
+}
+
+/// Computes the radii of the palindromes around every character of `s`, with Manacher's
+/// algorithm <https://en.wikipedia.org/wiki/Longest_palindromic_substring#Manacher's_algorithm>
+///
+/// Returns `(odd, even)`, indexed by characters:
+/// - `odd[i]` is the number of odd-length palindromes centered on character `i`, so the longest
+///   of them has length `2 odd[i] - 1`,
+/// - `even[i]` is the number of even-length palindromes centered between characters `i - 1`
+///   and `i`, so the longest of them has length `2 even[i]`.
+///
+/// Every palindrome found reuses the radii computed in its left half for the mirrored centers
+/// of its right half, and extends them only past its right end, which moves right at every
+/// comparison: this takes O(n) in total.
+pub fn palindromic_radii(s: &str) -> (Vec<usize>, Vec<usize>) {
+    let chars: Vec<char> = s.chars().collect();
+    let n = chars.len();
+
+    let mut odd = vec![0; n];
+    // the rightmost palindrome found so far is chars[left..right]
+    let (mut left, mut right) = (0, 0);
+    for i in 0..n {
+        let mut radius = if i < right {
+            odd[left + right - 1 - i].min(right - i)
+        } else {
+            1
+        };
+        while i + radius < n && i >= radius && chars[i - radius] == chars[i + radius] {
+            radius += 1;
+        }
+        odd[i] = radius;
+        if i + radius > right {
+            left = i + 1 - radius;
+            right = i + radius;
+        }
+    }
+
+    let mut even = vec![0; n];
+    let (mut left, mut right) = (0, 0);
+    for i in 0..n {
+        let mut radius = if i < right {
+            even[left + right - i].min(right - i)
+        } else {
+            0
+        };
+        while i + radius < n && i > radius && chars[i - radius - 1] == chars[i + radius] {
+            radius += 1;
+        }
+        even[i] = radius;
+        if i + radius > right {
+            left = i - radius;
+            right = i + radius;
+        }
+    }
+    (odd, even)
+}
+
+/// Returns the longest palindromic substring of `s`, the leftmost one if there are several, in
+/// O(n). The result is a slice of `s`: multi-byte characters are never split.
+pub fn longest_palindromic_substring(s: &str) -> &str {
+    let (odd, even) = palindromic_radii(s);
+    // (length, start) of the longest palindromes, in characters
+    let longest_odd = odd.iter().enumerate().map(|(i, &r)| (2 * r - 1, i + 1 - r));
+    let longest_even = even.iter().enumerate().map(|(i, &r)| (2 * r, i - r));
+    let Some((length, start)) = longest_odd
+        .chain(longest_even)
+        .max_by(|(l1, s1), (l2, s2)| l1.cmp(l2).then(s2.cmp(s1)))
+    else {
+        return s;
+    };
+    let boundaries: Vec<usize> = s
+        .char_indices()
+        .map(|(offset, _)| offset)
+        .chain(std::iter::once(s.len()))
+        .collect();
+    &s[boundaries[start]..boundaries[start + length]]
+}
+
+/// Returns the number of palindromic substrings of `s`, counting every position separately:
+/// "aaa" has 6 of them. This takes O(n).
+pub fn count_palindromic_substrings(s: &str) -> u64 {
+    let (odd, even) = palindromic_radii(s);
+    odd.iter().chain(&even).map(|&r| r as u64).sum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -21,4 +119,90 @@ mod tests {
         assert!(!is_palindrome("abcde"));
         assert!(!is_palindrome("aaaabbbb"));
     }
+
+    #[test]
+    fn palindromes_ignoring_case_and_punctuation() {
+        assert!(is_palindrome_alnum("A man, a plan, a canal: Panama"));
+        assert!(is_palindrome_alnum("No 'x' in Nixon"));
+        assert!(is_palindrome_alnum(""));
+        assert!(is_palindrome_alnum(".,!"));
+        assert!(is_palindrome_alnum("Was it a car or a cat I saw?"));
+        assert!(!is_palindrome_alnum("race a car"));
+        assert!(!is_palindrome_alnum("0P"));
+    }
+
+    #[test]
+    fn radii() {
+        // a b a a b a
+        let (odd, even) = palindromic_radii("abaaba");
+        assert_eq!(odd, vec![1, 2, 1, 1, 2, 1]);
+        assert_eq!(even, vec![0, 0, 0, 3, 0, 0]);
+        assert_eq!(palindromic_radii(""), (vec![], vec![]));
+    }
+
+    #[test]
+    fn longest_palindromes() {
+        // "bab" and "aba" both have length 3, "bab" is the leftmost
+        assert_eq!(longest_palindromic_substring("babad"), "bab");
+        assert_eq!(longest_palindromic_substring("cbbd"), "bb");
+        assert_eq!(longest_palindromic_substring("ac"), "a");
+        assert_eq!(
+            longest_palindromic_substring("forgeeksskeegfor"),
+            "geeksskeeg"
+        );
+        assert_eq!(longest_palindromic_substring("abacdfgdcaba"), "aba");
+        assert_eq!(longest_palindromic_substring("a"), "a");
+        assert_eq!(longest_palindromic_substring(""), "");
+    }
+
+    #[test]
+    fn unicode_palindromes() {
+        assert_eq!(longest_palindromic_substring("あいういあ"), "あいういあ");
+        assert_eq!(longest_palindromic_substring("xあいいあy"), "あいいあ");
+        assert_eq!(longest_palindromic_substring("日本本日語"), "日本本日");
+        assert_eq!(count_palindromic_substrings("あいういあ"), 7);
+    }
+
+    #[test]
+    fn long_uniform_string() {
+        let s = "a".repeat(100_000);
+        assert_eq!(longest_palindromic_substring(&s), s);
+        assert_eq!(count_palindromic_substrings(&s), 100_000 * 100_001 / 2);
+    }
+
+    /// Counts the palindromes by expanding around each of the 2n - 1 centers, in O(n^2)
+    fn brute_force_count(s: &[u8]) -> u64 {
+        let n = s.len() as i64;
+        let mut count = 0;
+        for center in 0..2 * n - 1 {
+            let (mut left, mut right) = (center / 2, center / 2 + center % 2);
+            while left >= 0 && right < n && s[left as usize] == s[right as usize] {
+                count += 1;
+                left -= 1;
+                right += 1;
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn count_agrees_with_brute_force() {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(112);
+        assert_eq!(count_palindromic_substrings(""), 0);
+        assert_eq!(count_palindromic_substrings("x"), 1);
+        assert_eq!(count_palindromic_substrings("aaa"), 6);
+        for _ in 0..300 {
+            let length = rng.gen_range(0..60);
+            let s: String = (0..length)
+                .map(|_| ['a', 'b', 'c'][rng.gen_range(0..3)])
+                .collect();
+            assert_eq!(
+                count_palindromic_substrings(&s),
+                brute_force_count(s.as_bytes())
+            );
+            let longest = longest_palindromic_substring(&s);
+            assert!(is_palindrome(longest));
+        }
+    }
 }
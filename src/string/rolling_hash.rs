@@ -0,0 +1,580 @@
+//! Polynomial rolling hashes of the substrings of a byte string
+//! <https://en.wikipedia.org/wiki/Rolling_hash>
+//!
+//! The hash of `s[l..r]` is `s[l] B^(r-l-1) + ... + s[r-1] B^0 mod M`. With the hashes of all
+//! prefixes precomputed, the hash of any substring is `hash(s[..r]) - hash(s[..l]) B^(r-l)`,
+//! in O(1). A single hash with a known base and modulus is easy to attack, with inputs built to
+//! collide, and collides anyway among about `sqrt(M)` substrings. `DoubleHash` uses two hash
+//! functions with different primes together: substrings of the same length and different
+//! content collide with probability about `n / (M1 M2)`, not `n / M`.
+
+use crate::error::{AlgorithmError, AlgorithmResult};
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// The moduli and bases of the two hash functions of `DoubleHash`
+const MODULI: [u64; 2] = [1_000_000_007, 998_244_353];
+const BASES: [u64; 2] = [911_382_323, 972_663_749];
+
+/// The hashes of the prefixes of a byte string with one base and modulus, answering hash
+/// queries on its substrings.
+pub struct RollingHash {
+    modulus: u64,
+    /// `prefixes[i]` is the hash of the first `i` bytes
+    prefixes: Vec<u64>,
+    /// `powers[i]` is the base to the power `i`
+    powers: Vec<u64>,
+}
+
+impl RollingHash {
+    /// Precomputes the hashes of the prefixes of `text` with the given base and modulus, in
+    /// O(n). Any modulus works, the products being taken over `u128`, but only a prime one
+    /// spreads the hashes well.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `modulus` is 0.
+    pub fn new(text: &str, base: u64, modulus: u64) -> Self {
+        Self::from_bytes(text.as_bytes(), base, modulus)
+    }
+
+    /// The same as `new`, for any bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `modulus` is 0.
+    pub fn from_bytes(text: &[u8], base: u64, modulus: u64) -> Self {
+        assert!(modulus > 0, "the modulus must be positive");
+        let multiply = |a: u64, b: u64| (a as u128 * b as u128 % modulus as u128) as u64;
+        let base = base % modulus;
+        let mut prefixes = Vec::with_capacity(text.len() + 1);
+        let mut powers = Vec::with_capacity(text.len() + 1);
+        prefixes.push(0);
+        powers.push(1 % modulus);
+        for (i, &byte) in text.iter().enumerate() {
+            // the bytes count from 1, for the leading zero bytes to change the hash
+            let next = multiply(prefixes[i], base) as u128 + byte as u128 + 1;
+            prefixes.push((next % modulus as u128) as u64);
+            powers.push(multiply(powers[i], base));
+        }
+        RollingHash {
+            modulus,
+            prefixes,
+            powers,
+        }
+    }
+
+    /// Returns the length of the text.
+    pub fn len(&self) -> usize {
+        self.prefixes.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn hash_unchecked(&self, range: Range<usize>) -> u64 {
+        let modulus = self.modulus as u128;
+        let removed =
+            self.prefixes[range.start] as u128 * self.powers[range.len()] as u128 % modulus;
+        ((self.prefixes[range.end] as u128 + modulus - removed) % modulus) as u64
+    }
+
+    /// Returns the hash of the substring `text[l..r]`, in O(1).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `l > r` or `r` is past the end of the text.
+    pub fn hash_range(&self, l: usize, r: usize) -> u64 {
+        assert!(
+            l <= r && r <= self.len(),
+            "{l}..{r} is not a range of a text of length {}",
+            self.len()
+        );
+        self.hash_unchecked(l..r)
+    }
+
+    /// Returns whether the substrings `text[l1..r1]` and `text[l2..r2]` are equal, comparing
+    /// their lengths and hashes in O(1). Different substrings of the same length collide with
+    /// probability about `n / M` for a prime modulus `M`, so a modulus about `2^61` is safer
+    /// than one about `1e9` for many comparisons.
+    ///
+    /// # Panics
+    ///
+    /// Panics if one of the ranges is reversed or goes past the end of the text.
+    pub fn compare(&self, l1: usize, r1: usize, l2: usize, r2: usize) -> bool {
+        let (first, second) = (self.hash_range(l1, r1), self.hash_range(l2, r2));
+        r1 - l1 == r2 - l2 && first == second
+    }
+}
+
+/// Two rolling hashes of a byte string with different primes, answering hash queries on its
+/// substrings.
+pub struct DoubleHash {
+    hashes: [RollingHash; 2],
+}
+
+impl DoubleHash {
+    /// Precomputes the hashes of the prefixes of `text`, in O(n).
+    pub fn new(text: &str) -> Self {
+        Self::from_bytes(text.as_bytes())
+    }
+
+    /// The same as `new`, for any bytes.
+    pub fn from_bytes(text: &[u8]) -> Self {
+        DoubleHash {
+            hashes: [0, 1].map(|k| RollingHash::from_bytes(text, BASES[k], MODULI[k])),
+        }
+    }
+
+    /// Returns the length of the text.
+    pub fn len(&self) -> usize {
+        self.hashes[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn check_range(&self, argument: &'static str, range: &Range<usize>) -> AlgorithmResult<()> {
+        if range.start > range.end || range.end > self.len() {
+            return Err(AlgorithmError::InvalidParameter {
+                argument,
+                reason: format!(
+                    "{range:?} is not a range of a text of length {}",
+                    self.len()
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    fn hash_unchecked(&self, range: Range<usize>) -> (u64, u64) {
+        (
+            self.hashes[0].hash_unchecked(range.clone()),
+            self.hashes[1].hash_unchecked(range),
+        )
+    }
+
+    /// Returns the pair of hashes of the substring `text[l..r]`, in O(1).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `l > r` or `r` is past the end of the text.
+    pub fn hash_range(&self, l: usize, r: usize) -> (u64, u64) {
+        (
+            self.hashes[0].hash_range(l, r),
+            self.hashes[1].hash_range(l, r),
+        )
+    }
+
+    /// Returns the pair of hashes of the substring over `range`, in O(1).
+    pub fn hash_of(&self, range: Range<usize>) -> AlgorithmResult<(u64, u64)> {
+        self.check_range("range", &range)?;
+        Ok(self.hash_unchecked(range))
+    }
+
+    /// Returns whether the substrings over the two ranges are equal, with a negligible
+    /// probability of a false positive, in O(1).
+    pub fn equal_substrings(
+        &self,
+        range_a: Range<usize>,
+        range_b: Range<usize>,
+    ) -> AlgorithmResult<bool> {
+        self.check_range("range_a", &range_a)?;
+        self.check_range("range_b", &range_b)?;
+        Ok(range_a.len() == range_b.len()
+            && self.hash_unchecked(range_a) == self.hash_unchecked(range_b))
+    }
+
+    /// Returns the length of the longest common prefix of the suffixes starting at `i` and `j`,
+    /// by binary search over the lengths whose prefixes have equal hashes, in O(log n). Comparing
+    /// the bytes after the common prefix then compares the suffixes, which sorts the suffixes of
+    /// a text in O(n log^2 n).
+    pub fn longest_common_prefix(&self, i: usize, j: usize) -> AlgorithmResult<usize> {
+        self.check_range("i", &(i..self.len()))?;
+        self.check_range("j", &(j..self.len()))?;
+        // the longest common prefix is in lo..=hi
+        let (mut lo, mut hi) = (0, self.len() - i.max(j));
+        while lo < hi {
+            let mid = lo + (hi - lo).div_ceil(2);
+            if self.hash_unchecked(i..i + mid) == self.hash_unchecked(j..j + mid) {
+                lo = mid;
+            } else {
+                hi = mid - 1;
+            }
+        }
+        Ok(lo)
+    }
+}
+
+/// Returns the ranges of a longest common substring of `a` and `b`, empty if they have no byte
+/// in common, in expected O((n + m) log(min(n, m))).
+/// <https://en.wikipedia.org/wiki/Longest_common_substring>
+///
+/// Two strings sharing a substring of some length also share one of any shorter length, so
+/// that the longest length is found by binary search. For a length `k`, the hashes of the
+/// windows of length `k` of `a` go to a hash map, in which those of `b` are looked up, the
+/// bytes being compared for a match so that a hash collision never gives a wrong answer.
+pub fn longest_common_substring(a: &[u8], b: &[u8]) -> (Range<usize>, Range<usize>) {
+    let (hash_a, hash_b) = (DoubleHash::from_bytes(a), DoubleHash::from_bytes(b));
+    let common = |length: usize| {
+        let windows: HashMap<(u64, u64), usize> = (0..=a.len() - length)
+            .map(|i| (hash_a.hash_unchecked(i..i + length), i))
+            .collect();
+        (0..=b.len() - length).find_map(|j| {
+            let i = *windows.get(&hash_b.hash_unchecked(j..j + length))?;
+            (a[i..i + length] == b[j..j + length]).then_some((i..i + length, j..j + length))
+        })
+    };
+    // a common substring of length lo is known, and none longer than hi
+    let (mut lo, mut hi) = (0, a.len().min(b.len()));
+    let mut found = (0..0, 0..0);
+    while lo < hi {
+        let mid = lo + (hi - lo).div_ceil(2);
+        match common(mid) {
+            Some(ranges) => {
+                found = ranges;
+                lo = mid;
+            }
+            None => hi = mid - 1,
+        }
+    }
+    found
+}
+
+/// Returns the start of every occurrence of `pattern` in `text`, overlapping ones included.
+///
+/// The windows of the text with the same hashes as the pattern are compared byte for byte, so
+/// that a hash collision never gives a wrong match. This takes O(n + m) unless there are many
+/// matches, since each of them is verified in O(m).
+pub fn rabin_karp(text: &[u8], pattern: &[u8]) -> Vec<usize> {
+    if pattern.is_empty() || pattern.len() > text.len() {
+        return vec![];
+    }
+    let text_hash = DoubleHash::from_bytes(text);
+    let pattern_hash = DoubleHash::from_bytes(pattern).hash_unchecked(0..pattern.len());
+    (0..=text.len() - pattern.len())
+        .filter(|&start| {
+            let window = start..start + pattern.len();
+            text_hash.hash_unchecked(window.clone()) == pattern_hash && text[window] == *pattern
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn naive_search(text: &[u8], pattern: &[u8]) -> Vec<usize> {
+        if pattern.is_empty() || pattern.len() > text.len() {
+            return vec![];
+        }
+        (0..=text.len() - pattern.len())
+            .filter(|&i| &text[i..i + pattern.len()] == pattern)
+            .collect()
+    }
+
+    fn random_text(rng: &mut ChaCha8Rng, length: usize, alphabet: &[u8]) -> Vec<u8> {
+        (0..length)
+            .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+            .collect()
+    }
+
+    #[test]
+    fn overlapping_occurrences() {
+        assert_eq!(rabin_karp(b"aaaaa", b"aa"), vec![0, 1, 2, 3]);
+        assert_eq!(rabin_karp(b"abababa", b"aba"), vec![0, 2, 4]);
+        assert_eq!(rabin_karp(b"hello world", b"o"), vec![4, 7]);
+        assert_eq!(rabin_karp(b"abc", b"abc"), vec![0]);
+        assert!(rabin_karp(b"abc", b"abcd").is_empty());
+        assert!(rabin_karp(b"abc", b"").is_empty());
+        assert!(rabin_karp(b"", b"a").is_empty());
+    }
+
+    #[test]
+    fn agrees_with_naive_search() {
+        let mut rng = ChaCha8Rng::seed_from_u64(111);
+        for _ in 0..200 {
+            let (text_length, pattern_length) = (rng.gen_range(0..200), rng.gen_range(1..6));
+            let text = random_text(&mut rng, text_length, b"ab");
+            let pattern = random_text(&mut rng, pattern_length, b"ab");
+            assert_eq!(rabin_karp(&text, &pattern), naive_search(&text, &pattern));
+        }
+    }
+
+    #[test]
+    fn equal_substrings_agree_with_slices() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1110);
+        let text = random_text(&mut rng, 300, b"abc");
+        let hash = DoubleHash::from_bytes(&text);
+        for _ in 0..5000 {
+            let length = rng.gen_range(0..8);
+            let a = rng.gen_range(0..=text.len() - length);
+            let b = rng.gen_range(0..=text.len() - length);
+            let (range_a, range_b) = (a..a + length, b..b + length);
+            assert_eq!(
+                hash.equal_substrings(range_a.clone(), range_b.clone()),
+                Ok(text[range_a] == text[range_b])
+            );
+        }
+        assert_eq!(hash.equal_substrings(0..2, 0..3), Ok(false));
+    }
+
+    #[test]
+    fn thue_morse_substrings_do_not_collide() {
+        // the Thue-Morse string and its complement collide for any base when hashing modulo
+        // 2^64, but not with prime moduli
+        let thue_morse: Vec<u8> = (0..2048u32)
+            .map(|i| if i.count_ones() % 2 == 0 { b'a' } else { b'b' })
+            .collect();
+        let complement: Vec<u8> = thue_morse
+            .iter()
+            .map(|&c| if c == b'a' { b'b' } else { b'a' })
+            .collect();
+        let text = [thue_morse, complement].concat();
+        let hash = DoubleHash::from_bytes(&text);
+        assert_eq!(hash.equal_substrings(0..2048, 2048..4096), Ok(false));
+        assert_ne!(hash.hash_of(0..2048), hash.hash_of(2048..4096));
+        // and every pair of distinct windows of some lengths gets distinct hashes
+        for length in [16, 64] {
+            for a in (0..text.len() - length).step_by(37) {
+                for b in (0..text.len() - length).step_by(41) {
+                    let equal = text[a..a + length] == text[b..b + length];
+                    assert_eq!(
+                        hash.equal_substrings(a..a + length, b..b + length),
+                        Ok(equal)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn longest_common_prefix() {
+        let text = b"banana";
+        let hash = DoubleHash::from_bytes(text);
+        assert_eq!(hash.longest_common_prefix(1, 3), Ok(3));
+        assert_eq!(hash.longest_common_prefix(0, 1), Ok(0));
+        assert_eq!(hash.longest_common_prefix(2, 2), Ok(4));
+        assert_eq!(hash.longest_common_prefix(6, 0), Ok(0));
+
+        let mut rng = ChaCha8Rng::seed_from_u64(11);
+        let text = random_text(&mut rng, 200, b"ab");
+        let hash = DoubleHash::from_bytes(&text);
+        for _ in 0..1000 {
+            let (i, j) = (rng.gen_range(0..=200), rng.gen_range(0..=200));
+            let expected = text[i..]
+                .iter()
+                .zip(&text[j..])
+                .take_while(|(a, b)| a == b)
+                .count();
+            assert_eq!(hash.longest_common_prefix(i, j), Ok(expected));
+        }
+        // sorting the suffixes with the longest common prefixes
+        let mut suffixes: Vec<usize> = (0..text.len()).collect();
+        suffixes.sort_by(|&i, &j| {
+            let lcp = hash.longest_common_prefix(i, j).unwrap();
+            text.get(i + lcp).cmp(&text.get(j + lcp))
+        });
+        let mut expected: Vec<usize> = (0..text.len()).collect();
+        expected.sort_by_key(|&i| &text[i..]);
+        assert_eq!(suffixes, expected);
+    }
+
+    #[test]
+    fn out_of_range_queries() {
+        let hash = DoubleHash::from_bytes(b"abc");
+        assert_eq!(hash.len(), 3);
+        assert!(hash.hash_of(0..3).is_ok());
+        assert!(hash.hash_of(3..3).is_ok());
+        assert_eq!(
+            hash.hash_of(2..4),
+            Err(AlgorithmError::InvalidParameter {
+                argument: "range",
+                reason: "2..4 is not a range of a text of length 3".to_string(),
+            })
+        );
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = 2..1;
+        assert!(hash.hash_of(reversed).is_err());
+        assert!(matches!(
+            hash.equal_substrings(0..1, 3..5),
+            Err(AlgorithmError::InvalidParameter {
+                argument: "range_b",
+                ..
+            })
+        ));
+        assert!(hash.longest_common_prefix(4, 0).is_err());
+        assert!(DoubleHash::from_bytes(b"").is_empty());
+    }
+
+    #[test]
+    fn single_hash_of_ranges() {
+        // "ab" hashes to 2 B + 3 and "ba" to 3 B + 2, with the bytes 'a' and 'b' counted from
+        // 'a' + 1 = 98
+        let (base, modulus) = (10, 1_000_000_007);
+        let hash = RollingHash::new("abab", base, modulus);
+        assert_eq!(hash.len(), 4);
+        assert_eq!(hash.hash_range(0, 0), 0);
+        assert_eq!(hash.hash_range(0, 2), 98 * 10 + 99);
+        assert_eq!(hash.hash_range(1, 3), 99 * 10 + 98);
+        assert_eq!(hash.hash_range(0, 2), hash.hash_range(2, 4));
+        // a modulus close to 2^64 does not overflow
+        let text = "the quick brown fox jumps over the lazy dog";
+        let hash = RollingHash::new(text, u64::MAX - 1, u64::MAX - 58);
+        assert_eq!(
+            hash.hash_range(4, 9),
+            RollingHash::new("quick", u64::MAX - 1, u64::MAX - 58).hash_range(0, 5)
+        );
+        assert_ne!(hash.hash_range(4, 9), hash.hash_range(10, 15));
+    }
+
+    #[test]
+    fn double_hash_of_ranges() {
+        let text = "mississippi";
+        let hash = DoubleHash::new(text);
+        assert_eq!(hash.hash_range(1, 4), hash.hash_range(4, 7));
+        assert_ne!(hash.hash_range(1, 4), hash.hash_range(2, 5));
+        assert_eq!(hash.hash_of(1..4), Ok(hash.hash_range(1, 4)));
+        let first = RollingHash::new(text, BASES[0], MODULI[0]);
+        let second = RollingHash::new(text, BASES[1], MODULI[1]);
+        for l in 0..=text.len() {
+            for r in l..=text.len() {
+                assert_eq!(
+                    hash.hash_range(l, r),
+                    (first.hash_range(l, r), second.hash_range(l, r))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn double_hash_does_not_collide_on_random_strings() {
+        // a small modulus collides among a few thousand strings, by the birthday paradox,
+        // while with both moduli a collision of two distinct strings of the same length has
+        // probability about 1e-18
+        let mut rng = ChaCha8Rng::seed_from_u64(136);
+        let strings: Vec<Vec<u8>> = (0..20_000)
+            .map(|_| random_text(&mut rng, 20, b"abcdefghijklmnopqrstuvwxyz"))
+            .collect();
+        let mut distinct = strings.clone();
+        distinct.sort_unstable();
+        distinct.dedup();
+        let count_distinct = |hashes: Vec<(u64, u64)>| {
+            let mut hashes = hashes;
+            hashes.sort_unstable();
+            hashes.dedup();
+            hashes.len()
+        };
+        let single = strings
+            .iter()
+            .map(|s| {
+                (
+                    RollingHash::from_bytes(s, 131, 1_000_003).hash_range(0, 20),
+                    0,
+                )
+            })
+            .collect();
+        assert!(count_distinct(single) < distinct.len());
+        let double = strings
+            .iter()
+            .map(|s| DoubleHash::from_bytes(s).hash_range(0, 20))
+            .collect();
+        assert_eq!(count_distinct(double), distinct.len());
+    }
+
+    #[test]
+    fn compare_agrees_with_slices() {
+        let mut rng = ChaCha8Rng::seed_from_u64(179);
+        let text = random_text(&mut rng, 1000, b"ab");
+        let hash = RollingHash::from_bytes(&text, 1_000_003, (1 << 61) - 1);
+        for length in [1, 5, 12, 40] {
+            for l1 in 0..=text.len() - length {
+                for l2 in 0..=text.len() - length {
+                    let (r1, r2) = (l1 + length, l2 + length);
+                    assert_eq!(hash.compare(l1, r1, l2, r2), text[l1..r1] == text[l2..r2]);
+                }
+            }
+        }
+        assert!(hash.compare(3, 3, 700, 700));
+        assert!(!hash.compare(0, 2, 0, 3));
+        let hash = RollingHash::new("abcab", 31, 1_000_000_007);
+        assert!(hash.compare(0, 2, 3, 5));
+        assert!(!hash.compare(0, 3, 2, 5));
+    }
+
+    #[test]
+    fn longest_common_substrings() {
+        assert_eq!(
+            longest_common_substring(b"xabcdey", b"zzbcdab"),
+            (2..5, 2..5)
+        );
+        assert_eq!(longest_common_substring(b"abc", b"xyz"), (0..0, 0..0));
+        assert_eq!(longest_common_substring(b"", b"abc"), (0..0, 0..0));
+        assert_eq!(longest_common_substring(b"banana", b"ananas"), (1..6, 0..5));
+        let mut rng = ChaCha8Rng::seed_from_u64(1790);
+        for _ in 0..200 {
+            let (n, m) = (rng.gen_range(0..60), rng.gen_range(0..60));
+            let (a, b) = (
+                random_text(&mut rng, n, b"ab"),
+                random_text(&mut rng, m, b"ab"),
+            );
+            let (range_a, range_b) = longest_common_substring(&a, &b);
+            assert_eq!(a[range_a.clone()], b[range_b]);
+            let expected = crate::dynamic_programming::longest_common_substring(
+                std::str::from_utf8(&a).unwrap(),
+                std::str::from_utf8(&b).unwrap(),
+            );
+            assert_eq!(range_a.len() as i32, expected);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "3..5 is not a range of a text of length 4")]
+    fn hash_range_past_the_end() {
+        DoubleHash::new("abcd").hash_range(3, 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "the modulus must be positive")]
+    fn zero_modulus() {
+        RollingHash::new("abcd", 10, 0);
+    }
+
+    #[test]
+    #[ignore = "slow test for measuring time"]
+    fn comparisons_in_constant_time() {
+        use std::time::Instant;
+        let mut rng = ChaCha8Rng::seed_from_u64(1360);
+        let text = random_text(&mut rng, 1 << 20, b"ab");
+        let start = Instant::now();
+        let hash = DoubleHash::from_bytes(&text);
+        println!(
+            "preprocessing of {} bytes: {:?}",
+            text.len(),
+            start.elapsed()
+        );
+        for length in [10, 1000, 100_000, 500_000] {
+            let starts: Vec<(usize, usize)> = (0..1_000_000)
+                .map(|_| {
+                    (
+                        rng.gen_range(0..=text.len() - length),
+                        rng.gen_range(0..=text.len() - length),
+                    )
+                })
+                .collect();
+            let start = Instant::now();
+            let equal = starts
+                .iter()
+                .filter(|&&(a, b)| hash.hash_range(a, a + length) == hash.hash_range(b, b + length))
+                .count();
+            println!(
+                "1e6 comparisons of length {length}: {:?}, {equal} equal",
+                start.elapsed()
+            );
+        }
+    }
+}
@@ -0,0 +1,93 @@
+/// Returns where the lexicographically smallest rotation of `s` starts, with Booth's algorithm
+/// <https://en.wikipedia.org/wiki/Lexicographically_minimal_string_rotation>
+///
+/// The failure function of Knuth-Morris-Pratt is computed over `s + s`, but for the rotation
+/// starting at the best index `k` so far, which moves forward whenever a mismatch finds a
+/// smaller character. That takes O(n) comparisons. The rotations are over the characters, and
+/// the index is in bytes, so that the smallest rotation is `s[k..]` followed by `s[..k]`. When
+/// `s` repeats itself, the first of the equal rotations is returned, so 0 for "abcabc".
+pub fn min_rotation(s: &str) -> usize {
+    let (offsets, chars): (Vec<usize>, Vec<char>) = s.char_indices().unzip();
+    let n = chars.len();
+    if n == 0 {
+        return 0;
+    }
+    let at = |i: usize| chars[i % n];
+    // failure[j] is the length minus one of the longest proper border of the rotation from k
+    // cut after j + 1 characters, and None without border
+    let mut failure: Vec<Option<usize>> = vec![None; 2 * n];
+    let mut k = 0;
+    for j in 1..2 * n {
+        let mut i = failure[j - k - 1];
+        loop {
+            let border = i.map_or(0, |i| i + 1);
+            let c = at(k + border);
+            if at(j) == c {
+                failure[j - k] = Some(border);
+                break;
+            }
+            if at(j) < c {
+                // the rotation from j - border is smaller
+                k = j - border;
+            }
+            match i {
+                Some(previous) => i = failure[previous],
+                None => {
+                    failure[j - k] = None;
+                    break;
+                }
+            }
+        }
+    }
+    offsets[k % n]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::string::is_rotation;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn rotate(s: &str, k: usize) -> String {
+        format!("{}{}", &s[k..], &s[..k])
+    }
+
+    fn naive_min_rotation(s: &str) -> usize {
+        s.char_indices()
+            .map(|(k, _)| k)
+            .min_by_key(|&k| rotate(s, k))
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn small_strings() {
+        assert_eq!(min_rotation("abcabc"), 0);
+        assert_eq!(min_rotation("bcabca"), 2);
+        assert_eq!(min_rotation("cabbca"), 1);
+        assert_eq!(min_rotation("bbaaccaadd"), 2);
+        assert_eq!(min_rotation(""), 0);
+        assert_eq!(min_rotation("z"), 0);
+        assert_eq!(min_rotation("aaaa"), 0);
+        // by characters, "é" being two bytes
+        assert_eq!(min_rotation("éa"), 2);
+        assert_eq!(rotate("zéaé", min_rotation("zéaé")), "aézé");
+        assert!(is_rotation(b"abcde", b"cdeab"));
+        assert!(!is_rotation(b"abcde", b"abced"));
+    }
+
+    #[test]
+    fn random_strings() {
+        let mut rng = ChaCha8Rng::seed_from_u64(177);
+        for _ in 0..2000 {
+            let n = rng.gen_range(0..30);
+            let alphabet = rng.gen_range(1..=4);
+            let s: String = (0..n)
+                .map(|_| ['a', 'b', 'é', 'c'][rng.gen_range(0..alphabet)])
+                .collect();
+            let k = min_rotation(&s);
+            assert_eq!(k, naive_min_rotation(&s), "{s}");
+            assert!(is_rotation(s.as_bytes(), rotate(&s, k).as_bytes()));
+        }
+    }
+}
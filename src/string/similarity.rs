@@ -0,0 +1,286 @@
+//! String similarity measures for fuzzy matching
+//! <https://en.wikipedia.org/wiki/Jaro%E2%80%93Winkler_distance>
+//! <https://en.wikipedia.org/wiki/Damerau%E2%80%93Levenshtein_distance>
+//!
+//! Every function works on the `char`s of its arguments, so that a multi-byte character counts
+//! as one. The scores are between 0 for nothing in common and 1 for identical strings, and the
+//! distances count edit operations.
+
+use crate::error::{AlgorithmError, AlgorithmResult};
+use std::collections::HashMap;
+
+/// Returns the Jaro similarity of two strings.
+///
+/// Two characters match if they are equal and at most `max(|a|, |b|) / 2 - 1` positions apart,
+/// each character matching at most one other. With `m` matches, `t` of them in a different
+/// order in the two strings, the similarity is `(m / |a| + m / |b| + (m - t / 2) / m) / 3`, or 0
+/// without matches.
+pub fn jaro(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut b_matched = vec![false; b.len()];
+    let mut a_matches = Vec::new();
+    for (i, &c) in a.iter().enumerate() {
+        let start = i.saturating_sub(window);
+        let end = (i + window + 1).min(b.len());
+        if let Some(j) = (start..end).find(|&j| !b_matched[j] && b[j] == c) {
+            b_matched[j] = true;
+            a_matches.push(c);
+        }
+    }
+    if a_matches.is_empty() {
+        return 0.0;
+    }
+    let b_matches = b.iter().zip(&b_matched).filter(|(_, &matched)| matched);
+    let transpositions = a_matches
+        .iter()
+        .zip(b_matches)
+        .filter(|(x, (y, _))| x != y)
+        .count();
+    let m = a_matches.len() as f64;
+    (m / a.len() as f64 + m / b.len() as f64 + (m - transpositions as f64 / 2.0) / m) / 3.0
+}
+
+/// Returns the Jaro-Winkler similarity of two strings, which raises the Jaro similarity `j`
+/// of strings sharing a prefix of `l` characters, up to 4, to `j + l prefix_scale (1 - j)`.
+///
+/// The usual `prefix_scale` is 0.1, and it may not exceed 0.25, for the score to stay at
+/// most 1.
+pub fn jaro_winkler(a: &str, b: &str, prefix_scale: f64) -> AlgorithmResult<f64> {
+    if !(0.0..=0.25).contains(&prefix_scale) {
+        return Err(AlgorithmError::InvalidParameter {
+            argument: "prefix_scale",
+            reason: format!("must be between 0 and 0.25, got {prefix_scale}"),
+        });
+    }
+    let similarity = jaro(a, b);
+    let prefix = a
+        .chars()
+        .zip(b.chars())
+        .take(4)
+        .take_while(|(x, y)| x == y)
+        .count();
+    Ok(similarity + prefix as f64 * prefix_scale * (1.0 - similarity))
+}
+
+/// Returns the Damerau-Levenshtein distance of two strings: the fewest insertions, deletions,
+/// substitutions and transpositions of two adjacent characters turning one into the other.
+///
+/// Unlike `optimal_string_alignment`, this allows the transposed characters to be edited
+/// again, with the algorithm of Lowrance and Wagner, in O(|a| |b|) time and space.
+pub fn damerau_levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let infinity = a.len() + b.len();
+    // d[i + 1][j + 1] is the distance between the first i characters of a and the first j of
+    // b, with a border of infinite distances for the transpositions out of the strings
+    let mut d = vec![vec![infinity; b.len() + 2]; a.len() + 2];
+    for i in 0..=a.len() {
+        d[i + 1][1] = i;
+    }
+    for j in 0..=b.len() {
+        d[1][j + 1] = j;
+    }
+    // the last row where every character of a was seen
+    let mut last_row: HashMap<char, usize> = HashMap::new();
+    for i in 1..=a.len() {
+        // the last column of this row where b has a[i - 1]
+        let mut last_column = 0;
+        for j in 1..=b.len() {
+            let k = last_row.get(&b[j - 1]).copied().unwrap_or(0);
+            let l = last_column;
+            let cost = if a[i - 1] == b[j - 1] {
+                last_column = j;
+                0
+            } else {
+                1
+            };
+            // transposing a[k - 1] and b[l - 1], after deleting the characters between them
+            // in a and inserting those between them in b
+            let transposition = d[k][l] + (i - k - 1) + 1 + (j - l - 1);
+            d[i + 1][j + 1] = (d[i][j] + cost)
+                .min(d[i + 1][j] + 1)
+                .min(d[i][j + 1] + 1)
+                .min(transposition);
+        }
+        last_row.insert(a[i - 1], i);
+    }
+    d[a.len() + 1][b.len() + 1]
+}
+
+/// Returns the optimal string alignment distance of two strings, the restricted variant of the
+/// Damerau-Levenshtein distance in which no substring is edited more than once.
+///
+/// It may exceed the Damerau-Levenshtein distance, and it breaks the triangle inequality:
+/// "CA" is one transposition from "AC", which is one insertion from "ABC", but turning "CA"
+/// into "ABC" takes 3 operations, since the inserted "B" would split the transposed pair.
+pub fn optimal_string_alignment(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    d[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j - 1] + cost)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// Counts the n-grams of characters of a string.
+fn ngrams(s: &str, n: usize) -> HashMap<Vec<char>, usize> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut counts = HashMap::new();
+    for gram in chars.windows(n) {
+        *counts.entry(gram.to_vec()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Returns the Dice coefficient of the multisets of `n` consecutive characters of two strings:
+/// twice the number of shared n-grams, over the total number of n-grams.
+///
+/// Strings shorter than `n` have no n-grams, so two of them score 1 if they are equal and
+/// 0 otherwise.
+pub fn ngram_similarity(a: &str, b: &str, n: usize) -> AlgorithmResult<f64> {
+    if n == 0 {
+        return Err(AlgorithmError::InvalidParameter {
+            argument: "n",
+            reason: "n-grams must have at least one character".to_string(),
+        });
+    }
+    let (a_grams, b_grams) = (ngrams(a, n), ngrams(b, n));
+    let total: usize = a_grams.values().chain(b_grams.values()).sum();
+    if total == 0 {
+        return Ok(if a == b { 1.0 } else { 0.0 });
+    }
+    let shared: usize = a_grams
+        .iter()
+        .map(|(gram, &count)| count.min(b_grams.get(gram).copied().unwrap_or(0)))
+        .sum();
+    Ok(2.0 * shared as f64 / total as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-4, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn reference_pairs() {
+        assert_close(jaro("MARTHA", "MARHTA"), 0.9444);
+        assert_close(jaro_winkler("MARTHA", "MARHTA", 0.1).unwrap(), 0.9611);
+        assert_close(jaro("DIXON", "DICKSONX"), 0.7667);
+        assert_close(jaro_winkler("DIXON", "DICKSONX", 0.1).unwrap(), 0.8133);
+        assert_close(
+            jaro_winkler("JELLYFISH", "SMELLYFISH", 0.1).unwrap(),
+            0.8963,
+        );
+        assert_eq!(jaro("abc", "xyz"), 0.0);
+        assert_eq!(
+            jaro_winkler("MARTHA", "MARHTA", 0.0),
+            Ok(jaro("MARTHA", "MARHTA"))
+        );
+        assert!(jaro_winkler("a", "a", 0.3).is_err());
+        assert!(jaro_winkler("a", "a", f64::NAN).is_err());
+    }
+
+    #[test]
+    fn transpositions() {
+        assert_eq!(damerau_levenshtein("CA", "ABC"), 2);
+        assert_eq!(optimal_string_alignment("CA", "ABC"), 3);
+        assert_eq!(damerau_levenshtein("ab", "ba"), 1);
+        assert_eq!(optimal_string_alignment("ab", "ba"), 1);
+        assert_eq!(damerau_levenshtein("kitten", "sitting"), 3);
+        assert_eq!(damerau_levenshtein("", "abc"), 3);
+        assert_eq!(optimal_string_alignment("abc", ""), 3);
+        assert_eq!(damerau_levenshtein("abcdef", "badcfe"), 3);
+    }
+
+    #[test]
+    fn trigrams() {
+        // "hello" has hel, ell, llo, and "help" hel, elp, so they share one of five
+        assert_eq!(ngram_similarity("hello", "help", 3), Ok(0.4));
+        // the bigrams of "aaa" are aa twice, and those of "aa" only once
+        assert_eq!(ngram_similarity("aaa", "aa", 2), Ok(2.0 / 3.0));
+        assert_eq!(ngram_similarity("ab", "ab", 3), Ok(1.0));
+        assert_eq!(ngram_similarity("ab", "ba", 3), Ok(0.0));
+        assert!(ngram_similarity("ab", "ab", 0).is_err());
+    }
+
+    #[test]
+    fn empty_identical_and_multi_byte() {
+        for s in ["", "a", "crème brûlée", "日本語のテキスト", "👍🏽"] {
+            assert_eq!(jaro(s, s), 1.0);
+            assert_eq!(jaro_winkler(s, s, 0.1), Ok(1.0));
+            assert_eq!(damerau_levenshtein(s, s), 0);
+            assert_eq!(optimal_string_alignment(s, s), 0);
+            assert_eq!(ngram_similarity(s, s, 2), Ok(1.0));
+        }
+        assert_eq!(jaro("", "abc"), 0.0);
+        assert_eq!(ngram_similarity("", "abc", 2), Ok(0.0));
+        assert_eq!(damerau_levenshtein("日本", "本日"), 1);
+        assert_eq!(damerau_levenshtein("crème", "creme"), 1);
+        assert_eq!(optimal_string_alignment("ü", ""), 1);
+        assert_close(jaro("åbç", "åçb"), jaro("abc", "acb"));
+        assert_eq!(ngram_similarity("ñañ", "ñaña", 2), Ok(0.8));
+    }
+
+    #[test]
+    fn random_pairs() {
+        let mut rng = ChaCha8Rng::seed_from_u64(119);
+        let alphabet = ['a', 'b', 'c', 'é', '日'];
+        let random_string = |rng: &mut ChaCha8Rng| -> String {
+            let len = rng.gen_range(0..8);
+            (0..len)
+                .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+                .collect()
+        };
+        for _ in 0..500 {
+            let (a, b, c) = (
+                random_string(&mut rng),
+                random_string(&mut rng),
+                random_string(&mut rng),
+            );
+            let scores = [
+                jaro(&a, &b),
+                jaro_winkler(&a, &b, 0.25).unwrap(),
+                ngram_similarity(&a, &b, 2).unwrap(),
+                ngram_similarity(&a, &b, 3).unwrap(),
+            ];
+            assert!(scores.iter().all(|score| (0.0..=1.0).contains(score)));
+            assert_eq!(jaro(&a, &b), jaro(&b, &a));
+            assert_eq!(jaro_winkler(&a, &b, 0.1), jaro_winkler(&b, &a, 0.1));
+            assert_eq!(ngram_similarity(&a, &b, 2), ngram_similarity(&b, &a, 2));
+
+            let distance = damerau_levenshtein(&a, &b);
+            assert_eq!(distance, damerau_levenshtein(&b, &a));
+            assert_eq!(
+                optimal_string_alignment(&a, &b),
+                optimal_string_alignment(&b, &a)
+            );
+            assert!(distance <= optimal_string_alignment(&a, &b));
+            assert!(distance <= a.chars().count().max(b.chars().count()));
+            assert_eq!(distance == 0, a == b);
+            assert!(distance <= damerau_levenshtein(&a, &c) + damerau_levenshtein(&c, &b));
+        }
+    }
+}
@@ -0,0 +1,350 @@
+//! A spell checker suggesting the dictionary words closest to a misspelled word
+//! <https://en.wikipedia.org/wiki/Levenshtein_distance>
+//! <http://stevehanov.ca/blog/?id=114>
+//!
+//! The dictionary is a trie of the `char`s of its words, and a query computes the row of the
+//! Levenshtein matrix of every node from the row of its parent, which the words under the node
+//! share. A subtree is left out as soon as every cell of its row exceeds the maximum distance,
+//! so that a query visits a small part of the dictionary instead of computing the distance to
+//! every word.
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+/// A dictionary word close to a query, with the edit distance between them and the frequency
+/// of the word
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    pub word: String,
+    pub distance: usize,
+    pub frequency: u64,
+}
+
+#[derive(Debug, Default)]
+struct Node {
+    children: BTreeMap<char, Node>,
+    /// The dictionary words ending here, with their frequencies, which are several when case is
+    /// ignored and the dictionary has the same word in different cases
+    words: Vec<(String, u64)>,
+}
+
+#[derive(Debug)]
+pub struct SpellChecker {
+    root: Node,
+    ignore_case: bool,
+}
+
+impl SpellChecker {
+    /// Creates an empty dictionary. When `ignore_case` is set, words match whatever their case,
+    /// and suggestions keep the case of the dictionary.
+    pub fn new(ignore_case: bool) -> Self {
+        SpellChecker {
+            root: Node::default(),
+            ignore_case,
+        }
+    }
+
+    /// Creates a case-sensitive dictionary of the words, with the number of times every word
+    /// appears as its frequency.
+    pub fn from_words(words: impl IntoIterator<Item = String>) -> Self {
+        Self::from_frequencies(words.into_iter().map(|word| (word, 1)))
+    }
+
+    /// Creates a case-sensitive dictionary of the words with their frequencies, those of a word
+    /// given more than once adding up.
+    pub fn from_frequencies(words: impl IntoIterator<Item = (String, u64)>) -> Self {
+        let mut checker = Self::new(false);
+        for (word, frequency) in words {
+            checker.insert(&word, frequency);
+        }
+        checker
+    }
+
+    fn key(&self, word: &str) -> Vec<char> {
+        if self.ignore_case {
+            word.to_lowercase().chars().collect()
+        } else {
+            word.chars().collect()
+        }
+    }
+
+    /// Adds a word to the dictionary, or its frequency to that of the word if it is already in.
+    pub fn insert(&mut self, word: &str, frequency: u64) {
+        let key = self.key(word);
+        let mut node = &mut self.root;
+        for c in key {
+            node = node.children.entry(c).or_default();
+        }
+        match node.words.iter_mut().find(|(known, _)| known == word) {
+            Some((_, total)) => *total += frequency,
+            None => node.words.push((word.to_string(), frequency)),
+        }
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        let mut node = &self.root;
+        for c in self.key(word) {
+            match node.children.get(&c) {
+                Some(child) => node = child,
+                None => return false,
+            }
+        }
+        !node.words.is_empty()
+    }
+
+    /// Returns at most `limit` dictionary words at a Levenshtein distance of at most
+    /// `max_distance` from `word`, by increasing distance, then decreasing frequency, then in
+    /// lexicographic order.
+    pub fn suggest(&self, word: &str, max_distance: usize, limit: usize) -> Vec<Suggestion> {
+        let query = self.key(word);
+        // the distances from the prefix of the node, empty at the root, to every prefix of the
+        // query
+        let row: Vec<usize> = (0..=query.len()).collect();
+        let mut suggestions = vec![];
+        collect(&self.root, &query, &row, max_distance, &mut suggestions);
+        suggestions.sort_by(|a, b| {
+            (a.distance, Reverse(a.frequency), &a.word).cmp(&(
+                b.distance,
+                Reverse(b.frequency),
+                &b.word,
+            ))
+        });
+        suggestions.truncate(limit);
+        suggestions
+    }
+}
+
+fn collect(
+    node: &Node,
+    query: &[char],
+    row: &[usize],
+    max_distance: usize,
+    suggestions: &mut Vec<Suggestion>,
+) {
+    let distance = row[query.len()];
+    if distance <= max_distance {
+        suggestions.extend(node.words.iter().map(|(word, frequency)| Suggestion {
+            word: word.clone(),
+            distance,
+            frequency: *frequency,
+        }));
+    }
+    for (&c, child) in &node.children {
+        let mut next = Vec::with_capacity(row.len());
+        next.push(row[0] + 1);
+        for (j, &q) in query.iter().enumerate() {
+            let substitution = row[j] + usize::from(q != c);
+            next.push(substitution.min(row[j + 1] + 1).min(next[j] + 1));
+        }
+        // the distances of longer words are at least the smallest one of the row
+        if next.iter().min().is_some_and(|&min| min <= max_distance) {
+            collect(child, query, &next, max_distance, suggestions);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::string::levenshtein_distance;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use std::time::Instant;
+
+    fn dictionary(words: &[&str]) -> SpellChecker {
+        SpellChecker::from_words(words.iter().map(|word| word.to_string()))
+    }
+
+    fn words(suggestions: &[Suggestion]) -> Vec<&str> {
+        suggestions.iter().map(|s| s.word.as_str()).collect()
+    }
+
+    #[test]
+    fn classic_typos() {
+        let checker = dictionary(&[
+            "receive",
+            "achieve",
+            "separate",
+            "definitely",
+            "the",
+            "their",
+            "there",
+            "necessary",
+            "occurrence",
+        ]);
+        let suggestion = &checker.suggest("recieve", 2, 1)[0];
+        assert_eq!(
+            (suggestion.word.as_str(), suggestion.distance),
+            ("receive", 2)
+        );
+        assert_eq!(words(&checker.suggest("seperate", 2, 3)), ["separate"]);
+        assert_eq!(words(&checker.suggest("definately", 2, 3)), ["definitely"]);
+        assert_eq!(words(&checker.suggest("neccesary", 2, 3)), ["necessary"]);
+        assert_eq!(words(&checker.suggest("occurence", 1, 3)), ["occurrence"]);
+        assert_eq!(
+            words(&checker.suggest("thier", 2, 5)),
+            ["the", "their", "there"]
+        );
+        assert!(checker.suggest("xyzzy", 2, 5).is_empty());
+    }
+
+    #[test]
+    fn zero_distance_is_lookup() {
+        let checker = dictionary(&["apple", "apply", "ape", ""]);
+        for word in ["apple", "apply", "ape", "", "app", "apples", "Apple"] {
+            let exact = checker.suggest(word, 0, 10);
+            assert_eq!(exact.len(), usize::from(checker.contains(word)), "{word}");
+            assert!(exact.iter().all(|s| s.word == word && s.distance == 0));
+        }
+        assert!(checker.contains("ape"));
+        assert!(!checker.contains("ap"));
+        assert!(checker.suggest("ape", 1, 0).is_empty());
+    }
+
+    #[test]
+    fn ranking() {
+        let checker = SpellChecker::from_frequencies(
+            [
+                ("cat", 5),
+                ("bat", 10),
+                ("hat", 10),
+                ("cart", 100),
+                ("at", 1),
+            ]
+            .into_iter()
+            .map(|(word, frequency)| (word.to_string(), frequency)),
+        );
+        // all at the distance 1, the most frequent first and the ties in lexicographic order
+        assert_eq!(
+            words(&checker.suggest("xat", 1, 10)),
+            ["bat", "hat", "cat", "at"]
+        );
+        // a closer word comes first, however rare
+        assert_eq!(
+            words(&checker.suggest("xat", 2, 10)),
+            ["bat", "hat", "cat", "at", "cart"]
+        );
+        assert_eq!(
+            words(&checker.suggest("cat", 1, 10)),
+            ["cat", "cart", "bat", "hat", "at"]
+        );
+        assert_eq!(words(&checker.suggest("xat", 2, 2)), ["bat", "hat"]);
+        // the frequencies of a repeated word add up
+        let checker = dictionary(&["b", "a", "b"]);
+        let suggestions = checker.suggest("c", 1, 10);
+        assert_eq!(words(&suggestions), ["b", "a"]);
+        assert_eq!(suggestions[0].frequency, 2);
+    }
+
+    #[test]
+    fn case_handling() {
+        let mut checker = SpellChecker::new(true);
+        for word in ["Paris", "London", "berlin", "US", "us"] {
+            checker.insert(word, 1);
+        }
+        assert!(checker.contains("PARIS"));
+        assert!(checker.contains("paris"));
+        assert_eq!(words(&checker.suggest("pariss", 1, 5)), ["Paris"]);
+        assert_eq!(words(&checker.suggest("BERLN", 1, 5)), ["berlin"]);
+        assert_eq!(words(&checker.suggest("Us", 0, 5)), ["US", "us"]);
+
+        let checker = dictionary(&["Paris"]);
+        assert!(!checker.contains("paris"));
+        assert_eq!(checker.suggest("paris", 1, 5)[0].distance, 1);
+    }
+
+    #[test]
+    fn unicode_words() {
+        let mut checker = SpellChecker::new(true);
+        for word in ["café", "naïve", "日本語", "Ärger", "straße"] {
+            checker.insert(word, 1);
+        }
+        assert_eq!(checker.suggest("cafe", 1, 5)[0].word, "café");
+        assert_eq!(checker.suggest("naive", 1, 5)[0].distance, 1);
+        assert_eq!(words(&checker.suggest("日本", 1, 5)), ["日本語"]);
+        assert_eq!(words(&checker.suggest("ärgr", 1, 5)), ["Ärger"]);
+        assert!(checker.contains("ÄRGER"));
+        assert!(checker.contains("STRAßE"));
+        assert!(checker.suggest("日", 1, 5).is_empty());
+    }
+
+    fn random_word(rng: &mut ChaCha8Rng, alphabet: &[u8], max_len: usize) -> String {
+        (0..rng.gen_range(0..=max_len))
+            .map(|_| alphabet[rng.gen_range(0..alphabet.len())] as char)
+            .collect()
+    }
+
+    fn brute_force(
+        dictionary: &[(String, u64)],
+        word: &str,
+        max_distance: usize,
+        limit: usize,
+    ) -> Vec<Suggestion> {
+        let mut suggestions: Vec<Suggestion> = dictionary
+            .iter()
+            .map(|(known, frequency)| Suggestion {
+                word: known.clone(),
+                distance: levenshtein_distance(known, word),
+                frequency: *frequency,
+            })
+            .filter(|s| s.distance <= max_distance)
+            .collect();
+        suggestions.sort_by_key(|s| (s.distance, Reverse(s.frequency), s.word.clone()));
+        suggestions.truncate(limit);
+        suggestions
+    }
+
+    #[test]
+    fn agrees_with_brute_force() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1263);
+        for _ in 0..50 {
+            let mut words = BTreeMap::new();
+            for _ in 0..rng.gen_range(1..200) {
+                let word = random_word(&mut rng, b"abcd", 7);
+                words.insert(word, rng.gen_range(1..5));
+            }
+            let words: Vec<(String, u64)> = words.into_iter().collect();
+            let checker = SpellChecker::from_frequencies(words.iter().cloned());
+            for _ in 0..20 {
+                let word = random_word(&mut rng, b"abcde", 8);
+                let max_distance = rng.gen_range(0..4);
+                let limit = rng.gen_range(0..30);
+                assert_eq!(
+                    checker.suggest(&word, max_distance, limit),
+                    brute_force(&words, &word, max_distance, limit)
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[ignore = "slow test for measuring time"]
+    fn faster_than_brute_force() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1264);
+        let alphabet = b"abcdefghijklmnopqrstuvwxyz";
+        let mut words = BTreeMap::new();
+        while words.len() < 100_000 {
+            let word = random_word(&mut rng, alphabet, 10);
+            words.insert(word, rng.gen_range(1..100));
+        }
+        let words: Vec<(String, u64)> = words.into_iter().collect();
+        let checker = SpellChecker::from_frequencies(words.iter().cloned());
+        let queries: Vec<String> = (0..100)
+            .map(|_| random_word(&mut rng, alphabet, 10))
+            .collect();
+
+        let start = Instant::now();
+        let from_trie: Vec<Vec<Suggestion>> =
+            queries.iter().map(|q| checker.suggest(q, 2, 5)).collect();
+        let trie_time = start.elapsed();
+        let start = Instant::now();
+        let from_scan: Vec<Vec<Suggestion>> = queries
+            .iter()
+            .map(|q| brute_force(&words, q, 2, 5))
+            .collect();
+        let scan_time = start.elapsed();
+        println!("trie: {trie_time:?}, brute force: {scan_time:?}");
+        assert_eq!(from_trie, from_scan);
+        assert!(trie_time < scan_time);
+    }
+}
@@ -2,6 +2,8 @@
 // It is a data structure used in, among others, full-text indices, data-compression algorithms,
 // and the field of bibliometrics. Source: https://en.wikipedia.org/wiki/Suffix_array
 
+use crate::data_structures::SparseTable;
+use crate::error::{AlgorithmError, AlgorithmResult};
 use std::cmp::Ordering;
 
 #[derive(Clone)]
@@ -83,13 +85,248 @@ pub fn generate_suffix_array(txt: &str) -> Vec<usize> {
     suffix_arr
 }
 
+/// Returns the LCP array of a text with Kasai's algorithm, in O(n).
+/// <https://en.wikipedia.org/wiki/LCP_array>
+///
+/// `lcp[r]` is the length of the longest common prefix of the suffixes at the ranks `r - 1` and
+/// `r` of `suffix_array`, which must sort the suffixes starting at every byte of `txt`, and
+/// `lcp[0]` is 0. Going through the suffixes in the order of the text, the common prefix with the
+/// suffix ranked before loses at most one byte from one suffix to the next, so that the
+/// comparisons add up to O(n).
+pub fn lcp_array(txt: &[u8], suffix_array: &[usize]) -> Vec<usize> {
+    let n = txt.len();
+    let mut rank = vec![0; n];
+    for (r, &i) in suffix_array.iter().enumerate() {
+        rank[i] = r;
+    }
+    let mut lcp = vec![0; n];
+    let mut common = 0;
+    for i in 0..n {
+        if rank[i] == 0 {
+            common = 0;
+            continue;
+        }
+        let previous = suffix_array[rank[i] - 1];
+        while i + common < n && previous + common < n && txt[i + common] == txt[previous + common] {
+            common += 1;
+        }
+        lcp[rank[i]] = common;
+        common = common.saturating_sub(1);
+    }
+    lcp
+}
+
+/// Sorts the suffixes of a text by prefix doubling, in O(n log(n)).
+///
+/// After the round `k`, the suffixes are sorted by their first `2^k` bytes, and their ranks in
+/// that order are used to sort them by their first `2^(k + 1)` bytes: by the rank from `2^k`
+/// bytes further, then by their own rank, with two stable counting sorts in O(n). It stops when
+/// all ranks are distinct, after at most log(n) rounds.
+fn sort_suffixes(txt: &[u8]) -> Vec<usize> {
+    let n = txt.len();
+    if n == 0 {
+        return vec![];
+    }
+    let mut suffix_array: Vec<usize> = (0..n).collect();
+    suffix_array.sort_by_key(|&i| txt[i]);
+    // the ranks start at 1, 0 being past the end of the text
+    let mut rank: Vec<usize> = txt.iter().map(|&b| usize::from(b) + 1).collect();
+    let mut classes = 257;
+    let mut next_rank = vec![0; n];
+    let mut width = 1;
+    loop {
+        // the suffixes shorter than the width first, then in the order of the second halves
+        let mut by_second: Vec<usize> = (n.saturating_sub(width)..n).collect();
+        by_second.extend(
+            suffix_array
+                .iter()
+                .filter(|&&i| i >= width)
+                .map(|&i| i - width),
+        );
+        let mut starts = vec![0; classes + 1];
+        for &i in &by_second {
+            starts[rank[i] + 1] += 1;
+        }
+        for r in 1..starts.len() {
+            starts[r] += starts[r - 1];
+        }
+        for &i in &by_second {
+            suffix_array[starts[rank[i]]] = i;
+            starts[rank[i]] += 1;
+        }
+        let key = |i: usize| (rank[i], rank.get(i + width).copied().unwrap_or(0));
+        next_rank[suffix_array[0]] = 1;
+        for w in 1..n {
+            let (previous, i) = (suffix_array[w - 1], suffix_array[w]);
+            next_rank[i] = next_rank[previous] + usize::from(key(previous) != key(i));
+        }
+        std::mem::swap(&mut rank, &mut next_rank);
+        classes = rank[suffix_array[n - 1]] + 1;
+        if classes == n + 1 {
+            break;
+        }
+        width *= 2;
+    }
+    suffix_array
+}
+
+/// Answers the longest common extension of any two suffixes of a text, the length of their
+/// longest common prefix, in O(1).
+/// <https://en.wikipedia.org/wiki/Longest_common_prefix#Longest_common_extension>
+///
+/// The common prefix of two suffixes is the minimum of the LCP array between their ranks in the
+/// suffix array, found with a sparse table. Sorting the suffixes by prefix doubling, the LCP
+/// array and the sparse table take O(n log(n)) to build.
+pub struct LongestCommonPrefix {
+    rank: Vec<usize>,
+    lcp: SparseTable<usize>,
+}
+
+impl LongestCommonPrefix {
+    pub fn new(txt: &[u8]) -> Self {
+        let suffix_array = sort_suffixes(txt);
+        let mut rank = vec![0; txt.len()];
+        for (r, &i) in suffix_array.iter().enumerate() {
+            rank[i] = r;
+        }
+        let lcp = SparseTable::min(&lcp_array(txt, &suffix_array));
+        LongestCommonPrefix { rank, lcp }
+    }
+
+    /// Returns the length of the longest common prefix of the suffixes starting at `i` and `j`,
+    /// which may be the end of the text.
+    pub fn query(&self, i: usize, j: usize) -> AlgorithmResult<usize> {
+        let n = self.rank.len();
+        if i > n || j > n {
+            return Err(AlgorithmError::InvalidParameter {
+                argument: "i",
+                reason: format!("({i}, {j}) must be positions of a text of length {n}"),
+            });
+        }
+        if i == n || j == n {
+            return Ok(0);
+        }
+        if i == j {
+            return Ok(n - i);
+        }
+        let (first, second) = (
+            self.rank[i].min(self.rank[j]),
+            self.rank[i].max(self.rank[j]),
+        );
+        self.lcp.query(first + 1..second + 1)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use std::time::Instant;
 
     #[test]
     fn test_suffix_array() {
         let a = generate_suffix_array("banana");
         assert_eq!(a, vec![5, 3, 1, 0, 4, 2]);
     }
+
+    #[test]
+    fn test_lcp_array() {
+        let suffix_array = generate_suffix_array("banana");
+        assert_eq!(lcp_array(b"banana", &suffix_array), [0, 1, 3, 0, 0, 2]);
+        assert_eq!(lcp_array(b"", &[]), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_longest_common_prefix() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1222);
+        for len in [0, 1, 2, 10, 100] {
+            let txt: Vec<u8> = (0..len).map(|_| rng.gen_range(b'a'..b'd')).collect();
+            let lcp = LongestCommonPrefix::new(&txt);
+            for i in 0..=len {
+                for j in 0..=len {
+                    let expected = txt[i..]
+                        .iter()
+                        .zip(&txt[j..])
+                        .take_while(|(a, b)| a == b)
+                        .count();
+                    assert_eq!(lcp.query(i, j), Ok(expected), "{i} {j}");
+                }
+            }
+            assert!(lcp.query(0, len + 1).is_err());
+        }
+    }
+
+    fn naive_extension(txt: &[u8], i: usize, j: usize) -> usize {
+        txt[i..]
+            .iter()
+            .zip(&txt[j..])
+            .take_while(|(a, b)| a == b)
+            .count()
+    }
+
+    #[test]
+    fn test_longest_common_extension() {
+        let txt = b"banana";
+        let lcp = LongestCommonPrefix::new(txt);
+        for i in 0..=6 {
+            for j in 0..=6 {
+                assert_eq!(lcp.query(i, j), Ok(naive_extension(txt, i, j)));
+            }
+        }
+        assert_eq!(lcp.query(1, 3), Ok(3));
+        assert_eq!(lcp.query(3, 5), Ok(1));
+        // a periodic text, whose suffixes starting in the same place of the period share all
+        // their bytes, which a comparison sort would take O(n^2 log(n)) to sort
+        let n = 100_000;
+        let txt: Vec<u8> = b"abc".iter().copied().cycle().take(n).collect();
+        let lcp = LongestCommonPrefix::new(&txt);
+        let mut rng = ChaCha8Rng::seed_from_u64(178);
+        for _ in 0..10_000 {
+            let (i, j) = (rng.gen_range(0..n), rng.gen_range(0..n));
+            let expected = if i % 3 == j % 3 { n - i.max(j) } else { 0 };
+            assert_eq!(lcp.query(i, j), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn test_sort_suffixes() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1780);
+        for len in 0..200 {
+            let alphabet = rng.gen_range(1..=4);
+            let txt: Vec<u8> = (0..len).map(|_| rng.gen_range(0..alphabet)).collect();
+            let mut expected: Vec<usize> = (0..len).collect();
+            expected.sort_by_key(|&i| &txt[i..]);
+            assert_eq!(sort_suffixes(&txt), expected);
+        }
+        assert_eq!(sort_suffixes(&[255, 0, 255]), [1, 2, 0]);
+    }
+
+    #[test]
+    #[ignore = "slow test for measuring time"]
+    fn test_longest_common_extension_time() {
+        let mut rng = ChaCha8Rng::seed_from_u64(17800);
+        let n = 100_000;
+        // a periodic text with a few changes, whose suffixes share long prefixes
+        let mut txt: Vec<u8> = b"abc".iter().copied().cycle().take(n).collect();
+        for _ in 0..10 {
+            txt[rng.gen_range(0..n)] = b'd';
+        }
+        let start = Instant::now();
+        let lcp = LongestCommonPrefix::new(&txt);
+        println!("built for {n} bytes in {:?}", start.elapsed());
+        let queries: Vec<(usize, usize)> = (0..10_000)
+            .map(|_| (rng.gen_range(0..n), rng.gen_range(0..n)))
+            .collect();
+        let start = Instant::now();
+        let total: usize = queries.iter().map(|&(i, j)| lcp.query(i, j).unwrap()).sum();
+        println!("{} queries in {:?}", queries.len(), start.elapsed());
+        let start = Instant::now();
+        let naive: usize = queries
+            .iter()
+            .map(|&(i, j)| naive_extension(&txt, i, j))
+            .sum();
+        println!("naive comparisons in {:?}", start.elapsed());
+        assert_eq!(total, naive);
+    }
 }
@@ -1,152 +1,344 @@
-// In computer science, a suffix tree (also called PAT tree or, in an earlier form, position tree)
-// is a compressed trie containing all the suffixes of the given text as their keys and positions
-// in the text as their values. Suffix trees allow particularly fast implementations of many
-// important string operations. Source: https://en.wikipedia.org/wiki/Suffix_tree
-
-#[derive(Debug, PartialEq, Eq, Clone)]
-pub struct Node {
-    pub sub: String,    // substring of input string
-    pub ch: Vec<usize>, // vector of child nodes
-}
+use std::collections::BTreeMap;
 
-impl Node {
-    fn new(sub: String, children: Vec<usize>) -> Self {
-        Node {
-            sub,
-            ch: children.to_vec(),
-        }
-    }
-    pub fn empty() -> Self {
-        Node {
-            sub: "".to_string(),
-            ch: vec![],
-        }
-    }
+/// The symbol ending the text, which is no character, so that every suffix ends at a leaf
+const TERMINAL: u32 = u32::MAX;
+
+/// The end of the edges of the leaves, which grow with the text during the construction
+const LEAF: usize = usize::MAX;
+
+/// A node of a suffix tree, below the edge labeled by the symbols `start..end` of the text
+#[derive(Debug, Clone)]
+struct Node {
+    start: usize,
+    end: usize,
+    /// The node of the same path without its first symbol, for the internal nodes
+    link: usize,
+    children: BTreeMap<u32, usize>,
 }
 
+/// The compressed trie of all the suffixes of a text, built online with Ukkonen's algorithm
+/// <https://en.wikipedia.org/wiki/Suffix_tree>
+///
+/// Every edge is labeled by a range of the text, so that the tree takes O(n) space, and a
+/// pattern follows a single path from the root, found in O(m log(σ)) for an alphabet of σ
+/// characters. The text gets a unique terminal symbol, so that every suffix ends at a leaf,
+/// and the occurrences of a pattern are the leaves below the end of its path.
+///
+/// Ukkonen's algorithm adds the characters one by one, keeping the tree of the suffixes of the
+/// prefix read so far. The leaves all end at the current end, so that they grow with it for
+/// free. The `remainder` suffixes not yet inserted explicitly are already paths of the tree, the
+/// longest one ending at the active point, `active_length` characters along the edge starting
+/// with `active_edge` from `active_node`. Each new character either continues the path of the
+/// active point, ending the step, or hangs a new leaf from it, splitting the edge if the path
+/// ends inside one, and moves the active point to the next shorter suffix by the suffix link of
+/// the active node. Every character is inserted once and every walk down an edge is paid for by
+/// the points it skips, so that the construction takes O(n log(σ)), O(n) for a fixed alphabet.
+/// <https://en.wikipedia.org/wiki/Ukkonen%27s_algorithm>
+#[derive(Debug, Clone)]
 pub struct SuffixTree {
-    pub nodes: Vec<Node>,
+    text: String,
+    /// The characters of the text then the terminal symbol
+    symbols: Vec<u32>,
+    /// The byte offset of every character in the text, and the length of the text
+    offsets: Vec<usize>,
+    nodes: Vec<Node>,
+    /// The number of leaves below every node
+    leaves: Vec<usize>,
 }
 
 impl SuffixTree {
     pub fn new(s: &str) -> Self {
-        let mut suf_tree = SuffixTree {
-            nodes: vec![Node::empty()],
+        let mut symbols: Vec<u32> = s.chars().map(u32::from).collect();
+        symbols.push(TERMINAL);
+        let mut offsets: Vec<usize> = s.char_indices().map(|(offset, _)| offset).collect();
+        offsets.push(s.len());
+        let mut tree = SuffixTree {
+            text: s.to_string(),
+            symbols,
+            offsets,
+            nodes: vec![],
+            leaves: vec![],
         };
-        for i in 0..s.len() {
-            let (_, substr) = s.split_at(i);
-            suf_tree.add_suffix(substr);
-        }
-        suf_tree
+        tree.build();
+        tree.count_leaves();
+        tree
     }
-    fn add_suffix(&mut self, suf: &str) {
-        let mut n = 0;
-        let mut i = 0;
-        while i < suf.len() {
-            let b = suf.chars().nth(i);
-            let mut x2 = 0;
-            let mut n2: usize;
-            loop {
-                let children = &self.nodes[n].ch;
-                if children.len() == x2 {
-                    n2 = self.nodes.len();
-                    self.nodes.push(Node::new(
-                        {
-                            let (_, sub) = suf.split_at(i);
-                            sub.to_string()
-                        },
-                        vec![],
-                    ));
-                    self.nodes[n].ch.push(n2);
-                    return;
+
+    fn add_node(&mut self, start: usize, end: usize) -> usize {
+        self.nodes.push(Node {
+            start,
+            end,
+            link: 0,
+            children: BTreeMap::new(),
+        });
+        self.nodes.len() - 1
+    }
+
+    /// The length of the edge above a node, once the first `read` symbols are in the tree
+    fn edge_length(&self, node: usize, read: usize) -> usize {
+        self.nodes[node].end.min(read) - self.nodes[node].start
+    }
+
+    fn build(&mut self) {
+        let root = self.add_node(0, 0);
+        let (mut active_node, mut active_edge, mut active_length) = (root, 0, 0);
+        let mut remainder = 0;
+        for i in 0..self.symbols.len() {
+            let symbol = self.symbols[i];
+            remainder += 1;
+            // the last internal node created in this step, waiting for its suffix link
+            let mut waiting: Option<usize> = None;
+            while remainder > 0 {
+                if active_length == 0 {
+                    active_edge = i;
                 }
-                n2 = children[x2];
-                if self.nodes[n2].sub.chars().next() == b {
-                    break;
+                let first = self.symbols[active_edge];
+                match self.nodes[active_node].children.get(&first).copied() {
+                    None => {
+                        let leaf = self.add_node(i, LEAF);
+                        self.nodes[active_node].children.insert(first, leaf);
+                        if let Some(node) = waiting.take() {
+                            self.nodes[node].link = active_node;
+                        }
+                    }
+                    Some(next) => {
+                        let length = self.edge_length(next, i + 1);
+                        if active_length >= length {
+                            // walk down to the node the active point is past
+                            active_node = next;
+                            active_edge += length;
+                            active_length -= length;
+                            continue;
+                        }
+                        if self.symbols[self.nodes[next].start + active_length] == symbol {
+                            // the suffix is already in the tree, and so are the shorter ones
+                            if let Some(node) = waiting.take() {
+                                self.nodes[node].link = active_node;
+                            }
+                            active_length += 1;
+                            break;
+                        }
+                        let start = self.nodes[next].start;
+                        let split = self.add_node(start, start + active_length);
+                        self.nodes[active_node].children.insert(first, split);
+                        let leaf = self.add_node(i, LEAF);
+                        self.nodes[split].children.insert(symbol, leaf);
+                        self.nodes[next].start += active_length;
+                        let rest = self.symbols[self.nodes[next].start];
+                        self.nodes[split].children.insert(rest, next);
+                        if let Some(node) = waiting.replace(split) {
+                            self.nodes[node].link = split;
+                        }
+                    }
+                }
+                remainder -= 1;
+                if active_node == root && active_length > 0 {
+                    active_length -= 1;
+                    active_edge = i + 1 - remainder;
+                } else if active_node != root {
+                    active_node = self.nodes[active_node].link;
                 }
-                x2 += 1;
             }
-            let sub2 = self.nodes[n2].sub.clone();
-            let mut j = 0;
-            while j < sub2.len() {
-                if suf.chars().nth(i + j) != sub2.chars().nth(j) {
-                    let n3 = n2;
-                    n2 = self.nodes.len();
-                    self.nodes.push(Node::new(
-                        {
-                            let (sub, _) = sub2.split_at(j);
-                            sub.to_string()
-                        },
-                        vec![n3],
-                    ));
-                    let (_, temp_sub) = sub2.split_at(j);
-                    self.nodes[n3].sub = temp_sub.to_string();
-                    self.nodes[n].ch[x2] = n2;
-                    break;
+        }
+        let end = self.symbols.len();
+        for node in &mut self.nodes {
+            node.end = node.end.min(end);
+        }
+    }
+
+    /// Counts the leaves below every node, the children coming after their parents.
+    fn count_leaves(&mut self) {
+        let mut order = vec![0];
+        let mut i = 0;
+        while i < order.len() {
+            order.extend(self.nodes[order[i]].children.values());
+            i += 1;
+        }
+        self.leaves = vec![0; self.nodes.len()];
+        for &node in order.iter().rev() {
+            let children = &self.nodes[node].children;
+            self.leaves[node] = if children.is_empty() {
+                1
+            } else {
+                children.values().map(|&child| self.leaves[child]).sum()
+            };
+        }
+    }
+
+    /// The node at or below the end of the path of a pattern from the root, if there is one
+    fn locate(&self, pattern: &str) -> Option<usize> {
+        let mut node = 0;
+        let mut chars = pattern.chars().map(u32::from).peekable();
+        while let Some(&first) = chars.peek() {
+            node = *self.nodes[node].children.get(&first)?;
+            let edge = &self.symbols[self.nodes[node].start..self.nodes[node].end];
+            for &symbol in edge {
+                match chars.next() {
+                    Some(c) if c == symbol => {}
+                    Some(_) => return None,
+                    None => break,
                 }
-                j += 1;
             }
-            i += j;
-            n = n2;
         }
+        Some(node)
+    }
+
+    /// Tells whether `pattern` is a substring of the text, in O(m log(σ)).
+    pub fn contains(&self, pattern: &str) -> bool {
+        self.locate(pattern).is_some()
+    }
+
+    /// Returns the number of possibly overlapping occurrences of `pattern` in the text, the
+    /// leaves below its path, in O(m log(σ)). The empty pattern occurs before every character
+    /// and at the end.
+    pub fn count_occurrences(&self, pattern: &str) -> usize {
+        self.locate(pattern).map_or(0, |node| self.leaves[node])
+    }
+
+    /// Returns the longest substring occurring at least twice, possibly overlapping, the path
+    /// of the deepest internal node, the first one in the text among those of the same length,
+    /// or an empty string if no character repeats.
+    pub fn longest_repeated_substring(&self) -> &str {
+        // the start in the text and the length of the path of every internal node
+        let (mut best_start, mut best_length) = (0, 0);
+        let mut stack = vec![(0, 0)];
+        while let Some((node, depth)) = stack.pop() {
+            let children = &self.nodes[node].children;
+            if children.is_empty() {
+                continue;
+            }
+            let start = self.nodes[node].end - depth;
+            if depth > best_length || (depth == best_length && start < best_start) {
+                (best_start, best_length) = (start, depth);
+            }
+            for &child in children.values() {
+                let length = self.nodes[child].end - self.nodes[child].start;
+                stack.push((child, depth + length));
+            }
+        }
+        &self.text[self.offsets[best_start]..self.offsets[best_start + best_length]]
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::string::{generate_suffix_array, lcp_array};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use std::time::Instant;
+
+    /// The paths of all the nodes, without the terminal symbol
+    fn paths(tree: &SuffixTree) -> Vec<String> {
+        let mut paths = vec![];
+        let mut stack = vec![(0, String::new())];
+        while let Some((node, path)) = stack.pop() {
+            for &child in tree.nodes[node].children.values() {
+                let (start, end) = (tree.nodes[child].start, tree.nodes[child].end);
+                let label: String = tree.symbols[start..end]
+                    .iter()
+                    .filter_map(|&s| char::from_u32(s))
+                    .collect();
+                stack.push((child, path.clone() + &label));
+            }
+            paths.push(path);
+        }
+        paths.sort();
+        paths
+    }
 
     #[test]
     fn test_suffix_tree() {
         let suf_tree = SuffixTree::new("banana$");
+        // the root and the leaf of the terminal symbol alone are both empty
         assert_eq!(
-            suf_tree.nodes,
-            vec![
-                Node {
-                    sub: "".to_string(),
-                    ch: vec![1, 8, 6, 10]
-                },
-                Node {
-                    sub: "banana$".to_string(),
-                    ch: vec![]
-                },
-                Node {
-                    sub: "na$".to_string(),
-                    ch: vec![]
-                },
-                Node {
-                    sub: "na$".to_string(),
-                    ch: vec![]
-                },
-                Node {
-                    sub: "na".to_string(),
-                    ch: vec![2, 5]
-                },
-                Node {
-                    sub: "$".to_string(),
-                    ch: vec![]
-                },
-                Node {
-                    sub: "na".to_string(),
-                    ch: vec![3, 7]
-                },
-                Node {
-                    sub: "$".to_string(),
-                    ch: vec![]
-                },
-                Node {
-                    sub: "a".to_string(),
-                    ch: vec![4, 9]
-                },
-                Node {
-                    sub: "$".to_string(),
-                    ch: vec![]
-                },
-                Node {
-                    sub: "$".to_string(),
-                    ch: vec![]
-                }
-            ]
+            paths(&suf_tree),
+            ["", "", "$", "a", "a$", "ana", "ana$", "anana$", "banana$", "na", "na$", "nana$"]
+        );
+        assert_eq!(suf_tree.count_occurrences("ana"), 2);
+        assert_eq!(suf_tree.count_occurrences("an"), 2);
+        assert_eq!(suf_tree.count_occurrences("a"), 3);
+        assert_eq!(suf_tree.count_occurrences("banana$"), 1);
+        assert_eq!(suf_tree.count_occurrences(""), 8);
+        assert!(suf_tree.contains("nan") && !suf_tree.contains("nab"));
+        assert!(!suf_tree.contains("banana$!"));
+        assert_eq!(suf_tree.longest_repeated_substring(), "ana");
+    }
+
+    #[test]
+    fn longest_repeated_substrings() {
+        // "abc" occurs twice, at 0 and 6
+        assert_eq!(
+            SuffixTree::new("abcabxabcd").longest_repeated_substring(),
+            "abc"
         );
+        assert_eq!(SuffixTree::new("xabyab").longest_repeated_substring(), "ab");
+        assert_eq!(SuffixTree::new("aaaa").longest_repeated_substring(), "aaa");
+        assert_eq!(SuffixTree::new("abcd").longest_repeated_substring(), "");
+        assert_eq!(SuffixTree::new("").longest_repeated_substring(), "");
+        // the characters beyond ASCII are whole characters
+        let tree = SuffixTree::new("né, né!");
+        assert_eq!(tree.longest_repeated_substring(), "né");
+        assert_eq!(tree.count_occurrences("é"), 2);
+        assert!(!tree.contains("nén"));
+    }
+
+    #[test]
+    fn agrees_with_the_suffix_array() {
+        let mut rng = ChaCha8Rng::seed_from_u64(174);
+        for _ in 0..50 {
+            let n = rng.gen_range(1..300);
+            let letters = rng.gen_range(1..5);
+            let text: String = (0..n)
+                .map(|_| (b'a' + rng.gen_range(0..letters)) as char)
+                .collect();
+            let tree = SuffixTree::new(&text);
+            // at most one leaf per suffix and one internal node per branching
+            assert!(tree.nodes.len() <= 2 * (n + 1));
+            let suffix_array = generate_suffix_array(&text);
+            for _ in 0..50 {
+                let length = rng.gen_range(1..6);
+                let pattern: String = (0..length)
+                    .map(|_| (b'a' + rng.gen_range(0..letters)) as char)
+                    .collect();
+                let count = suffix_array
+                    .iter()
+                    .filter(|&&i| text[i..].starts_with(&pattern))
+                    .count();
+                assert_eq!(
+                    tree.count_occurrences(&pattern),
+                    count,
+                    "{pattern} in {text}"
+                );
+                assert_eq!(tree.contains(&pattern), count > 0);
+            }
+            let longest = *lcp_array(text.as_bytes(), &suffix_array)
+                .iter()
+                .max()
+                .unwrap();
+            let repeated = tree.longest_repeated_substring();
+            assert_eq!(repeated.len(), longest);
+            assert!(tree.count_occurrences(repeated) >= 2 || longest == 0);
+        }
+    }
+
+    #[test]
+    #[ignore = "slow test for measuring time"]
+    fn linear_construction() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1740);
+        for n in [100_000, 200_000, 400_000] {
+            let text: String = (0..n)
+                .map(|_| (b'a' + rng.gen_range(0..4)) as char)
+                .collect();
+            let start = Instant::now();
+            let tree = SuffixTree::new(&text);
+            println!("{n} characters: {:?}", start.elapsed());
+            assert_eq!(tree.count_occurrences(""), n + 1);
+            let periodic = "ab".repeat(n / 2);
+            let start = Instant::now();
+            let tree = SuffixTree::new(&periodic);
+            println!("{n} periodic characters: {:?}", start.elapsed());
+            assert_eq!(tree.longest_repeated_substring().len(), n - 2);
+        }
     }
 }
@@ -1,24 +1,19 @@
-fn match_with_z_array<T: Eq>(
-    input_string: &[T],
-    pattern: &[T],
-    start_index: usize,
-    only_full_matches: bool,
-) -> Vec<usize> {
-    let size = input_string.len();
-    let pattern_size = pattern.len();
+/// Returns the Z-array of `input`: the length of the longest common prefix of `input` and every
+/// suffix `input[i..]`, in O(n), with 0 for the trivial suffix at 0.
+///
+/// The rightmost match `input[last_match..=match_end]` found so far is a copy of a prefix of
+/// `input`, so that a position `i` inside it starts with a copy of `input[i - last_match..]`, at
+/// least as far as the end of the match, and the comparisons only extend the matches rightward.
+pub fn z_array<T: Eq>(input: &[T]) -> Vec<usize> {
+    let size = input.len();
     let mut last_match: usize = 0;
     let mut match_end: usize = 0;
     let mut array = vec![0usize; size];
-    for i in start_index..size {
-        // getting plain z array of a string requires matching from index
-        // 1 instead of 0 (which gives a trivial result instead)
+    for i in 1..size {
         if i <= match_end {
             array[i] = std::cmp::min(array[i - last_match], match_end - i + 1);
         }
-        while (i + array[i]) < size && array[i] < pattern_size {
-            if input_string[i + array[i]] != pattern[array[i]] {
-                break;
-            }
+        while i + array[i] < size && input[i + array[i]] == input[array[i]] {
             array[i] += 1;
         }
         if (i + array[i]) > (match_end + 1) {
@@ -26,30 +21,82 @@ fn match_with_z_array<T: Eq>(
             last_match = i;
         }
     }
-    if !only_full_matches {
-        array
-    } else {
-        let mut answer: Vec<usize> = vec![];
-        for (idx, number) in array.iter().enumerate() {
-            if *number == pattern_size {
-                answer.push(idx);
-            }
-        }
-        answer
+    array
+}
+
+/// Returns the starting indices of the occurrences of `pattern` in `input`, like
+/// `find_occurrences`.
+pub fn match_pattern<T: Eq>(input: &[T], pattern: &[T]) -> Vec<usize> {
+    find_occurrences(input, pattern)
+}
+
+/// Returns the starting indices of the occurrences of `pattern` in `text`, overlapping ones
+/// included, like `knuth_morris_pratt`: byte indices for the bytes of a string, and nothing for
+/// an empty pattern.
+///
+/// The occurrences are the positions of `pattern + text` past the pattern whose Z-value reaches
+/// the length of the pattern. No separator is needed between the two, since a Z-value longer
+/// than the pattern still starts with a whole copy of it.
+pub fn find_occurrences<T: Eq>(text: &[T], pattern: &[T]) -> Vec<usize> {
+    if pattern.is_empty() || pattern.len() > text.len() {
+        return vec![];
     }
+    let joined: Vec<&T> = pattern.iter().chain(text).collect();
+    let z = z_array(&joined);
+    (0..=text.len() - pattern.len())
+        .filter(|&i| z[pattern.len() + i] >= pattern.len())
+        .collect()
 }
 
-pub fn z_array<T: Eq>(input: &[T]) -> Vec<usize> {
-    match_with_z_array(input, input, 1, false)
+/// Returns the length of the shortest string whose repetition is `s`, which is `s` itself if it
+/// is not a power of a shorter string, and 0 for an empty string.
+///
+/// A length `p` dividing `|s|` works if `s` starts again at `p`, that is if its Z-value at `p`
+/// covers the rest of `s`. A string like "abaab" starts again at 3, but 3 does not divide 5,
+/// so that it is its own period.
+pub fn smallest_period<T: Eq>(s: &[T]) -> usize {
+    let n = s.len();
+    let z = z_array(s);
+    (1..n)
+        .find(|&p| n.is_multiple_of(p) && z[p] == n - p)
+        .unwrap_or(n)
 }
 
-pub fn match_pattern<T: Eq>(input: &[T], pattern: &[T]) -> Vec<usize> {
-    match_with_z_array(input, pattern, 0, true)
+/// Returns whether `b` is a rotation of `a`, a suffix of `a` followed by the rest of it, which
+/// is when they have the same length and `a` appears in `b + b`.
+pub fn is_rotation<T: Eq + Clone>(a: &[T], b: &[T]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    if a.is_empty() {
+        return true;
+    }
+    let doubled: Vec<T> = b.iter().chain(b).cloned().collect();
+    !find_occurrences(&doubled, a).is_empty()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::string::knuth_morris_pratt;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn naive_search(text: &[u8], pattern: &[u8]) -> Vec<usize> {
+        if pattern.is_empty() || pattern.len() > text.len() {
+            return vec![];
+        }
+        (0..=text.len() - pattern.len())
+            .filter(|&i| &text[i..i + pattern.len()] == pattern)
+            .collect()
+    }
+
+    fn random_string(rng: &mut ChaCha8Rng, lengths: std::ops::Range<usize>) -> String {
+        let len = rng.gen_range(lengths);
+        (0..len)
+            .map(|_| rng.gen_range(b'a'..b'd') as char)
+            .collect()
+    }
 
     #[test]
     fn test_z_array() {
@@ -103,4 +150,77 @@ mod tests {
             expected_answer
         );
     }
+
+    #[test]
+    fn hand_computed_z_array() {
+        assert_eq!(
+            z_array("aabxaayaab".as_bytes()),
+            [0, 1, 0, 0, 2, 1, 0, 3, 1, 0]
+        );
+        assert_eq!(z_array("aaaa".as_bytes()), [0, 3, 2, 1]);
+        assert_eq!(z_array("a".as_bytes()), [0]);
+        assert!(z_array::<u8>(&[]).is_empty());
+    }
+
+    #[test]
+    fn occurrences_agree_with_kmp_and_naive_search() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1281);
+        for _ in 0..2000 {
+            let text = random_string(&mut rng, 0..40);
+            let pattern = random_string(&mut rng, 0..5);
+            let expected = naive_search(text.as_bytes(), pattern.as_bytes());
+            assert_eq!(
+                find_occurrences(text.as_bytes(), pattern.as_bytes()),
+                expected
+            );
+            assert_eq!(knuth_morris_pratt(&text, &pattern), expected);
+            assert_eq!(match_pattern(text.as_bytes(), pattern.as_bytes()), expected);
+        }
+        // byte indices of the words after multi-byte characters
+        assert_eq!(find_occurrences("é-a-é".as_bytes(), "é".as_bytes()), [0, 5]);
+        assert_eq!(find_occurrences(b"aaaa", b"aa"), [0, 1, 2]);
+        assert!(find_occurrences(b"abc", b"").is_empty());
+        assert!(find_occurrences(b"", b"a").is_empty());
+        assert_eq!(find_occurrences(b"a", b"a"), [0]);
+    }
+
+    #[test]
+    fn periods() {
+        for (s, period) in [
+            ("abcabcabc", 3),
+            ("aaaa", 1),
+            ("abab", 2),
+            ("abaab", 5),
+            ("abaaba", 3),
+            ("abaabaab", 8),
+            ("abcd", 4),
+            ("a", 1),
+            ("", 0),
+        ] {
+            assert_eq!(smallest_period(s.as_bytes()), period, "{s}");
+        }
+        let mut rng = ChaCha8Rng::seed_from_u64(1282);
+        for _ in 0..500 {
+            let s = random_string(&mut rng, 1..6).repeat(rng.gen_range(1..5));
+            let s = s.as_bytes();
+            let expected = (1..=s.len())
+                .find(|&p| s.len() % p == 0 && s == s[..p].repeat(s.len() / p).as_slice())
+                .unwrap();
+            assert_eq!(smallest_period(s), expected);
+        }
+    }
+
+    #[test]
+    fn rotations() {
+        assert!(is_rotation(b"waterbottle", b"erbottlewat"));
+        assert!(is_rotation(b"abc", b"abc"));
+        assert!(is_rotation(b"aab", b"baa"));
+        assert!(!is_rotation(b"aab", b"bba"));
+        assert!(!is_rotation(b"abc", b"abcabc"));
+        assert!(!is_rotation(b"ab", b"a"));
+        assert!(is_rotation(b"", b""));
+        assert!(is_rotation(b"x", b"x"));
+        assert!(!is_rotation(b"x", b"y"));
+        assert!(is_rotation(&[1, 2, 3], &[3, 1, 2]));
+    }
 }